@@ -1,5 +1,16 @@
 use tauri::{AppHandle, Manager};
-use crate::models::{Activity, ActivityStats};
+use crate::models::{
+    Activity, ActivityCapture, ActivityDetail, ActivityEvent, AppDirectoryEntry, AppMerge, AppRenameMatch, AppRenameReport, ProcessSession, ActivityMetadata, TrackingGap, ListeningStats, ActivityStats, DayBoundaryDebug, GroupedStats,
+    RelatedActivities, RelatedActivity, RelatedFileEvent,
+};
+
+const DETAIL_SCREEN_TEXT_LIMIT: usize = 20_000;
+/// How far a reference's timestamp may drift from the stored row when looking it up without an id.
+const DETAIL_MATCH_TOLERANCE_SECS: i64 = 120;
+/// Related sessions are looked up this far before and after the selected one.
+const RELATED_WINDOW_SECS: i64 = 48 * 3600;
+/// File events are only related when they happened close to the session.
+const RELATED_FILE_WINDOW_SECS: i64 = 2 * 3600;
 
 #[tauri::command]
 pub async fn get_activities(
@@ -8,10 +19,7 @@ pub async fn get_activities(
     end_time: i64,
     limit: Option<i32>,
 ) -> Result<Vec<Activity>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     crate::database::queries::get_activities(&conn, start_time, end_time, limit)
         .map_err(|e| e.to_string())
@@ -23,23 +31,41 @@ pub async fn get_activity_stats(
     start_time: i64,
     end_time: i64,
 ) -> Result<ActivityStats, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     crate::database::queries::get_activity_stats(&conn, start_time, end_time)
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn get_grouped_stats(
+    app_handle: AppHandle,
+    start_time: i64,
+    end_time: i64,
+    group_by: String,
+    metric: Option<String>,
+    top_n: Option<usize>,
+    include_other_bucket: Option<bool>,
+) -> Result<GroupedStats, String> {
+    let conn = crate::database::open(&app_handle)?;
+
+    crate::database::queries::get_grouped_stats(
+        &conn,
+        start_time,
+        end_time,
+        &group_by.trim().to_lowercase(),
+        &metric.unwrap_or_else(|| "duration".to_string()).trim().to_lowercase(),
+        top_n.unwrap_or(10),
+        include_other_bucket.unwrap_or(true),
+    )
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_current_activity(
     app_handle: AppHandle,
 ) -> Result<Option<Activity>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     let mut stmt = conn.prepare(
         "SELECT id, app_name, app_hash, window_title, window_title_hash, category_id,
@@ -68,9 +94,932 @@ pub async fn get_current_activity(
         })
     });
     
-    match result {
-        Ok(activity) => Ok(Some(activity)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.to_string()),
+    let stored = match result {
+        Ok(activity) => Some(activity),
+        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(e.to_string()),
+    };
+
+    // The newest session may still be in the tracker's write queue; it has no id (0) until written.
+    let queued = crate::services::activity_tracker::last_enqueued()
+        .filter(|_| !crate::database::is_demo_mode())
+        .filter(|q| stored.as_ref().map_or(true, |s| q.start_time > s.start_time));
+    Ok(match queued {
+        Some(event) => Some(Activity {
+            id: 0,
+            app_name: event.app_name,
+            app_hash: event.app_hash,
+            window_title: event.window_title,
+            window_title_hash: event.window_title_hash,
+            category_id: event.category_id,
+            start_time: event.start_time,
+            end_time: event.end_time,
+            duration_seconds: event.duration_seconds,
+            metadata: Some(event.metadata),
+        }),
+        None => stored,
+    })
+}
+
+#[tauri::command]
+pub async fn mark_private(
+    app_handle: AppHandle,
+    start_ts: i64,
+    end_ts: i64,
+    private: bool,
+) -> Result<usize, String> {
+    if start_ts > end_ts {
+        return Err("start_ts must be before end_ts".to_string());
+    }
+
+    let conn = crate::database::open(&app_handle)?;
+    
+    // Any activity overlapping the window is flagged, so a session that started
+    // just before the marker is still hidden from the agent.
+    let updated = conn.execute(
+        "UPDATE activities SET private = ?1
+         WHERE start_time <= ?3 AND end_time >= ?2",
+        rusqlite::params![private as i32, start_ts, end_ts],
+    ).map_err(|e| e.to_string())?;
+    
+    Ok(updated)
+}
+
+/// Drop the most recently stored activity (and its thumbnail). Returns the deleted row id.
+#[tauri::command]
+pub async fn delete_last_session(
+    app_handle: AppHandle,
+) -> Result<Option<i64>, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    // The session the user means may still be in the write queue.
+    tokio::task::spawn_blocking(crate::services::activity_tracker::flush_pending)
+        .await
+        .map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
+
+    let last: Option<(i64, Option<Vec<u8>>)> = conn
+        .query_row(
+            "SELECT id, metadata FROM activities ORDER BY end_time DESC, id DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    let Some((id, metadata)) = last else {
+        return Ok(None);
+    };
+
+    conn.execute("DELETE FROM activities WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+
+    let thumbnail = metadata
+        .and_then(|blob| serde_json::from_slice::<ActivityMetadata>(&blob).ok())
+        .and_then(|meta| meta.thumbnail_path);
+    if let Some(relative) = thumbnail {
+        let _ = std::fs::remove_file(data_dir.join(relative));
+    }
+
+    println!("[Activity] Deleted last session #{}", id);
+    Ok(Some(id))
+}
+
+#[tauri::command]
+pub async fn get_day_boundary_debug(
+    app_handle: AppHandle,
+    date: String,
+) -> Result<DayBoundaryDebug, String> {
+    let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{}': {}", date, e))?;
+    
+    let conn = crate::database::open(&app_handle)?;
+    
+    let bounds = crate::utils::time::day_bounds(Some(&conn), date);
+    let offset = chrono::FixedOffset::east_opt(bounds.offset_minutes * 60)
+        .ok_or_else(|| "Invalid offset".to_string())?;
+    let fmt = |ts: i64, local: bool| {
+        let dt = chrono::DateTime::from_timestamp(ts, 0).unwrap_or_default();
+        if local {
+            dt.with_timezone(&offset).to_rfc3339()
+        } else {
+            dt.to_rfc3339()
+        }
+    };
+    
+    Ok(DayBoundaryDebug {
+        timezone_mode: crate::utils::time::timezone_mode_label(),
+        start_local: fmt(bounds.start_ts, true),
+        end_local: fmt(bounds.end_ts, true),
+        start_utc: fmt(bounds.start_ts, false),
+        end_utc: fmt(bounds.end_ts, false),
+        stored_offsets: crate::utils::time::stored_offsets_for_date(&conn, date),
+        bounds,
+    })
+}
+
+/// Top tracks and artists, total play time and a per-day series for `[start_ts, end_ts)`.
+#[tauri::command]
+pub async fn get_listening_stats(
+    app_handle: AppHandle,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<ListeningStats, String> {
+    if end_ts <= start_ts {
+        return Err("End must be after start".to_string());
+    }
+    // Enough for a yearly recap without letting one call scan the whole history.
+    if end_ts - start_ts > 366 * 86_400 {
+        return Err("Range is limited to one year".to_string());
+    }
+    let conn = crate::database::open(&app_handle)?;
+    crate::database::queries::get_listening_stats(&conn, start_ts, end_ts).map_err(|e| e.to_string())
+}
+
+/// Stretches of `date_key` with no recorded activity, so the timeline can tell
+/// "nothing happened" apart from "tracking was down". Only the configured active
+/// hours are scanned, and only gaps of at least `tracking.gap_threshold_minutes`.
+#[tauri::command]
+pub async fn get_tracking_gaps(
+    app_handle: AppHandle,
+    date_key: String,
+) -> Result<Vec<TrackingGap>, String> {
+    let date = chrono::NaiveDate::parse_from_str(date_key.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{}': {}", date_key, e))?;
+    let tracking = crate::services::settings_bus::current(&app_handle)
+        .unwrap_or_default()
+        .tracking;
+    let conn = crate::database::open(&app_handle)?;
+
+    let bounds = crate::utils::time::day_bounds(Some(&conn), date);
+    let hour = |h: u32| bounds.start_ts + i64::from(h.min(24)) * 3600;
+    let (start, end) = (hour(tracking.active_hours_start), hour(tracking.active_hours_end));
+    let windows = if start < end {
+        vec![(start, end)]
+    } else {
+        // Wraps past midnight: the early morning and the evening of this date.
+        vec![(bounds.start_ts, end), (start, bounds.end_ts)]
+    };
+
+    // Sessions are written when they end: everything since the newest finalized one
+    // started is covered by it (possibly still queued) and the open session.
+    let now = chrono::Utc::now().timestamp();
+    let scan_until = if crate::services::activity_tracker::is_tracking_enabled() {
+        crate::services::activity_tracker::last_enqueued()
+            .map(|last| last.start_time.min(now))
+            .unwrap_or(now)
+    } else {
+        now
+    };
+    let min_gap_secs = (tracking.gap_threshold_minutes.max(1) * 60) as i64;
+
+    let mut gaps = Vec::new();
+    for (window_start, window_end) in windows {
+        let window_end = window_end.min(scan_until);
+        if window_end <= window_start {
+            continue;
+        }
+        gaps.extend(
+            crate::database::queries::get_tracking_gaps(&conn, window_start, window_end, min_gap_secs)
+                .map_err(|e| e.to_string())?,
+        );
+    }
+    Ok(gaps)
+}
+
+#[tauri::command]
+pub async fn get_activity_detail(
+    app_handle: AppHandle,
+    activity_id: i64,
+) -> Result<ActivityDetail, String> {
+    let conn = crate::database::open(&app_handle)?;
+    
+    load_activity_detail(
+        &conn,
+        "WHERE id = ?1",
+        rusqlite::params![activity_id],
+    )?
+    .ok_or_else(|| format!("Activity {} not found", activity_id))
+}
+
+/// Full OCR text and the other captured context of one session. Unlike
+/// `get_activity_detail` the screen text is not capped. `None` if the row is gone.
+#[tauri::command]
+pub async fn get_activity_ocr(
+    app_handle: AppHandle,
+    activity_id: i64,
+) -> Result<Option<ActivityCapture>, String> {
+    let conn = crate::database::open(&app_handle)?;
+
+    let result = conn.query_row(
+        "SELECT metadata, COALESCE(private, 0) FROM activities WHERE id = ?1",
+        [activity_id],
+        |row| Ok((row.get::<_, Option<Vec<u8>>>(0)?, row.get::<_, i64>(1)? != 0)),
+    );
+    let (metadata_blob, private) = match result {
+        Ok(found) => found,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let metadata: ActivityMetadata = metadata_blob
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .unwrap_or_default();
+    let background_windows = Some(metadata.background_windows_seen()).filter(|w| !w.is_empty());
+
+    Ok(Some(ActivityCapture {
+        activity_id,
+        private,
+        screen_text: metadata.screen_text,
+        ocr_score: metadata.ocr_score,
+        ocr_language: metadata.ocr_language,
+        media_info: metadata.media_info,
+        background_windows,
+    }))
+}
+
+/// Lookup for chat references that carry no row id: the closest row with the
+/// same app and window title within a couple of minutes of `start_time`.
+#[tauri::command]
+pub async fn find_activity_detail(
+    app_handle: AppHandle,
+    app_name: String,
+    window_title: String,
+    start_time: i64,
+) -> Result<Option<ActivityDetail>, String> {
+    let conn = crate::database::open(&app_handle)?;
+    
+    load_activity_detail(
+        &conn,
+        "WHERE app_name = ?1 AND window_title = ?2
+           AND start_time BETWEEN ?3 - ?4 AND ?3 + ?4
+         ORDER BY ABS(start_time - ?3)",
+        rusqlite::params![app_name, window_title, start_time, DETAIL_MATCH_TOLERANCE_SECS],
+    )
+}
+
+fn load_activity_detail(
+    conn: &rusqlite::Connection,
+    filter_sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Option<ActivityDetail>, String> {
+    let sql = format!(
+        "SELECT id, app_name, app_hash, window_title, window_title_hash, category_id,
+                start_time, end_time, duration_seconds, metadata,
+                COALESCE(private, 0), tz_offset_minutes
+         FROM activities
+         {}
+         LIMIT 1",
+        filter_sql
+    );
+    let result = conn.query_row(&sql, params, |row| {
+        let metadata_blob: Option<Vec<u8>> = row.get(9)?;
+        let metadata: Option<crate::models::ActivityMetadata> = metadata_blob
+            .and_then(|b| serde_json::from_slice(&b).ok());
+        
+        Ok((
+            Activity {
+                id: row.get(0)?,
+                app_name: row.get(1)?,
+                app_hash: row.get::<_, i64>(2)? as u64,
+                window_title: row.get::<_, String>(3).unwrap_or_default(),
+                window_title_hash: row.get::<_, i64>(4).unwrap_or(0) as u64,
+                category_id: row.get(5)?,
+                start_time: row.get(6)?,
+                end_time: row.get(7)?,
+                duration_seconds: row.get(8)?,
+                metadata,
+            },
+            row.get::<_, i64>(10)? != 0,
+            row.get::<_, Option<i32>>(11)?,
+        ))
+    });
+    
+    let (mut activity, private, tz_offset_minutes) = match result {
+        Ok(found) => found,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    
+    // screen_text is stored already cleaned by the capture pipeline; only the size is capped here.
+    let mut screen_text_chars = 0;
+    let mut screen_text_truncated = false;
+    if let Some(text) = activity.metadata.as_mut().and_then(|m| m.screen_text.as_mut()) {
+        screen_text_chars = text.chars().count();
+        if screen_text_chars > DETAIL_SCREEN_TEXT_LIMIT {
+            *text = text.chars().take(DETAIL_SCREEN_TEXT_LIMIT).collect();
+            screen_text_truncated = true;
+        }
+    }
+    
+    Ok(Some(ActivityDetail {
+        activity,
+        private,
+        tz_offset_minutes,
+        screen_text_truncated,
+        screen_text_chars,
+    }))
+}
+
+/// Other sessions of the same window title or app near the given activity,
+/// plus file changes in a project whose folder name appears in its title.
+/// Uses the stored hashes only; no AI involved.
+#[tauri::command]
+pub async fn get_related_activities(
+    app_handle: AppHandle,
+    activity_id: i64,
+    limit: Option<usize>,
+) -> Result<RelatedActivities, String> {
+    let limit = limit.unwrap_or(20).clamp(1, 100);
+    let conn = crate::database::open(&app_handle)?;
+
+    let base = load_activity_detail(&conn, "WHERE id = ?1", [activity_id])?
+        .ok_or_else(|| format!("Activity {} not found", activity_id))?
+        .activity;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, app_name, app_hash, window_title, window_title_hash, category_id,
+                start_time, end_time, duration_seconds
+         FROM activities
+         WHERE start_time >= ?1 AND start_time <= ?2 AND id != ?3
+           AND (app_hash = ?4 OR window_title_hash = ?5)",
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(
+        rusqlite::params![
+            base.start_time - RELATED_WINDOW_SECS,
+            base.end_time + RELATED_WINDOW_SECS,
+            base.id,
+            base.app_hash as i64,
+            base.window_title_hash as i64,
+        ],
+        |row| {
+            Ok(Activity {
+                id: row.get(0)?,
+                app_name: row.get(1)?,
+                app_hash: row.get::<_, i64>(2)? as u64,
+                window_title: row.get::<_, String>(3).unwrap_or_default(),
+                window_title_hash: row.get::<_, i64>(4).unwrap_or(0) as u64,
+                category_id: row.get(5)?,
+                start_time: row.get(6)?,
+                end_time: row.get(7)?,
+                duration_seconds: row.get(8)?,
+                metadata: None,
+            })
+        },
+    ).map_err(|e| e.to_string())?;
+
+    let mut activities: Vec<RelatedActivity> = rows
+        .filter_map(|r| r.ok())
+        .map(|activity| {
+            let same_title = base.window_title_hash != 0 && activity.window_title_hash == base.window_title_hash;
+            let gap_seconds = (activity.start_time - base.end_time)
+                .max(base.start_time - activity.end_time)
+                .max(0);
+            let similarity = if same_title { 1.0 } else { 0.4 };
+            let proximity = 1.0 - (gap_seconds as f64 / RELATED_WINDOW_SECS as f64).min(1.0);
+            RelatedActivity {
+                activity,
+                relation: if same_title { "same_title" } else { "same_app" }.to_string(),
+                gap_seconds,
+                score: similarity * (0.25 + 0.75 * proximity),
+            }
+        })
+        .collect();
+    activities.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.gap_seconds.cmp(&b.gap_seconds))
+    });
+    activities.truncate(limit);
+
+    let (project, file_events) = related_file_events(&conn, &base, limit)?;
+
+    Ok(RelatedActivities {
+        activity_id: base.id,
+        project,
+        activities,
+        file_events,
+    })
+}
+
+/// File events from the project whose folder name appears in the activity's
+/// window title (editors put it there, e.g. "main.rs - intent-flow - Visual Studio Code").
+fn related_file_events(
+    conn: &rusqlite::Connection,
+    base: &Activity,
+    limit: usize,
+) -> Result<(Option<String>, Vec<RelatedFileEvent>), String> {
+    let from = base.start_time - RELATED_FILE_WINDOW_SECS;
+    let to = base.end_time + RELATED_FILE_WINDOW_SECS;
+    let title_lower = base.window_title.to_lowercase();
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT project_root FROM code_file_events WHERE detected_at BETWEEN ?1 AND ?2",
+    ).map_err(|e| e.to_string())?;
+    let roots: Vec<String> = stmt
+        .query_map([from, to], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // Longest folder name wins so "intent-flow" beats a nested "flow".
+    let matched = roots
+        .into_iter()
+        .filter_map(|root| {
+            let name = std::path::Path::new(&root)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase())?;
+            (name.chars().count() >= 3 && title_lower.contains(&name)).then_some((name, root))
+        })
+        .max_by_key(|(name, _)| name.len());
+    let Some((name, root)) = matched else {
+        return Ok((None, Vec::new()));
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT path, project_root, change_type, detected_at
+         FROM code_file_events
+         WHERE project_root = ?1 AND detected_at BETWEEN ?2 AND ?3
+         ORDER BY ABS(detected_at - ?4)
+         LIMIT ?5",
+    ).map_err(|e| e.to_string())?;
+    let events = stmt
+        .query_map(
+            rusqlite::params![root, from, to, base.start_time, limit as i64],
+            |row| {
+                Ok(RelatedFileEvent {
+                    path: row.get(0)?,
+                    project_root: row.get(1)?,
+                    change_type: row.get(2)?,
+                    detected_at: row.get(3)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok((Some(name), events))
+}
+
+/// Every app in the activity history with its lifetime usage, main category and
+/// icon, most used first. Icons come from `services::app_icons` and may be missing.
+#[tauri::command]
+pub async fn get_app_directory(
+    app_handle: AppHandle,
+) -> Result<Vec<AppDirectoryEntry>, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let icons_dir = crate::services::app_icons::icons_dir(&data_dir);
+    let conn = crate::database::open(&app_handle)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.app_name, MAX(a.app_hash), SUM(a.duration_seconds), COUNT(*),
+                    MIN(a.start_time), MAX(a.end_time),
+                    (SELECT b.category_id FROM activities b
+                     WHERE b.app_name = a.app_name
+                     GROUP BY b.category_id
+                     ORDER BY SUM(b.duration_seconds) DESC
+                     LIMIT 1),
+                    (SELECT r.display_name FROM app_registry r WHERE r.app_name = a.app_name)
+             FROM activities a
+             GROUP BY a.app_name
+             ORDER BY SUM(a.duration_seconds) DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(String, i64, i64, i64, i64, i64, Option<i32>, Option<String>)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(rows
+        .into_iter()
+        .map(|(app_name, app_hash, total_seconds, activity_count, first_seen, last_seen, category_id, display_name)| {
+            let display_name = display_name
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| {
+                    let trimmed = app_name.trim();
+                    match trimmed.len().checked_sub(4) {
+                        Some(cut) if trimmed.is_char_boundary(cut) && trimmed[cut..].eq_ignore_ascii_case(".exe") => {
+                            trimmed[..cut].to_string()
+                        }
+                        _ => trimmed.to_string(),
+                    }
+                });
+            let icon = crate::services::app_icons::icon_data_url(&icons_dir, &app_name, app_hash as u64);
+            AppDirectoryEntry {
+                app_name,
+                display_name,
+                category_id: category_id.unwrap_or(crate::utils::categories::OTHER_CATEGORY_ID),
+                total_seconds,
+                activity_count,
+                first_seen,
+                last_seen,
+                icon,
+            }
+        })
+        .collect())
+}
+
+/// Merge several raw app names into `to` across history: activities get the
+/// new name and `app_hash`, clipboard events the new name. Names in
+/// `from_patterns` match stored names exactly, ignoring case. With `dry_run`
+/// the counts are returned and nothing changes; with `remember` the names are
+/// also stored as aliases so future captures arrive as `to`.
+#[tauri::command]
+pub async fn rename_app(
+    app_handle: AppHandle,
+    from_patterns: Vec<String>,
+    to: String,
+    dry_run: Option<bool>,
+    remember: Option<bool>,
+) -> Result<AppRenameReport, String> {
+    let to = to.trim().to_string();
+    if to.is_empty() {
+        return Err("Give the name to rename to".to_string());
+    }
+    let wanted: Vec<String> = from_patterns
+        .iter()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if wanted.is_empty() {
+        return Err("Give at least one app name to rename".to_string());
+    }
+    let dry_run = dry_run.unwrap_or(false);
+
+    let mut conn = crate::database::open(&app_handle)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    // Compared in Rust: SQLite's LOWER only folds ASCII.
+    let stored_names: Vec<String> = stored_app_names(&tx)?
+        .into_iter()
+        .filter(|name| name != &to && wanted.contains(&name.to_lowercase()))
+        .collect();
+
+    let app_hash = ActivityEvent::app_hash(&to) as i64;
+    let mut report = AppRenameReport {
+        dry_run,
+        to: to.clone(),
+        matched: Vec::with_capacity(stored_names.len()),
+        activities_updated: 0,
+        clipboard_events_updated: 0,
+        aliases: Vec::new(),
+    };
+    for name in stored_names {
+        let matched = rewrite_app_name(&tx, name, &to, app_hash)?;
+        report.activities_updated += matched.activities;
+        report.clipboard_events_updated += matched.clipboard_events;
+        report.matched.push(matched);
+    }
+
+    if remember.unwrap_or(false) {
+        let now = chrono::Utc::now().timestamp();
+        let to_lower = to.to_lowercase();
+        // The new name must never be merged itself, or captures would bounce between names.
+        tx.execute("DELETE FROM app_merge_map WHERE from_name = ?1", [&to_lower])
+            .map_err(|e| e.to_string())?;
+        // Merges that pointed at a renamed name follow it to the new one.
+        tx.execute(
+            "UPDATE app_merge_map SET to_name = ?1 WHERE LOWER(to_name) IN (SELECT value FROM json_each(?2))",
+            rusqlite::params![&to, serde_json::to_string(&wanted).map_err(|e| e.to_string())?],
+        )
+        .map_err(|e| e.to_string())?;
+        for from_name in wanted.iter().filter(|name| **name != to_lower) {
+            tx.execute(
+                "INSERT OR REPLACE INTO app_merge_map (from_name, to_name, created_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![from_name, &to, now],
+            )
+            .map_err(|e| e.to_string())?;
+            report.aliases.push(from_name.clone());
+        }
+    }
+
+    if dry_run {
+        // Dropping the transaction rolls every update back.
+        return Ok(report);
+    }
+    let merges = crate::database::queries::get_app_merges(&tx).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    crate::services::activity_tracker::set_app_merges(&merges);
+    println!(
+        "[Activity] Renamed {} app name(s) to '{}': {} activities, {} clipboard events",
+        report.matched.len(),
+        to,
+        report.activities_updated,
+        report.clipboard_events_updated
+    );
+    Ok(report)
+}
+
+/// Every distinct app name on activities and clipboard events.
+fn stored_app_names(tx: &rusqlite::Transaction) -> Result<Vec<String>, String> {
+    let mut stmt = tx
+        .prepare(
+            "SELECT app_name FROM activities
+             UNION SELECT app_name FROM clipboard_events WHERE app_name IS NOT NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0)).map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Move every row stored as `name` to `to`.
+fn rewrite_app_name(
+    tx: &rusqlite::Transaction,
+    name: String,
+    to: &str,
+    app_hash: i64,
+) -> Result<AppRenameMatch, String> {
+    let activities = tx
+        .execute(
+            "UPDATE activities SET app_name = ?1, app_hash = ?2 WHERE app_name = ?3",
+            rusqlite::params![to, app_hash, &name],
+        )
+        .map_err(|e| e.to_string())? as i64;
+    let clipboard_events = tx
+        .execute(
+            "UPDATE clipboard_events SET app_name = ?1 WHERE app_name = ?2",
+            rusqlite::params![to, &name],
+        )
+        .map_err(|e| e.to_string())? as i64;
+    tx.execute(
+        "UPDATE session_checkpoint SET app_name = ?1 WHERE app_name = ?2",
+        rusqlite::params![to, &name],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(AppRenameMatch {
+        app_name: name,
+        activities,
+        clipboard_events,
+    })
+}
+
+#[tauri::command]
+pub async fn get_app_merges(
+    app_handle: AppHandle,
+) -> Result<Vec<AppMerge>, String> {
+    let conn = crate::database::open(&app_handle)?;
+    crate::database::queries::get_app_merges(&conn).map_err(|e| e.to_string())
+}
+
+/// Count `from_name` as `to_name` in app stats and store new captures under
+/// `to_name`; history is left as stored. Merging into a name that is itself merged follows it to its target,
+/// and merges that pointed at `from_name` move along with it. With
+/// `search_only` set, `from_name` is only searched along with `to_name`.
+#[tauri::command]
+pub async fn create_app_merge(
+    app_handle: AppHandle,
+    from_name: String,
+    to_name: String,
+    search_only: Option<bool>,
+) -> Result<AppMerge, String> {
+    let search_only = search_only.unwrap_or(false);
+    let from_name = from_name.trim().to_lowercase();
+    let requested = to_name.trim().to_string();
+    let mut to_name = requested.clone();
+    if from_name.is_empty() || to_name.is_empty() {
+        return Err("Give both the old and the new app name".to_string());
+    }
+
+    let mut conn = crate::database::open(&app_handle)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    if let Some(target) = crate::database::queries::get_app_merges(&tx)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|m| !m.search_only && m.from_name == to_name.to_lowercase())
+    {
+        to_name = target.to_name;
+    }
+    if to_name.to_lowercase() == from_name {
+        return Err(format!("'{}' is already merged into '{}'", requested, to_name));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    tx.execute(
+        "UPDATE app_merge_map SET to_name = ?1 WHERE LOWER(to_name) = ?2",
+        rusqlite::params![&to_name, &from_name],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT OR REPLACE INTO app_merge_map (from_name, to_name, search_only, created_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![&from_name, &to_name, search_only, now],
+    )
+    .map_err(|e| e.to_string())?;
+    let merges = crate::database::queries::get_app_merges(&tx).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    crate::services::activity_tracker::set_app_merges(&merges);
+    Ok(AppMerge {
+        from_name,
+        to_name,
+        search_only,
+        created_at: now,
+    })
+}
+
+/// Stop merging `from_name`. Rows already rewritten to the target stay as they are.
+#[tauri::command]
+pub async fn delete_app_merge(
+    app_handle: AppHandle,
+    from_name: String,
+) -> Result<bool, String> {
+    let conn = crate::database::open(&app_handle)?;
+    let removed = conn
+        .execute("DELETE FROM app_merge_map WHERE from_name = ?1", [from_name.trim().to_lowercase()])
+        .map_err(|e| e.to_string())?;
+    let merges = crate::database::queries::get_app_merges(&conn).map_err(|e| e.to_string())?;
+    crate::services::activity_tracker::set_app_merges(&merges);
+    Ok(removed > 0)
+}
+
+/// Rewrite every stored name that has a merge to its target, grouped per target.
+fn apply_merges(tx: &rusqlite::Transaction, dry_run: bool) -> Result<Vec<AppRenameReport>, String> {
+    let merges: std::collections::HashMap<String, String> = crate::database::queries::get_app_merges(tx)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|m| !m.search_only)
+        .map(|m| (m.from_name, m.to_name))
+        .collect();
+    let mut reports: Vec<AppRenameReport> = Vec::new();
+    for name in stored_app_names(tx)? {
+        // Same ASCII-only folding as the query-time lookup, so both agree.
+        let Some(to) = merges.get(&name.to_ascii_lowercase()) else {
+            continue;
+        };
+        if &name == to {
+            continue;
+        }
+        let matched = rewrite_app_name(tx, name, to, ActivityEvent::app_hash(to) as i64)?;
+        let index = match reports.iter().position(|r| &r.to == to) {
+            Some(index) => index,
+            None => {
+                reports.push(AppRenameReport {
+                    dry_run,
+                    to: to.clone(),
+                    matched: Vec::new(),
+                    activities_updated: 0,
+                    clipboard_events_updated: 0,
+                    aliases: Vec::new(),
+                });
+                reports.len() - 1
+            }
+        };
+        let report = &mut reports[index];
+        report.activities_updated += matched.activities;
+        report.clipboard_events_updated += matched.clipboard_events;
+        report.matched.push(matched);
+    }
+
+    Ok(reports)
+}
+
+/// Rewrite history so every merged name is stored as its target, one report
+/// per target. Stats look the same before and after; this only saves the
+/// lookup and makes raw rows agree. The merges themselves are kept for rows
+/// captured under the old names later.
+#[tauri::command]
+pub async fn apply_app_merges_to_history(
+    app_handle: AppHandle,
+    dry_run: Option<bool>,
+) -> Result<Vec<AppRenameReport>, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let mut conn = crate::database::open(&app_handle)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let reports = apply_merges(&tx, dry_run)?;
+
+    if dry_run {
+        return Ok(reports);
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    println!(
+        "[Activity] Applied app merges to history: {} activities under {} name(s)",
+        reports.iter().map(|r| r.activities_updated).sum::<i64>(),
+        reports.len()
+    );
+    Ok(reports)
+}
+
+/// Runs of watched processes (`tracking.process_watch_list`) overlapping the range,
+/// optionally only those whose executable name contains `app`.
+#[tauri::command]
+pub async fn get_process_sessions(
+    app_handle: AppHandle,
+    app: Option<String>,
+    start_time: i64,
+    end_time: i64,
+) -> Result<Vec<ProcessSession>, String> {
+    let conn = crate::database::open(&app_handle)?;
+    crate::database::queries::get_process_sessions(&conn, app.as_deref(), start_time, end_time)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::apply_merges;
+    use crate::database::queries::{get_activity_stats, get_grouped_stats};
+    use rusqlite::Connection;
+
+
+    fn insert_activity(conn: &Connection, app: &str, start: i64, duration: i64) {
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+             VALUES (?1, 0, 'window', 1, ?2, ?3, ?4)",
+            rusqlite::params![app, start, start + duration, duration],
+        )
+        .unwrap();
+    }
+
+    fn app_stats(conn: &Connection) -> (Vec<(String, i64, i32)>, Vec<(String, i64)>) {
+        let mut top: Vec<_> = get_activity_stats(conn, 0, 10_000)
+            .unwrap()
+            .top_apps
+            .into_iter()
+            .map(|a| (a.app_name.to_lowercase(), a.duration, a.count))
+            .collect();
+        top.sort();
+        let mut grouped: Vec<_> = get_grouped_stats(conn, 0, 10_000, "app", "duration", 50, false)
+            .unwrap()
+            .rows
+            .into_iter()
+            .map(|r| (r.key.to_lowercase(), r.value))
+            .collect();
+        grouped.sort();
+        (top, grouped)
+    }
+
+    #[test]
+    fn query_time_merges_match_the_rewritten_history() {
+        let mut conn = test_connection();
+        insert_activity(&conn, "Code", 0, 100);
+        insert_activity(&conn, "code", 200, 50);
+        insert_activity(&conn, "Visual Studio Code", 300, 70);
+        insert_activity(&conn, "Chrome", 400, 30);
+        insert_activity(&conn, "Old Chat", 500, 20);
+        conn.execute("DELETE FROM app_merge_map", []).unwrap();
+        for (from, to) in [("code", "Visual Studio Code"), ("old chat", "Chat")] {
+            conn.execute(
+                "INSERT INTO app_merge_map (from_name, to_name, created_at) VALUES (?1, ?2, 0)",
+                [from, to],
+            )
+            .unwrap();
+        }
+
+        let merged_at_query_time = app_stats(&conn);
+        assert_eq!(
+            merged_at_query_time.1,
+            vec![
+                ("chat".to_string(), 20),
+                ("chrome".to_string(), 30),
+                ("visual studio code".to_string(), 220),
+            ]
+        );
+
+        let tx = conn.transaction().unwrap();
+        let reports = apply_merges(&tx, false).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(reports.iter().map(|r| r.activities_updated).sum::<i64>(), 3);
+
+        assert_eq!(app_stats(&conn), merged_at_query_time);
+        let stored: Vec<String> = conn
+            .prepare("SELECT DISTINCT app_name FROM activities ORDER BY app_name")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(stored, vec!["Chat", "Chrome", "Visual Studio Code"]);
+    }
+
+    #[test]
+    fn seeded_alias_groups_merge_stats_except_search_only_rows() {
+        let mut conn = test_connection();
+        let seeded: Vec<(String, String, bool)> = crate::database::queries::get_app_merges(&conn)
+            .unwrap()
+            .into_iter()
+            .filter(|m| m.to_name == "Visual Studio Code")
+            .map(|m| (m.from_name, m.to_name, m.search_only))
+            .collect();
+        assert!(seeded.contains(&("vscode".to_string(), "Visual Studio Code".to_string(), false)));
+        assert!(seeded.contains(&("antigravity".to_string(), "Visual Studio Code".to_string(), true)));
+
+        insert_activity(&conn, "Code", 0, 100);
+        insert_activity(&conn, "Antigravity", 200, 40);
+        let expected = vec![("antigravity".to_string(), 40), ("visual studio code".to_string(), 100)];
+        assert_eq!(app_stats(&conn).1, expected);
+
+        let tx = conn.transaction().unwrap();
+        apply_merges(&tx, false).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(app_stats(&conn).1, expected);
     }
 }
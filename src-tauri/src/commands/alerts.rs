@@ -0,0 +1,89 @@
+use tauri::AppHandle;
+use crate::models::{AppAlert, CreateAppAlertRequest};
+
+pub(crate) fn validate(app_pattern: &str, daily_threshold_seconds: i64, repeat_every_seconds: Option<i64>) -> Result<(), String> {
+    if app_pattern.trim().is_empty() {
+        return Err("App pattern cannot be empty".to_string());
+    }
+    if daily_threshold_seconds <= 0 {
+        return Err("Daily threshold must be positive".to_string());
+    }
+    if matches!(repeat_every_seconds, Some(r) if r <= 0) {
+        return Err("Repeat interval must be positive".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_app_alerts(
+    app_handle: AppHandle,
+) -> Result<Vec<AppAlert>, String> {
+    crate::database::with_db(&app_handle, |conn| {
+        crate::services::app_alerts::load_alerts(conn).map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+pub async fn create_app_alert(
+    app_handle: AppHandle,
+    alert: CreateAppAlertRequest,
+) -> Result<i64, String> {
+    validate(&alert.app_pattern, alert.daily_threshold_seconds, alert.repeat_every_seconds)?;
+    let conn = crate::database::open(&app_handle)?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO app_alerts (app_pattern, daily_threshold_seconds, repeat_every_seconds, enabled, created_at)
+         VALUES (?1, ?2, ?3, 1, ?4)",
+        rusqlite::params![
+            alert.app_pattern.trim(),
+            alert.daily_threshold_seconds,
+            alert.repeat_every_seconds,
+            now,
+        ],
+    ).map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+
+    crate::services::app_alerts::reload(&conn);
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn update_app_alert(
+    app_handle: AppHandle,
+    alert: AppAlert,
+) -> Result<(), String> {
+    validate(&alert.app_pattern, alert.daily_threshold_seconds, alert.repeat_every_seconds)?;
+    let conn = crate::database::open(&app_handle)?;
+
+    let changed = conn.execute(
+        "UPDATE app_alerts SET app_pattern = ?1, daily_threshold_seconds = ?2, repeat_every_seconds = ?3, enabled = ?4
+         WHERE id = ?5",
+        rusqlite::params![
+            alert.app_pattern.trim(),
+            alert.daily_threshold_seconds,
+            alert.repeat_every_seconds,
+            alert.enabled as i64,
+            alert.id,
+        ],
+    ).map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err(format!("Alert {} not found", alert.id));
+    }
+
+    crate::services::app_alerts::reload(&conn);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_app_alert(
+    app_handle: AppHandle,
+    alert_id: i64,
+) -> Result<(), String> {
+    let conn = crate::database::open(&app_handle)?;
+    conn.execute("DELETE FROM app_alerts WHERE id = ?1", [alert_id])
+        .map_err(|e| e.to_string())?;
+
+    crate::services::app_alerts::reload(&conn);
+    Ok(())
+}
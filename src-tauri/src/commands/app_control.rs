@@ -1,26 +1,66 @@
 use tauri::{AppHandle, Manager};
+use crate::error::AppError;
+use crate::models::LogEntry;
+use crate::services::now_playing::NowPlaying;
+use crate::services::screen_capture::{OcrStatusSummary, OcrTestReport};
+use crate::services::service_status::ServiceStatus;
 
 #[tauri::command]
 pub async fn minimize_to_tray(
     app_handle: AppHandle,
 ) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        window.hide().map_err(|e| e.to_string())?;
-    }
-    Ok(())
+    crate::services::app_lock::hide_main_window(&app_handle)
 }
 
 #[tauri::command]
 pub async fn show_window(
     app_handle: AppHandle,
 ) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
-    }
+    crate::services::app_lock::reveal_main_window(&app_handle, None);
     Ok(())
 }
 
+/// Whether the window should show the unlock screen. The frontend asks on load
+/// since `lock://required` may fire before it is listening.
+#[tauri::command]
+pub async fn is_app_locked(app_handle: AppHandle) -> Result<bool, String> {
+    Ok(crate::services::app_lock::is_locked(&app_handle))
+}
+
+/// Returns false if the password is wrong.
+#[tauri::command]
+pub async fn unlock_app(app_handle: AppHandle, password: String) -> Result<bool, String> {
+    let app = app_handle.clone();
+    let unlocked = tokio::task::spawn_blocking(move || crate::services::app_lock::unlock(&app, &password))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !unlocked {
+        // Argon2 is slow already; this just takes the edge off guessing.
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    Ok(unlocked)
+}
+
+/// Lock now and hide the window to the tray.
+#[tauri::command]
+pub async fn lock_app(app_handle: AppHandle) -> Result<(), String> {
+    crate::services::app_lock::hide_main_window(&app_handle)
+}
+
+/// Set or change the unlock password; an empty `new_password` removes it and turns the lock off.
+#[tauri::command]
+pub async fn set_unlock_password(
+    app_handle: AppHandle,
+    current_password: String,
+    new_password: String,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        crate::services::app_lock::set_password(&app_handle, &current_password, &new_password)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 pub async fn quit_app(
     app_handle: AppHandle,
@@ -28,3 +68,73 @@ pub async fn quit_app(
     app_handle.exit(0);
     Ok(())
 }
+
+#[tauri::command]
+pub async fn get_service_status() -> Result<Vec<ServiceStatus>, String> {
+    Ok(crate::services::service_status::snapshot())
+}
+
+/// Latest OCR capture outcome and latest stored reading; live updates arrive as `ocr://status`.
+#[tauri::command]
+pub async fn get_ocr_status() -> Result<OcrStatusSummary, String> {
+    Ok(crate::services::screen_capture::ocr_status())
+}
+
+/// Current track for the mini player; live updates come on `media://now-playing`.
+#[tauri::command]
+pub async fn get_now_playing() -> Result<NowPlaying, String> {
+    Ok(crate::services::now_playing::current())
+}
+
+/// Pause tracking and screen capture for `minutes` (0 resumes now).
+/// Returns when tracking resumes, or `None` if it is running.
+#[tauri::command]
+pub async fn pause_tracking_for(
+    app_handle: AppHandle,
+    minutes: u32,
+) -> Result<Option<i64>, String> {
+    let conn = crate::database::open(&app_handle)?;
+    let paused_until = crate::services::tracking_pause::pause_for(&conn, minutes)?;
+    crate::apply_monitoring_state(&app_handle);
+    Ok(paused_until)
+}
+
+/// Whether the app is serving the demo database (from settings or `--demo`).
+#[tauri::command]
+pub async fn get_demo_mode() -> Result<bool, String> {
+    Ok(crate::services::demo_mode::is_active())
+}
+
+/// Capture the focused window once and report every OCR variant with its score,
+/// for tuning the quality threshold.
+#[tauri::command]
+pub async fn test_ocr_now() -> Result<OcrTestReport, AppError> {
+    tokio::task::spawn_blocking(crate::services::screen_capture::test_ocr_now)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map_err(AppError::Ocr)
+}
+
+/// Most recent log lines (default 200), optionally only `level_filter` and above
+/// and only targets containing `module_filter` (e.g. "screen_capture").
+#[tauri::command]
+pub async fn get_recent_logs(
+    app_handle: AppHandle,
+    lines: Option<usize>,
+    level_filter: Option<String>,
+    module_filter: Option<String>,
+) -> Result<Vec<LogEntry>, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let lines = lines.unwrap_or(200).min(5000);
+    tokio::task::spawn_blocking(move || {
+        crate::logging::read_recent(&data_dir, lines, level_filter.as_deref(), module_filter.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Change the log level until the app restarts. Returns the level now in effect.
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<String, String> {
+    crate::logging::set_level(&level).map(|l| l.to_string().to_lowercase())
+}
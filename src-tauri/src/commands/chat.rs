@@ -1,18 +1,15 @@
 use chrono::{Local, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
-use crate::models::Settings;
+use crate::error::AppError;
+use crate::models::{LlmUsage, TimeAccounting};
+use crate::services::query_engine::AgentAction;
 use std::collections::HashSet;
 
-fn load_settings(app_handle: &AppHandle) -> Option<Settings> {
-    let data_dir = app_handle.path().app_data_dir().ok()?;
-    let settings_path = data_dir.join("config").join("settings.json");
-    let data = std::fs::read_to_string(settings_path).ok()?;
-    let mut settings: Settings = serde_json::from_str(&data).ok()?;
-    crate::utils::config::apply_env_defaults(&mut settings);
-    Some(settings)
-}
+/// Version of the `ChatMessageResponse` shape. 2: follow-up actions come in
+/// `actions` and `content` never carries `[[IF_ACTION:...]]` markers.
+pub const CHAT_RESPONSE_VERSION: u32 = 2;
 
 fn load_recent_chat_context(
     conn: &rusqlite::Connection,
@@ -33,12 +30,50 @@ fn load_recent_chat_context(
         .query_map(rusqlite::params![session_id, limit], |row| {
             Ok(crate::services::query_engine::ChatMessage {
                 role: row.get::<_, String>(0)?,
-                content: row.get::<_, String>(1)?,
+                content: crate::services::query_engine::strip_action_markers(&row.get::<_, String>(1)?),
             })
         })
         .map_err(|e| e.to_string())?;
 
-    Ok(rows.filter_map(|r| r.ok()).collect())
+    // Newest rows were fetched first; the agent expects them in conversation order.
+    let mut messages: Vec<_> = rows.filter_map(|r| r.ok()).collect();
+    messages.reverse();
+    Ok(messages)
+}
+
+/// Deterministic session title: the first user message cut at a word boundary.
+fn fallback_session_title(message: &str) -> String {
+    const MAX_WORDS: usize = 8;
+    const MAX_CHARS: usize = 50;
+
+    let words: Vec<&str> = message.split_whitespace().collect();
+    if words.is_empty() {
+        return "New Chat".to_string();
+    }
+
+    let mut title = String::new();
+    let mut used = 0usize;
+    for word in &words {
+        let extra = if title.is_empty() { 0 } else { 1 };
+        if used == MAX_WORDS || title.chars().count() + extra + word.chars().count() > MAX_CHARS {
+            break;
+        }
+        if extra == 1 {
+            title.push(' ');
+        }
+        title.push_str(word);
+        used += 1;
+    }
+
+    let truncated = used < words.len();
+    // A single oversized token (long URL, emoji run) still needs a char-safe cut.
+    if title.is_empty() {
+        title = words[0].chars().take(MAX_CHARS).collect();
+    }
+    if truncated {
+        title.push_str("...");
+    }
+    title
 }
 
 // ─── Types ───
@@ -49,6 +84,20 @@ pub struct ChatSession {
     pub title: String,
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub pinned: bool,
+    /// Tokens and cost of all answers in the session; only filled by `get_chat_sessions`.
+    #[serde(default)]
+    pub usage: Option<LlmUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatSessionSearchResult {
+    pub session: ChatSession,
+    pub snippet: String,
+    pub match_count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,7 +108,19 @@ pub struct ChatMessageResponse {
     pub content: String,
     pub tool_calls: Option<Vec<crate::services::query_engine::AgentStep>>,
     pub activities: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
+    pub actions: Vec<AgentAction>,
     pub created_at: i64,
+    #[serde(default)]
+    pub response_version: u32,
+    /// Tokens the agent spent on this answer; absent on user messages and older answers.
+    #[serde(default)]
+    pub usage: Option<LlmUsage>,
+    /// Exact time split shown next to summary answers.
+    #[serde(default)]
+    pub time_accounting: Option<TimeAccounting>,
+    #[serde(default)]
+    pub confidence_factors: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,43 +134,68 @@ pub struct RecentModel {
 // ─── Commands ───
 
 #[tauri::command]
-pub async fn create_chat_session(app_handle: AppHandle) -> Result<ChatSession, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+pub async fn create_chat_session(app_handle: AppHandle) -> Result<ChatSession, AppError> {
+    let conn = crate::database::open(&app_handle)?;
 
     let session = ChatSession {
         id: Uuid::new_v4().to_string(),
         title: "New Chat".to_string(),
         created_at: Utc::now().timestamp(),
         updated_at: Utc::now().timestamp(),
+        archived: false,
+        pinned: false,
+        usage: None,
     };
 
     conn.execute(
         "INSERT INTO chat_sessions (id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
         rusqlite::params![session.id, session.title, session.created_at, session.updated_at],
-    ).map_err(|e| e.to_string())?;
+    )?;
 
     Ok(session)
 }
 
+/// `filter`: `active` (default) hides archived sessions, `pinned` returns only
+/// pinned ones, `all` includes archived. Pinned sessions always sort first.
 #[tauri::command]
-pub async fn get_chat_sessions(app_handle: AppHandle) -> Result<Vec<ChatSession>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+pub async fn get_chat_sessions(
+    app_handle: AppHandle,
+    filter: Option<String>,
+) -> Result<Vec<ChatSession>, AppError> {
+    let filter_clause = match filter.as_deref().unwrap_or("active") {
+        "all" => "",
+        "pinned" => "AND s.pinned = 1",
+        "active" => "AND s.archived = 0",
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown session filter '{}' (expected all, pinned or active)",
+                other
+            )))
+        }
+    };
 
-    let mut stmt = conn.prepare(
-        "SELECT s.id, s.title, s.created_at, s.updated_at
+    let conn = crate::database::open(&app_handle)?;
+
+    let sql = format!(
+        "SELECT s.id, s.title, s.created_at, s.updated_at, s.archived, s.pinned,
+                m.prompt_tokens, m.completion_tokens, m.calls, m.estimated, m.cost_usd, m.unpriced
          FROM chat_sessions s
          INNER JOIN (
-            SELECT session_id, COUNT(*) as msg_count
+            SELECT session_id, COUNT(*) as msg_count,
+                   COALESCE(SUM(json_extract(usage, '$.prompt_tokens')), 0) as prompt_tokens,
+                   COALESCE(SUM(json_extract(usage, '$.completion_tokens')), 0) as completion_tokens,
+                   COALESCE(SUM(json_extract(usage, '$.calls')), 0) as calls,
+                   COALESCE(MAX(json_extract(usage, '$.estimated')), 0) as estimated,
+                   SUM(json_extract(usage, '$.cost_usd')) as cost_usd,
+                   SUM(usage IS NOT NULL AND json_extract(usage, '$.cost_usd') IS NULL) as unpriced
             FROM chat_messages
             GROUP BY session_id
          ) m ON m.session_id = s.id
-         WHERE m.msg_count > 0
-         ORDER BY s.updated_at DESC"
-    ).map_err(|e| e.to_string())?;
+         WHERE m.msg_count > 0 {}
+         ORDER BY s.pinned DESC, s.updated_at DESC",
+        filter_clause
+    );
+    let mut stmt = conn.prepare(&sql)?;
 
     let sessions = stmt.query_map([], |row| {
         Ok(ChatSession {
@@ -117,8 +203,17 @@ pub async fn get_chat_sessions(app_handle: AppHandle) -> Result<Vec<ChatSession>
             title: row.get(1)?,
             created_at: row.get(2)?,
             updated_at: row.get(3)?,
+            archived: row.get::<_, i32>(4)? != 0,
+            pinned: row.get::<_, i32>(5)? != 0,
+            usage: Some(LlmUsage {
+                prompt_tokens: row.get(6)?,
+                completion_tokens: row.get(7)?,
+                calls: row.get(8)?,
+                estimated: row.get::<_, i64>(9)? != 0,
+                cost_usd: if row.get::<_, i64>(11)? > 0 { None } else { row.get(10)? },
+            }),
         })
-    }).map_err(|e| e.to_string())?
+    })?
     .filter_map(|r| r.ok())
     .collect();
 
@@ -126,16 +221,175 @@ pub async fn get_chat_sessions(app_handle: AppHandle) -> Result<Vec<ChatSession>
 }
 
 #[tauri::command]
-pub async fn delete_chat_session(app_handle: AppHandle, session_id: String) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+pub async fn rename_chat_session(
+    app_handle: AppHandle,
+    session_id: String,
+    title: String,
+) -> Result<(), AppError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(AppError::InvalidInput("Title cannot be empty".to_string()));
+    }
+
+    let conn = crate::database::open(&app_handle)?;
+
+    let updated = conn.execute(
+        "UPDATE chat_sessions SET title = ?1, updated_at = ?2 WHERE id = ?3",
+        rusqlite::params![title, Utc::now().timestamp(), session_id],
+    )?;
+    if updated == 0 {
+        return Err(AppError::NotFound(format!("Chat session {} not found", session_id)));
+    }
+
+    Ok(())
+}
+
+fn set_session_flag(
+    app_handle: &AppHandle,
+    session_id: &str,
+    column: &str,
+    value: bool,
+) -> Result<(), AppError> {
+    let conn = crate::database::open(app_handle)?;
+
+    let updated = conn.execute(
+        &format!("UPDATE chat_sessions SET {} = ?1 WHERE id = ?2", column),
+        rusqlite::params![value as i32, session_id],
+    )?;
+    if updated == 0 {
+        return Err(AppError::NotFound(format!("Chat session {} not found", session_id)));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn archive_chat_session(
+    app_handle: AppHandle,
+    session_id: String,
+    archived: Option<bool>,
+) -> Result<(), AppError> {
+    set_session_flag(&app_handle, &session_id, "archived", archived.unwrap_or(true))
+}
+
+/// Archiving is a soft delete: the session and its messages stay searchable.
+#[tauri::command]
+pub async fn set_chat_session_archived(
+    app_handle: AppHandle,
+    session_id: String,
+    archived: bool,
+) -> Result<(), AppError> {
+    set_session_flag(&app_handle, &session_id, "archived", archived)
+}
+
+#[tauri::command]
+pub async fn set_chat_session_pinned(
+    app_handle: AppHandle,
+    session_id: String,
+    pinned: bool,
+) -> Result<(), AppError> {
+    set_session_flag(&app_handle, &session_id, "pinned", pinned)
+}
+
+#[tauri::command]
+pub async fn search_chat_sessions(
+    app_handle: AppHandle,
+    query: String,
+    limit: Option<i32>,
+) -> Result<Vec<ChatSessionSearchResult>, AppError> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conn = crate::database::open(&app_handle)?;
+    let row_limit = limit.unwrap_or(20).clamp(1, 100);
+    let pattern = format!("%{}%", needle);
+
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.title, s.created_at, s.updated_at, s.archived, s.pinned,
+                COUNT(m.id) as hits,
+                (SELECT content FROM chat_messages
+                 WHERE session_id = s.id AND LOWER(content) LIKE ?1
+                 ORDER BY created_at DESC LIMIT 1) as snippet_source
+         FROM chat_sessions s
+         LEFT JOIN chat_messages m ON m.session_id = s.id AND LOWER(m.content) LIKE ?1
+         GROUP BY s.id
+         HAVING hits > 0 OR LOWER(s.title) LIKE ?1
+         ORDER BY s.pinned DESC, s.updated_at DESC
+         LIMIT ?2"
+    )?;
+
+    let rows = stmt.query_map(rusqlite::params![pattern, row_limit], |row| {
+        let source: Option<String> = row.get(7)?;
+        Ok((
+            ChatSession {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                created_at: row.get(2)?,
+                updated_at: row.get(3)?,
+                archived: row.get::<_, i32>(4)? != 0,
+                pinned: row.get::<_, i32>(5)? != 0,
+                usage: None,
+            },
+            row.get::<_, i64>(6)?,
+            source,
+        ))
+    })?;
+
+    let results = rows
+        .filter_map(|r| r.ok())
+        .map(|(session, match_count, source)| {
+            let snippet = source
+                .map(|text| chat_snippet_around(&text, &needle))
+                .unwrap_or_else(|| session.title.clone());
+            ChatSessionSearchResult { session, snippet, match_count }
+        })
+        .collect();
+
+    Ok(results)
+}
+
+pub(crate) fn chat_snippet_around(text: &str, needle: &str) -> String {
+    const CONTEXT_CHARS: usize = 60;
+    let chars: Vec<char> = text.chars().collect();
+    let lowered: Vec<char> = text.to_lowercase().chars().collect();
+    let needle_chars: Vec<char> = needle.chars().collect();
+
+    // Lowercasing can change char counts for a few scripts; fall back to the head of the text.
+    let hit = if lowered.len() == chars.len() && !needle_chars.is_empty() {
+        lowered
+            .windows(needle_chars.len())
+            .position(|w| w == needle_chars.as_slice())
+    } else {
+        None
+    };
+
+    let (start, end) = match hit {
+        Some(pos) => (
+            pos.saturating_sub(CONTEXT_CHARS),
+            (pos + needle_chars.len() + CONTEXT_CHARS).min(chars.len()),
+        ),
+        None => (0, (CONTEXT_CHARS * 2).min(chars.len())),
+    };
+
+    let body: String = chars[start..end].iter().collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!(
+        "{}{}{}",
+        if start > 0 { "..." } else { "" },
+        body,
+        if end < chars.len() { "..." } else { "" }
+    )
+}
+
+#[tauri::command]
+pub async fn delete_chat_session(app_handle: AppHandle, session_id: String) -> Result<(), AppError> {
+    let conn = crate::database::open(&app_handle)?;
 
     // Delete messages first, then session
-    conn.execute("DELETE FROM chat_messages WHERE session_id = ?1", [&session_id])
-        .map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM chat_sessions WHERE id = ?1", [&session_id])
-        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM chat_messages WHERE session_id = ?1", [&session_id])?;
+    conn.execute("DELETE FROM chat_sessions WHERE id = ?1", [&session_id])?;
 
     Ok(())
 }
@@ -144,30 +398,47 @@ pub async fn delete_chat_session(app_handle: AppHandle, session_id: String) -> R
 pub async fn get_chat_messages(
     app_handle: AppHandle,
     session_id: String,
-) -> Result<Vec<ChatMessageResponse>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<Vec<ChatMessageResponse>, AppError> {
+    let conn = crate::database::open(&app_handle)?;
 
     let mut stmt = conn.prepare(
-        "SELECT id, session_id, role, content, agent_steps, activities, created_at 
+        "SELECT id, session_id, role, content, agent_steps, activities, created_at, actions, usage,
+                time_accounting, confidence_factors
          FROM chat_messages WHERE session_id = ?1 ORDER BY created_at ASC"
-    ).map_err(|e| e.to_string())?;
+    )?;
 
     let messages = stmt.query_map([&session_id], |row| {
         let steps_json: Option<String> = row.get(4)?;
         let activities_json: Option<String> = row.get(5)?;
+        let actions_json: Option<String> = row.get(7)?;
+        let usage_json: Option<String> = row.get(8)?;
+        let accounting_json: Option<String> = row.get(9)?;
+        let factors_json: Option<String> = row.get(10)?;
+        let content: String = row.get(3)?;
+
+        // Messages stored before the `actions` column kept them as markers in the text.
+        let (content, legacy_actions) = crate::services::query_engine::extract_action_markers(&content);
+        let actions = actions_json
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(legacy_actions);
 
         Ok(ChatMessageResponse {
             id: row.get(0)?,
             session_id: row.get(1)?,
             role: row.get(2)?,
-            content: row.get(3)?,
+            content,
             tool_calls: steps_json.and_then(|s| serde_json::from_str(&s).ok()),
             activities: activities_json.and_then(|s| serde_json::from_str(&s).ok()),
+            actions,
             created_at: row.get(6)?,
+            response_version: CHAT_RESPONSE_VERSION,
+            usage: usage_json.and_then(|s| serde_json::from_str(&s).ok()),
+            time_accounting: accounting_json.and_then(|s| serde_json::from_str(&s).ok()),
+            confidence_factors: factors_json
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
         })
-    }).map_err(|e| e.to_string())?
+    })?
     .filter_map(|r| r.ok())
     .collect();
 
@@ -182,24 +453,29 @@ pub async fn send_chat_message(
     model: Option<String>,
     time_range: Option<String>,
     selected_sources: Option<Vec<String>>,
-) -> Result<ChatMessageResponse, String> {
+    profile: Option<String>,
+) -> Result<ChatMessageResponse, AppError> {
     let now = Utc::now().timestamp();
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
+    let db_path = crate::database::db_path(&app_handle)?;
 
     // 1. Load recent chat context (before inserting this message)
     let recent_context = {
-        let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
-        load_recent_chat_context(&conn, &session_id, 12)?
+        let conn = rusqlite::Connection::open(&db_path)?;
+        load_recent_chat_context(
+            &conn,
+            &session_id,
+            crate::services::query_engine::CHAT_HISTORY_PREFIX_MESSAGES as i64,
+        )?
     };
 
     // 2. Store user message
+    let is_first_exchange;
     {
-        let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+        let conn = rusqlite::Connection::open(&db_path)?;
         conn.execute(
             "INSERT INTO chat_messages (session_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4)",
             rusqlite::params![session_id, "user", message, now],
-        ).map_err(|e| e.to_string())?;
+        )?;
 
         // Update session title from first message
         let msg_count: i64 = conn.query_row(
@@ -208,28 +484,26 @@ pub async fn send_chat_message(
             |row| row.get(0),
         ).unwrap_or(0);
 
-        if msg_count <= 1 {
-            // Use first ~50 chars of first message as title
-            let title = if message.len() > 50 {
-                let end = message.char_indices().nth(50).map(|(i, _)| i).unwrap_or(message.len());
-                format!("{}...", &message[..end])
-            } else {
-                message.clone()
-            };
+        is_first_exchange = msg_count <= 1;
+        if is_first_exchange {
+            // Placeholder title until the AI title (if any) arrives.
+            let title = fallback_session_title(&message);
             conn.execute(
                 "UPDATE chat_sessions SET title = ?1, updated_at = ?2 WHERE id = ?3",
                 rusqlite::params![title, now, session_id],
-            ).map_err(|e| e.to_string())?;
+            )?;
         } else {
             conn.execute(
                 "UPDATE chat_sessions SET updated_at = ?1 WHERE id = ?2",
                 rusqlite::params![now, session_id],
-            ).map_err(|e| e.to_string())?;
+            )?;
         }
     }
 
     // 4. Run agentic search with conversation context
-    let mut settings = load_settings(&app_handle).unwrap_or_default();
+    let mut settings = crate::utils::config::load_settings(&app_handle);
+    // An explicit model still wins over the profile's.
+    settings.ai.apply_profile(profile.as_deref()).map_err(AppError::InvalidInput)?;
     if let Some(model_id) = model.as_ref().map(|m| m.trim()).filter(|m| !m.is_empty()) {
         settings.ai.model = model_id.to_string();
     }
@@ -258,18 +532,27 @@ pub async fn send_chat_message(
             &scoped_query,
             &settings,
             &recent_context,
+            Some(&session_id),
             time_range.as_deref(),
         ).await
             .unwrap_or_else(|e| crate::services::query_engine::AgentResult {
                 answer: format!("Sorry, I encountered an error: {}", e),
                 steps: vec![],
                 activities_referenced: vec![],
+                actions: vec![AgentAction::RetryWithQuery { query: message.clone() }],
+                usage: LlmUsage::default(),
+                time_accounting: None,
+                confidence_factors: Vec::new(),
             })
     } else {
         crate::services::query_engine::AgentResult {
             answer: "AI is not configured. Please set your API key in Settings.".to_string(),
             steps: vec![],
             activities_referenced: vec![],
+            actions: vec![],
+            usage: LlmUsage::default(),
+            time_accounting: None,
+            confidence_factors: Vec::new(),
         }
     };
 
@@ -277,20 +560,40 @@ pub async fn send_chat_message(
     let response_time = Utc::now().timestamp();
     let steps_json = serde_json::to_string(&agent_result.steps).ok();
     let activities_json = serde_json::to_string(&agent_result.activities_referenced).ok();
+    let actions_json = if agent_result.actions.is_empty() {
+        None
+    } else {
+        serde_json::to_string(&agent_result.actions).ok()
+    };
+    let usage = Some(agent_result.usage.clone()).filter(|u| u.calls > 0);
+    let usage_json = usage.as_ref().and_then(|u| serde_json::to_string(u).ok());
+    let accounting_json = agent_result
+        .time_accounting
+        .as_ref()
+        .and_then(|t| serde_json::to_string(t).ok());
+    let factors_json = if agent_result.confidence_factors.is_empty() {
+        None
+    } else {
+        serde_json::to_string(&agent_result.confidence_factors).ok()
+    };
 
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = rusqlite::Connection::open(&db_path)?;
     conn.execute(
-        "INSERT INTO chat_messages (session_id, role, content, agent_steps, activities, created_at) 
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        "INSERT INTO chat_messages (session_id, role, content, agent_steps, activities, actions, usage, time_accounting, confidence_factors, created_at) 
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
         rusqlite::params![
             session_id,
             "assistant",
             agent_result.answer,
             steps_json,
             activities_json,
+            actions_json,
+            usage_json,
+            accounting_json,
+            factors_json,
             response_time
         ],
-    ).map_err(|e| e.to_string())?;
+    )?;
 
     let msg_id = conn.last_insert_rowid();
 
@@ -308,6 +611,42 @@ pub async fn send_chat_message(
         );
     }
 
+    // Name the session after its first exchange without holding up the answer.
+    if is_first_exchange && settings.ai.enabled && !resolved_api_key.is_empty() {
+        let title_handle = app_handle.clone();
+        let title_db_path = db_path.clone();
+        let title_session_id = session_id.clone();
+        let title_model = settings.ai.model.clone();
+        let title_key = resolved_api_key.clone();
+        let first_message = message.clone();
+        let first_answer = agent_result.answer.clone();
+        tauri::async_runtime::spawn(async move {
+            let title = match crate::services::query_engine::generate_chat_title(
+                &title_handle,
+                &title_model,
+                &title_key,
+                &first_message,
+                &first_answer,
+            ).await {
+                Ok(title) => title,
+                Err(e) => {
+                    println!("[Chat] Title generation failed, keeping fallback: {}", e);
+                    return;
+                }
+            };
+            let Ok(conn) = rusqlite::Connection::open(&title_db_path) else { return; };
+            if conn.execute(
+                "UPDATE chat_sessions SET title = ?1 WHERE id = ?2",
+                rusqlite::params![title, title_session_id],
+            ).is_ok() {
+                let _ = title_handle.emit(
+                    "chat://title",
+                    serde_json::json!({ "session_id": title_session_id, "title": title }),
+                );
+            }
+        });
+    }
+
     Ok(ChatMessageResponse {
         id: msg_id,
         session_id,
@@ -315,7 +654,12 @@ pub async fn send_chat_message(
         content: agent_result.answer,
         tool_calls: Some(agent_result.steps),
         activities: Some(agent_result.activities_referenced),
+        actions: agent_result.actions,
         created_at: response_time,
+        response_version: CHAT_RESPONSE_VERSION,
+        usage,
+        time_accounting: agent_result.time_accounting,
+        confidence_factors: agent_result.confidence_factors,
     })
 }
 
@@ -323,10 +667,8 @@ pub async fn send_chat_message(
 pub async fn get_recent_models(
     app_handle: AppHandle,
     limit: Option<i32>,
-) -> Result<Vec<RecentModel>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<Vec<RecentModel>, AppError> {
+    let conn = crate::database::open(&app_handle)?;
     let row_limit = limit.unwrap_or(5).clamp(1, 20);
 
     let mut stmt = conn
@@ -335,8 +677,7 @@ pub async fn get_recent_models(
              FROM ai_model_usage
              ORDER BY last_used DESC
              LIMIT ?1",
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
 
     let rows = stmt
         .query_map([row_limit], |row| {
@@ -346,8 +687,7 @@ pub async fn get_recent_models(
                 use_count: row.get(2)?,
                 last_used: row.get(3)?,
             })
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
@@ -356,14 +696,62 @@ pub async fn get_recent_models(
 pub async fn remove_recent_model(
     app_handle: AppHandle,
     model_id: String,
-) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<(), AppError> {
+    let conn = crate::database::open(&app_handle)?;
     conn.execute(
         "DELETE FROM ai_model_usage WHERE model_id = ?1",
         [&model_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::fallback_session_title;
+
+    #[test]
+    fn short_messages_are_kept_whole() {
+        assert_eq!(fallback_session_title("  what did I do   today?\n"), "what did I do today?");
+        assert_eq!(fallback_session_title(" \n\t "), "New Chat");
+    }
+
+    #[test]
+    fn long_messages_stop_at_a_word_boundary() {
+        let title = fallback_session_title("one two three four five six seven eight nine ten");
+        assert_eq!(title, "one two three four five six seven eight...");
+    }
+
+    #[test]
+    fn emoji_words_count_as_words() {
+        assert_eq!(fallback_session_title("🚀 ship the release 🎉"), "🚀 ship the release 🎉");
+        assert_eq!(
+            fallback_session_title("👋 😀 🎉 🚀 🔥 ✅ 💡 📈 🧠 🐛"),
+            "👋 😀 🎉 🚀 🔥 ✅ 💡 📈..."
+        );
+        // A family emoji is one word made of several chars joined by ZWJ.
+        assert_eq!(fallback_session_title("👨‍👩‍👧 budget"), "👨‍👩‍👧 budget");
+    }
+
+    #[test]
+    fn length_limit_counts_chars_not_bytes() {
+        // 49 chars but 58 bytes: a byte limit would stop two words earlier.
+        let title = fallback_session_title("größte änderung für übermäßig lange straßen naïve café résumé");
+        assert_eq!(title, "größte änderung für übermäßig lange straßen naïve...");
+
+        let title = fallback_session_title("сколько времени я провёл в редакторе кода на прошлой неделе");
+        assert_eq!(title, "сколько времени я провёл в редакторе кода на...");
+        assert!(title.chars().count() <= 53);
+    }
+
+    #[test]
+    fn unbroken_text_is_cut_on_a_char_boundary() {
+        let chinese = "我想知道上周我在编程工具上一共花了多少时间以及每天的平均使用时长是多少还有哪些应用占用了最多的时间和注意力能不能帮我整理一下";
+        assert!(chinese.chars().count() > 50);
+        let title = fallback_session_title(chinese);
+        assert_eq!(title, format!("{}...", chinese.chars().take(50).collect::<String>()));
+
+        let emoji_run = "😀".repeat(60);
+        let title = fallback_session_title(&emoji_run);
+        assert_eq!(title, format!("{}...", "😀".repeat(50)));
+    }
+}
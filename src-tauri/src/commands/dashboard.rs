@@ -1,42 +1,131 @@
 use tauri::AppHandle;
 
-use crate::models::DashboardOverview;
+use crate::error::AppError;
+use crate::models::{ContactTimeline, DailyRecap, DashboardOverview, MeetingsReport, ProjectChanges, SinceLastSeen};
 
 #[tauri::command]
 pub async fn get_dashboard_overview(
     app_handle: AppHandle,
     refresh: Option<bool>,
-) -> Result<DashboardOverview, String> {
+) -> Result<DashboardOverview, AppError> {
     if refresh.unwrap_or(false) {
-        return crate::services::dashboard_engine::refresh_dashboard_snapshot(&app_handle).await;
+        return crate::services::dashboard_engine::refresh_dashboard_snapshot(&app_handle)
+            .await
+            .map_err(AppError::from);
     }
 
     if let Some(snapshot) = crate::services::dashboard_engine::get_dashboard_snapshot(&app_handle)? {
         return Ok(snapshot);
     }
 
-    crate::services::dashboard_engine::refresh_dashboard_snapshot(&app_handle).await
+    crate::services::dashboard_engine::refresh_dashboard_snapshot(&app_handle)
+        .await
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
 pub async fn refresh_dashboard_overview(
     app_handle: AppHandle,
-) -> Result<DashboardOverview, String> {
-    crate::services::dashboard_engine::refresh_dashboard_snapshot(&app_handle).await
+) -> Result<DashboardOverview, AppError> {
+    crate::services::dashboard_engine::refresh_dashboard_snapshot(&app_handle)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Record that the user is looking at `surface` ("dashboard" or "timeline") now.
+#[tauri::command]
+pub async fn mark_surface_seen(
+    app_handle: AppHandle,
+    surface: String,
+) -> Result<i64, AppError> {
+    let surface = crate::services::last_seen::validate_surface(&surface).map_err(AppError::InvalidInput)?;
+    let conn = crate::database::open(&app_handle)?;
+    let now = chrono::Utc::now().timestamp();
+    crate::services::last_seen::mark(&conn, surface, now)?;
+    Ok(now)
+}
+
+/// Activity, file changes, media and notifications since `surface` was last marked seen,
+/// at most the last 48h.
+#[tauri::command]
+pub async fn get_since_last_seen(
+    app_handle: AppHandle,
+    surface: String,
+) -> Result<SinceLastSeen, AppError> {
+    let surface = crate::services::last_seen::validate_surface(&surface).map_err(AppError::InvalidInput)?;
+    let conn = crate::database::open(&app_handle)?;
+    crate::services::last_seen::compute(&conn, surface, chrono::Utc::now().timestamp())
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_daily_recap(
+    app_handle: AppHandle,
+    date_key: Option<String>,
+) -> Result<DailyRecap, AppError> {
+    crate::services::dashboard_engine::build_daily_recap(&app_handle, date_key.as_deref())
+}
+
+/// The day as a Markdown journal (Overview, Projects, Communications, Media,
+/// Timeline highlights). `date_key` is YYYY-MM-DD; today when omitted.
+#[tauri::command]
+pub async fn export_journal(
+    app_handle: AppHandle,
+    date_key: Option<String>,
+) -> Result<String, AppError> {
+    crate::services::dashboard_engine::build_journal(&app_handle, date_key.as_deref())
 }
 
 #[tauri::command]
 pub async fn summarize_contact(
     app_handle: AppHandle,
     name: String,
-) -> Result<String, String> {
-    crate::services::dashboard_engine::summarize_contact(&app_handle, &name).await
+) -> Result<String, AppError> {
+    crate::services::dashboard_engine::summarize_contact(&app_handle, &name)
+        .await
 }
 
 #[tauri::command]
 pub async fn summarize_project(
     app_handle: AppHandle,
     name: String,
-) -> Result<String, String> {
-    crate::services::dashboard_engine::summarize_project(&app_handle, &name).await
+) -> Result<String, AppError> {
+    crate::services::dashboard_engine::summarize_project(&app_handle, &name)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_contact_timeline(
+    app_handle: AppHandle,
+    contact_name_or_id: String,
+    days: Option<i64>,
+    limit: Option<usize>,
+) -> Result<ContactTimeline, AppError> {
+    crate::services::dashboard_engine::build_contact_timeline(
+        &app_handle,
+        &contact_name_or_id,
+        days.unwrap_or(7),
+        limit.unwrap_or(50),
+    )
+}
+
+#[tauri::command]
+pub async fn get_project_changes(
+    app_handle: AppHandle,
+    name: String,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<ProjectChanges, AppError> {
+    crate::services::dashboard_engine::build_project_changes(&app_handle, &name, start_ts, end_ts)
+}
+
+/// Meetings detected from Teams, Zoom and Google Meet window titles in `[start_ts, end_ts)`.
+#[tauri::command]
+pub async fn get_meetings(
+    app_handle: AppHandle,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<MeetingsReport, AppError> {
+    let conn = crate::database::open(&app_handle)?;
+    crate::services::dashboard_engine::meetings_report(&conn, start_ts, end_ts, false).map_err(AppError::from)
 }
@@ -1,4 +1,4 @@
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use crate::models::ManualEntry;
 
 #[tauri::command]
@@ -9,10 +9,7 @@ pub async fn create_entry(
     content: Option<String>,
     tags: Option<Vec<String>>,
 ) -> Result<String, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     let now = chrono::Utc::now().timestamp();
     let tags_blob = serde_json::to_vec(&tags.unwrap_or_default()).map_err(|e| e.to_string())?;
@@ -33,10 +30,7 @@ pub async fn get_entries(
     status: Option<String>,
     limit: Option<i32>,
 ) -> Result<Vec<ManualEntry>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     let limit_clause = limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default();
     let query = format!(
@@ -80,10 +74,7 @@ pub async fn update_entry_status(
     id: i64,
     status: String,
 ) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     let now = chrono::Utc::now().timestamp();
     let completed_at = if status == "completed" { Some(now) } else { None };
@@ -101,10 +92,7 @@ pub async fn delete_entry(
     app_handle: AppHandle,
     id: i64,
 ) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     conn.execute(
         "DELETE FROM manual_entries WHERE id = ?1",
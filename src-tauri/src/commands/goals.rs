@@ -0,0 +1,94 @@
+use tauri::AppHandle;
+use crate::models::{CategoryGoal, CreateCategoryGoalRequest, GoalStreak};
+
+fn validate(category_id: i64, target_seconds: i64) -> Result<(), String> {
+    if crate::utils::categories::get(category_id as i32).is_none() {
+        return Err(format!("Category {} not found", category_id));
+    }
+    if target_seconds <= 0 || target_seconds > 24 * 3600 {
+        return Err("Target must be between 1 second and 24 hours".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_category_goals(
+    app_handle: AppHandle,
+) -> Result<Vec<CategoryGoal>, String> {
+    crate::database::with_db(&app_handle, |conn| {
+        crate::services::goal_streaks::load_goals(conn).map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+pub async fn create_category_goal(
+    app_handle: AppHandle,
+    goal: CreateCategoryGoalRequest,
+) -> Result<i64, String> {
+    validate(goal.category_id, goal.target_seconds)?;
+    let conn = crate::database::open(&app_handle)?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO category_goals (category_id, kind, target_seconds, no_data_policy, enabled, created_at)
+         VALUES (?1, ?2, ?3, ?4, 1, ?5)",
+        rusqlite::params![
+            goal.category_id,
+            goal.kind.as_str(),
+            goal.target_seconds,
+            goal.no_data_policy.as_str(),
+            now,
+        ],
+    ).map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Changing the condition resets the recorded best, since old runs no longer describe it.
+#[tauri::command]
+pub async fn update_category_goal(
+    app_handle: AppHandle,
+    goal: CategoryGoal,
+) -> Result<(), String> {
+    validate(goal.category_id, goal.target_seconds)?;
+    let conn = crate::database::open(&app_handle)?;
+
+    let changed = conn.execute(
+        "UPDATE category_goals
+         SET best_streak = CASE WHEN category_id = ?1 AND kind = ?2 AND target_seconds = ?3 AND no_data_policy = ?4
+                                THEN best_streak ELSE 0 END,
+             category_id = ?1, kind = ?2, target_seconds = ?3, no_data_policy = ?4, enabled = ?5
+         WHERE id = ?6",
+        rusqlite::params![
+            goal.category_id,
+            goal.kind.as_str(),
+            goal.target_seconds,
+            goal.no_data_policy.as_str(),
+            goal.enabled as i64,
+            goal.id,
+        ],
+    ).map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err(format!("Goal {} not found", goal.id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_category_goal(
+    app_handle: AppHandle,
+    goal_id: i64,
+) -> Result<(), String> {
+    let conn = crate::database::open(&app_handle)?;
+    conn.execute("DELETE FROM category_goals WHERE id = ?1", [goal_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Current and best streak plus a 60-day met/missed/no-data calendar for each enabled goal.
+#[tauri::command]
+pub async fn get_goal_streaks(
+    app_handle: AppHandle,
+) -> Result<Vec<GoalStreak>, String> {
+    let conn = crate::database::open(&app_handle)?;
+    crate::services::goal_streaks::refresh(&app_handle, &conn)
+}
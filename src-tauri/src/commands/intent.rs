@@ -1,5 +1,5 @@
-use tauri::{AppHandle, Manager};
-use crate::models::{Intent, IntentType, Action, ActionType, Settings};
+use tauri::AppHandle;
+use crate::models::{Intent, IntentType, Action, ActionType, CurrentFocus, PatternKind, QuickAction};
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +22,8 @@ struct ChatSendMessage {
 #[derive(Deserialize)]
 struct ChatResponse {
     choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<crate::services::llm_client::ProviderUsage>,
 }
 
 #[derive(Deserialize)]
@@ -79,14 +81,15 @@ pub async fn parse_intent(
     input: String,
 ) -> Result<Intent, String> {
     // Try AI-powered parsing first
-    let settings = load_settings(&app_handle).unwrap_or_default();
+    let mut settings = crate::utils::config::load_settings(&app_handle);
+    settings.ai.apply_profile_or_default(None);
     
     if settings.ai.enabled {
         let api_key = settings.ai.api_key.clone();
         let model = settings.ai.model.clone();
         
         if !api_key.is_empty() {
-            match ai_parse_intent(&input, &api_key, &model).await {
+            match ai_parse_intent(&app_handle, &input, &api_key, &model).await {
                 Ok(intent) => return Ok(intent),
                 Err(e) => {
                     log::warn!("AI intent parsing failed, falling back to local: {}", e);
@@ -99,15 +102,17 @@ pub async fn parse_intent(
     Ok(local_parse_intent(&app_handle, &input).await)
 }
 
-async fn ai_parse_intent(input: &str, api_key: &str, model: &str) -> Result<Intent, String> {
-    let client = reqwest::Client::new();
-    
+async fn ai_parse_intent(app_handle: &AppHandle, input: &str, api_key: &str, model: &str) -> Result<Intent, String> {
     let request = ChatRequest {
         model: model.to_string(),
         messages: vec![
             ChatSendMessage {
                 role: "system".to_string(),
-                content: SYSTEM_PROMPT.to_string(),
+                content: format!(
+                    "{}\n\n{} This covers `reasoning` and any notification text.",
+                    SYSTEM_PROMPT,
+                    crate::utils::i18n::language_instruction(input)
+                ),
             },
             ChatSendMessage {
                 role: "user".to_string(),
@@ -118,20 +123,15 @@ async fn ai_parse_intent(input: &str, api_key: &str, model: &str) -> Result<Inte
         max_tokens: 512,
     };
     
-    let response = client
-        .post("https://integrate.api.nvidia.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API returned error {}: {}", status, body));
-    }
+    let response = crate::services::llm_client::send_chat_request(
+        api_key,
+        &request.model,
+        &request,
+        crate::services::llm_client::DEFAULT_TIMEOUT,
+        crate::services::llm_client::Priority::Interactive,
+        None,
+    )
+    .await?;
     
     let body_text = response.text().await
         .map_err(|e| format!("Failed to read response: {}", e))?;
@@ -146,6 +146,14 @@ async fn ai_parse_intent(input: &str, api_key: &str, model: &str) -> Result<Inte
     let content = choice.message.content.clone()
         .or_else(|| choice.message.reasoning_content.clone())
         .unwrap_or_default();
+
+    let usage = crate::services::llm_client::call_usage(
+        model,
+        chat_response.usage,
+        crate::services::llm_client::estimate_prompt_tokens(request.messages.iter().map(|m| m.content.as_str())),
+        &content,
+    );
+    crate::services::llm_client::record_usage(app_handle, crate::services::llm_client::CALLER_INTENT, model, &usage);
     
     // Clean possible markdown code fences
     let cleaned = content
@@ -309,42 +317,32 @@ async fn local_parse_intent(app_handle: &AppHandle, input: &str) -> Intent {
 }
 
 async fn check_time_patterns(app_handle: &AppHandle) -> Result<Vec<Action>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     // Get current hour
     let now = chrono::Local::now();
-    let current_hour = now.format("%H").to_string().parse::<u32>().unwrap_or(9);
+    let current_hour = now.format("%H").to_string().parse::<u64>().unwrap_or(9);
+
+    let routines = crate::services::pattern_engine::load_patterns(&conn, Some(PatternKind::Routine), false)
+        .map_err(|e| e.to_string())?;
 
-    let mut stmt = conn.prepare(
-        "SELECT pattern_data FROM patterns 
-         WHERE pattern_type = 'time_of_day' AND is_active = 1"
-    ).map_err(|e| e.to_string())?;
-    
-    let rows = stmt.query_map([], |row| {
-        let blob: Vec<u8> = row.get(0)?;
-        Ok(blob)
-    }).map_err(|e| e.to_string())?;
-    
     let mut actions = Vec::new();
-    
-    for blob in rows {
-        if let Ok(data) = blob {
-           // Parse JSON manually or use a struct
-           if let Ok(value) = serde_json::from_slice::<serde_json::Value>(&data) {
-               if let Some(h) = value.get("hour").and_then(|v| v.as_u64()) {
-                   if h as u32 == current_hour {
-                       if let Some(app) = value.get("app_name").and_then(|v| v.as_str()) {
-                           actions.push(Action {
-                               action_type: ActionType::LaunchApp,
-                               target: app.to_string(),
-                               args: vec![],
-                           });
-                       }
-                   }
-               }
-           }
+    let mut seen_apps = std::collections::HashSet::new();
+    for routine in routines {
+        let start = routine.data.get("hour_start").and_then(|v| v.as_u64()).unwrap_or(u64::MAX);
+        let end = routine.data.get("hour_end").and_then(|v| v.as_u64()).unwrap_or(0);
+        if current_hour < start || current_hour > end {
+            continue;
+        }
+        let apps = routine.data.get("apps").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        for app in apps.iter().filter_map(|a| a.as_str()) {
+            if seen_apps.insert(app.to_lowercase()) {
+                actions.push(Action {
+                    action_type: ActionType::LaunchApp,
+                    target: app.to_string(),
+                    args: vec![],
+                });
+            }
         }
     }
     
@@ -353,30 +351,32 @@ async fn check_time_patterns(app_handle: &AppHandle) -> Result<Vec<Action>, Stri
         actions.insert(0, Action {
             action_type: ActionType::ShowNotification,
             target: "Routine Detected".to_string(),
-            args: vec![format!("It's {} - usually you use these apps:", now.format("%I %p"))], 
+            args: vec![format!(
+                "It's {} - usually you use these apps:",
+                crate::utils::time::format_local_time(now.timestamp()).unwrap_or_default()
+            )], 
         });
     }
     
     Ok(actions)
 }
 
-fn load_settings(app_handle: &AppHandle) -> Option<Settings> {
-    let data_dir = app_handle.path().app_data_dir().ok()?;
-    let config_path = data_dir.join("config").join("settings.json");
-    
-    if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path).ok()?;
-        serde_json::from_str(&content).ok()
-    } else {
-        None
-    }
-}
-
 #[tauri::command]
 pub async fn execute_intent(
+    app_handle: AppHandle,
     intent: Intent,
 ) -> Result<(), String> {
-    for action in intent.suggested_actions {
+    let result = run_actions(&app_handle, &intent.suggested_actions).await;
+    if !intent.suggested_actions.is_empty() {
+        if let Err(e) = record_execution(&app_handle, &intent, result.as_ref().err()) {
+            println!("[Intent] Failed to record execution: {}", e);
+        }
+    }
+    result
+}
+
+async fn run_actions(app_handle: &AppHandle, actions: &[Action]) -> Result<(), String> {
+    for action in actions {
         match action.action_type {
             ActionType::LaunchApp => {
                 if cfg!(target_os = "windows") {
@@ -392,9 +392,496 @@ pub async fn execute_intent(
             ActionType::ShowNotification => {
                 log::info!("Notification: {} - {:?}", action.target, action.args);
             }
+            ActionType::ExecuteWorkflow => {
+                let run = crate::commands::workflow::execute_workflow(app_handle.clone(), action.target.clone(), None).await?;
+                if !run.executed {
+                    let failed: Vec<String> = run.checks.iter().filter(|c| !c.passed).map(|c| c.detail.clone()).collect();
+                    return Err(format!("Workflow not run: {}", failed.join("; ")));
+                }
+            }
             _ => {}
         }
     }
     
     Ok(())
 }
+
+fn record_execution(app_handle: &AppHandle, intent: &Intent, error: Option<&String>) -> Result<(), String> {
+    let conn = crate::database::open(&app_handle)?;
+
+    let intent_type = serde_json::to_value(&intent.intent_type)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let actions = serde_json::to_string(&intent.suggested_actions).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO intent_executions (intent_type, action_key, actions, success, error, executed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            intent_type,
+            action_key(&intent.suggested_actions),
+            actions,
+            error.is_none() as i64,
+            error,
+            chrono::Utc::now().timestamp(),
+        ],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ─── Quick actions (command palette) ───
+
+const QUICK_ACTION_LIMIT: usize = 12;
+/// Older history counts half as much every `HALF_LIFE_DAYS`.
+const HALF_LIFE_DAYS: f64 = 14.0;
+/// History older than this contributes less than 2% and is not read at all.
+const HISTORY_WINDOW_DAYS: i64 = 84;
+const HISTORY_WEIGHT: f64 = 1.0;
+const ROUTINE_WEIGHT: f64 = 0.8;
+const WORKFLOW_WEIGHT: f64 = 0.7;
+
+/// Stable id for a list of actions, e.g. `launch_app:code`; used to merge
+/// candidates from different sources and to key feedback.
+fn action_key(actions: &[Action]) -> String {
+    actions
+        .iter()
+        .map(|a| {
+            let kind = serde_json::to_value(&a.action_type)
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+            format!("{}:{}", kind, a.target.trim().to_lowercase())
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Exponential decay weight for something that happened `age_secs` ago.
+fn decay_weight(age_secs: i64) -> f64 {
+    let age_days = age_secs.max(0) as f64 / 86_400.0;
+    0.5f64.powf(age_days / HALF_LIFE_DAYS)
+}
+
+/// Ranking multiplier from accept/dismiss feedback, kept within 0.25–2.0 so a
+/// few clicks can reorder suggestions without burying them for good.
+fn feedback_multiplier(accepted: f64, rejected: f64) -> f64 {
+    ((1.0 + accepted) / (1.0 + rejected)).clamp(0.25, 2.0)
+}
+
+/// Local hour of the current time in the configured timezone.
+fn current_local_hour(now: i64) -> u32 {
+    use chrono::Timelike;
+    chrono::DateTime::from_timestamp(now, 0)
+        .map(|dt| dt.with_timezone(&crate::utils::time::offset_at(now)).hour())
+        .unwrap_or(0)
+}
+
+fn action_label(actions: &[Action]) -> String {
+    let Some(first) = actions.first() else {
+        return String::new();
+    };
+    let label = match first.action_type {
+        ActionType::LaunchApp => format!("Open {}", first.target),
+        ActionType::OpenUrl | ActionType::OpenFile => format!("Open {}", first.target),
+        ActionType::CloseApp => format!("Close {}", first.target),
+        ActionType::ShowNotification => first.target.clone(),
+        ActionType::ExecuteWorkflow => format!("Run workflow {}", first.target),
+    };
+    if actions.len() > 1 {
+        format!("{} (+{} more)", label, actions.len() - 1)
+    } else {
+        label
+    }
+}
+
+fn is_excluded_app(actions: &[Action], excluded_apps: &[String]) -> bool {
+    actions.iter().any(|a| {
+        matches!(a.action_type, ActionType::LaunchApp | ActionType::CloseApp) && {
+            let target = a.target.to_lowercase();
+            excluded_apps.iter().any(|ex| target.contains(ex.as_str()))
+        }
+    })
+}
+
+struct Candidate {
+    source: &'static str,
+    label: String,
+    reason: String,
+    intent_type: IntentType,
+    actions: Vec<Action>,
+    /// Source-relative strength in 0..=1 before weighting.
+    strength: f64,
+}
+
+fn history_candidates(conn: &rusqlite::Connection, now: i64) -> Result<Vec<Candidate>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT intent_type, action_key, actions, executed_at
+         FROM intent_executions
+         WHERE success = 1 AND executed_at >= ?1
+         ORDER BY executed_at DESC"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([now - HISTORY_WINDOW_DAYS * 86_400], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    }).map_err(|e| e.to_string())?;
+
+    // key -> (intent_type, actions, decayed score, uses)
+    let mut grouped: HashMap<String, (String, Vec<Action>, f64, usize)> = HashMap::new();
+    for (intent_type, key, actions_json, executed_at) in rows.filter_map(|r| r.ok()) {
+        let entry = grouped.entry(key).or_insert_with(|| {
+            let actions = serde_json::from_str(&actions_json).unwrap_or_default();
+            (intent_type, actions, 0.0, 0)
+        });
+        entry.2 += decay_weight(now - executed_at);
+        entry.3 += 1;
+    }
+
+    let max_score = grouped.values().map(|g| g.2).fold(0.0, f64::max);
+    Ok(grouped
+        .into_values()
+        .filter(|(_, actions, _, _)| !actions.is_empty())
+        .map(|(intent_type, actions, score, uses)| Candidate {
+            source: "history",
+            label: action_label(&actions),
+            reason: format!("Used {} time{} recently", uses, if uses == 1 { "" } else { "s" }),
+            intent_type: serde_json::from_value(serde_json::Value::String(intent_type))
+                .unwrap_or(IntentType::Unknown),
+            actions,
+            strength: if max_score > 0.0 { score / max_score } else { 0.0 },
+        })
+        .collect())
+}
+
+/// Apps used in the current local hour on past days, one count per app and day.
+fn routine_candidates(conn: &rusqlite::Connection, now: i64) -> Result<Vec<Candidate>, String> {
+    let hour = current_local_hour(now);
+    let local = crate::utils::time::ROW_LOCAL_DATETIME_SQL;
+    let sql = format!(
+        "SELECT app_name, MAX(start_time)
+         FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2
+           AND CAST(strftime('%H', {local}) AS INTEGER) = ?3
+           AND app_name IS NOT NULL AND app_name != ''
+         GROUP BY app_name, date({local})"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    // Exclude the last hour so the app the user is already in doesn't suggest itself.
+    let rows = stmt.query_map(
+        rusqlite::params![now - HISTORY_WINDOW_DAYS * 86_400, now - 3600, hour],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+    ).map_err(|e| e.to_string())?;
+
+    let mut scores: HashMap<String, (f64, usize)> = HashMap::new();
+    for (app, seen_at) in rows.filter_map(|r| r.ok()) {
+        let entry = scores.entry(app).or_insert((0.0, 0));
+        entry.0 += decay_weight(now - seen_at);
+        entry.1 += 1;
+    }
+
+    let max_score = scores.values().map(|s| s.0).fold(0.0, f64::max);
+    let time_label = crate::utils::time::format_local_time(now).unwrap_or_else(|| format!("{:02}:00", hour));
+    Ok(scores
+        .into_iter()
+        // A single day is a coincidence, not a routine.
+        .filter(|(_, (_, days))| *days >= 2)
+        .map(|(app, (score, days))| {
+            let actions = vec![Action {
+                action_type: ActionType::LaunchApp,
+                target: app.clone(),
+                args: vec![],
+            }];
+            Candidate {
+                source: "routine",
+                label: action_label(&actions),
+                reason: format!("You used {} around {} on {} recent days", app, time_label, days),
+                intent_type: IntentType::WorkStart,
+                actions,
+                strength: if max_score > 0.0 { score / max_score } else { 0.0 },
+            }
+        })
+        .collect())
+}
+
+async fn workflow_candidates(app_handle: &AppHandle) -> Vec<Candidate> {
+    let suggestions = crate::commands::workflow::get_workflow_suggestions(app_handle.clone())
+        .await
+        .unwrap_or_default();
+    suggestions
+        .into_iter()
+        .map(|s| Candidate {
+            source: "workflow",
+            label: format!("Run workflow {}", s.workflow.name),
+            reason: s.reason,
+            intent_type: IntentType::WorkStart,
+            actions: vec![Action {
+                action_type: ActionType::ExecuteWorkflow,
+                target: s.workflow.id,
+                args: vec![],
+            }],
+            strength: s.relevance_score as f64,
+        })
+        .collect()
+}
+
+/// Decayed accept/reject totals per quick-action id.
+fn load_feedback(conn: &rusqlite::Connection, now: i64) -> Result<HashMap<String, (f64, f64)>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT action_id, accepted, created_at FROM quick_action_feedback WHERE created_at >= ?1"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([now - HISTORY_WINDOW_DAYS * 86_400], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? != 0, row.get::<_, i64>(2)?))
+    }).map_err(|e| e.to_string())?;
+
+    let mut feedback: HashMap<String, (f64, f64)> = HashMap::new();
+    for (action_id, accepted, created_at) in rows.filter_map(|r| r.ok()) {
+        let entry = feedback.entry(action_id).or_insert((0.0, 0.0));
+        let weight = decay_weight(now - created_at);
+        if accepted {
+            entry.0 += weight;
+        } else {
+            entry.1 += weight;
+        }
+    }
+    Ok(feedback)
+}
+
+/// Ranked suggestions for the command palette, built from successful intent
+/// history, apps usually used at this hour and workflow suggestions.
+#[tauri::command]
+pub async fn get_quick_actions(
+    app_handle: AppHandle,
+) -> Result<Vec<QuickAction>, String> {
+    let settings = crate::utils::config::load_settings(&app_handle);
+    let excluded_apps: Vec<String> = settings
+        .tracking
+        .exclude_apps
+        .iter()
+        .map(|a| a.trim().to_lowercase())
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    let now = chrono::Utc::now().timestamp();
+    let mut candidates = workflow_candidates(&app_handle).await;
+
+    let conn = crate::database::open(&app_handle)?;
+    candidates.extend(history_candidates(&conn, now)?);
+    candidates.extend(routine_candidates(&conn, now)?);
+    let feedback = load_feedback(&conn, now)?;
+
+    // The same action can come from several sources; scores add up and the
+    // strongest source supplies the label and reason.
+    let mut merged: HashMap<String, (QuickAction, f64)> = HashMap::new();
+    for candidate in candidates {
+        if candidate.actions.is_empty() || is_excluded_app(&candidate.actions, &excluded_apps) {
+            continue;
+        }
+        let weight = match candidate.source {
+            "history" => HISTORY_WEIGHT,
+            "routine" => ROUTINE_WEIGHT,
+            _ => WORKFLOW_WEIGHT,
+        };
+        let contribution = weight * candidate.strength;
+        let id = action_key(&candidate.actions);
+        match merged.get_mut(&id) {
+            Some((item, best)) => {
+                item.score += contribution as f32;
+                if contribution > *best {
+                    *best = contribution;
+                    item.source = candidate.source.to_string();
+                    item.reason = candidate.reason;
+                    // History only knows the workflow id; keep the readable name.
+                    if !item.label.starts_with("Run workflow") || candidate.source == "workflow" {
+                        item.label = candidate.label;
+                    }
+                } else if candidate.source == "workflow" {
+                    item.label = candidate.label;
+                }
+            }
+            None => {
+                merged.insert(id.clone(), (
+                    QuickAction {
+                        id,
+                        label: candidate.label,
+                        source: candidate.source.to_string(),
+                        reason: candidate.reason,
+                        score: contribution as f32,
+                        intent: Intent {
+                            intent_type: candidate.intent_type,
+                            confidence: 1.0,
+                            parameters: HashMap::new(),
+                            suggested_actions: candidate.actions,
+                        },
+                    },
+                    contribution,
+                ));
+            }
+        }
+    }
+
+    let mut actions: Vec<QuickAction> = merged
+        .into_values()
+        .map(|(mut item, _)| {
+            if let Some((accepted, rejected)) = feedback.get(&item.id) {
+                item.score *= feedback_multiplier(*accepted, *rejected) as f32;
+            }
+            item
+        })
+        .collect();
+    actions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    actions.truncate(QUICK_ACTION_LIMIT);
+    Ok(actions)
+}
+
+/// Record whether a suggested quick action was used or dismissed.
+#[tauri::command]
+pub async fn record_quick_action_feedback(
+    app_handle: AppHandle,
+    action_id: String,
+    accepted: bool,
+) -> Result<(), String> {
+    if action_id.trim().is_empty() {
+        return Err("action_id is required".to_string());
+    }
+    let conn = crate::database::open(&app_handle)?;
+    conn.execute(
+        "INSERT INTO quick_action_feedback (action_id, accepted, created_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![action_id, accepted as i64, chrono::Utc::now().timestamp()],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record what the user is working on for `ttl_minutes` (default 4h); empty text clears it.
+#[tauri::command]
+pub async fn set_current_focus(
+    app_handle: AppHandle,
+    text: String,
+    ttl_minutes: Option<u32>,
+) -> Result<Option<CurrentFocus>, String> {
+    let conn = crate::database::open(&app_handle)?;
+    crate::services::current_focus::set(&conn, &text, ttl_minutes)
+}
+
+#[tauri::command]
+pub async fn get_current_focus(app_handle: AppHandle) -> Result<Option<CurrentFocus>, String> {
+    crate::database::with_db(&app_handle, |conn| Ok(crate::services::current_focus::get(conn)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+    use chrono::TimeZone;
+
+    const DAY: i64 = 86_400;
+
+
+    fn launch(app: &str) -> Vec<Action> {
+        vec![Action { action_type: ActionType::LaunchApp, target: app.to_string(), args: vec![] }]
+    }
+
+    fn local_ts(day: u32, hour: u32, minute: u32) -> i64 {
+        chrono::Local.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap().timestamp()
+    }
+
+    #[test]
+    fn history_halves_every_half_life() {
+        assert_eq!(decay_weight(0), 1.0);
+        assert!((decay_weight(14 * DAY) - 0.5).abs() < 1e-12);
+        assert!((decay_weight(28 * DAY) - 0.25).abs() < 1e-12);
+        assert!((decay_weight(7 * DAY) - 0.5f64.sqrt()).abs() < 1e-12);
+        // Clock skew never boosts an entry past a fresh one.
+        assert_eq!(decay_weight(-DAY), 1.0);
+        assert!(decay_weight(HISTORY_WINDOW_DAYS * DAY) < 0.02);
+    }
+
+    #[test]
+    fn feedback_reorders_without_burying() {
+        assert_eq!(feedback_multiplier(0.0, 0.0), 1.0);
+        assert_eq!(feedback_multiplier(1.0, 0.0), 2.0);
+        assert_eq!(feedback_multiplier(10.0, 0.0), 2.0);
+        assert_eq!(feedback_multiplier(0.0, 1.0), 0.5);
+        assert_eq!(feedback_multiplier(0.0, 10.0), 0.25);
+    }
+
+    #[test]
+    fn recent_history_outranks_older_repeats() {
+        let conn = test_connection();
+        let now = 1_767_225_600;
+        let url = vec![Action { action_type: ActionType::OpenUrl, target: "https://docs.rs".to_string(), args: vec![] }];
+        let mut record = |actions: &[Action], success: i64, executed_at: i64| {
+            conn.execute(
+                "INSERT INTO intent_executions (intent_type, action_key, actions, success, executed_at)
+                 VALUES ('work_start', ?1, ?2, ?3, ?4)",
+                rusqlite::params![action_key(actions), serde_json::to_string(actions).unwrap(), success, executed_at],
+            )
+            .unwrap();
+        };
+        for _ in 0..3 {
+            record(&launch("Code"), 1, now - 28 * DAY);
+        }
+        record(&url, 1, now);
+        record(&url, 0, now);
+        record(&launch("Slack"), 0, now);
+        record(&launch("Notes"), 1, now - (HISTORY_WINDOW_DAYS + 1) * DAY);
+
+        let mut candidates = history_candidates(&conn, now).unwrap();
+        candidates.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+        let ranked: Vec<(&str, f64)> = candidates.iter().map(|c| (c.label.as_str(), c.strength)).collect();
+        assert_eq!(ranked, vec![("Open https://docs.rs", 1.0), ("Open Code", 0.75)]);
+        assert_eq!(candidates[1].reason, "Used 3 times recently");
+        assert_eq!(candidates[0].reason, "Used 1 time recently");
+    }
+
+    #[test]
+    fn routines_bucket_by_the_local_hour_on_separate_days() {
+        let conn = test_connection();
+        let now = local_ts(10, 15, 30);
+        let mut seen = |app: &str, start: i64| {
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+                 VALUES (?1, 1, 'w', 1, ?2, ?2 + 60, 60)",
+                rusqlite::params![app, start],
+            )
+            .unwrap();
+        };
+        // Same hour on two days, twice on one of them.
+        seen("Code", local_ts(9, 15, 10));
+        seen("Code", local_ts(8, 15, 5));
+        seen("Code", local_ts(8, 15, 50));
+        // Same hour, but weeks ago.
+        seen("Terminal", local_ts(1, 15, 20));
+        seen("Terminal", local_ts(2, 15, 40));
+        // Only one day at this hour.
+        seen("Slack", local_ts(9, 15, 0));
+        // Other hours.
+        seen("Chrome", local_ts(9, 14, 59));
+        seen("Chrome", local_ts(8, 16, 0));
+        // Within the last hour: the user is already there.
+        seen("Spotify", local_ts(10, 15, 0));
+        seen("Spotify", local_ts(9, 15, 0));
+
+        let mut candidates = routine_candidates(&conn, now).unwrap();
+        candidates.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+        let apps: Vec<&str> = candidates.iter().map(|c| c.actions[0].target.as_str()).collect();
+        assert_eq!(apps, vec!["Code", "Terminal"]);
+        assert_eq!(candidates[0].strength, 1.0);
+        assert!(candidates[1].strength < 0.8);
+        assert!(candidates[0].reason.ends_with("on 2 recent days"), "{}", candidates[0].reason);
+        assert_eq!(current_local_hour(now), 15);
+    }
+
+    #[test]
+    fn excluded_apps_match_launch_and_close_targets_only() {
+        let excluded = vec!["discord".to_string()];
+        assert!(is_excluded_app(&launch("Discord.exe"), &excluded));
+        let close = vec![Action { action_type: ActionType::CloseApp, target: "discord".to_string(), args: vec![] }];
+        assert!(is_excluded_app(&close, &excluded));
+        let url = vec![Action { action_type: ActionType::OpenUrl, target: "https://discord.com".to_string(), args: vec![] }];
+        assert!(!is_excluded_app(&url, &excluded));
+        assert!(!is_excluded_app(&launch("Code"), &excluded));
+    }
+}
@@ -1,10 +1,15 @@
 pub mod activity;
+pub mod alerts;
 pub mod app_control;
 pub mod chat;
 pub mod dashboard;
 pub mod entry;
+pub mod goals;
 pub mod intent;
+pub mod patterns;
+pub mod profile;
 pub mod query;
+pub mod reports;
 pub mod settings;
 pub mod storage;
 pub mod workflow;
@@ -0,0 +1,35 @@
+use tauri::AppHandle;
+use crate::models::{DetectedPattern, PatternKind};
+
+/// Findings from the pattern engine. `kind` is routine, anomaly or correlation; omit for all.
+#[tauri::command]
+pub async fn get_patterns(
+    app_handle: AppHandle,
+    kind: Option<String>,
+    include_dismissed: Option<bool>,
+) -> Result<Vec<DetectedPattern>, String> {
+    let kind = match kind.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+        Some(value) => Some(PatternKind::parse(value).ok_or_else(|| format!("Unknown pattern kind: {}", value))?),
+        None => None,
+    };
+    crate::database::with_db(&app_handle, |conn| {
+        crate::services::pattern_engine::load_patterns(conn, kind, include_dismissed.unwrap_or(false))
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// Hide a finding from the UI and the assistant; re-detection keeps it dismissed.
+#[tauri::command]
+pub async fn dismiss_pattern(
+    app_handle: AppHandle,
+    pattern_id: i64,
+) -> Result<(), String> {
+    let conn = crate::database::open(&app_handle)?;
+    let changed = conn
+        .execute("UPDATE patterns SET dismissed = 1 WHERE id = ?1", [pattern_id])
+        .map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err(format!("Pattern {} not found", pattern_id));
+    }
+    Ok(())
+}
@@ -0,0 +1,940 @@
+use std::collections::HashSet;
+
+use argon2::Argon2;
+use base64::Engine;
+use rusqlite::Connection;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::models::{
+    AppAlert, Category, ProfileBundle, ProfileConflict, ProfileGoal, ProfileImportMode, ProfileImportReport,
+    ProfileSecrets, ProfileSectionResult, Settings, Workflow,
+};
+
+// ─── Profile bundles ───
+// Move settings, category rules, category goals, app alerts and workflows
+// between machines as one versioned JSON file. Activity history never goes in a bundle. Settings
+// fields tied to this machine (API key, unlock password, local paths) are left
+// out, and the API key only travels when asked for, obfuscated with a
+// passphrase.
+
+/// Bump when the bundle layout changes incompatibly.
+const PROFILE_BUNDLE_VERSION: u32 = 1;
+
+const SECTION_SETTINGS: &str = "settings";
+const SECTION_CATEGORIES: &str = "categories";
+const SECTION_APP_ALERTS: &str = "app_alerts";
+const SECTION_WORKFLOWS: &str = "workflows";
+const SECTION_GOALS: &str = "goals";
+const SECTIONS: [&str; 5] = [
+    SECTION_SETTINGS,
+    SECTION_CATEGORIES,
+    SECTION_GOALS,
+    SECTION_APP_ALERTS,
+    SECTION_WORKFLOWS,
+];
+
+/// Settings that only make sense on the machine they were set on, as JSON pointers.
+const MACHINE_SPECIFIC_SETTINGS: [&str; 8] = [
+    "/ai/api_key",
+    "/ai/extra_ca_cert",
+    "/privacy/unlock_password_hash",
+    "/privacy/require_unlock",
+    "/general/enable_startup",
+    "/general/demo_mode",
+    "/tracking/code_roots",
+    "/reports/output_dir",
+];
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+/// Prepended before obfuscating so a wrong passphrase is detected instead of yielding garbage.
+const SECRET_MARKER: &str = "intentflow-profile:";
+const IMPORTED_SUFFIX: &str = "imported";
+
+/// Write the chosen `sections` (all of them when empty) to `path`.
+#[tauri::command]
+pub async fn export_profile(
+    app_handle: AppHandle,
+    path: String,
+    sections: Vec<String>,
+    include_secrets: Option<bool>,
+    passphrase: Option<String>,
+) -> Result<String, String> {
+    let sections = parse_sections(&sections)?;
+    let settings = crate::utils::config::load_settings(&app_handle);
+    let conn = crate::database::open(&app_handle)?;
+
+    let mut bundle = build_bundle(
+        &conn,
+        &settings,
+        &sections,
+        app_handle.package_info().version.to_string(),
+    )?;
+    if include_secrets.unwrap_or(false) {
+        let passphrase = passphrase.unwrap_or_default();
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(format!(
+                "Exporting secrets needs a passphrase of at least {} characters",
+                MIN_PASSPHRASE_LEN
+            ));
+        }
+        bundle.secrets = Some(obfuscate_secrets(&settings.ai.api_key, &passphrase)?);
+    }
+
+    let path = std::path::PathBuf::from(path.trim());
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    log::info!("Exported profile sections {:?} to {}", bundle.sections, path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn build_bundle(
+    conn: &Connection,
+    settings: &Settings,
+    sections: &[&str],
+    app_version: String,
+) -> Result<ProfileBundle, String> {
+    let mut bundle = ProfileBundle {
+        version: PROFILE_BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        app_version,
+        sections: sections.iter().map(|s| s.to_string()).collect(),
+        settings: None,
+        categories: None,
+        goals: None,
+        app_alerts: None,
+        workflows: None,
+        secrets: None,
+    };
+    for section in sections {
+        match *section {
+            SECTION_SETTINGS => bundle.settings = Some(portable_settings(settings)?),
+            SECTION_CATEGORIES => {
+                bundle.categories = Some(crate::utils::categories::load_categories(conn).map_err(|e| e.to_string())?)
+            }
+            SECTION_GOALS => bundle.goals = Some(export_goals(conn)?),
+            SECTION_APP_ALERTS => {
+                bundle.app_alerts = Some(crate::services::app_alerts::load_alerts(conn).map_err(|e| e.to_string())?)
+            }
+            SECTION_WORKFLOWS => bundle.workflows = Some(crate::commands::workflow::load_workflows(conn)?),
+            _ => {}
+        }
+    }
+    Ok(bundle)
+}
+
+/// Apply a bundle written by `export_profile`. Every section is checked before
+/// anything is written, and the database sections go in one transaction.
+#[tauri::command]
+pub async fn import_profile(
+    app_handle: AppHandle,
+    path: String,
+    mode: ProfileImportMode,
+    passphrase: Option<String>,
+) -> Result<ProfileImportReport, String> {
+    let content = std::fs::read_to_string(path.trim()).map_err(|e| format!("Cannot read profile: {}", e))?;
+    let bundle: ProfileBundle =
+        serde_json::from_str(&content).map_err(|e| format!("Not a valid profile bundle: {}", e))?;
+    if bundle.version == 0 || bundle.version > PROFILE_BUNDLE_VERSION {
+        return Err(format!(
+            "Profile bundle version {} is not supported (this version of IntentFlow reads up to {})",
+            bundle.version, PROFILE_BUNDLE_VERSION
+        ));
+    }
+
+    let mut report = ProfileImportReport {
+        mode,
+        bundle_version: bundle.version,
+        sections: Vec::new(),
+        conflicts: Vec::new(),
+        secrets_imported: false,
+        warnings: Vec::new(),
+    };
+    for section in &bundle.sections {
+        if !SECTIONS.contains(&section.as_str()) {
+            report.warnings.push(format!("Skipped unknown section '{}'", section));
+        }
+    }
+
+    let current = crate::utils::config::load_settings(&app_handle);
+    let mut new_settings = match &bundle.settings {
+        Some(imported) => Some(merge_settings(&current, imported, mode)?),
+        None => None,
+    };
+    if let Some(secrets) = &bundle.secrets {
+        match passphrase.as_deref().filter(|p| !p.is_empty()) {
+            Some(passphrase) => {
+                let api_key = reveal_secrets(secrets, passphrase)?;
+                new_settings.get_or_insert_with(|| current.clone()).ai.api_key = api_key;
+                report.secrets_imported = true;
+            }
+            None => report
+                .warnings
+                .push("The bundle contains an API key; enter its passphrase to import it".to_string()),
+        }
+    }
+    if let Some(alerts) = &bundle.app_alerts {
+        for alert in alerts {
+            crate::commands::alerts::validate(
+                &alert.app_pattern,
+                alert.daily_threshold_seconds,
+                alert.repeat_every_seconds,
+            )
+            .map_err(|e| format!("Alert '{}' in the bundle is invalid: {}", alert.app_pattern, e))?;
+        }
+    }
+
+    {
+        let conn = crate::database::open(&app_handle)?;
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        import_database_sections(&tx, &bundle, mode, &mut report)?;
+        tx.commit().map_err(|e| e.to_string())?;
+        crate::utils::categories::reload(&conn);
+        crate::services::app_alerts::reload(&conn);
+    }
+
+    if let Some(settings) = new_settings {
+        report.sections.push(ProfileSectionResult {
+            section: SECTION_SETTINGS.to_string(),
+            added: 0,
+            updated: 1,
+            unchanged: 0,
+            removed: 0,
+        });
+        crate::commands::settings::update_settings(app_handle.clone(), settings).await?;
+    }
+
+    log::info!(
+        "Imported profile ({:?}): {} section(s), {} conflict(s)",
+        mode,
+        report.sections.len(),
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+/// Categories go first so goals can find categories imported alongside them.
+fn import_database_sections(
+    conn: &Connection,
+    bundle: &ProfileBundle,
+    mode: ProfileImportMode,
+    report: &mut ProfileImportReport,
+) -> Result<(), String> {
+    if let Some(categories) = &bundle.categories {
+        let result = import_categories(conn, categories, mode, &mut report.conflicts)?;
+        report.sections.push(result);
+    }
+    if let Some(goals) = &bundle.goals {
+        let result = import_goals(conn, goals, mode, &mut report.conflicts, &mut report.warnings)?;
+        report.sections.push(result);
+    }
+    if let Some(alerts) = &bundle.app_alerts {
+        let result = import_app_alerts(conn, alerts, mode, &mut report.conflicts)?;
+        report.sections.push(result);
+    }
+    if let Some(workflows) = &bundle.workflows {
+        let result = import_workflows(conn, workflows, mode, &mut report.conflicts)?;
+        report.sections.push(result);
+    }
+    Ok(())
+}
+
+fn parse_sections(requested: &[String]) -> Result<Vec<&'static str>, String> {
+    if requested.is_empty() {
+        return Ok(SECTIONS.to_vec());
+    }
+    let mut sections = Vec::new();
+    for name in requested {
+        let key = name.trim().to_lowercase();
+        let section = SECTIONS.iter().find(|s| **s == key).ok_or_else(|| {
+            format!("Unknown profile section '{}'; expected one of: {}", name, SECTIONS.join(", "))
+        })?;
+        if !sections.contains(section) {
+            sections.push(*section);
+        }
+    }
+    Ok(sections)
+}
+
+// ─── Settings ───
+
+fn portable_settings(settings: &Settings) -> Result<Value, String> {
+    let mut value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    for pointer in MACHINE_SPECIFIC_SETTINGS {
+        remove_at(&mut value, pointer);
+    }
+    Ok(value)
+}
+
+/// Lay the imported settings over this machine's (merge) or over the defaults
+/// (replace), keeping machine-specific fields from this machine either way.
+fn merge_settings(current: &Settings, imported: &Value, mode: ProfileImportMode) -> Result<Settings, String> {
+    let current_value = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    let mut merged = match mode {
+        ProfileImportMode::Merge => current_value.clone(),
+        ProfileImportMode::Replace => serde_json::to_value(Settings::default()).map_err(|e| e.to_string())?,
+    };
+    let mut imported = imported.clone();
+    for pointer in MACHINE_SPECIFIC_SETTINGS {
+        remove_at(&mut imported, pointer);
+    }
+    deep_merge(&mut merged, imported);
+    for pointer in MACHINE_SPECIFIC_SETTINGS {
+        if let Some(local) = current_value.pointer(pointer) {
+            set_at(&mut merged, pointer, local.clone());
+        }
+    }
+    let settings: Settings =
+        serde_json::from_value(merged).map_err(|e| format!("Settings in the bundle are invalid: {}", e))?;
+    settings.ai.validate_temperatures()?;
+    Ok(settings)
+}
+
+fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn remove_at(value: &mut Value, pointer: &str) {
+    if let Some((parent, key)) = pointer.rsplit_once('/') {
+        if let Some(object) = value.pointer_mut(parent).and_then(Value::as_object_mut) {
+            object.remove(key);
+        }
+    }
+}
+
+fn set_at(value: &mut Value, pointer: &str, new_value: Value) {
+    if let Some((parent, key)) = pointer.rsplit_once('/') {
+        if let Some(object) = value.pointer_mut(parent).and_then(Value::as_object_mut) {
+            object.insert(key.to_string(), new_value);
+        }
+    }
+}
+
+// ─── Secrets ───
+// Not encryption in the strict sense: the key is XORed with an Argon2 stream
+// derived from the passphrase, which keeps it out of plain sight in a file
+// that gets copied around.
+
+fn secret_stream(passphrase: &str, salt: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    let mut stream = vec![0u8; len.max(4)];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut stream)
+        .map_err(|e| e.to_string())?;
+    stream.truncate(len);
+    Ok(stream)
+}
+
+fn obfuscate_secrets(api_key: &str, passphrase: &str) -> Result<ProfileSecrets, String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let salt = uuid::Uuid::new_v4().into_bytes();
+    let plain = format!("{}{}", SECRET_MARKER, api_key).into_bytes();
+    let stream = secret_stream(passphrase, &salt, plain.len())?;
+    let hidden: Vec<u8> = plain.iter().zip(stream).map(|(b, k)| b ^ k).collect();
+    Ok(ProfileSecrets {
+        salt: engine.encode(salt),
+        api_key: engine.encode(hidden),
+    })
+}
+
+fn reveal_secrets(secrets: &ProfileSecrets, passphrase: &str) -> Result<String, String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let salt = engine.decode(&secrets.salt).map_err(|_| "Profile secrets are corrupted".to_string())?;
+    let hidden = engine.decode(&secrets.api_key).map_err(|_| "Profile secrets are corrupted".to_string())?;
+    let stream = secret_stream(passphrase, &salt, hidden.len())?;
+    let plain: Vec<u8> = hidden.iter().zip(stream).map(|(b, k)| b ^ k).collect();
+    String::from_utf8(plain)
+        .ok()
+        .and_then(|text| text.strip_prefix(SECRET_MARKER).map(str::to_string))
+        .ok_or_else(|| "Wrong passphrase for the profile's secrets".to_string())
+}
+
+// ─── Database sections ───
+
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// `name (imported)`, then `name (imported 2)` ... until unused; records the pick in `taken`.
+fn unique_imported_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut attempt = 1;
+    loop {
+        let candidate = if attempt == 1 {
+            format!("{} ({})", name.trim(), IMPORTED_SUFFIX)
+        } else {
+            format!("{} ({} {})", name.trim(), IMPORTED_SUFFIX, attempt)
+        };
+        if taken.insert(name_key(&candidate)) {
+            return candidate;
+        }
+        attempt += 1;
+    }
+}
+
+fn section_result(section: &str) -> ProfileSectionResult {
+    ProfileSectionResult {
+        section: section.to_string(),
+        added: 0,
+        updated: 0,
+        unchanged: 0,
+        removed: 0,
+    }
+}
+
+/// Categories are matched by name. Replace overwrites same-named categories but
+/// never deletes local ones, since activities point at them.
+fn import_categories(
+    conn: &Connection,
+    categories: &[Category],
+    mode: ProfileImportMode,
+    conflicts: &mut Vec<ProfileConflict>,
+) -> Result<ProfileSectionResult, String> {
+    let mut result = section_result(SECTION_CATEGORIES);
+    let local = crate::utils::categories::load_categories(conn).map_err(|e| e.to_string())?;
+    let mut taken: HashSet<String> = local.iter().map(|c| name_key(&c.name)).collect();
+
+    for category in categories {
+        let name = category.name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let keywords = serde_json::to_string(&category.keywords).map_err(|e| e.to_string())?;
+        let apps = serde_json::to_string(&category.apps).map_err(|e| e.to_string())?;
+        let existing = local.iter().find(|c| name_key(&c.name) == name_key(name));
+
+        let insert_as = match existing {
+            None => Some(name.to_string()),
+            Some(existing)
+                if existing.icon == category.icon
+                    && existing.color == category.color
+                    && existing.keywords == category.keywords
+                    && existing.apps == category.apps =>
+            {
+                result.unchanged += 1;
+                None
+            }
+            Some(existing) => {
+                let detail = "Icon, color or matching rules differ".to_string();
+                if mode == ProfileImportMode::Replace {
+                    conn.execute(
+                        "UPDATE categories SET icon = ?1, color = ?2, keywords = ?3, apps = ?4 WHERE id = ?5",
+                        rusqlite::params![category.icon, category.color, keywords, apps, existing.id],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    result.updated += 1;
+                    conflicts.push(ProfileConflict {
+                        section: SECTION_CATEGORIES.to_string(),
+                        name: name.to_string(),
+                        detail,
+                        resolution: "replaced the local category".to_string(),
+                    });
+                    None
+                } else {
+                    let renamed = unique_imported_name(name, &mut taken);
+                    conflicts.push(ProfileConflict {
+                        section: SECTION_CATEGORIES.to_string(),
+                        name: name.to_string(),
+                        detail,
+                        resolution: format!("imported as '{}'", renamed),
+                    });
+                    Some(renamed)
+                }
+            }
+        };
+
+        if let Some(insert_name) = insert_as {
+            conn.execute(
+                "INSERT INTO categories (name, icon, color, keywords, apps, created_at, is_builtin)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0)",
+                rusqlite::params![
+                    insert_name,
+                    category.icon,
+                    category.color,
+                    keywords,
+                    apps,
+                    chrono::Utc::now().timestamp()
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            taken.insert(name_key(&insert_name));
+            result.added += 1;
+        }
+    }
+    Ok(result)
+}
+
+fn export_goals(conn: &Connection) -> Result<Vec<ProfileGoal>, String> {
+    let goals = crate::services::goal_streaks::load_goals(conn).map_err(|e| e.to_string())?;
+    let categories = crate::utils::categories::load_categories(conn).map_err(|e| e.to_string())?;
+    Ok(goals
+        .into_iter()
+        .filter_map(|goal| {
+            let category = categories.iter().find(|c| c.id as i64 == goal.category_id)?;
+            Some(ProfileGoal {
+                category: category.name.clone(),
+                kind: goal.kind,
+                target_seconds: goal.target_seconds,
+                no_data_policy: goal.no_data_policy,
+                enabled: goal.enabled,
+            })
+        })
+        .collect())
+}
+
+/// Goals are matched by category name and kind. Like alerts they have no name
+/// to suffix, so in merge mode a differing local goal is kept. Goals whose
+/// category exists on neither side are skipped with a warning. Recorded bests
+/// stay with this machine's history.
+fn import_goals(
+    conn: &Connection,
+    goals: &[ProfileGoal],
+    mode: ProfileImportMode,
+    conflicts: &mut Vec<ProfileConflict>,
+    warnings: &mut Vec<String>,
+) -> Result<ProfileSectionResult, String> {
+    let mut result = section_result(SECTION_GOALS);
+    let categories = crate::utils::categories::load_categories(conn).map_err(|e| e.to_string())?;
+    let local = crate::services::goal_streaks::load_goals(conn).map_err(|e| e.to_string())?;
+    let mut matched_ids: HashSet<i64> = HashSet::new();
+    let now = chrono::Utc::now().timestamp();
+
+    for goal in goals {
+        let Some(category) = categories.iter().find(|c| name_key(&c.name) == name_key(&goal.category)) else {
+            warnings.push(format!("Skipped a goal for unknown category '{}'", goal.category.trim()));
+            continue;
+        };
+        if goal.target_seconds <= 0 || goal.target_seconds > 24 * 3600 {
+            return Err(format!(
+                "Goal for '{}' in the bundle is invalid: target must be between 1 second and 24 hours",
+                goal.category.trim()
+            ));
+        }
+        let existing = local
+            .iter()
+            .find(|l| l.category_id == category.id as i64 && l.kind == goal.kind);
+        match existing {
+            None => {
+                conn.execute(
+                    "INSERT INTO category_goals (category_id, kind, target_seconds, no_data_policy, enabled, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    rusqlite::params![
+                        category.id,
+                        goal.kind.as_str(),
+                        goal.target_seconds,
+                        goal.no_data_policy.as_str(),
+                        goal.enabled as i64,
+                        now,
+                    ],
+                )
+                .map_err(|e| e.to_string())?;
+                result.added += 1;
+            }
+            Some(existing) => {
+                matched_ids.insert(existing.id);
+                if existing.target_seconds == goal.target_seconds
+                    && existing.no_data_policy == goal.no_data_policy
+                    && existing.enabled == goal.enabled
+                {
+                    result.unchanged += 1;
+                    continue;
+                }
+                let resolution = match mode {
+                    ProfileImportMode::Replace => {
+                        // Same reset as `update_category_goal`: a new condition has no record yet.
+                        conn.execute(
+                            "UPDATE category_goals
+                             SET best_streak = CASE WHEN target_seconds = ?1 AND no_data_policy = ?2
+                                                    THEN best_streak ELSE 0 END,
+                                 target_seconds = ?1, no_data_policy = ?2, enabled = ?3
+                             WHERE id = ?4",
+                            rusqlite::params![
+                                goal.target_seconds,
+                                goal.no_data_policy.as_str(),
+                                goal.enabled as i64,
+                                existing.id,
+                            ],
+                        )
+                        .map_err(|e| e.to_string())?;
+                        result.updated += 1;
+                        "replaced the local goal"
+                    }
+                    ProfileImportMode::Merge => "kept the local goal",
+                };
+                conflicts.push(ProfileConflict {
+                    section: SECTION_GOALS.to_string(),
+                    name: format!("{} ({})", category.name, goal.kind.as_str()),
+                    detail: "Target, no-data policy or enabled state differ".to_string(),
+                    resolution: resolution.to_string(),
+                });
+            }
+        }
+    }
+
+    if mode == ProfileImportMode::Replace {
+        for stale in local.iter().filter(|l| !matched_ids.contains(&l.id)) {
+            conn.execute("DELETE FROM category_goals WHERE id = ?1", [stale.id])
+                .map_err(|e| e.to_string())?;
+            result.removed += 1;
+        }
+    }
+    Ok(result)
+}
+
+/// Alerts are matched by app pattern. A suffix would change what an alert
+/// matches, so in merge mode a differing local alert is simply kept.
+fn import_app_alerts(
+    conn: &Connection,
+    alerts: &[AppAlert],
+    mode: ProfileImportMode,
+    conflicts: &mut Vec<ProfileConflict>,
+) -> Result<ProfileSectionResult, String> {
+    let mut result = section_result(SECTION_APP_ALERTS);
+    let local = crate::services::app_alerts::load_alerts(conn).map_err(|e| e.to_string())?;
+    let same_rule = |a: &AppAlert, b: &AppAlert| {
+        a.daily_threshold_seconds == b.daily_threshold_seconds
+            && a.repeat_every_seconds == b.repeat_every_seconds
+            && a.enabled == b.enabled
+    };
+
+    let mut to_insert: Vec<&AppAlert> = Vec::new();
+    for alert in alerts {
+        match local.iter().find(|l| name_key(&l.app_pattern) == name_key(&alert.app_pattern)) {
+            None => {
+                result.added += 1;
+                to_insert.push(alert);
+            }
+            Some(existing) if same_rule(existing, alert) => {
+                result.unchanged += 1;
+                if mode == ProfileImportMode::Replace {
+                    to_insert.push(alert);
+                }
+            }
+            Some(_) => {
+                let resolution = match mode {
+                    ProfileImportMode::Replace => {
+                        result.updated += 1;
+                        to_insert.push(alert);
+                        "replaced the local alert"
+                    }
+                    ProfileImportMode::Merge => "kept the local alert",
+                };
+                conflicts.push(ProfileConflict {
+                    section: SECTION_APP_ALERTS.to_string(),
+                    name: alert.app_pattern.trim().to_string(),
+                    detail: "Threshold, repeat interval or enabled state differ".to_string(),
+                    resolution: resolution.to_string(),
+                });
+            }
+        }
+    }
+
+    if mode == ProfileImportMode::Replace {
+        let imported: HashSet<String> = alerts.iter().map(|a| name_key(&a.app_pattern)).collect();
+        result.removed = local.iter().filter(|l| !imported.contains(&name_key(&l.app_pattern))).count();
+        conn.execute("DELETE FROM app_alerts", []).map_err(|e| e.to_string())?;
+    }
+    let now = chrono::Utc::now().timestamp();
+    for alert in to_insert {
+        conn.execute(
+            "INSERT INTO app_alerts (app_pattern, daily_threshold_seconds, repeat_every_seconds, enabled, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                alert.app_pattern.trim(),
+                alert.daily_threshold_seconds,
+                alert.repeat_every_seconds,
+                alert.enabled as i64,
+                now,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(result)
+}
+
+fn workflow_actions(workflow: &Workflow) -> Value {
+    serde_json::json!({
+        "apps": workflow.apps,
+        "urls": workflow.urls,
+        "files": workflow.files,
+    })
+}
+
+/// Workflows are matched by name and compared by what they launch. Imported
+/// ones start with fresh usage stats.
+fn import_workflows(
+    conn: &Connection,
+    workflows: &[Workflow],
+    mode: ProfileImportMode,
+    conflicts: &mut Vec<ProfileConflict>,
+) -> Result<ProfileSectionResult, String> {
+    let mut result = section_result(SECTION_WORKFLOWS);
+    let local = crate::commands::workflow::load_workflows(conn)?;
+    let mut taken: HashSet<String> = local.iter().map(|w| name_key(&w.name)).collect();
+    let now = chrono::Utc::now().timestamp();
+
+    for workflow in workflows {
+        let name = workflow.name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let apps_blob = serde_json::to_vec(&workflow.apps).map_err(|e| e.to_string())?;
+        let files_blob = serde_json::to_vec(&workflow.files).map_err(|e| e.to_string())?;
+        let urls_blob = serde_json::to_vec(&workflow.urls).map_err(|e| e.to_string())?;
+        let existing = local.iter().find(|w| name_key(&w.name) == name_key(name));
+
+        let insert_as = match existing {
+            None => Some(name.to_string()),
+            Some(existing) if workflow_actions(existing) == workflow_actions(workflow) => {
+                result.unchanged += 1;
+                None
+            }
+            Some(existing) => {
+                let detail = "Same name, different apps, URLs or files".to_string();
+                if mode == ProfileImportMode::Replace {
+                    conn.execute(
+                        "UPDATE workflows SET description = ?1, icon = ?2, apps = ?3, files = ?4, urls = ?5 WHERE uuid = ?6",
+                        rusqlite::params![
+                            &workflow.description,
+                            &workflow.icon,
+                            &apps_blob,
+                            &files_blob,
+                            &urls_blob,
+                            &existing.id,
+                        ],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    result.updated += 1;
+                    conflicts.push(ProfileConflict {
+                        section: SECTION_WORKFLOWS.to_string(),
+                        name: name.to_string(),
+                        detail,
+                        resolution: "replaced the local workflow".to_string(),
+                    });
+                    None
+                } else {
+                    let renamed = unique_imported_name(name, &mut taken);
+                    conflicts.push(ProfileConflict {
+                        section: SECTION_WORKFLOWS.to_string(),
+                        name: name.to_string(),
+                        detail,
+                        resolution: format!("imported as '{}'", renamed),
+                    });
+                    Some(renamed)
+                }
+            }
+        };
+
+        if let Some(insert_name) = insert_as {
+            conn.execute(
+                "INSERT INTO workflows (uuid, name, description, icon, apps, files, urls, use_count, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, ?8)",
+                rusqlite::params![
+                    uuid::Uuid::new_v4().to_string(),
+                    &insert_name,
+                    &workflow.description,
+                    &workflow.icon,
+                    &apps_blob,
+                    &files_blob,
+                    &urls_blob,
+                    now,
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            taken.insert(name_key(&insert_name));
+            result.added += 1;
+        }
+    }
+
+    if mode == ProfileImportMode::Replace {
+        let imported: HashSet<String> = workflows.iter().map(|w| name_key(&w.name)).collect();
+        for stale in local.iter().filter(|w| !imported.contains(&name_key(&w.name))) {
+            conn.execute("DELETE FROM workflows WHERE uuid = ?1", [&stale.id])
+                .map_err(|e| e.to_string())?;
+            result.removed += 1;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+
+
+    fn add_workflow(conn: &Connection, name: &str, app: &str) {
+        let apps = serde_json::to_vec(&serde_json::json!([{ "path": app, "args": [] }])).unwrap();
+        conn.execute(
+            "INSERT INTO workflows (uuid, name, description, icon, apps, files, urls, created_at)
+             VALUES (?1, ?2, '', 'zap', ?3, X'5B5D', X'5B5D', 0)",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), name, apps],
+        )
+        .unwrap();
+    }
+
+    fn source_db() -> Connection {
+        let conn = test_connection();
+        conn.execute_batch(
+            "INSERT INTO categories (name, icon, color, keywords, apps, created_at, is_builtin)
+                 VALUES ('Design', 'pen', '#ff00aa', '[\"mockup\"]', '[\"figma\"]', 0, 0);
+             INSERT INTO category_goals (category_id, kind, target_seconds, no_data_policy, enabled, created_at)
+                 SELECT id, 'at_least', 3600, 'preserve', 1, 0 FROM categories WHERE name = 'Design';
+             INSERT INTO category_goals (category_id, kind, target_seconds, no_data_policy, enabled, created_at)
+                 VALUES (1, 'at_most', 7200, 'break', 0, 0);
+             INSERT INTO app_alerts (app_pattern, daily_threshold_seconds, repeat_every_seconds, enabled, created_at)
+                 VALUES ('slack', 1800, 600, 1, 0);",
+        )
+        .unwrap();
+        add_workflow(&conn, "Morning", "code.exe");
+        conn
+    }
+
+    /// Exports every database section, through JSON as the file would carry it.
+    fn export(conn: &Connection) -> ProfileBundle {
+        let sections = [SECTION_CATEGORIES, SECTION_GOALS, SECTION_APP_ALERTS, SECTION_WORKFLOWS];
+        let bundle = build_bundle(conn, &Settings::default(), &sections, "test".to_string()).unwrap();
+        serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap()
+    }
+
+    fn import(conn: &Connection, bundle: &ProfileBundle, mode: ProfileImportMode) -> ProfileImportReport {
+        let mut report = ProfileImportReport {
+            mode,
+            bundle_version: bundle.version,
+            sections: Vec::new(),
+            conflicts: Vec::new(),
+            secrets_imported: false,
+            warnings: Vec::new(),
+        };
+        import_database_sections(conn, bundle, mode, &mut report).unwrap();
+        report
+    }
+
+    /// What a bundle says, without ids and timestamps.
+    fn contents(bundle: &ProfileBundle) -> Value {
+        let mut categories: Vec<Value> = bundle
+            .categories
+            .iter()
+            .flatten()
+            .map(|c| serde_json::json!([c.name, c.icon, c.color, c.keywords, c.apps]))
+            .collect();
+        categories.sort_by_key(|c| c.to_string());
+        let mut goals: Vec<Value> = bundle.goals.iter().flatten().map(|g| serde_json::to_value(g).unwrap()).collect();
+        goals.sort_by_key(|g| g.to_string());
+        let alerts: Vec<Value> = bundle
+            .app_alerts
+            .iter()
+            .flatten()
+            .map(|a| serde_json::json!([a.app_pattern, a.daily_threshold_seconds, a.repeat_every_seconds, a.enabled]))
+            .collect();
+        let mut workflows: Vec<Value> = bundle
+            .workflows
+            .iter()
+            .flatten()
+            .map(|w| serde_json::json!([w.name, workflow_actions(w)]))
+            .collect();
+        workflows.sort_by_key(|w| w.to_string());
+        serde_json::json!({ "categories": categories, "goals": goals, "alerts": alerts, "workflows": workflows })
+    }
+
+    #[test]
+    fn every_database_section_survives_a_round_trip() {
+        let exported = export(&source_db());
+        assert_eq!(exported.goals.as_ref().map(Vec::len), Some(2));
+
+        let target = test_connection();
+        let report = import(&target, &exported, ProfileImportMode::Merge);
+        assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        assert_eq!(contents(&export(&target)), contents(&exported));
+
+        // Importing the same bundle again changes nothing.
+        let again = import(&target, &exported, ProfileImportMode::Merge);
+        assert!(again.conflicts.is_empty());
+        assert!(again.sections.iter().all(|s| s.added == 0 && s.updated == 0 && s.removed == 0));
+        assert_eq!(contents(&export(&target)), contents(&exported));
+    }
+
+    #[test]
+    fn merge_keeps_both_workflows_and_replace_takes_the_bundle() {
+        let exported = export(&source_db());
+        let target = test_connection();
+        add_workflow(&target, "Morning", "notepad.exe");
+        add_workflow(&target, "Evening", "spotify.exe");
+        target
+            .execute(
+                "INSERT INTO category_goals (category_id, kind, target_seconds, no_data_policy, enabled, created_at)
+                 VALUES (1, 'at_most', 60, 'break', 0, 0)",
+                [],
+            )
+            .unwrap();
+
+        let merged = import(&target, &exported, ProfileImportMode::Merge);
+        let names: Vec<String> = crate::commands::workflow::load_workflows(&target)
+            .unwrap()
+            .into_iter()
+            .map(|w| w.name)
+            .collect();
+        assert!(names.contains(&"Morning".to_string()));
+        assert!(names.contains(&"Morning (imported)".to_string()));
+        assert!(merged.conflicts.iter().any(|c| c.section == SECTION_GOALS && c.resolution == "kept the local goal"));
+
+        let target = test_connection();
+        add_workflow(&target, "Morning", "notepad.exe");
+        add_workflow(&target, "Evening", "spotify.exe");
+        import(&target, &exported, ProfileImportMode::Replace);
+        assert_eq!(contents(&export(&target)), contents(&exported));
+    }
+
+    #[test]
+    fn goals_for_unknown_categories_are_skipped() {
+        let mut bundle = export(&source_db());
+        bundle.categories = None;
+        let target = test_connection();
+        let report = import(&target, &bundle, ProfileImportMode::Merge);
+        assert_eq!(report.warnings, vec!["Skipped a goal for unknown category 'Design'".to_string()]);
+        assert_eq!(crate::services::goal_streaks::load_goals(&target).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn machine_specific_settings_stay_behind() {
+        let mut exported = Settings::default();
+        exported.ai.api_key = "remote-key".to_string();
+        exported.ai.extra_ca_cert = Some("remote.pem".to_string());
+        exported.tracking.ocr_interval = 42;
+        let portable = portable_settings(&exported).unwrap();
+        assert!(portable.pointer("/ai/api_key").is_none());
+        assert!(portable.pointer("/ai/extra_ca_cert").is_none());
+
+        let mut local = Settings::default();
+        local.ai.api_key = "local-key".to_string();
+        local.ai.extra_ca_cert = Some("local.pem".to_string());
+        for mode in [ProfileImportMode::Merge, ProfileImportMode::Replace] {
+            let merged = merge_settings(&local, &portable, mode).unwrap();
+            assert_eq!(merged.tracking.ocr_interval, 42);
+            assert_eq!(merged.ai.api_key, "local-key");
+            assert_eq!(merged.ai.extra_ca_cert.as_deref(), Some("local.pem"));
+        }
+    }
+
+    #[test]
+    fn secrets_need_the_passphrase_they_were_hidden_with() {
+        let secrets = obfuscate_secrets("sk-123", "correct horse").unwrap();
+        assert!(!secrets.api_key.contains("sk-123"));
+        assert_eq!(reveal_secrets(&secrets, "correct horse").unwrap(), "sk-123");
+        assert!(reveal_secrets(&secrets, "wrong passphrase").is_err());
+    }
+}
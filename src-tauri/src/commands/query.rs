@@ -1,5 +1,6 @@
-use tauri::{AppHandle, Manager};
-use crate::models::{QueryResult, QueryItem, Settings};
+use tauri::AppHandle;
+use crate::error::AppError;
+use crate::models::{ActivityMetadata, QueryResult, QueryItem, SearchHit};
 use chrono::{Datelike, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -23,6 +24,8 @@ struct QueryChatSendMessage {
 #[derive(Deserialize)]
 struct QueryChatResponse {
     choices: Vec<QueryChatChoice>,
+    #[serde(default)]
+    usage: Option<crate::services::llm_client::ProviderUsage>,
 }
 
 #[derive(Deserialize)]
@@ -40,17 +43,9 @@ struct QueryChatRecvMessage {
 
 // ─── Settings ───
 
-fn load_settings(app_handle: &AppHandle) -> Option<Settings> {
-    let data_dir = app_handle.path().app_data_dir().ok()?;
-    let settings_path = data_dir.join("config").join("settings.json");
-    let data = std::fs::read_to_string(settings_path).ok()?;
-    let mut settings: Settings = serde_json::from_str(&data).ok()?;
-    crate::utils::config::apply_env_defaults(&mut settings);
-    Some(settings)
-}
-
 // ─── AI System Prompt ───
 
+/// `{category_list}` is filled from the `categories` table at request time.
 const SYSTEM_PROMPT: &str = r#"You are IntentFlow's AI activity analyst — a smart, conversational assistant embedded inside the IntentFlow desktop app. IntentFlow tracks the user's active window at a configurable interval (default 10 seconds) and logs what apps and windows they use throughout the day.
 
 ## Your Purpose
@@ -64,13 +59,7 @@ You will receive:
 - The user's original question (which may contain typos — interpret intent, don't be literal)
 
 ## Activity Categories
-1 = Development (VS Code, Antigravity/Cursor IDE, terminals, code editors)
-2 = Browser (Chrome, Brave, Firefox, Edge — general web browsing)
-3 = Communication (Slack, Discord, Teams, WhatsApp, email)
-4 = Entertainment (Spotify, YouTube, Netflix, music/video streaming)
-5 = Productivity (Notion, Obsidian, Word, Excel, note-taking)
-6 = System (File Explorer, Settings, Task Manager)
-7 = Other/Unknown
+{category_list}
 
 ## Response Rules
 1. Be concise but insightful — 2-4 sentences, conversational tone
@@ -89,10 +78,12 @@ You will receive:
 // ─── AI call ───
 
 async fn ai_summarize_query(
+    app_handle: &tauri::AppHandle,
     query: &str,
     structured_data: &str,
     api_key: &str,
     model: &str,
+    temperature: f32,
 ) -> Result<String, String> {
     let user_message = format!(
         "User's question: \"{}\"\n\n{}",
@@ -104,34 +95,32 @@ async fn ai_summarize_query(
         messages: vec![
             QueryChatSendMessage {
                 role: "system".to_string(),
-                content: SYSTEM_PROMPT.to_string(),
+                content: format!(
+                    "{}\n\n{}",
+                    SYSTEM_PROMPT.replace("{category_list}", &crate::utils::categories::prompt_described()),
+                    crate::utils::i18n::language_instruction(query)
+                ),
             },
             QueryChatSendMessage {
                 role: "user".to_string(),
                 content: user_message,
             },
         ],
-        temperature: 0.7,
+        temperature,
         max_tokens: 512,
     };
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://integrate.api.nvidia.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
-
-    let status = response.status();
+    let response = crate::services::llm_client::send_chat_request(
+        api_key,
+        &request.model,
+        &request,
+        crate::services::llm_client::DEFAULT_TIMEOUT,
+        crate::services::llm_client::Priority::Interactive,
+        None,
+    )
+    .await?;
     let body_text = response.text().await
         .map_err(|e| format!("Failed to read response: {}", e))?;
-    
-    if !status.is_success() {
-        return Err(format!("API returned {}: {}", status, &body_text[..body_text.len().min(300)]));
-    }
 
     let chat_resp: QueryChatResponse = serde_json::from_str(&body_text)
         .map_err(|e| format!("JSON parse error: {} | Body: {}", e, &body_text[..body_text.len().min(300)]))?;
@@ -140,9 +129,18 @@ async fn ai_summarize_query(
         .ok_or_else(|| "Empty AI response".to_string())?;
     
     // Try content first, then reasoning_content (for reasoning models like GLM)
-    choice.message.content.clone()
-        .or_else(|| choice.message.reasoning_content.clone())
-        .ok_or_else(|| "AI returned null content".to_string())
+    let content = choice.message.content.clone()
+        .or_else(|| choice.message.reasoning_content.clone());
+
+    let usage = crate::services::llm_client::call_usage(
+        model,
+        chat_resp.usage,
+        crate::services::llm_client::estimate_prompt_tokens(request.messages.iter().map(|m| m.content.as_str())),
+        content.as_deref().unwrap_or(""),
+    );
+    crate::services::llm_client::record_usage(app_handle, crate::services::llm_client::CALLER_SUMMARY, model, &usage);
+
+    content.ok_or_else(|| "AI returned null content".to_string())
 }
 
 // ─── Build structured data for AI ───
@@ -156,7 +154,6 @@ fn build_structured_data(
         return format!("Time range: {}\nNo activities found in this time range.", time_range_label);
     }
     
-    let local_tz = chrono::Local::now().timezone();
     let mut data = String::new();
     
     // Header
@@ -190,7 +187,7 @@ fn build_structured_data(
     
     data.push_str("\n=== TIME PER CATEGORY ===\n");
     for (cat_id, dur) in &cat_list {
-        let name = category_name(*cat_id);
+        let name = crate::utils::categories::name_for(*cat_id as i64);
         let pct = if total_duration > 0 { (*dur as f64 / total_duration as f64 * 100.0) as i32 } else { 0 };
         data.push_str(&format!("  {} — {} ({}%)\n", name, format_duration(*dur), pct));
     }
@@ -201,9 +198,7 @@ fn build_structured_data(
     let recent: Vec<_> = filtered.iter().take(50).collect();
     data.push_str("\n=== ACTIVITY TIMELINE ===\n");
     for a in recent.iter().rev() {
-        let dt = chrono::DateTime::from_timestamp(a.start_time, 0)
-            .unwrap_or_default()
-            .with_timezone(&local_tz);
+        let time_str = crate::utils::time::format_local_time(a.start_time).unwrap_or_default();
         
         // Include OCR screen text if available
         let screen_info = if let Some(ref meta) = a.metadata {
@@ -223,7 +218,7 @@ fn build_structured_data(
         
         data.push_str(&format!(
             "  [{}] {} — {} ({}){}\n",
-            dt.format("%I:%M %p"),
+            time_str,
             a.app_name,
             a.window_title,
             format_duration(a.duration_seconds),
@@ -242,10 +237,7 @@ fn build_structured_data(
             if let Some(ref text) = meta.screen_text {
                 let trimmed = text.trim().to_string();
                 if !trimmed.is_empty() && !seen_texts.iter().any(|s| s == &trimmed) {
-                    let dt = chrono::DateTime::from_timestamp(a.start_time, 0)
-                        .unwrap_or_default()
-                        .with_timezone(&local_tz);
-                    let time_str = dt.format("%I:%M %p").to_string();
+                    let time_str = crate::utils::time::format_local_time(a.start_time).unwrap_or_default();
                     // Truncate to ~500 chars per entry (char-boundary safe)
                     let entry_text = if trimmed.len() > 500 {
                         let end = trimmed.char_indices().nth(500).map(|(i, _)| i).unwrap_or(trimmed.len());
@@ -314,11 +306,11 @@ fn build_structured_data(
 
     // ── Background Window Context (what was open in background) ──
     // Collect all unique background window titles seen in this period
+    // (snapshot plus diffs per session; rows from older builds may still hold noise)
     let mut bg_windows: Vec<String> = filtered.iter()
         .flat_map(|a| a.metadata.as_ref())
-        .flat_map(|m| m.background_windows.as_ref())
-        .flat_map(|w| w.iter())
-        .cloned()
+        .flat_map(|m| m.background_windows_seen())
+        .filter(|title| !crate::utils::windows::is_background_noise(title))
         .collect();
     bg_windows.sort();
     bg_windows.dedup();
@@ -326,51 +318,56 @@ fn build_structured_data(
     if !bg_windows.is_empty() {
         data.push_str("\n=== BACKGROUND APPS ===\n");
         data.push_str("(Apps visible in background while user was doing other things)\n");
-        // Filter out common noise
-        let noise = ["Program Manager", "Settings", "Calculate", "Windows Input Experience"];
         for title in bg_windows.iter().take(30) {
-            if !title.trim().is_empty() && !noise.contains(&title.as_str()) {
-                data.push_str(&format!("  • {}\n", title));
-            }
+            data.push_str(&format!("  • {}\n", title));
         }
     }
 
     data
 }
 
-fn category_name(id: i32) -> &'static str {
-    match id {
-        1 => "Development",
-        2 => "Browser",
-        3 => "Communication",
-        4 => "Entertainment",
-        5 => "Productivity",
-        6 => "System",
-        _ => "Other",
-    }
-}
-
 // ─── Main query command ───
 
 #[tauri::command]
 pub async fn execute_query(
     app_handle: AppHandle,
     query: String,
-) -> Result<QueryResult, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    profile: Option<String>,
+) -> Result<QueryResult, AppError> {
+    let conn = crate::database::open(&app_handle)?;
     
     // Parse the query and determine time range (local — simple date math)
-    let (start_time, end_time, time_label) = parse_query_time_range(&query);
+    let explicit_range = parse_explicit_time_range(&query, &conn);
+    let (start_time, end_time, time_label) = explicit_range.clone().unwrap_or_else(today_time_range);
+    let mut settings = crate::utils::config::load_settings(&app_handle);
+    settings.ai.apply_profile(profile.as_deref()).map_err(AppError::InvalidInput)?;
+
+    // "What songs did I hear today": the music tool already formats the answer, so skip the LLM.
+    if explicit_range.is_some() && extract_category_filter(&query) == Some(4) {
+        match crate::services::query_engine::music_history_fast_path(
+            &app_handle, &query, &settings, start_time, end_time, &scope_label(&time_label),
+        ) {
+            Ok(Some((summary, refs))) => {
+                let result = QueryResult {
+                    query: query.clone(),
+                    results: refs.iter().map(music_ref_to_item).collect(),
+                    summary,
+                    timestamp: Utc::now().timestamp(),
+                };
+                let _ = cache_query(&conn, &result);
+                return Ok(result);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Music fast path failed, using the agent: {}", e),
+        }
+    }
     
     // Determine if this is a broad query (multi-day) or a single-day query
     let time_span_hours = (end_time - start_time) / 3600;
     let is_broad_query = time_span_hours > 24;
     
     // Check for semantic hints to do efficient DB-level filtering
-    let semantic_hints = extract_search_hints(&query);
+    let semantic_hints = extract_search_hints(&conn, &query);
     
     // Two-tier strategy:
     // - Single day + no specific filter → send ALL to AI (full context, ~500 max)
@@ -382,15 +379,13 @@ pub async fn execute_query(
             &semantic_hints.keywords.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
             &semantic_hints.category_ids,
             Some(500),
-        ).map_err(|e| e.to_string())?
+        )?
     } else if is_broad_query {
         // Broad query without hints — get recent + aggregate stats
-        crate::database::queries::get_activities(&conn, start_time, end_time, Some(200))
-            .map_err(|e| e.to_string())?
+        crate::database::queries::get_activities(&conn, start_time, end_time, Some(200))?
     } else {
         // Single day — send everything for full AI analysis
-        crate::database::queries::get_activities(&conn, start_time, end_time, Some(500))
-            .map_err(|e| e.to_string())?
+        crate::database::queries::get_activities(&conn, start_time, end_time, Some(500))?
     };
     
     let total_duration: i32 = all_activities.iter().map(|a| a.duration_seconds).sum();
@@ -401,7 +396,7 @@ pub async fn execute_query(
     // For the display timeline, filter by app name or semantic hints
     let app_filter = extract_app_filter(&query);
     let display_activities = if let Some(ref app_name) = app_filter {
-        let search_terms = expand_app_aliases(app_name);
+        let search_terms = expand_app_aliases(&conn, app_name);
         all_activities.iter()
             .filter(|a| {
                 let app_lower = a.app_name.to_lowercase();
@@ -430,12 +425,8 @@ pub async fn execute_query(
     };
     
     // Convert to query items (use local time for display)
-    let local_tz = chrono::Local::now().timezone();
     let results: Vec<QueryItem> = display_activities.iter().map(|a| {
-        let dt = chrono::DateTime::from_timestamp(a.start_time, 0)
-            .unwrap_or_default()
-            .with_timezone(&local_tz);
-        let time_str = dt.format("%I:%M %p").to_string();
+        let time_str = crate::utils::time::format_local_time(a.start_time).unwrap_or_default();
         let duration = format_duration(a.duration_seconds);
         
         QueryItem {
@@ -466,7 +457,6 @@ pub async fn execute_query(
     }).collect();
     
     // AI gets ALL data — it decides what's relevant based on the query
-    let settings = load_settings(&app_handle).unwrap_or_default();
     let resolved_api_key = crate::utils::config::resolve_api_key(&settings.ai.api_key);
     let category_filter = extract_category_filter(&query);
     
@@ -478,7 +468,7 @@ pub async fn execute_query(
              Err(e) => {
                  eprintln!("Agentic search failed: {}", e);
                  // Fallback to old linear summary if agent fails
-                  match ai_summarize_query(&query, &structured_data, &resolved_api_key, &settings.ai.model).await {
+                  match ai_summarize_query(&app_handle, &query, &structured_data, &resolved_api_key, &settings.ai.model, settings.ai.summary_temperature).await {
                         Ok(linear) => format!("[Agent failed, used linear fallback] {}", linear),
                         Err(_e2) => format!("[AI Error: {}] {}", e, build_fallback_summary(&time_label, &app_filter, &category_filter, &all_activities, total_duration))
                   }
@@ -501,6 +491,109 @@ pub async fn execute_query(
     Ok(result)
 }
 
+/// Timeline row for one `get_music_history` activity ref.
+fn music_ref_to_item(track: &serde_json::Value) -> QueryItem {
+    let timestamp = track.get("time").and_then(|v| v.as_i64()).unwrap_or(0);
+    let app = track.get("app").and_then(|v| v.as_str()).unwrap_or("");
+    let title = track.get("title").and_then(|v| v.as_str()).unwrap_or("");
+    let media = track.get("media");
+    let song = media.and_then(|m| m.get("title")).and_then(|v| v.as_str());
+    let artist = media.and_then(|m| m.get("artist")).and_then(|v| v.as_str()).unwrap_or("Unknown");
+    QueryItem {
+        timestamp,
+        time_str: crate::utils::time::format_local_time(timestamp).unwrap_or_default(),
+        activity: format!("{} - {}", app, title),
+        duration: format_duration(track.get("duration_seconds").and_then(|v| v.as_i64()).unwrap_or(0) as i32),
+        details: song.map(|song| format!("♫ {} - {}", song, artist)),
+    }
+}
+
+// ─── Selection summary ───
+
+/// Most rows a single selection summary will load.
+const MAX_SELECTION_IDS: usize = 200;
+
+/// Explain a hand-picked set of timeline rows in one non-agentic LLM call.
+/// Falls back to a plain listing when AI is off or the call fails.
+#[tauri::command]
+pub async fn summarize_activities(
+    app_handle: AppHandle,
+    ids: Vec<i64>,
+) -> Result<String, AppError> {
+    let mut ids = ids;
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() {
+        return Err(AppError::InvalidInput("Select at least one activity".to_string()));
+    }
+    if ids.len() > MAX_SELECTION_IDS {
+        return Err(AppError::InvalidInput(format!("Select at most {} activities", MAX_SELECTION_IDS)));
+    }
+
+    let mut settings = crate::utils::config::load_settings(&app_handle);
+    settings.ai.apply_profile_or_default(None);
+    let api_key = crate::utils::config::resolve_api_key(&settings.ai.api_key);
+    let use_ai = settings.ai.enabled && !api_key.is_empty();
+
+    let conn = crate::database::open(&app_handle)?;
+    // Private rows stay out of anything sent to the model, like in chat.
+    let include_private = !use_ai || settings.privacy.include_private_in_ai;
+    let activities = crate::database::queries::get_activities_by_ids(&conn, &ids, include_private)?;
+    if activities.is_empty() {
+        return Ok("None of the selected activities are available to summarize.".to_string());
+    }
+
+    let total_duration: i32 = activities.iter().map(|a| a.duration_seconds).sum();
+    if !use_ai {
+        return Ok(build_selection_listing(&activities, total_duration));
+    }
+
+    let structured_data = build_structured_data(&activities, total_duration, &selection_label(&activities));
+    let prompt = format!(
+        "The user selected these activities on their timeline and asked \"what was I doing here?\". \
+         Explain in 2-4 sentences what they were working on, using the apps, window titles and screen text.\n\n{}",
+        structured_data
+    );
+    match crate::services::dashboard_engine::call_llm_for_summary(&app_handle, &api_key, &settings.ai.model, &prompt).await {
+        Ok(summary) => Ok(summary),
+        Err(e) => {
+            println!("[Query] Selection summary failed, using listing: {}", e);
+            Ok(build_selection_listing(&activities, total_duration))
+        }
+    }
+}
+
+/// "Selected activities, Mar 04, 09:12 AM – Mar 04, 10:40 AM" for the span covered by the selection.
+fn selection_label(activities: &[crate::models::Activity]) -> String {
+    let start = activities.iter().map(|a| a.start_time).min().unwrap_or(0);
+    let end = activities.iter().map(|a| a.end_time).max().unwrap_or(start);
+    format!(
+        "Selected activities, {} – {}",
+        crate::utils::time::format_local_datetime(start, "%b %d,").unwrap_or_default(),
+        crate::utils::time::format_local_datetime(end, "%b %d,").unwrap_or_default()
+    )
+}
+
+/// Deterministic summary for when no model is available: one bullet per row, oldest first.
+fn build_selection_listing(activities: &[crate::models::Activity], total_duration: i32) -> String {
+    let mut out = format!(
+        "{}: {} activities, {} tracked.\n",
+        selection_label(activities),
+        activities.len(),
+        format_duration(total_duration)
+    );
+    for a in activities.iter().rev() {
+        out.push_str(&format!(
+            "• {} {} — {} ({})\n",
+            crate::utils::time::format_local_time(a.start_time).unwrap_or_default(),
+            a.app_name,
+            a.window_title,
+            format_duration(a.duration_seconds)
+        ));
+    }
+    out.trim_end().to_string()
+}
+
 // ─── Fallback summary (no AI) ───
 
 fn build_fallback_summary(
@@ -510,7 +603,7 @@ fn build_fallback_summary(
     filtered: &[crate::models::Activity],
     total_duration: i32,
 ) -> String {
-    let cat_name = category_filter.map(|c| category_name(c));
+    let cat_name = category_filter.map(|c| crate::utils::categories::name_for(c as i64));
     
     if let Some(ref app_name) = app_filter {
         if filtered.is_empty() {
@@ -536,23 +629,183 @@ fn build_fallback_summary(
     }
 }
 
+// ─── Global search ───
+
+const SEARCH_SCAN_LIMIT: i64 = 2000;
+const SEARCH_MAX_RESULTS: usize = 200;
+
+/// Field weights: a title hit is usually the most specific, OCR the noisiest.
+const TITLE_WEIGHT: f64 = 3.0;
+const APP_WEIGHT: f64 = 2.0;
+const OCR_WEIGHT: f64 = 1.0;
+
+/// Free-text search over app name, window title and OCR text in one call,
+/// ranked by which fields matched and how closely. OCR goes through the
+/// `activities_fts` index when it exists.
+#[tauri::command]
+pub async fn search_everything(
+    app_handle: AppHandle,
+    query: String,
+    start_ts: i64,
+    end_ts: i64,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, AppError> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(50).clamp(1, SEARCH_MAX_RESULTS);
+
+    let conn = crate::database::open(&app_handle)?;
+
+    let pattern = format!(
+        "%{}%",
+        needle.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    );
+    let has_fts: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'activities_fts'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|n| n > 0)
+        .unwrap_or(false);
+    let ocr_condition = if has_fts {
+        "id IN (SELECT rowid FROM activities_fts WHERE activities_fts MATCH ?4)"
+    } else {
+        "LOWER(CAST(metadata AS TEXT)) LIKE ?3 ESCAPE '\\'"
+    };
+    let fts_phrase = format!("\"{}\"", needle.replace('"', "\"\""));
+
+    let sql = format!(
+        "SELECT id, app_name, window_title, start_time, duration_seconds, metadata, COALESCE(({ocr}), 0) AS ocr_match
+         FROM activities
+         WHERE start_time >= ?1 AND start_time <= ?2
+           AND (LOWER(app_name) LIKE ?3 ESCAPE '\\'
+                OR LOWER(window_title) LIKE ?3 ESCAPE '\\'
+                OR {ocr})
+         ORDER BY start_time DESC
+         LIMIT {limit}",
+        ocr = ocr_condition,
+        limit = SEARCH_SCAN_LIMIT
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let map_row = |row: &rusqlite::Row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2).unwrap_or_default(),
+            row.get::<_, i64>(3)?,
+            row.get::<_, i32>(4)?,
+            row.get::<_, Option<Vec<u8>>>(5)?,
+            row.get::<_, bool>(6)?,
+        ))
+    };
+    let rows: Vec<_> = if has_fts {
+        stmt.query_map(rusqlite::params![start_ts, end_ts, pattern, fts_phrase], map_row)
+    } else {
+        stmt.query_map(rusqlite::params![start_ts, end_ts, pattern], map_row)
+    }
+    ?
+    .filter_map(|r| r.ok())
+    .collect();
+
+    let mut hits: Vec<SearchHit> = Vec::new();
+    for (id, app_name, window_title, start_time, duration_seconds, metadata, ocr_match) in rows {
+        let screen_text = metadata
+            .and_then(|b| serde_json::from_slice::<ActivityMetadata>(&b).ok())
+            .and_then(|m| m.screen_text)
+            .filter(|t| !t.trim().is_empty());
+
+        let app_lower = app_name.to_lowercase();
+        let title_lower = window_title.to_lowercase();
+        let app_score = field_score(&app_lower, &needle) * APP_WEIGHT;
+        let title_score = field_score(&title_lower, &needle) * TITLE_WEIGHT;
+        // An FTS match is trusted as is; a LIKE match on the metadata blob is
+        // confirmed against the OCR text since it also covers URLs and media titles.
+        let ocr_hit = ocr_match
+            && screen_text
+                .as_ref()
+                .map(|t| has_fts || t.to_lowercase().contains(&needle))
+                .unwrap_or(false);
+        let ocr_score = if ocr_hit { OCR_WEIGHT } else { 0.0 };
+
+        let score = app_score + title_score + ocr_score;
+        if score <= 0.0 {
+            continue;
+        }
+        let match_field = if title_score >= app_score && title_score >= ocr_score {
+            "title"
+        } else if app_score >= ocr_score {
+            "app"
+        } else {
+            "ocr"
+        };
+
+        let details = if match_field == "ocr" {
+            screen_text.map(|t| crate::commands::chat::chat_snippet_around(&t, &needle))
+        } else {
+            None
+        };
+
+        hits.push(SearchHit {
+            item: QueryItem {
+                timestamp: start_time,
+                time_str: crate::utils::time::format_local_time(start_time).unwrap_or_default(),
+                activity: format!("{} - {}", app_name, window_title),
+                duration: format_duration(duration_seconds),
+                details,
+            },
+            activity_id: id,
+            match_field: match_field.to_string(),
+            score,
+        });
+    }
+
+    // Rows arrive newest first, so the stable sort keeps recency as the tie-break.
+    hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// 1.0 for an exact match, 0.75 when the text starts with the query, 0.5 for a
+/// match at a word start, 0.25 anywhere else, 0 for no match.
+fn field_score(text: &str, needle: &str) -> f64 {
+    if text == needle {
+        return 1.0;
+    }
+    if text.starts_with(needle) {
+        return 0.75;
+    }
+    let Some(pos) = text.find(needle) else {
+        return 0.0;
+    };
+    let at_word_start = text[..pos]
+        .chars()
+        .last()
+        .map(|c| !c.is_alphanumeric())
+        .unwrap_or(true);
+    if at_word_start {
+        0.5
+    } else {
+        0.25
+    }
+}
+
 // ─── Query history & cache ───
 
 #[tauri::command]
 pub async fn get_query_history(
     app_handle: AppHandle,
     limit: Option<i32>,
-) -> Result<Vec<QueryResult>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<Vec<QueryResult>, AppError> {
+    let conn = crate::database::open(&app_handle)?;
     
     let sql_limit = limit.unwrap_or(20);
     
     let mut stmt = conn.prepare(
         "SELECT query_text, result, created_at FROM query_cache ORDER BY created_at DESC LIMIT ?1"
-    ).map_err(|e| e.to_string())?;
+    )?;
     
     let history = stmt.query_map([sql_limit], |row| {
         let query_text: String = row.get(0)?;
@@ -569,8 +822,8 @@ pub async fn get_query_history(
             });
         
         Ok(result)
-    }).map_err(|e| e.to_string())?
-    .collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
     
     Ok(history)
 }
@@ -665,33 +918,27 @@ fn get_category_title_keywords(category_id: i32) -> Vec<&'static str> {
 
 // ─── App alias expansion ───
 
-fn expand_app_aliases(app: &str) -> Vec<String> {
+/// Every name merged with `app` in `app_merge_map`, search-only rows
+/// included, lowercased, `app` included.
+fn expand_app_aliases(conn: &rusqlite::Connection, app: &str) -> Vec<String> {
     let app_lower = app.to_lowercase();
-    
-    let alias_groups: Vec<Vec<&str>> = vec![
-        vec!["vs code", "vscode", "visual studio code", "code", "antigravity"],
-        vec!["chrome", "google chrome"],
-        vec!["edge", "microsoft edge", "msedge"],
-        vec!["brave", "brave browser"],
-        vec!["firefox", "mozilla firefox"],
-        vec!["teams", "microsoft teams"],
-        vec!["word", "microsoft word"],
-        vec!["excel", "microsoft excel"],
-        vec!["notepad", "notepad++"],
-        vec!["explorer", "file explorer", "windows explorer"],
-        vec!["spotify", "liked songs"],
-        vec!["youtube", "yt"],
-        vec!["whatsapp", "whatsapp.root"],
-        vec!["instagram", "insta"],
-    ];
-    
-    for group in &alias_groups {
-        if group.iter().any(|alias| *alias == app_lower) {
-            return group.iter().map(|s| s.to_string()).collect();
-        }
+    let merges = crate::database::queries::get_app_merges(conn).unwrap_or_default();
+
+    let canonical = merges
+        .iter()
+        .find(|m| m.from_name == app_lower)
+        .map(|m| m.to_name.to_lowercase())
+        .unwrap_or_else(|| app_lower.clone());
+    let mut names: Vec<String> = merges
+        .into_iter()
+        .filter(|m| m.to_name.to_lowercase() == canonical)
+        .map(|m| m.from_name)
+        .collect();
+    names.push(canonical);
+    if !names.contains(&app_lower) {
+        names.push(app_lower);
     }
-    
-    vec![app_lower]
+    names
 }
 
 // ─── Semantic search hints for DB-level filtering ───
@@ -703,7 +950,7 @@ struct SearchHints {
 
 /// Extract semantic hints from the query for efficient DB-level filtering.
 /// Used for broad multi-day queries where we can't send everything to AI.
-fn extract_search_hints(query: &str) -> SearchHints {
+fn extract_search_hints(conn: &rusqlite::Connection, query: &str) -> SearchHints {
     let q = query.to_lowercase();
     let mut keywords = Vec::new();
     let mut category_ids = Vec::new();
@@ -756,7 +1003,7 @@ fn extract_search_hints(query: &str) -> SearchHints {
     
     // Specific app mentions — add as keywords too
     if let Some(app) = extract_app_filter(query) {
-        let aliases = expand_app_aliases(&app);
+        let aliases = expand_app_aliases(conn, &app);
         keywords.extend(aliases);
     }
     
@@ -819,10 +1066,49 @@ fn extract_category_filter(query: &str) -> Option<i32> {
 
 // ─── Time range parsing ───
 
-fn parse_query_time_range(query: &str) -> (i64, i64, String) {
-    let now = chrono::Local::now();
+/// Default range for queries that name none (gibberish, vague, or any unmatched query).
+fn today_time_range() -> (i64, i64, String) {
+    let today = crate::utils::time::day_bounds(None, crate::utils::time::today());
+    (
+        today.start_ts,
+        Utc::now().timestamp(),
+        "Today's activity:".to_string(),
+    )
+}
+
+/// `hour`:00 on the day of `bounds`, in the offset resolved for that day.
+fn local_hour(bounds: &crate::utils::time::DayBounds, hour: u32) -> i64 {
+    let date = chrono::NaiveDate::parse_from_str(&bounds.date_key, "%Y-%m-%d").expect("valid date key");
+    date.and_hms_opt(hour, 0, 0).expect("valid hour").and_utc().timestamp() - bounds.offset_minutes as i64 * 60
+}
+
+/// "Yesterday's activity:" → "yesterday", for sentences like "songs you listened to in …".
+fn scope_label(time_label: &str) -> String {
+    let label = time_label
+        .trim_end_matches(':')
+        .trim_end_matches(" activity")
+        .trim_end_matches("'s");
+    match label.split_whitespace().next() {
+        Some("Today" | "Yesterday" | "This" | "Last") => {
+            let mut chars = label.chars();
+            chars.next().map(|c| c.to_lowercase().chain(chars).collect()).unwrap_or_default()
+        }
+        _ => label.to_string(),
+    }
+}
+
+/// The range a query names, or `None` when it names none and would fall back to today.
+fn parse_explicit_time_range(query: &str, conn: &rusqlite::Connection) -> Option<(i64, i64, String)> {
+    let tz = crate::utils::time::current_offset();
+    let now = Utc::now().with_timezone(&tz);
+    let today = crate::utils::time::today();
+    let day_start = |date: chrono::NaiveDate| crate::utils::time::day_bounds(Some(conn), date).start_ts;
     let query_lower = query.to_lowercase();
-    let tz = now.timezone();
+    
+    // Explicit dates, months, quarters and ranges: "on 2024-03-15", "in June", "Q2", "last tuesday to thursday"
+    if let Some(range) = crate::utils::date_expr::parse_date_expression(&query_lower, Some(conn)) {
+        return Some((range.start_ts, range.end_ts, format!("{} activity:", range.label)));
+    }
     
     // "yesterday" (with typo handling)
     if query_lower.contains("yesterday") || query_lower.contains("yesteray")
@@ -830,135 +1116,113 @@ fn parse_query_time_range(query: &str) -> (i64, i64, String) {
         || query_lower.contains("yesterda") || query_lower.contains("ysterday")
         || query_lower.contains("yesteday") || query_lower.contains("yesterdy")
     {
-        let yesterday = now - chrono::Duration::days(1);
-        let start = yesterday.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        let end = yesterday.date_naive().and_hms_opt(23, 59, 59).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
-            end.and_local_timezone(tz).unwrap().timestamp(),
+        let bounds = crate::utils::time::day_bounds(Some(conn), today - chrono::Duration::days(1));
+        return Some((
+            bounds.start_ts,
+            bounds.end_ts - 1,
             "Yesterday's activity:".to_string(),
-        );
+        ));
     }
     
     // "last week" / "past week"
     if query_lower.contains("last week") || query_lower.contains("past week") {
-        let start_date = now - chrono::Duration::days(7);
-        let start = start_date.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
+        return Some((
+            day_start(today - chrono::Duration::days(7)),
             now.timestamp(),
             "Last 7 days activity:".to_string(),
-        );
+        ));
     }
     
     // "this week"
     if query_lower.contains("this week") {
-        let weekday = now.date_naive().weekday().num_days_from_monday() as i64;
-        let monday = now - chrono::Duration::days(weekday);
-        let start = monday.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
+        let weekday = today.weekday().num_days_from_monday() as i64;
+        return Some((
+            day_start(today - chrono::Duration::days(weekday)),
             now.timestamp(),
             "This week's activity:".to_string(),
-        );
+        ));
     }
     
     // "last month" / "past month"
     if query_lower.contains("last month") || query_lower.contains("past month") {
-        let start_date = now - chrono::Duration::days(30);
-        let start = start_date.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
+        return Some((
+            day_start(today - chrono::Duration::days(30)),
             now.timestamp(),
             "Last 30 days activity:".to_string(),
-        );
+        ));
     }
     
     // "last N hours" / "past N hours"
     if let Some(hours) = extract_n_hours(&query_lower) {
         let start = now - chrono::Duration::hours(hours);
-        return (
+        return Some((
             start.timestamp(),
             now.timestamp(),
             format!("Last {} hour{} activity:", hours, if hours == 1 { "" } else { "s" }),
-        );
+        ));
     }
     
     // "last hour"
     if query_lower.contains("last hour") {
         let start = now - chrono::Duration::hours(1);
-        return (
+        return Some((
             start.timestamp(),
             now.timestamp(),
             "Last hour's activity:".to_string(),
-        );
+        ));
     }
     
     // "N days ago"
     if let Some(days) = extract_days_ago(&query_lower) {
-        let target = now - chrono::Duration::days(days);
-        let start = target.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        let end = target.date_naive().and_hms_opt(23, 59, 59).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
-            end.and_local_timezone(tz).unwrap().timestamp(),
+        let bounds = crate::utils::time::day_bounds(Some(conn), today - chrono::Duration::days(days));
+        return Some((
+            bounds.start_ts,
+            bounds.end_ts - 1,
             format!("{} days ago:", days),
-        );
+        ));
     }
     
     // Day names: "monday", "tuesday", etc. (finds the most recent one)
-    if let Some((day_start, day_end, label)) = parse_day_name(&query_lower, &now) {
-        return (day_start, day_end, label);
+    if let Some((day_start, day_end, label)) = parse_day_name(&query_lower, &now, conn) {
+        return Some((day_start, day_end, label));
     }
     
     // "today" or "so far" or any unrecognized query (default to today)
     if query_lower.contains("today") || query_lower.contains("so far") {
-        let start = now.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
+        return Some((
+            day_start(today),
             now.timestamp(),
             "Today's activity:".to_string(),
-        );
+        ));
     }
     
     // Time-of-day queries
+    let bounds = crate::utils::time::day_bounds(Some(conn), today);
     if query_lower.contains("morning") {
-        let start = now.date_naive().and_hms_opt(6, 0, 0).unwrap();
-        let end = now.date_naive().and_hms_opt(12, 0, 0).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
-            end.and_local_timezone(tz).unwrap().timestamp(),
+        return Some((
+            local_hour(&bounds, 6),
+            local_hour(&bounds, 12),
             "This morning's activity:".to_string(),
-        );
+        ));
     }
     
     if query_lower.contains("afternoon") {
-        let start = now.date_naive().and_hms_opt(12, 0, 0).unwrap();
-        let end = now.date_naive().and_hms_opt(18, 0, 0).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
-            end.and_local_timezone(tz).unwrap().timestamp(),
+        return Some((
+            local_hour(&bounds, 12),
+            local_hour(&bounds, 18),
             "This afternoon's activity:".to_string(),
-        );
+        ));
     }
     
     if query_lower.contains("evening") || query_lower.contains("tonight") || query_lower.contains("night") {
-        let start = now.date_naive().and_hms_opt(18, 0, 0).unwrap();
-        let end = now.date_naive().and_hms_opt(23, 59, 59).unwrap();
-        return (
-            start.and_local_timezone(tz).unwrap().timestamp(),
-            end.and_local_timezone(tz).unwrap().timestamp(),
+        return Some((
+            local_hour(&bounds, 18),
+            bounds.end_ts - 1,
             "This evening's activity:".to_string(),
-        );
+        ));
     }
     
-    // Default: today (handles gibberish, vague, or any unmatched query)
-    let start = now.date_naive().and_hms_opt(0, 0, 0).unwrap();
-    (
-        start.and_local_timezone(tz).unwrap().timestamp(),
-        now.timestamp(),
-        "Today's activity:".to_string(),
-    )
+    None
 }
 
 // ─── Helper parsers ───
@@ -977,7 +1241,11 @@ fn extract_n_hours(query: &str) -> Option<i64> {
         .and_then(|m| m.as_str().parse::<i64>().ok())
 }
 
-fn parse_day_name(query: &str, now: &chrono::DateTime<chrono::Local>) -> Option<(i64, i64, String)> {
+fn parse_day_name(
+    query: &str,
+    now: &chrono::DateTime<chrono::FixedOffset>,
+    conn: &rusqlite::Connection,
+) -> Option<(i64, i64, String)> {
     let days = [
         ("monday", chrono::Weekday::Mon),
         ("tuesday", chrono::Weekday::Tue),
@@ -998,16 +1266,14 @@ fn parse_day_name(query: &str, now: &chrono::DateTime<chrono::Local>) -> Option<
             }
             
             let target = *now - chrono::Duration::days(days_back);
-            let tz = now.timezone();
-            let start = target.date_naive().and_hms_opt(0, 0, 0).unwrap();
-            let end = target.date_naive().and_hms_opt(23, 59, 59).unwrap();
+            let bounds = crate::utils::time::day_bounds(Some(conn), target.date_naive());
             
             let label = format!("Last {}'s activity:", 
                 name.chars().next().unwrap().to_uppercase().collect::<String>() + &name[1..]);
             
             return Some((
-                start.and_local_timezone(tz).unwrap().timestamp(),
-                end.and_local_timezone(tz).unwrap().timestamp(),
+                bounds.start_ts,
+                bounds.end_ts - 1,
                 label,
             ));
         }
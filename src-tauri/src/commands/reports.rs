@@ -0,0 +1,26 @@
+use tauri::AppHandle;
+
+use crate::models::GeneratedReport;
+
+/// `start_date`/`end_date` are inclusive local dates (YYYY-MM-DD).
+/// `format` is "html" (default), "markdown" or "both".
+#[tauri::command]
+pub async fn generate_period_report(
+    app_handle: AppHandle,
+    start_date: String,
+    end_date: String,
+    format: Option<String>,
+) -> Result<GeneratedReport, String> {
+    let parse = |value: &str| {
+        chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", value))
+    };
+    let start = parse(&start_date)?;
+    let end = parse(&end_date)?;
+    crate::services::report_engine::generate_period_report(
+        &app_handle,
+        start,
+        end,
+        format.as_deref().unwrap_or("html"),
+    )
+}
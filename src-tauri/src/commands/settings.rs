@@ -1,22 +1,25 @@
-use tauri::{AppHandle, Manager};
-use crate::models::{Settings, Category};
+use tauri::AppHandle;
+use crate::models::{Settings, Category, CategorizationExplanation, LlmUsageStats, ModelCapabilities};
+use crate::services::model_capabilities::{self, CapabilitySource};
+use crate::services::settings_bus::SettingsPropagation;
 use serde::{Deserialize, Serialize};
 #[cfg(target_os = "windows")]
 use tauri_plugin_autostart::ManagerExt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ModelInfo {
     pub id: String,
     pub name: String,
+    pub capabilities: ModelCapabilities,
+    pub capability_source: CapabilitySource,
 }
 
 #[tauri::command]
 pub async fn get_nvidia_models(api_key: String) -> Result<Vec<ModelInfo>, String> {
-    let client = reqwest::Client::new();
-    
-    let response = client
+    let response = crate::utils::http::http_client()
         .get("https://integrate.api.nvidia.com/v1/models")
         .header("Authorization", format!("Bearer {}", api_key))
+        .timeout(std::time::Duration::from_secs(20))
         .send()
         .await
         .map_err(|e| format!("Failed to fetch models: {}", e))?;
@@ -45,48 +48,126 @@ pub async fn get_nvidia_models(api_key: String) -> Result<Vec<ModelInfo>, String
     let models: Vec<ModelInfo> = models_response
         .data
         .into_iter()
-        .map(|m| ModelInfo {
-            id: m.id.clone(),
-            name: m.id,
+        .map(|m| {
+            let (capabilities, capability_source) = model_capabilities::resolve_with_source(&m.id);
+            ModelInfo {
+                id: m.id.clone(),
+                name: m.id,
+                capabilities,
+                capability_source,
+            }
         })
         .collect();
     
     Ok(models)
 }
 
+/// Send a tiny request to `model` and infer reasoning and native tool support
+/// from the response shape. The result is cached and used by the agent unless
+/// the user set an override for the model.
+#[tauri::command]
+pub async fn probe_model_capabilities(
+    app_handle: AppHandle,
+    model: String,
+) -> Result<ModelCapabilities, String> {
+    let model = model.trim().to_string();
+    if model.is_empty() {
+        return Err("Model id is required".to_string());
+    }
+    let settings = get_settings(app_handle.clone()).await?;
+    let api_key = crate::utils::config::resolve_api_key(&settings.ai.api_key);
+    if api_key.is_empty() {
+        return Err("API key is missing".to_string());
+    }
+
+    let mut body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": "Reply with the single word OK." }],
+        "temperature": 0.0,
+        "max_tokens": 256,
+        "stream": false,
+        "tools": [{
+            "type": "function",
+            "function": {
+                "name": "ping",
+                "description": "Connectivity check. Never needs to be called.",
+                "parameters": { "type": "object", "properties": {} }
+            }
+        }]
+    });
+
+    let timeout = std::time::Duration::from_secs(30);
+    let priority = crate::services::llm_client::Priority::Interactive;
+    let (response, supports_native_tools) =
+        match crate::services::llm_client::send_chat_request(&api_key, &model, &body, timeout, priority, None).await {
+            Ok(response) => (response, true),
+            // Endpoints without tool support reject the field with a 4xx; retry plain.
+            Err(crate::error::AppError::Api(e)) if e.starts_with("API Error 4") => {
+                println!("[Models] {} rejected tools ({}), probing without", model, e);
+                if let Some(obj) = body.as_object_mut() {
+                    obj.remove("tools");
+                }
+                let response =
+                    crate::services::llm_client::send_chat_request(&api_key, &model, &body, timeout, priority, None).await?;
+                (response, false)
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+
+    let value: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+    let message = &value["choices"][0]["message"];
+    let has_reasoning_field = ["reasoning_content", "reasoning"].iter().any(|key| {
+        message[key].as_str().map(|s| !s.trim().is_empty()).unwrap_or(false)
+    });
+    let content = message["content"].as_str().unwrap_or("");
+    let supports_reasoning = has_reasoning_field || content.contains("<think>");
+
+    let base = model_capabilities::builtin_or_default(&model);
+    let capabilities = ModelCapabilities {
+        context_window: base.context_window,
+        supports_reasoning,
+        supports_native_tools,
+        // Reasoning tokens count against max_tokens; leave room for the answer.
+        max_tokens: if supports_reasoning { base.max_tokens.max(3_072) } else { base.max_tokens },
+    };
+    println!(
+        "[Models] Probed {}: reasoning={}, native_tools={}",
+        model, capabilities.supports_reasoning, capabilities.supports_native_tools
+    );
+
+    let conn = crate::database::open(&app_handle)?;
+    model_capabilities::save_probed(&conn, &model, &capabilities)?;
+    Ok(capabilities)
+}
+
 #[tauri::command]
 pub async fn get_settings(
     app_handle: AppHandle,
 ) -> Result<Settings, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let config_path = data_dir.join("config").join("settings.json");
-    
-    if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-        let mut settings: Settings = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-        crate::utils::config::apply_env_defaults(&mut settings);
-        Ok(settings)
-    } else {
-        let mut settings = Settings::default();
-        crate::utils::config::apply_env_defaults(&mut settings);
-        Ok(settings)
-    }
+    let mut settings = crate::utils::config::load_settings(&app_handle);
+    settings.privacy.unlock_password_hash = None;
+    Ok(settings)
+}
+
+/// What had to be dropped, reset or clamped when settings.json was last read.
+#[tauri::command]
+pub async fn get_settings_warnings() -> Result<Vec<String>, String> {
+    Ok(crate::utils::config::settings_warnings())
 }
 
 #[tauri::command]
 pub async fn update_settings(
     app_handle: AppHandle,
-    settings: Settings,
+    mut settings: Settings,
 ) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let config_dir = data_dir.join("config");
-    
-    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
-    
-    let config_path = config_dir.join("settings.json");
-    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    
-    std::fs::write(&config_path, content).map_err(|e| e.to_string())?;
+    settings.ai.validate_temperatures()?;
+    // The password hash is managed by `set_unlock_password` alone.
+    settings.privacy.unlock_password_hash = crate::services::app_lock::stored_password_hash(&app_handle);
+    for warning in settings.sanitize() {
+        log::warn!("[Settings] {}", warning);
+    }
+    crate::utils::config::save_settings_file(&app_handle, &settings)?;
+    crate::utils::config::clear_settings_warnings();
 
     #[cfg(all(target_os = "windows", not(debug_assertions)))]
     {
@@ -98,14 +179,13 @@ pub async fn update_settings(
         }
     }
 
-    crate::services::activity_tracker::set_tracking_enabled(settings.tracking.enabled);
-    crate::services::activity_tracker::set_tracking_interval(settings.tracking.tracking_interval);
+    // Services pick up the new values from the bus; no restart needed.
+    crate::services::settings_bus::publish(&app_handle, settings.clone());
 
     // Keep selected settings model visible in "recent models" so Chat can use it immediately.
     let model_id = settings.ai.model.trim();
     if !model_id.is_empty() {
-        let db_path = data_dir.join("intentflow.db");
-        let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+        let conn = crate::database::open(&app_handle)?;
         let now = chrono::Utc::now().timestamp();
         let _ = conn.execute(
             "INSERT INTO ai_model_usage (model_id, model_name, use_count, last_used)
@@ -120,63 +200,211 @@ pub async fn update_settings(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_settings_propagation() -> Result<Vec<SettingsPropagation>, String> {
+    Ok(crate::services::settings_bus::propagation_status())
+}
+
 #[tauri::command]
 pub async fn get_categories(
     app_handle: AppHandle,
 ) -> Result<Vec<Category>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    let mut stmt = conn.prepare(
-        "SELECT id, name, icon, color, keywords, apps FROM categories ORDER BY id"
-    ).map_err(|e| e.to_string())?;
-    
-    let categories = stmt.query_map([], |row| {
-        let keywords: Option<String> = row.get(4)?;
-        let apps: Option<String> = row.get(5)?;
-        
-        Ok(Category {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            icon: row.get(2)?,
-            color: row.get(3)?,
-            keywords: keywords.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
-            apps: apps.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
-        })
-    }).map_err(|e| e.to_string())?
-    .collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
-    Ok(categories)
+    crate::utils::categories::load_categories(&conn).map_err(|e| e.to_string())
 }
 
+/// Update existing categories and create new ones (id 0 or an unknown id).
+/// The tracker and prompts pick up the change immediately.
 #[tauri::command]
 pub async fn update_categories(
     app_handle: AppHandle,
     categories: Vec<Category>,
 ) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     for category in categories {
+        let name = category.name.trim();
+        if name.is_empty() {
+            return Err("Category name cannot be empty".to_string());
+        }
         let keywords = serde_json::to_string(&category.keywords).map_err(|e| e.to_string())?;
         let apps = serde_json::to_string(&category.apps).map_err(|e| e.to_string())?;
         
-        conn.execute(
+        let updated = conn.execute(
             "UPDATE categories SET name = ?1, icon = ?2, color = ?3, keywords = ?4, apps = ?5 WHERE id = ?6",
-            [
-                &category.name,
-                &category.icon,
-                &category.color,
-                &keywords,
-                &apps,
-                &category.id.to_string(),
-            ],
+            rusqlite::params![name, category.icon, category.color, keywords, apps, category.id],
         ).map_err(|e| e.to_string())?;
+        
+        if updated == 0 {
+            conn.execute(
+                "INSERT INTO categories (name, icon, color, keywords, apps, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    name,
+                    category.icon,
+                    category.color,
+                    keywords,
+                    apps,
+                    chrono::Utc::now().timestamp()
+                ],
+            ).map_err(|e| e.to_string())?;
+        }
     }
     
+    crate::utils::categories::reload(&conn);
     Ok(())
 }
+
+fn validate_category_color(color: &str) -> Result<(), String> {
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if (hex.len() == 6 || hex.len() == 3) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(format!("Invalid color '{}', expected #rrggbb", color))
+    }
+}
+
+#[tauri::command]
+pub async fn create_category(
+    app_handle: AppHandle,
+    name: String,
+    color: Option<String>,
+    icon: Option<String>,
+    keywords: Option<Vec<String>>,
+    apps: Option<Vec<String>>,
+) -> Result<Category, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Category name cannot be empty".to_string());
+    }
+    let defaults = Category::default();
+    let color = color.unwrap_or(defaults.color);
+    validate_category_color(&color)?;
+    let icon = icon.filter(|i| !i.trim().is_empty()).unwrap_or(defaults.icon);
+    let keywords = serde_json::to_string(&keywords.unwrap_or_default()).map_err(|e| e.to_string())?;
+    let apps = serde_json::to_string(&apps.unwrap_or_default()).map_err(|e| e.to_string())?;
+
+    let conn = crate::database::open(&app_handle)?;
+    
+    conn.execute(
+        "INSERT INTO categories (name, icon, color, keywords, apps, created_at, is_builtin)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0)",
+        rusqlite::params![name, icon, color, keywords, apps, chrono::Utc::now().timestamp()],
+    ).map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid() as i32;
+    
+    crate::utils::categories::reload(&conn);
+    crate::utils::categories::get(id).ok_or_else(|| "Category was not saved".to_string())
+}
+
+/// Rename, recolor or change the icon of a category; omitted fields are left as-is.
+#[tauri::command]
+pub async fn edit_category(
+    app_handle: AppHandle,
+    id: i32,
+    name: Option<String>,
+    color: Option<String>,
+    icon: Option<String>,
+) -> Result<Category, String> {
+    if let Some(ref color) = color {
+        validate_category_color(color)?;
+    }
+    let name = name.map(|n| n.trim().to_string());
+    if name.as_deref() == Some("") {
+        return Err("Category name cannot be empty".to_string());
+    }
+
+    let conn = crate::database::open(&app_handle)?;
+    
+    let updated = conn.execute(
+        "UPDATE categories SET
+            name = COALESCE(?1, name),
+            color = COALESCE(?2, color),
+            icon = COALESCE(?3, icon)
+         WHERE id = ?4",
+        rusqlite::params![name, color, icon, id],
+    ).map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Category {} not found", id));
+    }
+    
+    crate::utils::categories::reload(&conn);
+    crate::utils::categories::get(id).ok_or_else(|| format!("Category {} not found", id))
+}
+
+/// Delete a custom category. Its activities (and app registry entries) move to Other.
+/// Returns the number of activities reassigned.
+#[tauri::command]
+pub async fn delete_category(
+    app_handle: AppHandle,
+    id: i32,
+) -> Result<usize, String> {
+    let other_id = crate::utils::categories::OTHER_CATEGORY_ID;
+
+    let conn = crate::database::open(&app_handle)?;
+    
+    let is_builtin: bool = conn.query_row(
+        "SELECT COALESCE(is_builtin, 0) FROM categories WHERE id = ?1",
+        [id],
+        |row| row.get::<_, i64>(0).map(|v| v != 0),
+    ).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => format!("Category {} not found", id),
+        other => other.to_string(),
+    })?;
+    if is_builtin || id == other_id {
+        return Err("Built-in categories cannot be deleted".to_string());
+    }
+    
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let reassigned = tx.execute(
+        "UPDATE activities SET category_id = ?1 WHERE category_id = ?2",
+        [other_id, id],
+    ).map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE app_registry SET category_id = ?1 WHERE category_id = ?2",
+        [other_id, id],
+    ).map_err(|e| e.to_string())?;
+    // Summaries are unique per (date, hour, category), so moving them could collide with
+    // Other's rows; drop them instead.
+    tx.execute("DELETE FROM activity_summaries WHERE category_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM category_goals WHERE category_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM categories WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    
+    crate::utils::categories::reload(&conn);
+    Ok(reassigned)
+}
+
+/// Token usage and estimated cost of LLM calls in `month` ("YYYY-MM", default the current
+/// local month), per caller: chat, dashboard, summary, intent and chat titles.
+#[tauri::command]
+pub async fn get_llm_usage_stats(
+    app_handle: AppHandle,
+    month: Option<String>,
+) -> Result<LlmUsageStats, String> {
+    use chrono::Datelike;
+
+    let first_day = match month.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        Some(m) => chrono::NaiveDate::parse_from_str(&format!("{}-01", m), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid month '{}', expected YYYY-MM", m))?,
+        None => crate::utils::time::today().with_day(1).unwrap_or_else(crate::utils::time::today),
+    };
+    let next_month = first_day
+        .checked_add_months(chrono::Months::new(1))
+        .ok_or_else(|| format!("Month out of range: {}", first_day))?;
+
+    let conn = crate::database::open(&app_handle)?;
+    let start_ts = crate::utils::time::day_bounds(Some(&conn), first_day).start_ts;
+    let end_ts = crate::utils::time::day_bounds(Some(&conn), next_month).start_ts;
+    crate::services::llm_client::usage_stats(&conn, &first_day.format("%Y-%m").to_string(), start_ts, end_ts)
+}
+
+/// Category a window would get and the rule that decided it, for debugging custom rules.
+#[tauri::command]
+pub async fn explain_categorization(app_name: String, title: String) -> Result<CategorizationExplanation, String> {
+    Ok(crate::services::activity_tracker::explain_categorization(&app_name, &title))
+}
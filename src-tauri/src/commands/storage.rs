@@ -1,14 +1,21 @@
 use tauri::{AppHandle, Manager};
-use crate::models::StorageStats;
+use std::collections::BTreeSet;
+
+use rusqlite::types::Value as SqlValue;
+
+use crate::models::{
+    ActivityDeleteFilter, ActivityDeletion, ActivityMetadata, DailyIngest, DataIntegrityReport, IndexRebuildProgress,
+    KeywordAudit, PurgedTableRows, RecentDataPurge, RetentionImpact, SampleDataSummary, StorageCategoryUsage,
+    StorageContributor, StorageForecast, StorageSettings, StorageStats, TableStorage,
+};
+use crate::utils::ics::{render_calendar, IcsEvent};
 
 #[tauri::command]
 pub async fn get_storage_stats(
     app_handle: AppHandle,
 ) -> Result<StorageStats, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let db_path = crate::database::db_path(&app_handle)?;
+    let conn = crate::database::open(&app_handle)?;
     
     // Get database file size
     let total_size_bytes = db_path.metadata()
@@ -52,7 +59,22 @@ pub async fn get_storage_stats(
         [],
         |row| row.get(0),
     ).unwrap_or(0);
-    
+
+    let (tables, size_source) = table_storage(&conn)?;
+    let (ocr_text_bytes, _) = ocr_text_usage(&conn, None);
+
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let thumbnails_bytes = dir_size(&data_dir.join(crate::services::screen_capture::THUMBNAIL_DIR));
+    let icons_bytes = dir_size(&crate::services::app_icons::icons_dir(&data_dir));
+
+    let used_bytes: i64 = tables.iter().map(|t| t.size_bytes).sum();
+    let days_tracked = if oldest_activity > 0 {
+        ((chrono::Utc::now().timestamp() - oldest_activity) / 86400).max(1)
+    } else {
+        1
+    };
+    let categories = storage_categories(&tables, ocr_text_bytes, thumbnails_bytes, icons_bytes, total_size_bytes - used_bytes);
+
     Ok(StorageStats {
         total_size_bytes,
         activities_count,
@@ -61,18 +83,435 @@ pub async fn get_storage_stats(
         entries_count,
         oldest_activity,
         newest_activity,
+        tables,
+        categories,
+        ocr_text_bytes,
+        thumbnails_bytes,
+        icons_bytes,
+        bytes_per_day: used_bytes / days_tracked,
+        size_source,
+    })
+}
+
+/// Size the forecast counts down to.
+const STORAGE_LIMIT_BYTES: i64 = 10 * 1024 * 1024 * 1024;
+const FORECAST_WINDOW_DAYS: i64 = 30;
+/// OCR retention window suggested in the forecast; there is no such setting yet.
+const SUGGESTED_OCR_RETENTION_DAYS: i64 = 14;
+
+/// Raw bytes per row, with the time column it is bucketed by. Mirrors what
+/// `cleanup_old_data` and the trackers write, not every table.
+const INGEST_SOURCES: &[(&str, &str, &str)] = &[
+    ("activities", "start_time", "LENGTH(app_name) + LENGTH(window_title) + COALESCE(LENGTH(metadata), 0)"),
+    ("clipboard_events", "captured_at", "COALESCE(LENGTH(content), 0) + COALESCE(LENGTH(window_title), 0)"),
+    ("code_file_events", "detected_at", "LENGTH(path) + COALESCE(LENGTH(content_preview), 0)"),
+    (
+        "chat_messages",
+        "created_at",
+        "LENGTH(content) + COALESCE(LENGTH(agent_steps), 0) + COALESCE(LENGTH(activities), 0) + COALESCE(LENGTH(actions), 0)",
+    ),
+];
+
+#[tauri::command]
+pub async fn get_storage_forecast(
+    app_handle: AppHandle,
+) -> Result<StorageForecast, String> {
+    let settings = crate::utils::config::load_settings(&app_handle);
+    let db_path = crate::database::db_path(&app_handle)?;
+    let conn = crate::database::open(&app_handle)?;
+
+    let total_size_bytes = db_path.metadata().map(|m| m.len() as i64).unwrap_or(0);
+    let offset_secs = crate::utils::time::current_offset().local_minus_utc() as i64;
+    build_forecast(&conn, total_size_bytes, &settings.storage, chrono::Utc::now().timestamp(), offset_secs)
+}
+
+fn build_forecast(
+    conn: &rusqlite::Connection,
+    total_size_bytes: i64,
+    storage: &StorageSettings,
+    now: i64,
+    offset_secs: i64,
+) -> Result<StorageForecast, String> {
+    let (tables, size_source) = table_storage(conn)?;
+    let used_bytes: i64 = tables.iter().map(|t| t.size_bytes).sum();
+
+    let raw_total = raw_bytes_before(conn, None);
+    let overhead_factor = if raw_total > 0 {
+        (used_bytes as f64 / raw_total as f64).clamp(1.0, 10.0)
+    } else {
+        1.0
+    };
+    let scale = |bytes: i64| (bytes as f64 * overhead_factor) as i64;
+
+    let daily_ingest = daily_ingest(conn, offset_secs)?;
+    let bytes_per_day = scale(cumulative_slope(&daily_ingest) as i64).max(0);
+    let days_until_limit = if bytes_per_day > 0 {
+        Some(((STORAGE_LIMIT_BYTES - total_size_bytes).max(0) + bytes_per_day - 1) / bytes_per_day)
+    } else {
+        None
+    };
+
+    let mut contributors = storage_contributors(conn);
+    contributors.iter_mut().for_each(|c| c.size_bytes = scale(c.size_bytes));
+    contributors.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
+
+    let mut retention_impacts = Vec::new();
+    let retention_days = storage.retention_days as i64;
+    let auto_cleanup = storage.auto_cleanup;
+    for days in [180, 90, 30] {
+        if days >= retention_days && auto_cleanup {
+            continue;
+        }
+        let bytes_removed = scale(raw_bytes_before(conn, Some(now - days * 86400)));
+        if bytes_removed <= 0 {
+            continue;
+        }
+        retention_impacts.push(RetentionImpact {
+            setting: "retention_days".to_string(),
+            days,
+            bytes_removed,
+            description: format!("Keeping {} days of history would remove ~{}", days, format_size(bytes_removed)),
+        });
+    }
+    let (ocr_old_bytes, _) = ocr_text_usage(conn, Some(now - SUGGESTED_OCR_RETENTION_DAYS * 86400));
+    if ocr_old_bytes > 0 {
+        let bytes_removed = scale(ocr_old_bytes);
+        retention_impacts.push(RetentionImpact {
+            setting: "ocr_retention_days".to_string(),
+            days: SUGGESTED_OCR_RETENTION_DAYS,
+            bytes_removed,
+            description: format!(
+                "Keeping OCR text for {} days would remove ~{}",
+                SUGGESTED_OCR_RETENTION_DAYS,
+                format_size(bytes_removed)
+            ),
+        });
+    }
+    if !auto_cleanup {
+        retention_impacts.push(RetentionImpact {
+            setting: "auto_cleanup".to_string(),
+            days: retention_days,
+            bytes_removed: scale(raw_bytes_before(conn, Some(now - retention_days * 86400))),
+            description: format!("Automatic cleanup is off, so the {}-day retention is not applied", retention_days),
+        });
+    }
+
+    Ok(StorageForecast {
+        total_size_bytes,
+        size_source,
+        daily_ingest,
+        bytes_per_day,
+        overhead_factor,
+        forecast_30d_bytes: total_size_bytes + bytes_per_day * 30,
+        forecast_90d_bytes: total_size_bytes + bytes_per_day * 90,
+        limit_bytes: STORAGE_LIMIT_BYTES,
+        days_until_limit,
+        contributors,
+        retention_impacts,
     })
 }
 
+/// UTF-8 bytes and row count of OCR text in activity metadata, optionally only before `before`.
+fn ocr_text_usage(conn: &rusqlite::Connection, before: Option<i64>) -> (i64, i64) {
+    conn.query_row(
+        "SELECT COALESCE(SUM(LENGTH(CAST(json_extract(CAST(metadata AS TEXT), '$.screen_text') AS BLOB))), 0),
+                COUNT(json_extract(CAST(metadata AS TEXT), '$.screen_text'))
+         FROM activities
+         WHERE metadata IS NOT NULL AND json_valid(CAST(metadata AS TEXT)) AND start_time < ?1",
+        [before.unwrap_or(i64::MAX)],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .unwrap_or((0, 0))
+}
+
+/// Raw bytes of every ingest source, optionally only rows older than `before`.
+fn raw_bytes_before(conn: &rusqlite::Connection, before: Option<i64>) -> i64 {
+    INGEST_SOURCES
+        .iter()
+        .filter(|(table, _, _)| table_exists(conn, table))
+        .map(|(table, time_column, bytes)| {
+            conn.query_row(
+                &format!("SELECT COALESCE(SUM({}), 0) FROM {} WHERE {} < ?1", bytes, table, time_column),
+                [before.unwrap_or(i64::MAX)],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// Raw bytes written per local day over the last `FORECAST_WINDOW_DAYS`.
+fn daily_ingest(conn: &rusqlite::Connection, offset_secs: i64) -> Result<Vec<DailyIngest>, String> {
+    let today = crate::utils::time::today();
+    let first_day = today - chrono::Duration::days(FORECAST_WINDOW_DAYS - 1);
+    let since = first_day.and_hms_opt(0, 0, 0).map(|d| d.and_utc().timestamp() - offset_secs).unwrap_or(0);
+
+    let mut by_day: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    for (table, time_column, bytes) in INGEST_SOURCES {
+        if !table_exists(conn, table) {
+            continue;
+        }
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT date({0} + ?1, 'unixepoch') AS day, COALESCE(SUM({1}), 0)
+                 FROM {2} WHERE {0} >= ?2 GROUP BY day",
+                time_column, bytes, table
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([offset_secs, since], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+            .map_err(|e| e.to_string())?;
+        for (day, bytes) in rows.filter_map(|r| r.ok()) {
+            *by_day.entry(day).or_insert(0) += bytes;
+        }
+    }
+
+    Ok((0..FORECAST_WINDOW_DAYS)
+        .map(|i| {
+            let date_key = (first_day + chrono::Duration::days(i)).format("%Y-%m-%d").to_string();
+            let bytes = by_day.get(&date_key).copied().unwrap_or(0);
+            DailyIngest { date_key, bytes }
+        })
+        .collect())
+}
+
+/// Least-squares slope of cumulative ingest against day index, in bytes per day.
+/// Fitting the running total keeps one unusually heavy day from dominating.
+fn cumulative_slope(days: &[DailyIngest]) -> f64 {
+    let n = days.len() as f64;
+    if days.len() < 2 {
+        return days.first().map(|d| d.bytes as f64).unwrap_or(0.0);
+    }
+    let mut total = 0i64;
+    let points: Vec<(f64, f64)> = days
+        .iter()
+        .enumerate()
+        .map(|(i, d)| {
+            total += d.bytes;
+            (i as f64, total as f64)
+        })
+        .collect();
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance > 0.0 { covariance / variance } else { 0.0 }
+}
+
+/// Raw bytes and rows of the kinds of data that usually dominate the database.
+fn storage_contributors(conn: &rusqlite::Connection) -> Vec<StorageContributor> {
+    let (ocr_bytes, ocr_rows) = ocr_text_usage(conn, None);
+    let mut contributors = vec![StorageContributor {
+        name: "OCR text".to_string(),
+        size_bytes: ocr_bytes,
+        rows: ocr_rows,
+    }];
+    let queries: &[(&str, &str, &str)] = &[
+        (
+            "Media activity",
+            "activities",
+            "SELECT COALESCE(SUM(LENGTH(metadata)), 0), COUNT(*) FROM activities
+             WHERE metadata IS NOT NULL AND json_valid(CAST(metadata AS TEXT))
+               AND json_extract(CAST(metadata AS TEXT), '$.media_info') IS NOT NULL",
+        ),
+        (
+            "Chat messages",
+            "chat_messages",
+            "SELECT COALESCE(SUM(LENGTH(content) + COALESCE(LENGTH(agent_steps), 0) + COALESCE(LENGTH(activities), 0) + COALESCE(LENGTH(actions), 0)), 0), COUNT(*)
+             FROM chat_messages",
+        ),
+        (
+            "File previews",
+            "code_file_events",
+            "SELECT COALESCE(SUM(LENGTH(content_preview)), 0), COUNT(content_preview) FROM code_file_events",
+        ),
+        (
+            "Clipboard",
+            "clipboard_events",
+            "SELECT COALESCE(SUM(LENGTH(content)), 0), COUNT(*) FROM clipboard_events",
+        ),
+    ];
+    for (name, table, sql) in queries {
+        if !table_exists(conn, table) {
+            continue;
+        }
+        let (size_bytes, rows) = conn
+            .query_row(sql, [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap_or((0, 0));
+        contributors.push(StorageContributor {
+            name: name.to_string(),
+            size_bytes,
+            rows,
+        });
+    }
+    contributors
+}
+
+fn format_size(bytes: i64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Columns tried, in order, for a table's oldest and newest record.
+const RECORD_TIME_COLUMNS: &[&str] = &["start_time", "created_at", "detected_at", "captured_at", "timestamp", "updated_at"];
+
+/// Rows and bytes per table. Sizes come from the `dbstat` virtual table (pages
+/// of the table and its indexes); where SQLite was built without it, they are
+/// estimated from the summed length of every column.
+fn table_storage(conn: &rusqlite::Connection) -> Result<(Vec<TableStorage>, String), String> {
+    let names: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+
+    let page_sizes: Option<std::collections::HashMap<String, i64>> = conn
+        .prepare(
+            "SELECT m.tbl_name, SUM(d.pgsize) FROM dbstat d
+             JOIN sqlite_master m ON m.name = d.name
+             GROUP BY m.tbl_name",
+        )
+        .and_then(|mut stmt| {
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+            rows.collect::<Result<_, _>>()
+        })
+        .ok();
+
+    let mut tables = Vec::with_capacity(names.len());
+    for name in names {
+        let columns = table_columns(conn, &name);
+        let row_count: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", name), [], |row| row.get(0))
+            .unwrap_or(0);
+        let size_bytes = match &page_sizes {
+            Some(sizes) => sizes.get(&name).copied().unwrap_or(0),
+            None => estimated_table_size(conn, &name, &columns),
+        };
+        let (oldest_record, newest_record) = match RECORD_TIME_COLUMNS.iter().find(|c| columns.iter().any(|col| col == *c)) {
+            Some(column) => conn
+                .query_row(
+                    &format!("SELECT MIN(\"{0}\"), MAX(\"{0}\") FROM \"{1}\"", column, name),
+                    [],
+                    |row| Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, Option<i64>>(1)?)),
+                )
+                .unwrap_or((None, None)),
+            None => (None, None),
+        };
+        tables.push(TableStorage {
+            table: name,
+            row_count,
+            size_bytes,
+            oldest_record,
+            newest_record,
+        });
+    }
+    tables.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
+    let source = if page_sizes.is_some() { "dbstat" } else { "estimate" };
+    Ok((tables, source.to_string()))
+}
+
+/// Summed length of every column, for SQLite builds without `dbstat`.
+fn estimated_table_size(conn: &rusqlite::Connection, table: &str, columns: &[String]) -> i64 {
+    if columns.is_empty() {
+        return 0;
+    }
+    let lengths = columns
+        .iter()
+        .map(|c| format!("COALESCE(LENGTH(\"{}\"), 0)", c))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    conn.query_row(&format!("SELECT COALESCE(SUM({}), 0) FROM \"{}\"", lengths, table), [], |row| row.get(0))
+        .unwrap_or(0)
+}
+
+fn table_columns(conn: &rusqlite::Connection, table: &str) -> Vec<String> {
+    let Ok(mut stmt) = conn.prepare(&format!("PRAGMA table_info(\"{}\")", table)) else {
+        return Vec::new();
+    };
+    stmt.query_map([], |row| row.get::<_, String>(1))
+        .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        .unwrap_or_default()
+}
+
+fn storage_category(table: &str) -> &'static str {
+    match table {
+        "activities" | "activity_summaries" | "session_checkpoint" | "app_registry" | "process_events" => "Activities",
+        "code_file_events" => "File events",
+        "chat_sessions" | "chat_messages" => "Chat",
+        "clipboard_events" => "Clipboard",
+        "query_cache" | "dashboard_snapshots" | "ai_model_usage" | "llm_usage" => "Caches",
+        t if t.contains("fts") => "OCR search index",
+        _ => "Other",
+    }
+}
+
+/// Group table sizes by kind of data. OCR text is split out of the activity
+/// rows it is stored in; free pages left by deletes show as reclaimable.
+fn storage_categories(
+    tables: &[TableStorage],
+    ocr_text_bytes: i64,
+    thumbnails_bytes: i64,
+    icons_bytes: i64,
+    free_bytes: i64,
+) -> Vec<StorageCategoryUsage> {
+    let mut by_category: Vec<StorageCategoryUsage> = Vec::new();
+    let mut add = |category: &str, bytes: i64| {
+        if bytes <= 0 {
+            return;
+        }
+        match by_category.iter_mut().find(|c| c.category == category) {
+            Some(existing) => existing.size_bytes += bytes,
+            None => by_category.push(StorageCategoryUsage {
+                category: category.to_string(),
+                size_bytes: bytes,
+            }),
+        }
+    };
+
+    let activities_bytes = tables.iter().find(|t| t.table == "activities").map(|t| t.size_bytes).unwrap_or(0);
+    let ocr_bytes = ocr_text_bytes.min(activities_bytes);
+    add("OCR text", ocr_bytes);
+    for table in tables {
+        let bytes = if table.table == "activities" { table.size_bytes - ocr_bytes } else { table.size_bytes };
+        add(storage_category(&table.table), bytes);
+    }
+    add("Thumbnails", thumbnails_bytes);
+    add("App icons", icons_bytes);
+    add("Free (reclaimable)", free_bytes);
+
+    by_category.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
+    by_category
+}
+
+fn dir_size(dir: &std::path::Path) -> i64 {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len() as i64)
+        .sum()
+}
+
 #[tauri::command]
 pub async fn cleanup_old_data(
     app_handle: AppHandle,
     retention_days: i32,
 ) -> Result<i64, String> {
     let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     let cutoff = chrono::Utc::now().timestamp() - (retention_days as i64 * 86400);
     
@@ -80,18 +519,431 @@ pub async fn cleanup_old_data(
         "DELETE FROM activities WHERE start_time < ?1",
         [&cutoff],
     ).map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM clipboard_events WHERE captured_at < ?1", [&cutoff])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM process_events WHERE end_time < ?1", [&cutoff])
+        .map_err(|e| e.to_string())?;
+
+    cleanup_old_thumbnails(&data_dir, cutoff);
     
     Ok(deleted as i64)
 }
 
+/// Thumbnails live in `thumbnails/<YYYY-MM-DD>/`, so expired days are removed whole.
+fn cleanup_old_thumbnails(data_dir: &std::path::Path, cutoff: i64) {
+    let root = data_dir.join(crate::services::screen_capture::THUMBNAIL_DIR);
+    let Ok(entries) = std::fs::read_dir(&root) else { return; };
+    let Some(cutoff_day) = chrono::DateTime::from_timestamp(cutoff, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local).date_naive())
+    else {
+        return;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(day) = chrono::NaiveDate::parse_from_str(&name, "%Y-%m-%d") else { continue; };
+        if day < cutoff_day {
+            if let Err(e) = std::fs::remove_dir_all(entry.path()) {
+                println!("[Storage] Failed to remove thumbnails for {}: {}", name, e);
+            }
+        }
+    }
+}
+
+/// Delete activities by time range, app and/or category, with the clipboard
+/// events, OCR index rows and (optionally) file events from the same window.
+/// Rollups for touched days are rebuilt and their dashboard snapshots dropped
+/// so they regenerate. `dry_run` performs the same deletes and rolls them
+/// back, so its counts match a real run exactly.
+#[tauri::command]
+pub async fn delete_activities(
+    app_handle: AppHandle,
+    filter: ActivityDeleteFilter,
+) -> Result<ActivityDeletion, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let mut conn = crate::database::open(&app_handle)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let (result, thumbnails) = delete_in(&tx, filter)?;
+    if result.dry_run {
+        // Dropping the transaction rolls every delete back.
+        return Ok(result);
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    for relative in thumbnails {
+        let _ = std::fs::remove_file(data_dir.join(relative));
+    }
+    let activities_deleted = result
+        .rows
+        .iter()
+        .find(|r| r.table == "activities")
+        .map_or(0, |r| r.rows);
+    println!(
+        "[Storage] Bulk delete removed {} activities across {} day(s)",
+        activities_deleted,
+        result.affected_days.len()
+    );
+    Ok(result)
+}
+
+/// Deletes and logging of `delete_activities` inside `tx`. Returns the
+/// result and the thumbnails to remove once `tx` is committed.
+fn delete_in(
+    tx: &rusqlite::Transaction,
+    filter: ActivityDeleteFilter,
+) -> Result<(ActivityDeletion, Vec<String>), String> {
+    let app_patterns: Vec<String> = filter
+        .app_patterns
+        .iter()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let has_range = filter.start_time.is_some() || filter.end_time.is_some();
+    if !has_range && app_patterns.is_empty() && filter.category_ids.is_empty() {
+        return Err("Give a time range, app or category to delete".to_string());
+    }
+    let start = filter.start_time.unwrap_or(0);
+    let end = filter.end_time.unwrap_or(i64::MAX);
+    if start >= end {
+        return Err("start_time must be before end_time".to_string());
+    }
+
+    // Shared WHERE for activities and clipboard events: ?1 = end, ?2 = start, then app patterns.
+    let mut params: Vec<SqlValue> = vec![SqlValue::Integer(end), SqlValue::Integer(start)];
+    let mut app_clause = String::new();
+    if !app_patterns.is_empty() {
+        let conditions: Vec<String> = app_patterns
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("LOWER(app_name) LIKE ?{} ESCAPE '\\'", i + 3))
+            .collect();
+        app_clause = format!(" AND ({})", conditions.join(" OR "));
+        params.extend(app_patterns.iter().map(|p| SqlValue::Text(like_pattern(p))));
+    }
+    let category_clause = if filter.category_ids.is_empty() {
+        String::new()
+    } else {
+        let ids: Vec<String> = filter.category_ids.iter().map(|id| id.to_string()).collect();
+        format!(" AND category_id IN ({})", ids.join(","))
+    };
+    let activity_where = format!(
+        "start_time < ?1 AND end_time > ?2{}{}",
+        app_clause, category_clause
+    );
+
+    let matched = select_activities(tx, &activity_where, rusqlite::params_from_iter(params.iter()))?;
+
+    let mut rows = Vec::new();
+    let mut record = |table: &str, action: &str, count: usize| {
+        rows.push(PurgedTableRows {
+            table: table.to_string(),
+            action: action.to_string(),
+            rows: count as i64,
+        });
+    };
+    let mut touched: Vec<i64> = matched.iter().map(|(_, ts, _)| *ts).collect();
+    let thumbnails: Vec<String> = matched
+        .iter()
+        .filter_map(|(_, _, meta)| meta.as_ref().and_then(|m| m.thumbnail_path.clone()))
+        .collect();
+    let media_sessions = matched
+        .iter()
+        .filter(|(_, _, meta)| meta.as_ref().is_some_and(|m| m.media_info.is_some()))
+        .count();
+
+    if table_exists(tx, "activities_fts") {
+        let mut removed = 0;
+        for (id, _, _) in &matched {
+            removed += tx
+                .execute("DELETE FROM activities_fts WHERE rowid = ?1", [id])
+                .map_err(|e| e.to_string())?;
+        }
+        record("activities_fts", "deleted", removed);
+    }
+    let activities_deleted = tx
+        .execute(
+            &format!("DELETE FROM activities WHERE {}", activity_where),
+            rusqlite::params_from_iter(params.iter()),
+        )
+        .map_err(|e| e.to_string())?;
+    record("activities", "deleted", activities_deleted);
+    record("activities.media_info", "deleted", media_sessions);
+
+    // Clipboard rows have no category, so a category filter leaves them alone.
+    if filter.category_ids.is_empty() {
+        let deleted = tx
+            .execute(
+                &format!(
+                    "DELETE FROM clipboard_events WHERE captured_at < ?1 AND captured_at >= ?2{}",
+                    app_clause
+                ),
+                rusqlite::params_from_iter(params.iter()),
+            )
+            .map_err(|e| e.to_string())?;
+        record("clipboard_events", "deleted", deleted);
+    }
+
+    if filter.include_file_events && has_range {
+        let mut stmt = tx
+            .prepare("SELECT detected_at FROM code_file_events WHERE detected_at >= ?1 AND detected_at < ?2")
+            .map_err(|e| e.to_string())?;
+        let event_times = stmt
+            .query_map([start, end], |row| row.get::<_, i64>(0))
+            .map_err(|e| e.to_string())?;
+        touched.extend(event_times.filter_map(|r| r.ok()));
+        drop(stmt);
+        let deleted = tx
+            .execute(
+                "DELETE FROM code_file_events WHERE detected_at >= ?1 AND detected_at < ?2",
+                [start, end],
+            )
+            .map_err(|e| e.to_string())?;
+        record("code_file_events", "deleted", deleted);
+    }
+
+    let days: BTreeSet<chrono::NaiveDate> = touched
+        .iter()
+        .filter_map(|ts| {
+            chrono::DateTime::from_timestamp(*ts, 0)
+                .map(|dt| dt.with_timezone(&crate::utils::time::offset_at(*ts)).date_naive())
+        })
+        .collect();
+
+    let mut rebuilt = 0;
+    let mut snapshots = 0;
+    for day in &days {
+        let bounds = crate::utils::time::day_bounds(Some(tx), *day);
+        rebuilt += rebuild_day_rollup(tx, bounds.start_ts, bounds.end_ts)?;
+        snapshots += tx
+            .execute("DELETE FROM dashboard_snapshots WHERE date_key = ?1", [&bounds.date_key])
+            .map_err(|e| e.to_string())?;
+    }
+    record("activity_summaries", "rebuilt", rebuilt);
+    record("dashboard_snapshots", "deleted", snapshots);
+
+    let mut result = ActivityDeletion {
+        dry_run: filter.dry_run,
+        rows,
+        affected_days: days.iter().map(|d| d.format("%Y-%m-%d").to_string()).collect(),
+        log_id: None,
+    };
+    if filter.dry_run {
+        return Ok((result, thumbnails));
+    }
+
+    let logged_filter = ActivityDeleteFilter {
+        app_patterns,
+        dry_run: false,
+        ..filter
+    };
+    tx.execute(
+        "INSERT INTO deletion_log (filter_json, row_counts_json, deleted_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![
+            serde_json::to_string(&logged_filter).map_err(|e| e.to_string())?,
+            serde_json::to_string(&result.rows).map_err(|e| e.to_string())?,
+            chrono::Utc::now().timestamp(),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    result.log_id = Some(tx.last_insert_rowid());
+    Ok((result, thumbnails))
+}
+
+const DEFAULT_PURGE_RECENT_MINUTES: u32 = 5;
+const MAX_PURGE_RECENT_MINUTES: u32 = 60;
+
+/// "Undo last capture": forget everything recorded in the last `minutes`
+/// (default 5, at most 60). Works regardless of Incognito. Chat messages and
+/// manual entries are never touched.
+#[tauri::command]
+pub async fn purge_recent_data(
+    app_handle: AppHandle,
+    minutes: Option<u32>,
+) -> Result<RecentDataPurge, String> {
+    tokio::task::spawn_blocking(move || purge_recent(&app_handle, minutes.unwrap_or(DEFAULT_PURGE_RECENT_MINUTES)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Blocking part of `purge_recent_data`, shared with the tray item. Activities
+/// that started inside the window are deleted; one that started before it and
+/// ran into it keeps its row but loses its OCR text and thumbnail.
+pub fn purge_recent(app_handle: &AppHandle, minutes: u32) -> Result<RecentDataPurge, String> {
+    let minutes = minutes.clamp(1, MAX_PURGE_RECENT_MINUTES) as i64;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let since_ms = now_ms - minutes * 60_000;
+    let start = since_ms / 1000;
+
+    // In-memory state first, so nothing from the window is written after the purge.
+    crate::services::screen_capture::clear_latest_screen_text();
+    crate::services::activity_tracker::forget_since(since_ms);
+    crate::services::activity_tracker::flush_pending();
+
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let mut conn = crate::database::open(app_handle)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let (rows, thumbnails) = purge_rows_since(&tx, since_ms, now_ms / 1000)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    for relative in thumbnails {
+        let _ = std::fs::remove_file(data_dir.join(relative));
+    }
+    let count = |table: &str| rows.iter().find(|r| r.table == table).map(|r| r.rows).unwrap_or(0);
+    println!(
+        "[Storage] Forgot the last {} minute(s): {} activities deleted, {} redacted",
+        minutes,
+        count("activities"),
+        count("activities.screen_text")
+    );
+    Ok(RecentDataPurge {
+        start_time: start,
+        end_time: now_ms / 1000,
+        rows,
+    })
+}
+
+/// `(id, start_time, metadata)` for the activities matching `where_sql`.
+/// Media sessions are activity rows with SMTC info in their metadata, so
+/// callers count them from here rather than from a table of their own.
+fn select_activities<P: rusqlite::Params>(
+    tx: &rusqlite::Transaction,
+    where_sql: &str,
+    params: P,
+) -> Result<Vec<(i64, i64, Option<ActivityMetadata>)>, String> {
+    let mut stmt = tx
+        .prepare(&format!("SELECT id, start_time, metadata FROM activities WHERE {}", where_sql))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params, |row| {
+            let blob: Option<Vec<u8>> = row.get(2)?;
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                blob.and_then(|b| serde_json::from_slice::<ActivityMetadata>(&b).ok()),
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Database part of `purge_recent`: rows from `since_ms` up to `now`, with
+/// per-table counts and the thumbnails left to delete once committed.
+fn purge_rows_since(
+    tx: &rusqlite::Transaction,
+    since_ms: i64,
+    now: i64,
+) -> Result<(Vec<PurgedTableRows>, Vec<String>), String> {
+    let start = since_ms / 1000;
+
+    let matched = select_activities(tx, "end_time > ?1", [start])?;
+
+    let mut rows = Vec::new();
+    let mut record = |table: &str, action: &str, count: usize| {
+        rows.push(PurgedTableRows {
+            table: table.to_string(),
+            action: action.to_string(),
+            rows: count as i64,
+        });
+    };
+    let has_fts = table_exists(tx, "activities_fts");
+    let mut thumbnails = Vec::new();
+    let mut deleted = 0;
+    let mut redacted = 0;
+    let mut media_sessions = 0;
+    let mut fts_removed = 0;
+    for (id, start_time, metadata) in matched {
+        if has_fts {
+            fts_removed += tx
+                .execute("DELETE FROM activities_fts WHERE rowid = ?1", [id])
+                .map_err(|e| e.to_string())?;
+        }
+        let mut metadata = metadata.unwrap_or_default();
+        thumbnails.extend(metadata.thumbnail_path.clone());
+        if start_time >= start {
+            media_sessions += usize::from(metadata.media_info.is_some());
+            deleted += tx
+                .execute("DELETE FROM activities WHERE id = ?1", [id])
+                .map_err(|e| e.to_string())?;
+        } else if metadata.screen_text.is_some() || metadata.thumbnail_path.is_some() {
+            metadata.clear_screen_capture();
+            let blob = serde_json::to_vec(&metadata).map_err(|e| e.to_string())?;
+            redacted += tx
+                .execute("UPDATE activities SET metadata = ?1 WHERE id = ?2", rusqlite::params![blob, id])
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    record("activities", "deleted", deleted);
+    record("activities.screen_text", "redacted", redacted);
+    record("activities.media_info", "deleted", media_sessions);
+    if has_fts {
+        record("activities_fts", "deleted", fts_removed);
+    }
+
+    if table_exists(tx, "clipboard_events") {
+        let removed = tx
+            .execute("DELETE FROM clipboard_events WHERE captured_at >= ?1", [start])
+            .map_err(|e| e.to_string())?;
+        record("clipboard_events", "deleted", removed);
+    }
+    let checkpoints = tx
+        .execute("DELETE FROM session_checkpoint WHERE start_ms >= ?1", [since_ms])
+        .map_err(|e| e.to_string())?;
+    record("session_checkpoint", "deleted", checkpoints);
+
+    // The window spans at most two local days.
+    let days: BTreeSet<chrono::NaiveDate> = [start, now]
+        .iter()
+        .filter_map(|ts| {
+            chrono::DateTime::from_timestamp(*ts, 0)
+                .map(|dt| dt.with_timezone(&crate::utils::time::offset_at(*ts)).date_naive())
+        })
+        .collect();
+    if deleted > 0 {
+        let mut rebuilt = 0;
+        let mut snapshots = 0;
+        for day in &days {
+            let bounds = crate::utils::time::day_bounds(Some(tx), *day);
+            rebuilt += rebuild_day_rollup(tx, bounds.start_ts, bounds.end_ts)?;
+            snapshots += tx
+                .execute("DELETE FROM dashboard_snapshots WHERE date_key = ?1", [&bounds.date_key])
+                .map_err(|e| e.to_string())?;
+        }
+        record("activity_summaries", "rebuilt", rebuilt);
+        record("dashboard_snapshots", "deleted", snapshots);
+    }
+    Ok((rows, thumbnails))
+}
+
+/// Recompute `activity_summaries` (hour x category) for one local day from the
+/// remaining activities. Days that never had rollups are left without them.
+fn rebuild_day_rollup(conn: &rusqlite::Connection, day_start: i64, day_end: i64) -> Result<usize, String> {
+    let existing = conn
+        .execute(
+            "DELETE FROM activity_summaries WHERE date >= ?1 AND date < ?2",
+            [day_start, day_end],
+        )
+        .map_err(|e| e.to_string())?;
+    if existing == 0 {
+        return Ok(0);
+    }
+    conn.execute(
+        "INSERT INTO activity_summaries (date, hour, category_id, total_duration, event_count)
+         SELECT ?1, (start_time - ?1) / 3600, category_id, SUM(duration_seconds), COUNT(*)
+         FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2
+         GROUP BY (start_time - ?1) / 3600, category_id",
+        [day_start, day_end],
+    )
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn export_data(
     app_handle: AppHandle,
 ) -> Result<String, String> {
     let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     // Export activities
     let activities = crate::database::queries::get_activities(&conn, 0, i64::MAX, None)
@@ -114,3 +966,1117 @@ pub async fn export_data(
     
     Ok(export_path.to_string_lossy().to_string())
 }
+
+/// Same-app rows this close together are exported as one calendar event.
+const ICS_MERGE_GAP_SECS: i64 = 120;
+
+/// Export the timeline between `start_ts` and `end_ts` as an .ics calendar, one
+/// event per merged app session. Sessions shorter than `min_duration_secs`
+/// (default 5 minutes) are left out. Returns the file path.
+#[tauri::command]
+pub async fn export_timeline_ics(
+    app_handle: AppHandle,
+    start_ts: i64,
+    end_ts: i64,
+    min_duration_secs: Option<i64>,
+) -> Result<String, String> {
+    if end_ts <= start_ts {
+        return Err("End of the export range must be after its start".to_string());
+    }
+    let min_duration = min_duration_secs.unwrap_or(300).max(0);
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
+
+    let sessions = crate::database::queries::get_merged_sessions(&conn, start_ts, end_ts, ICS_MERGE_GAP_SECS)
+        .map_err(|e| e.to_string())?;
+
+    let events: Vec<IcsEvent> = sessions
+        .iter()
+        .filter(|s| s.duration_seconds >= min_duration)
+        .map(|s| {
+            // Private sessions keep their slot on the calendar but not their title.
+            let summary = if s.private || s.window_title.trim().is_empty() {
+                s.app_name.clone()
+            } else {
+                format!("{} — {}", s.app_name, s.window_title.trim())
+            };
+            IcsEvent {
+                uid: format!("activity-{}@intentflow", s.first_activity_id),
+                start_ts: s.start_time,
+                end_ts: s.end_time,
+                summary,
+                description: format!(
+                    "{} min active across {} window(s)",
+                    s.duration_seconds / 60,
+                    s.activity_count
+                ),
+                categories: vec![crate::utils::categories::name_for(s.category_id as i64)],
+            }
+        })
+        .collect();
+
+    let export_path = data_dir.join("exports").join(format!(
+        "intentflow_timeline_{}.ics",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S")
+    ));
+    std::fs::create_dir_all(export_path.parent().unwrap()).map_err(|e| e.to_string())?;
+    std::fs::write(&export_path, render_calendar("IntentFlow timeline", &events))
+        .map_err(|e| e.to_string())?;
+
+    println!(
+        "[Storage] Exported {} of {} timeline sessions to {}",
+        events.len(),
+        sessions.len(),
+        export_path.display()
+    );
+    Ok(export_path.to_string_lossy().to_string())
+}
+
+/// Delete all recorded clipboard history. Returns the number of removed entries.
+#[tauri::command]
+pub async fn purge_clipboard_history(
+    app_handle: AppHandle,
+) -> Result<usize, String> {
+    let conn = crate::database::open(&app_handle)?;
+
+    let deleted = conn
+        .execute("DELETE FROM clipboard_events", [])
+        .map_err(|e| e.to_string())?;
+    println!("[Storage] Purged {} clipboard entries", deleted);
+    Ok(deleted)
+}
+
+/// Fill the database with a scripted day of fictional activity ending now, for
+/// onboarding previews and manual testing. Refuses to touch a database that
+/// already has tracked activity unless `force` is set.
+#[tauri::command]
+pub async fn seed_sample_data(
+    app_handle: AppHandle,
+    force: Option<bool>,
+) -> Result<SampleDataSummary, String> {
+    let conn = crate::database::open(&app_handle)?;
+
+    let existing: i64 = conn
+        .query_row("SELECT COUNT(*) FROM activities", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if existing > 0 && !force.unwrap_or(false) {
+        return Err(format!(
+            "Database already has {} activities; pass force to add sample data anyway",
+            existing
+        ));
+    }
+
+    let summary = crate::database::sample_data::insert_sample_day(&conn, chrono::Utc::now().timestamp())
+        .map_err(|e| e.to_string())?;
+    crate::services::app_alerts::reload(&conn);
+    println!(
+        "[Storage] Seeded sample data: {} activities, {} file events",
+        summary.activities, summary.file_events
+    );
+    Ok(summary)
+}
+
+/// Tables of captured or derived data whose rows are deleted outright when any
+/// column mentions the keyword. User-authored configuration (categories, rules,
+/// goals, manual entries, settings) is left alone.
+const PURGED_LOG_TABLES: &[&str] = &[
+    "session_checkpoint",
+    "clipboard_events",
+    "notification_log",
+    "intent_logs",
+    "intent_executions",
+    "process_events",
+    "patterns",
+    "activity_summaries",
+    "app_registry",
+    "deletion_log",
+    "llm_cache",
+];
+
+/// Report where a keyword appears in stored data, or remove it with `purge`.
+/// Only counts are returned so the audit itself doesn't re-expose the content.
+#[tauri::command]
+pub async fn audit_keyword(
+    app_handle: AppHandle,
+    keyword: String,
+    purge: Option<bool>,
+) -> Result<KeywordAudit, String> {
+    let keyword = keyword.trim().to_lowercase();
+    if keyword.chars().count() < 2 {
+        return Err("Keyword must be at least 2 characters".to_string());
+    }
+    let purge = purge.unwrap_or(false);
+
+    let mut conn = crate::database::open(&app_handle)?;
+    // Counting and purging share one transaction; a plain audit just rolls it back.
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let audit = audit_in(&tx, &keyword, purge)?;
+    if !purge {
+        return Ok(audit);
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    println!(
+        "[Storage] Purged keyword from {} table(s)",
+        audit.purged_rows.iter().filter(|r| r.rows > 0).count()
+    );
+    Ok(audit)
+}
+
+fn audit_in(tx: &rusqlite::Transaction, keyword: &str, purge: bool) -> Result<KeywordAudit, String> {
+    let pattern = like_pattern(keyword);
+    let mut timestamps: Vec<i64> = Vec::new();
+
+    let title_matches: Vec<(i64, i64)> = query_id_time(
+        tx,
+        "SELECT id, start_time FROM activities WHERE LOWER(window_title) LIKE ?1 ESCAPE '\\'",
+        &pattern,
+    )?;
+    let app_matches: Vec<(i64, i64)> = query_id_time(
+        tx,
+        "SELECT id, start_time FROM activities WHERE LOWER(app_name) LIKE ?1 ESCAPE '\\'",
+        &pattern,
+    )?;
+    let (ocr_matches, ocr_match_mode) = find_ocr_matches(tx, keyword, &pattern)?;
+    let metadata_matches = find_metadata_matches(tx, keyword, &pattern)?;
+    for matches in [&title_matches, &app_matches, &ocr_matches, &metadata_matches] {
+        timestamps.extend(matches.iter().map(|(_, ts)| *ts));
+    }
+
+    let chat_sql = format!("FROM chat_messages WHERE {}", text_match_sql(tx, "chat_messages")?);
+    let session_sql = "FROM chat_sessions WHERE LOWER(title) LIKE ?1 ESCAPE '\\'";
+    let snapshot_sql = format!("FROM dashboard_snapshots WHERE {}", text_match_sql(tx, "dashboard_snapshots")?);
+    let code_sql = format!("FROM code_file_events WHERE {}", text_match_sql(tx, "code_file_events")?);
+    let cache_sql = format!("FROM query_cache WHERE {}", text_match_sql(tx, "query_cache")?);
+
+    let (chat_message_matches, chat_range) = count_with_range(tx, "created_at", &chat_sql, &pattern)?;
+    let (chat_session_matches, session_range) = count_with_range(tx, "updated_at", session_sql, &pattern)?;
+    let (dashboard_snapshot_matches, snapshot_range) = count_with_range(tx, "updated_at", &snapshot_sql, &pattern)?;
+    let (code_file_event_matches, code_range) = count_with_range(tx, "detected_at", &code_sql, &pattern)?;
+    let (query_cache_matches, _) = count_with_range(tx, "created_at", &cache_sql, &pattern)?;
+    for (min, max) in [chat_range, session_range, snapshot_range, code_range].into_iter().flatten() {
+        timestamps.push(min);
+        timestamps.push(max);
+    }
+
+    let mut other_matches = 0;
+    for table in PURGED_LOG_TABLES.iter().filter(|t| table_exists(tx, t)) {
+        let (count, _) = count_with_range(
+            tx,
+            "NULL",
+            &format!("FROM {} WHERE {}", table, text_match_sql(tx, table)?),
+            &pattern,
+        )?;
+        other_matches += count;
+    }
+
+    let mut audit = KeywordAudit {
+        keyword: keyword.to_string(),
+        activity_title_matches: title_matches.len() as i64,
+        activity_app_matches: app_matches.len() as i64,
+        activity_ocr_matches: ocr_matches.len() as i64,
+        activity_metadata_matches: metadata_matches.len() as i64,
+        chat_message_matches,
+        chat_session_matches,
+        dashboard_snapshot_matches,
+        code_file_event_matches,
+        query_cache_matches,
+        other_matches,
+        ocr_match_mode: ocr_match_mode.to_string(),
+        first_match_at: timestamps.iter().min().copied(),
+        last_match_at: timestamps.iter().max().copied(),
+        ..Default::default()
+    };
+
+    if !purge {
+        return Ok(audit);
+    }
+
+    let mut purged_rows = Vec::new();
+    let mut record = |table: &str, action: &str, rows: usize| {
+        purged_rows.push(PurgedTableRows {
+            table: table.to_string(),
+            action: action.to_string(),
+            rows: rows as i64,
+        });
+    };
+
+    // Activities keep their time so daily totals stay intact; only the text goes.
+    let mut redacted_titles = 0;
+    for (id, _) in &title_matches {
+        redacted_titles += tx
+            .execute(
+                "UPDATE activities SET window_title = '[redacted]' WHERE id = ?1",
+                [id],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    record("activities.window_title", "redacted", redacted_titles);
+
+    let redacted_hash = crate::models::ActivityEvent::app_hash("[redacted]") as i64;
+    let mut redacted_apps = 0;
+    for (id, _) in &app_matches {
+        redacted_apps += tx
+            .execute(
+                "UPDATE activities SET app_name = '[redacted]', app_hash = ?1 WHERE id = ?2",
+                rusqlite::params![redacted_hash, id],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    record("activities.app_name", "redacted", redacted_apps);
+
+    let ocr_ids: BTreeSet<i64> = ocr_matches.iter().map(|(id, _)| *id).collect();
+    let mut redacted_ocr = 0;
+    let mut redacted_metadata = 0;
+    let metadata_ids: BTreeSet<i64> = metadata_matches.iter().map(|(id, _)| *id).collect();
+    for id in ocr_ids.union(&metadata_ids) {
+        let blob: Option<Vec<u8>> = tx
+            .query_row("SELECT metadata FROM activities WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let updated = match blob.and_then(|b| serde_json::from_slice::<ActivityMetadata>(&b).ok()) {
+            Some(mut meta) => {
+                if ocr_ids.contains(id) {
+                    meta.screen_text = None;
+                    redacted_ocr += 1;
+                }
+                if scrub_metadata(&mut meta, keyword) {
+                    redacted_metadata += 1;
+                }
+                Some(serde_json::to_vec(&meta).map_err(|e| e.to_string())?)
+            }
+            // Unreadable metadata can't be scrubbed field by field.
+            None => {
+                redacted_metadata += 1;
+                None
+            }
+        };
+        tx.execute(
+            "UPDATE activities SET metadata = ?1 WHERE id = ?2",
+            rusqlite::params![updated, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    record("activities.screen_text", "redacted", redacted_ocr);
+    record("activities.metadata", "redacted", redacted_metadata);
+
+    let deleted = tx
+        .execute(&format!("DELETE {}", chat_sql), [&pattern])
+        .map_err(|e| e.to_string())?;
+    record("chat_messages", "deleted", deleted);
+
+    let redacted = tx
+        .execute(
+            &format!("UPDATE chat_sessions SET title = '[redacted]' WHERE id IN (SELECT id {})", session_sql),
+            [&pattern],
+        )
+        .map_err(|e| e.to_string())?;
+    record("chat_sessions.title", "redacted", redacted);
+
+    let deleted = tx
+        .execute(&format!("DELETE {}", snapshot_sql), [&pattern])
+        .map_err(|e| e.to_string())?;
+    record("dashboard_snapshots", "deleted", deleted);
+
+    // A matching preview is dropped; a matching path means the event itself goes.
+    let cleared = tx
+        .execute(
+            "UPDATE code_file_events SET content_preview = NULL WHERE LOWER(content_preview) LIKE ?1 ESCAPE '\\'",
+            [&pattern],
+        )
+        .map_err(|e| e.to_string())?;
+    record("code_file_events.content_preview", "redacted", cleared);
+    let deleted = tx
+        .execute(&format!("DELETE {}", code_sql), [&pattern])
+        .map_err(|e| e.to_string())?;
+    record("code_file_events", "deleted", deleted);
+
+    let deleted = tx
+        .execute(&format!("DELETE {}", cache_sql), [&pattern])
+        .map_err(|e| e.to_string())?;
+    record("query_cache", "deleted", deleted);
+
+    for table in PURGED_LOG_TABLES.iter().filter(|t| table_exists(tx, t)) {
+        let deleted = purge_text_columns(tx, table, &pattern)?;
+        record(table, "deleted", deleted);
+    }
+    optimize_ocr_index(tx);
+
+    audit.purged = true;
+    audit.purged_rows = purged_rows;
+    Ok(audit)
+}
+
+/// Lowercased `%keyword%` with LIKE wildcards escaped, so "%" can't match every row.
+fn like_pattern(keyword: &str) -> String {
+    let escaped = keyword
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn table_exists(conn: &rusqlite::Connection, name: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [name],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|n| n > 0)
+    .unwrap_or(false)
+}
+
+fn query_id_time(conn: &rusqlite::Connection, sql: &str, param: &str) -> Result<Vec<(i64, i64)>, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([param], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rows)
+}
+
+fn count_with_range(
+    conn: &rusqlite::Connection,
+    time_column: &str,
+    from_sql: &str,
+    pattern: &str,
+) -> Result<(i64, Option<(i64, i64)>), String> {
+    conn.query_row(
+        &format!("SELECT COUNT(*), MIN({0}), MAX({0}) {1}", time_column, from_sql),
+        [pattern],
+        |row| {
+            let count: i64 = row.get(0)?;
+            let min: Option<i64> = row.get(1)?;
+            let max: Option<i64> = row.get(2)?;
+            Ok((count, min.zip(max)))
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Activities whose OCR text contains the keyword. Uses the `activities_fts`
+/// index when present; otherwise prefilters the metadata JSON with LIKE and
+/// confirms against `screen_text` so URLs and media titles don't count.
+fn find_ocr_matches(
+    conn: &rusqlite::Connection,
+    keyword: &str,
+    pattern: &str,
+) -> Result<(Vec<(i64, i64)>, &'static str), String> {
+    if table_exists(conn, "activities_fts") {
+        let phrase = format!("\"{}\"", keyword.replace('"', "\"\""));
+        let matches = query_id_time(
+            conn,
+            "SELECT a.id, a.start_time FROM activities_fts f
+             JOIN activities a ON a.id = f.rowid
+             WHERE activities_fts MATCH ?1",
+            &phrase,
+        );
+        match matches {
+            Ok(rows) => return Ok((rows, "fts")),
+            Err(e) => println!("[Storage] OCR index lookup failed, falling back to LIKE: {}", e),
+        }
+    }
+
+    let matches = metadata_like_rows(conn, pattern)?
+        .into_iter()
+        .filter(|(_, _, meta)| {
+            meta.as_ref()
+                .and_then(|meta| meta.screen_text.as_ref())
+                .map(|text| text.to_lowercase().contains(keyword))
+                .unwrap_or(false)
+        })
+        .map(|(id, start_time, _)| (id, start_time))
+        .collect();
+    Ok((matches, "like"))
+}
+
+/// Activities whose metadata mentions the keyword outside the OCR text: URL,
+/// background windows, media and the like. Unreadable metadata that contains
+/// the keyword counts too.
+fn find_metadata_matches(
+    conn: &rusqlite::Connection,
+    keyword: &str,
+    pattern: &str,
+) -> Result<Vec<(i64, i64)>, String> {
+    Ok(metadata_like_rows(conn, pattern)?
+        .into_iter()
+        .filter(|(_, _, meta)| match meta {
+            Some(meta) => scrub_metadata(&mut meta.clone(), keyword),
+            None => true,
+        })
+        .map(|(id, start_time, _)| (id, start_time))
+        .collect())
+}
+
+/// Activities whose raw metadata text matches `pattern`, parsed where possible.
+fn metadata_like_rows(
+    conn: &rusqlite::Connection,
+    pattern: &str,
+) -> Result<Vec<(i64, i64, Option<ActivityMetadata>)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, start_time, metadata FROM activities
+             WHERE LOWER(CAST(metadata AS TEXT)) LIKE ?1 ESCAPE '\\'",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([pattern], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<Vec<u8>>>(2)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(|(id, start_time, blob)| {
+            let meta = blob.and_then(|b| serde_json::from_slice::<ActivityMetadata>(&b).ok());
+            (id, start_time, meta)
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// Drop every metadata field other than the OCR text that mentions the keyword.
+/// Returns whether anything was dropped.
+fn scrub_metadata(meta: &mut ActivityMetadata, keyword: &str) -> bool {
+    let mentions = |text: &str| text.to_lowercase().contains(keyword);
+    let mut changed = false;
+    for field in [&mut meta.url, &mut meta.thumbnail_path, &mut meta.ocr_language] {
+        if field.as_deref().is_some_and(mentions) {
+            *field = None;
+            changed = true;
+        }
+    }
+    if let Some(windows) = meta.background_windows.as_mut() {
+        let before = windows.len();
+        windows.retain(|w| !mentions(w));
+        changed |= windows.len() != before;
+    }
+    for change in meta.background_changes.iter_mut() {
+        let before = change.added.len() + change.removed.len();
+        change.added.retain(|w| !mentions(w));
+        change.removed.retain(|w| !mentions(w));
+        changed |= change.added.len() + change.removed.len() != before;
+    }
+    let before = meta.background_changes.len();
+    meta.background_changes
+        .retain(|change| !change.added.is_empty() || !change.removed.is_empty());
+    changed |= meta.background_changes.len() != before;
+    let media_mentions = meta.media_info.as_ref().is_some_and(|media| {
+        mentions(&media.title)
+            || mentions(&media.artist)
+            || mentions(&media.status)
+            || media.source_app.as_deref().is_some_and(mentions)
+    });
+    if media_mentions {
+        meta.media_info = None;
+        changed = true;
+    }
+    changed
+}
+
+/// `LOWER(col) LIKE ?1` over every column of `table`, joined with OR.
+fn text_match_sql(conn: &rusqlite::Connection, table: &str) -> Result<String, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| e.to_string())?;
+    let columns: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    if columns.is_empty() {
+        return Ok("0".to_string());
+    }
+    Ok(columns
+        .iter()
+        .map(|c| format!("LOWER(CAST(\"{}\" AS TEXT)) LIKE ?1 ESCAPE '\\'", c))
+        .collect::<Vec<_>>()
+        .join(" OR "))
+}
+
+/// Delete rows of a table where any column mentions the keyword.
+fn purge_text_columns(conn: &rusqlite::Connection, table: &str, pattern: &str) -> Result<usize, String> {
+    conn.execute(
+        &format!("DELETE FROM {} WHERE {}", table, text_match_sql(conn, table)?),
+        [pattern],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Merge the OCR index's segments so entries removed by a purge don't linger
+/// in older segments.
+fn optimize_ocr_index(conn: &rusqlite::Connection) {
+    if !table_exists(conn, "activities_fts") {
+        return;
+    }
+    if let Err(e) = conn.execute("INSERT INTO activities_fts (activities_fts) VALUES ('optimize')", []) {
+        println!("[Storage] OCR index optimize failed: {}", e);
+    }
+}
+
+/// Report constraint violations, orphaned rows and missing indexes/triggers
+/// for diagnostics. Read-only; the integrity migration does the repairs.
+#[tauri::command]
+pub async fn verify_data_integrity(
+    app_handle: AppHandle,
+) -> Result<DataIntegrityReport, String> {
+    let conn = crate::database::open(&app_handle)?;
+    crate::database::integrity::verify(&conn).map_err(|e| e.to_string())
+}
+
+/// Backfill the OCR full-text index from stored activities, creating it first
+/// when missing. Runs in chunks and reports `index://progress` after each;
+/// `cancel_index_rebuild` stops it between chunks. Safe to run again.
+#[tauri::command]
+pub async fn rebuild_indexes(
+    app_handle: AppHandle,
+) -> Result<IndexRebuildProgress, String> {
+    tokio::task::spawn_blocking(move || crate::services::ocr_index::rebuild(&app_handle))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Returns whether a rebuild was running.
+#[tauri::command]
+pub async fn cancel_index_rebuild() -> Result<bool, String> {
+    Ok(crate::services::ocr_index::cancel())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        audit_in, build_forecast, cumulative_slope, delete_in, estimated_table_size, purge_rows_since,
+        rebuild_day_rollup, table_columns, table_storage, STORAGE_LIMIT_BYTES,
+    };
+    use crate::models::{ActivityDeleteFilter, ActivityMetadata, BackgroundChange, DailyIngest, MediaInfo, StorageSettings};
+    use rusqlite::Connection;
+
+    const KEYWORD: &str = "zebra";
+
+    fn test_db() -> Connection {
+        let conn = crate::database::test_connection();
+        crate::services::ocr_index::ensure_index(&conn).unwrap();
+        conn
+    }
+
+    fn metadata() -> Vec<u8> {
+        let meta = ActivityMetadata {
+            screen_text: Some("notes about the Zebra project".to_string()),
+            url: Some("https://example.com/zebra".to_string()),
+            background_windows: Some(vec!["Zebra - Docs".to_string(), "Terminal".to_string()]),
+            background_changes: vec![BackgroundChange {
+                at: 10,
+                added: vec!["zebra.txt - Notepad".to_string()],
+                removed: Vec::new(),
+            }],
+            media_info: Some(MediaInfo {
+                title: "Zebra Song".to_string(),
+                artist: "Band".to_string(),
+                status: "Playing".to_string(),
+                source_app: None,
+            }),
+            ..Default::default()
+        };
+        serde_json::to_vec(&meta).unwrap()
+    }
+
+    fn seed(conn: &Connection) {
+        conn.execute_batch(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+             VALUES ('Zebra Client', 1, 'Inbox', 1, 100, 160, 60),
+                    ('Editor', 2, 'zebra plan.md', 1, 200, 260, 60),
+                    ('Editor', 2, 'clean', 1, 300, 360, 60);
+             INSERT INTO chat_sessions (id, title, created_at, updated_at) VALUES ('s1', 'About zebra', 1, 2);
+             INSERT INTO chat_messages (session_id, role, content, activities, created_at)
+                 VALUES ('s1', 'assistant', 'nothing here', '[\"Zebra Client\"]', 3);
+             INSERT INTO dashboard_snapshots (date_key, summary_json, updated_at) VALUES ('2026-01-01', '{\"top\":\"zebra\"}', 4);
+             INSERT INTO code_file_events (path, project_root, change_type, content_preview, detected_at)
+                 VALUES ('/src/main.rs', '/src', 'modified', 'let zebra = 1;', 5),
+                        ('/src/zebra.rs', '/src', 'created', NULL, 6);
+             INSERT INTO query_cache (query_hash, query_text, result, created_at, expires_at)
+                 VALUES ('h', 'when did I look at zebra', 'x', 7, 8);
+             INSERT INTO session_checkpoint (id, app_name, window_title, category_id, start_ms, last_seen_ms)
+                 VALUES (1, 'Browser', 'Zebra facts', 1, 9000, 9500);
+             INSERT INTO clipboard_events (content, content_hash, app_name, window_title, captured_at)
+                 VALUES ('copied zebra', 1, 'Browser', 'x', 10);
+             INSERT INTO notification_log (kind, title, body, created_at)
+                 VALUES ('app_alert', 'Zebra Client', 'over limit', 11);",
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE activities SET metadata = ?1 WHERE window_title = 'clean'",
+            [metadata()],
+        )
+        .unwrap();
+    }
+
+    /// Rows in any column of any table that still mention the keyword.
+    fn traces(conn: &Connection) -> Vec<String> {
+        let tables: Vec<String> = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table'")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let mut found = Vec::new();
+        for table in tables {
+            let columns: Vec<String> = conn
+                .prepare(&format!("PRAGMA table_info(\"{}\")", table))
+                .unwrap()
+                .query_map([], |row| row.get(1))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            for column in columns {
+                let count: i64 = conn
+                    .query_row(
+                        &format!(
+                            "SELECT COUNT(*) FROM \"{}\" WHERE LOWER(CAST(\"{}\" AS TEXT)) LIKE ?1",
+                            table, column
+                        ),
+                        [format!("%{}%", KEYWORD)],
+                        |row| row.get(0),
+                    )
+                    .unwrap();
+                if count > 0 {
+                    found.push(format!("{}.{}", table, column));
+                }
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn audit_counts_without_changing_anything() {
+        let mut conn = test_db();
+        seed(&conn);
+        let tx = conn.transaction().unwrap();
+        let audit = audit_in(&tx, KEYWORD, false).unwrap();
+        drop(tx);
+
+        assert_eq!(audit.activity_title_matches, 1);
+        assert_eq!(audit.activity_app_matches, 1);
+        assert_eq!(audit.activity_ocr_matches, 1);
+        assert_eq!(audit.ocr_match_mode, "fts");
+        assert_eq!(audit.activity_metadata_matches, 1);
+        assert_eq!(audit.chat_message_matches, 1);
+        assert_eq!(audit.chat_session_matches, 1);
+        assert_eq!(audit.dashboard_snapshot_matches, 1);
+        assert_eq!(audit.code_file_event_matches, 2);
+        assert_eq!(audit.query_cache_matches, 1);
+        assert_eq!(audit.other_matches, 3);
+        assert_eq!(audit.first_match_at, Some(2));
+        assert_eq!(audit.last_match_at, Some(300));
+        assert!(!traces(&conn).is_empty());
+    }
+
+    #[test]
+    fn purge_leaves_no_trace_in_any_column() {
+        let mut conn = test_db();
+        seed(&conn);
+        let tx = conn.transaction().unwrap();
+        let audit = audit_in(&tx, KEYWORD, true).unwrap();
+        tx.commit().unwrap();
+
+        assert!(audit.purged);
+        assert_eq!(traces(&conn), Vec::<String>::new());
+        // Activities are redacted, not deleted, so time totals survive.
+        let activities: i64 = conn
+            .query_row("SELECT COUNT(*) FROM activities", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(activities, 3);
+        let blob: Vec<u8> = conn
+            .query_row("SELECT metadata FROM activities WHERE window_title = 'clean'", [], |row| row.get(0))
+            .unwrap();
+        let meta: ActivityMetadata = serde_json::from_slice(&blob).unwrap();
+        assert_eq!(meta.background_windows, Some(vec!["Terminal".to_string()]));
+        assert!(meta.screen_text.is_none() && meta.url.is_none() && meta.media_info.is_none());
+        assert!(meta.background_changes.is_empty());
+    }
+
+    #[test]
+    fn recent_purge_deletes_from_the_start_and_redacts_what_ran_into_it() {
+        let mut conn = test_db();
+        let since_ms = 1_000_000_500;
+        let since = since_ms / 1000;
+        let insert = |title: &str, start: i64, end: i64| {
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+                 VALUES ('App', 1, ?1, 1, ?2, ?3, ?3 - ?2, ?4)",
+                rusqlite::params![title, start, end, metadata()],
+            )
+            .unwrap();
+        };
+        insert("ended at the start", since - 100, since);
+        insert("ran into the window", since - 100, since + 10);
+        insert("started at the start", since, since + 20);
+        insert("started inside", since + 50, since + 60);
+        conn.execute_batch(&format!(
+            "INSERT INTO clipboard_events (content, content_hash, captured_at) VALUES ('before', 1, {before}), ('at', 2, {since});
+             INSERT INTO session_checkpoint (id, app_name, window_title, category_id, start_ms, last_seen_ms)
+                 VALUES (1, 'App', 'open', 1, {since_ms}, {since_ms});",
+            before = since - 1,
+            since = since,
+            since_ms = since_ms,
+        ))
+        .unwrap();
+
+        let tx = conn.transaction().unwrap();
+        let (rows, _) = purge_rows_since(&tx, since_ms, since + 100).unwrap();
+        tx.commit().unwrap();
+
+        let count = |table: &str| rows.iter().find(|r| r.table == table).map(|r| r.rows);
+        assert_eq!(count("activities"), Some(2));
+        assert_eq!(count("activities.screen_text"), Some(1));
+        assert_eq!(count("clipboard_events"), Some(1));
+        assert_eq!(count("session_checkpoint"), Some(1));
+
+        let left: Vec<(String, Option<String>)> = conn
+            .prepare("SELECT window_title, metadata FROM activities ORDER BY id")
+            .unwrap()
+            .query_map([], |row| {
+                let blob: Vec<u8> = row.get(1)?;
+                let meta: ActivityMetadata = serde_json::from_slice(&blob).unwrap();
+                Ok((row.get(0)?, meta.screen_text))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            left,
+            vec![
+                ("ended at the start".to_string(), Some("notes about the Zebra project".to_string())),
+                ("ran into the window".to_string(), None),
+            ]
+        );
+        let clipboard: String = conn
+            .query_row("SELECT content FROM clipboard_events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(clipboard, "before");
+    }
+
+    /// Noon UTC on 2026-01-01; the second day starts a day later.
+    const NOON: i64 = 1_767_268_800;
+    const HOUR: i64 = 3_600;
+
+    fn local_day(ts: i64) -> chrono::NaiveDate {
+        chrono::DateTime::from_timestamp(ts, 0)
+            .unwrap()
+            .with_timezone(&crate::utils::time::offset_at(ts))
+            .date_naive()
+    }
+
+    /// Two days of Discord and Editor time with rollups and dashboard snapshots.
+    fn seed_days(conn: &Connection) {
+        let insert = |app: &str, category: i64, start: i64, seconds: i64| {
+            let meta = serde_json::to_vec(&ActivityMetadata {
+                screen_text: Some(format!("{} window text", app)),
+                ..Default::default()
+            })
+            .unwrap();
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+                 VALUES (?1, 1, 'window', ?2, ?3, ?3 + ?4, ?4, ?5)",
+                rusqlite::params![app, category, start, seconds, meta],
+            )
+            .unwrap();
+        };
+        for day in 0..2 {
+            let noon = NOON + day * 86_400;
+            insert("Discord", 3, noon - 2 * HOUR, 1_200);
+            insert("Editor", 1, noon - HOUR, 1_800);
+            insert("Editor", 1, noon + 2 * HOUR + 600, 900);
+            insert("Discord", 3, noon + 3 * HOUR, 600);
+            conn.execute(
+                "INSERT INTO clipboard_events (content, content_hash, app_name, window_title, captured_at)
+                 VALUES ('copied', ?1, 'Discord', 'x', ?2)",
+                rusqlite::params![day, noon + 3 * HOUR + 60],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO code_file_events (path, project_root, change_type, detected_at)
+                 VALUES ('/src/main.rs', '/src', 'modified', ?1)",
+                [noon + 2 * HOUR + 700],
+            )
+            .unwrap();
+
+            let bounds = crate::utils::time::day_bounds(Some(conn), local_day(noon));
+            conn.execute(
+                "INSERT INTO activity_summaries (date, hour, category_id, total_duration, event_count) VALUES (?1, 0, 7, 0, 0)",
+                [bounds.start_ts],
+            )
+            .unwrap();
+            rebuild_day_rollup(conn, bounds.start_ts, bounds.end_ts).unwrap();
+            conn.execute(
+                "INSERT INTO dashboard_snapshots (date_key, summary_json, updated_at) VALUES (?1, '{}', 0)",
+                [&bounds.date_key],
+            )
+            .unwrap();
+        }
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    fn rows_of(deletion: &crate::models::ActivityDeletion) -> Vec<(String, String, i64)> {
+        deletion
+            .rows
+            .iter()
+            .map(|r| (r.table.clone(), r.action.clone(), r.rows))
+            .collect()
+    }
+
+    #[test]
+    fn dry_run_counts_match_the_real_deletion() {
+        let mut conn = test_db();
+        seed_days(&conn);
+        let filter = ActivityDeleteFilter {
+            start_time: Some(NOON + 2 * HOUR),
+            end_time: Some(NOON + 4 * HOUR),
+            include_file_events: true,
+            ..Default::default()
+        };
+
+        let tx = conn.transaction().unwrap();
+        let (dry, _) = delete_in(
+            &tx,
+            ActivityDeleteFilter {
+                dry_run: true,
+                ..filter.clone()
+            },
+        )
+        .unwrap();
+        drop(tx);
+        assert!(dry.dry_run && dry.log_id.is_none());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities"), 8);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities_fts"), 8);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM deletion_log"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM dashboard_snapshots"), 2);
+
+        let tx = conn.transaction().unwrap();
+        let (real, _) = delete_in(&tx, filter).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(rows_of(&real), rows_of(&dry));
+        assert_eq!(real.affected_days, dry.affected_days);
+        assert_eq!(real.affected_days, vec![local_day(NOON).format("%Y-%m-%d").to_string()]);
+        let deleted = |table: &str| rows_of(&real).into_iter().find(|r| r.0 == table).map(|r| r.2);
+        assert_eq!(deleted("activities"), Some(2));
+        assert_eq!(deleted("activities_fts"), Some(2));
+        assert_eq!(deleted("clipboard_events"), Some(1));
+        assert_eq!(deleted("code_file_events"), Some(1));
+        assert_eq!(deleted("dashboard_snapshots"), Some(1));
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities"), 6);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities_fts"), 6);
+
+        // The log records the filter and counts, never the deleted content.
+        let (id, filter_json, counts_json): (i64, String, String) = conn
+            .query_row("SELECT id, filter_json, row_counts_json FROM deletion_log", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(real.log_id, Some(id));
+        assert!(!filter_json.contains("window text") && !counts_json.contains("window text"));
+        assert!(filter_json.contains("\"dry_run\":false"));
+    }
+
+    #[test]
+    fn rollups_match_the_remaining_activities_after_deletion() {
+        let mut conn = test_db();
+        seed_days(&conn);
+        let tx = conn.transaction().unwrap();
+        let (result, _) = delete_in(
+            &tx,
+            ActivityDeleteFilter {
+                app_patterns: vec!["  DISCORD ".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(result.affected_days.len(), 2);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities WHERE app_name = 'Discord'"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM clipboard_events"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM code_file_events"), 2);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM dashboard_snapshots"), 0);
+
+        let totals = |sql: &str| -> Vec<(i64, i64, i64)> {
+            conn.prepare(sql)
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        let rollups = totals(
+            "SELECT category_id, SUM(total_duration), SUM(event_count) FROM activity_summaries
+             GROUP BY category_id ORDER BY category_id",
+        );
+        assert_eq!(
+            rollups,
+            totals(
+                "SELECT category_id, SUM(duration_seconds), COUNT(*) FROM activities
+                 GROUP BY category_id ORDER BY category_id",
+            )
+        );
+        assert_eq!(rollups, vec![(1, 5_400, 4)]);
+    }
+
+    #[test]
+    fn deletion_needs_a_filter_and_a_valid_range() {
+        let mut conn = test_db();
+        let tx = conn.transaction().unwrap();
+        assert!(delete_in(&tx, ActivityDeleteFilter::default()).is_err());
+        assert!(delete_in(
+            &tx,
+            ActivityDeleteFilter {
+                app_patterns: vec!["  ".to_string()],
+                ..Default::default()
+            },
+        )
+        .is_err());
+        assert!(delete_in(
+            &tx,
+            ActivityDeleteFilter {
+                start_time: Some(NOON),
+                end_time: Some(NOON),
+                ..Default::default()
+            },
+        )
+        .is_err());
+    }
+
+    const DAY: i64 = 86_400;
+
+    /// One activity at `ts` whose raw size (`app_name` + `window_title` + metadata) is returned.
+    fn ocr_activity(conn: &Connection, ts: i64, ocr_chars: usize) -> i64 {
+        let metadata = serde_json::to_vec(&ActivityMetadata {
+            screen_text: Some("o".repeat(ocr_chars)),
+            ..Default::default()
+        })
+        .unwrap();
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+             VALUES ('Code', 1, 'main.rs', 1, ?1, ?1 + 60, 60, ?2)",
+            rusqlite::params![ts, metadata],
+        )
+        .unwrap();
+        ("Code".len() + "main.rs".len() + metadata.len()) as i64
+    }
+
+    #[test]
+    fn steady_ingest_fits_its_daily_rate() {
+        let days: Vec<DailyIngest> = (0..30)
+            .map(|i| DailyIngest { date_key: format!("d{}", i), bytes: 4_000 })
+            .collect();
+        assert!((cumulative_slope(&days) - 4_000.0).abs() < 1e-6);
+
+        // A single heavy day moves the fit far less than it moves the mean.
+        let mut spiky = days.clone();
+        spiky[0].bytes = 124_000;
+        let slope = cumulative_slope(&spiky);
+        assert!(slope < 4_000.0 + 120_000.0 / 30.0, "{}", slope);
+        assert_eq!(cumulative_slope(&days[..1]), 4_000.0);
+        assert_eq!(cumulative_slope(&[]), 0.0);
+    }
+
+    #[test]
+    fn forecast_of_a_generated_history() {
+        let conn = test_db();
+        let now = chrono::Utc::now().timestamp();
+        let offset_secs = crate::utils::time::current_offset().local_minus_utc() as i64;
+
+        // 30 days at one 2 000-char capture a day, plus a burst of old history.
+        let mut recent_raw = 0;
+        for day in 0..30 {
+            recent_raw = ocr_activity(&conn, now - day * DAY, 2_000);
+        }
+        let mut old_raw = 0;
+        for i in 0..5 {
+            old_raw += ocr_activity(&conn, now - 200 * DAY - i, 50_000);
+        }
+
+        let storage = StorageSettings {
+            retention_days: 365,
+            auto_cleanup: true,
+            ..Default::default()
+        };
+        let total = STORAGE_LIMIT_BYTES - 1_000_000;
+        let forecast = build_forecast(&conn, total, &storage, now, offset_secs).unwrap();
+        let scale = |bytes: i64| (bytes as f64 * forecast.overhead_factor) as i64;
+
+        assert_eq!(forecast.daily_ingest.len(), 30);
+        assert!(forecast.daily_ingest.iter().all(|d| d.bytes == recent_raw), "{:?}", forecast.daily_ingest);
+        assert!(forecast.overhead_factor >= 1.0);
+        assert_eq!(forecast.bytes_per_day, scale(recent_raw));
+        assert_eq!(forecast.forecast_30d_bytes, total + 30 * forecast.bytes_per_day);
+        assert_eq!(forecast.forecast_90d_bytes, total + 90 * forecast.bytes_per_day);
+        assert_eq!(
+            forecast.days_until_limit,
+            Some((1_000_000 + forecast.bytes_per_day - 1) / forecast.bytes_per_day)
+        );
+
+        assert_eq!(forecast.contributors[0].name, "OCR text");
+        assert_eq!(forecast.contributors[0].rows, 35);
+        assert_eq!(forecast.contributors[0].size_bytes, scale(30 * 2_000 + 5 * 50_000));
+
+        // Shorter retention drops exactly the old burst; 14-day OCR retention also drops 15 recent days.
+        let impacts: Vec<(&str, i64, i64)> = forecast
+            .retention_impacts
+            .iter()
+            .map(|r| (r.setting.as_str(), r.days, r.bytes_removed))
+            .collect();
+        assert_eq!(
+            impacts,
+            vec![
+                ("retention_days", 180, scale(old_raw)),
+                ("retention_days", 90, scale(old_raw)),
+                ("retention_days", 30, scale(old_raw)),
+                ("ocr_retention_days", 14, scale(5 * 50_000 + 15 * 2_000)),
+            ]
+        );
+        assert!(forecast.retention_impacts[3].description.starts_with("Keeping OCR text for 14 days would remove ~"));
+
+        // A shorter applied retention hides the longer suggestions; disabled cleanup is called out.
+        let storage = StorageSettings {
+            retention_days: 60,
+            auto_cleanup: false,
+            ..Default::default()
+        };
+        let forecast = build_forecast(&conn, total, &storage, now, offset_secs).unwrap();
+        let settings: Vec<(&str, i64)> =
+            forecast.retention_impacts.iter().map(|r| (r.setting.as_str(), r.days)).collect();
+        assert_eq!(
+            settings,
+            vec![
+                ("retention_days", 180),
+                ("retention_days", 90),
+                ("retention_days", 30),
+                ("ocr_retention_days", 14),
+                ("auto_cleanup", 60),
+            ]
+        );
+    }
+
+    #[test]
+    fn table_sizes_fall_back_to_column_lengths() {
+        let conn = test_db();
+        let raw = ocr_activity(&conn, 1_700_000_000, 10_000);
+        let columns = table_columns(&conn, "activities");
+        // The estimate also counts the numeric columns' text length, so it bounds the raw bytes from above.
+        let estimate = estimated_table_size(&conn, "activities", &columns);
+        assert!(estimate >= raw && estimate < raw + 200, "{} vs {}", estimate, raw);
+        assert_eq!(estimated_table_size(&conn, "activities", &[]), 0);
+
+        let (tables, source) = table_storage(&conn).unwrap();
+        assert!(source == "dbstat" || source == "estimate");
+        let activities = tables.iter().find(|t| t.table == "activities").unwrap();
+        assert_eq!(activities.row_count, 1);
+        assert!(activities.size_bytes >= 10_000);
+        assert_eq!(activities.oldest_record, Some(1_700_000_000));
+    }
+}
@@ -1,16 +1,23 @@
-use tauri::{AppHandle, Manager};
-use crate::models::{Workflow, WorkflowSuggestion, AppLaunch, CreateWorkflowRequest};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::AppHandle;
+use crate::models::{
+    ActionType, Workflow, WorkflowSuggestion, AppLaunch, CreateWorkflowRequest, PlannedAction, PreconditionCheck,
+    WorkflowPrecondition, WorkflowRunResult,
+};
 use uuid::Uuid;
 
 #[tauri::command]
 pub async fn get_workflows(
     app_handle: AppHandle,
 ) -> Result<Vec<Workflow>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
+    let conn = crate::database::open(&app_handle)?;
+    load_workflows(&conn)
+}
+
+pub(crate) fn load_workflows(conn: &rusqlite::Connection) -> Result<Vec<Workflow>, String> {
     let mut stmt = conn.prepare(
         "SELECT uuid, name, description, icon, apps, files, urls, use_count, last_used, created_at
          FROM workflows
@@ -55,10 +62,7 @@ pub async fn create_workflow(
     app_handle: AppHandle,
     workflow: CreateWorkflowRequest,
 ) -> Result<String, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     let uuid = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().timestamp();
@@ -90,10 +94,7 @@ pub async fn update_workflow(
     app_handle: AppHandle,
     workflow: Workflow,
 ) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     let apps_blob = serde_json::to_vec(&workflow.apps).map_err(|e| e.to_string())?;
     let files_blob = serde_json::to_vec(&workflow.files).map_err(|e| e.to_string())?;
@@ -120,10 +121,7 @@ pub async fn delete_workflow(
     app_handle: AppHandle,
     workflow_id: String,
 ) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     conn.execute(
         "DELETE FROM workflows WHERE uuid = ?1",
@@ -133,73 +131,275 @@ pub async fn delete_workflow(
     Ok(())
 }
 
-#[tauri::command]
-pub async fn execute_workflow(
-    app_handle: AppHandle,
-    workflow_id: String,
-) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    // Get workflow
-    let mut stmt = conn.prepare(
-        "SELECT apps, urls, files FROM workflows WHERE uuid = ?1"
-    ).map_err(|e| e.to_string())?;
-    
-    let (apps, urls, files): (Vec<AppLaunch>, Vec<String>, Vec<String>) = stmt
-        .query_row([&workflow_id], |row| {
+fn load_actions(conn: &rusqlite::Connection, workflow_id: &str) -> Result<(Vec<AppLaunch>, Vec<String>, Vec<String>), String> {
+    conn.query_row(
+        "SELECT apps, urls, files FROM workflows WHERE uuid = ?1",
+        [workflow_id],
+        |row| {
             let apps_blob: Option<Vec<u8>> = row.get(0)?;
             let urls_blob: Option<Vec<u8>> = row.get(1)?;
             let files_blob: Option<Vec<u8>> = row.get(2)?;
-            
+
             Ok((
                 apps_blob.and_then(|b| serde_json::from_slice(&b).ok()).unwrap_or_default(),
                 urls_blob.and_then(|b| serde_json::from_slice(&b).ok()).unwrap_or_default(),
                 files_blob.and_then(|b| serde_json::from_slice(&b).ok()).unwrap_or_default(),
             ))
-        })
-        .map_err(|e| e.to_string())?;
-    
-    // Launch apps
+        },
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => format!("Workflow {} not found", workflow_id),
+        other => other.to_string(),
+    })
+}
+
+const NETWORK_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// App Paths registry lookup; `crate::utils::windows::app_paths_lookup` outside tests.
+type AppPathsLookup = dyn Fn(&str) -> Option<String> + Sync;
+
+async fn check_precondition(precondition: &WorkflowPrecondition, app_paths: &AppPathsLookup) -> (bool, String) {
+    match precondition {
+        WorkflowPrecondition::PathExists { path } => {
+            if Path::new(path.trim()).exists() {
+                (true, format!("{} exists", path))
+            } else {
+                (false, format!("{} not found (is the drive connected?)", path))
+            }
+        }
+        WorkflowPrecondition::AppInstalled { exe } => match resolve_executable(exe.trim(), app_paths) {
+            Some(found) => (true, format!("found at {}", found.display())),
+            None => (false, format!("{} is not installed or not on PATH", exe)),
+        },
+        WorkflowPrecondition::NetworkReachable { host } => {
+            let host = host.trim();
+            let target = if host.contains(':') { host.to_string() } else { format!("{}:443", host) };
+            match tokio::time::timeout(NETWORK_CHECK_TIMEOUT, tokio::net::TcpStream::connect(&target)).await {
+                Ok(Ok(_)) => (true, format!("{} reachable", target)),
+                Ok(Err(e)) => (false, format!("{} unreachable: {}", target, e)),
+                Err(_) => (false, format!("{} did not answer within {}s", target, NETWORK_CHECK_TIMEOUT.as_secs())),
+            }
+        }
+    }
+}
+
+/// A full path as given, else the App Paths registration, else the first `PATH` hit.
+fn resolve_executable(exe: &str, app_paths: &AppPathsLookup) -> Option<PathBuf> {
+    if exe.is_empty() {
+        return None;
+    }
+    let direct = Path::new(exe);
+    if direct.is_absolute() {
+        return direct.exists().then(|| direct.to_path_buf());
+    }
+    if let Some(registered) = app_paths(exe) {
+        let registered = PathBuf::from(registered);
+        if registered.exists() {
+            return Some(registered);
+        }
+    }
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(exe))
+        .find(|candidate| candidate.is_file())
+}
+
+async fn run_checks(apps: &[AppLaunch], app_paths: &AppPathsLookup) -> Vec<PreconditionCheck> {
+    let mut checks = Vec::new();
     for app in apps {
+        for precondition in &app.preconditions {
+            let (passed, detail) = check_precondition(precondition, app_paths).await;
+            checks.push(PreconditionCheck {
+                action: app.path.clone(),
+                precondition: precondition.clone(),
+                passed,
+                detail,
+            });
+        }
+    }
+    checks
+}
+
+/// Run a workflow's checks without launching anything, so the editor can warn up front.
+#[tauri::command]
+pub async fn validate_workflow(
+    app_handle: AppHandle,
+    workflow_id: String,
+) -> Result<Vec<PreconditionCheck>, String> {
+    let (apps, _, _) = {
+        let conn = crate::database::open(&app_handle)?;
+        load_actions(&conn, &workflow_id)?
+    };
+    Ok(run_checks(&apps, &crate::utils::windows::app_paths_lookup).await)
+}
+
+/// Launches with a failed check, and whether one of them aborts the whole run.
+fn failed_launches<'a>(apps: &[AppLaunch], checks: &'a [PreconditionCheck]) -> (HashSet<&'a str>, bool) {
+    let failed_paths: HashSet<&str> = checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| c.action.as_str())
+        .collect();
+    let blocking = apps
+        .iter()
+        .any(|app| failed_paths.contains(app.path.as_str()) && !app.continue_on_failure);
+    (failed_paths, blocking)
+}
+
+/// Resolve every action the way a run would, without performing any of them.
+fn plan_actions(
+    apps: &[AppLaunch],
+    urls: &[String],
+    files: &[String],
+    failed_paths: &HashSet<&str>,
+    blocking: bool,
+    app_paths: &AppPathsLookup,
+) -> Vec<PlannedAction> {
+    let mut planned = Vec::new();
+    for app in apps {
+        let resolved = resolve_executable(app.path.trim(), app_paths);
+        let failed = failed_paths.contains(app.path.as_str());
+        planned.push(PlannedAction {
+            action_type: ActionType::LaunchApp,
+            target: app.path.clone(),
+            args: app.args.clone(),
+            note: match (&resolved, failed) {
+                (_, true) if app.continue_on_failure => Some("skipped: a check failed".to_string()),
+                (_, true) => Some("aborts the workflow: a check failed".to_string()),
+                (None, false) => Some("not found as a path, in App Paths or on PATH".to_string()),
+                (Some(_), false) => None,
+            },
+            resolved: resolved.map(|p| p.display().to_string()),
+            will_run: !blocking && !failed,
+        });
+    }
+    for url in urls {
+        planned.push(PlannedAction {
+            action_type: ActionType::OpenUrl,
+            target: url.clone(),
+            args: Vec::new(),
+            resolved: None,
+            will_run: !blocking,
+            note: (!url.contains("://")).then(|| "no scheme; may not open in a browser".to_string()),
+        });
+    }
+    for file in files {
+        let path = Path::new(file.trim());
+        let exists = path.exists();
+        planned.push(PlannedAction {
+            action_type: ActionType::OpenFile,
+            target: file.clone(),
+            args: Vec::new(),
+            resolved: exists
+                .then(|| std::fs::canonicalize(path).ok())
+                .flatten()
+                .map(|p| p.display().to_string()),
+            will_run: !blocking,
+            note: (!exists).then(|| "file not found".to_string()),
+        });
+    }
+    planned
+}
+
+/// Every check runs before the first action, so a missing drive or app can't
+/// leave the workflow half-executed. A failed check aborts the run unless its
+/// launch is marked `continue_on_failure`, in which case only that launch is skipped.
+/// With `dry_run` nothing is launched or opened; the result lists the resolved actions instead.
+#[tauri::command]
+pub async fn execute_workflow(
+    app_handle: AppHandle,
+    workflow_id: String,
+    dry_run: Option<bool>,
+) -> Result<WorkflowRunResult, String> {
+    let (apps, urls, files) = {
+        let conn = crate::database::open(&app_handle)?;
+        load_actions(&conn, &workflow_id)?
+    };
+
+    let app_paths = &crate::utils::windows::app_paths_lookup;
+    let checks = run_checks(&apps, app_paths).await;
+    let (failed_paths, blocking) = failed_launches(&apps, &checks);
+    if dry_run.unwrap_or(false) {
+        let planned = plan_actions(&apps, &urls, &files, &failed_paths, blocking, app_paths);
+        let skipped_actions = apps
+            .iter()
+            .filter(|app| !blocking && failed_paths.contains(app.path.as_str()))
+            .map(|app| app.path.clone())
+            .collect();
+        return Ok(WorkflowRunResult {
+            executed: false,
+            dry_run: true,
+            planned,
+            checks,
+            skipped_actions,
+            errors: Vec::new(),
+        });
+    }
+    if blocking {
+        println!("[Workflow] {} aborted: precondition failed", workflow_id);
+        return Ok(WorkflowRunResult {
+            executed: false,
+            dry_run: false,
+            planned: Vec::new(),
+            checks,
+            skipped_actions: Vec::new(),
+            errors: Vec::new(),
+        });
+    }
+
+    let mut skipped_actions = Vec::new();
+    let mut errors = Vec::new();
+
+    // Launch apps
+    for app in &apps {
+        if failed_paths.contains(app.path.as_str()) {
+            skipped_actions.push(app.path.clone());
+            continue;
+        }
         if cfg!(target_os = "windows") {
-            std::process::Command::new(&app.path)
-                .args(&app.args)
-                .spawn()
-                .ok();
+            if let Err(e) = std::process::Command::new(&app.path).args(&app.args).spawn() {
+                errors.push(format!("{}: {}", app.path, e));
+            }
         }
     }
-    
+
     // Open URLs
     for url in urls {
-        open::that(&url).ok();
+        if let Err(e) = open::that(&url) {
+            errors.push(format!("{}: {}", url, e));
+        }
     }
-    
+
     // Open files
     for file in files {
-        open::that(&file).ok();
+        if let Err(e) = open::that(&file) {
+            errors.push(format!("{}: {}", file, e));
+        }
     }
-    
+
     // Update use count
+    let conn = crate::database::open(&app_handle)?;
     let now = chrono::Utc::now().timestamp();
     conn.execute(
         "UPDATE workflows SET use_count = use_count + 1, last_used = ?1 WHERE uuid = ?2",
         [&now.to_string(), &workflow_id],
     ).map_err(|e| e.to_string())?;
-    
-    Ok(())
+
+    Ok(WorkflowRunResult {
+        executed: true,
+        dry_run: false,
+        planned: Vec::new(),
+        checks,
+        skipped_actions,
+        errors,
+    })
 }
 
 #[tauri::command]
 pub async fn get_workflow_suggestions(
     app_handle: AppHandle,
 ) -> Result<Vec<WorkflowSuggestion>, String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(&app_handle)?;
     
     // Suggest workflows based on most-used and recently used
     let mut stmt = conn.prepare(
@@ -253,3 +453,113 @@ pub async fn get_workflow_suggestions(
     
     Ok(suggestions)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir;
+
+    fn no_registry(_: &str) -> Option<String> {
+        None
+    }
+
+    fn launch(path: &str, preconditions: Vec<WorkflowPrecondition>, continue_on_failure: bool) -> AppLaunch {
+        AppLaunch {
+            path: path.to_string(),
+            args: Vec::new(),
+            preconditions,
+            continue_on_failure,
+        }
+    }
+
+    #[tokio::test]
+    async fn path_checks_follow_the_drive() {
+        let dir = test_dir("workflow-paths");
+        let project = dir.join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        let check = WorkflowPrecondition::PathExists { path: project.display().to_string() };
+
+        assert!(check_precondition(&check, &no_registry).await.0);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let (passed, detail) = check_precondition(&check, &no_registry).await;
+        assert!(!passed);
+        assert!(detail.ends_with("not found (is the drive connected?)"), "{}", detail);
+    }
+
+    #[tokio::test]
+    async fn installed_apps_resolve_through_a_fake_registry() {
+        let dir = test_dir("workflow-registry");
+        let editor = dir.join("Code.exe");
+        std::fs::write(&editor, b"").unwrap();
+        let registered = editor.display().to_string();
+        let registry = move |exe: &str| match exe {
+            "Code.exe" => Some(registered.clone()),
+            "Stale.exe" => Some("/nowhere/uninstalled/Stale.exe".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(resolve_executable("Code.exe", &registry), Some(editor.clone()));
+        assert_eq!(resolve_executable(&editor.display().to_string(), &no_registry), Some(editor.clone()));
+        // A registration whose file is gone falls through to PATH, which doesn't have it either.
+        assert_eq!(resolve_executable("Stale.exe", &registry), None);
+        assert_eq!(resolve_executable("intentflow-missing-app.exe", &registry), None);
+        assert_eq!(resolve_executable("", &registry), None);
+
+        let (passed, detail) =
+            check_precondition(&WorkflowPrecondition::AppInstalled { exe: " Code.exe ".to_string() }, &registry).await;
+        assert!(passed);
+        assert_eq!(detail, format!("found at {}", editor.display()));
+        let (passed, _) = check_precondition(&WorkflowPrecondition::AppInstalled { exe: "Stale.exe".to_string() }, &registry).await;
+        assert!(!passed);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn network_checks_need_a_listener() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        let check = WorkflowPrecondition::NetworkReachable { host: host.clone() };
+        assert!(check_precondition(&check, &no_registry).await.0);
+        drop(listener);
+        let (passed, detail) = check_precondition(&check, &no_registry).await;
+        assert!(!passed);
+        assert!(detail.starts_with(&host), "{}", detail);
+    }
+
+    #[tokio::test]
+    async fn failed_checks_abort_or_skip_their_launch() {
+        let dir = test_dir("workflow-gating");
+        let missing = WorkflowPrecondition::PathExists { path: dir.join("offline").display().to_string() };
+        let present = WorkflowPrecondition::PathExists { path: dir.display().to_string() };
+
+        let apps = vec![
+            launch("Code.exe", vec![present.clone()], false),
+            launch("Spotify.exe", vec![missing.clone()], true),
+        ];
+        let checks = run_checks(&apps, &no_registry).await;
+        assert_eq!(checks.iter().map(|c| (c.action.as_str(), c.passed)).collect::<Vec<_>>(), vec![
+            ("Code.exe", true),
+            ("Spotify.exe", false),
+        ]);
+        let (failed, blocking) = failed_launches(&apps, &checks);
+        assert!(!blocking);
+        assert_eq!(failed, HashSet::from(["Spotify.exe"]));
+        let planned = plan_actions(&apps, &[], &[], &failed, blocking, &no_registry);
+        assert!(planned[0].will_run);
+        assert!(!planned[1].will_run);
+        assert_eq!(planned[1].note.as_deref(), Some("skipped: a check failed"));
+
+        // Without continue_on_failure the same failure stops everything, URLs included.
+        let apps = vec![
+            launch("Code.exe", vec![missing.clone(), present], false),
+            launch("Spotify.exe", Vec::new(), false),
+        ];
+        let checks = run_checks(&apps, &no_registry).await;
+        let (failed, blocking) = failed_launches(&apps, &checks);
+        assert!(blocking);
+        let planned = plan_actions(&apps, &["https://github.com".to_string()], &[], &failed, blocking, &no_registry);
+        assert!(planned.iter().all(|p| !p.will_run));
+        assert_eq!(planned[0].note.as_deref(), Some("aborts the workflow: a check failed"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,216 @@
+use anyhow::Result;
+use rusqlite::Connection;
+
+use crate::models::{DataIntegrityReport, IntegrityViolation};
+
+// ─── Data integrity checks ───
+// Read-only counterpart of the `activities_integrity` migration: reports what
+// it would repair and whether its triggers and indexes are in place.
+
+const EXPECTED_INDEXES: [(&str, &str); 3] = [
+    ("activities", "idx_activities_start_time"),
+    ("activities", "idx_activities_category_start"),
+    ("code_file_events", "idx_code_file_events_detected_at"),
+];
+
+const EXPECTED_TRIGGERS: [(&str, &str); 4] = [
+    ("activities", "trg_activities_category_insert"),
+    ("activities", "trg_activities_category_update"),
+    ("activities", "trg_activities_title_insert"),
+    ("activities", "trg_activities_title_update"),
+];
+
+pub fn verify(conn: &Connection) -> Result<DataIntegrityReport> {
+    let mut violations: Vec<IntegrityViolation> = Vec::new();
+    let mut push = |check: &str, table: &str, count: i64, detail: String| {
+        if count > 0 {
+            violations.push(IntegrityViolation {
+                check: check.to_string(),
+                table: table.to_string(),
+                count,
+                detail,
+            });
+        }
+    };
+
+    let count = |sql: &str| -> Result<i64> { Ok(conn.query_row(sql, [], |row| row.get(0))?) };
+
+    push(
+        "unknown_category",
+        "activities",
+        count(
+            "SELECT COUNT(*) FROM activities
+             WHERE category_id IS NULL OR category_id NOT IN (SELECT id FROM categories)",
+        )?,
+        "category_id does not match any category".to_string(),
+    );
+    push(
+        "null_window_title",
+        "activities",
+        count("SELECT COUNT(*) FROM activities WHERE window_title IS NULL")?,
+        "window_title is NULL".to_string(),
+    );
+    push(
+        "negative_duration",
+        "activities",
+        count("SELECT COUNT(*) FROM activities WHERE end_time < start_time OR duration_seconds < 0")?,
+        "end_time before start_time or negative duration".to_string(),
+    );
+    push(
+        "orphaned_message",
+        "chat_messages",
+        count(
+            "SELECT COUNT(*) FROM chat_messages
+             WHERE session_id NOT IN (SELECT id FROM chat_sessions)",
+        )?,
+        "session_id does not match a chat session".to_string(),
+    );
+
+    // Declared foreign keys, checked even though most connections don't enforce them.
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let mut fk_counts: std::collections::BTreeMap<(String, String), i64> = std::collections::BTreeMap::new();
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(2)?)))?;
+    for (table, parent) in rows.filter_map(|r| r.ok()) {
+        *fk_counts.entry((table, parent)).or_insert(0) += 1;
+    }
+    for ((table, parent), n) in fk_counts {
+        push("foreign_key", &table, n, format!("rows reference missing {} rows", parent));
+    }
+
+    for (table, name) in EXPECTED_INDEXES {
+        if !schema_object_exists(conn, "index", name)? {
+            push("missing_index", table, 1, name.to_string());
+        }
+    }
+    for (table, name) in EXPECTED_TRIGGERS {
+        if !schema_object_exists(conn, "trigger", name)? {
+            push("missing_trigger", table, 1, name.to_string());
+        }
+    }
+
+    Ok(DataIntegrityReport {
+        clean: violations.is_empty(),
+        violations,
+        checked_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+fn schema_object_exists(conn: &Connection, kind: &str, name: &str) -> Result<bool> {
+    let found: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = ?1 AND name = ?2",
+        [kind, name],
+        |row| row.get(0),
+    )?;
+    Ok(found > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify;
+    use crate::database::migrations::MIGRATIONS;
+    use rusqlite::Connection;
+
+    const INTEGRITY_VERSION: i64 = 13;
+
+    /// A database from before the integrity migration, with the drift old builds left behind.
+    fn corrupted_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::schema::create_tables(&conn).unwrap();
+        conn.execute(
+            "CREATE TABLE schema_version (version INTEGER PRIMARY KEY, name TEXT NOT NULL, applied_at INTEGER NOT NULL)",
+            [],
+        )
+        .unwrap();
+        for migration in MIGRATIONS.iter().filter(|m| m.version < INTEGRITY_VERSION) {
+            (migration.apply)(&conn).unwrap();
+            conn.execute(
+                "INSERT INTO schema_version (version, name, applied_at) VALUES (?1, ?2, 0)",
+                rusqlite::params![migration.version, migration.name],
+            )
+            .unwrap();
+        }
+        // Old builds wrote these rows without foreign key enforcement.
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             DROP INDEX IF EXISTS idx_activities_category_start;
+             INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+             VALUES ('Code', 1, 'main.rs', 1, 100, 160, 60),
+                    ('Legacy', 2, 'old build', 42, 200, 260, 60),
+                    ('Legacy', 2, NULL, 99, 300, 360, 60),
+                    ('Browser', 3, NULL, 2, 400, 460, 60);
+             PRAGMA foreign_keys = ON;",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn violation_count(report: &crate::models::DataIntegrityReport, check: &str) -> i64 {
+        report
+            .violations
+            .iter()
+            .filter(|v| v.check == check)
+            .map(|v| v.count)
+            .sum()
+    }
+
+    #[test]
+    fn verify_reports_drift_without_repairing_it() {
+        let conn = corrupted_db();
+        let report = verify(&conn).unwrap();
+
+        assert!(!report.clean);
+        assert_eq!(violation_count(&report, "unknown_category"), 2);
+        assert_eq!(violation_count(&report, "null_window_title"), 2);
+        assert_eq!(violation_count(&report, "missing_trigger"), 4);
+        assert!(violation_count(&report, "missing_index") >= 1);
+        let nulls: i64 = conn
+            .query_row("SELECT COUNT(*) FROM activities WHERE window_title IS NULL", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(nulls, 2);
+    }
+
+    #[test]
+    fn migration_repairs_the_fixture_and_verify_reports_clean() {
+        let conn = corrupted_db();
+        crate::database::run_migrations(&conn).unwrap();
+
+        let report = verify(&conn).unwrap();
+        assert!(report.clean, "{:?}", report.violations);
+        let rows: Vec<(String, i64, String)> = conn
+            .prepare("SELECT app_name, category_id, window_title FROM activities ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("Code".to_string(), 1, "main.rs".to_string()),
+                ("Legacy".to_string(), 7, "old build".to_string()),
+                ("Legacy".to_string(), 7, "(unknown)".to_string()),
+                ("Browser".to_string(), 2, "(unknown)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn triggers_reject_new_drift() {
+        let conn = corrupted_db();
+        crate::database::run_migrations(&conn).unwrap();
+        let insert = |category_id: i64, title: Option<&str>| {
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+                 VALUES ('App', 1, ?1, ?2, 500, 560, 60)",
+                rusqlite::params![title, category_id],
+            )
+        };
+        assert!(insert(42, Some("title")).is_err());
+        assert!(insert(1, None).is_err());
+        assert!(insert(1, Some("title")).is_ok());
+        assert!(conn
+            .execute("UPDATE activities SET category_id = 42 WHERE app_name = 'App'", [])
+            .is_err());
+        assert!(verify(&conn).unwrap().clean);
+    }
+}
@@ -0,0 +1,444 @@
+use anyhow::Result;
+use rusqlite::Connection;
+
+use super::schema::ensure_column_exists;
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub apply: fn(&Connection) -> Result<()>,
+}
+
+/// Ordered schema changes applied on top of `schema::create_tables`.
+/// Append new steps with the next version number; never reorder or edit
+/// a released step. Steps must be idempotent because databases created
+/// before versioning may already contain some of these columns.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "activities_private_flag",
+        apply: add_activities_private_flag,
+    },
+    Migration {
+        version: 2,
+        name: "chat_sessions_archived",
+        apply: add_chat_sessions_archived,
+    },
+    Migration {
+        version: 3,
+        name: "activities_tz_offset",
+        apply: add_activities_tz_offset,
+    },
+    Migration {
+        version: 4,
+        name: "categories_builtin_flag",
+        apply: add_categories_builtin_flag,
+    },
+    Migration {
+        version: 5,
+        name: "clipboard_events",
+        apply: create_clipboard_events,
+    },
+    Migration {
+        version: 6,
+        name: "intent_executions",
+        apply: create_intent_executions,
+    },
+    Migration {
+        version: 7,
+        name: "quick_action_feedback",
+        apply: create_quick_action_feedback,
+    },
+    Migration {
+        version: 8,
+        name: "session_checkpoint",
+        apply: create_session_checkpoint,
+    },
+    Migration {
+        version: 9,
+        name: "app_alerts",
+        apply: create_app_alerts,
+    },
+    Migration {
+        version: 10,
+        name: "pattern_findings",
+        apply: add_pattern_findings,
+    },
+    Migration {
+        version: 11,
+        name: "chat_sessions_pinned",
+        apply: add_chat_sessions_pinned,
+    },
+    Migration {
+        version: 12,
+        name: "deletion_log",
+        apply: create_deletion_log,
+    },
+    Migration {
+        version: 13,
+        name: "activities_integrity",
+        apply: enforce_activities_integrity,
+    },
+    Migration {
+        version: 14,
+        name: "chat_messages_actions",
+        apply: add_chat_messages_actions,
+    },
+    Migration {
+        version: 15,
+        name: "process_events",
+        apply: create_process_events,
+    },
+    Migration {
+        version: 16,
+        name: "llm_usage",
+        apply: create_llm_usage,
+    },
+    Migration {
+        version: 17,
+        name: "app_merge_map",
+        apply: create_app_merge_map,
+    },
+    Migration {
+        version: 18,
+        name: "category_goals",
+        apply: create_category_goals,
+    },
+    Migration {
+        version: 19,
+        name: "chat_messages_time_accounting",
+        apply: add_chat_messages_time_accounting,
+    },
+    Migration {
+        version: 20,
+        name: "notification_log",
+        apply: create_notification_log,
+    },
+];
+
+// Rows flagged private stay in the timeline but are hidden from AI context.
+fn add_activities_private_flag(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "activities", "private", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn add_chat_sessions_archived(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "chat_sessions", "archived", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn add_chat_sessions_pinned(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "chat_sessions", "pinned", "INTEGER NOT NULL DEFAULT 0")
+}
+
+// UTC offset (minutes) in effect when the row was captured; keeps day bounds stable across travel.
+fn add_activities_tz_offset(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "activities", "tz_offset_minutes", "INTEGER")
+}
+
+// The seeded categories keep their ids (other code relies on e.g. 7 = Other), so they can't be deleted.
+fn add_categories_builtin_flag(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "categories", "is_builtin", "INTEGER NOT NULL DEFAULT 0")?;
+    conn.execute("UPDATE categories SET is_builtin = 1 WHERE id BETWEEN 1 AND 7", [])?;
+    Ok(())
+}
+
+// Opt-in clipboard history (settings.tracking.sources.clipboard); text is redacted before insert.
+fn create_clipboard_events(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS clipboard_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            content TEXT NOT NULL,
+            content_hash INTEGER NOT NULL,
+            app_name TEXT,
+            window_title TEXT,
+            captured_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_clipboard_events_captured_at ON clipboard_events(captured_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+// Audit of executed intents; successful rows feed the quick-action ranking.
+fn create_intent_executions(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS intent_executions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            intent_type TEXT NOT NULL,
+            action_key TEXT NOT NULL,
+            actions TEXT NOT NULL,
+            success INTEGER NOT NULL,
+            error TEXT,
+            executed_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_intent_executions_executed_at ON intent_executions(executed_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn create_quick_action_feedback(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS quick_action_feedback (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action_id TEXT NOT NULL,
+            accepted INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_quick_action_feedback_action ON quick_action_feedback(action_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+// Single-row copy of the tracker's open session so a crash loses at most one checkpoint interval.
+fn create_session_checkpoint(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS session_checkpoint (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            app_name TEXT NOT NULL,
+            window_title TEXT NOT NULL,
+            category_id INTEGER NOT NULL,
+            start_ms INTEGER NOT NULL,
+            last_seen_ms INTEGER NOT NULL,
+            metadata BLOB
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// Per-app daily usage alerts; a NULL repeat interval means notify once per day.
+fn create_app_alerts(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_alerts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            app_pattern TEXT NOT NULL,
+            daily_threshold_seconds INTEGER NOT NULL,
+            repeat_every_seconds INTEGER,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// Pattern engine findings (routine/anomaly/correlation) keyed so re-detection
+// updates a row in place and a dismissal sticks.
+fn add_pattern_findings(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "patterns", "pattern_key", "TEXT")?;
+    ensure_column_exists(conn, "patterns", "kind", "TEXT")?;
+    ensure_column_exists(conn, "patterns", "description", "TEXT")?;
+    ensure_column_exists(conn, "patterns", "supporting_window", "TEXT")?;
+    ensure_column_exists(conn, "patterns", "detected_at", "INTEGER")?;
+    ensure_column_exists(conn, "patterns", "dismissed", "INTEGER NOT NULL DEFAULT 0")?;
+    // The old engine appended a keyless row per app/hour on every run; the
+    // next run re-derives everything worth keeping.
+    conn.execute("DELETE FROM patterns WHERE pattern_key IS NULL", [])?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_patterns_key ON patterns(pattern_key)",
+        [],
+    )?;
+    Ok(())
+}
+
+// One row per bulk deletion: the filter and per-table counts, never the deleted content.
+fn create_deletion_log(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS deletion_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            filter_json TEXT NOT NULL,
+            row_counts_json TEXT NOT NULL,
+            deleted_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// Older builds used other category ids and sometimes stored no window title.
+// Existing rows are repaired once; triggers then reject such rows, since
+// SQLite can't add constraints to an existing table without rebuilding it
+// (and `foreign_keys` is off on most connections anyway).
+fn enforce_activities_integrity(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "UPDATE activities SET category_id = ?1
+         WHERE category_id IS NULL OR category_id NOT IN (SELECT id FROM categories)",
+        [crate::utils::categories::OTHER_CATEGORY_ID],
+    )?;
+    conn.execute(
+        "UPDATE activities SET window_title = '(unknown)' WHERE window_title IS NULL",
+        [],
+    )?;
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS trg_activities_category_insert
+         BEFORE INSERT ON activities
+         WHEN NOT EXISTS (SELECT 1 FROM categories WHERE id = NEW.category_id)
+         BEGIN SELECT RAISE(ABORT, 'activities.category_id does not match a category'); END;
+
+         CREATE TRIGGER IF NOT EXISTS trg_activities_category_update
+         BEFORE UPDATE OF category_id ON activities
+         WHEN NOT EXISTS (SELECT 1 FROM categories WHERE id = NEW.category_id)
+         BEGIN SELECT RAISE(ABORT, 'activities.category_id does not match a category'); END;
+
+         CREATE TRIGGER IF NOT EXISTS trg_activities_title_insert
+         BEFORE INSERT ON activities
+         WHEN NEW.window_title IS NULL
+         BEGIN SELECT RAISE(ABORT, 'activities.window_title may not be NULL'); END;
+
+         CREATE TRIGGER IF NOT EXISTS trg_activities_title_update
+         BEFORE UPDATE OF window_title ON activities
+         WHEN NEW.window_title IS NULL
+         BEGIN SELECT RAISE(ABORT, 'activities.window_title may not be NULL'); END;
+
+         CREATE INDEX IF NOT EXISTS idx_activities_start_time ON activities(start_time);
+         CREATE INDEX IF NOT EXISTS idx_activities_category_start ON activities(category_id, start_time);
+         CREATE INDEX IF NOT EXISTS idx_code_file_events_detected_at ON code_file_events(detected_at);",
+    )?;
+    Ok(())
+}
+
+// Follow-up actions (scope confirmation, retry) stored as JSON beside the
+// answer instead of as markers inside its text.
+fn add_chat_messages_actions(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "chat_messages", "actions", "TEXT")
+}
+
+// Lifetimes of processes on `settings.tracking.process_watch_list`, one row per
+// run. `created_at` is the OS creation time and, with the pid, identifies a run
+// across pid reuse; `start_time` may be later when tracking was suppressed.
+// `end_time` stays NULL while the process is running.
+fn create_process_events(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS process_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            process_name TEXT NOT NULL,
+            pid INTEGER NOT NULL,
+            parent_pid INTEGER,
+            parent_name TEXT,
+            created_at INTEGER,
+            start_time INTEGER NOT NULL,
+            end_time INTEGER,
+            last_seen INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_process_events_start_time ON process_events(start_time);
+        CREATE INDEX IF NOT EXISTS idx_process_events_open ON process_events(end_time) WHERE end_time IS NULL;",
+    )?;
+    Ok(())
+}
+
+// One row per LLM call (or per agent run) with provider-reported or estimated
+// tokens, for the monthly usage summary. Chat answers also keep their run's
+// totals so sessions can show what they cost.
+fn create_llm_usage(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS llm_usage (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            caller TEXT NOT NULL,
+            model TEXT NOT NULL,
+            prompt_tokens INTEGER NOT NULL,
+            completion_tokens INTEGER NOT NULL,
+            calls INTEGER NOT NULL DEFAULT 1,
+            estimated INTEGER NOT NULL DEFAULT 0,
+            cost_usd REAL,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_llm_usage_created_at ON llm_usage(created_at);",
+    )?;
+    // `LlmUsage` JSON on assistant messages, like `actions`.
+    ensure_column_exists(conn, "chat_messages", "usage", "TEXT")?;
+    Ok(())
+}
+
+// Old app name -> canonical name: applied when stats group by app, to new
+// captures, and to history on request. Seeded with the alias groups query
+// expansion used to hardcode so they can be edited. `search_only` rows only
+// widen app search: they name a different app that shouldn't share stats.
+fn create_app_merge_map(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_merge_map (
+            from_name TEXT PRIMARY KEY,
+            to_name TEXT NOT NULL,
+            search_only INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );",
+    )?;
+    let seed: &[(&str, &[&str], &[&str])] = &[
+        ("Visual Studio Code", &["vs code", "vscode", "code"], &["antigravity"]),
+        ("Google Chrome", &["chrome"], &[]),
+        ("Microsoft Edge", &["edge", "msedge"], &[]),
+        ("Brave", &["brave browser"], &[]),
+        ("Firefox", &["mozilla firefox"], &[]),
+        ("Microsoft Teams", &["teams"], &[]),
+        ("Microsoft Word", &["word"], &[]),
+        ("Microsoft Excel", &["excel"], &[]),
+        ("Notepad", &[], &["notepad++"]),
+        ("File Explorer", &["explorer", "windows explorer"], &[]),
+        ("Spotify", &["liked songs"], &[]),
+        ("YouTube", &["yt"], &[]),
+        ("WhatsApp", &["whatsapp.root"], &[]),
+        ("Instagram", &["insta"], &[]),
+    ];
+    let now = chrono::Utc::now().timestamp();
+    for (to_name, merged, search_only) in seed {
+        let rows = merged.iter().map(|n| (n, false)).chain(search_only.iter().map(|n| (n, true)));
+        for (from_name, search_only) in rows {
+            conn.execute(
+                "INSERT OR IGNORE INTO app_merge_map (from_name, to_name, search_only, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![from_name, to_name, search_only, now],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// `best_streak` persists the record beyond the 60-day calendar window;
+// `best_notified_on` limits the "new best" notification to once per goal per day.
+fn create_category_goals(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS category_goals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            category_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            target_seconds INTEGER NOT NULL,
+            no_data_policy TEXT NOT NULL DEFAULT 'break',
+            enabled INTEGER NOT NULL DEFAULT 1,
+            best_streak INTEGER NOT NULL DEFAULT 0,
+            best_notified_on TEXT,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (category_id) REFERENCES categories(id)
+        );",
+    )?;
+    Ok(())
+}
+
+// `TimeAccounting` JSON and confidence factors on assistant messages, like `usage`.
+fn add_chat_messages_time_accounting(conn: &Connection) -> Result<()> {
+    ensure_column_exists(conn, "chat_messages", "time_accounting", "TEXT")?;
+    ensure_column_exists(conn, "chat_messages", "confidence_factors", "TEXT")
+}
+
+// Every notification shown, so "since you last looked" can list what fired while away.
+fn create_notification_log(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS notification_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_notification_log_created_at ON notification_log(created_at);",
+    )?;
+    Ok(())
+}
@@ -1,9 +1,171 @@
 use anyhow::Result;
 use rusqlite::Connection;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
 
 pub mod schema;
 pub mod queries;
+pub mod migrations;
+pub mod integrity;
+pub mod sample_data;
+
+pub const DB_FILE: &str = "intentflow.db";
+/// Synthetic database served instead of `DB_FILE` while demo mode is on.
+pub const DEMO_DB_FILE: &str = "intentflow_demo.db";
+
+/// Prefix of every open failure, so the UI can show one "check storage" message.
+pub const DB_UNAVAILABLE: &str = "Database unavailable — check storage";
+
+/// How long a command waits on a lock held by a background writer.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Set once the first connection of this run passed `PRAGMA quick_check`.
+static INTEGRITY_CHECKED: AtomicBool = AtomicBool::new(false);
+
+/// Every `open`/`db_path` caller follows this, so switching it swaps the
+/// database for the whole app without a restart.
+static DEMO_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn is_demo_mode() -> bool {
+    DEMO_MODE.load(Ordering::Relaxed)
+}
+
+pub fn set_demo_mode(enabled: bool) {
+    if DEMO_MODE.swap(enabled, Ordering::Relaxed) != enabled {
+        // The other file hasn't been checked yet this run.
+        INTEGRITY_CHECKED.store(false, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+pub enum DbError {
+    /// The data directory can't be resolved or the file can't be opened (missing drive, permissions, locked).
+    Unavailable(String),
+    /// The file opened but still fails the integrity check after WAL recovery.
+    Corrupt(String),
+}
+
+impl std::fmt::Display for DbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbError::Unavailable(detail) => write!(f, "{}: {}", DB_UNAVAILABLE, detail),
+            DbError::Corrupt(detail) => write!(f, "{}: database is corrupt ({})", DB_UNAVAILABLE, detail),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<DbError> for String {
+    fn from(e: DbError) -> Self {
+        e.to_string()
+    }
+}
+
+pub fn db_path(app_handle: &AppHandle) -> Result<PathBuf, DbError> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| DbError::Unavailable(e.to_string()))?;
+    if !data_dir.is_dir() {
+        return Err(DbError::Unavailable(format!("{} is not reachable", data_dir.display())));
+    }
+    Ok(data_dir.join(if is_demo_mode() { DEMO_DB_FILE } else { DB_FILE }))
+}
+
+/// Open the app database for a command or service. The first open of each
+/// run also verifies integrity and tries to recover from a damaged WAL.
+pub fn open(app_handle: &AppHandle) -> Result<Connection, DbError> {
+    open_path(&db_path(app_handle)?)
+}
+
+/// Run `f` against a freshly opened connection.
+pub fn with_db<T>(
+    app_handle: &AppHandle,
+    f: impl FnOnce(&Connection) -> std::result::Result<T, String>,
+) -> std::result::Result<T, String> {
+    let conn = open(app_handle)?;
+    f(&conn)
+}
+
+fn open_path(path: &Path) -> Result<Connection, DbError> {
+    // An open failure is a missing drive, permissions or a lock, never a reason to touch the WAL.
+    let conn = Connection::open(path).map_err(|e| DbError::Unavailable(e.to_string()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| DbError::Unavailable(e.to_string()))?;
+
+    if INTEGRITY_CHECKED.load(Ordering::Relaxed) {
+        return Ok(conn);
+    }
+    if let Err(first) = quick_check(&conn) {
+        println!("[Database] Integrity check failed ({}), checkpointing WAL", first);
+        let conn = recover_wal(path, conn)?;
+        quick_check(&conn).map_err(DbError::Corrupt)?;
+        println!("[Database] Recovered after WAL checkpoint");
+        INTEGRITY_CHECKED.store(true, Ordering::Relaxed);
+        return Ok(conn);
+    }
+    INTEGRITY_CHECKED.store(true, Ordering::Relaxed);
+    Ok(conn)
+}
+
+fn quick_check(conn: &Connection) -> std::result::Result<(), String> {
+    let result: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if result.eq_ignore_ascii_case("ok") {
+        Ok(())
+    } else {
+        Err(result)
+    }
+}
+
+/// Whether SQLite reported the file itself as damaged, as opposed to busy,
+/// locked or unreachable.
+fn is_corruption(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+    )
+}
+
+/// Fold the WAL back into the main file. Only if the checkpoint itself reports
+/// corruption is the WAL moved aside and the database reopened from the last
+/// good checkpoint; a busy checkpoint (another connection reading) or any other
+/// error leaves it alone, since it may hold committed transactions.
+fn recover_wal(path: &Path, conn: Connection) -> Result<Connection, DbError> {
+    match conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get::<_, i64>(0)) {
+        Ok(0) => return Ok(conn),
+        Ok(_) => return Err(DbError::Unavailable("database is busy, WAL checkpoint deferred".to_string())),
+        Err(e) if is_corruption(&e) => {
+            println!("[Database] WAL checkpoint reports corruption ({}), setting the WAL aside", e);
+        }
+        Err(e) => return Err(DbError::Unavailable(e.to_string())),
+    }
+    drop(conn);
+    set_aside_wal(path);
+    let conn = Connection::open(path).map_err(|e| DbError::Unavailable(e.to_string()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| DbError::Unavailable(e.to_string()))?;
+    Ok(conn)
+}
+
+/// Rename `<db>-wal` (and its index) so SQLite starts without it; kept for manual inspection.
+fn set_aside_wal(path: &Path) {
+    let stamp = chrono::Utc::now().timestamp();
+    for suffix in ["-wal", "-shm"] {
+        let file = PathBuf::from(format!("{}{}", path.display(), suffix));
+        if file.exists() {
+            let target = PathBuf::from(format!("{}.corrupt-{}", file.display(), stamp));
+            match std::fs::rename(&file, &target) {
+                Ok(()) => println!("[Database] Moved {} to {}", file.display(), target.display()),
+                Err(e) => println!("[Database] Could not move {}: {}", file.display(), e),
+            }
+        }
+    }
+}
 
 pub fn init_database(db_path: &Path) -> Result<Connection> {
     let conn = Connection::open(db_path)?;
@@ -15,6 +177,75 @@ pub fn init_database(db_path: &Path) -> Result<Connection> {
     
     // Create schema
     schema::create_tables(&conn)?;
+    run_migrations(&conn)?;
     
     Ok(conn)
 }
+
+/// Current schema version (0 for a baseline database with no migrations applied).
+pub fn schema_version(conn: &Connection) -> Result<i64> {
+    let version: Option<i64> = conn.query_row(
+        "SELECT MAX(version) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Apply every migration newer than the recorded version, each in its own
+/// transaction so a failed step leaves the database at the previous version.
+pub fn run_migrations(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    let current = schema_version(conn)?;
+    for migration in migrations::MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.unchecked_transaction()?;
+        (migration.apply)(&tx)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![migration.version, migration.name, chrono::Utc::now().timestamp()],
+        )?;
+        tx.pragma_update(None, "user_version", &migration.version)?;
+        tx.commit()?;
+        println!("[Database] Applied migration {} ({})", migration.version, migration.name);
+    }
+
+    Ok(())
+}
+
+/// Fresh in-memory database with the full schema and every migration applied.
+#[cfg(test)]
+pub(crate) fn test_connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    schema::create_tables(&conn).unwrap();
+    run_migrations(&conn).unwrap();
+    conn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_corruption;
+    use rusqlite::ffi;
+
+    fn failure(code: i32) -> rusqlite::Error {
+        rusqlite::Error::SqliteFailure(ffi::Error::new(code), None)
+    }
+
+    #[test]
+    fn only_corruption_sets_the_wal_aside() {
+        assert!(is_corruption(&failure(ffi::SQLITE_CORRUPT)));
+        assert!(is_corruption(&failure(ffi::SQLITE_NOTADB)));
+        assert!(!is_corruption(&failure(ffi::SQLITE_BUSY)));
+        assert!(!is_corruption(&failure(ffi::SQLITE_LOCKED)));
+        assert!(!is_corruption(&failure(ffi::SQLITE_CANTOPEN)));
+        assert!(!is_corruption(&failure(ffi::SQLITE_PERM)));
+        assert!(!is_corruption(&rusqlite::Error::QueryReturnedNoRows));
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::Result;
 use rusqlite::Connection;
-use crate::models::{Activity, ActivityStats, AppStat, CategoryStat};
+use crate::models::{
+    Activity, ActivityStats, AppMerge, AppStat, CategoryStat, GroupedStatRow, GroupedStats, MergedSession,
+    ArtistStat, DailyTotal, ListeningStats, MediaKind, ProcessSession, TrackStat, TrackingGap,
+};
 
 pub fn get_activities(
     conn: &Connection,
@@ -42,6 +45,46 @@ pub fn get_activities(
     Ok(activities)
 }
 
+/// Specific rows by id, newest first. Private rows are left out unless `include_private`.
+pub fn get_activities_by_ids(
+    conn: &Connection,
+    ids: &[i64],
+    include_private: bool,
+) -> Result<Vec<Activity>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let private_clause = if include_private { "" } else { "AND COALESCE(private, 0) = 0" };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, app_name, app_hash, window_title, window_title_hash,
+                category_id, start_time, end_time, duration_seconds, metadata
+         FROM activities
+         WHERE id IN ({}) {}
+         ORDER BY start_time DESC",
+        placeholders, private_clause
+    ))?;
+
+    let activities = stmt.query_map(rusqlite::params_from_iter(ids.iter()), |row| {
+        Ok(Activity {
+            id: row.get(0)?,
+            app_name: row.get(1)?,
+            app_hash: row.get::<_, i64>(2)? as u64,
+            window_title: row.get::<_, String>(3).unwrap_or_default(),
+            window_title_hash: row.get::<_, i64>(4).unwrap_or(0) as u64,
+            category_id: row.get(5)?,
+            start_time: row.get(6)?,
+            end_time: row.get(7)?,
+            duration_seconds: row.get(8)?,
+            metadata: row.get::<_, Option<Vec<u8>>>(9)?.map(|b| {
+                serde_json::from_slice(&b).unwrap_or_default()
+            }),
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+
+    Ok(activities)
+}
+
 /// Search activities by title/app keywords at the SQL level.
 /// Efficient for broad queries spanning many days (e.g., "all songs I've heard").
 pub fn search_activities(
@@ -128,6 +171,12 @@ pub fn search_activities(
     Ok(activities)
 }
 
+/// SQL expression for an activity row's app name after `app_merge_map`, so
+/// renamed or replaced apps are counted together without rewriting history.
+/// Like `rename_app`'s matching it ignores case, but only ASCII case here.
+pub const MERGED_APP_NAME_SQL: &str = "COALESCE((SELECT m.to_name FROM app_merge_map m \
+     WHERE m.from_name = LOWER(activities.app_name) AND m.search_only = 0), activities.app_name)";
+
 pub fn get_activity_stats(
     conn: &Connection,
     start_time: i64,
@@ -143,14 +192,15 @@ pub fn get_activity_stats(
     )?;
 
     // Get top apps
-    let mut stmt = conn.prepare(
-        "SELECT app_name, SUM(duration_seconds) as duration, COUNT(*) as count
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} AS app, SUM(duration_seconds) as duration, COUNT(*) as count
          FROM activities 
          WHERE start_time >= ?1 AND end_time <= ?2
-         GROUP BY app_name
+         GROUP BY app
          ORDER BY duration DESC
-         LIMIT 10"
-    )?;
+         LIMIT 10",
+        MERGED_APP_NAME_SQL
+    ))?;
 
     let top_apps: Vec<AppStat> = stmt.query_map([start_time, end_time], |row| {
         let duration: i64 = row.get(1)?;
@@ -202,6 +252,400 @@ pub fn get_activity_stats(
     })
 }
 
+const GROUPED_STATS_MAX_TOP_N: usize = 100;
+const GROUPED_TITLE_LABEL_CHARS: usize = 120;
+const WEEKDAY_LABELS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Totals per app/category/title/hour/day/weekday in one GROUP BY.
+/// Time groupings use each row's local time; titles are grouped by their
+/// stored hash so long or numerous titles never leave the database whole.
+pub fn get_grouped_stats(
+    conn: &Connection,
+    start_time: i64,
+    end_time: i64,
+    group_by: &str,
+    metric: &str,
+    top_n: usize,
+    include_other_bucket: bool,
+) -> Result<GroupedStats> {
+    let local = crate::utils::time::ROW_LOCAL_DATETIME_SQL;
+    let (key_sql, label_sql) = match group_by {
+        "app" => (MERGED_APP_NAME_SQL.to_string(), "''".to_string()),
+        "category" => ("CAST(category_id AS TEXT)".to_string(), "''".to_string()),
+        "title" => (
+            "CAST(window_title_hash AS TEXT)".to_string(),
+            format!("substr(MAX(window_title), 1, {})", GROUPED_TITLE_LABEL_CHARS),
+        ),
+        "hour" => (format!("strftime('%H', {})", local), "''".to_string()),
+        "day" => (format!("date({})", local), "''".to_string()),
+        "weekday" => (format!("strftime('%w', {})", local), "''".to_string()),
+        other => anyhow::bail!("Unsupported group_by '{}'", other),
+    };
+    let value_sql = match metric {
+        "duration" => "COALESCE(SUM(duration_seconds), 0)",
+        "sessions" => "COUNT(*)",
+        other => anyhow::bail!("Unsupported metric '{}'", other),
+    };
+    let top_n = top_n.clamp(1, GROUPED_STATS_MAX_TOP_N);
+
+    let total: i64 = conn.query_row(
+        &format!(
+            "SELECT {} FROM activities WHERE start_time >= ?1 AND end_time <= ?2",
+            value_sql
+        ),
+        [start_time, end_time],
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {key} AS bucket, {label} AS label, {value} AS value
+         FROM activities
+         WHERE start_time >= ?1 AND end_time <= ?2
+         GROUP BY bucket
+         ORDER BY value DESC, bucket ASC
+         LIMIT ?3",
+        key = key_sql,
+        label = label_sql,
+        value = value_sql,
+    ))?;
+    let buckets = stmt
+        .query_map(rusqlite::params![start_time, end_time, top_n as i64], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                row.get::<_, i64>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let share = |value: i64| {
+        if total > 0 {
+            value as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    let mut rows: Vec<GroupedStatRow> = buckets
+        .into_iter()
+        .map(|(key, label, value)| {
+            let label = match group_by {
+                "category" => key
+                    .parse::<i64>()
+                    .map(crate::utils::categories::name_for)
+                    .unwrap_or_else(|_| "Other".to_string()),
+                "hour" => format!("{}:00", key),
+                "weekday" => key
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|d| WEEKDAY_LABELS.get(d))
+                    .map(|d| d.to_string())
+                    .unwrap_or_else(|| key.clone()),
+                "day" | "app" => key.clone(),
+                _ => label,
+            };
+            GroupedStatRow {
+                share: share(value),
+                key,
+                label,
+                value,
+            }
+        })
+        .collect();
+
+    // Time buckets read better in chronological order than by size.
+    if matches!(group_by, "hour" | "day" | "weekday") {
+        rows.sort_by(|a, b| a.key.cmp(&b.key));
+    }
+
+    // The remainder is computed from the total, so shares always add up to 100%.
+    let covered: i64 = rows.iter().map(|r| r.value).sum();
+    let remainder = total - covered;
+    if include_other_bucket && remainder > 0 {
+        rows.push(GroupedStatRow {
+            key: "other".to_string(),
+            label: "Other".to_string(),
+            value: remainder,
+            share: share(remainder),
+        });
+    }
+
+    Ok(GroupedStats {
+        group_by: group_by.to_string(),
+        metric: metric.to_string(),
+        total,
+        rows,
+    })
+}
+
+/// Activity rows in `[start_ts, end_ts)`, oldest first, with consecutive rows of
+/// the same app joined when at most `max_gap_secs` apart. Window switches within
+/// an app (tabs, files) therefore become one span.
+pub fn get_merged_sessions(
+    conn: &Connection,
+    start_ts: i64,
+    end_ts: i64,
+    max_gap_secs: i64,
+) -> Result<Vec<MergedSession>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, app_name, COALESCE(window_title, ''), category_id, start_time, end_time,
+                duration_seconds, COALESCE(private, 0)
+         FROM activities
+         WHERE end_time > ?1 AND start_time < ?2
+         ORDER BY start_time ASC, id ASC",
+    )?;
+    let rows = stmt.query_map([start_ts, end_ts], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i32>(3)?,
+            row.get::<_, i64>(4)?,
+            row.get::<_, i64>(5)?,
+            row.get::<_, i64>(6)?,
+            row.get::<_, i64>(7)? != 0,
+        ))
+    })?;
+
+    let mut sessions: Vec<MergedSession> = Vec::new();
+    // Duration of the row that currently names the last session.
+    let mut longest_part: i64 = 0;
+    for (id, app_name, title, category_id, start, end, duration, private) in rows.filter_map(|r| r.ok()) {
+        if let Some(last) = sessions.last_mut() {
+            if last.app_name == app_name && start - last.end_time <= max_gap_secs {
+                last.end_time = last.end_time.max(end);
+                last.duration_seconds += duration;
+                last.activity_count += 1;
+                last.private |= private;
+                if duration > longest_part {
+                    longest_part = duration;
+                    last.window_title = title;
+                    last.category_id = category_id;
+                }
+                continue;
+            }
+        }
+        longest_part = duration;
+        sessions.push(MergedSession {
+            first_activity_id: id,
+            app_name,
+            window_title: title,
+            category_id,
+            start_time: start,
+            end_time: end,
+            duration_seconds: duration,
+            activity_count: 1,
+            private,
+        });
+    }
+    Ok(sessions)
+}
+
+/// Runs of watched processes overlapping `[start_ts, end_ts)`, oldest first,
+/// optionally only those whose name contains `app` (case-insensitive).
+pub fn get_process_sessions(
+    conn: &Connection,
+    app: Option<&str>,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<Vec<ProcessSession>> {
+    let now = chrono::Utc::now().timestamp();
+    let pattern = app
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(|a| format!("%{}%", a.to_lowercase()));
+    let mut stmt = conn.prepare(
+        "SELECT id, process_name, pid, parent_pid, parent_name, start_time, end_time
+         FROM process_events
+         WHERE start_time < ?2 AND COALESCE(end_time, ?3) > ?1
+           AND (?4 IS NULL OR LOWER(process_name) LIKE ?4)
+         ORDER BY start_time ASC, id ASC",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![start_ts, end_ts, now, pattern], |row| {
+        let start_time: i64 = row.get(5)?;
+        let end_time: Option<i64> = row.get(6)?;
+        Ok(ProcessSession {
+            id: row.get(0)?,
+            process_name: row.get(1)?,
+            pid: row.get(2)?,
+            parent_pid: row.get(3)?,
+            parent_name: row.get(4)?,
+            start_time,
+            end_time,
+            duration_seconds: (end_time.unwrap_or(now) - start_time).max(0),
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Stretches of `[start_ts, end_ts)` at least `min_gap_secs` long that no activity overlaps.
+pub fn get_tracking_gaps(
+    conn: &Connection,
+    start_ts: i64,
+    end_ts: i64,
+    min_gap_secs: i64,
+) -> Result<Vec<TrackingGap>> {
+    let mut stmt = conn.prepare(
+        "SELECT start_time, end_time FROM activities
+         WHERE start_time < ?2 AND end_time > ?1
+         ORDER BY start_time ASC",
+    )?;
+    let sessions = stmt
+        .query_map([start_ts, end_ts], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut gaps = Vec::new();
+    let mut push_gap = |start: i64, end: i64| {
+        if end - start >= min_gap_secs {
+            gaps.push(TrackingGap { start, end, duration: end - start });
+        }
+    };
+    // Sessions can overlap, so track the furthest end covered so far.
+    let mut covered_until = start_ts;
+    for (start, end) in sessions {
+        if start > covered_until {
+            push_gap(covered_until, start);
+        }
+        covered_until = covered_until.max(end);
+    }
+    if end_ts > covered_until {
+        push_gap(covered_until, end_ts);
+    }
+    Ok(gaps)
+}
+
+const LISTENING_TOP_TRACKS: usize = 20;
+const LISTENING_TOP_ARTISTS: usize = 20;
+
+/// Music played in `[start_ts, end_ts)`, summed per track, artist and local day.
+/// A session counts with its full duration when its media reading was "Playing";
+/// paused and stopped sessions, and video, don't count.
+pub fn get_listening_stats(conn: &Connection, start_ts: i64, end_ts: i64) -> Result<ListeningStats> {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+
+    // The status check skips parsing the metadata of every non-media row.
+    let sql = format!(
+        "SELECT date({}) AS day, app_name, window_title, duration_seconds, metadata
+         FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2 AND metadata IS NOT NULL
+         AND COALESCE(private, 0) = 0
+         AND instr(CAST(metadata AS TEXT), '\"status\":\"Playing\"') > 0",
+        crate::utils::time::ROW_LOCAL_DATETIME_SQL
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([start_ts, end_ts], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i64>(3)?,
+            row.get::<_, Vec<u8>>(4)?,
+        ))
+    })?;
+
+    // (title, artist) -> (seconds, sessions, seconds per app)
+    let mut tracks: HashMap<(String, String), (i64, i64, HashMap<String, i64>)> = HashMap::new();
+    let mut by_day: BTreeMap<String, i64> = BTreeMap::new();
+    let mut total_play_seconds = 0;
+    for (day, app_name, window_title, duration_seconds, blob) in rows.filter_map(|r| r.ok()) {
+        let Ok(meta) = serde_json::from_slice::<crate::models::ActivityMetadata>(&blob) else {
+            continue;
+        };
+        let Some(media) = meta.media_info.as_ref() else { continue };
+        if !media.status.eq_ignore_ascii_case("playing") {
+            continue;
+        }
+        let kind = meta.media_kind.unwrap_or_else(|| {
+            crate::utils::media::classify_media(media, &app_name, &window_title, duration_seconds)
+        });
+        if kind != MediaKind::Music {
+            continue;
+        }
+
+        let player = media.source_app.as_deref().unwrap_or(&app_name);
+        let app = crate::utils::media::player_display_name(player).to_string();
+        let entry = tracks
+            .entry((media.title.trim().to_string(), media.artist.trim().to_string()))
+            .or_default();
+        entry.0 += duration_seconds;
+        entry.1 += 1;
+        *entry.2.entry(app).or_insert(0) += duration_seconds;
+        *by_day.entry(day).or_insert(0) += duration_seconds;
+        total_play_seconds += duration_seconds;
+    }
+
+    let mut artists: HashMap<String, (i64, HashSet<String>)> = HashMap::new();
+    for ((title, artist), (seconds, _, _)) in &tracks {
+        if artist.is_empty() {
+            continue;
+        }
+        let entry = artists.entry(artist.clone()).or_default();
+        entry.0 += seconds;
+        entry.1.insert(title.clone());
+    }
+
+    let mut top_tracks: Vec<TrackStat> = tracks
+        .into_iter()
+        .map(|((title, artist), (play_seconds, sessions, apps))| TrackStat {
+            title,
+            artist,
+            app: apps.into_iter().max_by_key(|(_, secs)| *secs).map(|(app, _)| app).unwrap_or_default(),
+            play_seconds,
+            sessions,
+        })
+        .collect();
+    top_tracks.sort_by(|a, b| b.play_seconds.cmp(&a.play_seconds).then_with(|| a.title.cmp(&b.title)));
+    top_tracks.truncate(LISTENING_TOP_TRACKS);
+
+    let mut top_artists: Vec<ArtistStat> = artists
+        .into_iter()
+        .map(|(artist, (play_seconds, titles))| ArtistStat {
+            artist,
+            play_seconds,
+            tracks: titles.len() as i64,
+        })
+        .collect();
+    top_artists.sort_by(|a, b| b.play_seconds.cmp(&a.play_seconds).then_with(|| a.artist.cmp(&b.artist)));
+    top_artists.truncate(LISTENING_TOP_ARTISTS);
+
+    // One entry per calendar day so quiet days still show up in the chart.
+    let local_date = |ts: i64| {
+        chrono::DateTime::from_timestamp(ts, 0)
+            .map(|dt| dt.with_timezone(&crate::utils::time::offset_at(ts)).date_naive())
+    };
+    let mut days = Vec::new();
+    if let (Some(mut date), Some(last)) = (local_date(start_ts), local_date(end_ts - 1)) {
+        while date <= last {
+            let date_key = date.format("%Y-%m-%d").to_string();
+            let seconds = by_day.get(&date_key).copied().unwrap_or(0);
+            days.push(DailyTotal { date_key, seconds });
+            date += chrono::Duration::days(1);
+        }
+    }
+
+    Ok(ListeningStats {
+        top_tracks,
+        top_artists,
+        total_play_seconds,
+        by_day: days,
+    })
+}
+
+pub fn get_app_merges(conn: &Connection) -> Result<Vec<AppMerge>> {
+    let mut stmt = conn.prepare(
+        "SELECT from_name, to_name, search_only, created_at FROM app_merge_map ORDER BY to_name, from_name",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(AppMerge {
+            from_name: row.get(0)?,
+            to_name: row.get(1)?,
+            search_only: row.get(2)?,
+            created_at: row.get(3)?,
+        })
+    })?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
 #[allow(dead_code)]
 pub fn insert_activity(conn: &Connection, activity: &crate::models::ActivityEvent) -> Result<i64> {
     let _metadata = serde_json::to_vec(&activity.metadata)?;
@@ -225,3 +669,107 @@ pub fn insert_activity(conn: &Connection, activity: &crate::models::ActivityEven
 
     Ok(conn.last_insert_rowid())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+
+    const T0: i64 = 1_767_225_600;
+
+
+    fn insert(conn: &Connection, app: &str, title: &str, start: i64, seconds: i64, tz_offset_minutes: i64) {
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, window_title_hash, category_id, start_time, end_time, duration_seconds, tz_offset_minutes)
+             VALUES (?1, 1, ?2, length(?2), 1, ?3, ?3 + ?4, ?4, ?5)",
+            rusqlite::params![app, title, start, seconds, tz_offset_minutes],
+        )
+        .unwrap();
+    }
+
+    fn shares_sum(stats: &GroupedStats) -> f64 {
+        stats.rows.iter().map(|r| r.share).sum()
+    }
+
+    #[test]
+    fn other_bucket_holds_everything_past_top_n() {
+        let conn = test_connection();
+        for (i, (app, seconds)) in [("Code", 500), ("Chrome", 300), ("Slack", 100), ("Spotify", 60), ("Notes", 40)]
+            .into_iter()
+            .enumerate()
+        {
+            insert(&conn, app, "w", T0 + i as i64 * 1000, seconds, 0);
+        }
+
+        let stats = get_grouped_stats(&conn, T0, T0 + 10_000, "app", "duration", 2, true).unwrap();
+        assert_eq!(stats.total, 1000);
+        let rows: Vec<(&str, i64)> = stats.rows.iter().map(|r| (r.key.as_str(), r.value)).collect();
+        // The seeded merges count both under their full names.
+        assert_eq!(rows, vec![("Visual Studio Code", 500), ("Google Chrome", 300), ("other", 200)]);
+        assert_eq!(stats.rows[2].label, "Other");
+        assert_eq!(stats.rows.iter().map(|r| r.share).collect::<Vec<_>>(), vec![50.0, 30.0, 20.0]);
+
+        let without = get_grouped_stats(&conn, T0, T0 + 10_000, "app", "duration", 2, false).unwrap();
+        assert_eq!(without.rows.len(), 2);
+        assert_eq!(shares_sum(&without), 80.0);
+    }
+
+    #[test]
+    fn shares_add_up_without_an_empty_other_bucket() {
+        let conn = test_connection();
+        insert(&conn, "Code", "w", T0, 100, 0);
+        insert(&conn, "Chrome", "w", T0 + 200, 100, 0);
+        insert(&conn, "Slack", "w", T0 + 400, 100, 0);
+
+        let stats = get_grouped_stats(&conn, T0, T0 + 1000, "app", "sessions", 10, true).unwrap();
+        assert_eq!(stats.total, 3);
+        assert!(stats.rows.iter().all(|r| r.key != "other"));
+        assert!((shares_sum(&stats) - 100.0).abs() < 1e-9);
+
+        let empty = get_grouped_stats(&conn, T0 + 5000, T0 + 6000, "app", "duration", 10, true).unwrap();
+        assert_eq!(empty.total, 0);
+        assert!(empty.rows.is_empty());
+    }
+
+    #[test]
+    fn title_buckets_group_by_hash_with_a_short_label() {
+        let conn = test_connection();
+        let long_title = "x".repeat(500);
+        insert(&conn, "Code", &long_title, T0, 100, 0);
+        insert(&conn, "Code", &long_title, T0 + 200, 50, 0);
+        insert(&conn, "Code", "short", T0 + 400, 30, 0);
+
+        let stats = get_grouped_stats(&conn, T0, T0 + 1000, "title", "duration", 1, true).unwrap();
+        assert_eq!(stats.rows.len(), 2);
+        assert_eq!(stats.rows[0].key, "500");
+        assert_eq!(stats.rows[0].value, 150);
+        assert_eq!(stats.rows[0].label.chars().count(), GROUPED_TITLE_LABEL_CHARS);
+        assert_eq!(stats.rows[1].key, "other");
+        assert_eq!(stats.rows[1].value, 30);
+    }
+
+    #[test]
+    fn hour_buckets_use_each_rows_local_time_in_order() {
+        let conn = test_connection();
+        // T0 is 00:00 UTC: 09:00 at UTC+9 and 19:00 the previous day at UTC-5.
+        insert(&conn, "Code", "w", T0, 600, 9 * 60);
+        insert(&conn, "Code", "w", T0 + 60, 1200, -5 * 60);
+        insert(&conn, "Code", "w", T0 + 3600, 200, 0);
+
+        let stats = get_grouped_stats(&conn, T0, T0 + 7200, "hour", "duration", 10, true).unwrap();
+        let rows: Vec<(&str, i64)> = stats.rows.iter().map(|r| (r.label.as_str(), r.value)).collect();
+        assert_eq!(rows, vec![("01:00", 200), ("09:00", 600), ("19:00", 1200)]);
+
+        let stats = get_grouped_stats(&conn, T0, T0 + 7200, "weekday", "sessions", 10, true).unwrap();
+        // 2026-01-01 is a Thursday.
+        let rows: Vec<(&str, i64)> = stats.rows.iter().map(|r| (r.label.as_str(), r.value)).collect();
+        assert_eq!(rows, vec![("Wed", 1), ("Thu", 2)]);
+    }
+
+    #[test]
+    fn unknown_groupings_are_rejected() {
+        let conn = test_connection();
+        assert!(get_grouped_stats(&conn, 0, 1, "month", "duration", 5, true).is_err());
+        assert!(get_grouped_stats(&conn, 0, 1, "app", "average", 5, true).is_err());
+    }
+}
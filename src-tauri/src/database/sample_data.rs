@@ -0,0 +1,395 @@
+use anyhow::Result;
+use rusqlite::Connection;
+
+use crate::models::{ActivityEvent, MediaInfo, SampleDataSummary};
+
+// ─── Sample data ───
+// A scripted working day of fictional activity so a fresh install can show
+// the dashboard, timeline and chat without hours of real tracking first.
+
+const SAMPLE_PROJECT_ROOT: &str = "C:\\Users\\demo\\Projects\\orbit-notes";
+
+struct SampleSession {
+    app: &'static str,
+    title: &'static str,
+    category_id: i32,
+    minutes: i64,
+    url: Option<&'static str>,
+    screen_text: Option<&'static str>,
+    /// (title, artist, source app) of a media session playing in the background.
+    media: Option<(&'static str, &'static str, &'static str)>,
+}
+
+const fn session(app: &'static str, title: &'static str, category_id: i32, minutes: i64) -> SampleSession {
+    SampleSession {
+        app,
+        title,
+        category_id,
+        minutes,
+        url: None,
+        screen_text: None,
+        media: None,
+    }
+}
+
+fn sample_day() -> Vec<SampleSession> {
+    vec![
+        SampleSession {
+            screen_text: Some("Inbox (12) — Standup moved to 10:15. Design review notes attached. Invoice #2291 from Northwind Cloud."),
+            ..session("Outlook.exe", "Inbox - dana@orbitlabs.dev - Outlook", 3, 14)
+        },
+        SampleSession {
+            screen_text: Some("#orbit-dev  Priya: pushed the sync fix, can someone review?  Marco: on it after standup"),
+            ..session("Slack.exe", "orbit-dev | Orbit Labs - Slack", 3, 9)
+        },
+        SampleSession {
+            screen_text: Some("fn merge_notes(local: &[Note], remote: &[Note]) -> Vec<Note> { // TODO: conflict markers"),
+            media: Some(("Midnight City", "M83", "Spotify.exe")),
+            ..session("Code.exe", "sync.rs - orbit-notes - Visual Studio Code", 1, 48)
+        },
+        SampleSession {
+            url: Some("https://github.com/orbit-labs/orbit-notes/pull/214"),
+            screen_text: Some("Fix duplicate notes after offline sync #214 — 3 files changed, 2 approvals pending"),
+            ..session("chrome.exe", "Fix duplicate notes after offline sync #214 - Google Chrome", 2, 12)
+        },
+        SampleSession {
+            screen_text: Some("PS C:\\Users\\demo\\Projects\\orbit-notes> cargo test sync:: — test result: ok. 18 passed"),
+            ..session("WindowsTerminal.exe", "cargo test - Windows Terminal", 1, 7)
+        },
+        SampleSession {
+            screen_text: Some("Standup — Dana, Priya, Marco, Lee. Sync fix ready for review; release candidate Thursday."),
+            ..session("Teams.exe", "Daily standup | Microsoft Teams", 3, 16)
+        },
+        SampleSession {
+            screen_text: Some("Q3 roadmap: offline-first sync, shared notebooks, mobile widgets. Owner: Dana. Due: Sep 30"),
+            ..session("Notion.exe", "Q3 Roadmap - Notion", 5, 22)
+        },
+        SampleSession {
+            url: Some("https://docs.rs/tokio/latest/tokio/sync/struct.Mutex.html"),
+            screen_text: Some("Struct tokio::sync::Mutex — An asynchronous Mutex-like type."),
+            ..session("chrome.exe", "Mutex in tokio::sync - Rust - Google Chrome", 2, 6)
+        },
+        SampleSession {
+            screen_text: Some("impl ConflictResolver { pub fn resolve(&self, a: &Note, b: &Note) -> Resolution {"),
+            media: Some(("Intro", "The xx", "Spotify.exe")),
+            ..session("Code.exe", "conflict.rs - orbit-notes - Visual Studio Code", 1, 55)
+        },
+        SampleSession {
+            url: Some("https://www.youtube.com/watch?v=sample-lofi"),
+            media: Some(("lofi beats to code to", "Chillhop Radio", "chrome")),
+            ..session("chrome.exe", "lofi beats to code to - YouTube - Google Chrome", 4, 18)
+        },
+        SampleSession {
+            screen_text: Some("Priya: lunch at 1? Lee: sure, the usual place  Marco: 👍"),
+            ..session("WhatsApp.exe", "WhatsApp", 3, 5)
+        },
+        SampleSession {
+            screen_text: Some("Release checklist: bump version, update changelog, tag v0.9.0-rc1, notify beta testers"),
+            ..session("Obsidian.exe", "Release checklist - orbit-vault - Obsidian", 5, 11)
+        },
+        SampleSession {
+            screen_text: Some("describe('sync', () => { it('keeps both edits when titles differ', ..."),
+            media: Some(("Breathe", "Télépopmusik", "Spotify.exe")),
+            ..session("Code.exe", "sync.test.ts - orbit-notes - Visual Studio Code", 1, 37)
+        },
+        session("explorer.exe", "Projects - File Explorer", 6, 3),
+        SampleSession {
+            url: Some("https://mail.google.com/mail/u/0/#inbox"),
+            screen_text: Some("Re: Beta feedback — \"Offline mode is great, but search feels slow on 10k notes\""),
+            ..session("chrome.exe", "Inbox - Gmail - Google Chrome", 2, 8)
+        },
+        SampleSession {
+            screen_text: Some("Changelog v0.9.0-rc1: faster offline sync, conflict resolution, fixed duplicate notes"),
+            ..session("Code.exe", "CHANGELOG.md - orbit-notes - Visual Studio Code", 1, 14)
+        },
+        SampleSession {
+            screen_text: Some("#release  Dana: rc1 tagged 🎉  Lee: starting smoke tests"),
+            ..session("Slack.exe", "release | Orbit Labs - Slack", 3, 6)
+        },
+    ]
+}
+
+/// (relative path, change type, minutes into the day, preview)
+const SAMPLE_FILE_EVENTS: [(&str, &str, i64, Option<&str>); 8] = [
+    ("src\\sync.rs", "modified", 40, Some("fn merge_notes(local: &[Note], remote: &[Note])")),
+    ("src\\sync.rs", "modified", 62, None),
+    ("src\\conflict.rs", "created", 150, Some("pub struct ConflictResolver")),
+    ("src\\conflict.rs", "modified", 190, None),
+    ("tests\\sync.test.ts", "created", 262, Some("describe('sync', () => {")),
+    ("tests\\sync.test.ts", "modified", 290, None),
+    ("CHANGELOG.md", "modified", 318, Some("## v0.9.0-rc1")),
+    ("Cargo.toml", "modified", 322, Some("version = \"0.9.0-rc1\"")),
+];
+
+/// Tables `generate_demo_data` rebuilds from scratch.
+const DEMO_TABLES: [&str; 6] = [
+    "activities",
+    "code_file_events",
+    "chat_messages",
+    "chat_sessions",
+    "dashboard_snapshots",
+    "patterns",
+];
+
+/// (role, content, seconds after the session starts)
+const DEMO_CHAT: [(&str, &str, i64); 4] = [
+    ("user", "What did I work on this morning?", 0),
+    (
+        "assistant",
+        "You spent most of the morning in VS Code on orbit-notes, mainly sync.rs and conflict.rs (about 1h 45m), \
+         with a 16 minute standup in Teams and a review of PR #214 in Chrome.",
+        6,
+    ),
+    ("user", "Who did I talk to about the release?", 60),
+    (
+        "assistant",
+        "Dana tagged v0.9.0-rc1 in #release on Slack, and Lee said they were starting smoke tests. \
+         Priya and Marco were in the morning standup where the Thursday release candidate was agreed.",
+        66,
+    ),
+];
+
+/// Write one scripted day ending at `end_ts`.
+pub fn insert_sample_day(conn: &Connection, end_ts: i64) -> Result<SampleDataSummary> {
+    let tx = conn.unchecked_transaction()?;
+    let summary = insert_day(&tx, end_ts, 0)?;
+    tx.commit()?;
+    Ok(summary)
+}
+
+/// Replace the demo tables with `days` scripted days ending now, a chat
+/// session and today's dashboard snapshot. Only meant for the demo database.
+pub fn generate_demo_data(conn: &Connection, days: i64) -> Result<SampleDataSummary> {
+    let days = days.clamp(1, 30);
+    let now = chrono::Utc::now().timestamp();
+    let today = chrono::Local::now().date_naive();
+
+    let tx = conn.unchecked_transaction()?;
+    for table in DEMO_TABLES {
+        tx.execute(&format!("DELETE FROM {}", table), [])?;
+    }
+
+    let mut summary = SampleDataSummary::default();
+    for back in (0..days).rev() {
+        // Past days end in the early evening; today ends now.
+        let end_ts = if back == 0 {
+            now
+        } else {
+            (today - chrono::Duration::days(back))
+                .and_hms_opt(17, 30, 0)
+                .and_then(|dt| dt.and_local_timezone(chrono::Local).earliest())
+                .map(|dt| dt.timestamp())
+                .unwrap_or(now - back * 86_400)
+        };
+        let day = insert_day(&tx, end_ts, back as usize)?;
+        summary.activities += day.activities;
+        summary.file_events += day.file_events;
+        summary.media_sessions += day.media_sessions;
+        summary.ocr_snapshots += day.ocr_snapshots;
+        if summary.start_time == 0 {
+            summary.start_time = day.start_time;
+        }
+        summary.end_time = day.end_time;
+    }
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let chat_start = now - 45 * 60;
+    tx.execute(
+        "INSERT INTO chat_sessions (id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![&session_id, "Morning recap", chat_start, chat_start + 66],
+    )?;
+    for (role, content, offset) in DEMO_CHAT {
+        tx.execute(
+            "INSERT INTO chat_messages (session_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![&session_id, role, content, chat_start + offset],
+        )?;
+    }
+    summary.chat_sessions = 1;
+    tx.commit()?;
+
+    crate::services::dashboard_engine::write_local_snapshot(conn).map_err(anyhow::Error::msg)?;
+    Ok(summary)
+}
+
+/// Insert one day of the script. Variant 0 is the full script; other variants
+/// drop some sessions and stretch the rest so past days don't look identical.
+fn insert_day(conn: &Connection, end_ts: i64, variant: usize) -> Result<SampleDataSummary> {
+    let sessions: Vec<SampleSession> = sample_day()
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| variant == 0 || (i + variant) % 4 != 0)
+        .map(|(i, mut sample)| {
+            if variant > 0 {
+                let percent = 75 + ((i * 7 + variant * 13) % 51) as i64;
+                sample.minutes = (sample.minutes * percent / 100).max(2);
+            }
+            sample
+        })
+        .collect();
+    let total_secs: i64 = sessions.iter().map(|s| s.minutes * 60).sum();
+    let day_start = end_ts - total_secs;
+    let offset_minutes = crate::utils::time::system_offset_minutes();
+
+    let mut summary = SampleDataSummary::default();
+    let mut cursor = day_start;
+    for sample in &sessions {
+        let end = cursor + sample.minutes * 60;
+        let mut activity = ActivityEvent::new(
+            sample.app.to_string(),
+            sample.title.to_string(),
+            sample.category_id,
+            cursor,
+            end,
+        );
+        activity.metadata.url = sample.url.map(str::to_string);
+        activity.metadata.screen_text = sample.screen_text.map(str::to_string);
+        activity.metadata.raw_duration_ms = Some(sample.minutes * 60_000);
+        activity.metadata.media_info = sample.media.map(|(title, artist, source)| MediaInfo {
+            title: title.to_string(),
+            artist: artist.to_string(),
+            status: "Playing".to_string(),
+            source_app: Some(source.to_string()),
+        });
+        activity.metadata.media_kind = activity.metadata.media_info.as_ref().map(|media| {
+            crate::utils::media::classify_media(media, sample.app, sample.title, sample.minutes * 60)
+        });
+
+        conn.execute(
+            "INSERT INTO activities
+             (app_name, app_hash, window_title, window_title_hash, category_id,
+              start_time, end_time, duration_seconds, metadata, tz_offset_minutes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                &activity.app_name,
+                activity.app_hash as i64,
+                &activity.window_title,
+                activity.window_title_hash as i64,
+                activity.category_id,
+                activity.start_time,
+                activity.end_time,
+                activity.duration_seconds,
+                serde_json::to_vec(&activity.metadata)?,
+                offset_minutes,
+            ],
+        )?;
+        summary.activities += 1;
+        if sample.screen_text.is_some() {
+            summary.ocr_snapshots += 1;
+        }
+        if sample.media.is_some() {
+            summary.media_sessions += 1;
+        }
+        cursor = end;
+    }
+
+    for (relative, change_type, minute, preview) in SAMPLE_FILE_EVENTS {
+        conn.execute(
+            "INSERT INTO code_file_events (path, project_root, entity_type, change_type, content_preview, detected_at)
+             VALUES (?1, ?2, 'file', ?3, ?4, ?5)",
+            rusqlite::params![
+                format!("{}\\{}", SAMPLE_PROJECT_ROOT, relative),
+                SAMPLE_PROJECT_ROOT,
+                change_type,
+                preview,
+                (day_start + minute * 60).min(end_ts),
+            ],
+        )?;
+        summary.file_events += 1;
+    }
+
+    summary.start_time = day_start;
+    summary.end_time = end_ts;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demo_db() -> Connection {
+        let conn = crate::database::test_connection();
+        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
+        conn
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn demo_rows_reference_existing_parents() {
+        let conn = demo_db();
+        let summary = generate_demo_data(&conn, 7).unwrap();
+
+        let violations = count(&conn, "SELECT COUNT(*) FROM pragma_foreign_key_check");
+        assert_eq!(violations, 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities WHERE category_id NOT IN (SELECT id FROM categories)"), 0);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM chat_messages WHERE session_id NOT IN (SELECT id FROM chat_sessions)"),
+            0
+        );
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM chat_messages"), DEMO_CHAT.len() as i64);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM dashboard_snapshots"), 1);
+
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities"), summary.activities as i64);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM code_file_events"), summary.file_events as i64);
+        assert_eq!(summary.file_events, 7 * SAMPLE_FILE_EVENTS.len());
+    }
+
+    #[test]
+    fn demo_days_are_sequential_and_never_overlap() {
+        let conn = demo_db();
+        generate_demo_data(&conn, 5).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT start_time, end_time, duration_seconds FROM activities ORDER BY start_time")
+            .unwrap();
+        let rows: Vec<(i64, i64, i64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for (start, end, duration) in &rows {
+            assert_eq!(end - start, *duration);
+        }
+        assert!(rows.windows(2).all(|pair| pair[0].1 <= pair[1].0));
+        assert!(rows.last().unwrap().1 <= chrono::Utc::now().timestamp());
+
+        let days = count(&conn, "SELECT COUNT(DISTINCT date(start_time + tz_offset_minutes * 60, 'unixepoch')) FROM activities");
+        assert!(days >= 5, "{} days", days);
+    }
+
+    #[test]
+    fn demo_category_mix_looks_like_a_working_week() {
+        let conn = demo_db();
+        generate_demo_data(&conn, 14).unwrap();
+        let total = count(&conn, "SELECT SUM(duration_seconds) FROM activities") as f64;
+        let share = |category_id: i32| {
+            count(
+                &conn,
+                &format!("SELECT COALESCE(SUM(duration_seconds), 0) FROM activities WHERE category_id = {}", category_id),
+            ) as f64
+                / total
+        };
+        let development = share(1);
+        assert!(development > 0.35 && development < 0.6, "development {:.2}", development);
+        for other in 2..=7 {
+            assert!(share(other) < development);
+        }
+        let entertainment = share(4);
+        assert!(entertainment > 0.0 && entertainment < 0.15, "entertainment {:.2}", entertainment);
+        // A few hours of focused time per day, not a 24h stream.
+        let per_day = total / 14.0 / 3600.0;
+        assert!((2.5..=6.0).contains(&per_day), "{:.1}h per day", per_day);
+    }
+
+    #[test]
+    fn regenerating_replaces_the_previous_demo() {
+        let conn = demo_db();
+        let first = generate_demo_data(&conn, 3).unwrap();
+        let second = generate_demo_data(&conn, 3).unwrap();
+        assert_eq!(first.activities, second.activities);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM activities"), second.activities as i64);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM chat_sessions"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM dashboard_snapshots"), 1);
+    }
+}
@@ -262,7 +262,7 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
-fn ensure_column_exists(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+pub(crate) fn ensure_column_exists(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
     let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
     let columns: Vec<String> = stmt
         .query_map([], |row| row.get::<_, String>(1))?
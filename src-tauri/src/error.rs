@@ -0,0 +1,116 @@
+use serde::Serialize;
+
+use crate::database::DbError;
+
+// ─── Command errors ───
+// Serialized as `{ kind, message }` so the frontend can branch on `kind`
+// (prompt for an API key, offer a retry, show the storage banner) instead of
+// matching on message text. Build the variant where the error is raised;
+// plain strings from services that still return `Result<_, String>` are
+// `Internal`.
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum AppError {
+    /// AI is switched off or no API key is configured.
+    AiDisabled(String),
+    /// The API rejected the key (401/403).
+    ApiAuth(String),
+    /// Any other non-success response from the API.
+    Api(String),
+    /// Timeouts, DNS, refused connections.
+    Network(String),
+    /// The database could not be opened or a query failed.
+    Database(String),
+    NotFound(String),
+    InvalidInput(String),
+    Ocr(String),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::AiDisabled(m)
+            | AppError::ApiAuth(m)
+            | AppError::Api(m)
+            | AppError::Network(m)
+            | AppError::Database(m)
+            | AppError::NotFound(m)
+            | AppError::InvalidInput(m)
+            | AppError::Ocr(m)
+            | AppError::Internal(m) => m,
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal(message.to_string())
+    }
+}
+
+/// For services that still report plain strings; the kind is dropped.
+impl From<AppError> for String {
+    fn from(e: AppError) -> Self {
+        e.to_string()
+    }
+}
+
+impl From<DbError> for AppError {
+    fn from(e: DbError) -> Self {
+        AppError::Database(e.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(e.to_string()),
+            other => AppError::Database(other.to_string()),
+        }
+    }
+}
+
+/// `database::queries` reports through anyhow; a SQLite error underneath is a database error.
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        if e.downcast_ref::<rusqlite::Error>().is_some() {
+            AppError::Database(e.to_string())
+        } else {
+            AppError::Internal(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        AppError::Network(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
@@ -0,0 +1,365 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::models::LogEntry;
+
+// ─── File logging ───
+// The release build has no console, so everything sent through the `log`
+// macros also goes to `logs/intentflow.log` under the app data dir, rotated
+// by size. Lines are written unbuffered so a crash loses nothing, and every
+// line passes through secret redaction (plus the configured API key) first.
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "intentflow.log";
+/// The active file is rotated once it would grow past this.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Active file plus rotated `.1` .. `.4`.
+const MAX_LOG_FILES: usize = 5;
+/// Overrides the starting level ("error" .. "trace").
+const LEVEL_ENV_VAR: &str = "INTENTFLOW_LOG_LEVEL";
+
+struct FileLogger {
+    dir: PathBuf,
+    /// Open active file and its current size.
+    file: Mutex<Option<(File, u64)>>,
+}
+
+fn logger_store() -> &'static OnceLock<FileLogger> {
+    static LOGGER: OnceLock<FileLogger> = OnceLock::new();
+    &LOGGER
+}
+
+/// Literal secrets masked in every line (the configured API key), on top of the
+/// patterns `redact_sensitive` already catches.
+fn secrets_store() -> &'static Mutex<Vec<String>> {
+    static SECRETS: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    SECRETS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Start writing logs under `data_dir`. Safe to call more than once; only the first call counts.
+pub fn init(data_dir: &Path) {
+    let dir = data_dir.join(LOG_DIR);
+    let _ = std::fs::create_dir_all(&dir);
+    let logger = logger_store().get_or_init(|| FileLogger {
+        dir,
+        file: Mutex::new(None),
+    });
+    if log::set_logger(logger).is_err() {
+        return;
+    }
+    let level = std::env::var(LEVEL_ENV_VAR)
+        .ok()
+        .and_then(|v| parse_level(&v))
+        .unwrap_or(LevelFilter::Info);
+    log::set_max_level(level);
+    install_panic_hook();
+    log::info!("Logging to {} at level {}", logger.dir.display(), level);
+}
+
+pub fn logs_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(LOG_DIR)
+}
+
+/// Change the level at runtime. Returns the level now in effect.
+pub fn set_level(level: &str) -> Result<LevelFilter, String> {
+    let filter = parse_level(level).ok_or_else(|| format!("Unknown log level '{}'", level))?;
+    log::set_max_level(filter);
+    log::info!("Log level set to {}", filter);
+    Ok(filter)
+}
+
+/// Replace the literal secrets masked in log lines.
+pub fn set_secrets(secrets: Vec<String>) {
+    if let Ok(mut store) = secrets_store().lock() {
+        *store = secrets.into_iter().filter(|s| s.len() >= 8).collect();
+    }
+}
+
+fn parse_level(value: &str) -> Option<LevelFilter> {
+    match value.trim().to_lowercase().as_str() {
+        "off" => Some(LevelFilter::Off),
+        "error" => Some(LevelFilter::Error),
+        "warn" | "warning" => Some(LevelFilter::Warn),
+        "info" => Some(LevelFilter::Info),
+        "debug" => Some(LevelFilter::Debug),
+        "trace" => Some(LevelFilter::Trace),
+        _ => None,
+    }
+}
+
+/// Panics in background tasks would otherwise vanish with the hidden console.
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error!(target: "panic", "{}", info);
+        previous(info);
+    }));
+}
+
+fn mask(text: &str) -> String {
+    let mut out = crate::utils::redact::redact_sensitive(text);
+    if let Ok(secrets) = secrets_store().lock() {
+        for secret in secrets.iter() {
+            out = out.replace(secret.as_str(), "[REDACTED]");
+        }
+    }
+    out
+}
+
+/// `intentflow.log` for index 0, then `intentflow.log.1` (newest rotated) onwards.
+fn log_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE)
+    } else {
+        dir.join(format!("{}.{}", LOG_FILE, index))
+    }
+}
+
+impl FileLogger {
+    fn path(&self, index: usize) -> PathBuf {
+        log_path(&self.dir, index)
+    }
+
+    /// Shift `intentflow.log` -> `.1` -> ... and drop the oldest.
+    fn rotate(&self) {
+        let _ = std::fs::remove_file(self.path(MAX_LOG_FILES - 1));
+        for index in (0..MAX_LOG_FILES - 1).rev() {
+            let from = self.path(index);
+            if from.exists() {
+                let _ = std::fs::rename(&from, self.path(index + 1));
+            }
+        }
+    }
+
+    fn open_active(&self) -> Option<(File, u64)> {
+        let file = OpenOptions::new().create(true).append(true).open(self.path(0)).ok()?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Some((file, size))
+    }
+
+    fn write_line(&self, line: &str) {
+        let Ok(mut guard) = self.file.lock() else {
+            return;
+        };
+        let incoming = line.len() as u64 + 1;
+        let needs_rotation = matches!(&*guard, Some((_, size)) if *size > 0 && size + incoming > MAX_LOG_BYTES);
+        if needs_rotation {
+            *guard = None;
+            self.rotate();
+        }
+        if guard.is_none() {
+            *guard = self.open_active();
+        }
+        if let Some((file, size)) = guard.as_mut() {
+            if writeln!(file, "{}", line).is_ok() {
+                *size += incoming;
+            }
+        }
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = mask(&record.args().to_string()).replace(['\r', '\n'], " ");
+        let line = format!(
+            "{} {:<5} {}: {}",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+            record.level(),
+            record.target(),
+            message.trim()
+        );
+        println!("{}", line);
+        self.write_line(&line);
+    }
+
+    fn flush(&self) {
+        if let Ok(mut guard) = self.file.lock() {
+            if let Some((file, _)) = guard.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    let rest = rest.trim_start();
+    let (level, rest) = rest.split_once(' ')?;
+    let (target, message) = rest.trim_start().split_once(": ")?;
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message: message.to_string(),
+    })
+}
+
+/// The last `lines` entries at or above `level_filter` whose target contains
+/// `module_filter`, oldest first. Reads rotated files as far back as needed.
+pub fn read_recent(
+    data_dir: &Path,
+    lines: usize,
+    level_filter: Option<&str>,
+    module_filter: Option<&str>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = match level_filter.filter(|l| !l.trim().is_empty()) {
+        Some(level) => parse_level(level).ok_or_else(|| format!("Unknown log level '{}'", level))?,
+        None => LevelFilter::Trace,
+    };
+    let module = module_filter.map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty());
+    let dir = logs_dir(data_dir);
+
+    let mut newest_first: Vec<LogEntry> = Vec::new();
+    if lines == 0 {
+        return Ok(newest_first);
+    }
+    for index in 0..MAX_LOG_FILES {
+        let Ok(file) = File::open(log_path(&dir, index)) else {
+            continue;
+        };
+        let mut matching: Vec<LogEntry> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| parse_line(&line))
+            .filter(|entry| {
+                entry.level.parse::<Level>().map(|l| l <= min_level).unwrap_or(true)
+                    && module.as_ref().map(|m| entry.target.to_lowercase().contains(m)).unwrap_or(true)
+            })
+            .collect();
+        matching.reverse();
+        let remaining = lines - newest_first.len();
+        newest_first.extend(matching.into_iter().take(remaining));
+        if newest_first.len() >= lines {
+            break;
+        }
+    }
+    newest_first.reverse();
+    Ok(newest_first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = crate::utils::test_dir(&format!("logging-{}", name));
+        std::fs::create_dir_all(logs_dir(&dir)).unwrap();
+        dir
+    }
+
+    fn logger(data_dir: &Path) -> FileLogger {
+        FileLogger {
+            dir: logs_dir(data_dir),
+            file: Mutex::new(None),
+        }
+    }
+
+    fn read(path: PathBuf) -> String {
+        std::fs::read_to_string(path).unwrap_or_default()
+    }
+
+    #[test]
+    fn active_file_rotates_once_the_next_line_would_overflow() {
+        let data_dir = temp_dir("rotate");
+        let logger = logger(&data_dir);
+        let active = logger.path(0);
+        std::fs::write(&active, "old line\n").unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&active)
+            .unwrap()
+            .set_len(MAX_LOG_BYTES - 20)
+            .unwrap();
+        for index in 1..MAX_LOG_FILES {
+            std::fs::write(logger.path(index), format!("file {}\n", index)).unwrap();
+        }
+
+        // Still fits: appended to the active file.
+        logger.write_line("short");
+        assert_eq!(std::fs::metadata(&active).unwrap().len(), MAX_LOG_BYTES - 14);
+        assert_eq!(read(logger.path(1)), "file 1\n");
+
+        logger.write_line("this line no longer fits");
+        assert_eq!(read(logger.path(0)), "this line no longer fits\n");
+        assert!(read(logger.path(1)).starts_with("old line\n"));
+        assert_eq!(read(logger.path(2)), "file 1\n");
+        assert_eq!(read(logger.path(MAX_LOG_FILES - 1)), format!("file {}\n", MAX_LOG_FILES - 2));
+        assert!(!logger.path(MAX_LOG_FILES).exists());
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn a_single_oversized_line_goes_into_an_empty_file() {
+        let data_dir = temp_dir("oversized");
+        let logger = logger(&data_dir);
+        logger.write_line(&"x".repeat(MAX_LOG_BYTES as usize + 1));
+        assert!(!logger.path(1).exists());
+        logger.write_line("next");
+        assert_eq!(read(logger.path(0)), "next\n");
+        assert!(logger.path(1).exists());
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn recent_logs_filter_by_level_and_module_across_files() {
+        let data_dir = temp_dir("read");
+        let dir = logs_dir(&data_dir);
+        std::fs::write(
+            log_path(&dir, 1),
+            "2026-01-01T10:00:00Z ERROR intentflow::services::screen_capture: OCR failed\n\
+             2026-01-01T10:00:01Z INFO  intentflow::services::query_engine: [Agent] turn 1\n",
+        )
+        .unwrap();
+        std::fs::write(
+            log_path(&dir, 0),
+            "2026-01-01T11:00:00Z WARN  intentflow::services::screen_capture: low quality\n\
+             not a log line\n\
+             2026-01-01T11:00:01Z DEBUG intentflow::services::screen_capture: raw text\n\
+             2026-01-01T11:00:02Z ERROR panic: boom\n",
+        )
+        .unwrap();
+
+        let messages = |entries: Vec<LogEntry>| entries.into_iter().map(|e| e.message).collect::<Vec<_>>();
+        assert_eq!(
+            messages(read_recent(&data_dir, 10, Some("warn"), None).unwrap()),
+            vec!["OCR failed", "low quality", "boom"]
+        );
+        assert_eq!(
+            messages(read_recent(&data_dir, 10, Some("info"), Some("Screen_Capture")).unwrap()),
+            vec!["OCR failed", "low quality"]
+        );
+        // The newest lines win when the limit cuts, still oldest first.
+        assert_eq!(
+            messages(read_recent(&data_dir, 2, None, None).unwrap()),
+            vec!["raw text", "boom"]
+        );
+        let entry = read_recent(&data_dir, 1, Some("error"), Some("screen")).unwrap().remove(0);
+        assert_eq!(
+            (entry.timestamp.as_str(), entry.level.as_str()),
+            ("2026-01-01T10:00:00Z", "ERROR")
+        );
+        assert!(read_recent(&data_dir, 0, None, None).unwrap().is_empty());
+        assert!(read_recent(&data_dir, 5, Some("loud"), None).is_err());
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn configured_secrets_are_masked() {
+        set_secrets(vec!["sk-test-1234567890".to_string(), "short".to_string()]);
+        let masked = mask("calling with key sk-test-1234567890 and short");
+        assert!(!masked.contains("sk-test-1234567890"));
+        assert!(masked.contains("short"));
+        set_secrets(Vec::new());
+    }
+}
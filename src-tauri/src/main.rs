@@ -3,19 +3,28 @@
 
 mod commands;
 mod database;
+mod error;
+mod logging;
 mod models;
 mod services;
 mod utils;
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{Emitter, Manager};
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri_plugin_autostart::ManagerExt;
 
 static GAME_MODE_ENABLED: AtomicBool = AtomicBool::new(false);
 static INCOGNITO_ENABLED: AtomicBool = AtomicBool::new(false);
 
+pub(crate) const TRAY_ID: &str = "main";
+
+/// Tray items whose label follows state that can also change outside the tray menu.
+struct TrayToggles {
+    incognito: MenuItem<tauri::Wry>,
+}
+
 fn main() {
     utils::config::load_dotenv();
 
@@ -24,6 +33,11 @@ fn main() {
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--autostart"]),
         ))
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| services::hotkeys::handle(app, shortcut, event))
+                .build(),
+        )
         .setup(|app| {
             // Initialize database
             let app_handle = app.handle();
@@ -31,10 +45,56 @@ fn main() {
             
             // Create data directory if it doesn't exist
             std::fs::create_dir_all(&data_dir).expect("Failed to create data directory");
+            logging::init(&data_dir);
             
             // Initialize database
-            let db_path = data_dir.join("intentflow.db");
-            database::init_database(&db_path).expect("Failed to initialize database");
+            let db_path = data_dir.join(database::DB_FILE);
+            let conn = database::init_database(&db_path).expect("Failed to initialize database");
+            utils::categories::reload(&conn);
+            services::tracking_pause::load(&conn);
+            services::model_capabilities::load(&conn);
+            services::app_alerts::reload(&conn);
+
+            // Settings bus: services below subscribe to it for live updates.
+            services::settings_bus::init(app_handle, utils::config::read_settings_file(&app_handle));
+            services::settings_bus::spawn_subscriber(app_handle, "timezone", |settings| {
+                utils::time::set_timezone_mode(
+                    &settings.general.timezone_mode,
+                    &settings.general.fixed_timezone,
+                );
+                utils::time::set_time_format(&settings.general.time_format);
+            });
+            services::settings_bus::spawn_subscriber(app_handle, "logging", |settings| {
+                logging::set_secrets(vec![settings.ai.api_key.clone()]);
+            });
+            services::settings_bus::spawn_subscriber(app_handle, "answer_language", |settings| {
+                utils::i18n::set_answer_language(&settings.ai.answer_language);
+            });
+            services::settings_bus::spawn_subscriber(app_handle, "http_client", |settings| {
+                utils::http::configure(settings.ai.extra_ca_cert.as_deref());
+            });
+            services::settings_bus::spawn_subscriber(app_handle, "model_capabilities", |settings| {
+                services::model_capabilities::set_overrides(&settings.ai.model_overrides);
+                services::model_capabilities::set_profile_limits(&settings.ai.ai_profiles);
+            });
+            services::settings_bus::spawn_subscriber(app_handle, "llm_endpoints", |settings| {
+                services::llm_client::set_profile_endpoints(&settings.ai.ai_profiles);
+            });
+
+            // Demo mode swaps the database before any service below opens it.
+            let demo_flag = services::demo_mode::init_from_args();
+            let demo_setting = utils::config::load_settings(&app_handle).general.demo_mode;
+            if demo_flag || demo_setting {
+                if let Err(e) = services::demo_mode::apply(app_handle, true) {
+                    println!("[Demo] Failed to start in demo mode: {}", e);
+                }
+            }
+            let demo_app = app_handle.clone();
+            services::settings_bus::spawn_subscriber(app_handle, "demo_mode", move |settings| {
+                if let Err(e) = services::demo_mode::apply(&demo_app, settings.general.demo_mode) {
+                    println!("[Demo] Failed to switch demo mode: {}", e);
+                }
+            });
             
             // Start activity tracker
             services::activity_tracker::start_tracking(app_handle.clone());
@@ -42,6 +102,12 @@ fn main() {
             // Start screen capture + OCR service (every ~10s, non-blocking)
             services::screen_capture::start_screen_capture(app_handle.clone());
 
+            // Opt-in clipboard history (no-op unless enabled in settings)
+            services::clipboard_monitor::start_clipboard_monitor(app_handle.clone());
+
+            // Opt-in process lifetimes for the watch list (idle while it is empty)
+            services::process_monitor::start_process_monitor(app_handle.clone());
+
             // Start code file monitor (for coding-context enrichment)
             services::file_monitor::start_file_monitor(app_handle.clone());
             
@@ -51,13 +117,14 @@ fn main() {
             // Start daily dashboard engine (today-focused summaries)
             services::dashboard_engine::start_dashboard_engine(app_handle.clone());
 
+            // Weekly report scheduler (no-op unless enabled in settings)
+            services::report_engine::start_report_scheduler(app_handle.clone());
+
             // Apply startup enable/disable on Windows from settings.
             #[cfg(all(target_os = "windows", not(debug_assertions)))]
             {
                 let autostart = app_handle.autolaunch();
-                let startup_enabled = read_settings(&app_handle)
-                    .map(|s| s.general.enable_startup)
-                    .unwrap_or(true);
+                let startup_enabled = utils::config::load_settings(&app_handle).general.enable_startup;
                 if startup_enabled {
                     let _ = autostart.enable();
                 } else {
@@ -73,70 +140,174 @@ fn main() {
             apply_startup_behavior(&app_handle);
             apply_monitoring_state(&app_handle);
             setup_tray(app)?;
+            services::demo_mode::update_tray(&app_handle);
+
+            // Global hotkeys (after the tray so toggles can update its labels)
+            services::hotkeys::start(app_handle);
             
             Ok(())
         })
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 if should_close_to_tray(window.app_handle()) {
+                    services::app_lock::lock(window.app_handle());
                     let _ = window.hide();
                     api.prevent_close();
                 }
             }
         })
-        .invoke_handler(tauri::generate_handler![
-            // Activity commands
-            commands::activity::get_activities,
-            commands::activity::get_activity_stats,
-            commands::activity::get_current_activity,
-            // Query commands
-            commands::query::execute_query,
-            commands::query::get_query_history,
-            // Intent commands
-            commands::intent::parse_intent,
-            commands::intent::execute_intent,
-            // Workflow commands
-            commands::workflow::get_workflows,
-            commands::workflow::create_workflow,
-            commands::workflow::update_workflow,
-            commands::workflow::delete_workflow,
-            commands::workflow::execute_workflow,
-            commands::workflow::get_workflow_suggestions,
-            // Entry commands
-            commands::entry::create_entry,
-            commands::entry::get_entries,
-            commands::entry::update_entry_status,
-            commands::entry::delete_entry,
-            // Settings commands
-            commands::settings::get_settings,
-            commands::settings::update_settings,
-            commands::settings::get_categories,
-            commands::settings::update_categories,
-            commands::settings::get_nvidia_models,
-            // Storage commands
-            commands::storage::get_storage_stats,
-            commands::storage::cleanup_old_data,
-            commands::storage::export_data,
-            // App control commands
-            commands::app_control::minimize_to_tray,
-            commands::app_control::show_window,
-            commands::app_control::quit_app,
-            // Chat commands
-            commands::chat::create_chat_session,
-            commands::chat::get_chat_sessions,
-            commands::chat::delete_chat_session,
-            commands::chat::get_chat_messages,
-            commands::chat::send_chat_message,
-            commands::chat::get_recent_models,
-            commands::chat::remove_recent_model,
-            // Dashboard commands
-            commands::dashboard::get_dashboard_overview,
-            commands::dashboard::refresh_dashboard_overview,
-            commands::dashboard::summarize_contact,
-            commands::dashboard::summarize_project,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler({
+            let handler: fn(tauri::ipc::Invoke) -> bool = tauri::generate_handler![
+                // Activity commands
+                commands::activity::get_activities,
+                commands::activity::get_activity_stats,
+                commands::activity::get_grouped_stats,
+                commands::activity::get_related_activities,
+                commands::activity::get_current_activity,
+                commands::activity::mark_private,
+                commands::activity::get_day_boundary_debug,
+                commands::activity::get_activity_detail,
+                commands::activity::find_activity_detail,
+                commands::activity::get_activity_ocr,
+                commands::activity::get_app_directory,
+                commands::activity::get_process_sessions,
+                commands::activity::get_tracking_gaps,
+                commands::activity::get_listening_stats,
+                commands::activity::delete_last_session,
+                commands::activity::rename_app,
+                commands::activity::get_app_merges,
+                commands::activity::create_app_merge,
+                commands::activity::delete_app_merge,
+                commands::activity::apply_app_merges_to_history,
+                // Query commands
+                commands::query::execute_query,
+                commands::query::search_everything,
+                commands::query::summarize_activities,
+                commands::query::get_query_history,
+                // Intent commands
+                commands::intent::parse_intent,
+                commands::intent::execute_intent,
+                commands::intent::get_quick_actions,
+                commands::intent::record_quick_action_feedback,
+                commands::intent::set_current_focus,
+                commands::intent::get_current_focus,
+                // Pattern commands
+                commands::patterns::get_patterns,
+                commands::patterns::dismiss_pattern,
+                // Workflow commands
+                commands::workflow::get_workflows,
+                commands::workflow::create_workflow,
+                commands::workflow::update_workflow,
+                commands::workflow::delete_workflow,
+                commands::workflow::execute_workflow,
+                commands::workflow::validate_workflow,
+                commands::workflow::get_workflow_suggestions,
+                commands::alerts::get_app_alerts,
+                commands::alerts::create_app_alert,
+                commands::alerts::update_app_alert,
+                commands::alerts::delete_app_alert,
+                commands::goals::get_category_goals,
+                commands::goals::create_category_goal,
+                commands::goals::update_category_goal,
+                commands::goals::delete_category_goal,
+                commands::goals::get_goal_streaks,
+                // Entry commands
+                commands::entry::create_entry,
+                commands::entry::get_entries,
+                commands::entry::update_entry_status,
+                commands::entry::delete_entry,
+                // Settings commands
+                commands::settings::get_settings,
+                commands::settings::update_settings,
+                commands::settings::get_settings_propagation,
+                commands::settings::get_settings_warnings,
+                commands::settings::get_categories,
+                commands::settings::update_categories,
+                commands::settings::create_category,
+                commands::settings::edit_category,
+                commands::settings::delete_category,
+                commands::settings::explain_categorization,
+                commands::settings::get_nvidia_models,
+                commands::settings::probe_model_capabilities,
+                commands::settings::get_llm_usage_stats,
+                commands::profile::export_profile,
+                commands::profile::import_profile,
+                // Storage commands
+                commands::storage::get_storage_stats,
+                commands::storage::get_storage_forecast,
+                commands::storage::purge_recent_data,
+                commands::storage::cleanup_old_data,
+                commands::storage::export_data,
+                commands::storage::export_timeline_ics,
+                commands::storage::audit_keyword,
+                commands::storage::delete_activities,
+                commands::storage::verify_data_integrity,
+                commands::storage::rebuild_indexes,
+                commands::storage::cancel_index_rebuild,
+                commands::storage::purge_clipboard_history,
+                commands::storage::seed_sample_data,
+                // App control commands
+                commands::app_control::minimize_to_tray,
+                commands::app_control::show_window,
+                commands::app_control::is_app_locked,
+                commands::app_control::unlock_app,
+                commands::app_control::lock_app,
+                commands::app_control::set_unlock_password,
+                commands::app_control::get_recent_logs,
+                commands::app_control::set_log_level,
+                commands::app_control::quit_app,
+                commands::app_control::get_service_status,
+                commands::app_control::get_ocr_status,
+                commands::app_control::get_now_playing,
+                commands::app_control::pause_tracking_for,
+                commands::app_control::get_demo_mode,
+                commands::app_control::test_ocr_now,
+                // Chat commands
+                commands::chat::create_chat_session,
+                commands::chat::get_chat_sessions,
+                commands::chat::rename_chat_session,
+                commands::chat::archive_chat_session,
+                commands::chat::set_chat_session_archived,
+                commands::chat::set_chat_session_pinned,
+                commands::chat::search_chat_sessions,
+                commands::chat::delete_chat_session,
+                commands::chat::get_chat_messages,
+                commands::chat::send_chat_message,
+                commands::chat::get_recent_models,
+                commands::chat::remove_recent_model,
+                // Dashboard commands
+                commands::dashboard::get_dashboard_overview,
+                commands::dashboard::refresh_dashboard_overview,
+                commands::dashboard::mark_surface_seen,
+                commands::dashboard::get_since_last_seen,
+                commands::dashboard::get_daily_recap,
+                commands::dashboard::export_journal,
+                commands::dashboard::summarize_contact,
+                commands::dashboard::get_contact_timeline,
+                commands::dashboard::summarize_project,
+                commands::dashboard::get_project_changes,
+                commands::dashboard::get_meetings,
+                // Report commands
+                commands::reports::generate_period_report,
+            ];
+            // The webview can't be trusted to keep quiet while locked, so gate here.
+            move |invoke: tauri::ipc::Invoke| {
+                let webview = invoke.message.webview();
+                if !services::app_lock::allows_command(webview.app_handle(), invoke.message.command()) {
+                    invoke.resolver.reject(services::app_lock::LOCKED_ERROR);
+                    return true;
+                }
+                handler(invoke)
+            }
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                // Queued tracker sessions would otherwise be lost with the process.
+                services::activity_tracker::flush_pending();
+            }
+        });
 }
 
 fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
@@ -144,6 +315,19 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     let open_chat_item = MenuItem::with_id(app, "open_chat", "Open Chat", true, None::<&str>)?;
     let game_mode_item = MenuItem::with_id(app, "toggle_game_mode", "Game Mode: OFF", true, None::<&str>)?;
     let incognito_item = MenuItem::with_id(app, "toggle_incognito", "Incognito: OFF", true, None::<&str>)?;
+    let pause_menu = Submenu::with_items(
+        app,
+        "Pause Tracking",
+        true,
+        &[
+            &MenuItem::with_id(app, "pause_15", "Pause 15m", true, None::<&str>)?,
+            &MenuItem::with_id(app, "pause_30", "Pause 30m", true, None::<&str>)?,
+            &MenuItem::with_id(app, "pause_60", "Pause 1h", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "pause_resume", "Resume Now", true, None::<&str>)?,
+        ],
+    )?;
+    let forget_item = MenuItem::with_id(app, "forget_recent", "Forget last 5 minutes", true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
     let menu = Menu::with_items(
@@ -154,15 +338,19 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             &PredefinedMenuItem::separator(app)?,
             &game_mode_item,
             &incognito_item,
+            &pause_menu,
+            &forget_item,
             &PredefinedMenuItem::separator(app)?,
             &quit_item,
         ],
     )?;
 
     let game_mode_item_handle = game_mode_item.clone();
-    let incognito_item_handle = incognito_item.clone();
+    app.manage(TrayToggles {
+        incognito: incognito_item.clone(),
+    });
 
-    let mut tray_builder = TrayIconBuilder::new()
+    let mut tray_builder = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .on_menu_event(move |app, event| {
             let id = event.id().as_ref();
@@ -184,15 +372,29 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                     apply_monitoring_state(app);
                 }
                 "toggle_incognito" => {
-                    let next = !INCOGNITO_ENABLED.load(Ordering::Relaxed);
-                    INCOGNITO_ENABLED.store(next, Ordering::Relaxed);
-                    let _ = incognito_item_handle.set_text(if next {
-                        "Incognito: ON"
-                    } else {
-                        "Incognito: OFF"
-                    });
+                    toggle_incognito(app);
+                }
+                "pause_15" | "pause_30" | "pause_60" | "pause_resume" => {
+                    let minutes = match id {
+                        "pause_15" => 15,
+                        "pause_30" => 30,
+                        "pause_60" => 60,
+                        _ => 0,
+                    };
+                    let paused = database::with_db(app, |conn| services::tracking_pause::pause_for(conn, minutes));
+                    if let Err(e) = paused {
+                        println!("[Pause] Failed to update pause: {}", e);
+                    }
                     apply_monitoring_state(app);
                 }
+                "forget_recent" => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn_blocking(move || {
+                        if let Err(e) = commands::storage::purge_recent(&app, 5) {
+                            println!("[Tray] Failed to forget recent data: {}", e);
+                        }
+                    });
+                }
                 "quit" => {
                     app.exit(0);
                 }
@@ -223,56 +425,89 @@ fn apply_startup_behavior(app_handle: &tauri::AppHandle) {
     let args: Vec<String> = std::env::args().collect();
     let is_autostart = args.iter().any(|arg| arg == "--autostart");
 
-    let behavior = read_settings(app_handle)
-        .map(|s| s.general.startup_behavior.to_lowercase())
-        .unwrap_or_else(|| "normal".to_string());
+    let behavior = utils::config::load_settings(app_handle).general.startup_behavior.to_lowercase();
 
     if is_autostart && (behavior == "minimized_to_tray" || behavior == "hidden") {
         // Keep it hidden (it's hidden by default in tauri.conf.json)
     } else {
         // Show the window for manual launch or if startup behavior is normal
-        if let Some(window) = app_handle.get_webview_window("main") {
-            let _ = window.show();
-            let _ = window.set_focus();
-        }
+        services::app_lock::reveal_main_window(app_handle, None);
     }
 }
 
-fn apply_monitoring_state(app_handle: &tauri::AppHandle) {
-    let settings_enabled = read_settings(app_handle)
-        .map(|s| s.tracking.enabled)
-        .unwrap_or(true);
-    let effective_enabled = settings_enabled
-        && !GAME_MODE_ENABLED.load(Ordering::Relaxed)
-        && !INCOGNITO_ENABLED.load(Ordering::Relaxed);
+/// Whether capture may run when the `tracking.enabled` setting is `settings_enabled`.
+fn monitoring_allowed(settings_enabled: bool, paused: bool, game_mode: bool, incognito: bool, demo: bool) -> bool {
+    settings_enabled && !paused && !game_mode && !incognito && !demo
+}
+
+/// `tracking.enabled` after a timed pause, Game Mode, Incognito and demo mode.
+/// Settings subscribers go through this too, so a save never re-enables capture.
+pub(crate) fn effective_monitoring_enabled(settings_enabled: bool) -> bool {
+    monitoring_allowed(
+        settings_enabled,
+        services::tracking_pause::paused_until().is_some(),
+        GAME_MODE_ENABLED.load(Ordering::Relaxed),
+        INCOGNITO_ENABLED.load(Ordering::Relaxed),
+        database::is_demo_mode(),
+    )
+}
+
+pub(crate) fn apply_monitoring_state(app_handle: &tauri::AppHandle) {
+    let settings_enabled = utils::config::load_settings(app_handle).tracking.enabled;
+    let paused_until = services::tracking_pause::paused_until();
+    let effective_enabled = effective_monitoring_enabled(settings_enabled);
     services::activity_tracker::set_tracking_enabled(effective_enabled);
     services::screen_capture::set_capture_enabled(effective_enabled);
+    services::clipboard_monitor::set_suppressed(!effective_enabled);
+    services::process_monitor::set_suppressed(!effective_enabled);
+    let _ = app_handle.emit("tracking://paused", paused_until);
+
+    // Re-apply once the pause runs out. Timers from earlier pauses just re-apply the current state.
+    if let Some(until) = paused_until {
+        let app = app_handle.clone();
+        let wait_secs = (until - chrono::Utc::now().timestamp()).max(0) as u64 + 1;
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
+            apply_monitoring_state(&app);
+        });
+    }
 }
 
-fn show_window_and_navigate(app_handle: &tauri::AppHandle, page: &str) {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.show();
-        let _ = window.set_focus();
-        let _ = window.emit("tray:navigate", page.to_string());
+/// Flip Incognito (from the tray or the hotkey), keep the tray label in sync and
+/// re-apply monitoring. Returns the new state.
+pub(crate) fn toggle_incognito(app_handle: &tauri::AppHandle) -> bool {
+    let next = !INCOGNITO_ENABLED.load(Ordering::Relaxed);
+    INCOGNITO_ENABLED.store(next, Ordering::Relaxed);
+    if let Some(toggles) = app_handle.try_state::<TrayToggles>() {
+        let _ = toggles.incognito.set_text(if next {
+            "Incognito: ON"
+        } else {
+            "Incognito: OFF"
+        });
     }
+    apply_monitoring_state(app_handle);
+    next
+}
+
+pub(crate) fn show_window_and_navigate(app_handle: &tauri::AppHandle, page: &str) {
+    services::app_lock::reveal_main_window(app_handle, Some(page));
 }
 
 fn should_close_to_tray(app_handle: &tauri::AppHandle) -> bool {
-    read_settings(app_handle)
-        .map(|s| s.general.close_to_tray)
-        .unwrap_or(true)
+    utils::config::load_settings(app_handle).general.close_to_tray
 }
 
-fn read_settings(app_handle: &tauri::AppHandle) -> Option<models::Settings> {
-    let data_dir = app_handle.path().app_data_dir().ok()?;
-    let config_path = data_dir.join("config").join("settings.json");
-    if !config_path.exists() {
-        let mut settings = models::Settings::default();
-        utils::config::apply_env_defaults(&mut settings);
-        return Some(settings);
+#[cfg(test)]
+mod tests {
+    use super::monitoring_allowed;
+
+    #[test]
+    fn any_suppression_keeps_monitoring_off() {
+        assert!(monitoring_allowed(true, false, false, false, false));
+        assert!(!monitoring_allowed(false, false, false, false, false));
+        assert!(!monitoring_allowed(true, true, false, false, false));
+        assert!(!monitoring_allowed(true, false, true, false, false));
+        assert!(!monitoring_allowed(true, false, false, true, false));
+        assert!(!monitoring_allowed(true, false, false, false, true));
     }
-    let content = std::fs::read_to_string(config_path).ok()?;
-    let mut settings = serde_json::from_str::<models::Settings>(&content).ok()?;
-    utils::config::apply_env_defaults(&mut settings);
-    Some(settings)
 }
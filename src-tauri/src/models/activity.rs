@@ -7,9 +7,105 @@ pub struct ActivityMetadata {
     pub process_id: Option<u32>,
     pub url: Option<String>,
     pub screen_text: Option<String>,
+    /// Quality score (0–1) of `screen_text` as computed by the OCR pipeline.
+    #[serde(default)]
+    pub ocr_score: Option<f64>,
+    /// Recognizer language that produced `screen_text`, e.g. "de-DE".
+    #[serde(default)]
+    pub ocr_language: Option<String>,
+    /// Background windows when the session started; later refreshes only append
+    /// to `background_changes`. Use `background_windows_seen` to read them.
     pub background_windows: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub background_changes: Vec<BackgroundChange>,
     pub media_info: Option<MediaInfo>,
     pub raw_duration_ms: Option<i64>,
+    /// Path of the downscaled screenshot, relative to the app data dir.
+    pub thumbnail_path: Option<String>,
+    /// Set when the session is stored; rows from older builds are classified on read.
+    #[serde(default)]
+    pub media_kind: Option<MediaKind>,
+    /// Restored from a tracker checkpoint after a crash or restart; the end time is the last time the window was seen.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub recovered: bool,
+}
+
+/// Background windows that appeared or disappeared at one metadata refresh.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BackgroundChange {
+    pub at: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+}
+
+/// Sessions stop recording background changes after this many.
+const MAX_BACKGROUND_CHANGES: usize = 50;
+
+impl ActivityMetadata {
+    /// Background windows at the latest refresh: the snapshot with every change applied.
+    pub fn background_windows_now(&self) -> Vec<String> {
+        let mut current = self.background_windows.clone().unwrap_or_default();
+        for change in &self.background_changes {
+            current.retain(|t| !change.removed.contains(t));
+            for title in &change.added {
+                if !current.contains(title) {
+                    current.push(title.clone());
+                }
+            }
+        }
+        current.sort();
+        current
+    }
+
+    /// Every background window open at some point during the session, sorted.
+    pub fn background_windows_seen(&self) -> Vec<String> {
+        let mut seen: Vec<String> = self
+            .background_windows
+            .iter()
+            .flatten()
+            .chain(self.background_changes.iter().flat_map(|c| c.added.iter()))
+            .cloned()
+            .collect();
+        seen.sort();
+        seen.dedup();
+        seen
+    }
+
+    /// Record `titles` from a refresh: the first becomes the snapshot, later ones
+    /// only store what changed since the previous state.
+    pub fn record_background_windows(&mut self, titles: Vec<String>, at: i64) {
+        if self.background_windows.is_none() {
+            self.background_windows = Some(titles);
+            return;
+        }
+        if self.background_changes.len() >= MAX_BACKGROUND_CHANGES {
+            return;
+        }
+        let current = self.background_windows_now();
+        let added: Vec<String> = titles.iter().filter(|t| !current.contains(t)).cloned().collect();
+        let removed: Vec<String> = current.into_iter().filter(|t| !titles.contains(t)).collect();
+        if !added.is_empty() || !removed.is_empty() {
+            self.background_changes.push(BackgroundChange { at, added, removed });
+        }
+    }
+
+    /// Drop what the screen capture contributed: OCR text and the thumbnail reference.
+    pub fn clear_screen_capture(&mut self) {
+        self.screen_text = None;
+        self.ocr_score = None;
+        self.ocr_language = None;
+        self.thumbnail_path = None;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Music,
+    Video,
+    Unknown,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -17,6 +113,9 @@ pub struct MediaInfo {
     pub title: String,
     pub artist: String,
     pub status: String, // "Playing", "Paused", "Stopped"
+    /// SMTC source app id (e.g. "Spotify.exe", "chrome"); missing on older rows.
+    #[serde(default)]
+    pub source_app: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +132,113 @@ pub struct Activity {
     pub metadata: Option<ActivityMetadata>,
 }
 
+/// Full activity row for evidence drill-down from chat references.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityDetail {
+    pub activity: Activity,
+    pub private: bool,
+    pub tz_offset_minutes: Option<i32>,
+    /// Set when `metadata.screen_text` was cut to the response limit.
+    pub screen_text_truncated: bool,
+    /// Length of the cleaned screen text before truncation, in characters.
+    pub screen_text_chars: usize,
+}
+
+/// Everything the capture pipeline stored for one session, untruncated, for a detail panel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityCapture {
+    pub activity_id: i64,
+    pub private: bool,
+    pub screen_text: Option<String>,
+    pub ocr_score: Option<f64>,
+    pub ocr_language: Option<String>,
+    pub media_info: Option<MediaInfo>,
+    pub background_windows: Option<Vec<String>>,
+}
+
+/// Consecutive activity rows of one app joined into a single span (see `queries::get_merged_sessions`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedSession {
+    /// Id of the first merged row.
+    pub first_activity_id: i64,
+    pub app_name: String,
+    /// Title of the longest merged row.
+    pub window_title: String,
+    /// Category of the longest merged row.
+    pub category_id: i32,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Sum of the merged rows, without the gaps between them.
+    pub duration_seconds: i64,
+    pub activity_count: usize,
+    /// Any merged row was marked private.
+    pub private: bool,
+}
+
+/// App name counted as another: in app stats right away, on captures from now
+/// on, and in stored history once `apply_app_merges_to_history` runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppMerge {
+    /// Lowercased; matched against app names ignoring case.
+    pub from_name: String,
+    pub to_name: String,
+    /// Only widens app search for `to_name`; stats and captures keep `from_name`.
+    pub search_only: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRenameMatch {
+    pub app_name: String,
+    pub activities: i64,
+    pub clipboard_events: i64,
+}
+
+/// Result of `rename_app`; counts are identical between a dry run and the real rename.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRenameReport {
+    pub dry_run: bool,
+    pub to: String,
+    /// Each distinct stored name that matched, with its rows.
+    pub matched: Vec<AppRenameMatch>,
+    pub activities_updated: i64,
+    pub clipboard_events_updated: i64,
+    /// Names stored as merges into `to`; empty unless `remember` was set.
+    pub aliases: Vec<String>,
+}
+
+/// One app seen in the activity history, for the timeline's app identity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppDirectoryEntry {
+    /// Name as stored on activity rows; use it to filter by app.
+    pub app_name: String,
+    /// Registry display name, otherwise `app_name` without a trailing ".exe".
+    pub display_name: String,
+    /// Category the app spent the most time in.
+    pub category_id: i32,
+    pub total_seconds: i64,
+    pub activity_count: i64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    /// PNG data URL of the executable's icon, when one could be extracted.
+    pub icon: Option<String>,
+}
+
+/// One run of a watched process (see `services::process_monitor`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSession {
+    pub id: i64,
+    pub process_name: String,
+    pub pid: i64,
+    pub parent_pid: Option<i64>,
+    pub parent_name: Option<String>,
+    pub start_time: i64,
+    /// `None` while the process is still running.
+    pub end_time: Option<i64>,
+    /// Up to now for a running process.
+    pub duration_seconds: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActivityStats {
     pub total_duration: i64,
@@ -58,6 +264,100 @@ pub struct CategoryStat {
     pub percentage: f32,
 }
 
+/// One bucket of `get_grouped_stats`; `share` is a percentage of the range total.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupedStatRow {
+    pub key: String,
+    pub label: String,
+    pub value: i64,
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupedStats {
+    pub group_by: String,
+    pub metric: String,
+    pub total: i64,
+    /// Top buckets by value; a trailing `key = "other"` row holds the rest when requested.
+    pub rows: Vec<GroupedStatRow>,
+}
+
+/// Another row with the same title or app as the selected one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedActivity {
+    pub activity: Activity,
+    /// "same_title" or "same_app".
+    pub relation: String,
+    /// Seconds between the two sessions; 0 when they touch or overlap.
+    pub gap_seconds: i64,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedFileEvent {
+    pub path: String,
+    pub project_root: String,
+    pub change_type: String,
+    pub detected_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedActivities {
+    pub activity_id: i64,
+    /// Project folder name found in the window title, if any.
+    pub project: Option<String>,
+    pub activities: Vec<RelatedActivity>,
+    pub file_events: Vec<RelatedFileEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayBoundaryDebug {
+    pub bounds: crate::utils::time::DayBounds,
+    pub timezone_mode: String,
+    pub start_local: String,
+    pub end_local: String,
+    pub start_utc: String,
+    pub end_utc: String,
+    /// (offset_minutes, activity_count) recorded on that day, most frequent first.
+    pub stored_offsets: Vec<(i32, i64)>,
+}
+
+/// Music listening over a range; only time reported as "Playing" counts.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListeningStats {
+    pub top_tracks: Vec<TrackStat>,
+    pub top_artists: Vec<ArtistStat>,
+    pub total_play_seconds: i64,
+    /// One entry per local calendar day of the range, empty days included.
+    pub by_day: Vec<crate::models::DailyTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackStat {
+    pub title: String,
+    pub artist: String,
+    /// Player it was mostly heard in, e.g. "Spotify".
+    pub app: String,
+    pub play_seconds: i64,
+    pub sessions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtistStat {
+    pub artist: String,
+    pub play_seconds: i64,
+    /// Distinct tracks heard.
+    pub tracks: i64,
+}
+
+/// A stretch inside the active hours with no recorded activity at all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackingGap {
+    pub start: i64,
+    pub end: i64,
+    pub duration: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActivityEvent {
     pub app_name: String,
@@ -72,6 +372,14 @@ pub struct ActivityEvent {
 }
 
 impl ActivityEvent {
+    /// Case-sensitive XxHash64 of the app name, as stored in `activities.app_hash`.
+    pub fn app_hash(app_name: &str) -> u64 {
+        use std::hash::Hasher;
+        let mut hasher = twox_hash::XxHash64::default();
+        hasher.write(app_name.as_bytes());
+        hasher.finish()
+    }
+
     pub fn new(
         app_name: String,
         window_title: String,
@@ -82,9 +390,7 @@ impl ActivityEvent {
         use twox_hash::XxHash64;
         use std::hash::Hasher;
         
-        let mut hasher = XxHash64::default();
-        hasher.write(app_name.as_bytes());
-        let app_hash = hasher.finish();
+        let app_hash = Self::app_hash(&app_name);
         
         let mut hasher = XxHash64::default();
         hasher.write(window_title.to_lowercase().as_bytes());
@@ -107,9 +413,15 @@ impl ActivityEvent {
                 process_id: None,
                 url: None,
                 screen_text: None,
+                ocr_score: None,
+                ocr_language: None,
                 background_windows: None,
+                background_changes: Vec::new(),
                 media_info: None,
                 raw_duration_ms: None,
+                thumbnail_path: None,
+                media_kind: None,
+                recovered: false,
             },
         }
     }
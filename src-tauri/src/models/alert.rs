@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Daily usage watchdog for apps or sites whose app name or window title contains `app_pattern`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppAlert {
+    pub id: i64,
+    pub app_pattern: String,
+    pub daily_threshold_seconds: i64,
+    /// `None` notifies once per day; otherwise again after every further interval.
+    pub repeat_every_seconds: Option<i64>,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAppAlertRequest {
+    pub app_pattern: String,
+    pub daily_threshold_seconds: i64,
+    pub repeat_every_seconds: Option<i64>,
+}
+
+/// Payload of the `alerts://triggered` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppAlertTriggered {
+    pub alert_id: i64,
+    pub app_pattern: String,
+    pub app_name: String,
+    pub total_seconds: i64,
+    pub threshold_seconds: i64,
+}
@@ -8,6 +8,32 @@ pub struct Category {
     pub color: String,
     pub keywords: Vec<String>,
     pub apps: Vec<String>,
+    /// Seeded categories (1-7) can be renamed and recolored but not deleted.
+    #[serde(default)]
+    pub is_builtin: bool,
+}
+
+/// Where the category of a window came from, for `explain_categorization`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CategorizationSource {
+    /// App/keyword rule of a user-defined category; these are checked first.
+    UserRule,
+    /// Built-in app/title heuristic.
+    BuiltinDefault,
+    /// App/keyword rule stored on a built-in category.
+    BuiltinRule,
+    /// Nothing matched.
+    Fallback,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategorizationExplanation {
+    pub category_id: i32,
+    pub category_name: String,
+    pub source: CategorizationSource,
+    /// e.g. `app name contains "spotify"` or `title contains ".rs"`.
+    pub matched_rule: String,
 }
 
 impl Default for Category {
@@ -19,6 +45,7 @@ impl Default for Category {
             color: String::from("#6b7280"),
             keywords: vec![],
             apps: vec![],
+            is_builtin: false,
         }
     }
 }
@@ -32,6 +59,7 @@ pub fn get_default_categories() -> Vec<Category> {
             color: "#3b82f6".to_string(),
             keywords: vec!["vscode".to_string(), "terminal".to_string(), "git".to_string(), "code".to_string()],
             apps: vec!["Code.exe".to_string(), "git-bash.exe".to_string()],
+            is_builtin: true,
         },
         Category {
             id: 2,
@@ -40,6 +68,7 @@ pub fn get_default_categories() -> Vec<Category> {
             color: "#10b981".to_string(),
             keywords: vec!["chrome".to_string(), "firefox".to_string(), "edge".to_string()],
             apps: vec!["chrome.exe".to_string(), "firefox.exe".to_string(), "msedge.exe".to_string()],
+            is_builtin: true,
         },
         Category {
             id: 3,
@@ -48,6 +77,7 @@ pub fn get_default_categories() -> Vec<Category> {
             color: "#8b5cf6".to_string(),
             keywords: vec!["slack".to_string(), "discord".to_string(), "teams".to_string()],
             apps: vec!["slack.exe".to_string(), "discord.exe".to_string()],
+            is_builtin: true,
         },
         Category {
             id: 4,
@@ -56,6 +86,7 @@ pub fn get_default_categories() -> Vec<Category> {
             color: "#f59e0b".to_string(),
             keywords: vec!["youtube".to_string(), "spotify".to_string(), "netflix".to_string()],
             apps: vec!["spotify.exe".to_string()],
+            is_builtin: true,
         },
         Category {
             id: 5,
@@ -64,6 +95,7 @@ pub fn get_default_categories() -> Vec<Category> {
             color: "#ec4899".to_string(),
             keywords: vec!["notion".to_string(), "obsidian".to_string(), "todo".to_string()],
             apps: vec!["notion.exe".to_string(), "obsidian.exe".to_string()],
+            is_builtin: true,
         },
         Category {
             id: 6,
@@ -72,6 +104,7 @@ pub fn get_default_categories() -> Vec<Category> {
             color: "#6b7280".to_string(),
             keywords: vec!["explorer".to_string(), "settings".to_string()],
             apps: vec!["explorer.exe".to_string()],
+            is_builtin: true,
         },
         Category {
             id: 7,
@@ -80,6 +113,7 @@ pub fn get_default_categories() -> Vec<Category> {
             color: "#9ca3af".to_string(),
             keywords: vec![],
             apps: vec![],
+            is_builtin: true,
         },
     ]
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::activity::{AppStat, CategoryStat};
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DashboardTask {
     pub title: String,
@@ -20,15 +22,307 @@ pub struct ContactOverview {
     pub name: String,
     pub context: String,
     pub last_seen: Option<i64>,
+    /// Interactions per tag, most frequent first. `Unknown` is never counted.
+    #[serde(default)]
+    pub tags: Vec<InteractionTagCount>,
+}
+
+/// Coarse kind of a communication interaction, from keyword rules over its OCR text.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionTag {
+    Scheduling,
+    Question,
+    Work,
+    Social,
+    /// Unreadable or too little text to tell.
+    #[default]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractionTagCount {
+    pub tag: InteractionTag,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusPointKind {
+    Deadline,
+    Project,
+    Contact,
+    Habit,
+    #[default]
+    Info,
+}
+
+impl FocusPointKind {
+    /// Lenient parse for model output; anything unknown is plain info.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "deadline" | "task" | "due" => Self::Deadline,
+            "project" => Self::Project,
+            "contact" | "person" => Self::Contact,
+            "habit" | "routine" => Self::Habit,
+            _ => Self::Info,
+        }
+    }
+}
+
+/// What a focus point links to, so the frontend can open it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FocusEntityRef {
+    /// "project", "contact" or "deadline".
+    #[serde(rename = "type")]
+    pub entity_type: String,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl FocusEntityRef {
+    pub fn named(entity_type: &str, name: &str) -> Self {
+        Self {
+            entity_type: entity_type.to_string(),
+            id: None,
+            name: Some(name.to_string()),
+        }
+    }
+
+    /// Same entity: same type and the same id, or (without ids) the same name.
+    pub fn same_entity(&self, other: &FocusEntityRef) -> bool {
+        if !self.entity_type.eq_ignore_ascii_case(&other.entity_type) {
+            return false;
+        }
+        match (&self.id, &other.id) {
+            (Some(a), Some(b)) => a == b,
+            _ => match (&self.name, &other.name) {
+                (Some(a), Some(b)) => a.trim().eq_ignore_ascii_case(b.trim()),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Priorities run 1 (low) to 3 (high).
+pub const FOCUS_PRIORITY_LOW: u8 = 1;
+pub const FOCUS_PRIORITY_HIGH: u8 = 3;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FocusPoint {
+    pub kind: FocusPointKind,
+    pub text: String,
+    pub entity_ref: Option<FocusEntityRef>,
+    pub priority: u8,
+}
+
+impl FocusPoint {
+    pub fn info(text: impl Into<String>) -> Self {
+        Self {
+            kind: FocusPointKind::Info,
+            text: text.into(),
+            entity_ref: None,
+            priority: FOCUS_PRIORITY_LOW,
+        }
+    }
+
+    /// Merge key: the linked entity when there is one, else the wording.
+    pub fn same_point(&self, other: &FocusPoint) -> bool {
+        if self.kind != other.kind {
+            return false;
+        }
+        match (&self.entity_ref, &other.entity_ref) {
+            (Some(a), Some(b)) => a.same_entity(b),
+            (None, None) => self.text.trim().eq_ignore_ascii_case(other.text.trim()),
+            _ => false,
+        }
+    }
+}
+
+/// Snapshots saved before focus points were structured (and some model
+/// replies) hold plain strings; those load as info points.
+impl<'de> Deserialize<'de> for FocusPoint {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Text(String),
+            Item {
+                #[serde(default)]
+                kind: Option<String>,
+                text: String,
+                #[serde(default)]
+                entity_ref: Option<FocusEntityRef>,
+                #[serde(default)]
+                priority: Option<u8>,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Text(text) => FocusPoint::info(text),
+            Repr::Item { kind, text, entity_ref, priority } => FocusPoint {
+                kind: kind.as_deref().map(FocusPointKind::parse).unwrap_or_default(),
+                text,
+                entity_ref: entity_ref.filter(|r| r.id.is_some() || r.name.is_some()),
+                priority: priority.unwrap_or(FOCUS_PRIORITY_LOW).clamp(FOCUS_PRIORITY_LOW, FOCUS_PRIORITY_HIGH),
+            },
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DashboardOverview {
     pub date_key: String,
     pub summary: String,
-    pub focus_points: Vec<String>,
+    pub focus_points: Vec<FocusPoint>,
     pub deadlines: Vec<DashboardTask>,
     pub projects: Vec<ProjectOverview>,
     pub contacts: Vec<ContactOverview>,
+    /// Detected from meeting-app window titles, not from the model.
+    #[serde(default)]
+    pub meetings: Vec<Meeting>,
     pub updated_at: i64,
 }
+
+/// Meeting-titled Teams/Zoom/Meet activity, clustered like contact conversations.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Meeting {
+    pub name: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Time spent in the meeting's windows, not `end_time - start_time`.
+    pub duration_seconds: i64,
+    /// Approximate: names read from the participant list in screen text.
+    pub attendees: Vec<String>,
+    pub apps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MeetingsReport {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub total_duration_seconds: i64,
+    pub meetings: Vec<Meeting>,
+}
+
+/// AI-free recap of a single local day, computed directly from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DailyRecap {
+    pub date_key: String,
+    pub total_tracked_seconds: i64,
+    pub top_apps: Vec<AppStat>,
+    pub category_split: Vec<CategoryStat>,
+    pub projects: Vec<ProjectOverview>,
+    pub songs_played: i64,
+    pub contacts: Vec<ContactOverview>,
+    pub generated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContactInteraction {
+    pub app_name: String,
+    pub window_title: String,
+    pub start_time: i64,
+    pub duration_seconds: i64,
+    /// OCR text around the contact's name; omitted when anonymization is on.
+    pub snippet: Option<String>,
+    #[serde(default)]
+    pub tag: InteractionTag,
+}
+
+/// Interactions less than 10 minutes apart, merged into one conversation.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContactConversationBlock {
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_duration_seconds: i64,
+    pub apps: Vec<String>,
+    pub events: Vec<ContactInteraction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContactTimeline {
+    pub contact: String,
+    pub days: i64,
+    pub blocks: Vec<ContactConversationBlock>,
+    pub has_more: bool,
+}
+
+/// All changes to one file within the requested window.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectFileChange {
+    pub path: String,
+    pub area: Option<String>,
+    pub entity_type: String,
+    pub created: i32,
+    pub modified: i32,
+    pub deleted: i32,
+    pub last_change_type: String,
+    pub last_detected_at: i64,
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectAreaChange {
+    pub area: String,
+    pub changes: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectChanges {
+    pub name: String,
+    pub project_roots: Vec<String>,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub total_changes: i32,
+    pub created: i32,
+    pub modified: i32,
+    pub deleted: i32,
+    /// Events dropped as build output, dependency caches and similar noise.
+    pub noise_filtered: i32,
+    pub areas: Vec<ProjectAreaChange>,
+    pub files: Vec<ProjectFileChange>,
+    pub files_truncated: bool,
+}
+
+/// What happened on a surface ("dashboard", "timeline") since the user last looked at it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SinceLastSeen {
+    pub surface: String,
+    /// No last-seen time was recorded yet; the gap starts at the beginning of today.
+    pub first_visit: bool,
+    pub gap_start: i64,
+    pub gap_end: i64,
+    pub new_activities: i32,
+    pub tracked_seconds: i64,
+    pub top_apps: Vec<AppStat>,
+    pub projects: Vec<ProjectDelta>,
+    pub media: Vec<MediaDelta>,
+    pub notifications: Vec<NotificationRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectDelta {
+    pub name: String,
+    pub project_root: String,
+    pub changes: i32,
+    pub files: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MediaDelta {
+    pub title: String,
+    pub artist: String,
+    pub sessions: i32,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NotificationRecord {
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub created_at: i64,
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// Daily time target for one category: at least or at most `target_seconds` per local day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryGoal {
+    pub id: i64,
+    pub category_id: i64,
+    pub kind: GoalKind,
+    pub target_seconds: i64,
+    pub no_data_policy: NoDataPolicy,
+    pub enabled: bool,
+    /// Longest streak ever recorded, including runs older than the calendar window.
+    pub best_streak: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCategoryGoalRequest {
+    pub category_id: i64,
+    pub kind: GoalKind,
+    pub target_seconds: i64,
+    #[serde(default)]
+    pub no_data_policy: NoDataPolicy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalKind {
+    AtLeast,
+    AtMost,
+}
+
+impl GoalKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GoalKind::AtLeast => "at_least",
+            GoalKind::AtMost => "at_most",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "at_least" => Some(GoalKind::AtLeast),
+            "at_most" => Some(GoalKind::AtMost),
+            _ => None,
+        }
+    }
+}
+
+/// How a day without any tracked activity (machine off, tracking paused) affects a streak.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoDataPolicy {
+    /// The day is skipped: it neither extends nor ends the streak.
+    Preserve,
+    #[default]
+    Break,
+}
+
+impl NoDataPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NoDataPolicy::Preserve => "preserve",
+            NoDataPolicy::Break => "break",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Self {
+        match value {
+            "preserve" => NoDataPolicy::Preserve,
+            _ => NoDataPolicy::Break,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalDayStatus {
+    Met,
+    Missed,
+    NoData,
+    /// Today, while an "at least" target isn't reached yet; doesn't end the streak.
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalDay {
+    pub date_key: String,
+    pub seconds: i64,
+    pub status: GoalDayStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalStreak {
+    pub goal: CategoryGoal,
+    pub category_name: String,
+    pub current_streak: i64,
+    pub best_streak: i64,
+    /// Oldest first, ending with today.
+    pub calendar: Vec<GoalDay>,
+}
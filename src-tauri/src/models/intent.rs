@@ -38,3 +38,23 @@ pub enum ActionType {
     ShowNotification,
     ExecuteWorkflow,
 }
+
+/// Command-palette entry; `intent` can be passed straight to `execute_intent`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickAction {
+    pub id: String,
+    pub label: String,
+    /// "history", "routine" or "workflow".
+    pub source: String,
+    pub reason: String,
+    pub score: f32,
+    pub intent: Intent,
+}
+
+/// What the user said they're working on; expires at `expires_at` (unix seconds).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentFocus {
+    pub text: String,
+    pub set_at: i64,
+    pub expires_at: i64,
+}
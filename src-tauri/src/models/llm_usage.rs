@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// Token usage of one LLM call or several summed, with a rough cost where the model's price is known.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LlmUsage {
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub calls: i64,
+    /// At least one call had no provider `usage` and was estimated from text length.
+    pub estimated: bool,
+    /// US dollars at list price; `None` when any call's model has no known price.
+    pub cost_usd: Option<f64>,
+}
+
+impl LlmUsage {
+    pub fn total_tokens(&self) -> i64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    pub fn add(&mut self, other: &LlmUsage) {
+        if other.calls == 0 {
+            return;
+        }
+        self.cost_usd = if self.calls == 0 {
+            other.cost_usd
+        } else {
+            self.cost_usd.zip(other.cost_usd).map(|(a, b)| a + b)
+        };
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.calls += other.calls;
+        self.estimated |= other.estimated;
+    }
+}
+
+/// Payload of the `chat://status` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatStatus {
+    pub message: String,
+    /// Running total for the current agent run; absent for client-level notices such as rate limiting.
+    pub usage: Option<LlmUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmCallerUsage {
+    /// "chat", "dashboard", "summary", "intent", "chat_title".
+    pub caller: String,
+    pub usage: LlmUsage,
+}
+
+/// Usage summed over one calendar month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmUsageStats {
+    /// "YYYY-MM".
+    pub month: String,
+    pub total: LlmUsage,
+    pub by_caller: Vec<LlmCallerUsage>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// One line of the app log, as returned by `get_recent_logs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// UTC, RFC 3339 to the second.
+    pub timestamp: String,
+    pub level: String,
+    /// Module the line came from, e.g. `intentflow::services::screen_capture`.
+    pub target: String,
+    pub message: String,
+}
@@ -1,20 +1,33 @@
 pub mod activity;
+pub mod alert;
 pub mod category;
 pub mod dashboard;
 pub mod entry;
+pub mod goal;
 pub mod intent;
+pub mod llm_usage;
+pub mod log;
 pub mod pattern;
+pub mod profile;
 pub mod query;
+pub mod report;
 pub mod settings;
 pub mod storage;
 pub mod workflow;
 
 pub use activity::*;
+pub use alert::*;
 pub use category::*;
 pub use dashboard::*;
 pub use entry::*;
+pub use goal::*;
 pub use intent::*;
+pub use llm_usage::*;
+pub use log::LogEntry;
+pub use pattern::{DetectedPattern, PatternKind};
+pub use profile::*;
 pub use query::*;
+pub use report::*;
 pub use settings::*;
 pub use storage::*;
 pub use workflow::*;
@@ -31,3 +31,50 @@ pub struct PatternData {
     pub time_of_day: Option<i32>,
     pub likely_intent: Option<String>,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternKind {
+    /// Same apps at similar hours on most recent weekdays.
+    Routine,
+    /// Today's category mix far from the 30-day baseline.
+    Anomaly,
+    /// Two behaviours that tend to happen together.
+    Correlation,
+}
+
+impl PatternKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatternKind::Routine => "routine",
+            PatternKind::Anomaly => "anomaly",
+            PatternKind::Correlation => "correlation",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "routine" => Some(PatternKind::Routine),
+            "anomaly" => Some(PatternKind::Anomaly),
+            "correlation" => Some(PatternKind::Correlation),
+            _ => None,
+        }
+    }
+}
+
+/// A pattern engine finding as stored in `patterns`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedPattern {
+    pub id: i64,
+    pub kind: PatternKind,
+    pub description: String,
+    pub confidence: f32,
+    /// Human-readable span of data the finding is based on.
+    pub supporting_window: String,
+    /// First time this finding was detected; `last_observed` is the latest run that confirmed it.
+    pub detected_at: i64,
+    pub last_observed: i64,
+    pub dismissed: bool,
+    /// Detector-specific details (apps and hours, percentages, ...).
+    pub data: serde_json::Value,
+}
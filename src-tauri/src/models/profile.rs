@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::{AppAlert, Category, GoalKind, NoDataPolicy, Workflow};
+
+/// Settings, rules, goals and workflows carried between installations by
+/// `export_profile` / `import_profile`. Sections left out of the export are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileBundle {
+    /// Bundle format version; imports reject versions newer than this build knows.
+    pub version: u32,
+    pub exported_at: String,
+    pub app_version: String,
+    pub sections: Vec<String>,
+    /// `settings.json` without machine-specific fields (API key, unlock password, local paths).
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
+    #[serde(default)]
+    pub categories: Option<Vec<Category>>,
+    #[serde(default)]
+    pub goals: Option<Vec<ProfileGoal>>,
+    #[serde(default)]
+    pub app_alerts: Option<Vec<AppAlert>>,
+    #[serde(default)]
+    pub workflows: Option<Vec<Workflow>>,
+    /// Only present when exported with `include_secrets`.
+    #[serde(default)]
+    pub secrets: Option<ProfileSecrets>,
+}
+
+/// A category goal, keyed by its category's name since ids differ between installations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileGoal {
+    pub category: String,
+    pub kind: GoalKind,
+    pub target_seconds: i64,
+    #[serde(default)]
+    pub no_data_policy: NoDataPolicy,
+    pub enabled: bool,
+}
+
+/// API key obfuscated with a key derived from the user's passphrase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSecrets {
+    /// Base64 salt for the passphrase derivation.
+    pub salt: String,
+    /// Base64 obfuscated `ai.api_key`.
+    pub api_key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileImportMode {
+    /// Keep local entries; conflicting imports are added under a suffixed name.
+    Merge,
+    /// Imported entries overwrite local ones of the same name; workflows,
+    /// goals and alerts missing from the bundle are removed.
+    Replace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSectionResult {
+    pub section: String,
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+/// Same-named entry whose content differs between the bundle and this machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileConflict {
+    pub section: String,
+    pub name: String,
+    pub detail: String,
+    /// What the import did about it, e.g. "imported as 'Morning (imported)'".
+    pub resolution: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileImportReport {
+    pub mode: ProfileImportMode,
+    pub bundle_version: u32,
+    pub sections: Vec<ProfileSectionResult>,
+    pub conflicts: Vec<ProfileConflict>,
+    pub secrets_imported: bool,
+    /// Non-fatal notes, such as secrets skipped for lack of a passphrase.
+    pub warnings: Vec<String>,
+}
@@ -16,3 +16,41 @@ pub struct QueryItem {
     pub duration: String,
     pub details: Option<String>,
 }
+
+/// One `search_everything` hit: the timeline row plus where the query matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub item: QueryItem,
+    pub activity_id: i64,
+    /// Best matching field: "title", "app" or "ocr".
+    pub match_field: String,
+    pub score: f64,
+}
+
+/// Exact time split of a chat answer's scope, computed from SQL rather than by the model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimeAccounting {
+    pub scope_label: String,
+    pub start_ts: i64,
+    /// Scope end, capped at the time of the query so the rest of today isn't counted as a gap.
+    pub end_ts: i64,
+    pub span_seconds: i64,
+    pub tracked_seconds: i64,
+    /// Rows the tracker flagged as idle.
+    pub idle_seconds: i64,
+    /// Span minus tracked minus idle: machine off, tracking paused or excluded apps.
+    pub untracked_seconds: i64,
+    /// Share of tracked time, largest first.
+    pub categories: Vec<TimeShare>,
+    /// Top five apps by tracked time; private rows are left out unless the chat includes them.
+    pub top_apps: Vec<TimeShare>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeShare {
+    pub name: String,
+    pub seconds: i64,
+    /// Percent of `tracked_seconds`.
+    pub percent: f64,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use super::activity::{AppStat, CategoryStat};
+use super::dashboard::{ContactOverview, DashboardTask};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DailyTotal {
+    pub date_key: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ReportProject {
+    pub name: String,
+    pub file_events: i64,
+    pub files_changed: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FocusMetrics {
+    /// Time in Development/Productivity blocks of at least 25 minutes.
+    pub deep_work_seconds: i64,
+    pub focus_blocks: i32,
+    pub longest_focus_block_seconds: i64,
+    pub app_switches_per_hour: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PeriodReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub total_tracked_seconds: i64,
+    pub active_days: i32,
+    pub daily_average_seconds: i64,
+    pub daily_totals: Vec<DailyTotal>,
+    pub category_split: Vec<CategoryStat>,
+    pub top_apps: Vec<AppStat>,
+    /// Apps beyond `top_apps`, folded into a single "N others" line.
+    pub other_apps_count: i32,
+    pub other_apps_seconds: i64,
+    pub projects: Vec<ReportProject>,
+    pub focus: FocusMetrics,
+    pub contacts: Vec<ContactOverview>,
+    pub deadlines: Vec<DashboardTask>,
+    pub generated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GeneratedReport {
+    pub paths: Vec<String>,
+    pub report: PeriodReport,
+}
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub version: String,
     pub general: GeneralSettings,
@@ -9,6 +12,8 @@ pub struct Settings {
     pub ai: AISettings,
     pub privacy: PrivacySettings,
     pub notifications: NotificationSettings,
+    #[serde(default)]
+    pub reports: ReportSettings,
 }
 
 impl Default for Settings {
@@ -21,11 +26,73 @@ impl Default for Settings {
             ai: AISettings::default(),
             privacy: PrivacySettings::default(),
             notifications: NotificationSettings::default(),
+            reports: ReportSettings::default(),
+        }
+    }
+}
+
+/// Clamp `value` into `range`, noting the change in `warnings` under `name`.
+fn clamp_setting<T: PartialOrd + Copy + std::fmt::Display>(
+    name: &str,
+    value: &mut T,
+    range: std::ops::RangeInclusive<T>,
+    warnings: &mut Vec<String>,
+) {
+    let clamped = if *value < *range.start() {
+        *range.start()
+    } else if *value > *range.end() {
+        *range.end()
+    } else {
+        return;
+    };
+    warnings.push(format!("{} was {}, clamped to {}", name, value, clamped));
+    *value = clamped;
+}
+
+impl Settings {
+    /// Pull out-of-range numbers back into what the services accept.
+    /// Returns one line per adjusted field.
+    pub fn sanitize(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let t = &mut self.tracking;
+        clamp_setting("tracking.tracking_interval", &mut t.tracking_interval, 1..=60, &mut warnings);
+        clamp_setting("tracking.idle_timeout", &mut t.idle_timeout, 30..=24 * 3600, &mut warnings);
+        clamp_setting("tracking.ocr_min_quality", &mut t.ocr_min_quality, 0.0..=1.0, &mut warnings);
+        clamp_setting("tracking.ocr_interval", &mut t.ocr_interval, 2..=600, &mut warnings);
+        clamp_setting("tracking.ocr_min_interval", &mut t.ocr_min_interval, 2..=600, &mut warnings);
+        let min_interval = t.ocr_min_interval;
+        clamp_setting("tracking.ocr_max_interval", &mut t.ocr_max_interval, min_interval..=600, &mut warnings);
+        clamp_setting("tracking.active_hours_start", &mut t.active_hours_start, 0..=24, &mut warnings);
+        clamp_setting("tracking.active_hours_end", &mut t.active_hours_end, 0..=24, &mut warnings);
+        clamp_setting("tracking.gap_threshold_minutes", &mut t.gap_threshold_minutes, 1..=24 * 60, &mut warnings);
+
+        let s = &mut self.storage;
+        clamp_setting("storage.retention_days", &mut s.retention_days, 1..=36_500, &mut warnings);
+        clamp_setting("storage.max_cache_size_mb", &mut s.max_cache_size_mb, 0..=1_048_576, &mut warnings);
+
+        let ai = &mut self.ai;
+        clamp_setting("ai.agent_temperature", &mut ai.agent_temperature, TEMPERATURE_RANGE, &mut warnings);
+        clamp_setting("ai.summary_temperature", &mut ai.summary_temperature, TEMPERATURE_RANGE, &mut warnings);
+        clamp_setting("ai.dashboard_temperature", &mut ai.dashboard_temperature, TEMPERATURE_RANGE, &mut warnings);
+        clamp_setting("ai.agent.max_turns", &mut ai.agent.max_turns, AGENT_MAX_TURNS_RANGE, &mut warnings);
+        for profile in &mut ai.ai_profiles {
+            if let Some(temperature) = profile.temperature.as_mut() {
+                let name = format!("ai.ai_profiles[{}].temperature", profile.name);
+                clamp_setting(&name, temperature, TEMPERATURE_RANGE, &mut warnings);
+            }
         }
+        clamp_setting(
+            "ai.agent.long_range_pipeline_threshold_days",
+            &mut ai.agent.long_range_pipeline_threshold_days,
+            1..=3650,
+            &mut warnings,
+        );
+        warnings
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GeneralSettings {
     pub language: String,
     pub theme: String,
@@ -34,6 +101,36 @@ pub struct GeneralSettings {
     pub startup_behavior: String,
     pub minimize_to_tray: bool,
     pub close_to_tray: bool,
+    /// "system" follows the OS zone (per-day, from stored offsets); "fixed" pins `fixed_timezone`.
+    #[serde(default = "default_timezone_mode")]
+    pub timezone_mode: String,
+    #[serde(default)]
+    pub fixed_timezone: String,
+    /// "12h" or "24h" for every time shown to the user and the assistant.
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+    /// Serve the generated demo database instead of real history; recording is off meanwhile.
+    #[serde(default)]
+    pub demo_mode: bool,
+    #[serde(default)]
+    pub hotkeys: HotkeySettings,
+}
+
+/// Global shortcuts, written like "Ctrl+Alt+Space" or "Win+Shift+I". Empty leaves the action unbound.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HotkeySettings {
+    pub toggle_incognito: String,
+    pub quick_chat: String,
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            toggle_incognito: "Ctrl+Alt+I".to_string(),
+            quick_chat: "Ctrl+Alt+Space".to_string(),
+        }
+    }
 }
 
 impl Default for GeneralSettings {
@@ -45,6 +142,11 @@ impl Default for GeneralSettings {
             startup_behavior: "minimized_to_tray".to_string(),
             minimize_to_tray: true,
             close_to_tray: true,
+            timezone_mode: default_timezone_mode(),
+            fixed_timezone: String::new(),
+            time_format: default_time_format(),
+            demo_mode: false,
+            hotkeys: HotkeySettings::default(),
         }
     }
 }
@@ -53,7 +155,16 @@ fn default_enable_startup() -> bool {
     true
 }
 
+fn default_timezone_mode() -> String {
+    "system".to_string()
+}
+
+fn default_time_format() -> String {
+    "12h".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TrackingSettings {
     pub enabled: bool,
     pub tracking_interval: u64,
@@ -61,6 +172,165 @@ pub struct TrackingSettings {
     pub exclude_apps: Vec<String>,
     pub exclude_urls: Vec<String>,
     pub track_browser: bool,
+    #[serde(default)]
+    pub save_thumbnails: bool,
+    #[serde(default)]
+    pub sources: TrackingSources,
+    /// OCR text scoring below this (0–1) is discarded as noise; lower it if real text gets dropped.
+    #[serde(default = "default_ocr_min_quality")]
+    pub ocr_min_quality: f64,
+    /// BCP-47 tags (e.g. "de-DE", "en-US") to OCR with; each capture keeps the best-scoring
+    /// language. Empty uses the Windows profile languages.
+    #[serde(default)]
+    pub ocr_languages: Vec<String>,
+    /// Seconds between screen captures.
+    #[serde(default = "default_ocr_interval")]
+    pub ocr_interval: u64,
+    /// Scale `ocr_interval` by the focused window's category: shorter for chats and
+    /// browsing, longer for development, paused for fullscreen video and games.
+    #[serde(default = "default_adaptive_ocr")]
+    pub adaptive_ocr: bool,
+    /// Bounds in seconds for the adapted interval; `sanitize` keeps min <= max.
+    #[serde(default = "default_ocr_min_interval")]
+    pub ocr_min_interval: u64,
+    #[serde(default = "default_ocr_max_interval")]
+    pub ocr_max_interval: u64,
+    /// Folders the file monitor watches. Empty falls back to `INTENTFLOW_CODE_ROOTS`,
+    /// then the usual code folders under the user profile.
+    #[serde(default)]
+    pub code_roots: Vec<String>,
+    /// Executable names (case-insensitive substring, e.g. "docker desktop", "node")
+    /// whose running time is recorded in `process_events`. Empty turns it off.
+    #[serde(default)]
+    pub process_watch_list: Vec<String>,
+    /// How screen text is kept per app; the first profile whose pattern matches wins,
+    /// anything unmatched uses `full`.
+    #[serde(default = "default_ocr_profiles")]
+    pub ocr_profiles: Vec<OcrProfile>,
+    /// Also emit the in-progress `ocr://status` stages (capturing, ocr-running),
+    /// not just each capture's outcome.
+    #[serde(default)]
+    pub ocr_debug_events: bool,
+    /// When non-empty, OCR runs only for apps matching one of these (case-insensitive
+    /// substring of the app name); `exclude_apps` still wins over it.
+    #[serde(default)]
+    pub ocr_allowed_apps: Option<Vec<String>>,
+    /// Local hours [start, end) the user is normally at the computer; stretches without
+    /// activity outside them aren't reported as tracking gaps. `end <= start` wraps past midnight.
+    #[serde(default = "default_active_hours_start")]
+    pub active_hours_start: u32,
+    #[serde(default = "default_active_hours_end")]
+    pub active_hours_end: u32,
+    /// Minimum minutes without any recorded activity reported as a tracking gap.
+    #[serde(default = "default_gap_threshold_minutes")]
+    pub gap_threshold_minutes: u64,
+    /// Window titles (case-insensitive, whole title) never recorded as background windows.
+    #[serde(default = "default_background_window_denylist")]
+    pub background_window_denylist: Vec<String>,
+}
+
+/// How OCR text of one app is cleaned before it is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrMode {
+    /// All recognized text flattened to a single line.
+    #[default]
+    Full,
+    /// One recognized line per stored line, so chat messages stay separate.
+    LinesPreserved,
+    /// No OCR at all, e.g. for editors the file monitor already covers.
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrProfile {
+    /// Case-insensitive substring of the app name, as in `exclude_apps`.
+    pub app_pattern: String,
+    pub mode: OcrMode,
+}
+
+impl OcrProfile {
+    fn new(app_pattern: &str, mode: OcrMode) -> Self {
+        Self { app_pattern: app_pattern.to_string(), mode }
+    }
+}
+
+/// Chat apps keep their line breaks; editors and terminals are skipped.
+pub fn default_ocr_profiles() -> Vec<OcrProfile> {
+    let chat = ["whatsapp", "telegram", "signal", "discord", "slack", "teams", "messenger", "instagram"];
+    let skipped = [
+        "visual studio code",
+        "cursor",
+        "intellij",
+        "pycharm",
+        "webstorm",
+        "rider",
+        "android studio",
+        "windows terminal",
+        "powershell",
+        "command prompt",
+        "wezterm",
+        "alacritty",
+    ];
+    chat.iter()
+        .map(|app| OcrProfile::new(app, OcrMode::LinesPreserved))
+        .chain(skipped.iter().map(|app| OcrProfile::new(app, OcrMode::Skip)))
+        .collect()
+}
+
+fn default_ocr_min_quality() -> f64 {
+    crate::services::screen_capture::DEFAULT_MIN_OCR_QUALITY
+}
+
+fn default_ocr_interval() -> u64 {
+    crate::services::screen_capture::DEFAULT_OCR_INTERVAL_SECS
+}
+
+fn default_adaptive_ocr() -> bool {
+    true
+}
+
+fn default_ocr_min_interval() -> u64 {
+    5
+}
+
+fn default_ocr_max_interval() -> u64 {
+    30
+}
+
+fn default_active_hours_start() -> u32 {
+    8
+}
+
+fn default_active_hours_end() -> u32 {
+    23
+}
+
+fn default_gap_threshold_minutes() -> u64 {
+    30
+}
+
+pub fn default_background_window_denylist() -> Vec<String> {
+    [
+        "Program Manager",
+        "Windows Input Experience",
+        "Microsoft Text Input Application",
+        "Windows Shell Experience Host",
+        "Settings",
+        "Calculator",
+        "NVIDIA GeForce Overlay",
+    ]
+    .iter()
+    .map(|t| t.to_string())
+    .collect()
+}
+
+/// Optional capture sources beyond window tracking and OCR. All off by default.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TrackingSources {
+    /// Record copied text (redacted, truncated) into `clipboard_events`.
+    #[serde(default)]
+    pub clipboard: bool,
 }
 
 impl Default for TrackingSettings {
@@ -72,11 +342,29 @@ impl Default for TrackingSettings {
             exclude_apps: vec![],
             exclude_urls: vec![],
             track_browser: true,
+            save_thumbnails: false,
+            sources: TrackingSources::default(),
+            ocr_min_quality: default_ocr_min_quality(),
+            ocr_languages: Vec::new(),
+            ocr_interval: default_ocr_interval(),
+            adaptive_ocr: default_adaptive_ocr(),
+            ocr_min_interval: default_ocr_min_interval(),
+            ocr_max_interval: default_ocr_max_interval(),
+            code_roots: Vec::new(),
+            process_watch_list: Vec::new(),
+            ocr_profiles: default_ocr_profiles(),
+            ocr_debug_events: false,
+            ocr_allowed_apps: None,
+            active_hours_start: default_active_hours_start(),
+            active_hours_end: default_active_hours_end(),
+            gap_threshold_minutes: default_gap_threshold_minutes(),
+            background_window_denylist: default_background_window_denylist(),
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StorageSettings {
     pub retention_days: i32,
     pub auto_cleanup: bool,
@@ -96,6 +384,7 @@ impl Default for StorageSettings {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AISettings {
     pub enabled: bool,
     pub provider: String,
@@ -103,6 +392,240 @@ pub struct AISettings {
     pub model: String,
     pub local_only: bool,
     pub fallback_to_local: bool,
+    /// Agent tools the chat assistant may not call: hidden from the prompt and
+    /// rejected if the model calls them anyway. Tools added later start enabled.
+    pub disabled_tools: Vec<String>,
+    /// PEM/DER CA certificate trusted in addition to the system store,
+    /// for networks behind a TLS-inspecting proxy.
+    #[serde(default)]
+    pub extra_ca_cert: Option<String>,
+    /// Per-model capability overrides keyed by model id; these win over the
+    /// built-in table and probe results.
+    #[serde(default)]
+    pub model_overrides: HashMap<String, ModelCapabilities>,
+    /// Language of answers, summaries and fallback text: "auto" follows the
+    /// language of each question, otherwise a code such as "en", "fr" or "de".
+    #[serde(default = "default_answer_language")]
+    pub answer_language: String,
+    /// Sampling temperature for the chat agent's tool planning and answers.
+    #[serde(default = "default_agent_temperature")]
+    pub agent_temperature: f32,
+    /// Sampling temperature for the linear summary used when the agent fails.
+    #[serde(default = "default_summary_temperature")]
+    pub summary_temperature: f32,
+    /// Sampling temperature for the dashboard overview.
+    #[serde(default = "default_dashboard_temperature")]
+    pub dashboard_temperature: f32,
+    /// Guardrails of the chat agent's tool loop.
+    #[serde(default)]
+    pub agent: AgentSettings,
+    /// Named model presets (e.g. "fast", "smart") that a query can pick instead of `model`.
+    #[serde(default)]
+    pub ai_profiles: Vec<AiProfile>,
+    /// Profile used when a request doesn't name one; `None` uses `model` and the temperatures above.
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Profile for the dashboard overview and summaries; `None` follows `active_profile`.
+    #[serde(default)]
+    pub dashboard_profile: Option<String>,
+}
+
+/// One entry of `AISettings::ai_profiles`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AiProfile {
+    pub name: String,
+    pub model: String,
+    /// OpenAI-compatible API root such as "https://openrouter.ai/api/v1"; empty uses the default endpoint.
+    /// Applies to every call made with this profile's model.
+    pub base_url: String,
+    /// Replaces all three `*_temperature` settings while the profile is in use.
+    pub temperature: Option<f32>,
+    /// Completion budget per agent turn; replaces the model's capability value.
+    pub max_tokens: Option<u32>,
+}
+
+/// Knobs for how hard the chat agent works before it may answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentSettings {
+    /// LLM turns per answer before the agent gives up.
+    pub max_turns: usize,
+    /// Tool calls required before answering who a person is (e.g. "who is my crush"); 0 turns the rule off.
+    pub min_evidence_tools_for_identity: usize,
+    /// Run a `parallel_search` on the model's behalf when it tries to answer without evidence.
+    pub force_validation_enabled: bool,
+    /// Phrases that make a narrow scope too small; the agent is told to widen it first.
+    pub broad_scope_keywords: Vec<String>,
+    /// Greetings and thanks are answered without any tool calls.
+    pub smalltalk_bypass: bool,
+    /// Summary questions spanning at least this many days use the multi-step long-range pipeline.
+    pub long_range_pipeline_threshold_days: i64,
+    /// How much evidence the agent must gather before its answer is accepted.
+    pub evidence_strictness: EvidenceStrictness,
+}
+
+/// Trade answer rigor for fewer LLM turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EvidenceStrictness {
+    /// Accept the model's first answer: no forced searches, evidence checks or claim scrubbing.
+    Off,
+    /// Forced validation and evidence checks, without the identity tool-call minimum.
+    #[default]
+    Normal,
+    /// Everything, including `min_evidence_tools_for_identity`.
+    Strict,
+}
+
+/// Accepted range for `AgentSettings::max_turns`.
+pub const AGENT_MAX_TURNS_RANGE: std::ops::RangeInclusive<usize> = 1..=50;
+
+pub fn default_broad_scope_keywords() -> Vec<String> {
+    [
+        // Time
+        "first", "last time", "ever", "always", "never", "usually", "often",
+        "history", "past", "before", "earlier", "since", "overall", "all time",
+        "months", "years", "weeks", "days ago", "long time", "recently",
+        // Aggregates
+        "how many times", "how often", "when did i", "longest", "best", "worst",
+        "favorite", "most", "top", "frequent",
+        // Identity
+        "who is", "what is my", "guess", "crush", "relationship", "friend", "girlfriend", "boyfriend",
+    ]
+    .iter()
+    .map(|k| k.to_string())
+    .collect()
+}
+
+impl Default for AgentSettings {
+    fn default() -> Self {
+        Self {
+            max_turns: 20,
+            min_evidence_tools_for_identity: 5,
+            force_validation_enabled: true,
+            broad_scope_keywords: default_broad_scope_keywords(),
+            smalltalk_bypass: true,
+            long_range_pipeline_threshold_days: 90,
+            evidence_strictness: EvidenceStrictness::default(),
+        }
+    }
+}
+
+fn default_answer_language() -> String {
+    crate::utils::i18n::AUTO.to_string()
+}
+
+/// Range accepted for the `*_temperature` settings.
+pub const TEMPERATURE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=2.0;
+
+pub fn default_agent_temperature() -> f32 {
+    0.0
+}
+
+fn default_summary_temperature() -> f32 {
+    0.7
+}
+
+fn default_dashboard_temperature() -> f32 {
+    0.2
+}
+
+impl AISettings {
+    /// Rejects temperatures outside `TEMPERATURE_RANGE`.
+    pub fn validate_temperatures(&self) -> Result<(), String> {
+        let profiles = self
+            .ai_profiles
+            .iter()
+            .filter_map(|p| p.temperature.map(|t| (format!("temperature of profile \"{}\"", p.name), t)));
+        for (name, value) in [
+            ("agent_temperature".to_string(), self.agent_temperature),
+            ("summary_temperature".to_string(), self.summary_temperature),
+            ("dashboard_temperature".to_string(), self.dashboard_temperature),
+        ]
+        .into_iter()
+        .chain(profiles)
+        {
+            if !TEMPERATURE_RANGE.contains(&value) {
+                return Err(format!(
+                    "{} must be between {:.1} and {:.1}, got {}",
+                    name,
+                    TEMPERATURE_RANGE.start(),
+                    TEMPERATURE_RANGE.end(),
+                    value
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Profile `name`, or the active profile when `name` is `None`. Names match case-insensitively.
+    pub fn profile(&self, name: Option<&str>) -> Result<Option<&AiProfile>, String> {
+        let Some(name) = name.or(self.active_profile.as_deref()).map(str::trim).filter(|n| !n.is_empty()) else {
+            return Ok(None);
+        };
+        self.ai_profiles
+            .iter()
+            .find(|p| p.name.trim().eq_ignore_ascii_case(name))
+            .map(Some)
+            .ok_or_else(|| format!("AI profile \"{}\" not found", name))
+    }
+
+    /// Switch these settings to profile `name` (or the active one): its model
+    /// replaces `model` and its temperature, when set, every `*_temperature`.
+    pub fn apply_profile(&mut self, name: Option<&str>) -> Result<(), String> {
+        let Some(profile) = self.profile(name)?.cloned() else {
+            return Ok(());
+        };
+        if !profile.model.trim().is_empty() {
+            self.model = profile.model.trim().to_string();
+        }
+        if let Some(temperature) = profile.temperature {
+            self.agent_temperature = temperature;
+            self.summary_temperature = temperature;
+            self.dashboard_temperature = temperature;
+        }
+        Ok(())
+    }
+
+    /// `apply_profile` for background work: a missing profile is logged and the defaults kept.
+    pub fn apply_profile_or_default(&mut self, name: Option<&str>) {
+        if let Err(e) = self.apply_profile(name) {
+            log::warn!("[AI] {}, using the default model", e);
+        }
+    }
+}
+
+/// What a chat model handles well; the agent sizes its prompt and output from this.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelCapabilities {
+    /// Context window in tokens.
+    pub context_window: u32,
+    /// Streams `reasoning_content` (or `<think>` blocks) before the answer.
+    pub supports_reasoning: bool,
+    /// Accepts the OpenAI `tools` request field.
+    pub supports_native_tools: bool,
+    /// Completion budget per agent turn, reasoning included.
+    pub max_tokens: u32,
+}
+
+/// Below this context window the agent uses the compact prompt and smaller evidence budgets.
+pub const COMPACT_CONTEXT_WINDOW: u32 = 16_384;
+
+impl ModelCapabilities {
+    pub fn is_compact(&self) -> bool {
+        self.context_window < COMPACT_CONTEXT_WINDOW
+    }
+
+    /// Characters of one tool result kept in the conversation.
+    pub fn tool_output_chars(&self) -> usize {
+        if self.is_compact() { 3_000 } else { 10_000 }
+    }
+
+    /// Characters of prefetched/forced evidence injected as a user message.
+    pub fn evidence_chars(&self) -> usize {
+        if self.is_compact() { 1_500 } else { 3_500 }
+    }
 }
 
 impl Default for AISettings {
@@ -115,15 +638,35 @@ impl Default for AISettings {
             model: "moonshotai/kimi-k2-instruct-0905".to_string(),
             local_only: false,
             fallback_to_local: true,
+            disabled_tools: Vec::new(),
+            extra_ca_cert: None,
+            model_overrides: HashMap::new(),
+            answer_language: default_answer_language(),
+            agent_temperature: default_agent_temperature(),
+            summary_temperature: default_summary_temperature(),
+            dashboard_temperature: default_dashboard_temperature(),
+            agent: AgentSettings::default(),
+            ai_profiles: Vec::new(),
+            active_profile: None,
+            dashboard_profile: None,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PrivacySettings {
     pub encrypt_database: bool,
     pub exclude_incognito: bool,
     pub anonymize_data: bool,
+    #[serde(default)]
+    pub include_private_in_ai: bool,
+    /// Ask for the unlock password before showing the main window.
+    #[serde(default)]
+    pub require_unlock: bool,
+    /// Argon2 PHC string; only written by `set_unlock_password`, never sent to the frontend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unlock_password_hash: Option<String>,
 }
 
 impl Default for PrivacySettings {
@@ -132,16 +675,23 @@ impl Default for PrivacySettings {
             encrypt_database: false,
             exclude_incognito: true,
             anonymize_data: false,
+            include_private_in_ai: false,
+            require_unlock: false,
+            unlock_password_hash: None,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NotificationSettings {
     pub workflow_suggestions: bool,
     pub pattern_insights: bool,
     pub daily_summary: bool,
     pub summary_time: String,
+    /// Case-insensitive keywords that trigger an alert when they appear in fresh OCR text.
+    #[serde(default)]
+    pub ocr_alert_keywords: Vec<String>,
 }
 
 impl Default for NotificationSettings {
@@ -151,6 +701,33 @@ impl Default for NotificationSettings {
             pattern_insights: true,
             daily_summary: true,
             summary_time: "09:00".to_string(),
+            ocr_alert_keywords: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportSettings {
+    pub weekly_enabled: bool,
+    /// Lowercase weekday name, e.g. "friday".
+    pub weekday: String,
+    /// Local time "HH:MM" after which the weekly report is written.
+    pub time: String,
+    /// "html", "markdown" or "both".
+    pub format: String,
+    /// Empty means `<app data>/reports`.
+    pub output_dir: String,
+}
+
+impl Default for ReportSettings {
+    fn default() -> Self {
+        Self {
+            weekly_enabled: false,
+            weekday: "friday".to_string(),
+            time: "16:00".to_string(),
+            format: "html".to_string(),
+            output_dir: String::new(),
         }
     }
 }
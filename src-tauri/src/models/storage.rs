@@ -9,4 +9,196 @@ pub struct StorageStats {
     pub entries_count: i64,
     pub oldest_activity: i64,
     pub newest_activity: i64,
+    /// Every table with its row count and size (indexes included), largest first.
+    #[serde(default)]
+    pub tables: Vec<TableStorage>,
+    /// Bytes per kind of data, database and files on disk together; for the breakdown chart.
+    #[serde(default)]
+    pub categories: Vec<StorageCategoryUsage>,
+    /// UTF-8 bytes of OCR text stored in activity metadata.
+    #[serde(default)]
+    pub ocr_text_bytes: i64,
+    #[serde(default)]
+    pub thumbnails_bytes: i64,
+    #[serde(default)]
+    pub icons_bytes: i64,
+    /// Database bytes in use divided by the days since the oldest activity.
+    #[serde(default)]
+    pub bytes_per_day: i64,
+    /// "dbstat" when page sizes were measured, "estimate" when summed from column lengths.
+    #[serde(default)]
+    pub size_source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableStorage {
+    pub table: String,
+    pub row_count: i64,
+    pub size_bytes: i64,
+    /// From the table's time column, when it has one.
+    pub oldest_record: Option<i64>,
+    pub newest_record: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageCategoryUsage {
+    pub category: String,
+    pub size_bytes: i64,
+}
+
+/// Where the database is heading: recent daily ingest, a linear forecast and
+/// what shrinks it. Byte counts are estimates scaled to on-disk size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageForecast {
+    pub total_size_bytes: i64,
+    /// "dbstat" or "estimate", as in `StorageStats`.
+    pub size_source: String,
+    /// Raw bytes written per local day over the last 30 days, oldest first; days without data are 0.
+    pub daily_ingest: Vec<DailyIngest>,
+    /// Slope of a least-squares fit over the cumulative ingest, scaled by `overhead_factor`.
+    pub bytes_per_day: i64,
+    /// Database bytes in use divided by raw column bytes (indexes, page slack).
+    pub overhead_factor: f64,
+    pub forecast_30d_bytes: i64,
+    pub forecast_90d_bytes: i64,
+    pub limit_bytes: i64,
+    /// `None` when the database is not growing.
+    pub days_until_limit: Option<i64>,
+    /// Largest kinds of stored data, biggest first.
+    pub contributors: Vec<StorageContributor>,
+    pub retention_impacts: Vec<RetentionImpact>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyIngest {
+    /// "YYYY-MM-DD".
+    pub date_key: String,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageContributor {
+    pub name: String,
+    pub size_bytes: i64,
+    pub rows: i64,
+}
+
+/// A retention change and roughly how much it would free right now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionImpact {
+    /// "retention_days" or "ocr_retention_days".
+    pub setting: String,
+    pub days: i64,
+    pub bytes_removed: i64,
+    pub description: String,
+}
+
+/// Rows that mention a keyword, per table. Counts only; content is never returned.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct KeywordAudit {
+    pub keyword: String,
+    pub activity_title_matches: i64,
+    pub activity_app_matches: i64,
+    pub activity_ocr_matches: i64,
+    /// URL, background windows or media info; the OCR text is counted above.
+    pub activity_metadata_matches: i64,
+    pub chat_message_matches: i64,
+    pub chat_session_matches: i64,
+    pub dashboard_snapshot_matches: i64,
+    pub code_file_event_matches: i64,
+    pub query_cache_matches: i64,
+    /// Rows of logs and derived tables (clipboard, notifications, checkpoint, ...).
+    pub other_matches: i64,
+    /// "fts" when the OCR index was used, otherwise "like".
+    pub ocr_match_mode: String,
+    pub first_match_at: Option<i64>,
+    pub last_match_at: Option<i64>,
+    pub purged: bool,
+    /// Affected rows per table when `purged` is true.
+    pub purged_rows: Vec<PurgedTableRows>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgedTableRows {
+    pub table: String,
+    pub action: String,
+    pub rows: i64,
+}
+
+/// Rows written by `seed_sample_data` or the demo database generator.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SampleDataSummary {
+    pub activities: usize,
+    pub file_events: usize,
+    pub media_sessions: usize,
+    pub ocr_snapshots: usize,
+    pub chat_sessions: usize,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+/// Which activity rows `delete_activities` removes. Criteria combine with AND;
+/// at least one of the time range, apps or categories must be set.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ActivityDeleteFilter {
+    /// Unix seconds; rows overlapping `[start_time, end_time)` match.
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    /// Case-insensitive substrings of the app name, e.g. "discord".
+    pub app_patterns: Vec<String>,
+    pub category_ids: Vec<i32>,
+    /// Also delete code file events in the time range (needs a time range).
+    pub include_file_events: bool,
+    /// Count what would be deleted without deleting anything.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ActivityDeletion {
+    pub dry_run: bool,
+    /// Affected rows per table; identical between a dry run and the real deletion.
+    pub rows: Vec<PurgedTableRows>,
+    /// Local days (YYYY-MM-DD) whose rollups and dashboard snapshot were touched.
+    pub affected_days: Vec<String>,
+    /// `deletion_log` row written for a real deletion.
+    pub log_id: Option<i64>,
+}
+
+/// Result of `purge_recent_data`. The window is `[start_time, end_time]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentDataPurge {
+    pub start_time: i64,
+    pub end_time: i64,
+    pub rows: Vec<PurgedTableRows>,
+}
+
+
+/// Result of `verify_data_integrity`; nothing is repaired.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DataIntegrityReport {
+    pub clean: bool,
+    pub violations: Vec<IntegrityViolation>,
+    pub checked_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityViolation {
+    /// e.g. "unknown_category", "null_window_title", "missing_index".
+    pub check: String,
+    pub table: String,
+    pub count: i64,
+    pub detail: String,
+}
+
+/// Payload of `index://progress` and result of `rebuild_indexes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexRebuildProgress {
+    /// Activities re-indexed so far, out of `total` when the rebuild started.
+    pub processed: i64,
+    pub total: i64,
+    /// Of `processed`, those with OCR text in the index.
+    pub indexed: i64,
+    pub done: bool,
+    pub cancelled: bool,
 }
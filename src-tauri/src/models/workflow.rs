@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::intent::ActionType;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workflow {
     pub id: String,
@@ -18,6 +20,63 @@ pub struct Workflow {
 pub struct AppLaunch {
     pub path: String,
     pub args: Vec<String>,
+    /// Checked before the workflow runs anything.
+    #[serde(default)]
+    pub preconditions: Vec<WorkflowPrecondition>,
+    /// Skip just this launch when its checks fail instead of aborting the whole workflow.
+    #[serde(default)]
+    pub continue_on_failure: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WorkflowPrecondition {
+    /// File or folder, e.g. a project on an external drive.
+    PathExists { path: String },
+    /// Executable name resolvable through App Paths or `PATH` (e.g. "Code.exe"), or a full path.
+    AppInstalled { exe: String },
+    /// "host" or "host:port" (default port 443) accepting a TCP connection.
+    NetworkReachable { host: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreconditionCheck {
+    /// Path of the app launch the check belongs to.
+    pub action: String,
+    pub precondition: WorkflowPrecondition,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Outcome of `execute_workflow`; `validate_workflow` fills only the checks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunResult {
+    /// False when a failed check aborted the run before any action was taken, and on a dry run.
+    pub executed: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Dry run only: every action in execution order with what it resolved to.
+    #[serde(default)]
+    pub planned: Vec<PlannedAction>,
+    pub checks: Vec<PreconditionCheck>,
+    /// Launches skipped because their checks failed with `continue_on_failure`.
+    pub skipped_actions: Vec<String>,
+    /// Actions that were attempted but could not be started.
+    pub errors: Vec<String>,
+}
+
+/// One step of a dry run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedAction {
+    pub action_type: ActionType,
+    pub target: String,
+    pub args: Vec<String>,
+    /// Executable path found for a launch, or the absolute path of a file.
+    pub resolved: Option<String>,
+    /// False when a failed check would skip or abort it.
+    pub will_run: bool,
+    /// Why the step looks misconfigured, e.g. "not found on PATH".
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,17 +1,38 @@
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
-use crate::models::{ActivityEvent, ActivityMetadata};
+use crate::models::{ActivityEvent, ActivityMetadata, AppMerge, CategorizationExplanation, CategorizationSource};
+use crate::services::service_status::{self, ACTIVITY_TRACKER};
 
 static TRACKING_ENABLED: AtomicBool = AtomicBool::new(true);
 static TRACKING_INTERVAL_SECS: AtomicU64 = AtomicU64::new(DEFAULT_TRACKING_INTERVAL_SECS);
+/// Whether `session_checkpoint` may hold a row. Most sessions end before their
+/// first checkpoint, so this spares a connection per focus change. Starts true
+/// so a row left by a previous run is still cleared.
+static CHECKPOINT_PRESENT: AtomicBool = AtomicBool::new(true);
+/// Epoch ms set by `forget_since`; the loop drops or redacts the open session on its next tick. 0 when idle.
+static FORGET_SINCE_MS: AtomicI64 = AtomicI64::new(0);
+/// Category of the focused window as of the last poll; 0 when nothing is tracked.
+static FOREGROUND_CATEGORY: AtomicI32 = AtomicI32::new(0);
+static FOREGROUND_FULLSCREEN: AtomicBool = AtomicBool::new(false);
 
 const DEFAULT_TRACKING_INTERVAL_SECS: u64 = 10;
 const MIN_TRACKING_INTERVAL_SECS: u64 = 1;
 const MAX_TRACKING_INTERVAL_SECS: u64 = 60;
 const FOCUS_POLL_INTERVAL_MS: u64 = 200;
 const MIN_RECORDED_DURATION_MS: i64 = 1;
+const CHECKPOINT_INTERVAL_MS: i64 = 15_000;
+/// Finalized sessions are written in one transaction once this many are queued…
+const WRITE_BATCH_SIZE: usize = 20;
+/// …or once the oldest queued one is this old.
+const WRITE_BATCH_MAX_AGE: Duration = Duration::from_secs(5);
+const WRITE_QUEUE_CAPACITY: usize = 1024;
+/// How long an explicit flush waits for the writer.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+/// Forgotten windows the writer keeps filtering; older ones have long been applied.
+const MAX_FORGOTTEN_WINDOWS: usize = 8;
 
 #[derive(Clone)]
 struct ActivitySession {
@@ -23,26 +44,58 @@ struct ActivitySession {
 }
 
 pub fn start_tracking(app_handle: AppHandle) {
+    crate::services::settings_bus::spawn_subscriber(&app_handle, "activity_tracker", |settings| {
+        set_tracking_enabled(crate::effective_monitoring_enabled(settings.tracking.enabled));
+        set_tracking_interval(settings.tracking.tracking_interval);
+        crate::utils::windows::set_background_window_denylist(&settings.tracking.background_window_denylist);
+    });
+    start_writer(app_handle.clone());
     tauri::async_runtime::spawn(async move {
-        initialize_tracking_from_settings(&app_handle);
+        service_status::mark_started(ACTIVITY_TRACKER);
+        load_app_merges(&app_handle);
+        recover_checkpoint(&app_handle);
         let mut session: Option<ActivitySession> = None;
+        let mut last_checkpoint_ms: i64 = 0;
 
         loop {
             let interval_secs = tracking_interval_secs();
             let metadata_refresh_ms = (interval_secs * 1000) as i64;
             let now_ms = chrono::Utc::now().timestamp_millis();
 
-            if !TRACKING_ENABLED.load(Ordering::Relaxed) {
+            let forget_since = FORGET_SINCE_MS.swap(0, Ordering::Relaxed);
+            if forget_since > 0 {
+                if session.as_ref().is_some_and(|active| active.start_ms >= forget_since) {
+                    session = None;
+                    if let Err(e) = clear_checkpoint(&app_handle) {
+                        log::error!("Failed to clear session checkpoint: {}", e);
+                    }
+                } else if let Some(ref mut active) = session {
+                    active.metadata.clear_screen_capture();
+                }
+            }
+
+            // A timed pause is checked here as well so it holds even if a
+            // settings update re-enables tracking before the deadline.
+            if !TRACKING_ENABLED.load(Ordering::Relaxed)
+                || crate::services::tracking_pause::is_paused()
+            {
                 if let Some(active) = session.take() {
                     if let Err(e) = finalize_and_store_activity(&app_handle, active, now_ms) {
                         log::error!("Failed to store activity while disabling tracking: {}", e);
+                        service_status::report_error(ACTIVITY_TRACKER, &e);
                     }
+                    // Nothing more is coming; don't leave the last sessions waiting in the queue.
+                    request_flush();
                 }
+                publish_foreground(None);
+                service_status::heartbeat(ACTIVITY_TRACKER);
                 tokio::time::sleep(Duration::from_millis(FOCUS_POLL_INTERVAL_MS)).await;
                 continue;
             }
 
-            match get_active_window() {
+            let active_window = get_active_window();
+            publish_foreground(active_window.as_ref().ok().and_then(|w| w.as_ref()));
+            match active_window {
                 Ok(Some(window)) => {
                     if let Some(ref mut active) = session {
                         let is_same_window = active.window.app_name == window.app_name
@@ -51,7 +104,7 @@ pub fn start_tracking(app_handle: AppHandle) {
                         if is_same_window {
                             active.last_seen_ms = now_ms;
                             if now_ms - active.metadata_last_updated_ms >= metadata_refresh_ms {
-                                let refreshed = capture_metadata().await;
+                                let refreshed = capture_metadata(&app_handle, &active.window.app_name).await;
                                 merge_session_metadata(&mut active.metadata, refreshed);
                                 active.metadata_last_updated_ms = now_ms;
                             }
@@ -59,21 +112,24 @@ pub fn start_tracking(app_handle: AppHandle) {
                             let finished = active.clone();
                             if let Err(e) = finalize_and_store_activity(&app_handle, finished, now_ms) {
                                 log::error!("Failed to store activity on focus change: {}", e);
+                                service_status::report_error(ACTIVITY_TRACKER, &e);
                             }
+                            let metadata = capture_metadata(&app_handle, &window.app_name).await;
                             session = Some(ActivitySession {
                                 window,
                                 start_ms: now_ms,
                                 last_seen_ms: now_ms,
-                                metadata: capture_metadata().await,
+                                metadata,
                                 metadata_last_updated_ms: now_ms,
                             });
                         }
                     } else {
+                        let metadata = capture_metadata(&app_handle, &window.app_name).await;
                         session = Some(ActivitySession {
                             window,
                             start_ms: now_ms,
                             last_seen_ms: now_ms,
-                            metadata: capture_metadata().await,
+                            metadata,
                             metadata_last_updated_ms: now_ms,
                         });
                     }
@@ -82,39 +138,34 @@ pub fn start_tracking(app_handle: AppHandle) {
                     if let Some(active) = session.take() {
                         if let Err(e) = finalize_and_store_activity(&app_handle, active, now_ms) {
                             log::error!("Failed to store activity: {}", e);
+                            service_status::report_error(ACTIVITY_TRACKER, &e);
                         }
                     }
                 }
                 Err(e) => {
                     log::error!("Failed to get active window: {}", e);
+                    service_status::report_error(ACTIVITY_TRACKER, &e);
                 }
             }
 
+            if let Some(ref active) = session {
+                // The single checkpoint row may still describe a queued session;
+                // overwriting it before that is stored would leave a crash nothing to recover.
+                if QUEUED_ROWS.load(Ordering::Relaxed) > 0 {
+                    request_flush();
+                } else if now_ms - last_checkpoint_ms >= CHECKPOINT_INTERVAL_MS {
+                    if let Err(e) = write_checkpoint(&app_handle, active) {
+                        log::error!("Failed to write session checkpoint: {}", e);
+                    }
+                    last_checkpoint_ms = now_ms;
+                }
+            }
+
+            service_status::heartbeat(ACTIVITY_TRACKER);
             tokio::time::sleep(Duration::from_millis(FOCUS_POLL_INTERVAL_MS)).await;
         }
     });
 }
-fn initialize_tracking_from_settings(app_handle: &AppHandle) {
-    let data_dir = match app_handle.path().app_data_dir() {
-        Ok(dir) => dir,
-        Err(_) => return,
-    };
-    let config_path = data_dir.join("config").join("settings.json");
-    if !config_path.exists() {
-        return;
-    }
-
-    let content = match std::fs::read_to_string(config_path) {
-        Ok(content) => content,
-        Err(_) => return,
-    };
-
-    if let Ok(settings) = serde_json::from_str::<crate::models::Settings>(&content) {
-        set_tracking_enabled(settings.tracking.enabled);
-        set_tracking_interval(settings.tracking.tracking_interval);
-    }
-}
-
 fn clamp_tracking_interval(seconds: u64) -> u64 {
     seconds.clamp(MIN_TRACKING_INTERVAL_SECS, MAX_TRACKING_INTERVAL_SECS)
 }
@@ -123,16 +174,22 @@ fn tracking_interval_secs() -> u64 {
     clamp_tracking_interval(TRACKING_INTERVAL_SECS.load(Ordering::Relaxed))
 }
 
-async fn capture_metadata() -> ActivityMetadata {
+async fn capture_metadata(app_handle: &AppHandle, app_name: &str) -> ActivityMetadata {
     let mut metadata = ActivityMetadata::default();
-    metadata.screen_text = super::screen_capture::get_latest_screen_text();
+    if let Some(reading) = super::screen_capture::get_latest_screen_text() {
+        metadata.screen_text = Some(reading.text);
+        metadata.ocr_score = Some(reading.score);
+        metadata.ocr_language = reading.language;
+    }
+    metadata.thumbnail_path = super::screen_capture::get_latest_thumbnail_for(app_name);
 
     let bg_windows = crate::utils::windows::get_open_windows();
     if !bg_windows.is_empty() {
         metadata.background_windows = Some(bg_windows);
     }
 
-    metadata.media_info = match tokio::task::spawn_blocking(|| crate::utils::windows::get_media_info()).await {
+    let handle = app_handle.clone();
+    metadata.media_info = match tokio::task::spawn_blocking(move || super::now_playing::poll(&handle)).await {
         Ok(info) => info,
         Err(e) => {
             println!("[Tracker] SMTC spawn_blocking failed: {:?}", e);
@@ -146,13 +203,18 @@ async fn capture_metadata() -> ActivityMetadata {
 fn merge_session_metadata(current: &mut ActivityMetadata, incoming: ActivityMetadata) {
     if incoming.screen_text.is_some() {
         current.screen_text = incoming.screen_text;
+        current.ocr_score = incoming.ocr_score;
+        current.ocr_language = incoming.ocr_language;
     }
-    if incoming.background_windows.is_some() {
-        current.background_windows = incoming.background_windows;
+    if let Some(titles) = incoming.background_windows {
+        current.record_background_windows(titles, chrono::Utc::now().timestamp());
     }
     if incoming.media_info.is_some() {
         current.media_info = incoming.media_info;
     }
+    if incoming.thumbnail_path.is_some() {
+        current.thumbnail_path = incoming.thumbnail_path;
+    }
 }
 
 fn finalize_and_store_activity(
@@ -160,6 +222,13 @@ fn finalize_and_store_activity(
     session: ActivitySession,
     end_ms: i64,
 ) -> Result<(), String> {
+    // Alerts are counted and the checkpoint cleared by the writer once the row is stored.
+    enqueue_activity(app_handle, session_activity(session, end_ms));
+    Ok(())
+}
+
+/// The activity row for `session` ending at `end_ms`.
+fn session_activity(session: ActivitySession, end_ms: i64) -> ActivityEvent {
     let duration_ms = (end_ms - session.start_ms).max(MIN_RECORDED_DURATION_MS);
     let duration_seconds = ((duration_ms + 999) / 1000) as i32;
 
@@ -176,8 +245,111 @@ fn finalize_and_store_activity(
     activity.duration_seconds = duration_seconds;
     activity.metadata = session.metadata;
     activity.metadata.raw_duration_ms = Some(duration_ms);
+    if log::log_enabled!(log::Level::Debug) {
+        let stored = serde_json::to_string(&(&activity.metadata.background_windows, &activity.metadata.background_changes))
+            .map(|json| json.len())
+            .unwrap_or(0);
+        log::debug!(
+            "[Tracker] Background windows for '{}': {} seen, {} changes, {} bytes",
+            activity.app_name,
+            activity.metadata.background_windows_seen().len(),
+            activity.metadata.background_changes.len(),
+            stored
+        );
+    }
+    activity.metadata.media_kind = activity.metadata.media_info.as_ref().map(|media| {
+        crate::utils::media::classify_media(
+            media,
+            &activity.app_name,
+            &activity.window_title,
+            duration_seconds as i64,
+        )
+    });
+    activity
+}
+
+fn open_db(app_handle: &AppHandle) -> Result<rusqlite::Connection, String> {
+    Ok(crate::database::open(app_handle)?)
+}
+
+fn write_checkpoint(app_handle: &AppHandle, session: &ActivitySession) -> Result<(), String> {
+    if crate::database::is_demo_mode() {
+        return Ok(());
+    }
+    save_checkpoint(&open_db(app_handle)?, session)?;
+    CHECKPOINT_PRESENT.store(true, Ordering::Relaxed);
+    Ok(())
+}
 
-    store_activity(app_handle, &activity)
+fn save_checkpoint(conn: &rusqlite::Connection, session: &ActivitySession) -> Result<(), String> {
+    let metadata_blob = serde_json::to_vec(&session.metadata).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO session_checkpoint
+         (id, app_name, window_title, category_id, start_ms, last_seen_ms, metadata)
+         VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            &session.window.app_name,
+            &session.window.title,
+            session.window.category_id,
+            session.start_ms,
+            session.last_seen_ms,
+            &metadata_blob,
+        ],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn clear_checkpoint(app_handle: &AppHandle) -> Result<(), String> {
+    if !CHECKPOINT_PRESENT.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    let conn = open_db(app_handle).inspect_err(|_| CHECKPOINT_PRESENT.store(true, Ordering::Relaxed))?;
+    conn.execute("DELETE FROM session_checkpoint", []).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Store the session a previous run left open, ending at the last time its
+/// window was seen so it can't overlap the session that starts now.
+fn recover_checkpoint(app_handle: &AppHandle) {
+    let checkpoint = open_db(app_handle).and_then(|conn| load_checkpoint(&conn).map_err(|e| e.to_string()));
+    let Ok(mut session) = checkpoint else { return; };
+    let end_ms = session.last_seen_ms;
+    session.metadata.recovered = true;
+    println!(
+        "[Tracker] Recovering unfinished session: {} ({}s)",
+        session.window.app_name,
+        (end_ms - session.start_ms) / 1000
+    );
+    if let Err(e) = finalize_and_store_activity(app_handle, session, end_ms) {
+        log::error!("Failed to store recovered session: {}", e);
+        service_status::report_error(ACTIVITY_TRACKER, &e);
+        let _ = clear_checkpoint(app_handle);
+    }
+}
+
+fn load_checkpoint(conn: &rusqlite::Connection) -> rusqlite::Result<ActivitySession> {
+    conn.query_row(
+        "SELECT app_name, window_title, category_id, start_ms, last_seen_ms, metadata
+         FROM session_checkpoint WHERE id = 1",
+        [],
+        |row| {
+            let metadata: Option<Vec<u8>> = row.get(5)?;
+            Ok(ActivitySession {
+                window: ActiveWindow {
+                    app_name: row.get(0)?,
+                    title: row.get(1)?,
+                    category_id: row.get(2)?,
+                    is_fullscreen: false,
+                },
+                start_ms: row.get(3)?,
+                last_seen_ms: row.get(4)?,
+                metadata: metadata
+                    .and_then(|b| serde_json::from_slice(&b).ok())
+                    .unwrap_or_default(),
+                metadata_last_updated_ms: 0,
+            })
+        },
+    )
 }
 
 #[derive(Clone)]
@@ -185,6 +357,7 @@ struct ActiveWindow {
     app_name: String,
     title: String,
     category_id: i32,
+    is_fullscreen: bool,
 }
 
 fn get_active_window() -> Result<Option<ActiveWindow>, String> {
@@ -192,16 +365,21 @@ fn get_active_window() -> Result<Option<ActiveWindow>, String> {
     match active_win_pos_rs::get_active_window() {
         Ok(window) => {
             // Sanitize app_name - remove control characters and normalize
-            let app_name = sanitize_app_name(&window.app_name);
+            let app_name = canonical_app_name(sanitize_app_name(&window.app_name));
             let title = window.title;
+            crate::services::app_icons::remember_executable(&app_name, &window.process_path);
             
             // Categorize the window
             let category_id = categorize_window(&app_name, &title);
+            let position = &window.position;
+            let is_fullscreen =
+                crate::utils::windows::covers_monitor(position.x, position.y, position.width, position.height);
             
             Ok(Some(ActiveWindow {
                 app_name,
                 title,
                 category_id,
+                is_fullscreen,
             }))
         }
         Err(_) => Ok(None),
@@ -226,163 +404,421 @@ fn sanitize_app_name(name: &str) -> String {
     cleaned
 }
 
+/// Lowercased merged name -> canonical app name, from `app_merge_map`.
+fn app_merges_store() -> &'static Mutex<std::collections::HashMap<String, String>> {
+    static STORE: OnceLock<Mutex<std::collections::HashMap<String, String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(std::collections::HashMap::new()))
+}
+
+fn load_app_merges(app_handle: &AppHandle) {
+    let merges = open_db(app_handle)
+        .and_then(|conn| crate::database::queries::get_app_merges(&conn).map_err(|e| e.to_string()));
+    match merges {
+        Ok(merges) => set_app_merges(&merges),
+        Err(e) => log::error!("Failed to load app merges: {}", e),
+    }
+}
+
+/// Replace the merges applied to newly captured windows.
+pub fn set_app_merges(merges: &[AppMerge]) {
+    if let Ok(mut store) = app_merges_store().lock() {
+        *store = merges
+            .iter()
+            .filter(|m| !m.search_only)
+            .map(|m| (m.from_name.to_lowercase(), m.to_name.clone()))
+            .collect();
+    }
+}
+
+fn canonical_app_name(app_name: String) -> String {
+    app_merges_store()
+        .lock()
+        .ok()
+        .and_then(|store| store.get(&app_name.to_lowercase()).cloned())
+        .unwrap_or(app_name)
+}
+
+/// Which part of the window a built-in rule looks at.
+#[derive(Clone, Copy)]
+enum RuleField {
+    App,
+    Title,
+}
+
+/// Built-in heuristics in match order: (category, field, substrings).
+/// Entertainment titles come before browsers so Spotify/YouTube playing inside
+/// a browser is Entertainment; Spotify web shows tracks as "Song • Artist - Browser".
+const BUILTIN_RULES: &[(i32, RuleField, &[&str])] = &[
+    // Development (category 1)
+    (1, RuleField::App, &[
+        "code", "vscode", "antigravity", "cursor", "idea", "pycharm", "webstorm", "phpstorm", "rider", "clion",
+        "goland", "android studio", "eclipse", "sublime", "atom", "vim", "neovim", "emacs", "git", "terminal",
+        "powershell", "cmd", "windowsterminal", "wt", "postman", "insomnia", "docker",
+    ]),
+    (1, RuleField::Title, &["visual studio", "- antigravity"]),
+    // Code file extensions in the title
+    (1, RuleField::Title, &[
+        ".ts", ".tsx", ".js", ".jsx", ".py", ".rs", ".go", ".java", ".cpp", ".c", ".cs", ".rb", ".php", ".vue",
+        ".svelte", ".html", ".css", ".scss", ".json", ".toml", ".yaml", ".yml", ".md", ".sql",
+    ]),
+    // Entertainment (category 4)
+    (4, RuleField::App, &["spotify", "netflix", "youtube", "vlc", "media player"]),
+    (4, RuleField::Title, &[
+        "spotify", "youtube", "netflix", "twitch", "soundcloud", "apple music", "liked songs",
+        "\u{2022}", // "•" bullet — Spotify uses "Song • Artist" format
+    ]),
+    // Browser (category 2)
+    (2, RuleField::App, &[
+        "chrome", "firefox", "edge", "brave", "opera", "vivaldi", "safari", "webview2", "msedgewebview",
+    ]),
+    // Communication (category 3)
+    (3, RuleField::App, &[
+        "slack", "discord", "teams", "zoom", "telegram", "whatsapp", "signal", "skype", "outlook", "thunderbird",
+        "gmail",
+    ]),
+    // Productivity (category 5)
+    (5, RuleField::App, &[
+        "notion", "obsidian", "todo", "word", "excel", "powerpoint", "onenote", "notepad", "figma",
+    ]),
+    (5, RuleField::Title, &["notion", "google docs", "google sheets"]),
+    // System (category 6)
+    (6, RuleField::App, &["explorer", "settings", "task manager", "control panel", "systemsettings"]),
+];
+
 fn categorize_window(app_name: &str, title: &str) -> i32 {
+    explain_window_category(app_name, title).0
+}
+
+/// `categorize_window` with where the answer came from and the rule that fired.
+fn explain_window_category(app_name: &str, title: &str) -> (i32, CategorizationSource, String) {
+    // User-defined categories take precedence over the built-in heuristics.
+    if let Some((category_id, rule)) = crate::utils::categories::match_rule(app_name, title, true) {
+        return (category_id, CategorizationSource::UserRule, rule);
+    }
+
     let app_lower = app_name.to_lowercase();
     let title_lower = title.to_lowercase();
-    
-    // Development (category 1)
-    if app_lower.contains("code") || 
-       app_lower.contains("vscode") ||
-       app_lower.contains("antigravity") ||
-       app_lower.contains("cursor") ||
-       app_lower.contains("idea") ||
-       app_lower.contains("pycharm") ||
-       app_lower.contains("webstorm") ||
-       app_lower.contains("phpstorm") ||
-       app_lower.contains("rider") ||
-       app_lower.contains("clion") ||
-       app_lower.contains("goland") ||
-       app_lower.contains("android studio") ||
-       app_lower.contains("eclipse") ||
-       app_lower.contains("sublime") ||
-       app_lower.contains("atom") ||
-       app_lower.contains("vim") ||
-       app_lower.contains("neovim") ||
-       app_lower.contains("emacs") ||
-       app_lower.contains("git") ||
-       app_lower.contains("terminal") ||
-       app_lower.contains("powershell") ||
-       app_lower.contains("cmd") ||
-       app_lower.contains("windowsterminal") ||
-       app_lower.contains("wt") ||
-       app_lower.contains("postman") ||
-       app_lower.contains("insomnia") ||
-       app_lower.contains("docker") ||
-       title_lower.contains("visual studio") ||
-       title_lower.contains("- antigravity") {
-        return 1;
+    for (category_id, field, needles) in BUILTIN_RULES {
+        let (haystack, label) = match field {
+            RuleField::App => (&app_lower, "app name"),
+            RuleField::Title => (&title_lower, "title"),
+        };
+        if let Some(needle) = needles.iter().find(|n| haystack.contains(*n)) {
+            return (
+                *category_id,
+                CategorizationSource::BuiltinDefault,
+                format!("{} contains \"{}\"", label, needle),
+            );
+        }
     }
-    
-    // Check title for code file extensions → Development
-    let code_extensions = [".ts", ".tsx", ".js", ".jsx", ".py", ".rs", ".go", 
-                           ".java", ".cpp", ".c", ".cs", ".rb", ".php", ".vue",
-                           ".svelte", ".html", ".css", ".scss", ".json", ".toml",
-                           ".yaml", ".yml", ".md", ".sql"];
-    for ext in code_extensions {
-        if title_lower.contains(ext) {
-            return 1;
+
+    // Stored app/keyword rules of the built-in categories, then Other (category 7)
+    match crate::utils::categories::match_rule(app_name, title, false) {
+        Some((category_id, rule)) => (category_id, CategorizationSource::BuiltinRule, rule),
+        None => (
+            crate::utils::categories::OTHER_CATEGORY_ID,
+            CategorizationSource::Fallback,
+            "no rule matched".to_string(),
+        ),
+    }
+}
+
+/// Category a window with this app name and title would get, and why.
+pub fn explain_categorization(app_name: &str, title: &str) -> CategorizationExplanation {
+    let app_name = sanitize_app_name(app_name);
+    let (category_id, source, matched_rule) = explain_window_category(&app_name, title);
+    CategorizationExplanation {
+        category_id,
+        category_name: crate::utils::categories::name_for(category_id as i64),
+        source,
+        matched_rule,
+    }
+}
+
+// ─── Write buffer ───
+// Finalized sessions go through a bounded channel to a single writer task
+// that stores them in batches, so a day of rapid focus switching costs a few
+// hundred transactions instead of thousands of opens and fsyncs. The queue is
+// flushed explicitly when tracking stops, before demo mode swaps databases and
+// on app exit. A session's checkpoint is only cleared in the transaction that
+// stores it, so a crash while it waits in the queue still recovers it.
+
+enum WriterMessage {
+    Row(ActivityEvent),
+    /// Write everything queued; signals the sender (if any) once done.
+    Flush(Option<std::sync::mpsc::Sender<()>>),
+}
+
+static WRITER: OnceLock<tokio::sync::mpsc::Sender<WriterMessage>> = OnceLock::new();
+/// Sessions handed to the writer and not yet stored.
+static QUEUED_ROWS: AtomicUsize = AtomicUsize::new(0);
+
+/// Newest session handed to the writer, so the live view can show it before it is written.
+fn last_enqueued_store() -> &'static Mutex<Option<ActivityEvent>> {
+    static STORE: OnceLock<Mutex<Option<ActivityEvent>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(None))
+}
+
+fn start_writer(app_handle: AppHandle) {
+    let (tx, rx) = tokio::sync::mpsc::channel(WRITE_QUEUE_CAPACITY);
+    if WRITER.set(tx).is_err() {
+        return;
+    }
+    tauri::async_runtime::spawn(run_writer(rx, move |batch| write_batch(&app_handle, batch)));
+}
+
+/// Hand queued rows to `write` in batches of `WRITE_BATCH_SIZE`, or once the
+/// oldest has waited `WRITE_BATCH_MAX_AGE`, on a flush and when the channel closes.
+/// `write` clears the batch on success and leaves it for the next attempt on failure.
+async fn run_writer<F>(mut rx: tokio::sync::mpsc::Receiver<WriterMessage>, mut write: F)
+where
+    F: FnMut(&mut Vec<ActivityEvent>),
+{
+    let mut batch: Vec<ActivityEvent> = Vec::new();
+    let mut deadline: Option<tokio::time::Instant> = None;
+    loop {
+        let message = match deadline {
+            Some(at) => match tokio::time::timeout_at(at, rx.recv()).await {
+                Ok(message) => message,
+                Err(_) => {
+                    write(&mut batch);
+                    deadline = None;
+                    continue;
+                }
+            },
+            None => rx.recv().await,
+        };
+        match message {
+            Some(WriterMessage::Row(activity)) => {
+                batch.push(activity);
+                if batch.len() >= WRITE_BATCH_SIZE {
+                    write(&mut batch);
+                    deadline = None;
+                } else if deadline.is_none() {
+                    deadline = Some(tokio::time::Instant::now() + WRITE_BATCH_MAX_AGE);
+                }
+            }
+            Some(WriterMessage::Flush(done)) => {
+                write(&mut batch);
+                deadline = None;
+                if let Some(done) = done {
+                    let _ = done.send(());
+                }
+            }
+            None => {
+                write(&mut batch);
+                break;
+            }
         }
     }
-    
-    // Entertainment (category 4) — check TITLE first (before browser) so
-    // Spotify/YouTube playing inside a browser gets tagged as Entertainment
-    // Note: Spotify web shows playing tracks as "Song • Artist - Browser"
-    if app_lower.contains("spotify") || 
-       app_lower.contains("netflix") ||
-       app_lower.contains("youtube") ||
-       app_lower.contains("vlc") ||
-       app_lower.contains("media player") ||
-       title_lower.contains("spotify") ||
-       title_lower.contains("youtube") ||
-       title_lower.contains("netflix") ||
-       title_lower.contains("twitch") ||
-       title_lower.contains("soundcloud") ||
-       title_lower.contains("apple music") ||
-       title_lower.contains("liked songs") ||
-       title_lower.contains("\u{2022}") {  // "•" bullet — Spotify uses "Song • Artist" format
-        return 4;
+}
+
+/// Queue a finalized session for the writer. Falls back to a direct insert if
+/// the writer isn't running or its queue is full.
+fn enqueue_activity(app_handle: &AppHandle, activity: ActivityEvent) {
+    if crate::database::is_demo_mode() {
+        return;
     }
-    
-    // Browser (category 2) — only if title didn't match entertainment above
-    if app_lower.contains("chrome") || 
-       app_lower.contains("firefox") ||
-       app_lower.contains("edge") ||
-       app_lower.contains("brave") ||
-       app_lower.contains("opera") ||
-       app_lower.contains("vivaldi") ||
-       app_lower.contains("safari") ||
-       app_lower.contains("webview2") ||
-       app_lower.contains("msedgewebview") {
-        return 2;
+    if let Ok(mut last) = last_enqueued_store().lock() {
+        *last = Some(activity.clone());
     }
-    
-    // Communication (category 3)
-    if app_lower.contains("slack") || 
-       app_lower.contains("discord") ||
-       app_lower.contains("teams") ||
-       app_lower.contains("zoom") ||
-       app_lower.contains("telegram") ||
-       app_lower.contains("whatsapp") ||
-       app_lower.contains("signal") ||
-       app_lower.contains("skype") ||
-       app_lower.contains("outlook") ||
-       app_lower.contains("thunderbird") ||
-       app_lower.contains("gmail") {
-        return 3;
+    QUEUED_ROWS.fetch_add(1, Ordering::Relaxed);
+    let activity = match WRITER.get() {
+        Some(tx) => match tx.try_send(WriterMessage::Row(activity)) {
+            Ok(()) => return,
+            Err(tokio::sync::mpsc::error::TrySendError::Full(WriterMessage::Row(a)))
+            | Err(tokio::sync::mpsc::error::TrySendError::Closed(WriterMessage::Row(a))) => a,
+            Err(_) => return,
+        },
+        None => activity,
+    };
+    let mut single = vec![activity];
+    write_batch(app_handle, &mut single);
+    // Nothing retries a failed direct insert.
+    unqueue(single.len());
+}
+
+fn unqueue(rows: usize) {
+    let _ = QUEUED_ROWS.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |queued| Some(queued.saturating_sub(rows)));
+}
+
+/// Ask the writer to store its queue now, without waiting.
+pub fn request_flush() {
+    if let Some(tx) = WRITER.get() {
+        let _ = tx.try_send(WriterMessage::Flush(None));
     }
-    
-    // Productivity (category 5)
-    if app_lower.contains("notion") || 
-       app_lower.contains("obsidian") ||
-       app_lower.contains("todo") ||
-       app_lower.contains("word") ||
-       app_lower.contains("excel") ||
-       app_lower.contains("powerpoint") ||
-       app_lower.contains("onenote") ||
-       app_lower.contains("notepad") ||
-       app_lower.contains("figma") ||
-       title_lower.contains("notion") ||
-       title_lower.contains("google docs") ||
-       title_lower.contains("google sheets") {
-        return 5;
+}
+
+/// Store every queued session before returning (or after `FLUSH_TIMEOUT`).
+/// Blocks the calling thread; used on exit and before switching databases.
+pub fn flush_pending() {
+    let Some(tx) = WRITER.get() else { return; };
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    if tx.try_send(WriterMessage::Flush(Some(done_tx))).is_err() {
+        return;
     }
-    
-    // System (category 6)
-    if app_lower.contains("explorer") || 
-       app_lower.contains("settings") ||
-       app_lower.contains("task manager") ||
-       app_lower.contains("control panel") ||
-       app_lower.contains("systemsettings") {
-        return 6;
+    if done_rx.recv_timeout(FLUSH_TIMEOUT).is_err() {
+        println!("[Tracker] Timed out waiting for queued sessions to be written");
     }
-    
-    // Other (category 7)
-    7
 }
 
-fn store_activity(app_handle: &AppHandle, activity: &ActivityEvent) -> Result<(), String> {
-    // Get database path
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    
-    // Open connection and store
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
+/// Epoch-second windows `[since, until]` passed to `forget_since`, newest last.
+fn forgotten_windows_store() -> &'static Mutex<Vec<(i64, i64)>> {
+    static STORE: OnceLock<Mutex<Vec<(i64, i64)>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Discard the open session if it started at or after `since_ms`, otherwise
+/// strip its screen text. Applied by the tracking loop within one poll interval;
+/// until then the session may still be finalized and queued, so the writer
+/// applies the same rule to every row that started before this call.
+pub fn forget_since(since_ms: i64) {
+    let until = chrono::Utc::now().timestamp();
+    if let Ok(mut windows) = forgotten_windows_store().lock() {
+        windows.push((since_ms.div_euclid(1000), until));
+        let excess = windows.len().saturating_sub(MAX_FORGOTTEN_WINDOWS);
+        windows.drain(..excess);
+    }
+    FORGET_SINCE_MS.store(since_ms.max(1), Ordering::Relaxed);
+    if let Ok(mut last) = last_enqueued_store().lock() {
+        if last.as_ref().is_some_and(|a| a.end_time * 1000 > since_ms) {
+            *last = None;
+        }
+    }
+}
+
+/// Drop queued rows that started inside a forgotten window and strip the screen
+/// capture of ones that ran into it, as `purge_recent` does for stored rows.
+/// Returns how many rows were dropped.
+fn apply_forgotten(batch: &mut Vec<ActivityEvent>, windows: &[(i64, i64)]) -> usize {
+    let before = batch.len();
+    batch.retain(|a| !windows.iter().any(|(since, until)| (*since..=*until).contains(&a.start_time)));
+    for activity in batch.iter_mut() {
+        let ran_into = windows
+            .iter()
+            .any(|(since, _)| activity.start_time < *since && activity.end_time > *since);
+        if ran_into {
+            activity.metadata.clear_screen_capture();
+        }
+    }
+    before - batch.len()
+}
+
+/// Newest finalized session, which may still be waiting in the write queue.
+pub fn last_enqueued() -> Option<ActivityEvent> {
+    last_enqueued_store().lock().ok().and_then(|last| last.clone())
+}
+
+/// Insert `batch` in one transaction and clear it. On failure the rows stay
+/// queued for the next attempt, up to the queue capacity.
+fn write_batch(app_handle: &AppHandle, batch: &mut Vec<ActivityEvent>) {
+    if batch.is_empty() {
+        return;
+    }
+    // Sessions queued before a switch to demo mode never belong in the demo database.
+    if crate::database::is_demo_mode() {
+        unqueue(batch.len());
+        batch.clear();
+        return;
+    }
+    let windows = forgotten_windows_store().lock().map(|w| w.clone()).unwrap_or_default();
+    unqueue(apply_forgotten(batch, &windows));
+    if batch.is_empty() {
+        return;
+    }
+    let started = std::time::Instant::now();
+    let result = crate::database::open(app_handle)
+        .map_err(|e| e.to_string())
+        .and_then(|conn| store_batch(&conn, batch));
+    match result {
+        Ok(()) => {
+            let elapsed = started.elapsed();
+            log::debug!(
+                "[Tracker] Wrote {} activities in {:.1} ms ({:.0} rows/s)",
+                batch.len(),
+                elapsed.as_secs_f64() * 1000.0,
+                batch.len() as f64 / elapsed.as_secs_f64().max(1e-6)
+            );
+            crate::services::app_alerts::record_sessions(app_handle, batch);
+            unqueue(batch.len());
+            batch.clear();
+        }
+        Err(e) => {
+            log::error!("Failed to store {} queued activities: {}", batch.len(), e);
+            service_status::report_error(ACTIVITY_TRACKER, &e);
+            if batch.len() > WRITE_QUEUE_CAPACITY {
+                let overflow = batch.len() - WRITE_QUEUE_CAPACITY;
+                batch.drain(..overflow);
+                unqueue(overflow);
+            }
+        }
+    }
+}
+
+fn store_batch(conn: &rusqlite::Connection, batch: &[ActivityEvent]) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for activity in batch {
+        store_activity(&tx, activity)?;
+        // Stored now, so the checkpoint describing it (if any) has done its job.
+        tx.execute(
+            "DELETE FROM session_checkpoint WHERE app_name = ?1 AND start_ms / 1000 = ?2",
+            rusqlite::params![&activity.app_name, activity.start_time],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn store_activity(conn: &rusqlite::Connection, activity: &ActivityEvent) -> Result<(), String> {
     let metadata_blob = serde_json::to_vec(&activity.metadata).map_err(|e| e.to_string())?;
+    // The table rejects unknown ids; a category deleted mid-session lands in Other instead.
+    let category_id = crate::utils::categories::known_or_other(activity.category_id);
+    if category_id != activity.category_id {
+        println!("[Tracker] Unknown category {} for {}, storing as Other", activity.category_id, activity.app_name);
+    }
     
-    conn.execute(
+    // Cached so a batch prepares the insert once per connection.
+    let mut stmt = conn.prepare_cached(
         "INSERT INTO activities 
          (app_name, app_hash, window_title, window_title_hash, category_id, 
-          start_time, end_time, duration_seconds, metadata)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+          start_time, end_time, duration_seconds, metadata, tz_offset_minutes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+    ).map_err(|e| e.to_string())?;
+    stmt.execute(
         rusqlite::params![
             &activity.app_name,
             activity.app_hash as i64,
             &activity.window_title,
             activity.window_title_hash as i64,
-            activity.category_id,
+            category_id,
             activity.start_time,
             activity.end_time,
             activity.duration_seconds,
             &metadata_blob,
+            crate::utils::time::system_offset_minutes(),
         ],
     ).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
+fn publish_foreground(window: Option<&ActiveWindow>) {
+    FOREGROUND_CATEGORY.store(window.map(|w| w.category_id).unwrap_or(0), Ordering::Relaxed);
+    FOREGROUND_FULLSCREEN.store(window.is_some_and(|w| w.is_fullscreen), Ordering::Relaxed);
+}
+
+/// Category id of the focused window and whether it covers its monitor, as of
+/// the tracker's last poll. `None` while tracking is off or no window is focused.
+pub fn foreground() -> Option<(i32, bool)> {
+    match FOREGROUND_CATEGORY.load(Ordering::Relaxed) {
+        0 => None,
+        category_id => Some((category_id, FOREGROUND_FULLSCREEN.load(Ordering::Relaxed))),
+    }
+}
+
 pub fn set_tracking_enabled(enabled: bool) {
     TRACKING_ENABLED.store(enabled, Ordering::Relaxed);
+    service_status::set_enabled(ACTIVITY_TRACKER, enabled);
 }
 
 pub fn is_tracking_enabled() -> bool {
@@ -393,3 +829,168 @@ pub fn set_tracking_interval(seconds: u64) {
     TRACKING_INTERVAL_SECS.store(clamp_tracking_interval(seconds), Ordering::Relaxed);
 }
 
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+    use std::sync::Arc;
+
+    fn row(n: i64) -> ActivityEvent {
+        ActivityEvent::new("app".to_string(), format!("window {}", n), 1, n, n + 1)
+    }
+
+    /// Runs the writer with a recording sink on tokio's paused clock.
+    fn spawn_writer() -> (tokio::sync::mpsc::Sender<WriterMessage>, Arc<Mutex<Vec<usize>>>, tokio::task::JoinHandle<()>) {
+        let (tx, rx) = tokio::sync::mpsc::channel(WRITE_QUEUE_CAPACITY);
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let sink = writes.clone();
+        let handle = tokio::spawn(run_writer(rx, move |batch: &mut Vec<ActivityEvent>| {
+            if !batch.is_empty() {
+                sink.lock().unwrap().push(batch.len());
+            }
+            batch.clear();
+        }));
+        (tx, writes, handle)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn full_batch_is_written_without_waiting() {
+        let (tx, writes, _) = spawn_writer();
+        for n in 0..WRITE_BATCH_SIZE as i64 + 3 {
+            tx.send(WriterMessage::Row(row(n))).await.unwrap();
+        }
+        tokio::task::yield_now().await;
+        assert_eq!(*writes.lock().unwrap(), vec![WRITE_BATCH_SIZE]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn partial_batch_waits_for_max_age() {
+        let (tx, writes, _) = spawn_writer();
+        for n in 0..3 {
+            tx.send(WriterMessage::Row(row(n))).await.unwrap();
+        }
+        tokio::time::sleep(WRITE_BATCH_MAX_AGE - Duration::from_millis(100)).await;
+        assert!(writes.lock().unwrap().is_empty());
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(*writes.lock().unwrap(), vec![3]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn flush_writes_pending_rows_before_signalling() {
+        let (tx, writes, _) = spawn_writer();
+        tx.send(WriterMessage::Row(row(1))).await.unwrap();
+        tx.send(WriterMessage::Row(row(2))).await.unwrap();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        tx.send(WriterMessage::Flush(Some(done_tx))).await.unwrap();
+        tokio::task::yield_now().await;
+        done_rx.try_recv().expect("flush acknowledged");
+        assert_eq!(*writes.lock().unwrap(), vec![2]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_writes_what_is_left() {
+        let (tx, writes, handle) = spawn_writer();
+        tx.send(WriterMessage::Row(row(1))).await.unwrap();
+        drop(tx);
+        handle.await.unwrap();
+        assert_eq!(*writes.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn forgotten_windows_apply_to_queued_rows() {
+        let with_text = |start: i64, end: i64| {
+            let mut a = ActivityEvent::new("app".to_string(), format!("{}-{}", start, end), 1, start, end);
+            a.metadata.screen_text = Some("secret".to_string());
+            a
+        };
+        let mut batch = vec![
+            with_text(90, 100),  // ended as the window opened: untouched
+            with_text(90, 101),  // ran into it: loses its screen text
+            with_text(100, 105), // started at `since`: dropped
+            with_text(200, 210), // started at `until`: dropped
+            with_text(201, 210), // started after the purge: kept
+        ];
+        assert_eq!(apply_forgotten(&mut batch, &[(100, 200)]), 2);
+
+        let kept: Vec<(&str, bool)> = batch
+            .iter()
+            .map(|a| (a.window_title.as_str(), a.metadata.screen_text.is_some()))
+            .collect();
+        assert_eq!(kept, vec![("90-100", true), ("90-101", false), ("201-210", true)]);
+    }
+
+
+    fn session(app: &str, start_ms: i64, last_seen_ms: i64) -> ActivitySession {
+        ActivitySession {
+            window: ActiveWindow {
+                app_name: app.to_string(),
+                title: format!("{} window", app),
+                category_id: 1,
+                is_fullscreen: false,
+            },
+            start_ms,
+            last_seen_ms,
+            metadata: ActivityMetadata::default(),
+            metadata_last_updated_ms: 0,
+        }
+    }
+
+    fn checkpoint_rows(conn: &rusqlite::Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM session_checkpoint", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn crashed_session_is_recovered_up_to_its_last_sighting() {
+        let conn = test_connection();
+        let mut open = session("Code", 1_000_000_400, 1_000_030_000);
+        open.metadata.screen_text = Some("fn main()".to_string());
+        save_checkpoint(&conn, &open).unwrap();
+        // Seen again before the crash; the checkpoint is one row, rewritten in place.
+        open.last_seen_ms = 1_000_042_100;
+        save_checkpoint(&conn, &open).unwrap();
+        assert_eq!(checkpoint_rows(&conn), 1);
+
+        // Next start: what recover_checkpoint stores, then a fresh session a few seconds later.
+        let mut recovered = load_checkpoint(&conn).unwrap();
+        assert_eq!(recovered.metadata.screen_text.as_deref(), Some("fn main()"));
+        recovered.metadata.recovered = true;
+        let end_ms = recovered.last_seen_ms;
+        let activity = session_activity(recovered, end_ms);
+        assert_eq!(activity.duration_seconds, 42);
+        store_batch(&conn, &[activity]).unwrap();
+        assert_eq!(checkpoint_rows(&conn), 0);
+
+        let restart_ms = 1_000_047_000;
+        store_batch(&conn, &[session_activity(session("Code", restart_ms, restart_ms), restart_ms + 10_000)]).unwrap();
+
+        let mut stmt = conn
+            .prepare("SELECT start_time, end_time, duration_seconds, metadata FROM activities ORDER BY start_time")
+            .unwrap();
+        let rows: Vec<(i64, i64, i64, bool)> = stmt
+            .query_map([], |row| {
+                let metadata: Vec<u8> = row.get(3)?;
+                let metadata: ActivityMetadata = serde_json::from_slice(&metadata).unwrap();
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, metadata.recovered))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(rows, vec![(1_000_000, 1_000_042, 42, true), (1_000_047, 1_000_057, 10, false)]);
+        assert!(rows[0].1 <= rows[1].0, "recovered row overlaps the new session");
+    }
+
+    #[test]
+    fn storing_an_older_session_keeps_the_newer_checkpoint() {
+        let conn = test_connection();
+        let queued = session("Slack", 2_000_000_000, 2_000_005_000);
+        let current = session("Code", 2_000_005_000, 2_000_020_000);
+        save_checkpoint(&conn, &current).unwrap();
+
+        store_batch(&conn, &[session_activity(queued, 2_000_005_000)]).unwrap();
+        assert_eq!(load_checkpoint(&conn).unwrap().window.app_name, "Code");
+
+        store_batch(&conn, &[session_activity(current, 2_000_020_000)]).unwrap();
+        assert!(matches!(load_checkpoint(&conn), Err(rusqlite::Error::QueryReturnedNoRows)));
+    }
+}
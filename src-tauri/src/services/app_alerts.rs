@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::{AppAlert, AppAlertTriggered};
+
+// ─── App usage alerts ───
+// Today's usage per alert is kept in memory and bumped from the tracker's
+// finalize path, so checking thresholds never scans the activities table.
+// Totals are rebuilt from the DB at startup, after alert edits and when the
+// local day rolls over.
+
+struct AlertState {
+    day: NaiveDate,
+    alerts: Vec<AppAlert>,
+    /// alert id -> seconds matched today.
+    totals: HashMap<i64, i64>,
+    /// alert id -> total at which it fires next; `i64::MAX` once done for the day.
+    next_fire_at: HashMap<i64, i64>,
+}
+
+fn state_store() -> &'static Mutex<Option<AlertState>> {
+    static STORE: OnceLock<Mutex<Option<AlertState>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(None))
+}
+
+pub fn load_alerts(conn: &Connection) -> rusqlite::Result<Vec<AppAlert>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, app_pattern, daily_threshold_seconds, repeat_every_seconds, enabled, created_at
+         FROM app_alerts ORDER BY id",
+    )?;
+    let alerts = stmt
+        .query_map([], |row| {
+            Ok(AppAlert {
+                id: row.get(0)?,
+                app_pattern: row.get(1)?,
+                daily_threshold_seconds: row.get(2)?,
+                repeat_every_seconds: row.get(3)?,
+                enabled: row.get::<_, i64>(4)? != 0,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(alerts)
+}
+
+fn matches(alert: &AppAlert, app_lower: &str, title_lower: &str) -> bool {
+    let pattern = alert.app_pattern.trim().to_lowercase();
+    !pattern.is_empty() && (app_lower.contains(&pattern) || title_lower.contains(&pattern))
+}
+
+/// Next firing level above `total`: the threshold, then every `repeat` after it.
+fn next_level_after(alert: &AppAlert, total: i64) -> i64 {
+    if total < alert.daily_threshold_seconds {
+        return alert.daily_threshold_seconds;
+    }
+    match alert.repeat_every_seconds.filter(|r| *r > 0) {
+        Some(repeat) => {
+            let steps = (total - alert.daily_threshold_seconds) / repeat + 1;
+            alert.daily_threshold_seconds + steps * repeat
+        }
+        None => i64::MAX,
+    }
+}
+
+fn build_state(conn: &Connection, day: NaiveDate) -> Result<AlertState, String> {
+    let bounds = crate::utils::time::day_bounds(Some(conn), day);
+    let alerts: Vec<AppAlert> = load_alerts(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|a| a.enabled && a.daily_threshold_seconds > 0)
+        .collect();
+
+    let mut totals = HashMap::new();
+    let mut next_fire_at = HashMap::new();
+    for alert in &alerts {
+        let pattern = format!("%{}%", alert.app_pattern.trim().to_lowercase());
+        let total: i64 = conn
+            .query_row(
+                "SELECT COALESCE(SUM(duration_seconds), 0) FROM activities
+                 WHERE start_time >= ?1 AND start_time < ?2
+                   AND (LOWER(app_name) LIKE ?3 OR LOWER(window_title) LIKE ?3)",
+                rusqlite::params![bounds.start_ts, bounds.end_ts, pattern],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        totals.insert(alert.id, total);
+        // Levels already passed before this rebuild count as fired, so a
+        // restart doesn't repeat today's notifications.
+        next_fire_at.insert(alert.id, next_level_after(alert, total));
+    }
+
+    Ok(AlertState { day, alerts, totals, next_fire_at })
+}
+
+/// Reload alerts and today's totals; call at startup and after alerts change.
+pub fn reload(conn: &Connection) {
+    match build_state(conn, crate::utils::time::today()) {
+        Ok(state) => {
+            println!("[Alerts] Watching {} app alert(s)", state.alerts.len());
+            if let Ok(mut store) = state_store().lock() {
+                *store = Some(state);
+            }
+        }
+        Err(e) => println!("[Alerts] Failed to load app alerts: {}", e),
+    }
+}
+
+/// Whether the totals belong to another local day (or were never loaded).
+fn is_stale(state: Option<&AlertState>, today: NaiveDate) -> bool {
+    state.map(|s| s.day != today).unwrap_or(true)
+}
+
+/// Add a session that was just stored and return the alerts it pushed over
+/// their next level. Pure bookkeeping; the caller decides how to notify.
+fn apply_session(state: &mut AlertState, app_name: &str, title: &str, duration_seconds: i64) -> Vec<AppAlertTriggered> {
+    let app_lower = app_name.to_lowercase();
+    let title_lower = title.to_lowercase();
+    let mut triggered = Vec::new();
+    for alert in &state.alerts {
+        if !matches(alert, &app_lower, &title_lower) {
+            continue;
+        }
+        let total = state.totals.entry(alert.id).or_insert(0);
+        *total += duration_seconds.max(0);
+        let next = state.next_fire_at.entry(alert.id).or_insert(alert.daily_threshold_seconds);
+        if *total >= *next {
+            // Jump past every level this session crossed so one long session fires once.
+            *next = next_level_after(alert, *total);
+            triggered.push(AppAlertTriggered {
+                alert_id: alert.id,
+                app_pattern: alert.app_pattern.clone(),
+                app_name: app_name.to_string(),
+                total_seconds: *total,
+                threshold_seconds: alert.daily_threshold_seconds,
+            });
+        }
+    }
+    triggered
+}
+
+/// Count just-stored sessions towards today's totals and fire crossed alerts.
+/// Called by the tracker's writer after the batch is committed.
+pub fn record_sessions(app_handle: &AppHandle, sessions: &[crate::models::ActivityEvent]) {
+    let today = crate::utils::time::today();
+    let needs_rebuild = state_store()
+        .lock()
+        .map(|store| is_stale(store.as_ref(), today))
+        .unwrap_or(false);
+    if needs_rebuild {
+        // New local day (or never loaded): the DB already holds these sessions,
+        // so the rebuilt totals include them.
+        if let Ok(conn) = open_db(app_handle) {
+            reload(&conn);
+        }
+        return;
+    }
+
+    let triggered: Vec<_> = match state_store().lock() {
+        Ok(mut store) => match store.as_mut() {
+            Some(state) => sessions
+                .iter()
+                .flat_map(|s| apply_session(state, &s.app_name, &s.window_title, s.duration_seconds as i64))
+                .collect(),
+            None => return,
+        },
+        Err(_) => return,
+    };
+
+    for alert in triggered {
+        println!(
+            "[Alerts] '{}' reached {}s today (threshold {}s)",
+            alert.app_pattern, alert.total_seconds, alert.threshold_seconds
+        );
+        let _ = app_handle.emit("alerts://triggered", &alert);
+        crate::services::notifier::notify(
+            app_handle,
+            "app_alert",
+            &format!("{} limit reached", alert.app_pattern),
+            &format!(
+                "You've spent {} on {} today.",
+                format_minutes(alert.total_seconds),
+                alert.app_pattern
+            ),
+        );
+    }
+}
+
+fn format_minutes(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{} min", minutes)
+    }
+}
+
+fn open_db(app_handle: &AppHandle) -> Result<Connection, String> {
+    Ok(crate::database::open(app_handle)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(id: i64, pattern: &str, threshold: i64, repeat: Option<i64>) -> AppAlert {
+        AppAlert {
+            id,
+            app_pattern: pattern.to_string(),
+            daily_threshold_seconds: threshold,
+            repeat_every_seconds: repeat,
+            enabled: true,
+            created_at: 0,
+        }
+    }
+
+    fn state(alerts: Vec<AppAlert>) -> AlertState {
+        AlertState {
+            day: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap(),
+            alerts,
+            totals: HashMap::new(),
+            next_fire_at: HashMap::new(),
+        }
+    }
+
+    fn fired(state: &mut AlertState, app: &str, seconds: i64) -> Vec<i64> {
+        apply_session(state, app, "", seconds)
+            .into_iter()
+            .map(|t| t.total_seconds)
+            .collect()
+    }
+
+    #[test]
+    fn reaching_the_threshold_exactly_fires_once() {
+        let mut state = state(vec![alert(1, "YouTube", 45 * 60, None)]);
+        assert!(fired(&mut state, "YouTube", 20 * 60).is_empty());
+        assert!(fired(&mut state, "youtube", 25 * 60 - 1).is_empty());
+        assert_eq!(fired(&mut state, "YouTube", 1), vec![45 * 60]);
+        // Notify-once alerts stay quiet for the rest of the day.
+        assert!(fired(&mut state, "YouTube", 60 * 60).is_empty());
+        assert!(fired(&mut state, "YouTube", 1).is_empty());
+        assert_eq!(state.totals[&1], 45 * 60 + 60 * 60 + 1);
+    }
+
+    #[test]
+    fn repeating_alerts_fire_once_per_level_crossed() {
+        let mut state = state(vec![alert(1, "youtube", 600, Some(300))]);
+        assert_eq!(fired(&mut state, "YouTube", 600), vec![600]);
+        assert!(fired(&mut state, "YouTube", 299).is_empty());
+        assert_eq!(fired(&mut state, "YouTube", 1), vec![900]);
+        // One long session past several levels fires once and lands on the next level.
+        assert_eq!(fired(&mut state, "YouTube", 700), vec![1600]);
+        assert_eq!(state.next_fire_at[&1], 1800);
+    }
+
+    #[test]
+    fn only_matching_sessions_count() {
+        let mut state = state(vec![alert(1, "youtube", 60, None), alert(2, "slack", 60, None)]);
+        assert!(fired(&mut state, "Code", 600).is_empty());
+        let triggered = apply_session(&mut state, "Firefox", "Lo-fi beats - YouTube", 60);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].alert_id, 1);
+        assert_eq!(triggered[0].app_name, "Firefox");
+        assert_eq!(state.totals.get(&2), None);
+    }
+
+    #[test]
+    fn midnight_rollover_rebuilds_from_the_new_local_day() {
+        let conn = crate::database::test_connection();
+        conn.execute(
+            "INSERT INTO app_alerts (app_pattern, daily_threshold_seconds, created_at) VALUES ('youtube', 600, 0)",
+            [],
+        )
+        .unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let today = yesterday.succ_opt().unwrap();
+        let midnight = crate::utils::time::day_bounds(Some(&conn), today).start_ts;
+        // Up to a second before midnight, then a short session right after it.
+        for (start, seconds) in [(midnight - 900, 899), (midnight, 120)] {
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+                 VALUES ('YouTube', 1, 'w', 1, ?1, ?1 + ?2, ?2)",
+                [start, seconds],
+            )
+            .unwrap();
+        }
+
+        let old = build_state(&conn, yesterday).unwrap();
+        assert_eq!(old.totals[&1], 899);
+        assert_eq!(old.next_fire_at[&1], i64::MAX);
+        assert!(is_stale(Some(&old), today));
+        assert!(!is_stale(Some(&old), yesterday));
+        assert!(is_stale(None, today));
+
+        let mut new = build_state(&conn, today).unwrap();
+        assert_eq!(new.totals[&1], 120);
+        assert_eq!(new.next_fire_at[&1], 600);
+        assert_eq!(fired(&mut new, "YouTube", 480), vec![600]);
+    }
+}
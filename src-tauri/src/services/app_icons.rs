@@ -0,0 +1,76 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use base64::Engine;
+
+// ─── App icons ───
+// Icons are pulled from the executable of each app the tracker sees and
+// cached as PNG under `<app data>/icons/<app_hash>.png`, so an app keeps its
+// icon after it is closed or uninstalled. Extraction is best-effort: an app
+// never seen running in this session, or whose executable has no icon, just
+// gets none.
+
+/// Executable path of each app name seen in the foreground this session.
+fn executables() -> &'static Mutex<HashMap<String, PathBuf>> {
+    static STORE: OnceLock<Mutex<HashMap<String, PathBuf>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Apps whose extraction already failed this session; not retried until restart.
+fn failed() -> &'static Mutex<HashSet<u64>> {
+    static STORE: OnceLock<Mutex<HashSet<u64>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Called by the activity tracker for the foreground window.
+pub fn remember_executable(app_name: &str, path: &Path) {
+    if path.as_os_str().is_empty() {
+        return;
+    }
+    if let Ok(mut map) = executables().lock() {
+        if !map.contains_key(app_name) {
+            map.insert(app_name.to_string(), path.to_path_buf());
+        }
+    }
+}
+
+pub fn icons_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("icons")
+}
+
+/// The app's icon as a PNG data URL, extracting and caching it on first use.
+pub fn icon_data_url(icons_dir: &Path, app_name: &str, app_hash: u64) -> Option<String> {
+    let cached = icons_dir.join(format!("{:016x}.png", app_hash));
+    if !cached.exists() && !extract(&cached, app_name, app_hash) {
+        return None;
+    }
+    let bytes = std::fs::read(&cached).ok()?;
+    Some(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+fn extract(target: &Path, app_name: &str, app_hash: u64) -> bool {
+    if failed().lock().map(|set| set.contains(&app_hash)).unwrap_or(true) {
+        return false;
+    }
+    let Some(exe) = executables().lock().ok().and_then(|map| map.get(app_name).cloned()) else {
+        return false;
+    };
+
+    let result = std::fs::create_dir_all(target.parent().unwrap_or(Path::new(".")))
+        .map_err(|e| e.to_string())
+        .and_then(|_| crate::utils::windows::extract_exe_icon_png(&exe, target));
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            println!("[AppIcons] No icon for {} ({}): {}", app_name, exe.display(), e);
+            if let Ok(mut set) = failed().lock() {
+                set.insert(app_hash);
+            }
+            false
+        }
+    }
+}
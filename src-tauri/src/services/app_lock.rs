@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use tauri::{AppHandle, Emitter, Manager};
+
+// ─── App lock ───
+// Optional password gate (`privacy.require_unlock`) so a laptop left unlocked
+// doesn't show the activity history to whoever sits down. While locked the
+// main window only ever shows the unlock screen: revealing it emits
+// `lock://required` instead of navigating, and nothing else renders until
+// `unlock` verifies the password against the stored Argon2 hash. Hiding the
+// window to the tray locks it again. The webview is not trusted to honour
+// this: while locked, every command outside `ALLOWED_WHILE_LOCKED` is refused.
+
+/// Shortest password accepted when setting one.
+const MIN_PASSWORD_LEN: usize = 4;
+
+/// Returned for any other command while the app is locked.
+pub const LOCKED_ERROR: &str = "IntentFlow is locked";
+
+/// What the unlock screen and the tray need; nothing here reads stored data.
+const ALLOWED_WHILE_LOCKED: &[&str] = &["is_app_locked", "unlock_app", "lock_app", "show_window", "minimize_to_tray"];
+
+static UNLOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Page the window was opened for while locked; navigated to after unlocking.
+fn pending_page_store() -> &'static Mutex<Option<String>> {
+    static STORE: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(None))
+}
+
+/// Hash currently saved in settings. The frontend never receives it, so saves
+/// from the settings page carry this one over.
+pub fn stored_password_hash(app_handle: &AppHandle) -> Option<String> {
+    crate::services::settings_bus::current(app_handle)
+        .and_then(|s| s.privacy.unlock_password_hash)
+        .filter(|h| !h.is_empty())
+}
+
+/// Whether the lock is on and hasn't been opened since the app started or was last hidden.
+/// Without a password set there is nothing to check, so the lock stays off.
+pub fn is_locked(app_handle: &AppHandle) -> bool {
+    if UNLOCKED.load(Ordering::Relaxed) {
+        return false;
+    }
+    let require_unlock = crate::services::settings_bus::current(app_handle)
+        .map(|s| s.privacy.require_unlock)
+        .unwrap_or(false);
+    require_unlock && stored_password_hash(app_handle).is_some()
+}
+
+/// Whether the invoke handler may run `command` right now.
+pub fn allows_command(app_handle: &AppHandle, command: &str) -> bool {
+    ALLOWED_WHILE_LOCKED.contains(&command) || !is_locked(app_handle)
+}
+
+/// Lock and, if the lock is on, switch the (hidden) window to the unlock screen
+/// so its old content is never on screen when it is revealed again.
+pub fn lock(app_handle: &AppHandle) {
+    UNLOCKED.store(false, Ordering::Relaxed);
+    if is_locked(app_handle) {
+        let _ = app_handle.emit("lock://required", ());
+    }
+}
+
+/// Show the main window (on `page`, if given), or on the unlock screen if the app is locked.
+pub fn reveal_main_window(app_handle: &AppHandle, page: Option<&str>) {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+    if is_locked(app_handle) {
+        if let (Some(page), Ok(mut pending)) = (page, pending_page_store().lock()) {
+            *pending = Some(page.to_string());
+        }
+        // Before showing, so the unlock screen is what appears.
+        let _ = window.emit("lock://required", ());
+    } else if let Some(page) = page {
+        let _ = window.emit("tray:navigate", page.to_string());
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// Hide the main window, locking it first so the next reveal asks for the password.
+pub fn hide_main_window(app_handle: &AppHandle) -> Result<(), String> {
+    lock(app_handle);
+    if let Some(window) = app_handle.get_webview_window("main") {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Check `password` and open the lock. Returns false on a wrong password.
+pub fn unlock(app_handle: &AppHandle, password: &str) -> bool {
+    if !is_locked(app_handle) {
+        return true;
+    }
+    let verified = stored_password_hash(app_handle)
+        .map(|hash| verify_password(password, &hash))
+        .unwrap_or(false);
+    if !verified {
+        println!("[Lock] Unlock attempt failed");
+        return false;
+    }
+    UNLOCKED.store(true, Ordering::Relaxed);
+    let page = pending_page_store().lock().ok().and_then(|mut p| p.take());
+    let _ = app_handle.emit("lock://unlocked", page);
+    println!("[Lock] Unlocked");
+    true
+}
+
+/// Set, change or (with `new_password` empty) remove the unlock password.
+/// An existing password must be confirmed first. Removing it also turns the lock off.
+pub fn set_password(app_handle: &AppHandle, current_password: &str, new_password: &str) -> Result<(), String> {
+    let mut settings = crate::services::settings_bus::current(app_handle).ok_or("Settings are not loaded")?;
+    if let Some(hash) = stored_password_hash(app_handle) {
+        if !verify_password(current_password, &hash) {
+            return Err("Current password is incorrect".to_string());
+        }
+    }
+
+    if new_password.is_empty() {
+        settings.privacy.unlock_password_hash = None;
+        settings.privacy.require_unlock = false;
+    } else {
+        if new_password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(format!("Password must be at least {} characters", MIN_PASSWORD_LEN));
+        }
+        settings.privacy.unlock_password_hash = Some(hash_password(new_password)?);
+    }
+    crate::utils::config::save_settings_file(app_handle, &settings)?;
+    crate::services::settings_bus::publish(app_handle, settings);
+    // Whoever set the password is already looking at the app.
+    UNLOCKED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+fn hash_password(password: &str) -> Result<String, String> {
+    let salt = SaltString::encode_b64(uuid::Uuid::new_v4().as_bytes()).map_err(|e| e.to_string())?;
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
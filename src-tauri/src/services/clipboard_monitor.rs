@@ -0,0 +1,264 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use tauri::AppHandle;
+
+use crate::services::service_status::{self, CLIPBOARD_MONITOR};
+
+// ─── Clipboard history (opt-in) ───
+// Listens for clipboard changes and stores copied text so questions like
+// "what was that error I copied" can be answered. Off unless
+// `settings.tracking.sources.clipboard` is set, and suppressed whenever
+// tracking is paused, disabled, or in Incognito/Game Mode.
+
+pub const MAX_CLIPBOARD_CHARS: usize = 1000;
+
+/// The user opted in (`sources.clipboard` and tracking enabled).
+static SOURCE_ENABLED: AtomicBool = AtomicBool::new(false);
+/// Incognito, Game Mode or tracking disabled; set from `apply_monitoring_state`.
+static SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+fn excluded_apps_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+pub fn set_suppressed(suppressed: bool) {
+    SUPPRESSED.store(suppressed, Ordering::Relaxed);
+}
+
+fn is_recording() -> bool {
+    SOURCE_ENABLED.load(Ordering::Relaxed)
+        && !SUPPRESSED.load(Ordering::Relaxed)
+        && !crate::services::tracking_pause::is_paused()
+}
+
+fn is_app_excluded(app_name: &str) -> bool {
+    let app = app_name.to_lowercase();
+    excluded_apps_store()
+        .lock()
+        .map(|list| list.iter().any(|excluded| app.contains(excluded.as_str())))
+        .unwrap_or(true)
+}
+
+pub fn start_clipboard_monitor(app_handle: AppHandle) {
+    crate::services::settings_bus::spawn_subscriber(&app_handle, "clipboard_monitor", |settings| {
+        let enabled = settings.tracking.enabled && settings.tracking.sources.clipboard;
+        SOURCE_ENABLED.store(enabled, Ordering::Relaxed);
+        service_status::set_enabled(CLIPBOARD_MONITOR, enabled);
+        if let Ok(mut store) = excluded_apps_store().lock() {
+            *store = settings
+                .tracking
+                .exclude_apps
+                .iter()
+                .map(|a| a.trim().to_lowercase())
+                .filter(|a| !a.is_empty())
+                .collect();
+        }
+    });
+
+    // The listener needs a thread with its own message loop.
+    let spawned = std::thread::Builder::new()
+        .name("clipboard-monitor".to_string())
+        .spawn(move || platform::run(app_handle));
+    if let Err(e) = spawned {
+        println!("[Clipboard] Failed to start monitor thread: {}", e);
+        service_status::report_error(CLIPBOARD_MONITOR, e);
+    }
+}
+
+/// Store one copied text. The platform listener has already dropped
+/// password-manager content; everything else is filtered here.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn record_clipboard_text(app_handle: &AppHandle, text: &str, app_name: &str, window_title: &str) {
+    service_status::heartbeat(CLIPBOARD_MONITOR);
+    if !is_recording() || text.trim().is_empty() || is_app_excluded(app_name) {
+        return;
+    }
+
+    let redacted = crate::utils::redact::redact_sensitive(text.trim());
+    let content: String = redacted.chars().take(MAX_CLIPBOARD_CHARS).collect();
+    let content_hash = crate::utils::hash_string(&content);
+
+    let result = (|| -> Result<bool, String> {
+        let conn = crate::database::open(app_handle)?;
+        // Copying the same text repeatedly (or re-copying after a paste) adds nothing.
+        let last_hash: Option<i64> = conn
+            .query_row(
+                "SELECT content_hash FROM clipboard_events ORDER BY captured_at DESC, id DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .ok();
+        if last_hash == Some(content_hash) {
+            return Ok(false);
+        }
+        conn.execute(
+            "INSERT INTO clipboard_events (content, content_hash, app_name, window_title, captured_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                content,
+                content_hash,
+                app_name,
+                window_title,
+                chrono::Utc::now().timestamp()
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(true)
+    })();
+
+    match result {
+        Ok(true) => println!("[Clipboard] Recorded {} chars from {}", content.chars().count(), app_name),
+        Ok(false) => {}
+        Err(e) => {
+            println!("[Clipboard] Failed to store entry: {}", e);
+            service_status::report_error(CLIPBOARD_MONITOR, e);
+        }
+    }
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn active_window() -> (String, String) {
+    active_win_pos_rs::get_active_window()
+        .map(|w| (w.app_name.trim().to_string(), w.title))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ptr::null_mut;
+    use std::sync::OnceLock;
+
+    use tauri::AppHandle;
+
+    use winapi::shared::minwindef::{LPARAM, LRESULT, UINT, WPARAM};
+    use winapi::shared::windef::HWND;
+    use winapi::um::winbase::{GlobalLock, GlobalUnlock};
+    use winapi::um::winuser::{
+        AddClipboardFormatListener, CloseClipboard, CreateWindowExW, DefWindowProcW, DispatchMessageW,
+        GetClipboardData, GetMessageW, IsClipboardFormatAvailable, OpenClipboard, RegisterClassW,
+        RegisterClipboardFormatW, TranslateMessage, CF_UNICODETEXT, HWND_MESSAGE, MSG, WM_CLIPBOARDUPDATE,
+        WNDCLASSW,
+    };
+
+    use super::{active_window, record_clipboard_text};
+    use crate::services::service_status::{self, CLIPBOARD_MONITOR};
+
+    static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// Formats password managers and other apps set to keep content out of
+    /// clipboard history and monitors.
+    unsafe fn has_privacy_marker() -> bool {
+        for name in ["ExcludeClipboardContentFromMonitorProcessing", "Clipboard Viewer Ignore"] {
+            let format = RegisterClipboardFormatW(wide(name).as_ptr());
+            if format != 0 && IsClipboardFormatAvailable(format) != 0 {
+                return true;
+            }
+        }
+        // Present with a zero DWORD when the app opted out of clipboard history.
+        let format = RegisterClipboardFormatW(wide("CanIncludeInClipboardHistory").as_ptr());
+        if format != 0 && IsClipboardFormatAvailable(format) != 0 {
+            let handle = GetClipboardData(format);
+            if !handle.is_null() {
+                let ptr = GlobalLock(handle) as *const u32;
+                let allowed = ptr.is_null() || *ptr != 0;
+                GlobalUnlock(handle);
+                return !allowed;
+            }
+        }
+        false
+    }
+
+    unsafe fn read_clipboard_text(hwnd: HWND) -> Option<String> {
+        if OpenClipboard(hwnd) == 0 {
+            return None;
+        }
+        let text = (|| {
+            if has_privacy_marker() || IsClipboardFormatAvailable(CF_UNICODETEXT) == 0 {
+                return None;
+            }
+            let handle = GetClipboardData(CF_UNICODETEXT);
+            if handle.is_null() {
+                return None;
+            }
+            let ptr = GlobalLock(handle) as *const u16;
+            if ptr.is_null() {
+                return None;
+            }
+            let mut len = 0usize;
+            while *ptr.add(len) != 0 {
+                len += 1;
+            }
+            let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+            GlobalUnlock(handle);
+            Some(text)
+        })();
+        CloseClipboard();
+        text
+    }
+
+    unsafe extern "system" fn wnd_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if msg == WM_CLIPBOARDUPDATE {
+            if let (Some(app_handle), Some(text)) = (APP_HANDLE.get(), read_clipboard_text(hwnd)) {
+                let (app_name, window_title) = active_window();
+                record_clipboard_text(app_handle, &text, &app_name, &window_title);
+            }
+            return 0;
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    pub fn run(app_handle: AppHandle) {
+        let _ = APP_HANDLE.set(app_handle);
+        unsafe {
+            let class_name = wide("IntentFlowClipboardMonitor");
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                lpszClassName: class_name.as_ptr(),
+                ..std::mem::zeroed()
+            };
+            RegisterClassW(&class);
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                class_name.as_ptr(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                null_mut(),
+                null_mut(),
+                null_mut(),
+            );
+            if hwnd.is_null() || AddClipboardFormatListener(hwnd) == 0 {
+                println!("[Clipboard] Failed to register clipboard listener");
+                service_status::report_error(CLIPBOARD_MONITOR, "Failed to register clipboard listener");
+                return;
+            }
+            println!("[Clipboard] Listener registered");
+            service_status::mark_started(CLIPBOARD_MONITOR);
+
+            let mut msg: MSG = std::mem::zeroed();
+            while GetMessageW(&mut msg, null_mut(), 0, 0) > 0 {
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use tauri::AppHandle;
+
+    pub fn run(_app_handle: AppHandle) {
+        println!("[Clipboard] Clipboard history is only supported on Windows");
+    }
+}
@@ -0,0 +1,72 @@
+use rusqlite::Connection;
+
+use crate::models::CurrentFocus;
+
+// ─── Current focus ───
+// A short "working on …" note the user sets by hand. Dashboard summaries and
+// the agent anchor to it while it is live. Kept in the `settings` table like
+// the tracking pause; an expired note is deleted the next time it is read.
+
+const CURRENT_FOCUS_KEY: &str = "current_focus";
+/// TTL used when the caller doesn't give one, in minutes.
+pub const DEFAULT_FOCUS_MINUTES: u32 = 4 * 60;
+/// Longest TTL accepted, in minutes.
+pub const MAX_FOCUS_MINUTES: u32 = 24 * 60;
+const MAX_FOCUS_CHARS: usize = 200;
+
+/// The live focus note, clearing it if its TTL has passed.
+pub fn get(conn: &Connection) -> Option<CurrentFocus> {
+    let stored: String = conn
+        .query_row(
+            "SELECT CAST(value AS TEXT) FROM settings WHERE key = ?1",
+            [CURRENT_FOCUS_KEY],
+            |row| row.get(0),
+        )
+        .ok()?;
+    let focus = serde_json::from_str::<CurrentFocus>(&stored).ok();
+    match focus {
+        Some(focus) if focus.expires_at > chrono::Utc::now().timestamp() => Some(focus),
+        _ => {
+            let _ = clear(conn);
+            None
+        }
+    }
+}
+
+/// Set the focus note for `ttl_minutes` (default 4h). Blank text clears it.
+pub fn set(conn: &Connection, text: &str, ttl_minutes: Option<u32>) -> Result<Option<CurrentFocus>, String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        clear(conn)?;
+        return Ok(None);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let minutes = ttl_minutes.unwrap_or(DEFAULT_FOCUS_MINUTES).clamp(1, MAX_FOCUS_MINUTES);
+    let focus = CurrentFocus {
+        text: text.chars().take(MAX_FOCUS_CHARS).collect(),
+        set_at: now,
+        expires_at: now + minutes as i64 * 60,
+    };
+    let value = serde_json::to_string(&focus).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        rusqlite::params![CURRENT_FOCUS_KEY, value, now],
+    )
+    .map_err(|e| e.to_string())?;
+    println!("[Focus] Working on \"{}\" for {} min", focus.text, minutes);
+    Ok(Some(focus))
+}
+
+pub fn clear(conn: &Connection) -> Result<(), String> {
+    conn.execute("DELETE FROM settings WHERE key = ?1", [CURRENT_FOCUS_KEY])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// One line for prompts, e.g. `"Q3 report" (set 40 min ago)`.
+pub fn describe(focus: &CurrentFocus) -> String {
+    let minutes = ((chrono::Utc::now().timestamp() - focus.set_at) / 60).max(0);
+    format!("\"{}\" (set {} min ago)", focus.text, minutes)
+}
@@ -1,14 +1,28 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
 use std::time::Duration;
 
-use chrono::TimeZone;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
-use crate::models::{DashboardOverview, DashboardTask, ProjectOverview, ContactOverview, Settings, ActivityMetadata};
+use crate::models::{
+    ActivityMetadata, AppStat, CategoryStat, ContactConversationBlock, ContactInteraction, ContactOverview,
+    ContactTimeline, DailyRecap, DashboardOverview, DashboardTask, FocusEntityRef, FocusPoint, FocusPointKind,
+    InteractionTag, InteractionTagCount, MediaKind, Meeting, MeetingsReport, ProjectAreaChange, ProjectChanges, ProjectFileChange, ProjectOverview, FOCUS_PRIORITY_HIGH,
+};
+
+use crate::error::AppError;
+use crate::services::llm_client;
+use crate::utils::i18n::{self, Lang, Msg};
+use crate::services::service_status::{self, DASHBOARD_ENGINE};
 
 const DASHBOARD_REFRESH_SECS: u64 = 15 * 60;
+const CONTACT_TIMELINE_SCAN_LIMIT: i64 = 2000;
+const CONTACT_BLOCK_GAP_SECS: i64 = 10 * 60;
+const PROJECT_CHANGES_SCAN_LIMIT: i64 = 5000;
+const PROJECT_CHANGES_MAX_FILES: usize = 200;
 
 #[derive(Debug, Clone, Serialize)]
 struct DashboardChatRequest {
@@ -27,6 +41,8 @@ struct DashboardChatMessage {
 #[derive(Debug, Clone, Deserialize)]
 struct DashboardChatResponse {
     choices: Vec<DashboardChoice>,
+    #[serde(default)]
+    usage: Option<llm_client::ProviderUsage>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -42,7 +58,8 @@ struct DashboardMessage {
 #[derive(Debug, Clone, Deserialize, Default)]
 struct DashboardLLMOutput {
     summary: Option<String>,
-    focus_points: Option<Vec<String>>,
+    /// Parsed item by item so one malformed point doesn't cost the whole reply.
+    focus_points: Option<Vec<serde_json::Value>>,
     deadlines: Option<Vec<DashboardTask>>,
     projects: Option<Vec<ProjectOverview>>,
     contacts: Option<Vec<ContactOverview>>,
@@ -51,42 +68,80 @@ struct DashboardLLMOutput {
 pub fn start_dashboard_engine(app_handle: AppHandle) {
     tauri::async_runtime::spawn(async move {
         tokio::time::sleep(Duration::from_secs(30)).await;
-        let _ = refresh_dashboard_snapshot(&app_handle).await;
+        service_status::mark_started(DASHBOARD_ENGINE);
         loop {
+            match refresh_dashboard_snapshot(&app_handle).await {
+                Ok(_) => service_status::heartbeat(DASHBOARD_ENGINE),
+                Err(e) => service_status::report_error(DASHBOARD_ENGINE, &e),
+            }
             tokio::time::sleep(Duration::from_secs(DASHBOARD_REFRESH_SECS)).await;
-            let _ = refresh_dashboard_snapshot(&app_handle).await;
         }
     });
 }
 
+/// Bumped each time a refresh finishes, so a caller that waited on the lock can
+/// tell whether a refresh completed in the meantime.
+static REFRESH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Held for the whole refresh; holds the overview of the last successful one.
+fn refresh_lock() -> &'static tokio::sync::Mutex<Option<DashboardOverview>> {
+    static LOCK: OnceLock<tokio::sync::Mutex<Option<DashboardOverview>>> = OnceLock::new();
+    LOCK.get_or_init(|| tokio::sync::Mutex::new(None))
+}
+
+/// Refresh today's snapshot. Concurrent callers coalesce: one that arrives while
+/// a refresh is running waits for it and returns its result instead of making a
+/// second LLM call whose upsert could clobber the first.
 pub async fn refresh_dashboard_snapshot(app_handle: &AppHandle) -> Result<DashboardOverview, String> {
-    let (date_key, day_start, day_end) = today_bounds_local();
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let seen = REFRESH_GENERATION.load(Ordering::SeqCst);
+    let mut last = refresh_lock().lock().await;
+    if REFRESH_GENERATION.load(Ordering::SeqCst) != seen {
+        if let Some(overview) = last.as_ref() {
+            return Ok(overview.clone());
+        }
+    }
+    let result = run_dashboard_refresh(app_handle).await;
+    *last = result.as_ref().ok().cloned();
+    REFRESH_GENERATION.fetch_add(1, Ordering::SeqCst);
+    result
+}
+
+async fn run_dashboard_refresh(app_handle: &AppHandle) -> Result<DashboardOverview, String> {
+    let conn = crate::database::open(app_handle)?;
+    let (date_key, day_start, day_end) = today_bounds_local(&conn);
     let previous_snapshot = load_snapshot_for_date(&conn, &date_key);
 
     let context = build_today_context(&conn, day_start, day_end)?;
-    let settings = load_settings(app_handle).unwrap_or_default();
+    if let Err(e) = crate::services::goal_streaks::refresh(app_handle, &conn) {
+        log::warn!("Goal streak check failed: {}", e);
+    }
+    let settings = dashboard_settings(app_handle);
     let api_key = crate::utils::config::resolve_api_key(&settings.ai.api_key);
     let model = settings.ai.model.clone();
     let now = chrono::Utc::now().timestamp();
 
     let mut overview = if settings.ai.enabled && !api_key.is_empty() {
-        match ai_dashboard_summary(&context, &api_key, &model).await {
+        match ai_dashboard_summary(app_handle, &context, &api_key, &model, settings.ai.dashboard_temperature).await {
             Ok(o) => o,
             Err(e) => {
+                // Keep raw API errors out of the summary text; they go to logs and service status.
+                log::warn!("AI summary failed: {}", e);
+                service_status::report_error(DASHBOARD_ENGINE, &e);
                 let mut fallback = fallback_dashboard_summary(&context);
-                fallback.summary = format!("{}\n\n(AI Summary Failed: {})", fallback.summary, e);
+                fallback.summary = format!(
+                    "{}\n\n(AI summary unavailable right now; showing a local summary.)",
+                    fallback.summary
+                );
                 fallback
             }
         }
     } else {
         let mut fallback = fallback_dashboard_summary(&context);
+        let lang = context_lang(&context);
         if api_key.is_empty() {
-            fallback.summary = format!("{}\n\n(AI Summary disabled: No API key provided)", fallback.summary);
+            fallback.summary = format!("{}\n\n{}", fallback.summary, Msg::AiSummaryNoKey.text(lang));
         } else if !settings.ai.enabled {
-            fallback.summary = format!("{}\n\n(AI Summary disabled in settings)", fallback.summary);
+            fallback.summary = format!("{}\n\n{}", fallback.summary, Msg::AiSummaryDisabled.text(lang));
         }
         fallback
     };
@@ -98,12 +153,15 @@ pub async fn refresh_dashboard_snapshot(app_handle: &AppHandle) -> Result<Dashbo
         overview.contacts = derived_contacts;
     } else {
         for contact in derived_contacts {
-            let exists = overview
+            match overview
                 .contacts
-                .iter()
-                .any(|c| c.name.eq_ignore_ascii_case(&contact.name));
-            if !exists {
-                overview.contacts.push(contact);
+                .iter_mut()
+                .find(|c| c.name.eq_ignore_ascii_case(&contact.name))
+            {
+                // The model doesn't tag interactions; keep the rule-based tags.
+                Some(existing) if existing.tags.is_empty() => existing.tags = contact.tags,
+                Some(_) => {}
+                None => overview.contacts.push(contact),
             }
         }
         overview.contacts.truncate(12);
@@ -115,26 +173,42 @@ pub async fn refresh_dashboard_snapshot(app_handle: &AppHandle) -> Result<Dashbo
 
     overview.date_key = date_key.clone();
     overview.updated_at = now;
+    save_snapshot(&conn, &overview)?;
 
-    let serialized = serde_json::to_string(&overview).map_err(|e| e.to_string())?;
+    Ok(overview)
+}
+
+/// Today's snapshot from the local (non-AI) summary, for databases that
+/// should look populated without an API call, such as the demo database.
+pub(crate) fn write_local_snapshot(conn: &Connection) -> Result<DashboardOverview, String> {
+    let (date_key, day_start, day_end) = today_bounds_local(conn);
+    let context = build_today_context(conn, day_start, day_end)?;
+    let mut overview = fallback_dashboard_summary(&context);
+    overview.projects = enrich_projects_with_file_upgrades(&context, overview.projects);
+    overview.contacts = derive_contacts_from_context(&context);
+    overview.date_key = date_key;
+    overview.updated_at = chrono::Utc::now().timestamp();
+    save_snapshot(conn, &overview)?;
+    Ok(overview)
+}
+
+fn save_snapshot(conn: &Connection, overview: &DashboardOverview) -> Result<(), String> {
+    let serialized = serde_json::to_string(overview).map_err(|e| e.to_string())?;
     conn.execute(
         "INSERT INTO dashboard_snapshots (date_key, summary_json, updated_at)
          VALUES (?1, ?2, ?3)
          ON CONFLICT(date_key) DO UPDATE SET
              summary_json = excluded.summary_json,
              updated_at = excluded.updated_at",
-        rusqlite::params![date_key, serialized, now],
+        rusqlite::params![overview.date_key, serialized, overview.updated_at],
     )
     .map_err(|e| e.to_string())?;
-
-    Ok(overview)
+    Ok(())
 }
 
 pub fn get_dashboard_snapshot(app_handle: &AppHandle) -> Result<Option<DashboardOverview>, String> {
-    let (date_key, _, _) = today_bounds_local();
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(app_handle)?;
+    let (date_key, _, _) = today_bounds_local(&conn);
 
     let result: Result<String, _> = conn.query_row(
         "SELECT summary_json FROM dashboard_snapshots WHERE date_key = ?1",
@@ -149,16 +223,12 @@ pub fn get_dashboard_snapshot(app_handle: &AppHandle) -> Result<Option<Dashboard
     }
 }
 
-fn load_settings(app_handle: &AppHandle) -> Option<Settings> {
-    let data_dir = app_handle.path().app_data_dir().ok()?;
-    let settings_path = data_dir.join("config").join("settings.json");
-    if !settings_path.exists() {
-        return Some(Settings::default());
-    }
-    let data = std::fs::read_to_string(settings_path).ok()?;
-    let mut settings: Settings = serde_json::from_str(&data).ok()?;
-    crate::utils::config::apply_env_defaults(&mut settings);
-    Some(settings)
+/// Settings with `ai.dashboard_profile` applied.
+fn dashboard_settings(app_handle: &AppHandle) -> crate::models::Settings {
+    let mut settings = crate::utils::config::load_settings(app_handle);
+    let profile = settings.ai.dashboard_profile.clone();
+    settings.ai.apply_profile_or_default(profile.as_deref());
+    settings
 }
 
 #[derive(Default)]
@@ -169,17 +239,22 @@ struct TodayContext {
     entries: Vec<(String, String, String)>,
     file_changes: Vec<(String, String, String, String, String, i64)>, // path, root, entity, change_type, preview, detected_at
     communication_events: Vec<(String, String, String, i64)>,
+    meetings: Vec<Meeting>,
     chat_turns: Vec<(String, String, i64)>, // user, assistant, assistant timestamp
+    current_focus: Option<String>,
+    goal_streaks: Vec<(String, i64)>, // goal description, days in a row
 }
 
 fn build_today_context(conn: &Connection, day_start: i64, day_end: i64) -> Result<TodayContext, String> {
     let mut ctx = TodayContext::default();
+    ctx.current_focus = crate::services::current_focus::get(conn).map(|f| f.text);
+    ctx.goal_streaks = crate::services::goal_streaks::active_streaks(conn);
 
     let mut stmt = conn
         .prepare(
             "SELECT app_name, window_title, start_time, duration_seconds, metadata
              FROM activities
-             WHERE start_time >= ?1 AND start_time < ?2
+             WHERE start_time >= ?1 AND start_time < ?2 AND COALESCE(private, 0) = 0
              ORDER BY start_time DESC
              LIMIT 400",
         )
@@ -215,7 +290,9 @@ fn build_today_context(conn: &Connection, day_start: i64, day_end: i64) -> Resul
             let snippet = ocr.chars().take(180).collect::<String>();
             ctx.ocr_snippets.push(snippet);
         }
+        // Meeting windows reach the model as clustered `meetings` instead.
         if is_communication_app(&app)
+            && meeting_name(&app, &window_title).is_none()
             && (!window_title.trim().is_empty() || !ocr.trim().is_empty())
             && ctx.communication_events.len() < 240
         {
@@ -231,6 +308,7 @@ fn build_today_context(conn: &Connection, day_start: i64, day_end: i64) -> Resul
     let mut top_apps: Vec<(String, i64)> = app_totals.into_iter().collect();
     top_apps.sort_by(|a, b| b.1.cmp(&a.1));
     ctx.top_apps = top_apps.into_iter().take(8).collect();
+    ctx.meetings = detect_meetings(conn, day_start, day_end, false)?;
 
     let mut entry_stmt = conn
         .prepare(
@@ -317,31 +395,75 @@ fn build_today_context(conn: &Connection, day_start: i64, day_end: i64) -> Resul
     Ok(ctx)
 }
 
-async fn ai_dashboard_summary(
-    context: &TodayContext,
-    api_key: &str,
-    model: &str,
-) -> Result<DashboardOverview, String> {
-    let prompt = format!(
+/// Recent questions from the chat, for picking the dashboard's language on "auto".
+fn context_language_sample(context: &TodayContext) -> String {
+    context
+        .chat_turns
+        .iter()
+        .rev()
+        .take(5)
+        .map(|(user, _, _)| user.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn context_lang(context: &TodayContext) -> Lang {
+    i18n::answer_lang(&context_language_sample(context))
+}
+
+/// User prompt for the AI dashboard, with today's context and the language instruction.
+fn dashboard_prompt(context: &TodayContext) -> String {
+    format!(
         "Build a personal dashboard from today's data only.\n\
-Return strict JSON with keys: summary (string), focus_points (string[]), deadlines ([{{title,due_date,status,source}}]), \
+{}\n\
+Return strict JSON with keys: summary (string), \
+focus_points ([{{kind: \"deadline\"|\"project\"|\"contact\"|\"habit\"|\"info\", text, entity_ref: {{type: \"project\"|\"contact\"|\"deadline\", name}} or null, priority: 1-3 with 3 most urgent}}]), deadlines ([{{title,due_date,status,source}}]), \
 projects ([{{name,update,files_changed}}]), contacts ([{{name,context,last_seen}}]).\n\
-Keep response factual and concise. The summary should be a comprehensive paragraph summarizing the user's overall activity, including project updates, file changes, music/songs listened to (if any), ongoing projects, and chat interactions.\n\n\
+Keep response factual and concise. The summary should be a comprehensive paragraph summarizing the user's overall activity, including project updates, file changes, music/songs listened to (if any), ongoing projects, meetings, and chat interactions.\n\
+Meetings are already detected and listed as (name, start, minutes, attendees); report them as given, do not re-derive them from communication events.\n\
+Focus points are things to act on next (an upcoming deadline, a project to continue, someone to reply to), not statistics; \
+link each to the project, contact or deadline it is about using the same name as in the projects/contacts/deadlines lists.\n\
+If the user stated a current focus, open the summary with how today's activity relates to it and make it the first focus point.\n\n\
+Stated current focus: {}\n\
 Top apps: {:?}\n\
 Total tracked seconds: {}\n\
 Entries: {:?}\n\
 Recent file changes: {:?}\n\
 OCR snippets: {:?}\n\
+Meetings: {:?}\n\
 Communication events: {:?}\n\
 Chat turns: {:?}",
+        i18n::language_instruction(&context_language_sample(context)),
+        context.current_focus.as_deref().unwrap_or("none"),
         context.top_apps,
         context.total_duration,
         context.entries,
         context.file_changes.iter().take(80).collect::<Vec<_>>(),
         context.ocr_snippets.iter().take(60).collect::<Vec<_>>(),
+        context
+            .meetings
+            .iter()
+            .take(20)
+            .map(|m| (
+                m.name.as_str(),
+                crate::utils::time::format_local_time(m.start_time).unwrap_or_default(),
+                m.duration_seconds / 60,
+                m.attendees.join(", "),
+            ))
+            .collect::<Vec<_>>(),
         context.communication_events.iter().take(120).collect::<Vec<_>>(),
         context.chat_turns.iter().rev().take(50).collect::<Vec<_>>(),
-    );
+    )
+}
+
+async fn ai_dashboard_summary(
+    app_handle: &AppHandle,
+    context: &TodayContext,
+    api_key: &str,
+    model: &str,
+    temperature: f32,
+) -> Result<DashboardOverview, String> {
+    let prompt = dashboard_prompt(context);
 
     let request = DashboardChatRequest {
         model: model.to_string(),
@@ -355,50 +477,101 @@ Chat turns: {:?}",
                 content: prompt,
             },
         ],
-        temperature: 0.2,
+        temperature,
         max_tokens: 900,
     };
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://integrate.api.nvidia.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("dashboard API request failed: {}", e))?;
-
-    let status = response.status();
-    let text = response.text().await.map_err(|e| e.to_string())?;
-    if !status.is_success() {
-        return Err(format!("dashboard API error {}: {}", status, text));
-    }
-
-    let parsed: DashboardChatResponse = serde_json::from_str(&text).map_err(|e| e.to_string())?;
-    let content = parsed
-        .choices
-        .first()
-        .and_then(|c| c.message.content.clone())
-        .ok_or_else(|| "dashboard AI returned empty content".to_string())?;
-        
-    let clean_content = content.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
-    let payload: DashboardLLMOutput = serde_json::from_str(clean_content).map_err(|e| format!("JSON parse error: {} - Content: {}", e, clean_content))?;
+    let content = send_dashboard_request(app_handle, api_key, &request).await?;
+    let payload = match parse_dashboard_output(&content) {
+        Ok(payload) => payload,
+        Err(e) => {
+            // One more try with the bad reply in view before giving up on the AI summary.
+            log::warn!("Summary was not valid JSON ({}), asking again", e);
+            let mut retry = request.clone();
+            retry.messages.push(DashboardChatMessage {
+                role: "assistant".to_string(),
+                content: content.clone(),
+            });
+            retry.messages.push(DashboardChatMessage {
+                role: "user".to_string(),
+                content: format!(
+                    "That reply was not valid JSON ({}). Return only valid JSON with the requested keys: no commentary, no code fences.",
+                    e
+                ),
+            });
+            let content = send_dashboard_request(app_handle, api_key, &retry).await?;
+            parse_dashboard_output(&content)
+                .map_err(|e| format!("JSON parse error after retry: {} - Content: {}", e, content))?
+        }
+    };
 
     Ok(DashboardOverview {
         date_key: String::new(),
-        summary: payload.summary.unwrap_or_else(|| "No summary generated yet.".to_string()),
-        focus_points: payload.focus_points.unwrap_or_default(),
+        summary: payload
+            .summary
+            .unwrap_or_else(|| Msg::NoSummaryYet.text(context_lang(context)).to_string()),
+        focus_points: payload
+            .focus_points
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|v| serde_json::from_value::<FocusPoint>(v).ok())
+            .filter(|p| !p.text.trim().is_empty())
+            .take(10)
+            .collect(),
         deadlines: payload.deadlines.unwrap_or_default().into_iter().take(10).collect(),
         projects: payload.projects.unwrap_or_default().into_iter().take(10).collect(),
         contacts: payload.contacts.unwrap_or_default().into_iter().take(10).collect(),
+        meetings: context.meetings.clone(),
         updated_at: 0,
     })
 }
 
+async fn send_dashboard_request(
+    app_handle: &AppHandle,
+    api_key: &str,
+    request: &DashboardChatRequest,
+) -> Result<String, String> {
+    let response = llm_client::send_chat_request(
+        api_key,
+        &request.model,
+        request,
+        llm_client::DEFAULT_TIMEOUT,
+        llm_client::Priority::Background,
+        None,
+    )
+    .await
+    .map_err(|e| format!("dashboard {}", e))?;
+    let text = response.text().await.map_err(|e| e.to_string())?;
+
+    let parsed: DashboardChatResponse = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    record_dashboard_usage(app_handle, llm_client::CALLER_DASHBOARD, request, &parsed);
+    parsed
+        .choices
+        .first()
+        .and_then(|c| c.message.content.clone())
+        .ok_or_else(|| "dashboard AI returned empty content".to_string())
+}
+
+/// The reply as-is, else the first complete top-level object in it (models
+/// like to wrap the JSON in fences or commentary). The error is the one from
+/// the whole reply.
+fn parse_dashboard_output(content: &str) -> Result<DashboardLLMOutput, String> {
+    let content = crate::services::query_engine::strip_think_blocks(content);
+    let clean_content = content.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
+    let error = match serde_json::from_str::<DashboardLLMOutput>(clean_content) {
+        Ok(payload) => return Ok(payload),
+        Err(e) => e.to_string(),
+    };
+    crate::services::query_engine::top_level_json_objects(clean_content)
+        .into_iter()
+        .find_map(|candidate| serde_json::from_str::<DashboardLLMOutput>(candidate).ok())
+        .ok_or(error)
+}
+
 fn fallback_dashboard_summary(context: &TodayContext) -> DashboardOverview {
+    let lang = context_lang(context);
     let summary = if context.top_apps.is_empty() {
-        "No tracked activity yet today.".to_string()
+        Msg::NoActivityToday.text(lang).to_string()
     } else {
         let apps = context
             .top_apps
@@ -407,10 +580,95 @@ fn fallback_dashboard_summary(context: &TodayContext) -> DashboardOverview {
             .map(|(name, dur)| format!("{} ({}m)", name, dur / 60))
             .collect::<Vec<_>>()
             .join(", ");
-        format!("Today you mostly worked in {}.", apps)
+        i18n::fill(Msg::MostlyWorkedIn, lang, &[&apps])
+    };
+    let summary = match &context.current_focus {
+        Some(focus) => i18n::fill(Msg::StatedFocus, lang, &[focus, &summary]),
+        None => summary,
+    };
+    let summary = if context.goal_streaks.is_empty() {
+        summary
+    } else {
+        let streaks = context
+            .goal_streaks
+            .iter()
+            .take(3)
+            .map(|(goal, days)| format!("{} ({}d)", goal, days))
+            .collect::<Vec<_>>()
+            .join(", ");
+        i18n::fill(Msg::ActiveStreaks, lang, &[&summary, &streaks])
     };
 
-    let deadlines = context
+    let deadlines = derive_deadlines_from_context(context);
+
+    let projects = summarize_projects_from_file_changes(context, 8);
+
+    let contacts = derive_contacts_from_context(context);
+    let focus_points = fallback_focus_points(context, &deadlines, &projects, &contacts, lang);
+
+    DashboardOverview {
+        date_key: String::new(),
+        summary,
+        focus_points,
+        deadlines,
+        projects,
+        contacts,
+        meetings: context.meetings.clone(),
+        updated_at: 0,
+    }
+}
+
+/// Focus points from what the fallback already found: open deadlines first,
+/// then the busiest projects and the people talked to most.
+fn fallback_focus_points(
+    context: &TodayContext,
+    deadlines: &[DashboardTask],
+    projects: &[ProjectOverview],
+    contacts: &[ContactOverview],
+    lang: Lang,
+) -> Vec<FocusPoint> {
+    let mut points = Vec::new();
+    if let Some(focus) = &context.current_focus {
+        points.push(FocusPoint {
+            priority: FOCUS_PRIORITY_HIGH,
+            ..FocusPoint::info(i18n::fill(Msg::KeepGoingOn, lang, &[focus]))
+        });
+    }
+    for task in deadlines.iter().take(3) {
+        points.push(FocusPoint {
+            kind: FocusPointKind::Deadline,
+            text: match &task.due_date {
+                Some(due) => i18n::fill(Msg::DueOn, lang, &[&task.title, due]),
+                None => task.title.clone(),
+            },
+            entity_ref: Some(FocusEntityRef::named("deadline", &task.title)),
+            priority: FOCUS_PRIORITY_HIGH,
+        });
+    }
+    for project in projects.iter().take(3) {
+        points.push(FocusPoint {
+            kind: FocusPointKind::Project,
+            text: i18n::fill(Msg::ContinueProject, lang, &[&project.name, &project.files_changed]),
+            entity_ref: Some(FocusEntityRef::named("project", &project.name)),
+            priority: 2,
+        });
+    }
+    for contact in contacts.iter().take(2) {
+        points.push(FocusPoint {
+            kind: FocusPointKind::Contact,
+            text: i18n::fill(Msg::FollowUpWith, lang, &[&contact.name]),
+            entity_ref: Some(FocusEntityRef::named("contact", &contact.name)),
+            priority: 2,
+        });
+    }
+    if points.is_empty() {
+        points.push(FocusPoint::info(Msg::NothingToFollowUp.text(lang)));
+    }
+    points
+}
+
+fn derive_deadlines_from_context(context: &TodayContext) -> Vec<DashboardTask> {
+    context
         .entries
         .iter()
         .filter(|(_, content, status)| {
@@ -426,32 +684,11 @@ fn fallback_dashboard_summary(context: &TodayContext) -> DashboardOverview {
             status: status.clone(),
             source: "entry".to_string(),
         })
-        .collect::<Vec<_>>();
-
-    let projects = summarize_projects_from_file_changes(context, 8);
-
-    let contacts = derive_contacts_from_context(context);
-    let comm_events_count = context.communication_events.len();
-    let file_change_count = context.file_changes.len();
-    let chat_turn_count = context.chat_turns.len();
-
-    DashboardOverview {
-        date_key: String::new(),
-        summary,
-        focus_points: vec![
-            "Summary uses only today's tracked activity".to_string(),
-            format!("Detected {} communication events today", comm_events_count),
-            format!("Tracked {} file/code changes today", file_change_count),
-            format!("Reviewed {} chat turn(s) for recap", chat_turn_count),
-        ],
-        deadlines,
-        projects,
-        contacts,
-        updated_at: 0,
-    }
+        .collect()
 }
 
-fn extract_due_hint(text: &str) -> Option<String> {
+/// "due Friday", "deadline 12 May", "by tomorrow" — the first such phrase in free text.
+pub(crate) fn extract_due_hint(text: &str) -> Option<String> {
     let lower = text.to_lowercase();
     for marker in ["due ", "deadline ", "by "] {
         if let Some(idx) = lower.find(marker) {
@@ -473,11 +710,16 @@ fn is_communication_app(app: &str) -> bool {
 }
 
 fn derive_contacts_from_context(context: &TodayContext) -> Vec<ContactOverview> {
-    let mut by_name: HashMap<String, (i32, i64, String)> = HashMap::new();
+    let lang = context_lang(context);
+    let mut by_name: HashMap<String, (i32, i64, String, HashMap<InteractionTag, i32>)> = HashMap::new();
 
     for (app, title, ocr, start_time) in &context.communication_events {
         let mut candidates = extract_contact_candidates(title);
         candidates.extend(extract_contact_candidates(ocr));
+        if candidates.is_empty() {
+            continue;
+        }
+        let tag = classify_interaction(ocr);
 
         for candidate in candidates {
             let normalized = normalize_contact_name(&candidate);
@@ -485,30 +727,37 @@ fn derive_contacts_from_context(context: &TodayContext) -> Vec<ContactOverview>
                 continue;
             }
             let key = normalized.to_lowercase();
-            let entry = by_name.entry(key).or_insert((0, 0, app.clone()));
+            let entry = by_name.entry(key).or_insert((0, 0, app.clone(), HashMap::new()));
             entry.0 += 1;
             if *start_time > entry.1 {
                 entry.1 = *start_time;
             }
+            if tag != InteractionTag::Unknown {
+                *entry.3.entry(tag).or_insert(0) += 1;
+            }
         }
     }
 
     let mut contacts: Vec<ContactOverview> = by_name
         .into_iter()
-        .map(|(key, (count, last_seen, app))| ContactOverview {
-            name: key
-                .split_whitespace()
-                .map(|w| {
-                    let mut chars = w.chars();
-                    match chars.next() {
-                        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-                        None => String::new(),
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(" "),
-            context: format!("{} interaction(s) in {}", count, app),
-            last_seen: Some(last_seen),
+        .map(|(key, (count, last_seen, app, tag_counts))| {
+            let tags = sorted_tag_counts(tag_counts);
+            ContactOverview {
+                name: key
+                    .split_whitespace()
+                    .map(|w| {
+                        let mut chars = w.chars();
+                        match chars.next() {
+                            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                            None => String::new(),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                context: contact_context(count, &app, &tags, lang),
+                last_seen: Some(last_seen),
+                tags,
+            }
         })
         .collect();
 
@@ -517,6 +766,20 @@ fn derive_contacts_from_context(context: &TodayContext) -> Vec<ContactOverview>
     contacts
 }
 
+/// Same name matching the contact cards use, applied to one stored event.
+fn event_mentions_contact(key: &str, title: &str, ocr: &str) -> bool {
+    let mut candidates = extract_contact_candidates(title);
+    candidates.extend(extract_contact_candidates(ocr));
+    if candidates
+        .iter()
+        .any(|c| normalize_contact_name(c).to_lowercase() == key)
+    {
+        return true;
+    }
+    normalize_contact_name(title).to_lowercase().contains(key)
+        || normalize_contact_name(ocr).to_lowercase().contains(key)
+}
+
 fn extract_contact_candidates(text: &str) -> Vec<String> {
     let cleaned = text.replace('\n', " ");
     let mut out = Vec::new();
@@ -589,6 +852,104 @@ fn normalize_contact_name(text: &str) -> String {
         .join(" ")
 }
 
+/// "2 scheduling, 1 social in WhatsApp"; the plain count when no interaction could be tagged.
+fn contact_context(count: i32, app: &str, tags: &[InteractionTagCount], lang: Lang) -> String {
+    if tags.is_empty() {
+        return i18n::fill(Msg::Interactions, lang, &[&count, &app]);
+    }
+    let parts: Vec<String> = tags
+        .iter()
+        .map(|t| format!("{} {}", t.count, tag_label(t.tag, lang)))
+        .collect();
+    i18n::fill(Msg::TaggedInteractions, lang, &[&parts.join(", "), &app])
+}
+
+fn tag_label(tag: InteractionTag, lang: Lang) -> &'static str {
+    match tag {
+        InteractionTag::Scheduling => Msg::TagScheduling.text(lang),
+        InteractionTag::Question => Msg::TagQuestion.text(lang),
+        InteractionTag::Work => Msg::TagWork.text(lang),
+        InteractionTag::Social => Msg::TagSocial.text(lang),
+        InteractionTag::Unknown => "unknown",
+    }
+}
+
+fn sorted_tag_counts(counts: HashMap<InteractionTag, i32>) -> Vec<InteractionTagCount> {
+    let mut tags: Vec<InteractionTagCount> = counts
+        .into_iter()
+        .map(|(tag, count)| InteractionTagCount { tag, count })
+        .collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then(a.tag.cmp(&b.tag)));
+    tags
+}
+
+const SCHEDULING_WORDS: &[&str] = &[
+    "meet", "meeting", "tomorrow", "tonight", "schedule", "reschedule", "calendar", "available", "appointment",
+    "invite", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday", "when's",
+];
+const SCHEDULING_PHRASES: &[&str] = &["see you", "next week", "what time", "free at", "are you free", "call at", "let's meet"];
+const QUESTION_WORDS: &[&str] = &["what", "why", "how", "where", "who", "which", "anyone"];
+const QUESTION_PHRASES: &[&str] = &["can you", "could you", "do you", "did you", "any idea", "is it", "are you"];
+const WORK_WORDS: &[&str] = &[
+    "deadline", "deploy", "review", "client", "report", "invoice", "task", "ticket", "bug", "build", "release",
+    "standup", "sprint", "jira", "spec", "project", "budget", "pr", "merge", "draft", "slides", "office",
+];
+const WORK_PHRASES: &[&str] = &["pull request", "action items", "follow up", "status update"];
+const SOCIAL_WORDS: &[&str] = &[
+    "haha", "hahaha", "lol", "lmao", "congrats", "birthday", "love", "party", "dinner", "lunch", "movie",
+    "weekend", "thanks", "hey", "hi", "hello", "bro", "miss", "gm", "gn",
+];
+const SOCIAL_PHRASES: &[&str] = &["thank you", "good morning", "good night", "miss you", "how are you"];
+
+/// Keyword-rule tag for one communication capture. Deterministic and cheap, so it
+/// runs on every event instead of going through the LLM. Unreadable OCR (and
+/// text that matches no rule) is `Unknown` and left out of the contact's counts.
+fn classify_interaction(text: &str) -> InteractionTag {
+    if is_unreadable_ocr(text) {
+        return InteractionTag::Unknown;
+    }
+    let lower = text.to_lowercase();
+    let words: HashSet<&str> = lower
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let phrase_text = format!(" {} ", lower.split_whitespace().collect::<Vec<_>>().join(" "));
+    let score = |single: &[&str], phrases: &[&str]| -> usize {
+        single.iter().filter(|w| words.contains(*w)).count()
+            + phrases.iter().filter(|p| phrase_text.contains(&format!(" {} ", p))).count() * 2
+    };
+
+    let emoji = text.chars().filter(|c| crate::utils::text::is_emoji(*c)).count();
+    // Ordered by precedence: on a tie the earlier tag wins.
+    let scores = [
+        (InteractionTag::Scheduling, score(SCHEDULING_WORDS, SCHEDULING_PHRASES)),
+        (InteractionTag::Work, score(WORK_WORDS, WORK_PHRASES)),
+        (InteractionTag::Social, score(SOCIAL_WORDS, SOCIAL_PHRASES) + emoji.min(2)),
+        // Question words turn up in every kind of message, so this tag loses ties.
+        (InteractionTag::Question, score(QUESTION_WORDS, QUESTION_PHRASES) + lower.matches('?').count().min(2)),
+    ];
+    scores
+        .iter()
+        .fold((InteractionTag::Unknown, 0), |best, &(tag, score)| if score > best.1 { (tag, score) } else { best })
+        .0
+}
+
+/// Too short, mostly symbols, or consonant soup: OCR noise rather than a conversation.
+fn is_unreadable_ocr(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let letters = chars.iter().filter(|c| c.is_alphabetic()).count();
+    if letters < 6 {
+        return true;
+    }
+    let readable = chars
+        .iter()
+        .filter(|c| c.is_alphanumeric() || crate::utils::text::is_emoji(**c) || crate::utils::text::is_expected_symbol(**c))
+        .count();
+    let readable_ratio = readable as f64 / chars.len() as f64;
+    let consonant_soup = crate::utils::text::latin_vowel_ratio(&chars, 11).is_some_and(|ratio| ratio < 0.08);
+    readable_ratio < 0.65 || consonant_soup
+}
+
 fn summarize_projects_from_file_changes(context: &TodayContext, limit: usize) -> Vec<ProjectOverview> {
     #[derive(Default)]
     struct ProjectStat {
@@ -782,7 +1143,7 @@ fn sanitize_preview_snippet(preview: &str) -> String {
     cleaned.chars().take(140).collect::<String>()
 }
 
-fn is_noise_file_change(path: &str, root: &str, preview: &str) -> bool {
+pub(crate) fn is_noise_file_change(path: &str, root: &str, preview: &str) -> bool {
     let p = path.to_lowercase();
     let r = root.to_lowercase();
     if p.contains("/target/") || p.contains("\\target\\") || r.ends_with("/target") || r.ends_with("\\target") {
@@ -815,12 +1176,14 @@ fn merge_dashboard_overview(previous: DashboardOverview, mut fresh: DashboardOve
         fresh.summary = previous.summary;
     }
 
-    let mut merged_focus = Vec::new();
+    // Fresh points win; an older one about the same entity is a rewording, not a new point.
+    let mut merged_focus: Vec<FocusPoint> = Vec::new();
     for point in fresh.focus_points.into_iter().chain(previous.focus_points.into_iter()) {
-        if !point.trim().is_empty() && !merged_focus.contains(&point) {
+        if !point.text.trim().is_empty() && !merged_focus.iter().any(|p| p.same_point(&point)) {
             merged_focus.push(point);
         }
     }
+    merged_focus.sort_by(|a, b| b.priority.cmp(&a.priority));
     fresh.focus_points = merged_focus.into_iter().take(10).collect();
 
     let mut merged_deadlines = fresh.deadlines;
@@ -865,19 +1228,354 @@ fn merge_dashboard_overview(previous: DashboardOverview, mut fresh: DashboardOve
     fresh
 }
 
-fn today_bounds_local() -> (String, i64, i64) {
-    let now = chrono::Local::now();
-    let date = now.date_naive();
-    let start_local = chrono::Local
-        .from_local_datetime(&date.and_hms_opt(0, 0, 0).expect("valid local midnight"))
-        .single()
-        .unwrap_or(now);
-    let start = start_local.timestamp();
-    let end = start + 24 * 3600;
-    (date.format("%Y-%m-%d").to_string(), start, end)
+fn today_bounds_local(conn: &Connection) -> (String, i64, i64) {
+    let bounds = crate::utils::time::day_bounds(Some(conn), crate::utils::time::today());
+    (bounds.date_key, bounds.start_ts, bounds.end_ts)
+}
+
+/// Deterministic recap for one local day. Never calls the LLM, so it works with AI
+/// disabled and doubles as ground truth for the AI dashboard summary.
+pub fn build_daily_recap(app_handle: &AppHandle, date_key: Option<&str>) -> Result<DailyRecap, AppError> {
+    let date = match date_key.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(key) => chrono::NaiveDate::parse_from_str(key, "%Y-%m-%d")
+            .map_err(|_| AppError::InvalidInput(format!("Invalid date_key '{}', expected YYYY-MM-DD", key)))?,
+        None => crate::utils::time::today(),
+    };
+
+    let conn = crate::database::open(app_handle)?;
+    let bounds = crate::utils::time::day_bounds(Some(&conn), date);
+    let (day_start, day_end) = (bounds.start_ts, bounds.end_ts);
+
+    let (total_tracked_seconds, mut top_apps, category_split) =
+        usage_totals_for_range(&conn, day_start, day_end)?;
+    top_apps.truncate(5);
+
+    let songs_played = songs_played(&conn, day_start, day_end)?.len() as i64;
+
+    let context = build_today_context(&conn, day_start, day_end)?;
+    let projects = summarize_projects_from_file_changes(&context, 10);
+    let contacts = derive_contacts_from_context(&context);
+
+    Ok(DailyRecap {
+        date_key: date.format("%Y-%m-%d").to_string(),
+        total_tracked_seconds,
+        top_apps,
+        category_split,
+        projects,
+        songs_played,
+        contacts,
+        generated_at: chrono::Utc::now().timestamp(),
+    })
 }
 
-async fn call_llm_for_summary(api_key: &str, model: &str, prompt: &str) -> Result<String, String> {
+/// Tracked total plus every app and category for `[start_ts, end_ts)`, largest first.
+/// Private rows are excluded. Shared by the daily recap and period reports.
+pub(crate) fn usage_totals_for_range(
+    conn: &Connection,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<(i64, Vec<AppStat>, Vec<CategoryStat>), String> {
+    let total_tracked_seconds: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(duration_seconds), 0) FROM activities
+             WHERE start_time >= ?1 AND start_time < ?2 AND COALESCE(private, 0) = 0",
+            rusqlite::params![start_ts, end_ts],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let share = |duration: i64| -> f32 {
+        if total_tracked_seconds > 0 {
+            ((duration as f64 / total_tracked_seconds as f64) * 100.0) as f32
+        } else {
+            0.0
+        }
+    };
+
+    let mut app_stmt = conn
+        .prepare(
+            "SELECT app_name, SUM(duration_seconds) as total, COUNT(*)
+             FROM activities
+             WHERE start_time >= ?1 AND start_time < ?2 AND COALESCE(private, 0) = 0
+             GROUP BY app_name
+             ORDER BY total DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let top_apps = app_stmt
+        .query_map(rusqlite::params![start_ts, end_ts], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i32>(2)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(|(app_name, duration, count)| AppStat {
+            app_name,
+            duration,
+            count,
+            percentage: share(duration),
+        })
+        .collect::<Vec<_>>();
+
+    let mut category_stmt = conn
+        .prepare(
+            "SELECT a.category_id, COALESCE(c.name, 'Other'), SUM(a.duration_seconds) as total, COUNT(*)
+             FROM activities a
+             LEFT JOIN categories c ON c.id = a.category_id
+             WHERE a.start_time >= ?1 AND a.start_time < ?2 AND COALESCE(a.private, 0) = 0
+             GROUP BY a.category_id
+             ORDER BY total DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let category_split = category_stmt
+        .query_map(rusqlite::params![start_ts, end_ts], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i32>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(|(category_id, category_name, duration, count)| CategoryStat {
+            category_id,
+            category_name,
+            duration,
+            count,
+            percentage: share(duration),
+        })
+        .collect::<Vec<_>>();
+
+    Ok((total_tracked_seconds, top_apps, category_split))
+}
+
+/// Contacts and open deadlines for an arbitrary range, using the same
+/// heuristics as the dashboard cards.
+pub(crate) fn contacts_and_deadlines_for_range(
+    conn: &Connection,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<(Vec<ContactOverview>, Vec<DashboardTask>), String> {
+    let context = build_today_context(conn, start_ts, end_ts)?;
+    Ok((derive_contacts_from_context(&context), derive_deadlines_from_context(&context)))
+}
+
+/// Distinct songs (title, artist) that were playing in `[day_start, day_end)`, first play first.
+fn songs_played(conn: &Connection, day_start: i64, day_end: i64) -> Result<Vec<(String, String)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT metadata, app_name, window_title, duration_seconds FROM activities
+             WHERE start_time >= ?1 AND start_time < ?2 AND metadata IS NOT NULL
+             AND COALESCE(private, 0) = 0
+             ORDER BY start_time",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![day_start, day_end], |row| {
+            Ok((
+                row.get::<_, Vec<u8>>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut songs = Vec::new();
+    for (blob, app_name, window_title, duration_seconds) in rows.filter_map(|r| r.ok()) {
+        let Ok(meta) = serde_json::from_slice::<ActivityMetadata>(&blob) else {
+            continue;
+        };
+        let Some(media) = meta.media_info else {
+            continue;
+        };
+        let kind = meta.media_kind.unwrap_or_else(|| {
+            crate::utils::media::classify_media(&media, &app_name, &window_title, duration_seconds)
+        });
+        if kind != MediaKind::Music || !media.status.eq_ignore_ascii_case("playing") {
+            continue;
+        }
+        let key = format!(
+            "{}|{}",
+            media.title.trim().to_lowercase(),
+            media.artist.trim().to_lowercase()
+        );
+        if seen.insert(key) {
+            songs.push((media.title.trim().to_string(), media.artist.trim().to_string()));
+        }
+    }
+    Ok(songs)
+}
+
+/// Sessions listed under "Timeline highlights" in the journal.
+const JOURNAL_HIGHLIGHTS: usize = 8;
+const JOURNAL_SONGS: usize = 15;
+
+/// One local day as a Markdown journal: the deterministic recap in dated
+/// sections, plus the dashboard summary prose when AI is on and a snapshot
+/// exists for that day.
+pub fn build_journal(app_handle: &AppHandle, date_key: Option<&str>) -> Result<String, AppError> {
+    let recap = build_daily_recap(app_handle, date_key)?;
+    let date = chrono::NaiveDate::parse_from_str(&recap.date_key, "%Y-%m-%d").map_err(|e| e.to_string())?;
+    let conn = crate::database::open(app_handle)?;
+    let bounds = crate::utils::time::day_bounds(Some(&conn), date);
+    let settings = crate::utils::config::load_settings(app_handle);
+    let snapshot = settings
+        .ai
+        .enabled
+        .then(|| load_snapshot_for_date(&conn, &recap.date_key))
+        .flatten();
+    let meetings = detect_meetings(&conn, bounds.start_ts, bounds.end_ts, false)?;
+    let songs = songs_played(&conn, bounds.start_ts, bounds.end_ts)?;
+    let sessions = crate::database::queries::get_merged_sessions(&conn, bounds.start_ts, bounds.end_ts, 120)
+        .map_err(|e| e.to_string())?;
+    let lang = i18n::default_lang();
+    let duration = |secs: i64| i18n::format_duration(secs, lang);
+    let time = |ts: i64| crate::utils::time::format_local_time(ts).unwrap_or_default();
+
+    let mut md = format!("# {}\n\n", date.format("%A, %B %-d, %Y"));
+
+    md.push_str("## Overview\n\n");
+    if recap.total_tracked_seconds == 0 {
+        md.push_str("No activity was tracked on this day.\n\n");
+    } else {
+        md.push_str(&format!("- Tracked: {}\n", duration(recap.total_tracked_seconds)));
+        for category in recap.category_split.iter().take(4) {
+            md.push_str(&format!(
+                "- {}: {} ({:.0}%)\n",
+                category.category_name,
+                duration(category.duration),
+                category.percentage
+            ));
+        }
+        if !recap.top_apps.is_empty() {
+            let apps: Vec<String> = recap
+                .top_apps
+                .iter()
+                .map(|app| format!("{} ({})", app.app_name, duration(app.duration)))
+                .collect();
+            md.push_str(&format!("- Top apps: {}\n", apps.join(", ")));
+        }
+        md.push('\n');
+    }
+    if let Some(summary) = snapshot.as_ref().map(|s| s.summary.trim()).filter(|s| !s.is_empty()) {
+        md.push_str(summary);
+        md.push_str("\n\n");
+    }
+
+    md.push_str("## Projects\n\n");
+    if recap.projects.is_empty() {
+        md.push_str("No project files changed.\n\n");
+    } else {
+        for project in &recap.projects {
+            md.push_str(&format!(
+                "- **{}** ({} file{}): {}\n",
+                project.name,
+                project.files_changed,
+                if project.files_changed == 1 { "" } else { "s" },
+                project.update.trim()
+            ));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Communications\n\n");
+    if recap.contacts.is_empty() && meetings.is_empty() {
+        md.push_str("No conversations or meetings detected.\n\n");
+    } else {
+        for meeting in &meetings {
+            let attendees = if meeting.attendees.is_empty() {
+                String::new()
+            } else {
+                format!(" with {}", meeting.attendees.join(", "))
+            };
+            md.push_str(&format!(
+                "- Meeting **{}**, {}–{} ({}){}\n",
+                meeting.name,
+                time(meeting.start_time),
+                time(meeting.end_time),
+                duration(meeting.duration_seconds),
+                attendees
+            ));
+        }
+        for contact in &recap.contacts {
+            let last_seen = contact.last_seen.map(|ts| format!(", last at {}", time(ts))).unwrap_or_default();
+            md.push_str(&format!("- **{}**: {}{}\n", contact.name, contact.context.trim(), last_seen));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Media\n\n");
+    if songs.is_empty() {
+        md.push_str("No music played.\n\n");
+    } else {
+        md.push_str(&format!("{} song{} played:\n\n", songs.len(), if songs.len() == 1 { "" } else { "s" }));
+        for (title, artist) in songs.iter().take(JOURNAL_SONGS) {
+            if artist.is_empty() {
+                md.push_str(&format!("- {}\n", title));
+            } else {
+                md.push_str(&format!("- {} — {}\n", title, artist));
+            }
+        }
+        if songs.len() > JOURNAL_SONGS {
+            md.push_str(&format!("- …and {} more\n", songs.len() - JOURNAL_SONGS));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Timeline highlights\n\n");
+    let mut highlights: Vec<_> = sessions.iter().filter(|s| s.duration_seconds >= 300).collect();
+    highlights.sort_by(|a, b| b.duration_seconds.cmp(&a.duration_seconds));
+    highlights.truncate(JOURNAL_HIGHLIGHTS);
+    highlights.sort_by_key(|s| s.start_time);
+    if highlights.is_empty() {
+        md.push_str("No session lasted five minutes or more.\n");
+    } else {
+        for session in highlights {
+            // Private sessions keep their slot but not their title, as in the calendar export.
+            let label = if session.private || session.window_title.trim().is_empty() {
+                session.app_name.clone()
+            } else {
+                format!("{} — {}", session.app_name, session.window_title.trim())
+            };
+            md.push_str(&format!(
+                "- {}–{} {} ({})\n",
+                time(session.start_time),
+                time(session.end_time),
+                label,
+                duration(session.duration_seconds)
+            ));
+        }
+    }
+    Ok(md)
+}
+
+/// Record a completed dashboard/summary call under `caller`.
+fn record_dashboard_usage(
+    app_handle: &AppHandle,
+    caller: &str,
+    request: &DashboardChatRequest,
+    response: &DashboardChatResponse,
+) {
+    let completion = response
+        .choices
+        .first()
+        .and_then(|c| c.message.content.as_deref())
+        .unwrap_or("");
+    let usage = llm_client::call_usage(
+        &request.model,
+        response.usage,
+        llm_client::estimate_prompt_tokens(request.messages.iter().map(|m| m.content.as_str())),
+        completion,
+    );
+    llm_client::record_usage(app_handle, caller, &request.model, &usage);
+}
+
+pub(crate) async fn call_llm_for_summary(
+    app_handle: &AppHandle,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+) -> Result<String, AppError> {
     let request = DashboardChatRequest {
         model: model.to_string(),
         messages: vec![
@@ -894,46 +1592,41 @@ async fn call_llm_for_summary(api_key: &str, model: &str, prompt: &str) -> Resul
         max_tokens: 300,
     };
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://integrate.api.nvidia.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
-
-    let status = response.status();
-    let text = response.text().await.map_err(|e| e.to_string())?;
-    if !status.is_success() {
-        return Err(format!("API error {}: {}", status, text));
-    }
+    // On-demand contact/project summaries: the user is waiting on these.
+    let response = llm_client::send_chat_request(
+        api_key,
+        &request.model,
+        &request,
+        llm_client::DEFAULT_TIMEOUT,
+        llm_client::Priority::Interactive,
+        None,
+    )
+    .await?;
+    let text = response.text().await?;
 
-    let parsed: DashboardChatResponse = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let parsed: DashboardChatResponse = serde_json::from_str(&text)?;
+    record_dashboard_usage(app_handle, llm_client::CALLER_SUMMARY, &request, &parsed);
     let content = parsed
         .choices
         .first()
         .and_then(|c| c.message.content.clone())
-        .ok_or_else(|| "AI returned empty content".to_string())?;
+        .ok_or_else(|| AppError::Api("AI returned empty content".to_string()))?;
     
     Ok(content.trim().to_string())
 }
 
-pub async fn summarize_contact(app_handle: &AppHandle, name: &str) -> Result<String, String> {
-    let settings = load_settings(app_handle).unwrap_or_default();
+pub async fn summarize_contact(app_handle: &AppHandle, name: &str) -> Result<String, AppError> {
+    let settings = dashboard_settings(app_handle);
     let api_key = crate::utils::config::resolve_api_key(&settings.ai.api_key);
     let model = settings.ai.model.clone();
 
     if !settings.ai.enabled || api_key.is_empty() {
-        return Ok(format!("AI is disabled or API key is missing. Cannot summarize {}.", name));
+        return Err(AppError::AiDisabled(format!("AI is disabled or API key is missing. Cannot summarize {}.", name)));
     }
 
     let mut context_data = Vec::new();
     {
-        let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-        let db_path = data_dir.join("intentflow.db");
-        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+        let conn = crate::database::open(app_handle)?;
 
         let now = chrono::Local::now().timestamp();
         let start = now - (3 * 24 * 3600);
@@ -941,10 +1634,10 @@ pub async fn summarize_contact(app_handle: &AppHandle, name: &str) -> Result<Str
         let mut stmt = conn.prepare(
             "SELECT app_name, window_title, metadata 
              FROM activities 
-             WHERE start_time >= ?1 AND start_time < ?2 
+             WHERE start_time >= ?1 AND start_time < ?2 AND COALESCE(private, 0) = 0
              AND (window_title LIKE ?3 OR metadata LIKE ?3)
              ORDER BY start_time DESC LIMIT 50"
-        ).map_err(|e| e.to_string())?;
+        )?;
 
         let name_pattern = format!("%{}%", name);
         let rows = stmt.query_map(rusqlite::params![start, now, name_pattern], |row| {
@@ -957,7 +1650,7 @@ pub async fn summarize_contact(app_handle: &AppHandle, name: &str) -> Result<Str
                 .and_then(|m| m.screen_text)
                 .unwrap_or_default();
             Ok((app, title, ocr))
-        }).map_err(|e| e.to_string())?;
+        })?;
 
         for row in rows.filter_map(|r| r.ok()) {
             let ocr_snippet = row.2.chars().take(200).collect::<String>();
@@ -978,50 +1671,36 @@ pub async fn summarize_contact(app_handle: &AppHandle, name: &str) -> Result<Str
         context_data.join("\n")
     );
 
-    call_llm_for_summary(&api_key, &model, &prompt).await
+    call_llm_for_summary(app_handle, &api_key, &model, &prompt).await
 }
 
-pub async fn summarize_project(app_handle: &AppHandle, name: &str) -> Result<String, String> {
-    let settings = load_settings(app_handle).unwrap_or_default();
+pub async fn summarize_project(app_handle: &AppHandle, name: &str) -> Result<String, AppError> {
+    let settings = dashboard_settings(app_handle);
     let api_key = crate::utils::config::resolve_api_key(&settings.ai.api_key);
     let model = settings.ai.model.clone();
 
     if !settings.ai.enabled || api_key.is_empty() {
-        return Ok(format!("AI is disabled or API key is missing. Cannot summarize {}.", name));
+        return Err(AppError::AiDisabled(format!("AI is disabled or API key is missing. Cannot summarize {}.", name)));
     }
 
-    let mut context_data = Vec::new();
-    {
-        let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-        let db_path = data_dir.join("intentflow.db");
-        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-
-        let now = chrono::Local::now().timestamp();
-        let start = now - (3 * 24 * 3600);
-
-        let mut stmt = conn.prepare(
-            "SELECT path, entity_type, change_type, COALESCE(content_preview, '')
-             FROM code_file_events 
-             WHERE detected_at >= ?1 AND detected_at < ?2 
-             AND project_root LIKE ?3
-             ORDER BY detected_at DESC LIMIT 100"
-        ).map_err(|e| e.to_string())?;
-
-        let name_pattern = format!("%{}%", name);
-        let rows = stmt.query_map(rusqlite::params![start, now, name_pattern], |row| {
-            let path: String = row.get(0)?;
-            let entity_type: String = row.get(1)?;
-            let change_type: String = row.get(2)?;
-            let preview: String = row.get(3)?;
-            Ok((path, entity_type, change_type, preview))
-        }).map_err(|e| e.to_string())?;
-
-        for row in rows.filter_map(|r| r.ok()) {
-            let preview_snippet = row.3.chars().take(150).collect::<String>();
-            let clean_preview = preview_snippet.replace('\n', " ");
-            context_data.push(format!("File: {} ({}), Change: {}, Preview: {}", row.0, row.1, row.2, clean_preview));
-        }
-    }
+    let now = chrono::Utc::now().timestamp();
+    let changes = build_project_changes(app_handle, name, now - (3 * 24 * 3600), now)?;
+    let context_data: Vec<String> = changes
+        .files
+        .iter()
+        .take(100)
+        .map(|file| {
+            format!(
+                "File: {} ({}), Changes: {} created / {} modified / {} deleted, Preview: {}",
+                file.path,
+                file.entity_type,
+                file.created,
+                file.modified,
+                file.deleted,
+                file.preview.as_deref().unwrap_or("")
+            )
+        })
+        .collect();
 
     if context_data.is_empty() {
         return Ok(format!("No recent file changes found for project {}.", name));
@@ -1035,5 +1714,703 @@ pub async fn summarize_project(app_handle: &AppHandle, name: &str) -> Result<Str
         context_data.join("\n")
     );
 
-    call_llm_for_summary(&api_key, &model, &prompt).await
+    call_llm_for_summary(app_handle, &api_key, &model, &prompt).await
+}
+
+/// Deterministic view of a project's file changes in `[start_ts, end_ts)`, grouped per file.
+/// `name` matches any monitored project root containing it.
+pub fn build_project_changes(
+    app_handle: &AppHandle,
+    name: &str,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<ProjectChanges, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput("Project name is required".to_string()));
+    }
+    let (start_ts, end_ts) = if start_ts <= end_ts { (start_ts, end_ts) } else { (end_ts, start_ts) };
+
+    let conn = crate::database::open(app_handle)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, project_root, entity_type, change_type, COALESCE(content_preview, ''), detected_at
+             FROM code_file_events
+             WHERE detected_at >= ?1 AND detected_at < ?2
+               AND LOWER(project_root) LIKE ?3
+             ORDER BY detected_at DESC
+             LIMIT ?4",
+        )
+        ?;
+    let name_pattern = format!("%{}%", name.to_lowercase());
+    let rows = stmt
+        .query_map(
+            rusqlite::params![start_ts, end_ts, name_pattern, PROJECT_CHANGES_SCAN_LIMIT],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            },
+        )
+        ?;
+
+    let mut result = ProjectChanges {
+        name: name.to_string(),
+        start_ts,
+        end_ts,
+        ..Default::default()
+    };
+    let mut roots: HashSet<String> = HashSet::new();
+    let mut areas: HashMap<String, i32> = HashMap::new();
+    let mut files: HashMap<String, ProjectFileChange> = HashMap::new();
+
+    // Rows arrive newest first, so the first event seen per file is its latest state.
+    for (path, root, entity_type, change_type, preview, detected_at) in rows.filter_map(|r| r.ok()) {
+        if is_noise_file_change(&path, &root, &preview) {
+            result.noise_filtered += 1;
+            continue;
+        }
+        roots.insert(root.clone());
+        result.total_changes += 1;
+
+        let area = derive_area_from_path(&path, &root);
+        if let Some(ref area) = area {
+            *areas.entry(area.clone()).or_insert(0) += 1;
+        }
+
+        let file = files.entry(path.clone()).or_insert_with(|| ProjectFileChange {
+            path: path.clone(),
+            area,
+            entity_type: entity_type.clone(),
+            last_change_type: change_type.clone(),
+            last_detected_at: detected_at,
+            ..Default::default()
+        });
+        match change_type.to_lowercase().as_str() {
+            "created" => {
+                file.created += 1;
+                result.created += 1;
+            }
+            "deleted" => {
+                file.deleted += 1;
+                result.deleted += 1;
+            }
+            _ => {
+                file.modified += 1;
+                result.modified += 1;
+            }
+        }
+        if file.preview.is_none() && !preview.trim().is_empty() {
+            let snippet = sanitize_preview_snippet(&preview);
+            if !snippet.is_empty() {
+                file.preview = Some(snippet);
+            }
+        }
+    }
+
+    let mut roots: Vec<String> = roots.into_iter().collect();
+    roots.sort();
+    result.project_roots = roots;
+
+    let mut areas: Vec<ProjectAreaChange> = areas
+        .into_iter()
+        .map(|(area, changes)| ProjectAreaChange { area, changes })
+        .collect();
+    areas.sort_by(|a, b| b.changes.cmp(&a.changes).then_with(|| a.area.cmp(&b.area)));
+    result.areas = areas;
+
+    let mut files: Vec<ProjectFileChange> = files.into_values().collect();
+    files.sort_by(|a, b| b.last_detected_at.cmp(&a.last_detected_at));
+    result.files_truncated = files.len() > PROJECT_CHANGES_MAX_FILES;
+    files.truncate(PROJECT_CHANGES_MAX_FILES);
+    result.files = files;
+
+    Ok(result)
+}
+
+pub fn build_contact_timeline(
+    app_handle: &AppHandle,
+    contact_name_or_id: &str,
+    days: i64,
+    limit: usize,
+) -> Result<ContactTimeline, AppError> {
+    let key = normalize_contact_name(contact_name_or_id).to_lowercase();
+    if key.is_empty() {
+        return Err(AppError::InvalidInput("Contact name is required".to_string()));
+    }
+    let days = days.clamp(1, 90);
+    let limit = limit.clamp(1, 200);
+
+    let settings = crate::utils::config::load_settings(app_handle);
+    let excluded_apps: Vec<String> = settings
+        .tracking
+        .exclude_apps
+        .iter()
+        .map(|a| a.trim().to_lowercase())
+        .filter(|a| !a.is_empty())
+        .collect();
+    let include_snippets = !settings.privacy.anonymize_data;
+
+    let conn = crate::database::open(app_handle)?;
+
+    let now = chrono::Utc::now().timestamp();
+    let start = now - days * 24 * 3600;
+
+    // Newest first so the scan cap drops the oldest events, not the latest ones.
+    let mut stmt = conn.prepare(
+        "SELECT app_name, window_title, start_time, duration_seconds, metadata
+         FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2 AND COALESCE(private, 0) = 0
+         AND (window_title LIKE ?3 OR metadata LIKE ?3)
+         ORDER BY start_time DESC LIMIT ?4"
+    )?;
+
+    let name_pattern = format!("%{}%", key);
+    let rows = stmt.query_map(
+        rusqlite::params![start, now, name_pattern, CONTACT_TIMELINE_SCAN_LIMIT],
+        |row| {
+            let app: String = row.get(0)?;
+            let title: String = row.get(1)?;
+            let start_time: i64 = row.get(2)?;
+            let duration: i64 = row.get(3)?;
+            let metadata: Option<Vec<u8>> = row.get(4)?;
+            let ocr = metadata
+                .as_ref()
+                .and_then(|blob| serde_json::from_slice::<ActivityMetadata>(blob).ok())
+                .and_then(|m| m.screen_text)
+                .unwrap_or_default();
+            Ok((app, title, start_time, duration, ocr))
+        },
+    )?;
+
+    let mut scanned = 0i64;
+    let mut events: Vec<ContactInteraction> = Vec::new();
+    for (app, title, start_time, duration, ocr) in rows.filter_map(|r| r.ok()) {
+        scanned += 1;
+        if !is_communication_app(&app) {
+            continue;
+        }
+        let app_lower = app.to_lowercase();
+        if excluded_apps.iter().any(|excluded| app_lower.contains(excluded.as_str())) {
+            continue;
+        }
+        if !event_mentions_contact(&key, &title, &ocr) {
+            continue;
+        }
+        let snippet = if include_snippets {
+            contact_snippet(&ocr, &key)
+        } else {
+            None
+        };
+        events.push(ContactInteraction {
+            app_name: app,
+            window_title: title,
+            start_time,
+            duration_seconds: duration.max(0),
+            snippet,
+            tag: classify_interaction(&ocr),
+        });
+    }
+    events.reverse();
+
+    let mut blocks: Vec<ContactConversationBlock> = Vec::new();
+    for event in events {
+        let event_end = event.start_time + event.duration_seconds;
+        match blocks.last_mut() {
+            Some(block) if event.start_time - block.end_time <= CONTACT_BLOCK_GAP_SECS => {
+                block.end_time = block.end_time.max(event_end);
+                block.total_duration_seconds += event.duration_seconds;
+                if !block.apps.contains(&event.app_name) {
+                    block.apps.push(event.app_name.clone());
+                }
+                block.events.push(event);
+            }
+            _ => blocks.push(ContactConversationBlock {
+                start_time: event.start_time,
+                end_time: event_end,
+                total_duration_seconds: event.duration_seconds,
+                apps: vec![event.app_name.clone()],
+                events: vec![event],
+            }),
+        }
+    }
+
+    let mut has_more = scanned >= CONTACT_TIMELINE_SCAN_LIMIT;
+    if blocks.len() > limit {
+        blocks.drain(..blocks.len() - limit);
+        has_more = true;
+    }
+
+    Ok(ContactTimeline {
+        contact: contact_name_or_id.trim().to_string(),
+        days,
+        blocks,
+        has_more,
+    })
+}
+
+fn contact_snippet(ocr: &str, key: &str) -> Option<String> {
+    let text = ocr.replace('\n', " ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    let lower = text.to_lowercase();
+    let Some(idx) = lower.find(key) else {
+        return Some(text.chars().take(120).collect());
+    };
+    // Char-safe window of ~60 chars either side of the name.
+    let before = lower[..idx].chars().count();
+    let start_char = before.saturating_sub(60);
+    let snippet: String = text
+        .chars()
+        .skip(start_char)
+        .take(120 + key.chars().count())
+        .collect();
+    Some(format!("...{}...", snippet.trim()))
+}
+
+/// Shortest clustered meeting worth reporting; shorter hits are usually a glance at the lobby.
+const MEETING_MIN_SECS: i64 = 10 * 60;
+const MEETING_SCAN_LIMIT: i64 = 5000;
+const MEETING_MAX_ATTENDEES: usize = 12;
+
+/// Words that make a plain Teams window title a meeting rather than a chat.
+const MEETING_TITLE_HINTS: &[&str] = &[
+    "meeting", "sync", "standup", "stand-up", "1:1", "call with", "review", "retro", "planning", "interview",
+    "demo", "all hands", "all-hands", "huddle",
+];
+
+/// Teams panes whose title segment is not a meeting name.
+const TEAMS_UI_SEGMENTS: &[&str] = &[
+    "chat", "activity", "calendar", "teams", "calls", "files", "microsoft teams", "meeting compact view",
+];
+
+/// Meeting name from a window title, for Teams ("Weekly Sync | Microsoft Teams"),
+/// Zoom ("Zoom Meeting", "Weekly Sync - Zoom") and Google Meet in a browser
+/// ("Meet - abc-defg-hij - Google Chrome", "Weekly Sync - Google Meet - Brave").
+fn meeting_name(app: &str, title: &str) -> Option<String> {
+    let title = title.trim();
+    let lower = title.to_lowercase();
+    let app_lower = app.to_lowercase();
+
+    if lower.contains("microsoft teams") || app_lower.contains("teams") {
+        let segment = title
+            .split('|')
+            .map(str::trim)
+            .find(|s| !s.is_empty() && !TEAMS_UI_SEGMENTS.contains(&s.to_lowercase().as_str()))?;
+        if let Some(name) = segment.strip_suffix("(Meeting)") {
+            return Some(name.trim().to_string()).filter(|n| !n.is_empty());
+        }
+        let segment_lower = segment.to_lowercase();
+        return MEETING_TITLE_HINTS
+            .iter()
+            .any(|hint| segment_lower.contains(hint))
+            .then(|| segment.to_string());
+    }
+
+    let parts: Vec<&str> = title
+        .split(" - ")
+        .flat_map(|p| p.split(" – "))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    if lower.contains("google meet") || lower.starts_with("meet - ") || lower.starts_with("meet – ") {
+        if parts.first().map(|p| p.eq_ignore_ascii_case("meet")).unwrap_or(false) {
+            let name = parts.get(1)?;
+            return Some(if is_meet_code(name) {
+                format!("Google Meet ({})", name)
+            } else {
+                name.to_string()
+            });
+        }
+        let idx = parts.iter().position(|p| p.eq_ignore_ascii_case("google meet"))?;
+        return idx.checked_sub(1).map(|i| parts[i].to_string());
+    }
+
+    if app_lower.contains("zoom") || lower.contains("zoom meeting") || lower.contains("zoom webinar") {
+        if lower.contains("zoom webinar") {
+            return Some("Zoom webinar".to_string());
+        }
+        if lower.contains("zoom meeting") {
+            return Some("Zoom meeting".to_string());
+        }
+        let idx = parts.iter().position(|p| p.eq_ignore_ascii_case("zoom"))?;
+        return idx.checked_sub(1).map(|i| parts[i].to_string());
+    }
+
+    None
+}
+
+/// "abc-defg-hij", the room code Meet shows when a meeting has no name.
+fn is_meet_code(text: &str) -> bool {
+    let groups: Vec<&str> = text.split('-').collect();
+    groups.len() == 3
+        && groups.iter().all(|g| !g.is_empty() && g.chars().all(|c| c.is_ascii_lowercase()))
+        && groups[0].len() == 3
+        && groups[1].len() == 4
+        && groups[2].len() == 3
+}
+
+/// Participant names from a screenshot of the meeting window: one name per
+/// line, with "(Host)", "(Organizer)" and similar markers removed.
+fn meeting_attendees(ocr: &str) -> Vec<String> {
+    ocr.lines()
+        .map(|line| line.split('(').next().unwrap_or("").trim())
+        .map(normalize_contact_name)
+        .filter(|name| looks_like_human_name(name))
+        .collect()
+}
+
+/// Meetings in `[start_ts, end_ts)`: meeting-titled windows with the same name less than
+/// 10 minutes apart form one meeting, kept when it adds up to at least 10 minutes.
+pub fn detect_meetings(
+    conn: &Connection,
+    start_ts: i64,
+    end_ts: i64,
+    include_private: bool,
+) -> Result<Vec<Meeting>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT app_name, window_title, start_time, duration_seconds, metadata
+             FROM activities
+             WHERE start_time >= ?1 AND start_time < ?2 AND (?3 OR COALESCE(private, 0) = 0)
+             AND (app_name LIKE '%teams%' OR app_name LIKE '%zoom%'
+                  OR window_title LIKE '%teams%' OR window_title LIKE '%zoom%' OR window_title LIKE '%meet%')
+             ORDER BY start_time ASC
+             LIMIT ?4",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![start_ts, end_ts, include_private, MEETING_SCAN_LIMIT],
+            |row| {
+                let app: String = row.get(0)?;
+                let title: String = row.get(1)?;
+                let start_time: i64 = row.get(2)?;
+                let duration: i64 = row.get(3)?;
+                let metadata: Option<Vec<u8>> = row.get(4)?;
+                let ocr = metadata
+                    .as_ref()
+                    .and_then(|blob| serde_json::from_slice::<ActivityMetadata>(blob).ok())
+                    .and_then(|m| m.screen_text)
+                    .unwrap_or_default();
+                Ok((app, title, start_time, duration, ocr))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut meetings: Vec<Meeting> = Vec::new();
+    for (app, title, start_time, duration, ocr) in rows.filter_map(|r| r.ok()) {
+        let Some(name) = meeting_name(&app, &title) else {
+            continue;
+        };
+        let duration = duration.max(0);
+        let event_end = start_time + duration;
+        let meeting = match meetings.iter_mut().rev().find(|m| m.name.eq_ignore_ascii_case(&name)) {
+            Some(m) if start_time - m.end_time <= CONTACT_BLOCK_GAP_SECS => m,
+            _ => {
+                meetings.push(Meeting {
+                    name,
+                    start_time,
+                    end_time: event_end,
+                    ..Meeting::default()
+                });
+                meetings.last_mut().expect("just pushed")
+            }
+        };
+        meeting.end_time = meeting.end_time.max(event_end);
+        meeting.duration_seconds += duration;
+        if !meeting.apps.contains(&app) {
+            meeting.apps.push(app);
+        }
+        for attendee in meeting_attendees(&ocr) {
+            if meeting.attendees.len() >= MEETING_MAX_ATTENDEES {
+                break;
+            }
+            if !attendee.eq_ignore_ascii_case(&meeting.name)
+                && !meeting.attendees.iter().any(|a| a.eq_ignore_ascii_case(&attendee))
+            {
+                meeting.attendees.push(attendee);
+            }
+        }
+    }
+
+    meetings.retain(|m| m.duration_seconds >= MEETING_MIN_SECS);
+    Ok(meetings)
+}
+
+pub fn meetings_report(
+    conn: &Connection,
+    start_ts: i64,
+    end_ts: i64,
+    include_private: bool,
+) -> Result<MeetingsReport, String> {
+    let meetings = detect_meetings(conn, start_ts, end_ts, include_private)?;
+    Ok(MeetingsReport {
+        start_ts,
+        end_ts,
+        total_duration_seconds: meetings.iter().map(|m| m.duration_seconds).sum(),
+        meetings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FOCUS_PRIORITY_LOW;
+
+    fn project_point(text: &str, name: &str, priority: u8) -> FocusPoint {
+        FocusPoint {
+            kind: FocusPointKind::Project,
+            text: text.to_string(),
+            entity_ref: Some(FocusEntityRef::named("project", name)),
+            priority,
+        }
+    }
+
+    fn overview(focus_points: Vec<FocusPoint>) -> DashboardOverview {
+        DashboardOverview {
+            summary: "summary".to_string(),
+            focus_points,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_dedups_focus_points_by_kind_and_entity() {
+        let previous = overview(vec![
+            project_point("Continue intent-flow (3 files changed)", "intent-flow", 2),
+            FocusPoint::info("Reviewed 6 chat turn(s) for recap"),
+            FocusPoint {
+                kind: FocusPointKind::Contact,
+                text: "Follow up with Priya".to_string(),
+                entity_ref: Some(FocusEntityRef::named("contact", "Priya")),
+                priority: 2,
+            },
+        ]);
+        let fresh = overview(vec![
+            project_point("Keep going on Intent-Flow: 7 files today", " INTENT-FLOW ", 2),
+            FocusPoint::info("reviewed 6 chat turn(s) for recap "),
+            FocusPoint {
+                kind: FocusPointKind::Deadline,
+                text: "Report due Friday".to_string(),
+                entity_ref: Some(FocusEntityRef::named("deadline", "Report")),
+                priority: FOCUS_PRIORITY_HIGH,
+            },
+            FocusPoint::info("  "),
+        ]);
+
+        let merged = merge_dashboard_overview(previous, fresh).focus_points;
+        let texts: Vec<&str> = merged.iter().map(|p| p.text.as_str()).collect();
+        // The rewording of the same project and the same info line appear once,
+        // in their fresh wording; high priority comes first.
+        assert_eq!(
+            texts,
+            vec![
+                "Report due Friday",
+                "Keep going on Intent-Flow: 7 files today",
+                "Follow up with Priya",
+                "reviewed 6 chat turn(s) for recap ",
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_keeps_points_about_different_entities_or_kinds() {
+        let previous = overview(vec![project_point("Continue api", "api", 2)]);
+        let fresh = overview(vec![
+            project_point("Continue web", "web", 2),
+            FocusPoint {
+                kind: FocusPointKind::Deadline,
+                ..project_point("api release", "api", 2)
+            },
+        ]);
+        assert_eq!(merge_dashboard_overview(previous, fresh).focus_points.len(), 3);
+
+        // Repeated refreshes never grow the list past the cap.
+        let many: Vec<FocusPoint> = (0..15).map(|i| project_point("p", &format!("p{}", i), 1)).collect();
+        let merged = merge_dashboard_overview(overview(many.clone()), overview(many));
+        assert_eq!(merged.focus_points.len(), 10);
+    }
+
+    #[test]
+    fn legacy_snapshots_with_string_focus_points_still_load() {
+        let json = r#"{"date_key":"2026-01-05","summary":"Busy day","focus_points":["Reviewed 6 chat turn(s) for recap","Continue intent-flow"],
+            "deadlines":[],"projects":[],"contacts":[],"updated_at":1}"#;
+        let overview: DashboardOverview = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            overview.focus_points,
+            vec![
+                FocusPoint::info("Reviewed 6 chat turn(s) for recap"),
+                FocusPoint::info("Continue intent-flow"),
+            ]
+        );
+        assert!(overview.meetings.is_empty());
+
+        // Structured points round-trip.
+        let saved = serde_json::to_string(&DashboardOverview {
+            focus_points: vec![project_point("Continue api", "api", 2)],
+            ..overview
+        })
+        .unwrap();
+        let loaded: DashboardOverview = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.focus_points, vec![project_point("Continue api", "api", 2)]);
+    }
+
+    #[test]
+    fn model_focus_points_parse_leniently() {
+        let output = parse_dashboard_output(
+            r#"Here you go:
+```json
+{"summary":"ok","focus_points":[
+  "plain string",
+  {"kind":"Task","text":"Send invoice","entity_ref":{"type":"deadline","name":"Invoice"},"priority":9},
+  {"kind":"person","text":"Call Sam","entity_ref":{"type":"contact"}},
+  {"kind":"project"}
+]}
+```"#,
+        )
+        .unwrap();
+        let points: Vec<FocusPoint> = output
+            .focus_points
+            .unwrap()
+            .into_iter()
+            .filter_map(|v| serde_json::from_value::<FocusPoint>(v).ok())
+            .collect();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0], FocusPoint::info("plain string"));
+        assert_eq!(points[1].kind, FocusPointKind::Deadline);
+        assert_eq!(points[1].priority, FOCUS_PRIORITY_HIGH);
+        assert_eq!(points[1].entity_ref, Some(FocusEntityRef::named("deadline", "Invoice")));
+        // A reference without an id or name links nowhere and is dropped.
+        assert_eq!(points[2].kind, FocusPointKind::Contact);
+        assert_eq!(points[2].entity_ref, None);
+        assert_eq!(points[2].priority, FOCUS_PRIORITY_LOW);
+    }
+
+    fn french_context() -> TodayContext {
+        TodayContext {
+            top_apps: vec![("Code".to_string(), 5_400)],
+            chat_turns: vec![(
+                "Qu'est-ce que j'ai fait hier sur mon projet ?".to_string(),
+                "Vous avez travaillé sur intent-flow.".to_string(),
+                0,
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dashboard_prompt_carries_the_language_instruction() {
+        let context = french_context();
+        let prompt = dashboard_prompt(&context);
+        assert!(prompt.contains(&i18n::language_instruction(&context_language_sample(&context))));
+        assert!(prompt.contains("in French (français)"));
+        assert!(dashboard_prompt(&TodayContext::default()).contains("in English"));
+    }
+
+    #[test]
+    fn fallback_summary_uses_the_table_language() {
+        let overview = fallback_dashboard_summary(&french_context());
+        assert_eq!(overview.summary, "Aujourd'hui, vous avez surtout travaillé dans Code (90m).");
+        assert_eq!(
+            overview.focus_points,
+            vec![FocusPoint::info("Rien à suivre pour l'instant aujourd'hui")]
+        );
+        assert_eq!(
+            fallback_dashboard_summary(&TodayContext::default()).summary,
+            "No tracked activity yet today."
+        );
+    }
+
+    #[test]
+    fn meeting_names_come_from_teams_zoom_and_meet_titles() {
+        let cases = [
+            ("Microsoft Teams", "Weekly Sync | Microsoft Teams", Some("Weekly Sync")),
+            ("ms-teams.exe", "Design Review (Meeting) | Microsoft Teams", Some("Design Review")),
+            ("Microsoft Teams", "Chat | Priya Sharma | Microsoft Teams", None),
+            ("Microsoft Teams", "Calendar | Microsoft Teams", None),
+            ("Zoom", "Zoom Meeting", Some("Zoom meeting")),
+            ("Zoom Workplace", "Zoom Webinar", Some("Zoom webinar")),
+            ("Zoom", "Sprint Planning - Zoom", Some("Sprint Planning")),
+            ("Google Chrome", "Meet - abc-defg-hij - Google Chrome", Some("Google Meet (abc-defg-hij)")),
+            ("Brave Browser", "Customer Call - Google Meet - Brave", Some("Customer Call")),
+            ("Google Chrome", "Meeting notes - Google Docs - Google Chrome", None),
+            ("Slack", "huddle | general - Slack", None),
+        ];
+        for (app, title, expected) in cases {
+            assert_eq!(meeting_name(app, title).as_deref(), expected, "{} / {}", app, title);
+        }
+        assert!(!is_meet_code("abc-defg-hijk"));
+    }
+
+    #[test]
+    fn attendees_are_read_from_the_participant_list() {
+        let ocr = "Participants (3)\nPriya Sharma (Host)\nTom Becker\nUnmute\n42\nJosé García (Guest)";
+        assert_eq!(meeting_attendees(ocr), vec!["Priya Sharma", "Tom Becker", "José García"]);
+    }
+
+    fn window(conn: &Connection, app: &str, title: &str, start: i64, secs: i64, ocr: Option<&str>) {
+        let metadata = ocr.map(|text| {
+            serde_json::to_vec(&ActivityMetadata {
+                screen_text: Some(text.to_string()),
+                ..Default::default()
+            })
+            .unwrap()
+        });
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+             VALUES (?1, 1, ?2, 3, ?3, ?3 + ?4, ?4, ?5)",
+            rusqlite::params![app, title, start, secs, metadata],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn meeting_windows_cluster_into_meetings() {
+        let conn = crate::database::test_connection();
+        let t0 = 1_700_000_000;
+        let teams = "Weekly Sync | Microsoft Teams";
+        window(&conn, "Microsoft Teams", teams, t0, 900, Some("Priya Sharma (Host)\nTom Becker"));
+        // Back after a detour shorter than the gap: same meeting.
+        window(&conn, "Code", "main.rs - intent-flow", t0 + 900, 120, None);
+        window(&conn, "Microsoft Teams", teams, t0 + 1_020, 600, Some("Tom Becker\nAnna Schmidt"));
+        // Too short to count.
+        window(&conn, "Zoom", "Zoom Meeting", t0 + 3_000, 300, None);
+        window(&conn, "Google Chrome", "Customer Call - Google Meet - Google Chrome", t0 + 4_000, 1_200, None);
+        // Same name much later is a separate meeting.
+        window(&conn, "Microsoft Teams", teams, t0 + 20_000, 700, None);
+
+        let report = meetings_report(&conn, t0, t0 + 86_400, false).unwrap();
+        let summary: Vec<(&str, i64, i64)> = report
+            .meetings
+            .iter()
+            .map(|m| (m.name.as_str(), m.start_time, m.duration_seconds))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("Weekly Sync", t0, 1_500), ("Customer Call", t0 + 4_000, 1_200), ("Weekly Sync", t0 + 20_000, 700)]
+        );
+        assert_eq!(report.meetings[0].end_time, t0 + 1_620);
+        assert_eq!(report.meetings[0].attendees, vec!["Priya Sharma", "Tom Becker", "Anna Schmidt"]);
+        assert_eq!(report.meetings[1].apps, vec!["Google Chrome"]);
+        assert_eq!(report.total_duration_seconds, 3_400);
+
+        // The dashboard prompt gets the clustered meetings, not the raw windows.
+        let context = TodayContext {
+            meetings: report.meetings.clone(),
+            ..Default::default()
+        };
+        let prompt = dashboard_prompt(&context);
+        assert!(prompt.contains("(\"Weekly Sync\", "), "{}", prompt);
+        assert!(prompt.contains("25, \"Priya Sharma, Tom Becker, Anna Schmidt\")"));
+    }
 }
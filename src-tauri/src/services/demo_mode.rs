@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::database;
+
+// ─── Demo mode ───
+// Serves a generated database of fictional activity for screenshots and
+// recordings. Tracking, capture and file monitoring stay off while it is
+// active so nothing real leaks in, and the real database is never touched;
+// turning it off switches back without a restart.
+
+/// Days of history generated each time demo mode is switched on.
+const DEMO_DAYS: i64 = 7;
+
+/// Set by `--demo`; keeps demo mode on for this run whatever the settings say.
+static FORCED_BY_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Read `--demo` from the command line. Returns whether it was passed.
+pub fn init_from_args() -> bool {
+    let forced = std::env::args().any(|arg| arg == "--demo");
+    FORCED_BY_FLAG.store(forced, Ordering::Relaxed);
+    forced
+}
+
+pub fn is_active() -> bool {
+    database::is_demo_mode()
+}
+
+/// Switch to the demo database (regenerated so it looks current) or back to the real one.
+pub fn apply(app_handle: &AppHandle, requested: bool) -> Result<(), String> {
+    let enabled = requested || FORCED_BY_FLAG.load(Ordering::Relaxed);
+    if enabled == database::is_demo_mode() {
+        return Ok(());
+    }
+
+    if enabled {
+        prepare_demo_database(app_handle)?;
+    }
+    // Sessions still queued for writing belong to the database being left.
+    crate::services::activity_tracker::flush_pending();
+    // Tracker and checkpoint writes check the flag themselves, so a session
+    // finalized during the switch is dropped rather than stored in the wrong file.
+    database::set_demo_mode(enabled);
+    crate::apply_monitoring_state(app_handle);
+
+    // In-memory caches were loaded from the other database.
+    let conn = database::open(app_handle)?;
+    crate::utils::categories::reload(&conn);
+    crate::services::app_alerts::reload(&conn);
+
+    update_tray(app_handle);
+    let _ = app_handle.emit("demo://changed", enabled);
+    println!("[Demo] Demo mode {}", if enabled { "on" } else { "off" });
+    Ok(())
+}
+
+fn prepare_demo_database(app_handle: &AppHandle) -> Result<(), String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let conn = database::init_database(&data_dir.join(database::DEMO_DB_FILE)).map_err(|e| e.to_string())?;
+    let summary = database::sample_data::generate_demo_data(&conn, DEMO_DAYS).map_err(|e| e.to_string())?;
+    println!(
+        "[Demo] Generated {} activities and {} file events over {} days",
+        summary.activities, summary.file_events, DEMO_DAYS
+    );
+    Ok(())
+}
+
+/// Mark the tray icon so it's obvious the app is showing demo data.
+pub fn update_tray(app_handle: &AppHandle) {
+    let Some(tray) = app_handle.tray_by_id(crate::TRAY_ID) else {
+        return;
+    };
+    if is_active() {
+        let _ = tray.set_tooltip(Some("IntentFlow — DEMO"));
+        let _ = tray.set_title(Some("DEMO"));
+    } else {
+        let _ = tray.set_tooltip(Some("IntentFlow"));
+        let _ = tray.set_title(None::<&str>);
+    }
+}
@@ -1,35 +1,75 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use walkdir::WalkDir;
 
+use crate::services::service_status::{self, FILE_MONITOR};
+
 const SCAN_INTERVAL_SECS: u64 = 2;
 const MAX_SCAN_DEPTH: usize = 8;
 const RECENT_CREATE_WINDOW_MS: i64 = 180_000;
 const MAX_SNAPSHOT_CHARS: usize = 4000;
 const MAX_PREVIEW_CHARS: usize = 500;
 
+fn configured_roots_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+pub fn configure_roots(roots: &[String]) {
+    if let Ok(mut store) = configured_roots_store().lock() {
+        *store = roots
+            .iter()
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty())
+            .collect();
+    }
+}
+
+fn configured_roots() -> Vec<String> {
+    configured_roots_store().lock().map(|r| r.clone()).unwrap_or_default()
+}
+
 pub fn start_file_monitor(app_handle: AppHandle) {
+    crate::services::settings_bus::spawn_subscriber(&app_handle, "file_monitor", |settings| {
+        configure_roots(&settings.tracking.code_roots);
+    });
     tauri::async_runtime::spawn(async move {
-        let roots = discover_roots();
-        if roots.is_empty() {
-            println!("[FileMonitor] No valid roots found. Set INTENTFLOW_CODE_ROOTS to enable monitoring.");
-            return;
-        }
         println!("[FileMonitor] Active monitoring started (interval={}s, depth={})", SCAN_INTERVAL_SECS, MAX_SCAN_DEPTH);
-        for root in &roots {
-            println!("[FileMonitor] Watching root: {}", root.to_string_lossy());
-        }
+        service_status::mark_started(FILE_MONITOR);
 
         let mut known_mtimes: HashMap<String, i64> = HashMap::new();
         let mut known_dirs: HashSet<String> = HashSet::new();
         let mut known_content: HashMap<String, String> = HashMap::new();
         let mut initialized_roots: HashSet<String> = HashSet::new();
+        let mut active_config: Option<Vec<String>> = None;
+        let mut roots: Vec<PathBuf> = Vec::new();
 
         loop {
-            for root in &roots {
+            // Roots are re-resolved whenever `tracking.code_roots` changes. A newly
+            // added root gets a silent baseline scan like at startup.
+            let config = configured_roots();
+            if active_config.as_ref() != Some(&config) {
+                roots = discover_roots(&config);
+                if roots.is_empty() {
+                    println!("[FileMonitor] No valid roots found. Add code folders in settings or set INTENTFLOW_CODE_ROOTS.");
+                    service_status::set_enabled(FILE_MONITOR, false);
+                    service_status::report_error(FILE_MONITOR, "No valid roots found (add code folders in settings)");
+                } else {
+                    service_status::set_enabled(FILE_MONITOR, true);
+                    for root in &roots {
+                        println!("[FileMonitor] Watching root: {}", root.to_string_lossy());
+                    }
+                }
+                active_config = Some(config);
+            }
+
+            // Skipped in demo mode; changes made meanwhile are picked up as diffs afterwards.
+            let roots_to_scan = if crate::database::is_demo_mode() { &[][..] } else { &roots[..] };
+            for root in roots_to_scan {
                 scan_root(
                     &app_handle,
                     root,
@@ -39,19 +79,23 @@ pub fn start_file_monitor(app_handle: AppHandle) {
                     &mut initialized_roots,
                 );
             }
+            service_status::heartbeat(FILE_MONITOR);
             tokio::time::sleep(Duration::from_secs(SCAN_INTERVAL_SECS)).await;
         }
     });
 }
 
-fn discover_roots() -> Vec<PathBuf> {
-    let mut roots: Vec<PathBuf> = Vec::new();
+/// Roots from settings, else `INTENTFLOW_CODE_ROOTS`, else the usual code folders.
+fn discover_roots(configured: &[String]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = configured.iter().map(PathBuf::from).collect();
 
-    if let Ok(raw) = std::env::var("INTENTFLOW_CODE_ROOTS") {
-        for part in raw.split(';').flat_map(|p| p.split(',')) {
-            let trimmed = part.trim();
-            if !trimmed.is_empty() {
-                roots.push(PathBuf::from(trimmed));
+    if roots.is_empty() {
+        if let Ok(raw) = std::env::var("INTENTFLOW_CODE_ROOTS") {
+            for part in raw.split(';').flat_map(|p| p.split(',')) {
+                let trimmed = part.trim();
+                if !trimmed.is_empty() {
+                    roots.push(PathBuf::from(trimmed));
+                }
             }
         }
     }
@@ -217,9 +261,7 @@ fn insert_event(
     content_preview: Option<&str>,
     detected_at: i64,
 ) -> Result<(), String> {
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = crate::database::open(app_handle)?;
     conn.execute(
         "INSERT INTO code_file_events (path, project_root, entity_type, change_type, content_preview, detected_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use rusqlite::Connection;
+use tauri::AppHandle;
+
+use crate::models::{CategoryGoal, GoalDay, GoalDayStatus, GoalKind, GoalStreak, NoDataPolicy};
+
+/// Days shown in each goal's calendar, today included.
+pub const CALENDAR_DAYS: i64 = 60;
+
+/// Streaks shorter than this aren't worth a notification or a dashboard mention.
+const MIN_NOTEWORTHY_STREAK: i64 = 2;
+
+pub fn load_goals(conn: &Connection) -> rusqlite::Result<Vec<CategoryGoal>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, category_id, kind, target_seconds, no_data_policy, enabled, best_streak, created_at
+         FROM category_goals ORDER BY id",
+    )?;
+    let goals = stmt
+        .query_map([], |row| {
+            let kind: String = row.get(2)?;
+            let policy: String = row.get(4)?;
+            Ok(CategoryGoal {
+                id: row.get(0)?,
+                category_id: row.get(1)?,
+                kind: GoalKind::from_str(&kind).unwrap_or(GoalKind::AtLeast),
+                target_seconds: row.get(3)?,
+                no_data_policy: NoDataPolicy::from_str(&policy),
+                enabled: row.get::<_, i64>(5)? != 0,
+                best_streak: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(goals)
+}
+
+/// Per-category seconds for one local day, or `None` when nothing was tracked at all.
+/// Uses the hourly rollups when the day has them, raw activities otherwise.
+fn day_totals(conn: &Connection, date: NaiveDate) -> rusqlite::Result<Option<HashMap<i64, i64>>> {
+    let bounds = crate::utils::time::day_bounds(Some(conn), date);
+    let query = |sql: &str| -> rusqlite::Result<HashMap<i64, i64>> {
+        let mut stmt = conn.prepare_cached(sql)?;
+        let rows = stmt.query_map([bounds.start_ts, bounds.end_ts], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        rows.collect()
+    };
+
+    let rollup = query(
+        "SELECT category_id, SUM(total_duration) FROM activity_summaries
+         WHERE date >= ?1 AND date < ?2 GROUP BY category_id",
+    )?;
+    if !rollup.is_empty() {
+        return Ok(Some(rollup));
+    }
+    let raw = query(
+        "SELECT category_id, SUM(duration_seconds) FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2 GROUP BY category_id",
+    )?;
+    Ok(if raw.is_empty() { None } else { Some(raw) })
+}
+
+fn day_status(goal: &CategoryGoal, seconds: Option<i64>, is_today: bool) -> GoalDayStatus {
+    let Some(seconds) = seconds else {
+        // Today simply hasn't had any tracking yet.
+        return if is_today { GoalDayStatus::Pending } else { GoalDayStatus::NoData };
+    };
+    match goal.kind {
+        GoalKind::AtLeast if seconds >= goal.target_seconds => GoalDayStatus::Met,
+        GoalKind::AtLeast if is_today => GoalDayStatus::Pending,
+        GoalKind::AtLeast => GoalDayStatus::Missed,
+        GoalKind::AtMost if seconds <= goal.target_seconds => GoalDayStatus::Met,
+        GoalKind::AtMost => GoalDayStatus::Missed,
+    }
+}
+
+/// Current and longest run of met days in `days` (oldest first). Pending days
+/// are skipped; no-data days are skipped or end the run depending on `policy`.
+pub fn streak_lengths(days: &[GoalDayStatus], policy: NoDataPolicy) -> (i64, i64) {
+    let mut run = 0;
+    let mut best = 0;
+    for status in days {
+        match status {
+            GoalDayStatus::Met => {
+                run += 1;
+                best = best.max(run);
+            }
+            GoalDayStatus::Missed => run = 0,
+            GoalDayStatus::NoData if policy == NoDataPolicy::Break => run = 0,
+            GoalDayStatus::NoData | GoalDayStatus::Pending => {}
+        }
+    }
+    (run, best)
+}
+
+/// Streaks of every enabled goal over the last `CALENDAR_DAYS` days.
+pub fn compute_streaks(conn: &Connection) -> Result<Vec<GoalStreak>, String> {
+    let goals: Vec<CategoryGoal> = load_goals(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|g| g.enabled)
+        .collect();
+    if goals.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let today = crate::utils::time::today();
+    let mut days = Vec::with_capacity(CALENDAR_DAYS as usize);
+    for back in (0..CALENDAR_DAYS).rev() {
+        let date = today - Duration::days(back);
+        days.push((date, day_totals(conn, date).map_err(|e| e.to_string())?));
+    }
+
+    Ok(goals
+        .into_iter()
+        .map(|goal| {
+            let calendar: Vec<GoalDay> = days
+                .iter()
+                .map(|(date, totals)| {
+                    let seconds = totals
+                        .as_ref()
+                        .map(|t| t.get(&goal.category_id).copied().unwrap_or(0));
+                    GoalDay {
+                        date_key: date.format("%Y-%m-%d").to_string(),
+                        seconds: seconds.unwrap_or(0),
+                        status: day_status(&goal, seconds, *date == today),
+                    }
+                })
+                .collect();
+            let statuses: Vec<GoalDayStatus> = calendar.iter().map(|d| d.status).collect();
+            let (current_streak, window_best) = streak_lengths(&statuses, goal.no_data_policy);
+            GoalStreak {
+                category_name: crate::utils::categories::name_for(goal.category_id),
+                current_streak,
+                best_streak: window_best.max(goal.best_streak),
+                goal,
+                calendar,
+            }
+        })
+        .collect())
+}
+
+/// Compute streaks, persist new records and celebrate beaten ones. Each goal
+/// notifies at most once per day, however often this runs.
+pub fn refresh(app_handle: &AppHandle, conn: &Connection) -> Result<Vec<GoalStreak>, String> {
+    let streaks = compute_streaks(conn)?;
+    let today_key = crate::utils::time::today().format("%Y-%m-%d").to_string();
+
+    for streak in &streaks {
+        let previous_best = streak.goal.best_streak;
+        if streak.best_streak <= previous_best {
+            continue;
+        }
+        // The first run after creating a goal only establishes the record.
+        let celebrate = previous_best > 0
+            && streak.current_streak == streak.best_streak
+            && streak.current_streak >= MIN_NOTEWORTHY_STREAK;
+        let notified_today: bool = conn
+            .query_row(
+                "SELECT COALESCE(best_notified_on = ?2, 0) FROM category_goals WHERE id = ?1",
+                rusqlite::params![streak.goal.id, today_key],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+
+        if celebrate && !notified_today {
+            conn.execute(
+                "UPDATE category_goals SET best_streak = ?2, best_notified_on = ?3 WHERE id = ?1",
+                rusqlite::params![streak.goal.id, streak.best_streak, today_key],
+            )
+            .map_err(|e| e.to_string())?;
+            crate::services::notifier::notify(
+                app_handle,
+                "goal_streak",
+                "New best streak",
+                &format!(
+                    "{} days in a row with {} (previous best {}).",
+                    streak.current_streak,
+                    describe_goal(&streak.goal, &streak.category_name),
+                    previous_best
+                ),
+            );
+        } else {
+            conn.execute(
+                "UPDATE category_goals SET best_streak = ?2 WHERE id = ?1",
+                rusqlite::params![streak.goal.id, streak.best_streak],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(streaks)
+}
+
+/// Goals currently on a run worth mentioning, as (description, days), longest first.
+pub fn active_streaks(conn: &Connection) -> Vec<(String, i64)> {
+    let mut active: Vec<(String, i64)> = compute_streaks(conn)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.current_streak >= MIN_NOTEWORTHY_STREAK)
+        .map(|s| (describe_goal(&s.goal, &s.category_name), s.current_streak))
+        .collect();
+    active.sort_by(|a, b| b.1.cmp(&a.1));
+    active
+}
+
+/// "≥2h Development", "≤1h Entertainment".
+pub fn describe_goal(goal: &CategoryGoal, category_name: &str) -> String {
+    let sign = match goal.kind {
+        GoalKind::AtLeast => "≥",
+        GoalKind::AtMost => "≤",
+    };
+    format!("{}{} {}", sign, format_target(goal.target_seconds), category_name)
+}
+
+fn format_target(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GoalDayStatus::{Met, Missed, NoData, Pending};
+
+    #[test]
+    fn streaks_count_met_days_and_reset_on_misses() {
+        let days = [Met, Met, Met, Missed, Met, Met];
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Break), (2, 3));
+        assert_eq!(streak_lengths(&[], NoDataPolicy::Break), (0, 0));
+        assert_eq!(streak_lengths(&[Met, Missed], NoDataPolicy::Preserve), (0, 1));
+    }
+
+    #[test]
+    fn no_data_gaps_follow_the_goal_policy() {
+        // A weekend with the machine off in the middle of a run.
+        let days = [Met, Met, NoData, NoData, Met, Met, Met];
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Preserve), (5, 5));
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Break), (3, 3));
+
+        // Trailing gaps: preserved runs stay current, broken ones end.
+        let days = [Met, Met, NoData];
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Preserve), (2, 2));
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Break), (0, 2));
+
+        // A gap never extends a run by itself.
+        assert_eq!(streak_lengths(&[NoData, NoData], NoDataPolicy::Preserve), (0, 0));
+        // Misses still break a preserved run on either side of a gap.
+        let days = [Met, NoData, Missed, Met];
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Preserve), (1, 1));
+    }
+
+    #[test]
+    fn pending_today_keeps_the_run_under_both_policies() {
+        let days = [Met, NoData, Met, Pending];
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Preserve), (2, 2));
+        assert_eq!(streak_lengths(&days, NoDataPolicy::Break), (1, 1));
+    }
+
+    fn goal(kind: GoalKind, policy: NoDataPolicy) -> CategoryGoal {
+        CategoryGoal {
+            id: 1,
+            category_id: 1,
+            kind,
+            target_seconds: 3600,
+            no_data_policy: policy,
+            enabled: true,
+            best_streak: 0,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn day_status_separates_untracked_days_from_zero_usage() {
+        let at_least = goal(GoalKind::AtLeast, NoDataPolicy::Break);
+        assert_eq!(day_status(&at_least, None, false), NoData);
+        assert_eq!(day_status(&at_least, None, true), Pending);
+        assert_eq!(day_status(&at_least, Some(0), false), Missed);
+        assert_eq!(day_status(&at_least, Some(1800), true), Pending);
+        assert_eq!(day_status(&at_least, Some(3600), false), Met);
+
+        // Tracked time without the category is a met "at most" day, not a gap.
+        let at_most = goal(GoalKind::AtMost, NoDataPolicy::Break);
+        assert_eq!(day_status(&at_most, Some(0), false), Met);
+        assert_eq!(day_status(&at_most, Some(3601), true), Missed);
+        assert_eq!(day_status(&at_most, None, false), NoData);
+    }
+
+    #[test]
+    fn computed_streaks_span_untracked_days_per_policy() {
+        let conn = crate::database::test_connection();
+        for policy in ["preserve", "break"] {
+            conn.execute(
+                "INSERT INTO category_goals (category_id, kind, target_seconds, no_data_policy, created_at)
+                 VALUES (1, 'at_least', 3600, ?1, 0)",
+                [policy],
+            )
+            .unwrap();
+        }
+        // Two hours on each of the last five days except the day before yesterday.
+        let today = crate::utils::time::today();
+        for back in [5, 4, 3, 1] {
+            let bounds = crate::utils::time::day_bounds(None, today - Duration::days(back));
+            let start = bounds.start_ts + 3600;
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+                 VALUES ('Code', 1, 'main.rs', 1, ?1, ?2, 7200)",
+                [start, start + 7200],
+            )
+            .unwrap();
+        }
+
+        let streaks = compute_streaks(&conn).unwrap();
+        let preserve = &streaks[0];
+        assert_eq!(preserve.goal.no_data_policy, NoDataPolicy::Preserve);
+        assert_eq!((preserve.current_streak, preserve.best_streak), (4, 4));
+        let broken = &streaks[1];
+        assert_eq!((broken.current_streak, broken.best_streak), (1, 3));
+
+        let calendar: Vec<GoalDayStatus> =
+            preserve.calendar.iter().rev().take(4).map(|d| d.status).collect();
+        assert_eq!(calendar, vec![Pending, Met, NoData, Met]);
+        assert_eq!(preserve.calendar.len(), CALENDAR_DAYS as usize);
+    }
+}
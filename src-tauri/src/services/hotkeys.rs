@@ -0,0 +1,225 @@
+use std::sync::{Mutex, OnceLock};
+
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::models::HotkeySettings;
+use crate::services::service_status::{self, GLOBAL_HOTKEYS};
+
+// ─── Global hotkeys ───
+// Bindings come from `settings.general.hotkeys` and are re-applied on every
+// settings update: changed combos are unregistered before the new ones are
+// registered. A combo another app already holds is reported in the service
+// status instead of failing startup.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    ToggleIncognito,
+    QuickChat,
+}
+
+impl HotkeyAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleIncognito => "toggle_incognito",
+            HotkeyAction::QuickChat => "quick_chat",
+        }
+    }
+}
+
+fn bindings_store() -> &'static Mutex<Vec<(Shortcut, HotkeyAction)>> {
+    static STORE: OnceLock<Mutex<Vec<(Shortcut, HotkeyAction)>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Parse "Ctrl+Alt+Space", "Win+Shift+I" and similar. Modifier names are
+/// case-insensitive and accept the usual aliases; at least one modifier and
+/// exactly one key are required. An empty string means unbound (`Ok(None)`).
+pub fn parse_hotkey(raw: &str) -> Result<Option<Shortcut>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+
+    let mut modifiers: Vec<&str> = Vec::new();
+    let mut key: Option<&str> = None;
+    for part in raw.split('+').map(|p| p.trim()) {
+        if part.is_empty() {
+            return Err(format!("invalid hotkey \"{}\": empty key name", raw));
+        }
+        let modifier = match part.to_lowercase().as_str() {
+            "ctrl" | "control" => Some("Control"),
+            "alt" | "option" => Some("Alt"),
+            "shift" => Some("Shift"),
+            "win" | "windows" | "super" | "meta" | "cmd" | "command" => Some("Super"),
+            _ => None,
+        };
+        match modifier {
+            Some(m) if !modifiers.contains(&m) => modifiers.push(m),
+            Some(_) => return Err(format!("invalid hotkey \"{}\": repeated modifier", raw)),
+            None if key.is_none() => key = Some(part),
+            None => return Err(format!("invalid hotkey \"{}\": more than one key", raw)),
+        }
+    }
+    let Some(key) = key else {
+        return Err(format!("invalid hotkey \"{}\": missing a key", raw));
+    };
+    if modifiers.is_empty() {
+        return Err(format!("invalid hotkey \"{}\": needs at least one modifier", raw));
+    }
+
+    let normalized = format!("{}+{}", modifiers.join("+"), key);
+    normalized
+        .parse::<Shortcut>()
+        .map(Some)
+        .map_err(|e| format!("invalid hotkey \"{}\": {}", raw, e))
+}
+
+/// Apply `settings.general.hotkeys` now and on every settings update.
+pub fn start(app_handle: &AppHandle) {
+    service_status::mark_started(GLOBAL_HOTKEYS);
+    let app = app_handle.clone();
+    crate::services::settings_bus::spawn_subscriber(app_handle, "hotkeys", move |settings| {
+        apply(&app, &settings.general.hotkeys);
+    });
+}
+
+/// Register the bindings from settings, replacing whatever was registered before.
+fn apply(app_handle: &AppHandle, settings: &HotkeySettings) {
+    let wanted = [
+        (HotkeyAction::ToggleIncognito, settings.toggle_incognito.as_str()),
+        (HotkeyAction::QuickChat, settings.quick_chat.as_str()),
+    ];
+
+    // The store lock is not held while talking to the plugin; `handle` takes
+    // it from the event loop.
+    let previous = bindings_store()
+        .lock()
+        .map(|mut bindings| std::mem::take(&mut *bindings))
+        .unwrap_or_default();
+    let shortcuts = app_handle.global_shortcut();
+    for (shortcut, action) in previous {
+        if let Err(e) = shortcuts.unregister(shortcut) {
+            println!("[Hotkeys] Failed to unregister {}: {}", action.as_str(), e);
+        }
+    }
+
+    let mut bindings: Vec<(Shortcut, HotkeyAction)> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for (action, raw) in wanted {
+        let shortcut = match parse_hotkey(raw) {
+            Ok(Some(shortcut)) => shortcut,
+            Ok(None) => continue,
+            Err(e) => {
+                errors.push(format!("{}: {}", action.as_str(), e));
+                continue;
+            }
+        };
+        if bindings.iter().any(|(bound, _)| *bound == shortcut) {
+            errors.push(format!("{}: \"{}\" is already bound to another action", action.as_str(), raw));
+            continue;
+        }
+        match shortcuts.register(shortcut) {
+            Ok(()) => {
+                println!("[Hotkeys] {} bound to {}", action.as_str(), raw);
+                bindings.push((shortcut, action));
+            }
+            // Usually another application already owns the combo.
+            Err(e) => errors.push(format!("{}: could not register \"{}\": {}", action.as_str(), raw, e)),
+        }
+    }
+
+    service_status::set_enabled(GLOBAL_HOTKEYS, !bindings.is_empty());
+    service_status::heartbeat(GLOBAL_HOTKEYS);
+    if !errors.is_empty() {
+        let message = errors.join("; ");
+        println!("[Hotkeys] {}", message);
+        service_status::report_error(GLOBAL_HOTKEYS, message);
+    }
+    if let Ok(mut store) = bindings_store().lock() {
+        *store = bindings;
+    }
+}
+
+/// Handler passed to the global-shortcut plugin.
+pub fn handle(app_handle: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = bindings_store()
+        .lock()
+        .ok()
+        .and_then(|bindings| bindings.iter().find(|(bound, _)| bound == shortcut).map(|(_, a)| *a));
+    match action {
+        Some(HotkeyAction::ToggleIncognito) => {
+            let enabled = crate::toggle_incognito(app_handle);
+            println!("[Hotkeys] Incognito {}", if enabled { "on" } else { "off" });
+        }
+        Some(HotkeyAction::QuickChat) => {
+            crate::show_window_and_navigate(app_handle, "chat");
+            let _ = app_handle.emit("chat://focus-input", ());
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri_plugin_global_shortcut::{Code, Modifiers};
+
+    fn shortcut(raw: &str) -> Shortcut {
+        parse_hotkey(raw).unwrap().unwrap()
+    }
+
+    #[test]
+    fn modifier_aliases_and_case_are_normalized() {
+        let expected = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::Space);
+        assert_eq!(shortcut("Win+Shift+Space"), expected);
+        assert_eq!(shortcut(" super + SHIFT + space "), expected);
+        assert_eq!(shortcut("Shift+Cmd+Space"), expected);
+        assert_eq!(
+            shortcut("control+option+i"),
+            Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyI)
+        );
+    }
+
+    #[test]
+    fn empty_binding_means_unbound() {
+        assert_eq!(parse_hotkey(""), Ok(None));
+        assert_eq!(parse_hotkey("   "), Ok(None));
+    }
+
+    #[test]
+    fn malformed_bindings_are_rejected_with_a_reason() {
+        let reason = |raw: &str| parse_hotkey(raw).unwrap_err();
+        assert!(reason("Space").contains("needs at least one modifier"));
+        assert!(reason("Ctrl+Alt").contains("missing a key"));
+        assert!(reason("Ctrl+A+B").contains("more than one key"));
+        assert!(reason("Ctrl+ctrl+A").contains("repeated modifier"));
+        assert!(reason("Ctrl++A").contains("empty key name"));
+        assert!(reason("Ctrl+Alt+").contains("empty key name"));
+        assert!(reason("Ctrl+NotAKey").starts_with("invalid hotkey \"Ctrl+NotAKey\""));
+    }
+
+    #[test]
+    fn default_bindings_are_valid_and_distinct() {
+        let defaults = HotkeySettings::default();
+        let incognito = shortcut(&defaults.toggle_incognito);
+        let quick_chat = shortcut(&defaults.quick_chat);
+        assert_ne!(incognito, quick_chat);
+    }
+
+    #[test]
+    fn partial_hotkey_settings_fill_in_defaults() {
+        let (settings, warnings) =
+            crate::utils::config::parse_settings(r#"{"general": {"hotkeys": {"quick_chat": ""}}}"#);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(settings.general.hotkeys.quick_chat, "");
+        assert_eq!(settings.general.hotkeys.toggle_incognito, HotkeySettings::default().toggle_incognito);
+
+        let (settings, warnings) = crate::utils::config::parse_settings(r#"{"general": {"hotkeys": {"quick_chat": 5}}}"#);
+        assert_eq!(warnings, vec!["Invalid value for general.hotkeys.quick_chat, using the default".to_string()]);
+        assert_eq!(settings.general.hotkeys, HotkeySettings::default());
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::Connection;
+
+use crate::models::{MediaDelta, NotificationRecord, ProjectDelta, SinceLastSeen};
+
+// ─── Since last seen ───
+// When the user last looked at each surface, kept in the `settings` table like
+// the current focus. The delta only reads the gap since then, never more than
+// `MAX_GAP_SECS`, so it stays a narrow indexed scan however large the database is.
+
+pub const SURFACES: [&str; 2] = ["dashboard", "timeline"];
+const MAX_GAP_SECS: i64 = 48 * 3600;
+const TOP_APPS: usize = 5;
+const MAX_PROJECTS: usize = 8;
+const MAX_MEDIA: usize = 8;
+const MAX_NOTIFICATIONS: usize = 20;
+
+fn key(surface: &str) -> String {
+    format!("last_seen:{}", surface)
+}
+
+pub fn validate_surface(surface: &str) -> Result<&'static str, String> {
+    let surface = surface.trim().to_lowercase();
+    SURFACES
+        .iter()
+        .copied()
+        .find(|s| *s == surface)
+        .ok_or_else(|| format!("Unknown surface '{}' (expected one of: {})", surface, SURFACES.join(", ")))
+}
+
+pub fn get(conn: &Connection, surface: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT CAST(value AS TEXT) FROM settings WHERE key = ?1",
+        [key(surface)],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.trim().parse().ok())
+}
+
+pub fn mark(conn: &Connection, surface: &str, at: i64) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        rusqlite::params![key(surface), at.to_string(), at],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// The window `[start, now)` to report: from the last visit, capped at `MAX_GAP_SECS`;
+/// from the start of today on a first visit. The second value is `first_visit`.
+pub fn gap_window(conn: &Connection, last_seen: Option<i64>, now: i64) -> (i64, bool) {
+    let earliest = now - MAX_GAP_SECS;
+    match last_seen {
+        Some(ts) => (ts.clamp(earliest, now), false),
+        None => {
+            let today = crate::utils::time::day_bounds(Some(conn), crate::utils::time::today());
+            (today.start_ts.clamp(earliest, now), true)
+        }
+    }
+}
+
+pub fn compute(conn: &Connection, surface: &str, now: i64) -> Result<SinceLastSeen, String> {
+    let (gap_start, first_visit) = gap_window(conn, get(conn, surface), now);
+    let stats = crate::database::queries::get_activity_stats(conn, gap_start, now).map_err(|e| e.to_string())?;
+
+    Ok(SinceLastSeen {
+        surface: surface.to_string(),
+        first_visit,
+        gap_start,
+        gap_end: now,
+        new_activities: stats.total_events,
+        tracked_seconds: stats.total_duration,
+        top_apps: stats.top_apps.into_iter().take(TOP_APPS).collect(),
+        projects: project_deltas(conn, gap_start, now)?,
+        media: media_deltas(conn, gap_start, now)?,
+        notifications: notifications(conn, gap_start, now)?,
+    })
+}
+
+fn project_deltas(conn: &Connection, start: i64, end: i64) -> Result<Vec<ProjectDelta>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, project_root, COALESCE(content_preview, '')
+             FROM code_file_events
+             WHERE detected_at >= ?1 AND detected_at < ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([start, end], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut by_root: HashMap<String, (i32, HashSet<String>)> = HashMap::new();
+    for (path, root, preview) in rows.filter_map(|r| r.ok()) {
+        if crate::services::dashboard_engine::is_noise_file_change(&path, &root, &preview) {
+            continue;
+        }
+        let entry = by_root.entry(root).or_default();
+        entry.0 += 1;
+        entry.1.insert(path);
+    }
+
+    let mut projects: Vec<ProjectDelta> = by_root
+        .into_iter()
+        .map(|(root, (changes, files))| ProjectDelta {
+            name: root
+                .trim_end_matches(['/', '\\'])
+                .rsplit(['/', '\\'])
+                .next()
+                .unwrap_or(&root)
+                .to_string(),
+            project_root: root,
+            changes,
+            files: files.len() as i32,
+        })
+        .collect();
+    projects.sort_by(|a, b| b.changes.cmp(&a.changes).then_with(|| a.name.cmp(&b.name)));
+    projects.truncate(MAX_PROJECTS);
+    Ok(projects)
+}
+
+fn media_deltas(conn: &Connection, start: i64, end: i64) -> Result<Vec<MediaDelta>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT json_extract(CAST(metadata AS TEXT), '$.media_info.title'),
+                    COALESCE(json_extract(CAST(metadata AS TEXT), '$.media_info.artist'), ''),
+                    COUNT(*), SUM(duration_seconds)
+             FROM activities
+             WHERE start_time >= ?1 AND start_time < ?2
+               AND metadata IS NOT NULL AND json_valid(CAST(metadata AS TEXT))
+               AND json_extract(CAST(metadata AS TEXT), '$.media_info.status') = 'Playing'
+             GROUP BY 1, 2
+             ORDER BY SUM(duration_seconds) DESC
+             LIMIT ?3",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![start, end, MAX_MEDIA as i64], |row| {
+            Ok(MediaDelta {
+                title: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                artist: row.get(1)?,
+                sessions: row.get(2)?,
+                seconds: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).filter(|m| !m.title.trim().is_empty()).collect())
+}
+
+fn notifications(conn: &Connection, start: i64, end: i64) -> Result<Vec<NotificationRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, title, body, created_at FROM notification_log
+             WHERE created_at >= ?1 AND created_at < ?2
+             ORDER BY created_at DESC
+             LIMIT ?3",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![start, end, MAX_NOTIFICATIONS as i64], |row| {
+            Ok(NotificationRecord {
+                kind: row.get(0)?,
+                title: row.get(1)?,
+                body: row.get(2)?,
+                created_at: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+
+
+    fn activity(conn: &Connection, app: &str, start: i64, secs: i64, metadata: Option<&str>) {
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+             VALUES (?1, 1, 'w', 7, ?2, ?2 + ?3, ?3, CAST(?4 AS BLOB))",
+            rusqlite::params![app, start, secs, metadata],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn first_visit_starts_at_the_beginning_of_today() {
+        let conn = test_connection();
+        assert_eq!(get(&conn, "dashboard"), None);
+        let now = chrono::Utc::now().timestamp();
+        let today = crate::utils::time::day_bounds(Some(&conn), crate::utils::time::today());
+        let start = today.start_ts.max(now - 600);
+        activity(&conn, "Code", start, (now - start).min(60), None);
+
+        let delta = compute(&conn, "dashboard", now).unwrap();
+        assert!(delta.first_visit);
+        assert_eq!((delta.gap_start, delta.gap_end), (today.start_ts.clamp(now - MAX_GAP_SECS, now), now));
+        assert_eq!(delta.new_activities, 1);
+
+        mark(&conn, "dashboard", now).unwrap();
+        assert_eq!(get(&conn, "dashboard"), Some(now));
+        // Surfaces are tracked separately.
+        assert_eq!(get(&conn, "timeline"), None);
+        let delta = compute(&conn, "dashboard", now + 5).unwrap();
+        assert!(!delta.first_visit);
+        assert_eq!((delta.gap_start, delta.new_activities), (now, 0));
+        assert_eq!(validate_surface(" Timeline "), Ok("timeline"));
+        assert!(validate_surface("settings").is_err());
+    }
+
+    #[test]
+    fn gaps_spanning_midnight_cover_both_days() {
+        let conn = test_connection();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let midnight = crate::utils::time::day_bounds(Some(&conn), date).start_ts;
+        let last_seen = midnight - 2 * 3600;
+        let now = midnight + 3600;
+        mark(&conn, "timeline", last_seen).unwrap();
+
+        activity(&conn, "Code", last_seen - 600, 300, None);
+        activity(&conn, "Code", midnight - 1800, 1200, None);
+        activity(&conn, "Firefox", midnight + 600, 600, None);
+        activity(
+            &conn,
+            "Spotify",
+            midnight - 900,
+            900,
+            Some(r#"{"media_info":{"title":"Night Drive","artist":"Chromatics","status":"Playing"}}"#),
+        );
+        for (path, at) in [("/src/intent-flow/src/main.rs", midnight - 60), ("/src/intent-flow/src/lib.rs", midnight + 60)] {
+            conn.execute(
+                "INSERT INTO code_file_events (path, project_root, change_type, content_preview, detected_at)
+                 VALUES (?1, '/src/intent-flow', 'modified', 'fn main() {}', ?2)",
+                rusqlite::params![path, at],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO notification_log (kind, title, body, created_at) VALUES ('goal', 'Goal reached', '2h of focus', ?1)",
+            [midnight + 120],
+        )
+        .unwrap();
+
+        let delta = compute(&conn, "timeline", now).unwrap();
+        assert!(!delta.first_visit);
+        assert_eq!((delta.gap_start, delta.gap_end), (last_seen, now));
+        assert_eq!(delta.new_activities, 3);
+        assert_eq!(delta.tracked_seconds, 2_700);
+        assert_eq!(delta.top_apps[0].app_name, "Visual Studio Code");
+        assert_eq!(delta.projects.len(), 1);
+        assert_eq!((delta.projects[0].name.as_str(), delta.projects[0].changes, delta.projects[0].files), ("intent-flow", 2, 2));
+        assert_eq!(delta.media.len(), 1);
+        assert_eq!((delta.media[0].title.as_str(), delta.media[0].seconds), ("Night Drive", 900));
+        assert_eq!(delta.notifications.len(), 1);
+
+        // A visit long ago still only reports the last 48 hours.
+        mark(&conn, "timeline", now - 5 * 86_400).unwrap();
+        let delta = compute(&conn, "timeline", now).unwrap();
+        assert_eq!(delta.gap_start, now - MAX_GAP_SECS);
+        assert_eq!(delta.new_activities, 4);
+    }
+}
@@ -0,0 +1,648 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppError;
+use crate::models::{AiProfile, ChatStatus, LlmCallerUsage, LlmUsage, LlmUsageStats};
+
+// ─── Shared NVIDIA API client ───
+// Every chat-completions call goes through `send_chat_request` so retries
+// and the request budget are shared by the agent, dashboard, intent parser
+// and summaries instead of each caller hammering the endpoint on its own.
+
+pub const CHAT_COMPLETIONS_URL: &str = "https://integrate.api.nvidia.com/v1/chat/completions";
+
+/// API roots set by AI profiles, keyed by lowercased model id.
+fn endpoints_store() -> &'static RwLock<HashMap<String, String>> {
+    static STORE: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
+    STORE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Replace the per-model endpoints; called from the settings bus. When two
+/// profiles share a model, the first one with a `base_url` wins.
+pub fn set_profile_endpoints(profiles: &[AiProfile]) {
+    let mut endpoints = HashMap::new();
+    for profile in profiles {
+        let base_url = profile.base_url.trim().trim_end_matches('/');
+        if !base_url.is_empty() {
+            endpoints.entry(profile.model.trim().to_lowercase()).or_insert_with(|| base_url.to_string());
+        }
+    }
+    if let Ok(mut store) = endpoints_store().write() {
+        *store = endpoints;
+    }
+}
+
+/// Chat-completions URL for `model`: its profile's API root, or the NVIDIA endpoint.
+pub fn chat_completions_url(model: &str) -> String {
+    let base_url = endpoints_store()
+        .read()
+        .ok()
+        .and_then(|s| s.get(&model.trim().to_lowercase()).cloned());
+    match base_url {
+        Some(url) if url.ends_with("/chat/completions") => url,
+        Some(url) => format!("{}/chat/completions", url),
+        None => CHAT_COMPLETIONS_URL.to_string(),
+    }
+}
+
+/// Per-request timeout for callers without a tighter budget of their own.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+const MAX_ATTEMPTS: u32 = 3;
+const BASE_BACKOFF_MS: u64 = 1_000;
+const MAX_BACKOFF_MS: u64 = 30_000;
+/// Requests per minute across all callers.
+const REQUESTS_PER_MINUTE: f64 = 40.0;
+/// Part of the budget background work may not spend, so chat still has room.
+const INTERACTIVE_RESERVE: f64 = 8.0;
+/// Longest single sleep while waiting for budget; waiters re-check after this.
+const BUDGET_POLL_MS: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// User is waiting on the answer (chat, intent parsing, on-demand summaries).
+    Interactive,
+    /// Periodic refreshes and other work nobody is actively waiting on.
+    Background,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(now: Instant) -> Self {
+        TokenBucket {
+            tokens: REQUESTS_PER_MINUTE,
+            last_refill: now,
+        }
+    }
+
+    /// Take one request at `now`, or how long to wait before trying again.
+    /// Background requests leave the reserve alone and yield to waiting chat.
+    fn take(&mut self, now: Instant, priority: Priority, interactive_waiting: bool) -> Result<(), Duration> {
+        let refill_per_sec = REQUESTS_PER_MINUTE / 60.0;
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * refill_per_sec).min(REQUESTS_PER_MINUTE);
+        self.last_refill = now;
+
+        let floor = match priority {
+            Priority::Interactive => 0.0,
+            Priority::Background if interactive_waiting => return Err(Duration::from_millis(BUDGET_POLL_MS)),
+            Priority::Background => INTERACTIVE_RESERVE,
+        };
+        if self.tokens - 1.0 >= floor {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        let missing = floor + 1.0 - self.tokens;
+        Err(Duration::from_secs_f64(missing / refill_per_sec))
+    }
+}
+
+fn bucket() -> &'static Mutex<TokenBucket> {
+    static BUCKET: OnceLock<Mutex<TokenBucket>> = OnceLock::new();
+    BUCKET.get_or_init(|| Mutex::new(TokenBucket::full(Instant::now())))
+}
+
+/// Interactive callers currently waiting for budget; background callers yield while this is non-zero.
+static INTERACTIVE_WAITING: AtomicUsize = AtomicUsize::new(0);
+
+/// Take one request from the budget, or how long to wait before trying again.
+fn try_acquire(priority: Priority) -> Result<(), Duration> {
+    let interactive_waiting = INTERACTIVE_WAITING.load(Ordering::SeqCst) > 0;
+    let mut bucket = bucket().lock().unwrap_or_else(|e| e.into_inner());
+    bucket.take(Instant::now(), priority, interactive_waiting)
+}
+
+struct WaitingGuard(bool);
+
+impl Drop for WaitingGuard {
+    fn drop(&mut self) {
+        if self.0 {
+            INTERACTIVE_WAITING.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+async fn acquire_budget(priority: Priority, status: Option<&AppHandle>) {
+    let mut guard = WaitingGuard(false);
+    let mut announced = false;
+    loop {
+        let wait = match try_acquire(priority) {
+            Ok(()) => return,
+            Err(wait) => wait,
+        };
+        if priority == Priority::Interactive && !guard.0 {
+            INTERACTIVE_WAITING.fetch_add(1, Ordering::SeqCst);
+            guard.0 = true;
+        }
+        if !announced {
+            println!("[LLM] Request budget exhausted, waiting ({:?})", priority);
+            emit_status(status, "Waiting for API (rate limited)…");
+            announced = true;
+        }
+        tokio::time::sleep(wait.min(Duration::from_millis(BUDGET_POLL_MS))).await;
+    }
+}
+
+fn emit_status(status: Option<&AppHandle>, message: &str) {
+    if let Some(app_handle) = status {
+        let payload = ChatStatus {
+            message: message.to_string(),
+            usage: None,
+        };
+        let _ = app_handle.emit("chat://status", payload);
+    }
+}
+
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Seconds from a `Retry-After` header; HTTP-date values are ignored in favour of backoff.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(|secs| Duration::from_millis((secs * 1000).min(MAX_BACKOFF_MS)))
+}
+
+/// Exponential backoff with up to 50% jitter so parallel callers don't retry in lockstep.
+fn backoff_delay(attempt: u32) -> Duration {
+    let base = BASE_BACKOFF_MS
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(10))
+        .min(MAX_BACKOFF_MS);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    Duration::from_millis(base + nanos % (base / 2 + 1))
+}
+
+/// POST a chat-completions body, retrying 429/5xx responses and network errors.
+/// Returns the successful response so callers can read it whole or stream it.
+/// `model` picks the endpoint. Pass `status` to surface rate-limit waits to the chat UI. `timeout` covers
+/// each attempt including reading the body.
+pub async fn send_chat_request<T: Serialize>(
+    api_key: &str,
+    model: &str,
+    body: &T,
+    timeout: Duration,
+    priority: Priority,
+    status: Option<&AppHandle>,
+) -> Result<reqwest::Response, AppError> {
+    let client = crate::utils::http::http_client();
+    let url = chat_completions_url(model);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        acquire_budget(priority, status).await;
+
+        let result = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(body)
+            .timeout(timeout)
+            .send()
+            .await;
+
+        let delay = match result {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let code = response.status();
+                if !is_retryable(code) || attempt >= MAX_ATTEMPTS {
+                    let text = response.text().await.unwrap_or_default();
+                    let message = format!("API Error {}: {}", code, text.chars().take(300).collect::<String>());
+                    return Err(match code {
+                        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => AppError::ApiAuth(message),
+                        _ => AppError::Api(message),
+                    });
+                }
+                let delay = retry_after(&response).unwrap_or_else(|| backoff_delay(attempt));
+                println!("[LLM] API returned {}, retrying in {:?} (attempt {}/{})", code, delay, attempt, MAX_ATTEMPTS);
+                if code == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    emit_status(status, "Waiting for API (rate limited)…");
+                }
+                delay
+            }
+            Err(e) => {
+                if attempt >= MAX_ATTEMPTS || !(e.is_timeout() || e.is_connect()) {
+                    return Err(AppError::Network(format!("Net err: {}", e)));
+                }
+                let delay = backoff_delay(attempt);
+                println!("[LLM] Request failed ({}), retrying in {:?}", e, delay);
+                delay
+            }
+        };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+// ─── Token usage ───
+// Non-streaming responses carry the provider's `usage` block; streams usually
+// don't, so those calls are estimated at ~4 characters per token. Each caller
+// records what it spent in `llm_usage` for the monthly summary.
+
+pub const CALLER_CHAT: &str = "chat";
+pub const CALLER_DASHBOARD: &str = "dashboard";
+pub const CALLER_SUMMARY: &str = "summary";
+pub const CALLER_INTENT: &str = "intent";
+pub const CALLER_CHAT_TITLE: &str = "chat_title";
+
+const CHARS_PER_TOKEN: u64 = 4;
+/// Role and separator tokens the chat template adds around each message.
+const MESSAGE_OVERHEAD_TOKENS: i64 = 4;
+
+/// Rough list prices in USD per million (prompt, completion) tokens, matched
+/// against the model id. Models not listed get no cost figure.
+const MODEL_PRICES_PER_MTOK: &[(&str, f64, f64)] = &[
+    ("kimi-k2", 0.60, 2.50),
+    ("deepseek-r1", 0.55, 2.19),
+    ("deepseek-v3", 0.27, 1.10),
+    ("llama-3.3-70b", 0.23, 0.40),
+    ("llama-3.1-8b", 0.02, 0.05),
+    ("qwen3-235b", 0.20, 0.60),
+];
+
+/// `usage` block of a chat-completions response.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct ProviderUsage {
+    #[serde(default)]
+    pub prompt_tokens: i64,
+    #[serde(default)]
+    pub completion_tokens: i64,
+}
+
+pub fn estimate_tokens(text: &str) -> i64 {
+    (text.chars().count() as u64).div_ceil(CHARS_PER_TOKEN) as i64
+}
+
+/// Estimated prompt size of a request made of these message contents.
+pub fn estimate_prompt_tokens<'a>(contents: impl IntoIterator<Item = &'a str>) -> i64 {
+    contents
+        .into_iter()
+        .map(|content| estimate_tokens(content) + MESSAGE_OVERHEAD_TOKENS)
+        .sum()
+}
+
+pub fn estimate_cost(model: &str, prompt_tokens: i64, completion_tokens: i64) -> Option<f64> {
+    let model = model.to_lowercase();
+    MODEL_PRICES_PER_MTOK
+        .iter()
+        .find(|(pattern, _, _)| model.contains(pattern))
+        .map(|(_, prompt_price, completion_price)| {
+            (prompt_tokens as f64 * prompt_price + completion_tokens as f64 * completion_price) / 1_000_000.0
+        })
+}
+
+/// Usage of one call: the provider's numbers when it reported them, otherwise
+/// `estimated_prompt_tokens` plus an estimate from the completion text.
+pub fn call_usage(
+    model: &str,
+    reported: Option<ProviderUsage>,
+    estimated_prompt_tokens: i64,
+    completion: &str,
+) -> LlmUsage {
+    let (prompt_tokens, completion_tokens, estimated) = match reported {
+        Some(u) if u.prompt_tokens > 0 || u.completion_tokens > 0 => (u.prompt_tokens, u.completion_tokens, false),
+        _ => (estimated_prompt_tokens, estimate_tokens(completion), true),
+    };
+    LlmUsage {
+        prompt_tokens,
+        completion_tokens,
+        calls: 1,
+        estimated,
+        cost_usd: estimate_cost(model, prompt_tokens, completion_tokens),
+    }
+}
+
+/// Store `usage` for the monthly summary. Failures are logged, never surfaced:
+/// accounting must not break the call it describes.
+pub fn record_usage(app_handle: &AppHandle, caller: &str, model: &str, usage: &LlmUsage) {
+    if usage.calls == 0 {
+        return;
+    }
+    let result = crate::database::open(app_handle)
+        .map_err(|e| e.to_string())
+        .and_then(|conn| insert_usage(&conn, caller, model, usage, chrono::Utc::now().timestamp()));
+    if let Err(e) = result {
+        log::warn!("Failed to record LLM usage for {}: {}", caller, e);
+    }
+}
+
+fn insert_usage(
+    conn: &rusqlite::Connection,
+    caller: &str,
+    model: &str,
+    usage: &LlmUsage,
+    created_at: i64,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO llm_usage (caller, model, prompt_tokens, completion_tokens, calls, estimated, cost_usd, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            caller,
+            model,
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.calls,
+            usage.estimated as i64,
+            usage.cost_usd,
+            created_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Usage between `start_ts` and `end_ts`, in total and per caller (largest first).
+pub fn usage_stats(
+    conn: &rusqlite::Connection,
+    month: &str,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<LlmUsageStats, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT caller, SUM(prompt_tokens), SUM(completion_tokens), SUM(calls),
+                    MAX(estimated), SUM(cost_usd), SUM(cost_usd IS NULL)
+             FROM llm_usage
+             WHERE created_at >= ?1 AND created_at < ?2
+             GROUP BY caller
+             ORDER BY SUM(prompt_tokens) + SUM(completion_tokens) DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let by_caller = stmt
+        .query_map([start_ts, end_ts], |row| {
+            let unpriced: i64 = row.get(6)?;
+            Ok(LlmCallerUsage {
+                caller: row.get(0)?,
+                usage: LlmUsage {
+                    prompt_tokens: row.get(1)?,
+                    completion_tokens: row.get(2)?,
+                    calls: row.get(3)?,
+                    estimated: row.get::<_, i64>(4)? != 0,
+                    cost_usd: if unpriced > 0 { None } else { row.get(5)? },
+                },
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut total = LlmUsage::default();
+    for caller in &by_caller {
+        total.add(&caller.usage);
+    }
+    Ok(LlmUsageStats {
+        month: month.to_string(),
+        total,
+        by_caller,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn background_work_leaves_the_reserve_to_chat() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::full(start);
+        let background_budget = (REQUESTS_PER_MINUTE - INTERACTIVE_RESERVE) as usize;
+        for _ in 0..background_budget {
+            assert!(bucket.take(start, Priority::Background, false).is_ok());
+        }
+        // One request refills in 1.5s at 40/min.
+        assert_eq!(bucket.take(start, Priority::Background, false), Err(Duration::from_millis(1500)));
+        for _ in 0..INTERACTIVE_RESERVE as usize {
+            assert!(bucket.take(start, Priority::Interactive, false).is_ok());
+        }
+        assert_eq!(bucket.take(start, Priority::Interactive, false), Err(Duration::from_millis(1500)));
+
+        // Refill is linear and shared: after 1.5s chat gets the next request.
+        let later = start + Duration::from_millis(1500);
+        assert!(bucket.take(later, Priority::Interactive, false).is_ok());
+    }
+
+    #[test]
+    fn waiting_chat_preempts_background_requests() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::full(now);
+        assert_eq!(
+            bucket.take(now, Priority::Background, true),
+            Err(Duration::from_millis(BUDGET_POLL_MS))
+        );
+        assert!(bucket.take(now, Priority::Interactive, true).is_ok());
+        assert!(bucket.take(now, Priority::Background, false).is_ok());
+    }
+
+    #[test]
+    fn backoff_doubles_with_bounded_jitter() {
+        for (attempt, base) in [(1, 1_000), (2, 2_000), (3, 4_000), (20, MAX_BACKOFF_MS)] {
+            let delay = backoff_delay(attempt).as_millis() as u64;
+            assert!(delay >= base && delay <= base + base / 2, "attempt {}: {}ms", attempt, delay);
+        }
+    }
+
+    /// Answers each connection with the next canned response and counts requests.
+    async fn mock_server(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+        });
+        (url, hits)
+    }
+
+    fn route(model: &str, base_url: &str) {
+        set_profile_endpoints(&[AiProfile {
+            name: "mock".to_string(),
+            model: model.to_string(),
+            base_url: base_url.to_string(),
+            temperature: None,
+            max_tokens: None,
+        }]);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_request_waits_for_retry_after_then_succeeds() {
+        let (url, hits) = mock_server(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+        ])
+        .await;
+        route("mock-rate-limited", &url);
+
+        let started = Instant::now();
+        let response = send_chat_request(
+            "key",
+            "mock-rate-limited",
+            &serde_json::json!({}),
+            Duration::from_secs(5),
+            Priority::Interactive,
+            None,
+        )
+        .await
+        .unwrap();
+        let elapsed = started.elapsed();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        // Retry-After wins over the (longer, jittered) backoff.
+        assert!(elapsed >= Duration::from_secs(1) && elapsed < Duration::from_millis(1_500), "{:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let (url, hits) = mock_server(vec![
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 9\r\nConnection: close\r\n\r\nbad model",
+        ])
+        .await;
+        route("mock-bad-request", &url);
+
+        let error = send_chat_request(
+            "key",
+            "mock-bad-request",
+            &serde_json::json!({}),
+            Duration::from_secs(5),
+            Priority::Background,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(&error, AppError::Api(m) if m.starts_with("API Error 400") && m.ends_with("bad model")),
+            "{:?}",
+            error
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_keys_are_auth_errors() {
+        let (url, _) = mock_server(vec![
+            "HTTP/1.1 401 Unauthorized
+Content-Length: 11
+Connection: close
+
+bad api key",
+        ])
+        .await;
+        route("mock-unauthorized", &url);
+
+        let error = send_chat_request(
+            "key",
+            "mock-unauthorized",
+            &serde_json::json!({}),
+            Duration::from_secs(5),
+            Priority::Background,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, AppError::ApiAuth(_)), "{:?}", error);
+    }
+
+    fn reported(prompt_tokens: i64, completion_tokens: i64) -> Option<ProviderUsage> {
+        Some(ProviderUsage { prompt_tokens, completion_tokens })
+    }
+
+    #[test]
+    fn provider_usage_wins_over_the_estimate() {
+        let usage = call_usage("moonshotai/kimi-k2-instruct", reported(1_000, 200), 9_999, "ignored");
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens, usage.estimated), (1_000, 200, false));
+        assert!((usage.cost_usd.unwrap() - 0.0011).abs() < 1e-9);
+
+        // Streams without a usage block (or an all-zero one) fall back to chars/4.
+        let usage = call_usage("unknown/model", reported(0, 0), 50, "twelve chars");
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens, usage.estimated), (50, 3, true));
+        assert_eq!(usage.cost_usd, None);
+        assert_eq!(estimate_prompt_tokens(["abcd", "abcde"]), 1 + 2 + 2 * MESSAGE_OVERHEAD_TOKENS);
+    }
+
+    #[test]
+    fn usage_accumulates_across_agent_turns() {
+        let model = "deepseek-ai/deepseek-v3";
+        let mut run = LlmUsage::default();
+        run.add(&LlmUsage::default());
+        assert_eq!(run, LlmUsage::default());
+
+        let turns = [
+            call_usage(model, reported(1_200, 80), 0, ""),
+            call_usage(model, None, 1_500, "a tool call with its arguments"),
+            call_usage(model, reported(2_100, 300), 0, ""),
+        ];
+        for turn in &turns {
+            run.add(turn);
+        }
+        assert_eq!(run.calls, 3);
+        assert_eq!(run.prompt_tokens, 1_200 + 1_500 + 2_100);
+        assert_eq!(run.completion_tokens, 80 + 8 + 300);
+        assert!(run.estimated);
+        let cost: f64 = turns.iter().filter_map(|t| t.cost_usd).sum();
+        assert!((run.cost_usd.unwrap() - cost).abs() < 1e-12);
+
+        // One call of an unpriced model leaves the run without a cost figure.
+        run.add(&call_usage("local/unknown", reported(10, 10), 0, ""));
+        assert_eq!(run.cost_usd, None);
+        assert_eq!(run.total_tokens(), 4_800 + 388 + 20);
+    }
+
+    #[test]
+    fn monthly_rollup_groups_by_caller_within_the_month() {
+        let conn = crate::database::test_connection();
+        let (start, end) = (1_000_000, 2_000_000);
+        let priced = |p, c| call_usage("kimi-k2", reported(p, c), 0, "");
+
+        insert_usage(&conn, CALLER_CHAT, "kimi-k2", &priced(1_000, 100), start).unwrap();
+        insert_usage(&conn, CALLER_CHAT, "kimi-k2", &priced(3_000, 400), end - 1).unwrap();
+        insert_usage(&conn, CALLER_DASHBOARD, "kimi-k2", &priced(500, 50), start + 10).unwrap();
+        insert_usage(&conn, CALLER_SUMMARY, "mystery", &call_usage("mystery", None, 40, "abcd"), start + 20).unwrap();
+        // Outside the month on either side.
+        insert_usage(&conn, CALLER_CHAT, "kimi-k2", &priced(9_000, 900), start - 1).unwrap();
+        insert_usage(&conn, CALLER_DASHBOARD, "kimi-k2", &priced(9_000, 900), end).unwrap();
+
+        let stats = usage_stats(&conn, "2024-05", start, end).unwrap();
+        assert_eq!(stats.month, "2024-05");
+        let callers: Vec<&str> = stats.by_caller.iter().map(|c| c.caller.as_str()).collect();
+        assert_eq!(callers, vec![CALLER_CHAT, CALLER_DASHBOARD, CALLER_SUMMARY]);
+
+        let chat = &stats.by_caller[0].usage;
+        assert_eq!((chat.prompt_tokens, chat.completion_tokens, chat.calls, chat.estimated), (4_000, 500, 2, false));
+        assert!(chat.cost_usd.is_some());
+        let summary = &stats.by_caller[2].usage;
+        assert!(summary.estimated);
+        assert_eq!(summary.cost_usd, None);
+
+        assert_eq!(stats.total.calls, 4);
+        assert_eq!(stats.total.total_tokens(), 4_500 + 550 + 41);
+        assert!(stats.total.estimated);
+        assert_eq!(stats.total.cost_usd, None);
+
+        let empty = usage_stats(&conn, "2023-01", 0, 1_000).unwrap();
+        assert_eq!(empty.total, LlmUsage::default());
+        assert!(empty.by_caller.is_empty());
+    }
+}
@@ -1,6 +1,25 @@
 pub mod activity_tracker;
+pub mod app_alerts;
+pub mod app_icons;
+pub mod app_lock;
+pub mod clipboard_monitor;
+pub mod current_focus;
 pub mod dashboard_engine;
+pub mod demo_mode;
 pub mod file_monitor;
+pub mod goal_streaks;
+pub mod hotkeys;
+pub mod last_seen;
+pub mod notifier;
+pub mod now_playing;
+pub mod ocr_index;
+pub mod process_monitor;
 pub mod screen_capture;
+pub mod service_status;
 pub mod pattern_engine;
+pub mod llm_client;
+pub mod model_capabilities;
 pub mod query_engine;
+pub mod report_engine;
+pub mod settings_bus;
+pub mod tracking_pause;
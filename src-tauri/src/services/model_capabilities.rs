@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::models::{AiProfile, ModelCapabilities};
+
+// ─── Model capability registry ───
+// Resolution order: user overrides from settings, then probe results, then
+// the built-in table below, then a conservative default. Probe results are
+// kept in the DB `settings` table so they survive restarts. An AI profile's
+// `max_tokens` replaces that one field whatever the source.
+
+const PROBED_KEY: &str = "model_capabilities";
+
+/// Matched against the lowercased model id in order, so specific ids come first.
+/// (pattern, context_window, supports_reasoning, supports_native_tools, max_tokens)
+const KNOWN_MODELS: &[(&str, u32, bool, bool, u32)] = &[
+    ("phi-3-mini-4k", 4_096, false, false, 512),
+    ("deepseek-r1", 128_000, true, false, 4_096),
+    ("qwq", 32_768, true, false, 4_096),
+    ("qwen3", 32_768, true, true, 3_072),
+    ("gpt-oss", 128_000, true, true, 3_072),
+    ("glm", 128_000, true, true, 3_072),
+    ("kimi-k2", 128_000, false, true, 1_600),
+    ("deepseek-v3", 128_000, false, true, 1_600),
+    ("llama-3.1-8b", 128_000, false, true, 1_024),
+    ("llama-3.1", 128_000, false, true, 1_600),
+    ("llama-3.2", 128_000, false, true, 1_024),
+    ("llama-3.3", 128_000, false, true, 1_600),
+    ("llama3-", 8_192, false, false, 1_024),
+    ("nemotron", 128_000, false, true, 1_600),
+    ("mixtral-8x22b", 65_536, false, true, 1_600),
+    ("mixtral", 32_768, false, true, 1_024),
+    ("mistral-7b", 32_768, false, false, 1_024),
+    ("gemma-2", 8_192, false, false, 1_024),
+    ("phi-3", 128_000, false, false, 1_024),
+];
+
+const DEFAULT_CAPABILITIES: ModelCapabilities = ModelCapabilities {
+    context_window: 32_768,
+    supports_reasoning: false,
+    supports_native_tools: false,
+    max_tokens: 1_600,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CapabilitySource {
+    Override,
+    Probed,
+    Builtin,
+    Default,
+}
+
+fn overrides_store() -> &'static RwLock<HashMap<String, ModelCapabilities>> {
+    static STORE: OnceLock<RwLock<HashMap<String, ModelCapabilities>>> = OnceLock::new();
+    STORE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn probed_store() -> &'static RwLock<HashMap<String, ModelCapabilities>> {
+    static STORE: OnceLock<RwLock<HashMap<String, ModelCapabilities>>> = OnceLock::new();
+    STORE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Replace the user overrides; called from the settings bus.
+pub fn set_overrides(overrides: &HashMap<String, ModelCapabilities>) {
+    if let Ok(mut store) = overrides_store().write() {
+        *store = overrides
+            .iter()
+            .map(|(model, caps)| (model.trim().to_lowercase(), caps.clone()))
+            .collect();
+    }
+}
+
+/// `max_tokens` set by AI profiles, keyed by lowercased model id.
+fn profile_limits_store() -> &'static RwLock<HashMap<String, u32>> {
+    static STORE: OnceLock<RwLock<HashMap<String, u32>>> = OnceLock::new();
+    STORE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Replace the per-profile completion budgets; called from the settings bus.
+/// These win over every other source for `max_tokens` only.
+pub fn set_profile_limits(profiles: &[AiProfile]) {
+    if let Ok(mut store) = profile_limits_store().write() {
+        *store = profiles
+            .iter()
+            .filter_map(|p| Some((p.model.trim().to_lowercase(), p.max_tokens.filter(|&m| m > 0)?)))
+            .collect();
+    }
+}
+
+/// Restore probe results saved by earlier runs.
+pub fn load(conn: &Connection) {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT CAST(value AS TEXT) FROM settings WHERE key = ?1",
+            [PROBED_KEY],
+            |row| row.get(0),
+        )
+        .ok();
+    let probed: HashMap<String, ModelCapabilities> = stored
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default();
+    if !probed.is_empty() {
+        println!("[Models] Loaded probed capabilities for {} model(s)", probed.len());
+    }
+    if let Ok(mut store) = probed_store().write() {
+        *store = probed;
+    }
+}
+
+/// Remember a probe result and persist all of them.
+pub fn save_probed(conn: &Connection, model: &str, caps: &ModelCapabilities) -> Result<(), String> {
+    let snapshot = {
+        let mut store = probed_store().write().map_err(|e| e.to_string())?;
+        store.insert(model.trim().to_lowercase(), caps.clone());
+        store.clone()
+    };
+    let json = serde_json::to_string(&snapshot).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        rusqlite::params![PROBED_KEY, json, chrono::Utc::now().timestamp()],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Entry from the built-in table, if the model id matches one.
+pub fn builtin(model: &str) -> Option<ModelCapabilities> {
+    let id = model.trim().to_lowercase();
+    KNOWN_MODELS
+        .iter()
+        .find(|(pattern, ..)| id.contains(pattern))
+        .map(|(_, context_window, supports_reasoning, supports_native_tools, max_tokens)| ModelCapabilities {
+            context_window: *context_window,
+            supports_reasoning: *supports_reasoning,
+            supports_native_tools: *supports_native_tools,
+            max_tokens: *max_tokens,
+        })
+}
+
+pub fn builtin_or_default(model: &str) -> ModelCapabilities {
+    builtin(model).unwrap_or(DEFAULT_CAPABILITIES)
+}
+
+pub fn resolve_with_source(model: &str) -> (ModelCapabilities, CapabilitySource) {
+    let (mut caps, source) = lookup(model);
+    let id = model.trim().to_lowercase();
+    if let Some(max_tokens) = profile_limits_store().read().ok().and_then(|s| s.get(&id).copied()) {
+        caps.max_tokens = max_tokens;
+    }
+    (caps, source)
+}
+
+fn lookup(model: &str) -> (ModelCapabilities, CapabilitySource) {
+    let id = model.trim().to_lowercase();
+    if let Some(caps) = overrides_store().read().ok().and_then(|s| s.get(&id).cloned()) {
+        return (caps, CapabilitySource::Override);
+    }
+    if let Some(caps) = probed_store().read().ok().and_then(|s| s.get(&id).cloned()) {
+        return (caps, CapabilitySource::Probed);
+    }
+    if let Some(caps) = builtin(&id) {
+        return (caps, CapabilitySource::Builtin);
+    }
+    (DEFAULT_CAPABILITIES, CapabilitySource::Default)
+}
+
+pub fn resolve(model: &str) -> ModelCapabilities {
+    resolve_with_source(model).0
+}
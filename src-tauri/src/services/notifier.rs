@@ -0,0 +1,45 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Payload for `notification://show`; the frontend turns it into a system
+/// notification through the webview Notification API.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppNotification {
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+}
+
+pub fn notify(app_handle: &AppHandle, kind: &str, title: &str, body: &str) {
+    println!("[Notify] {}: {}", title, body);
+    if let Err(e) = log_notification(app_handle, kind, title, body) {
+        log::warn!("Failed to log notification: {}", e);
+    }
+    let _ = app_handle.emit(
+        "notification://show",
+        AppNotification {
+            kind: kind.to_string(),
+            title: title.to_string(),
+            body: body.to_string(),
+        },
+    );
+}
+
+/// Notifications are kept this long in `notification_log`.
+const LOG_RETENTION_SECS: i64 = 30 * 24 * 3600;
+
+fn log_notification(app_handle: &AppHandle, kind: &str, title: &str, body: &str) -> Result<(), String> {
+    let conn = crate::database::open(app_handle)?;
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO notification_log (kind, title, body, created_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![kind, title, body, now],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM notification_log WHERE created_at < ?1",
+        [now - LOG_RETENTION_SECS],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::MediaInfo;
+
+// ─── Now playing ───
+// Latest SMTC reading, shared by the tracker (which polls it while refreshing
+// session metadata) and `get_now_playing`. A new track or playback status is
+// broadcast on `media://now-playing`; repeated identical readings are not.
+// An empty reading keeps the last track, which then goes stale.
+
+/// A track reported as playing but not seen for this long probably belongs to a closed player.
+const STALE_AFTER_SECS: i64 = 30;
+
+/// Payload of `get_now_playing` and the `media://now-playing` event.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NowPlaying {
+    pub media: Option<MediaInfo>,
+    /// Player name derived from the SMTC id, e.g. "Spotify".
+    pub source_app: Option<String>,
+    /// When the media session last reported this track.
+    pub updated_at: Option<i64>,
+    pub seconds_ago: Option<i64>,
+    pub stale: bool,
+}
+
+/// Last reading and when it was taken.
+fn latest_store() -> &'static Mutex<Option<(MediaInfo, i64)>> {
+    static STORE: OnceLock<Mutex<Option<(MediaInfo, i64)>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(None))
+}
+
+/// Read the media session and record it. Blocks on SMTC; call from `spawn_blocking`.
+pub fn poll(app_handle: &AppHandle) -> Option<MediaInfo> {
+    let info = crate::utils::windows::get_media_info();
+    if let Some(ref info) = info {
+        record(app_handle, info.clone());
+    }
+    info
+}
+
+pub fn record(app_handle: &AppHandle, info: MediaInfo) {
+    let now = chrono::Utc::now().timestamp();
+    let changed = match latest_store().lock() {
+        Ok(mut latest) => store_reading(&mut latest, info, now),
+        Err(_) => return,
+    };
+    if changed {
+        let _ = app_handle.emit("media://now-playing", current());
+    }
+}
+
+/// Keep `info` as the latest reading; true when the track or playback status changed.
+fn store_reading(latest: &mut Option<(MediaInfo, i64)>, info: MediaInfo, now: i64) -> bool {
+    let changed = latest.as_ref().map_or(true, |(previous, _)| *previous != info);
+    *latest = Some((info, now));
+    changed
+}
+
+pub fn current() -> NowPlaying {
+    let latest = latest_store().lock().ok().and_then(|latest| latest.clone());
+    snapshot(latest, chrono::Utc::now().timestamp())
+}
+
+fn snapshot(latest: Option<(MediaInfo, i64)>, now: i64) -> NowPlaying {
+    let Some((info, updated_at)) = latest else {
+        return NowPlaying::default();
+    };
+    let seconds_ago = (now - updated_at).max(0);
+    NowPlaying {
+        source_app: info.source_app.as_deref().map(player_name),
+        stale: is_stale(&info, seconds_ago),
+        media: Some(info),
+        updated_at: Some(updated_at),
+        seconds_ago: Some(seconds_ago),
+    }
+}
+
+/// Paused or stopped tracks legitimately sit unchanged, so only a "playing" one goes stale.
+pub fn is_stale(info: &MediaInfo, seconds_ago: i64) -> bool {
+    info.status.eq_ignore_ascii_case("playing") && seconds_ago > STALE_AFTER_SECS
+}
+
+/// "Spotify.exe" → "Spotify"; "SpotifyAB.SpotifyMusic_zpdnekdrzrea0!Spotify" → "Spotify".
+fn player_name(source_app: &str) -> String {
+    let name = source_app.rsplit('!').next().unwrap_or(source_app);
+    let name = name.strip_suffix(".exe").or_else(|| name.strip_suffix(".EXE")).unwrap_or(name);
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(title: &str, status: &str) -> MediaInfo {
+        MediaInfo {
+            title: title.to_string(),
+            artist: "Chromatics".to_string(),
+            status: status.to_string(),
+            source_app: Some("SpotifyAB.SpotifyMusic_zpdnekdrzrea0!Spotify".to_string()),
+        }
+    }
+
+    #[test]
+    fn only_playing_tracks_go_stale() {
+        assert!(!is_stale(&track("Shadow", "Playing"), STALE_AFTER_SECS));
+        assert!(is_stale(&track("Shadow", "Playing"), STALE_AFTER_SECS + 1));
+        assert!(is_stale(&track("Shadow", "playing"), 600));
+        assert!(!is_stale(&track("Shadow", "Paused"), 600));
+        assert!(!is_stale(&track("Shadow", "Stopped"), 600));
+
+        let t0 = 1_700_000_000;
+        assert_eq!(snapshot(None, t0).media, None);
+        let live = snapshot(Some((track("Shadow", "Playing"), t0)), t0 + 10);
+        assert_eq!((live.seconds_ago, live.stale), (Some(10), false));
+        assert_eq!(live.source_app.as_deref(), Some("Spotify"));
+        // The player was closed mid-track: no new reading for 45s.
+        let closed = snapshot(Some((track("Shadow", "Playing"), t0)), t0 + 45);
+        assert_eq!((closed.updated_at, closed.stale), (Some(t0), true));
+        // A clock step backwards never reports a negative age.
+        assert_eq!(snapshot(Some((track("Shadow", "Playing"), t0)), t0 - 5).seconds_ago, Some(0));
+    }
+
+    #[test]
+    fn events_fire_only_on_track_or_status_changes() {
+        let mut latest = None;
+        let t0 = 1_700_000_000;
+        let readings = [
+            (track("Shadow", "Playing"), true),
+            (track("Shadow", "Playing"), false),
+            (track("Shadow", "Paused"), true),
+            (track("Shadow", "Paused"), false),
+            (track("Cherry", "Paused"), true),
+            (track("Cherry", "Playing"), true),
+        ];
+        for (i, (info, expect_event)) in readings.into_iter().enumerate() {
+            assert_eq!(store_reading(&mut latest, info, t0 + i as i64), expect_event, "reading {}", i);
+        }
+        // Unchanged readings still refresh the time, so a playing track doesn't go stale while polled.
+        assert!(!store_reading(&mut latest, track("Cherry", "Playing"), t0 + 100));
+        assert!(!snapshot(latest, t0 + 110).stale);
+        assert_eq!(player_name("Spotify.exe"), "Spotify");
+        assert_eq!(player_name("chrome"), "chrome");
+    }
+}
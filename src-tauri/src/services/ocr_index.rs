@@ -0,0 +1,181 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rusqlite::Connection;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::IndexRebuildProgress;
+
+// ─── OCR full-text index ───
+// `activities_fts` holds each activity's OCR text under the activity id as
+// rowid. Search, keyword audit and deletion already use it whenever the table
+// exists; once created, triggers keep it in step with new captures and
+// redactions. `rebuild` creates it if needed and backfills existing history.
+
+pub const FTS_TABLE: &str = "activities_fts";
+
+/// Activities indexed per transaction, so the tracker's writes never wait long.
+const CHUNK_ROWS: i64 = 500;
+
+/// OCR text of an `activities` row (`NEW.` or unqualified), or NULL when the
+/// metadata has none or isn't valid JSON; a bad blob must never abort a capture.
+fn screen_text_sql(row: &str) -> String {
+    format!(
+        "CASE WHEN json_valid(CAST({row}metadata AS TEXT)) \
+         THEN NULLIF(TRIM(json_extract(CAST({row}metadata AS TEXT), '$.screen_text')), '') END",
+        row = row
+    )
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Create the index and its triggers. Returns whether the table was new.
+pub fn ensure_index(conn: &Connection) -> rusqlite::Result<bool> {
+    let existed: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [FTS_TABLE],
+        |row| row.get(0),
+    )?;
+    let new_text = screen_text_sql("NEW.");
+    conn.execute_batch(&format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS activities_fts USING fts5(screen_text);
+
+         CREATE TRIGGER IF NOT EXISTS trg_activities_fts_insert
+         AFTER INSERT ON activities
+         WHEN {text} IS NOT NULL
+         BEGIN INSERT INTO activities_fts (rowid, screen_text) VALUES (NEW.id, {text}); END;
+
+         CREATE TRIGGER IF NOT EXISTS trg_activities_fts_update
+         AFTER UPDATE OF metadata ON activities
+         BEGIN
+             DELETE FROM activities_fts WHERE rowid = OLD.id;
+             INSERT INTO activities_fts (rowid, screen_text) SELECT NEW.id, {text} WHERE {text} IS NOT NULL;
+         END;
+
+         CREATE TRIGGER IF NOT EXISTS trg_activities_fts_delete
+         AFTER DELETE ON activities
+         BEGIN DELETE FROM activities_fts WHERE rowid = OLD.id; END;",
+        text = new_text
+    ))?;
+    Ok(!existed)
+}
+
+fn drop_index(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "DROP TRIGGER IF EXISTS trg_activities_fts_insert;
+         DROP TRIGGER IF EXISTS trg_activities_fts_update;
+         DROP TRIGGER IF EXISTS trg_activities_fts_delete;
+         DROP TABLE IF EXISTS activities_fts;",
+    )
+}
+
+/// Ask a running rebuild to stop after its current chunk. Returns whether one was running.
+pub fn cancel() -> bool {
+    let running = RUNNING.load(Ordering::Relaxed);
+    if running {
+        CANCEL_REQUESTED.store(true, Ordering::Relaxed);
+    }
+    running
+}
+
+/// Re-index every activity up to the newest one at start; later rows are
+/// covered by the triggers. Each chunk replaces its id range wholesale, so
+/// running it again (or after a cancel) gives the same index. A cancelled
+/// first build drops the table again: search falls back to scanning the
+/// metadata rather than trusting a partial index.
+pub fn rebuild(app_handle: &AppHandle) -> Result<IndexRebuildProgress, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("An index rebuild is already running".to_string());
+    }
+    CANCEL_REQUESTED.store(false, Ordering::Relaxed);
+    let result = run(app_handle);
+    RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+fn run(app_handle: &AppHandle) -> Result<IndexRebuildProgress, String> {
+    let mut conn = crate::database::open(app_handle)?;
+    let created = ensure_index(&conn).map_err(|e| e.to_string())?;
+    let (total, max_id): (i64, i64) = conn
+        .query_row("SELECT COUNT(*), COALESCE(MAX(id), 0) FROM activities", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut progress = IndexRebuildProgress {
+        processed: 0,
+        total,
+        indexed: 0,
+        done: false,
+        cancelled: false,
+    };
+    let emit = |progress: &IndexRebuildProgress| {
+        let _ = app_handle.emit("index://progress", progress);
+    };
+    emit(&progress);
+
+    let text = screen_text_sql("");
+    let mut last_id = 0;
+    while last_id < max_id {
+        if CANCEL_REQUESTED.load(Ordering::Relaxed) {
+            progress.cancelled = true;
+            break;
+        }
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let (rows, chunk_end): (i64, Option<i64>) = tx
+            .query_row(
+                "SELECT COUNT(*), MAX(id) FROM (
+                     SELECT id FROM activities WHERE id > ?1 AND id <= ?2 ORDER BY id LIMIT ?3
+                 )",
+                rusqlite::params![last_id, max_id, CHUNK_ROWS],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        // Past the last row the range runs to `max_id`, clearing entries of deleted rows too.
+        let chunk_end = chunk_end.unwrap_or(max_id);
+        tx.execute(
+            "DELETE FROM activities_fts WHERE rowid > ?1 AND rowid <= ?2",
+            [last_id, chunk_end],
+        )
+        .map_err(|e| e.to_string())?;
+        let indexed = tx
+            .execute(
+                &format!(
+                    "INSERT INTO activities_fts (rowid, screen_text)
+                     SELECT id, {text} FROM activities
+                     WHERE id > ?1 AND id <= ?2 AND {text} IS NOT NULL",
+                    text = text
+                ),
+                [last_id, chunk_end],
+            )
+            .map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        last_id = chunk_end;
+        progress.processed += rows;
+        progress.indexed += indexed as i64;
+        emit(&progress);
+    }
+
+    if progress.cancelled {
+        if created {
+            drop_index(&conn).map_err(|e| e.to_string())?;
+        }
+        log::info!("OCR index rebuild cancelled after {} of {} activities", progress.processed, total);
+    } else {
+        // Rows deleted before the triggers existed may have left entries behind.
+        conn.execute(
+            "DELETE FROM activities_fts WHERE rowid NOT IN (SELECT id FROM activities)",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
+        progress.done = true;
+        log::info!(
+            "OCR index rebuilt: {} activities, {} with text",
+            progress.processed,
+            progress.indexed
+        );
+    }
+    emit(&progress);
+    Ok(progress)
+}
@@ -1,103 +1,644 @@
 use tauri::AppHandle;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::time::Duration;
+use chrono::{Datelike, NaiveDate, Weekday};
 use rusqlite::Connection;
-use serde::{Serialize, Deserialize};
-use tauri::Manager;
+use serde_json::json;
+
+use crate::models::{ActivityMetadata, DetectedPattern, MediaKind, PatternKind};
+use crate::services::service_status::{self, PATTERN_ENGINE};
 
 // Only run pattern analysis every 30 minutes
-const ANALYSIS_INTERVAL_SECS: u64 = 30 * 60; 
+const ANALYSIS_INTERVAL_SECS: u64 = 30 * 60;
+
+/// Routines: an app used for at least this long within an hour counts for that hour.
+const ROUTINE_MIN_SECONDS_PER_HOUR: i64 = 10 * 60;
+const ROUTINE_WEEKDAYS_CHECKED: usize = 5;
+const ROUTINE_MIN_DAYS: usize = 3;
+
+/// Anomalies: today vs the per-day category mix of the previous 30 days.
+const BASELINE_DAYS: i64 = 30;
+const MIN_BASELINE_DAYS: usize = 7;
+/// Days with less tracked time than this are too thin to judge a mix.
+const MIN_DAY_TRACKED_SECONDS: i64 = 60 * 60;
+const ANOMALY_SIGMA: f64 = 2.0;
+/// Floors so a category that is always ~0% doesn't flag on a few minutes.
+const MIN_SHARE_STDDEV: f64 = 0.03;
+const MIN_SHARE_DELTA: f64 = 0.05;
+
+/// Correlations: music during development sessions.
+const DEVELOPMENT_CATEGORY_ID: i64 = 1;
+const MIN_DEV_SECONDS: i64 = 2 * 60 * 60;
+const MIN_MUSIC_SHARE: f64 = 0.25;
+const MIN_MUSIC_SHARE_LIFT: f64 = 0.10;
 
 pub fn start_pattern_engine(app_handle: AppHandle) {
     tauri::async_runtime::spawn(async move {
         println!("[PatternEngine] ⏳ Waiting 60s before first analysis run...");
         tokio::time::sleep(Duration::from_secs(60)).await;
-        
+
         println!("[PatternEngine] ✅ Service started (runs every 30m)");
-        
+        service_status::mark_started(PATTERN_ENGINE);
+
         loop {
             println!("[PatternEngine] 🧠 Running pattern analysis...");
-            if let Err(e) = run_analysis(&app_handle) {
-                println!("[PatternEngine] ❌ Analysis failed: {}", e);
+            match run_analysis(&app_handle) {
+                Ok(()) => service_status::heartbeat(PATTERN_ENGINE),
+                Err(e) => {
+                    println!("[PatternEngine] ❌ Analysis failed: {}", e);
+                    service_status::report_error(PATTERN_ENGINE, &e);
+                }
             }
-            
+
             tokio::time::sleep(Duration::from_secs(ANALYSIS_INTERVAL_SECS)).await;
         }
     });
 }
 
+/// One detector result before it is stored. `key` identifies the same finding across runs.
+struct Finding {
+    key: String,
+    kind: PatternKind,
+    description: String,
+    confidence: f32,
+    supporting_window: String,
+    data: serde_json::Value,
+}
+
 fn run_analysis(app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let data_dir = app_handle.path().app_data_dir()?;
-    let db_path = data_dir.join("intentflow.db");
-    let conn = Connection::open(db_path)?;
-    
-    // 1. Time-of-day Patterns
-    // "User often opens VS Code between 9am-10am"
-    analyze_time_patterns(&conn)?;
-    
+    let conn = crate::database::open(app_handle)?;
+    let today = crate::utils::time::today();
+
+    let mut findings = Vec::new();
+    findings.extend(detect_routines(&conn, today)?);
+    findings.extend(detect_anomalies(&conn, today)?);
+    findings.extend(detect_correlations(&conn, today)?);
+    store_findings(&conn, &findings)?;
+
+    println!("[PatternEngine] Stored {} finding(s)", findings.len());
     Ok(())
 }
 
-#[derive(Serialize, Deserialize)]
-struct TimePatternData {
-    app_name: String,
-    hour: u32,
-    day_type: String,
+fn date_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
 }
 
-fn analyze_time_patterns(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
-    let now = chrono::Utc::now().timestamp();
-    let two_weeks_ago = now - (14 * 24 * 3600);
-    
-    let mut stmt = conn.prepare(r#"
-        SELECT 
-            app_name, 
-            strftime('%H', datetime(start_time, 'unixepoch', 'localtime')) as hour,
-            count(*) as frequency
-        FROM activities 
-        WHERE start_time > ?1
-        GROUP BY app_name, hour
-        HAVING frequency > 5
-        ORDER BY frequency DESC
-    "#)?;
-    
-    let rows = stmt.query_map([two_weeks_ago], |row: &rusqlite::Row| {
-        let app: String = row.get(0)?;
-        let hour_str: String = row.get(1)?;
-        let count: i32 = row.get(2)?;
-        let hour: u32 = hour_str.parse().unwrap_or(0);
-        Ok((app, hour, count))
+/// The `count` most recent Monday–Friday dates before `today`, newest first.
+fn recent_weekdays(today: NaiveDate, count: usize) -> Vec<NaiveDate> {
+    let mut days = Vec::with_capacity(count);
+    let mut day = today;
+    while days.len() < count {
+        day = match day.pred_opt() {
+            Some(d) => d,
+            None => break,
+        };
+        if !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+            days.push(day);
+        }
+    }
+    days
+}
+
+fn format_hour_range(start_hour: u32, end_hour: u32) -> String {
+    format!("{:02}:00–{:02}:00", start_hour, (end_hour + 1) % 24)
+}
+
+/// Apps used at the same hour on at least 3 of the last 5 weekdays, merged
+/// into one finding per run of consecutive hours with the same app cluster.
+fn detect_routines(conn: &Connection, today: NaiveDate) -> Result<Vec<Finding>, Box<dyn std::error::Error>> {
+    let weekdays = recent_weekdays(today, ROUTINE_WEEKDAYS_CHECKED);
+    let (Some(oldest), Some(newest)) = (weekdays.last().copied(), weekdays.first().copied()) else {
+        return Ok(Vec::new());
+    };
+    let wanted: HashSet<String> = weekdays.iter().map(|d| date_key(*d)).collect();
+    let start_ts = crate::utils::time::day_bounds(Some(conn), oldest).start_ts;
+    let end_ts = crate::utils::time::day_bounds(Some(conn), today).start_ts;
+
+    // Bucket each row by the hour in the zone it was captured in, not today's zone.
+    let local = crate::utils::time::ROW_LOCAL_DATETIME_SQL;
+    let sql = format!(
+        "SELECT date({local}) AS day, CAST(strftime('%H', {local}) AS INTEGER) AS hour, app_name, SUM(duration_seconds) AS total
+         FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2 AND app_name != ''
+         GROUP BY day, hour, app_name
+         HAVING total >= ?3"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params![start_ts, end_ts, ROUTINE_MIN_SECONDS_PER_HOUR], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, String>(2)?))
     })?;
-    
+
+    let mut days_seen: HashMap<(u32, String), HashSet<String>> = HashMap::new();
     for row in rows {
-        let (app, hour, count) = row?;
-        
-        let pattern_data = TimePatternData {
-            app_name: app.clone(),
-            hour,
-            day_type: "daily".to_string(),
-        };
-        let json_data = serde_json::to_vec(&pattern_data)?;
-        
-        let confidence = (count as f32 / 10.0).min(0.95);
-        
-        store_pattern(conn, "time_of_day", &json_data, confidence)?;
+        let (day, hour, app) = row?;
+        if wanted.contains(&day) {
+            days_seen.entry((hour, app)).or_default().insert(day);
+        }
     }
-    
-    Ok(())
+
+    // hour -> (apps seen on enough days, fewest days any of them was seen)
+    let mut clusters: BTreeMap<u32, (Vec<String>, usize)> = BTreeMap::new();
+    for ((hour, app), days) in days_seen {
+        if days.len() < ROUTINE_MIN_DAYS {
+            continue;
+        }
+        let entry = clusters.entry(hour).or_insert_with(|| (Vec::new(), usize::MAX));
+        entry.0.push(app);
+        entry.1 = entry.1.min(days.len());
+    }
+    for (apps, _) in clusters.values_mut() {
+        apps.sort();
+    }
+
+    let mut windows: Vec<(u32, u32, Vec<String>, usize)> = Vec::new();
+    for (hour, (apps, days)) in clusters {
+        match windows.last_mut() {
+            Some(last) if last.1 + 1 == hour && last.2 == apps => {
+                last.1 = hour;
+                last.3 = last.3.min(days);
+            }
+            _ => windows.push((hour, hour, apps, days)),
+        }
+    }
+
+    let supporting_window = format!("{} to {} (weekdays)", date_key(oldest), date_key(newest));
+    Ok(windows
+        .into_iter()
+        .map(|(start_hour, end_hour, apps, days)| Finding {
+            key: format!("routine:{}-{}:{}", start_hour, end_hour, apps.join(",").to_lowercase()),
+            kind: PatternKind::Routine,
+            description: format!(
+                "Usually {} around {} on weekdays ({} of the last {})",
+                apps.join(", "),
+                format_hour_range(start_hour, end_hour),
+                days,
+                ROUTINE_WEEKDAYS_CHECKED
+            ),
+            confidence: days as f32 / ROUTINE_WEEKDAYS_CHECKED as f32,
+            supporting_window: supporting_window.clone(),
+            data: json!({
+                "apps": apps,
+                "hour_start": start_hour,
+                "hour_end": end_hour,
+                "days_seen": days,
+                "days_checked": ROUTINE_WEEKDAYS_CHECKED,
+            }),
+        })
+        .collect())
+}
+
+/// Category shares of today's tracked time more than 2σ away from the
+/// previous 30 days.
+fn detect_anomalies(conn: &Connection, today: NaiveDate) -> Result<Vec<Finding>, Box<dyn std::error::Error>> {
+    let today_key = date_key(today);
+    let baseline_start = today - chrono::Duration::days(BASELINE_DAYS);
+    let start_ts = crate::utils::time::day_bounds(Some(conn), baseline_start).start_ts;
+    let end_ts = crate::utils::time::day_bounds(Some(conn), today).end_ts;
+
+    let local = crate::utils::time::ROW_LOCAL_DATETIME_SQL;
+    let sql = format!(
+        "SELECT date({local}) AS day, category_id, SUM(duration_seconds)
+         FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2
+         GROUP BY day, category_id"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params![start_ts, end_ts], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+    })?;
+
+    let mut per_day: BTreeMap<String, HashMap<i64, i64>> = BTreeMap::new();
+    for row in rows {
+        let (day, category_id, seconds) = row?;
+        *per_day.entry(day).or_default().entry(category_id).or_insert(0) += seconds;
+    }
+
+    let Some(today_totals) = per_day.remove(&today_key) else {
+        return Ok(Vec::new());
+    };
+    let today_total: i64 = today_totals.values().sum();
+    let baseline: Vec<(HashMap<i64, i64>, i64)> = per_day
+        .into_values()
+        .map(|totals| {
+            let sum = totals.values().sum();
+            (totals, sum)
+        })
+        .filter(|(_, sum)| *sum >= MIN_DAY_TRACKED_SECONDS)
+        .collect();
+    if today_total < MIN_DAY_TRACKED_SECONDS || baseline.len() < MIN_BASELINE_DAYS {
+        return Ok(Vec::new());
+    }
+
+    let mut categories: HashSet<i64> = today_totals.keys().copied().collect();
+    for (totals, _) in &baseline {
+        categories.extend(totals.keys().copied());
+    }
+
+    let supporting_window = format!("{} vs previous {} days ({} with data)", today_key, BASELINE_DAYS, baseline.len());
+    let mut findings = Vec::new();
+    for category_id in categories {
+        let shares: Vec<f64> = baseline
+            .iter()
+            .map(|(totals, sum)| *totals.get(&category_id).unwrap_or(&0) as f64 / *sum as f64)
+            .collect();
+        let mean = shares.iter().sum::<f64>() / shares.len() as f64;
+        let variance = shares.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / shares.len() as f64;
+        let stddev = variance.sqrt();
+        let share = *today_totals.get(&category_id).unwrap_or(&0) as f64 / today_total as f64;
+        let z = (share - mean) / stddev.max(MIN_SHARE_STDDEV);
+        if z.abs() <= ANOMALY_SIGMA || (share - mean).abs() < MIN_SHARE_DELTA {
+            continue;
+        }
+
+        let name = crate::utils::categories::name_for(category_id);
+        findings.push(Finding {
+            key: format!("anomaly:{}:{}", today_key, category_id),
+            kind: PatternKind::Anomaly,
+            description: format!(
+                "{} is {:.0}% of today's tracked time, {} than the usual {:.0}% (±{:.0}%)",
+                name,
+                share * 100.0,
+                if z > 0.0 { "far more" } else { "far less" },
+                mean * 100.0,
+                stddev * 100.0
+            ),
+            confidence: (z.abs() / (ANOMALY_SIGMA * 2.0)).min(0.95) as f32,
+            supporting_window: supporting_window.clone(),
+            data: json!({
+                "date": today_key,
+                "category_id": category_id,
+                "category": name,
+                "share_today": share,
+                "baseline_mean": mean,
+                "baseline_stddev": stddev,
+                "z_score": z,
+            }),
+        });
+    }
+    Ok(findings)
+}
+
+/// Whether music was playing during development sessions noticeably more often than otherwise.
+fn detect_correlations(conn: &Connection, today: NaiveDate) -> Result<Vec<Finding>, Box<dyn std::error::Error>> {
+    let since = today - chrono::Duration::days(BASELINE_DAYS);
+    let start_ts = crate::utils::time::day_bounds(Some(conn), since).start_ts;
+
+    let mut stmt = conn.prepare(
+        "SELECT category_id, duration_seconds, app_name, COALESCE(window_title, ''), metadata
+         FROM activities
+         WHERE start_time >= ?1",
+    )?;
+    let rows = stmt.query_map([start_ts], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, Option<Vec<u8>>>(4)?,
+        ))
+    })?;
+
+    let (mut dev_total, mut dev_music, mut other_total, mut other_music) = (0i64, 0i64, 0i64, 0i64);
+    let mut dev_artists: HashMap<String, i64> = HashMap::new();
+    for row in rows {
+        let (category_id, seconds, app_name, title, blob) = row?;
+        let playing_artist = blob
+            .and_then(|b| serde_json::from_slice::<ActivityMetadata>(&b).ok())
+            .and_then(|meta| {
+                let media = meta.media_info?;
+                let kind = meta.media_kind.unwrap_or_else(|| {
+                    crate::utils::media::classify_media(&media, &app_name, &title, seconds)
+                });
+                (kind == MediaKind::Music && media.status.eq_ignore_ascii_case("playing"))
+                    .then(|| media.artist.trim().to_string())
+            });
+
+        if category_id == DEVELOPMENT_CATEGORY_ID {
+            dev_total += seconds;
+            if let Some(artist) = playing_artist {
+                dev_music += seconds;
+                if !artist.is_empty() {
+                    *dev_artists.entry(artist).or_insert(0) += seconds;
+                }
+            }
+        } else {
+            other_total += seconds;
+            if playing_artist.is_some() {
+                other_music += seconds;
+            }
+        }
+    }
+
+    if dev_total < MIN_DEV_SECONDS {
+        return Ok(Vec::new());
+    }
+    let dev_share = dev_music as f64 / dev_total as f64;
+    let other_share = if other_total > 0 { other_music as f64 / other_total as f64 } else { 0.0 };
+    if dev_share < MIN_MUSIC_SHARE || dev_share < other_share + MIN_MUSIC_SHARE_LIFT {
+        return Ok(Vec::new());
+    }
+
+    let mut artists: Vec<(String, i64)> = dev_artists.into_iter().collect();
+    artists.sort_by(|a, b| b.1.cmp(&a.1));
+    let top_artists: Vec<String> = artists.into_iter().take(3).map(|(name, _)| name).collect();
+    let artist_note = if top_artists.is_empty() {
+        String::new()
+    } else {
+        format!("; mostly {}", top_artists.join(", "))
+    };
+
+    Ok(vec![Finding {
+        key: "correlation:music_during_development".to_string(),
+        kind: PatternKind::Correlation,
+        description: format!(
+            "Music is playing during {:.0}% of development time vs {:.0}% otherwise{}",
+            dev_share * 100.0,
+            other_share * 100.0,
+            artist_note
+        ),
+        confidence: dev_share.min(0.95) as f32,
+        supporting_window: format!("last {} days", BASELINE_DAYS),
+        data: json!({
+            "dev_seconds": dev_total,
+            "dev_music_share": dev_share,
+            "other_music_share": other_share,
+            "top_artists": top_artists,
+        }),
+    }])
 }
 
-fn store_pattern(conn: &Connection, p_type: &str, data: &[u8], confidence: f32) -> Result<(), Box<dyn std::error::Error>> {
-    conn.execute(
-        "INSERT INTO patterns (pattern_type, pattern_data, confidence, last_observed, occurrence_count, is_active)
-         VALUES (?1, ?2, ?3, ?4, ?5, 1)",
-        rusqlite::params![
-            p_type,
-            data,
-            confidence,
-            chrono::Utc::now().timestamp(),
-            1,
-        ],
+/// Upsert this run's findings. Routines and correlations that weren't
+/// re-detected go inactive; anomalies describe a past day and stay until
+/// they age out of the baseline window.
+fn store_findings(conn: &Connection, findings: &[Finding]) -> Result<(), Box<dyn std::error::Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "UPDATE patterns SET is_active = 0 WHERE kind IN ('routine', 'correlation')",
+        [],
+    )?;
+    tx.execute(
+        "DELETE FROM patterns WHERE kind = 'anomaly' AND detected_at < ?1",
+        [now - BASELINE_DAYS * 86_400],
     )?;
-    
+
+    for finding in findings {
+        let data = serde_json::to_vec(&finding.data)?;
+        tx.execute(
+            "INSERT INTO patterns
+             (pattern_key, pattern_type, kind, description, pattern_data, confidence,
+              supporting_window, detected_at, last_observed, occurrence_count, is_active, dismissed)
+             VALUES (?1, ?2, ?2, ?3, ?4, ?5, ?6, ?7, ?7, 1, 1, 0)
+             ON CONFLICT(pattern_key) DO UPDATE SET
+                description = excluded.description,
+                pattern_data = excluded.pattern_data,
+                confidence = excluded.confidence,
+                supporting_window = excluded.supporting_window,
+                last_observed = excluded.last_observed,
+                occurrence_count = occurrence_count + 1,
+                is_active = 1",
+            rusqlite::params![
+                &finding.key,
+                finding.kind.as_str(),
+                &finding.description,
+                &data,
+                finding.confidence,
+                &finding.supporting_window,
+                now,
+            ],
+        )?;
+    }
+
+    tx.commit()?;
     Ok(())
 }
+
+/// Active findings, most confident first.
+pub fn load_patterns(
+    conn: &Connection,
+    kind: Option<PatternKind>,
+    include_dismissed: bool,
+) -> rusqlite::Result<Vec<DetectedPattern>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, description, confidence, COALESCE(supporting_window, ''),
+                COALESCE(detected_at, last_observed), last_observed, dismissed, pattern_data
+         FROM patterns
+         WHERE is_active = 1 AND kind IS NOT NULL
+           AND (?1 IS NULL OR kind = ?1)
+           AND (?2 = 1 OR dismissed = 0)
+         ORDER BY confidence DESC, last_observed DESC",
+    )?;
+    let rows = stmt.query_map(
+        rusqlite::params![kind.map(|k| k.as_str()), include_dismissed as i64],
+        |row| {
+            // Skip kinds written by a newer build.
+            let Some(kind) = PatternKind::parse(&row.get::<_, String>(1)?) else {
+                return Ok(None);
+            };
+            let data: Vec<u8> = row.get(8)?;
+            Ok(Some(DetectedPattern {
+                id: row.get(0)?,
+                kind,
+                description: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                confidence: row.get(3)?,
+                supporting_window: row.get(4)?,
+                detected_at: row.get(5)?,
+                last_observed: row.get(6)?,
+                dismissed: row.get::<_, i64>(7)? != 0,
+                data: serde_json::from_slice(&data).unwrap_or(serde_json::Value::Null),
+            }))
+        },
+    )?;
+    let patterns = rows.collect::<Result<Vec<_>, _>>()?;
+    Ok(patterns.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+    use crate::models::MediaInfo;
+
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// One row at `hour:minute` UTC on `day`, stored with a zero offset so
+    /// local days and hours are the UTC ones whatever zone the tests run in.
+    fn insert(conn: &Connection, day: NaiveDate, hour: u32, minute: u32, app: &str, category_id: i64, seconds: i64) {
+        insert_with(conn, day, hour, minute, app, category_id, seconds, ActivityMetadata::default());
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert_with(
+        conn: &Connection,
+        day: NaiveDate,
+        hour: u32,
+        minute: u32,
+        app: &str,
+        category_id: i64,
+        seconds: i64,
+        metadata: ActivityMetadata,
+    ) {
+        let start = day.and_hms_opt(hour, minute, 0).unwrap().and_utc().timestamp();
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata, tz_offset_minutes)
+             VALUES (?1, 1, 'w', ?2, ?3, ?3 + ?4, ?4, ?5, 0)",
+            rusqlite::params![app, category_id, start, seconds, serde_json::to_vec(&metadata).unwrap()],
+        )
+        .unwrap();
+    }
+
+    fn music(artist: &str) -> ActivityMetadata {
+        ActivityMetadata {
+            media_info: Some(MediaInfo {
+                title: "Track".to_string(),
+                artist: artist.to_string(),
+                status: "Playing".to_string(),
+                source_app: Some("Spotify.exe".to_string()),
+            }),
+            media_kind: Some(MediaKind::Music),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn weekday_routines_need_three_of_the_last_five() {
+        let conn = test_connection();
+        // Friday; the five weekdays checked are Mar 6 and Mar 9–12.
+        let today = date(2026, 3, 13);
+        insert(&conn, today, 8, 0, "Code", 1, 60);
+        for day in 9..=12 {
+            for hour in [9, 10] {
+                insert(&conn, date(2026, 3, day), hour, 0, "Code", 1, 30 * 60);
+                insert(&conn, date(2026, 3, day), hour, 30, "Slack", 3, 15 * 60);
+            }
+        }
+        // Only two days at this hour.
+        for day in [10, 11] {
+            insert(&conn, date(2026, 3, day), 14, 0, "Spotify", 4, 30 * 60);
+        }
+        // Every day, but too briefly to count.
+        for day in [6, 9, 10, 11, 12] {
+            insert(&conn, date(2026, 3, day), 16, 0, "Notes", 5, 5 * 60);
+        }
+        // Evenings on the weekend don't make a weekday routine.
+        for day in [6, 7, 8] {
+            insert(&conn, date(2026, 3, day), 20, 0, "Chrome", 2, 60 * 60);
+        }
+
+        let findings = detect_routines(&conn, today).unwrap();
+        assert_eq!(findings.len(), 1);
+        let routine = &findings[0];
+        assert_eq!(routine.key, "routine:9-10:code,slack");
+        assert_eq!(routine.confidence, 0.8);
+        assert_eq!(
+            routine.description,
+            "Usually Code, Slack around 09:00–11:00 on weekdays (4 of the last 5)"
+        );
+        assert_eq!(routine.supporting_window, "2026-03-06 to 2026-03-12 (weekdays)");
+    }
+
+    fn baseline_month(conn: &Connection, today: NaiveDate, days: i64) {
+        for back in 1..=days {
+            let day = today - chrono::Duration::days(back);
+            insert(conn, day, 9, 0, "Code", 1, 8000 + (back % 3) * 400);
+            insert(conn, day, 12, 0, "Chrome", 2, 4000);
+            insert(conn, day, 14, 0, "Slack", 3, 1500);
+        }
+    }
+
+    #[test]
+    fn category_mix_far_from_the_baseline_is_an_anomaly() {
+        let conn = test_connection();
+        let today = date(2026, 3, 31);
+        baseline_month(&conn, today, 30);
+        insert(&conn, today, 9, 0, "Code", 1, 3000);
+        insert(&conn, today, 10, 0, "YouTube", 4, 6000);
+        insert(&conn, today, 12, 0, "Chrome", 2, 3800);
+        insert(&conn, today, 14, 0, "Slack", 3, 1300);
+
+        let mut findings = detect_anomalies(&conn, today).unwrap();
+        findings.sort_by(|a, b| a.key.cmp(&b.key));
+        let keys: Vec<&str> = findings.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, vec!["anomaly:2026-03-31:1", "anomaly:2026-03-31:4"]);
+        assert!(findings[0].description.contains("far less"));
+        assert!(findings[1].description.contains("far more"));
+        assert!(findings[1].data["z_score"].as_f64().unwrap() > ANOMALY_SIGMA);
+        assert_eq!(findings[0].supporting_window, "2026-03-31 vs previous 30 days (30 with data)");
+    }
+
+    #[test]
+    fn usual_days_and_thin_baselines_raise_no_anomaly() {
+        let conn = test_connection();
+        let today = date(2026, 3, 31);
+        baseline_month(&conn, today, 30);
+        insert(&conn, today, 9, 0, "Code", 1, 8200);
+        insert(&conn, today, 12, 0, "Chrome", 2, 4000);
+        insert(&conn, today, 14, 0, "Slack", 3, 1500);
+        assert!(detect_anomalies(&conn, today).unwrap().is_empty());
+
+        // Six days of history isn't a baseline yet.
+        let conn = test_connection();
+        baseline_month(&conn, today, MIN_BASELINE_DAYS as i64 - 1);
+        insert(&conn, today, 10, 0, "YouTube", 4, 6000);
+        assert!(detect_anomalies(&conn, today).unwrap().is_empty());
+    }
+
+    #[test]
+    fn music_mostly_during_development_is_a_correlation() {
+        let conn = test_connection();
+        let today = date(2026, 3, 31);
+        for back in 1..=21 {
+            let day = today - chrono::Duration::days(back);
+            let artist = if back % 3 == 0 { "Tycho" } else { "M83" };
+            insert_with(&conn, day, 9, 0, "Code", 1, 90 * 60, music(artist));
+            insert(&conn, day, 11, 0, "Code", 1, 30 * 60);
+            insert_with(&conn, day, 13, 0, "Chrome", 2, 10 * 60, music("M83"));
+            insert(&conn, day, 14, 0, "Slack", 3, 110 * 60);
+        }
+
+        let findings = detect_correlations(&conn, today).unwrap();
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.key, "correlation:music_during_development");
+        assert_eq!(finding.data["dev_music_share"], 0.75);
+        assert_eq!(finding.data["top_artists"], json!(["M83", "Tycho"]));
+        assert!(finding.description.starts_with("Music is playing during 75% of development time vs 8% otherwise"));
+    }
+
+    #[test]
+    fn music_everywhere_is_no_correlation() {
+        let conn = test_connection();
+        let today = date(2026, 3, 31);
+        for back in 1..=21 {
+            let day = today - chrono::Duration::days(back);
+            insert_with(&conn, day, 9, 0, "Code", 1, 60 * 60, music("M83"));
+            insert(&conn, day, 11, 0, "Code", 1, 60 * 60);
+            insert_with(&conn, day, 13, 0, "Chrome", 2, 60 * 60, music("M83"));
+            insert(&conn, day, 14, 0, "Slack", 3, 60 * 60);
+        }
+        assert!(detect_correlations(&conn, today).unwrap().is_empty());
+    }
+
+    #[test]
+    fn routines_not_seen_again_go_inactive() {
+        let conn = test_connection();
+        let finding = |key: &str| Finding {
+            key: key.to_string(),
+            kind: PatternKind::Routine,
+            description: key.to_string(),
+            confidence: 0.6,
+            supporting_window: String::new(),
+            data: json!({}),
+        };
+        store_findings(&conn, &[finding("routine:a"), finding("routine:b")]).unwrap();
+        store_findings(&conn, &[finding("routine:b")]).unwrap();
+
+        let patterns = load_patterns(&conn, Some(PatternKind::Routine), false).unwrap();
+        let descriptions: Vec<&str> = patterns.iter().map(|p| p.description.as_str()).collect();
+        assert_eq!(descriptions, vec!["routine:b"]);
+        assert!(load_patterns(&conn, Some(PatternKind::Anomaly), true).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use rusqlite::Connection;
+use tauri::AppHandle;
+
+use crate::services::service_status::{self, PROCESS_MONITOR};
+use crate::utils::windows::ProcessSnapshotEntry;
+
+// ─── Process lifetimes (opt-in) ───
+// Focus tracking only sees the foreground window, so it can't tell how long
+// Docker or a dev server kept running in the background. This takes a process
+// snapshot every 30s and records start/stop of the executables on
+// `settings.tracking.process_watch_list` into `process_events`. Empty list
+// (the default) means nothing is snapshotted at all.
+
+/// Snapshots are never taken more often than this.
+const SNAPSHOT_INTERVAL_SECS: u64 = 30;
+
+/// Incognito, Game Mode, demo mode or tracking disabled; set from `apply_monitoring_state`.
+static SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+fn watch_list_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+pub fn set_suppressed(suppressed: bool) {
+    SUPPRESSED.store(suppressed, Ordering::Relaxed);
+}
+
+fn matches_watch_list(watch_list: &[String], process_name: &str) -> bool {
+    let name = process_name.to_lowercase();
+    watch_list.iter().any(|entry| name.contains(entry.as_str()))
+}
+
+/// Identifies one run of a process. The creation time tells a reused pid apart;
+/// for processes it couldn't be read from, the first sighting stands in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ProcessKey {
+    pid: u32,
+    created_at: i64,
+}
+
+#[derive(Debug, Clone)]
+struct TrackedProcess {
+    row_id: i64,
+    name: String,
+    /// Whether `created_at` in the key came from the OS.
+    created_known: bool,
+    last_seen: i64,
+}
+
+#[derive(Debug, Default)]
+struct ProcessDiff {
+    /// Newly seen runs with the key they are tracked under.
+    started: Vec<(ProcessKey, ProcessSnapshotEntry)>,
+    /// Tracked runs missing from the snapshot.
+    stopped: Vec<ProcessKey>,
+    /// Tracked runs still present.
+    still_running: Vec<ProcessKey>,
+}
+
+/// Compare a (watch-list filtered) snapshot with the tracked runs.
+fn diff_snapshot(tracked: &HashMap<ProcessKey, TrackedProcess>, snapshot: &[ProcessSnapshotEntry], now: i64) -> ProcessDiff {
+    let mut diff = ProcessDiff::default();
+    for entry in snapshot {
+        let key = match entry.start_time {
+            Some(created_at) => ProcessKey { pid: entry.pid, created_at },
+            // Without a creation time, the run already tracked under this pid and name is the same one.
+            None => tracked
+                .iter()
+                .find(|(key, t)| key.pid == entry.pid && !t.created_known && t.name.eq_ignore_ascii_case(&entry.name))
+                .map(|(key, _)| *key)
+                .unwrap_or(ProcessKey { pid: entry.pid, created_at: now }),
+        };
+        if tracked.contains_key(&key) {
+            diff.still_running.push(key);
+        } else if !diff.started.iter().any(|(k, _)| *k == key) {
+            diff.started.push((key, entry.clone()));
+        }
+    }
+    diff.stopped = tracked
+        .keys()
+        .filter(|key| !diff.still_running.contains(key))
+        .copied()
+        .collect();
+    diff
+}
+
+struct MonitorState {
+    tracked: HashMap<ProcessKey, TrackedProcess>,
+    /// Runs first seen after this (app start or the end of a suppression) can't
+    /// be recorded as starting earlier, so suppressed time is never filled in.
+    observing_since: Option<i64>,
+}
+
+pub fn start_process_monitor(app_handle: AppHandle) {
+    crate::services::settings_bus::spawn_subscriber(&app_handle, "process_monitor", |settings| {
+        let list: Vec<String> = settings
+            .tracking
+            .process_watch_list
+            .iter()
+            .map(|a| a.trim().to_lowercase())
+            .filter(|a| !a.is_empty())
+            .collect();
+        service_status::set_enabled(PROCESS_MONITOR, settings.tracking.enabled && !list.is_empty());
+        if let Ok(mut store) = watch_list_store().lock() {
+            *store = list;
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        service_status::mark_started(PROCESS_MONITOR);
+        let mut state = MonitorState {
+            tracked: HashMap::new(),
+            observing_since: None,
+        };
+        match crate::database::open(&app_handle) {
+            Ok(conn) => match load_open_runs(&conn) {
+                Ok(open) => state.tracked = open,
+                Err(e) => service_status::report_error(PROCESS_MONITOR, e),
+            },
+            Err(e) => service_status::report_error(PROCESS_MONITOR, e),
+        }
+
+        loop {
+            service_status::heartbeat(PROCESS_MONITOR);
+            let now = chrono::Utc::now().timestamp();
+            let watch_list = watch_list_store().lock().map(|l| l.clone()).unwrap_or_default();
+            let active = !SUPPRESSED.load(Ordering::Relaxed)
+                && !crate::services::tracking_pause::is_paused()
+                && !watch_list.is_empty();
+
+            if !active {
+                // Nothing is observed while suppressed, so open runs end where they were last seen.
+                if !state.tracked.is_empty() {
+                    if let Ok(conn) = crate::database::open(&app_handle) {
+                        close_all(&conn, &mut state.tracked);
+                    }
+                }
+                state.observing_since = Some(now);
+            } else {
+                let list = watch_list.clone();
+                let snapshot = tokio::task::spawn_blocking(move || {
+                    crate::utils::windows::snapshot_processes(&|name| matches_watch_list(&list, name))
+                })
+                .await
+                .unwrap_or_default();
+                let result = crate::database::open(&app_handle)
+                    .map_err(|e| e.to_string())
+                    .and_then(|conn| apply_snapshot(&conn, &mut state, &watch_list, &snapshot, now));
+                if let Err(e) = result {
+                    println!("[Process] Failed to record snapshot: {}", e);
+                    service_status::report_error(PROCESS_MONITOR, e);
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(SNAPSHOT_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+/// Runs left open by the previous app session; the first snapshot closes the ones that are gone.
+fn load_open_runs(conn: &Connection) -> Result<HashMap<ProcessKey, TrackedProcess>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, process_name, pid, created_at, start_time, last_seen
+             FROM process_events WHERE end_time IS NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            let created_at: Option<i64> = row.get(3)?;
+            let start_time: i64 = row.get(4)?;
+            Ok((
+                ProcessKey {
+                    pid: row.get::<_, i64>(2)? as u32,
+                    created_at: created_at.unwrap_or(start_time),
+                },
+                TrackedProcess {
+                    row_id: row.get(0)?,
+                    name: row.get(1)?,
+                    created_known: created_at.is_some(),
+                    last_seen: row.get(5)?,
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn apply_snapshot(
+    conn: &Connection,
+    state: &mut MonitorState,
+    watch_list: &[String],
+    snapshot: &[ProcessSnapshotEntry],
+    now: i64,
+) -> Result<(), String> {
+    let parents: HashMap<u32, &str> = snapshot.iter().map(|p| (p.pid, p.name.as_str())).collect();
+    let watched: Vec<ProcessSnapshotEntry> = snapshot
+        .iter()
+        .filter(|p| matches_watch_list(watch_list, &p.name))
+        .cloned()
+        .collect();
+    let diff = diff_snapshot(&state.tracked, &watched, now);
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for key in &diff.stopped {
+        if let Some(run) = state.tracked.remove(key) {
+            // It exited some time since the last snapshot; after a gap that is all we know.
+            let end_time = if now - run.last_seen <= 2 * SNAPSHOT_INTERVAL_SECS as i64 { now } else { run.last_seen };
+            tx.execute(
+                "UPDATE process_events SET end_time = ?1 WHERE id = ?2",
+                rusqlite::params![end_time, run.row_id],
+            )
+            .map_err(|e| e.to_string())?;
+            println!("[Process] {} (pid {}) stopped", run.name, key.pid);
+        }
+    }
+    for (key, entry) in &diff.started {
+        let start_time = match state.observing_since {
+            Some(since) => key.created_at.max(since),
+            None => key.created_at,
+        };
+        tx.execute(
+            "INSERT INTO process_events
+             (process_name, pid, parent_pid, parent_name, created_at, start_time, end_time, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7)",
+            rusqlite::params![
+                entry.name,
+                entry.pid as i64,
+                entry.parent_pid as i64,
+                parents.get(&entry.parent_pid).copied(),
+                entry.start_time,
+                start_time,
+                now
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        state.tracked.insert(
+            *key,
+            TrackedProcess {
+                row_id: tx.last_insert_rowid(),
+                name: entry.name.clone(),
+                created_known: entry.start_time.is_some(),
+                last_seen: now,
+            },
+        );
+        println!("[Process] {} (pid {}) started", entry.name, entry.pid);
+    }
+    for key in &diff.still_running {
+        if let Some(run) = state.tracked.get_mut(key) {
+            run.last_seen = now;
+        }
+    }
+    tx.execute(
+        "UPDATE process_events SET last_seen = ?1 WHERE end_time IS NULL",
+        [now],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    state.observing_since = None;
+    Ok(())
+}
+
+fn close_all(conn: &Connection, tracked: &mut HashMap<ProcessKey, TrackedProcess>) {
+    for (_, run) in tracked.drain() {
+        let _ = conn.execute(
+            "UPDATE process_events SET end_time = last_seen WHERE id = ?1",
+            [run.row_id],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+
+
+    fn entry(pid: u32, name: &str, start_time: Option<i64>) -> ProcessSnapshotEntry {
+        ProcessSnapshotEntry {
+            pid,
+            parent_pid: 1,
+            name: name.to_string(),
+            start_time,
+        }
+    }
+
+    fn state() -> MonitorState {
+        MonitorState {
+            tracked: HashMap::new(),
+            observing_since: None,
+        }
+    }
+
+    fn runs(conn: &Connection) -> Vec<(String, i64, i64, Option<i64>)> {
+        conn.prepare("SELECT process_name, pid, start_time, end_time FROM process_events ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn reused_pids_are_told_apart_by_creation_time() {
+        let tracked_run = |created_known| TrackedProcess {
+            row_id: 1,
+            name: "node.exe".to_string(),
+            created_known,
+            last_seen: 100,
+        };
+        let mut tracked = HashMap::new();
+        tracked.insert(ProcessKey { pid: 42, created_at: 50 }, tracked_run(true));
+
+        // Same pid, later creation time: the old run stopped and a new one started.
+        let diff = diff_snapshot(&tracked, &[entry(42, "node.exe", Some(120))], 130);
+        assert_eq!(diff.stopped, vec![ProcessKey { pid: 42, created_at: 50 }]);
+        assert_eq!(diff.started.len(), 1);
+        assert_eq!(diff.started[0].0, ProcessKey { pid: 42, created_at: 120 });
+        assert!(diff.still_running.is_empty());
+
+        let diff = diff_snapshot(&tracked, &[entry(42, "node.exe", Some(50))], 130);
+        assert_eq!(diff.still_running, vec![ProcessKey { pid: 42, created_at: 50 }]);
+        assert!(diff.started.is_empty() && diff.stopped.is_empty());
+    }
+
+    #[test]
+    fn unknown_creation_times_follow_pid_and_name() {
+        let mut tracked = HashMap::new();
+        tracked.insert(
+            ProcessKey { pid: 7, created_at: 100 },
+            TrackedProcess {
+                row_id: 1,
+                name: "Docker Desktop.exe".to_string(),
+                created_known: false,
+                last_seen: 100,
+            },
+        );
+        let diff = diff_snapshot(&tracked, &[entry(7, "docker desktop.exe", None)], 130);
+        assert_eq!(diff.still_running, vec![ProcessKey { pid: 7, created_at: 100 }]);
+
+        // A different executable under the reused pid is a new run keyed by first sighting.
+        let diff = diff_snapshot(&tracked, &[entry(7, "python.exe", None), entry(7, "python.exe", None)], 130);
+        assert_eq!(diff.stopped, vec![ProcessKey { pid: 7, created_at: 100 }]);
+        assert_eq!(diff.started.len(), 1);
+        assert_eq!(diff.started[0].0, ProcessKey { pid: 7, created_at: 130 });
+    }
+
+    #[test]
+    fn snapshots_record_starts_stops_and_gaps() {
+        let conn = test_connection();
+        let watch = vec!["docker".to_string(), "node".to_string()];
+        let mut state = state();
+        let explorer = ProcessSnapshotEntry {
+            pid: 1,
+            parent_pid: 0,
+            name: "explorer.exe".to_string(),
+            start_time: Some(0),
+        };
+
+        apply_snapshot(
+            &conn,
+            &mut state,
+            &watch,
+            &[explorer.clone(), entry(10, "Docker Desktop.exe", Some(90)), entry(11, "node.exe", Some(95))],
+            100,
+        )
+        .unwrap();
+        assert_eq!(state.tracked.len(), 2);
+        let parent: String = conn
+            .query_row("SELECT parent_name FROM process_events WHERE pid = 10", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(parent, "explorer.exe");
+
+        // node exits between snapshots and ends at the snapshot that missed it.
+        apply_snapshot(&conn, &mut state, &watch, &[explorer.clone(), entry(10, "Docker Desktop.exe", Some(90))], 130)
+            .unwrap();
+        // After a long gap, Docker's run ends where it was last seen.
+        apply_snapshot(&conn, &mut state, &watch, &[explorer], 1_000).unwrap();
+
+        assert_eq!(
+            runs(&conn),
+            vec![
+                ("Docker Desktop.exe".to_string(), 10, 90, Some(130)),
+                ("node.exe".to_string(), 11, 95, Some(130)),
+            ]
+        );
+        assert!(state.tracked.is_empty());
+    }
+
+    #[test]
+    fn runs_seen_after_suppression_start_when_observing_resumed() {
+        let conn = test_connection();
+        let watch = vec!["docker".to_string()];
+        let mut state = state();
+        apply_snapshot(&conn, &mut state, &watch, &[entry(10, "docker.exe", Some(100))], 130).unwrap();
+        close_all(&conn, &mut state.tracked);
+        state.observing_since = Some(500);
+
+        apply_snapshot(&conn, &mut state, &watch, &[entry(10, "docker.exe", Some(100))], 530).unwrap();
+        assert_eq!(
+            runs(&conn),
+            vec![
+                ("docker.exe".to_string(), 10, 100, Some(130)),
+                ("docker.exe".to_string(), 10, 500, None),
+            ]
+        );
+        assert_eq!(state.observing_since, None);
+
+        // A restart picks the open run up again instead of inserting another.
+        let mut restarted = MonitorState {
+            tracked: load_open_runs(&conn).unwrap(),
+            observing_since: None,
+        };
+        apply_snapshot(&conn, &mut restarted, &watch, &[entry(10, "docker.exe", Some(100))], 560).unwrap();
+        assert_eq!(runs(&conn).len(), 2);
+    }
+}
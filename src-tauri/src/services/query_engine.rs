@@ -1,16 +1,23 @@
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use crate::models::{Settings, ActivityMetadata};
-use tauri::{Manager, Emitter};
-use chrono::{Datelike, Duration, Local, TimeZone};
+use crate::error::AppError;
+use crate::models::{Settings, ActivityMetadata, AgentSettings, ChatStatus, EvidenceStrictness, AGENT_MAX_TURNS_RANGE, LlmUsage, MediaKind, ModelCapabilities, TimeAccounting, TimeShare};
+use crate::services::llm_client;
+use crate::utils::i18n::{Lang, Msg};
+use tauri::Emitter;
+use chrono::{Datelike, Duration};
 use std::time::Duration as StdDuration;
 
 // ─── Constants ───
 
-const MAX_TURNS: usize = 20;
 const MAX_TOOL_RETRY_LOOPS: usize = 3;
+/// Scanning tools report progress once per this many rows.
+const PROGRESS_EVERY_ROWS: usize = 500;
 const LLM_TIMEOUT_SECS: u64 = 60;
+/// Seeded categories the OCR tools are narrowed to for chat and code questions.
+const CODE_CATEGORY_ID: i64 = 1;
+const CHAT_CATEGORY_ID: i64 = 3;
 
 // ─── Types ───
 
@@ -32,6 +39,8 @@ pub struct ChatMessage {
 #[derive(Deserialize)]
 struct ChatResponse {
     choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<llm_client::ProviderUsage>,
 }
 
 #[derive(Deserialize)]
@@ -49,7 +58,11 @@ struct ChatRecvMessage {
 // For streaming
 #[derive(Deserialize)]
 struct ChatStreamResponse {
+    #[serde(default)]
     choices: Vec<ChatStreamChoice>,
+    /// Sent in the last chunk by providers that report stream usage.
+    #[serde(default)]
+    usage: Option<llm_client::ProviderUsage>,
 }
 
 #[derive(Deserialize)]
@@ -69,87 +82,182 @@ struct ChatStreamDelta {
 // ─── Agent Logic ───
 
 // We define the agent tools and instructions here
-const AGENT_SYSTEM_PROMPT: &str = r#"You are IntentFlow's AI activity analyst — a smart, conversational assistant embedded inside the desktop app.
+/// Chat messages passed verbatim ahead of the question; older ones are left to `search_chat_history`.
+pub const CHAT_HISTORY_PREFIX_MESSAGES: usize = 4;
+
+const AGENT_PROMPT_INTRO: &str = r#"You are IntentFlow's AI activity analyst — a smart, conversational assistant embedded inside the desktop app.
 You have access to the user's activity history (apps, windows, duration, time) and OCR screen text.
 
-## Your Tools
-1. `get_music_history` - For finding songs/music
-   - Args: hours (default 24), limit (default 50)
-   - Returns formatted list of songs with title, artist, app, and time
+"#;
 
-2. `get_recent_activities` - For events/tasks/recent activity timeline
+/// Tool documentation listed in the system prompt, in presentation order.
+/// Disabled tools (see `AISettings::disabled_tools`) are left out of the prompt.
+const AGENT_TOOL_DOCS: &[(&str, &str)] = &[
+    (
+        "get_music_history",
+        r#"For finding songs/music
+   - Args: hours (default 24), limit (default 50)
+   - Returns formatted list of songs with title, artist, app, and time"#,
+    ),
+    (
+        "get_recent_activities",
+        r#"For events/tasks/recent activity timeline
    - Args: hours (default 24), limit (default 100), category_id (optional)
-   - Returns chronological activity events with app, title, category, duration, and time
-
-3. `query_activities` - SQL queries on the `activities` table
-   - Fields: app_name, window_title, start_time (unix timestamp), duration_seconds, category_id, metadata
-   - metadata.media_info contains {title, artist, status} for music
-
-4. `get_usage_stats` - Aggregated stats by app
-   - Args: start_time_iso, end_time_iso
-
-5. `search_ocr` - Search screen text content
-   - Args: keyword, limit (default 100)
-
-6. `get_recent_ocr` - Browse recent OCR captures (including chats) without exact keyword
-   - Args: hours (default 24), limit (default 100), app (optional), keyword (optional)
-   - Returns recent OCR snippets with app and timestamp
-
-7. `get_recent_file_changes` - Recent code/document file changes from monitored project roots
+   - Returns chronological activity events with app, title, category, duration, and time"#,
+    ),
+    (
+        "query_activities",
+        r#"SQL queries on the `activities` table
+   - Fields: id, app_name, window_title, start_time (unix timestamp), duration_seconds, category_id, metadata
+   - Select `id` when returning individual rows so they can be cited
+   - metadata.media_info contains {title, artist, status} for music"#,
+    ),
+    (
+        "get_usage_stats",
+        r#"Aggregated stats by app
+   - Args: start_time_iso, end_time_iso"#,
+    ),
+    (
+        "compare_usage",
+        r#"Compare usage across two time ranges (e.g. this week vs last week)
+   - Args: range_a, range_b — each either {scope: "today"|"yesterday"|"this_week"|"last_week"|"this_month"|"last_month"|"last_7_days"|"last_30_days"|"this_year"|a date expression like "june", "q2 2024", "2024-03-15"} or {start_time_iso, end_time_iso}, plus an optional label
+   - Optional filters: category_id, app (substring match)
+   - Returns compact JSON per range (total, per-day average, categories, top apps) and deltas of range_a relative to range_b, including percent change of the per-day average"#,
+    ),
+    (
+        "search_ocr",
+        r#"Search screen text content
+   - Args: keyword, limit (default 100), category_id or categories (optional, e.g. 3 for chat apps, 1 for code editors)
+   - Filter by category whenever the question is about one kind of app; it is much faster than scanning everything"#,
+    ),
+    (
+        "get_recent_ocr",
+        r#"Browse recent OCR captures (including chats) without exact keyword
+   - Args: hours (default 24), limit (default 100), app (optional), keyword (optional), category_id or categories (optional)
+   - Returns recent OCR snippets with app and timestamp"#,
+    ),
+    (
+        "search_clipboard",
+        r#"Search copied text (opt-in clipboard history; empty unless the user enabled it)
+   - Args: keyword (optional), hours (default 24), limit (default 30)
+   - Returns copied snippets with the app that was active and the time"#,
+    ),
+    (
+        "get_recent_file_changes",
+        r#"Recent code/document file changes from monitored project roots
    - Args: hours (default 24), limit (default 40), change_type (optional: created|modified|deleted)
-   - Returns recent file change events with project root and timestamp
-
-8. `parallel_search` - Run multiple tool calls in parallel for broader coverage
+   - Returns recent file change events with project root and timestamp"#,
+    ),
+    (
+        "get_process_runtime",
+        r#"How long watched background processes (dev servers, Docker, builds) were running, regardless of focus
+   - Args: app (optional, substring of the executable name), hours (default 24)
+   - Only apps on the user's process watch list are recorded; empty otherwise
+   - Returns each run with start, end (or still running), duration and parent process, plus running time per executable within the range"#,
+    ),
+    (
+        "get_detected_patterns",
+        r#"Habits and findings precomputed by the background pattern engine over the last 30 days
+   - Args: kind (optional: routine|anomaly|correlation)
+   - routine = usual apps per hour on weekdays, anomaly = today's category mix vs baseline, correlation = e.g. music during development
+   - Returns each finding with confidence and the window it is based on"#,
+    ),
+    (
+        "get_meetings",
+        r#"Meetings detected from Teams, Zoom and Google Meet window titles (clustered, at least 10 minutes each)
+   - Args: hours (default 24)
+   - Returns each meeting with name, start, time spent, approximate attendees and app, plus the total meeting time"#,
+    ),
+    (
+        "search_chat_history",
+        r#"Search earlier messages of your conversations with the user
+   - Args: keyword (optional), role (optional: user|assistant), session (optional: current|all, default all), start_date / end_date (optional, YYYY-MM-DD), days (optional, instead of dates), limit (default 20)
+   - Not bound to the selected time scope; only the last few messages are in your context, so use this for anything earlier
+   - Returns matching message snippets, newest first, with role, session title and time"#,
+    ),
+    (
+        "get_entries",
+        r#"The user's own tasks, notes and goals entered in the app (archived ones are left out)
+   - Args: status (optional: active|completed), entry_type (optional: task|note|goal), limit (default 50)
+   - Not bound to the selected time scope
+   - Returns each entry with type, title, status, due hint taken from its text, a content snippet and when it was created"#,
+    ),
+    (
+        "parallel_search",
+        r#"Run multiple tool calls in parallel for broader coverage
    - Args: calls = [{tool: "...", args: {...}}, ...]
-   - Use for complex queries that need combining activity + OCR + music evidence quickly
-
-9. `resolve_query_scope` - Widen the time range or request additional data sources
-   - Args: suggested_scope (one of: "today", "yesterday", "last_3_days", "last_7_days", "last_30_days", "this_year", "all_time"), enable_sources (optional array of: "apps", "screen", "media", "browser", "files"), reason (string explaining why)
+   - Use for complex queries that need combining activity + OCR + music evidence quickly"#,
+    ),
+    (
+        "resolve_query_scope",
+        r#"Widen the time range or request additional data sources
+   - Args: suggested_scope (one of: "today", "yesterday", "last_3_days", "last_7_days", "last_30_days", "this_year", "all_time", or an explicit date/range such as "2024-03-15", "june 2024", "q2", "2024-03-01..2024-03-10"), enable_sources (optional array of: "apps", "screen", "media", "browser", "files"), reason (string explaining why)
    - Use when user's query implies a different time range than what is currently selected (e.g. "few days back", "from the start", "not just today", "earlier")
    - Use when you need data sources that are not currently enabled
    - Returns a confirmation prompt to the user; after user confirms, the query re-runs with the new scope
-   - ALWAYS use this tool when the user says things like "not just today", "days back", "from the start", "earlier", "before", "across days", "overall", "from few days", etc.
-
-## Category IDs
-- 1 = Development | 2 = Browser | 3 = Communication | 4 = Entertainment | 5 = Productivity | 6 = System | 7 = Other
+   - ALWAYS use this tool when the user says things like "not just today", "days back", "from the start", "earlier", "before", "across days", "overall", "from few days", etc."#,
+    ),
+    (
+        "ask_clarification",
+        r#"Ask the user a short question when the request is too ambiguous to search well
+   - Args: question (string), suggestions (optional array of 2-4 short likely replies, e.g. ["Today", "This week"])
+   - Ends this turn: the question is shown with the suggestions as quick replies, and the user's pick arrives as the next message
+   - Use instead of writing a clarifying question as a final answer"#,
+    ),
+];
+
+/// `{category_list}` is filled from the `categories` table when the prompt is built.
+const AGENT_PROMPT_RULES: &str = r#"## Category IDs
+- {category_list}
 
 ## CRITICAL RULES
 1. For music/song queries → Use get_music_history tool
 2. For "what did I do", "events", "timeline", "recent activity" queries → Use get_recent_activities first
 3. For time spent / top apps / summary queries → Use get_usage_stats or query_activities with SUM
-4. For "what did I text", "WhatsApp chat", "what did I chat" queries → Use get_recent_ocr with app="whatsapp" first, then search_ocr if needed
+4. For "what did I text", "WhatsApp chat", "what did I chat" queries → Use get_recent_ocr with app="whatsapp" or category_id=3 first, then search_ocr if needed
 5. For "show OCR data" queries → Use get_recent_ocr without keyword
 6. NEVER give up after one query if results are empty - try different approaches
 7. If a tool returns empty results, try a broader query or different keywords
 8. For coding progress or project-change questions, use get_recent_file_changes.
 9. For broad/ambiguous requests, prefer parallel_search with 2-3 tool calls
-10. Use conversation history to resolve references like "it", "that", "the previous one", "what was it about".
+10. Use conversation history to resolve references like "it", "that", "the previous one", "what was it about". Only the last few messages are included; use `search_chat_history` for anything older.
 11. Never claim facts without tool evidence from the requested time scope.
-12. If evidence is weak or contradictory, ask a clarifying date/day question instead of guessing.
+12. If evidence is weak or contradictory, call `ask_clarification` with a date/day question instead of guessing.
 13. For "what am I hearing right now", rely only on very recent records marked as Playing.
-14. For underspecified queries (missing source/app or time intent), ask a short clarifying question before searching.
+14. For underspecified queries (missing source/app or time intent), call `ask_clarification` with a short question and likely replies before searching.
 15. If you are asked about people, names, or girls, use `search_ocr` with a high limit and try different keywords or no keywords at all to get all the data.
 16. If you are asked about chats, use `get_recent_ocr` with a high limit and try different apps like "whatsapp", "instagram", "telegram", etc.
-17. For person-identity queries (for example: "who is my crush"), never guess. Gather evidence using at least 2 distinct tools first; if evidence is weak or conflicting, ask a clarification question.
+17. For person-identity queries (for example: "who is my crush"), never guess. Gather evidence using at least 2 distinct tools first; if evidence is weak or conflicting, call `ask_clarification`.
 18. For any non-trivial factual query, fetch tool evidence before giving a final answer. If a final answer is attempted without evidence, call tools first.
 19. Never claim the user texted/chatted someone unless there is explicit chat-app evidence (e.g., WhatsApp/Telegram/Instagram chat OCR/activity) in the current time scope.
 20. For large-range summaries (like "this year" or "all time"), collect evidence in multiple compact aggregation steps (usage stats + grouped SQL rollups + focused slices) before writing the final answer.
-21. For complex queries, especially those about people, relationships, or identifying someone (e.g., "who is my crush"), you MUST make a minimum of 5 distinct tool calls to gather comprehensive evidence across different apps, timeframes, and contexts before providing a final answer. Do not jump to conclusions based on limited recent data.
+21. For complex queries, especially those about people, relationships, or identifying someone (e.g., "who is my crush"), you MUST make a minimum of {identity_min_tools} distinct tool calls to gather comprehensive evidence across different apps, timeframes, and contexts before providing a final answer. Do not jump to conclusions based on limited recent data.
 22. If the user asks a general question about habits, preferences, relationships, history, or asks "when", "how often", "first time", "ever" AND the current scope is narrow (like "Today" or "Last 7 Days"), you MUST call `resolve_query_scope` IMMEDIATELY as your first tool call to widen the scope to "last_30_days" or "all_time". Do NOT attempt to answer general or historical questions with just a few days of data. Also use this tool if the user's query implies a time range broader than the current scope (e.g., "few days back", "not just today", "earlier", "from the start", "before", "overall", "from the beginning", "across days", "the other day", "days ago", "recently" when scope is Today).
 23. If you detect the user needs data from sources that are not currently enabled (e.g., asking about files but Files source is disabled, or asking about browser history but Browser source is disabled), call `resolve_query_scope` with the required enable_sources array so the user can enable them.
+24. For comparison questions ("more than", "less than", "compared to", "vs last week"), call `compare_usage` once with both ranges instead of running two separate queries, and quote its numbers exactly. Ranges of different length are compared by per-day average.
+25. For "what did I copy" questions (an error message, link or snippet the user copied), use `search_clipboard`.
+26. For habit questions ("what are my usual work hours", "do I usually…", "is today unusual"), call `get_detected_patterns` first and cite its findings; they already cover the last 30 days, so rule 22 does not apply to them. Query raw activity only if no finding answers the question.
+27. When the user refers to an earlier conversation ("you said", "earlier you told me", "in our previous conversation", "what did you tell me yesterday about…"), call `search_chat_history` with a keyword from the question (role=assistant for what you said) and quote what was actually said. Do not reconstruct it from memory, and rule 22 does not apply.
+28. For "was X running", "how long was X open/running" or "was my dev server up while I was in the meeting", use `get_process_runtime` (focus time from activity tools does not show background running time); combine it with `get_recent_activities` to line runs up with what the user was doing.
+29. For meeting questions ("how many hours of meetings this week", "who was in the standup"), use `get_meetings` and quote its total; do not add up communication-app time yourself.
+30. Tool results tag individual activity rows like `[#1234]`. End each evidence bullet with the tags of the rows it is based on, copied exactly. Never invent a tag or cite one that did not appear in a tool result.
+31. For to-do, task, note or deadline questions ("what tasks do I have open", "what's due tomorrow"), call `get_entries`; rule 22 does not apply. Entries have no `[#id]` tags, so cite them by title.
 
 ## Response Format
 Output JSON for tool calls: { "tool": "tool_name", "args": { ... }, "reasoning": "..." }
 Output detailed, crisp, and highly specific final answers. Use markdown (like bolding and bullet points) to make the answer easy to read.
 For final answers, include:
 - A direct answer first
-- Evidence bullets with specific app/window/title + timestamp
+- Evidence bullets with specific app/window/title + timestamp, each ending with its `[#id]` citation tags
 - A short confidence statement
 - If evidence is incomplete, explicitly say what is missing
 Do not be overly brief for non-trivial queries.
 
 Do NOT output markdown code blocks for tool calls. Output RAW JSON only.
+"#;
 
+/// For models without a reasoning channel: any thinking they print lands in the answer stream.
+const AGENT_THINKING_RULES: &str = r#"
 ## Thinking Quality Rules
 - If reasoning content is emitted, keep it user-facing and concise (max 1 short sentence).
 - Never include internal planning language such as "the user is asking", "I should", "let me", "likely", or tool-selection analysis.
@@ -158,6 +266,30 @@ Do NOT output markdown code blocks for tool calls. Output RAW JSON only.
 - Good example: "Checking your recent music activity now."
 "#;
 
+/// Reasoning models think in a separate channel shown collapsed in the UI, so it isn't capped.
+const AGENT_REASONING_RULES: &str = r#"
+## Reasoning
+- Reason as much as the question needs; it is shown to the user in a collapsible block.
+- Never echo raw tool-call JSON inside reasoning. Put the tool call in the answer itself.
+"#;
+
+/// Short rule set for small-context models; the full rules alone overflow a 4-8k window.
+const AGENT_PROMPT_RULES_COMPACT: &str = r#"## Category IDs
+{category_list}
+
+## Rules
+1. Keep retrieval inside the selected time scope.
+2. Use `get_usage_stats` for totals, `search_ocr` / `get_recent_ocr` for on-screen text, `get_music_history` for songs, `compare_usage` for comparisons, `get_detected_patterns` for habits and routines, `get_meetings` for meetings, `search_chat_history` for "you said" / earlier conversations, `get_entries` for the user's tasks and notes.
+3. Only state apps, names and times that appear in tool results.
+4. If the question is too vague to search, call `ask_clarification` instead of guessing.
+5. One tool call per turn; answer once you have enough evidence.
+6. End each evidence bullet with the `[#id]` tags of the rows it uses, copied exactly from tool results.
+
+## Response Format
+Tool call: RAW JSON only, { "tool": "tool_name", "args": { ... } }
+Final answer: the direct answer first, then short evidence bullets with app, window title and time.
+"#;
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(untagged)]
 enum AgentResponse {
@@ -179,6 +311,82 @@ struct TimeScope {
     end_ts: i64,
 }
 
+/// Per-run limits on what the agent tools may touch, taken from settings.
+#[derive(Clone, Debug)]
+struct ToolPolicy {
+    include_private: bool,
+    disabled_tools: Vec<String>,
+    /// Chat session the run answers, for `search_chat_history` with session=current.
+    chat_session_id: Option<String>,
+}
+
+impl ToolPolicy {
+    fn from_settings(settings: &Settings) -> Self {
+        Self {
+            include_private: settings.privacy.include_private_in_ai,
+            disabled_tools: settings.ai.disabled_tools.clone(),
+            chat_session_id: None,
+        }
+    }
+
+    fn is_enabled(&self, tool: &str) -> bool {
+        !self.disabled_tools.iter().any(|t| t.trim().eq_ignore_ascii_case(tool))
+    }
+}
+
+fn disabled_tool_message(tool: &str) -> String {
+    format!(
+        "Tool `{}` is disabled in this installation's settings and cannot be used. Answer with the remaining tools, or tell the user this information is unavailable.",
+        tool
+    )
+}
+
+/// Full prompt for large-context models; compact models get each tool's
+/// summary line and the short rule set instead.
+fn build_agent_system_prompt(
+    policy: &ToolPolicy,
+    caps: &ModelCapabilities,
+    agent: &AgentSettings,
+    user_query: &str,
+) -> String {
+    let compact = caps.is_compact();
+    let mut prompt = String::from(AGENT_PROMPT_INTRO);
+    prompt.push_str("## Your Tools\n");
+    let mut index = 0;
+    for (name, doc) in AGENT_TOOL_DOCS {
+        if !policy.is_enabled(name) {
+            continue;
+        }
+        index += 1;
+        if compact {
+            let summary = doc.lines().next().unwrap_or("");
+            let args = doc
+                .lines()
+                .find(|line| line.trim_start().starts_with("- Args:"))
+                .map(|line| format!(" ({})", line.trim().trim_start_matches("- ")))
+                .unwrap_or_default();
+            prompt.push_str(&format!("{}. `{}` - {}{}\n", index, name, summary, args));
+        } else {
+            prompt.push_str(&format!("{}. `{}` - {}\n\n", index, name, doc));
+        }
+    }
+    if compact {
+        prompt.push('\n');
+    }
+    if index < AGENT_TOOL_DOCS.len() {
+        prompt.push_str("Only the tools listed above are available. If a rule below mentions another tool, skip that step and use the closest available tool instead.\n\n");
+    }
+    let rules = if compact { AGENT_PROMPT_RULES_COMPACT } else { AGENT_PROMPT_RULES };
+    prompt.push_str(
+        &rules
+            .replace("{category_list}", &crate::utils::categories::prompt_inline())
+            .replace("{identity_min_tools}", &agent.min_evidence_tools_for_identity.max(1).to_string()),
+    );
+    prompt.push_str(if caps.supports_reasoning { AGENT_REASONING_RULES } else { AGENT_THINKING_RULES });
+    prompt.push_str(&format!("\n## Language\n{}\n", crate::utils::i18n::language_instruction(user_query)));
+    prompt
+}
+
 #[derive(Clone, Debug, Default)]
 struct QueryIntent {
     wants_music: bool,
@@ -186,6 +394,9 @@ struct QueryIntent {
     wants_files: bool,
     wants_timeline: bool,
     broad_summary: bool,
+    wants_comparison: bool,
+    /// Category the OCR tools are narrowed to: chats or code, when the question is clearly about one.
+    ocr_category: Option<i64>,
 }
 
 // ─── Public API ───
@@ -194,12 +405,49 @@ pub async fn run_agentic_search(
     app_handle: &tauri::AppHandle,
     user_query: &str,
     settings: &Settings,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     // Delegate to the step-tracking version, just return the answer
     let result = run_agentic_search_with_steps_and_scope(app_handle, user_query, settings, None).await?;
     Ok(result.answer)
 }
 
+/// Answers a plain "what songs did I hear today" question straight from
+/// `get_music_history`, without the agent. Returns `None` unless the query is
+/// only about music (no OCR, files, comparisons or rankings) and the tool is enabled;
+/// the caller is responsible for having resolved a concrete `[start_ts, end_ts]`.
+pub fn music_history_fast_path(
+    app_handle: &tauri::AppHandle,
+    user_query: &str,
+    settings: &Settings,
+    start_ts: i64,
+    end_ts: i64,
+    scope_label: &str,
+) -> Result<Option<(String, Vec<Value>)>, String> {
+    let intent = detect_query_intent(user_query);
+    if !intent.wants_music
+        || intent.wants_ocr
+        || intent.wants_files
+        || intent.wants_comparison
+        || requires_broad_scope(user_query, &settings.ai.agent)
+    {
+        return Ok(None);
+    }
+    let policy = ToolPolicy::from_settings(settings);
+    if !policy.is_enabled("get_music_history") {
+        return Ok(None);
+    }
+
+    let db_path = crate::database::db_path(app_handle)?;
+    let conn = open_tool_connection(&db_path, policy.include_private)?;
+    let args = serde_json::json!({
+        "start_ts": start_ts,
+        "end_ts": end_ts,
+        "scope_label": scope_label,
+    });
+    let (output, activities) = execute_tool(&conn, "get_music_history", &args, &policy, None)?;
+    Ok(Some((output, transform_activities_for_frontend("get_music_history", &activities))))
+}
+
 // ─── Structured Agent Result (for Chat UI) ───
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -211,18 +459,142 @@ pub struct AgentStep {
     pub reasoning: String,
 }
 
+/// Payload for `chat://pipeline_step`, emitted as each long-range aggregation starts and finishes.
+#[derive(Serialize, Clone, Debug)]
+pub struct PipelineStepEvent {
+    pub index: usize,
+    pub tool: String,
+    pub label: String,
+    /// "started", "completed" or "failed".
+    pub status: String,
+    pub rows: usize,
+    pub elapsed_ms: u64,
+    /// Compressed result line added to the digest, present once the step completes.
+    pub digest: Option<String>,
+}
+
+/// Payload for `chat://tool-progress`, emitted while a tool scans rows and as
+/// each call of a `parallel_search` finishes.
+#[derive(Serialize, Clone, Debug)]
+pub struct ToolProgress {
+    pub tool: String,
+    /// "scanning" every `PROGRESS_EVERY_ROWS` rows, "done" when the scan ends,
+    /// "call_done" when a `parallel_search` call finishes.
+    pub phase: String,
+    /// Rows read so far; for "call_done", calls finished so far.
+    pub scanned: usize,
+    /// Rows kept so far; for "call_done", the finished call's referenced items.
+    pub matched: usize,
+    /// For "call_done", the call that finished.
+    pub detail: Option<String>,
+}
+
+type ProgressCallback = std::sync::Arc<dyn Fn(ToolProgress) + Send + Sync>;
+
+fn tool_progress_emitter(app_handle: &tauri::AppHandle) -> ProgressCallback {
+    let app_handle = app_handle.clone();
+    std::sync::Arc::new(move |progress| {
+        let _ = app_handle.emit("chat://tool-progress", progress);
+    })
+}
+
+/// Row counter for a tool's scan loop that reports through the progress callback.
+struct ScanProgress<'a> {
+    callback: Option<&'a ProgressCallback>,
+    tool: &'static str,
+    scanned: usize,
+    matched: usize,
+}
+
+impl<'a> ScanProgress<'a> {
+    fn new(callback: Option<&'a ProgressCallback>, tool: &'static str) -> Self {
+        Self { callback, tool, scanned: 0, matched: 0 }
+    }
+
+    fn row(&mut self) {
+        self.scanned += 1;
+        if self.scanned % PROGRESS_EVERY_ROWS == 0 {
+            self.report("scanning");
+        }
+    }
+
+    fn matched(&mut self, matched: usize) {
+        self.matched = matched;
+    }
+
+    fn finish(&self) {
+        self.report("done");
+    }
+
+    fn report(&self, phase: &str) {
+        if let Some(callback) = self.callback {
+            callback(ToolProgress {
+                tool: self.tool.to_string(),
+                phase: phase.to_string(),
+                scanned: self.scanned,
+                matched: self.matched,
+                detail: None,
+            });
+        }
+    }
+}
+
+/// Follow-up the UI offers next to an answer. Travels as data beside the
+/// answer text; it is never embedded in it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AgentAction {
+    /// Re-ask with a wider time range and/or more data sources enabled.
+    ConfirmScopeOrSources {
+        reason: String,
+        suggested_time_range: Option<String>,
+        #[serde(default)]
+        enable_sources: Vec<String>,
+        #[serde(default)]
+        retry_message: String,
+    },
+    /// Ask the same question again, e.g. after a failed run.
+    RetryWithQuery { query: String },
+    /// The agent needs more detail before searching; suggestions are quick replies.
+    Clarify {
+        question: String,
+        #[serde(default)]
+        suggestions: Vec<String>,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AgentResult {
     pub answer: String,
     pub steps: Vec<AgentStep>,
     pub activities_referenced: Vec<Value>,
+    #[serde(default)]
+    pub actions: Vec<AgentAction>,
+    /// Tokens spent by every LLM call of the run.
+    #[serde(default)]
+    pub usage: LlmUsage,
+    /// Exact per-category / per-app split of the scope, for summary and timeline questions.
+    #[serde(default)]
+    pub time_accounting: Option<TimeAccounting>,
+    /// Reasons to trust the answer less, e.g. stated totals that contradict `time_accounting`.
+    #[serde(default)]
+    pub confidence_factors: Vec<String>,
+}
+
+/// `chat://status` with the run's usage so far.
+fn emit_status(app_handle: &tauri::AppHandle, message: impl Into<String>, usage: &LlmUsage) {
+    let payload = ChatStatus {
+        message: message.into(),
+        usage: Some(usage.clone()),
+    };
+    let _ = app_handle.emit("chat://status", payload);
 }
 
 pub async fn run_agentic_search_with_steps(
     app_handle: &tauri::AppHandle,
     user_query: &str,
     settings: &Settings,
-) -> Result<AgentResult, String> {
+) -> Result<AgentResult, AppError> {
     run_agentic_search_with_steps_and_scope(app_handle, user_query, settings, None).await
 }
 
@@ -231,12 +603,13 @@ pub async fn run_agentic_search_with_steps_and_scope(
     user_query: &str,
     settings: &Settings,
     time_scope: Option<&str>,
-) -> Result<AgentResult, String> {
+) -> Result<AgentResult, AppError> {
     run_agentic_search_with_steps_and_history_and_scope(
         app_handle,
         user_query,
         settings,
         &[],
+        None,
         time_scope,
     ).await
 }
@@ -246,46 +619,92 @@ pub async fn run_agentic_search_with_steps_and_history(
     user_query: &str,
     settings: &Settings,
     prior_messages: &[ChatMessage],
-) -> Result<AgentResult, String> {
+    chat_session_id: Option<&str>,
+) -> Result<AgentResult, AppError> {
     run_agentic_search_with_steps_and_history_and_scope(
         app_handle,
         user_query,
         settings,
         prior_messages,
+        chat_session_id,
         None,
     ).await
 }
 
+/// Runs the agent and records what it spent, including runs that fail part-way.
 pub async fn run_agentic_search_with_steps_and_history_and_scope(
     app_handle: &tauri::AppHandle,
     user_query: &str,
     settings: &Settings,
     prior_messages: &[ChatMessage],
+    chat_session_id: Option<&str>,
+    time_scope: Option<&str>,
+) -> Result<AgentResult, AppError> {
+    let mut usage = LlmUsage::default();
+    let result = run_agent(
+        app_handle,
+        user_query,
+        settings,
+        prior_messages,
+        chat_session_id,
+        time_scope,
+        &mut usage,
+    )
+    .await;
+    log::info!(
+        "Agent run used {} tokens over {} LLM call(s){}",
+        usage.total_tokens(),
+        usage.calls,
+        if usage.estimated { " (estimated)" } else { "" }
+    );
+    llm_client::record_usage(app_handle, llm_client::CALLER_CHAT, &settings.ai.model, &usage);
+    result
+}
+
+async fn run_agent(
+    app_handle: &tauri::AppHandle,
+    user_query: &str,
+    settings: &Settings,
+    prior_messages: &[ChatMessage],
+    chat_session_id: Option<&str>,
     time_scope: Option<&str>,
-) -> Result<AgentResult, String> {
+    usage: &mut LlmUsage,
+) -> Result<AgentResult, AppError> {
     let api_key = crate::utils::config::resolve_api_key(&settings.ai.api_key);
     let model = &settings.ai.model;
     
     if api_key.is_empty() {
-        return Err("AI is disabled or API key is missing".to_string());
+        return Err(AppError::AiDisabled("AI is disabled or API key is missing".to_string()));
     }
 
-    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let db_path = data_dir.join("intentflow.db");
+    let db_path = crate::database::db_path(app_handle)?;
     
     let mut steps: Vec<AgentStep> = Vec::new();
     let mut all_activities: Vec<Value> = Vec::new();
-    let resolved_scope = resolve_time_scope(time_scope);
+    let (resolved_scope, current_focus) = {
+        let conn = Connection::open(&db_path).ok();
+        (
+            resolve_time_scope(time_scope, conn.as_ref()),
+            conn.as_ref().and_then(crate::services::current_focus::get),
+        )
+    };
     let intent = detect_query_intent(user_query);
+    let mut policy = ToolPolicy::from_settings(settings);
+    policy.chat_session_id = chat_session_id.map(str::to_string);
+    let caps = crate::services::model_capabilities::resolve(model);
+    let evidence_chars = caps.evidence_chars();
+    let lang = crate::utils::i18n::answer_lang(user_query);
     
     // Initial messages
     let mut messages = vec![ChatMessage {
         role: "system".to_string(),
-        content: AGENT_SYSTEM_PROMPT.to_string(),
+        content: build_agent_system_prompt(&policy, &caps, &settings.ai.agent, user_query),
     }];
 
-    // Include recent chat history so follow-up questions keep context.
-    for msg in prior_messages.iter().rev().take(12).rev() {
+    // Include the last few chat messages so follow-up questions keep context;
+    // anything older is reachable through `search_chat_history`.
+    let history_chars = if caps.is_compact() { 600 } else { 1200 };
+    for msg in prior_messages.iter().rev().take(CHAT_HISTORY_PREFIX_MESSAGES).rev() {
         if msg.content.trim().is_empty() {
             continue;
         }
@@ -296,36 +715,84 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
         };
         messages.push(ChatMessage {
             role: role.to_string(),
-            content: truncate_for_token_limit(&msg.content, 1200),
+            content: truncate_for_token_limit(&msg.content, history_chars),
         });
     }
 
-    let needs_broad_scope = requires_broad_scope(user_query);
+    let agent = &settings.ai.agent;
+    let max_turns = agent.max_turns.clamp(*AGENT_MAX_TURNS_RANGE.start(), *AGENT_MAX_TURNS_RANGE.end());
+    let needs_broad_scope = requires_broad_scope(user_query, agent);
+    let progress = tool_progress_emitter(app_handle);
+    let comparison_hint = if intent.wants_comparison && policy.is_enabled("compare_usage") {
+        "\nThis is a comparison question: call `compare_usage` with both ranges (range_a = the period asked about, range_b = the baseline) rather than querying each period separately."
+    } else {
+        ""
+    };
     let scope_warning = if needs_broad_scope && (resolved_scope.id == "today" || resolved_scope.id == "yesterday" || resolved_scope.id == "last_3_days" || resolved_scope.id == "last_7_days") {
         "\nCRITICAL: Your current search scope is narrow, but the user's query requires historical data, aggregation, or general knowledge about their habits/relationships. You MUST call `resolve_query_scope` immediately to widen the scope to 'last_30_days' or 'all_time' before doing anything else."
     } else {
         ""
     };
 
+    let focus_note = current_focus
+        .map(|focus| format!(
+            "\nUser's stated current focus: {}. Relate the answer to it when relevant, but don't assume every activity belongs to it.",
+            crate::services::current_focus::describe(&focus)
+        ))
+        .unwrap_or_default();
+
     messages.push(ChatMessage {
         role: "user".to_string(),
         content: format!(
-            "User query: \"{}\"\nCurrent Time: {}\nSelected Time Scope: {} ({} to {})\nAlways keep retrieval strictly inside this scope unless the user asks to change it. If you need to search for people, names, or girls, use `search_ocr` with a high limit and try different keywords or no keywords at all to get all the data. If you need to search for chats, use `get_recent_ocr` with a high limit and try different apps like \"whatsapp\", \"instagram\", \"telegram\", etc.{}",
+            "User query: \"{}\"\nCurrent Time: {}{}\nSelected Time Scope: {} ({} to {})\nAlways keep retrieval strictly inside this scope unless the user asks to change it. If you need to search for people, names, or girls, use `search_ocr` with a high limit and try different keywords or no keywords at all to get all the data. If you need to search for chats, use `get_recent_ocr` with a high limit and try different apps like \"whatsapp\", \"instagram\", \"telegram\", etc.{}{}",
             user_query,
-            chrono::Local::now().to_rfc3339(),
+            chrono::Utc::now().with_timezone(&crate::utils::time::current_offset()).to_rfc3339(),
+            focus_note,
             resolved_scope.label,
-            format_time_scope_ts(resolved_scope.start_ts),
-            format_time_scope_ts(resolved_scope.end_ts),
-            scope_warning
+            format_time_scope_ts(resolved_scope.start_ts, lang),
+            format_time_scope_ts(resolved_scope.end_ts, lang),
+            scope_warning,
+            comparison_hint
         ),
     });
 
-    let use_long_range_pipeline = should_use_long_range_pipeline(user_query, &resolved_scope, &intent);
+    // Summary and timeline answers get exact figures from SQL; the model's prose must agree with them.
+    let time_accounting = if intent.wants_timeline || intent.broad_summary {
+        Connection::open(&db_path)
+            .map_err(|e| e.to_string())
+            .and_then(|conn| compute_time_accounting(&conn, &resolved_scope, policy.include_private))
+            .map_err(|e| log::warn!("Time accounting failed: {}", e))
+            .ok()
+    } else {
+        None
+    };
+    if let Some(accounting) = &time_accounting {
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: time_accounting_prompt(accounting),
+        });
+    }
+
+    let use_long_range_pipeline = should_use_long_range_pipeline(user_query, &resolved_scope, &intent, agent);
     if use_long_range_pipeline {
-        let _ = app_handle.emit("chat://status", "Building long-range evidence (multi-step)...");
-        if let Ok((pipeline_steps, pipeline_activities, digest)) =
-            run_long_range_summary_pipeline(&db_path, &resolved_scope, &intent, user_query)
-        {
+        emit_status(app_handle, "Building long-range evidence (multi-step)...", usage);
+        // The aggregations are synchronous SQL over potentially a year of rows; keep them
+        // off the async workers so streaming and other commands stay responsive.
+        let pipeline = {
+            let app = app_handle.clone();
+            let db_path = db_path.clone();
+            let scope = resolved_scope.clone();
+            let intent = intent.clone();
+            let query = user_query.to_string();
+            let policy = policy.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                run_long_range_summary_pipeline(&app, &db_path, &scope, &intent, &query, &policy)
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result)
+        };
+        if let Ok((pipeline_steps, pipeline_activities, digest)) = pipeline {
             let start_turn = steps.len();
             for (idx, mut step) in pipeline_steps.into_iter().enumerate() {
                 step.turn = start_turn + idx + 1;
@@ -342,14 +809,14 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                 role: "user".to_string(),
                 content: format!(
                     "Pre-aggregated long-range evidence:\n{}\nUse this structured evidence first. Only call extra tools if there are clear gaps.",
-                    truncate_for_token_limit(&digest, 3500)
+                    truncate_for_token_limit(&digest, evidence_chars)
                 ),
             });
         }
     } else if intent.broad_summary {
         let prefetch_args = build_prefetch_parallel_args(&resolved_scope, &intent);
         if let Ok((prefetch_output, prefetch_activities)) =
-            execute_parallel_search(&db_path, &prefetch_args, Some(&resolved_scope), user_query)
+            execute_parallel_search(&db_path, &prefetch_args, Some(&resolved_scope), user_query, &policy, Some(&progress))
         {
             if !prefetch_activities.is_empty() {
                 all_activities.extend(prefetch_activities);
@@ -365,18 +832,18 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                 role: "user".to_string(),
                 content: format!(
                     "Prefetched evidence before tool-planning:\n{}",
-                    truncate_for_token_limit(&prefetch_output, 3500)
+                    truncate_for_token_limit(&prefetch_output, evidence_chars)
                 ),
             });
         }
     }
 
-    let must_validate_with_tools = requires_evidence_for_query(user_query);
+    let must_validate_with_tools = requires_evidence_for_query(user_query, agent);
     let mut final_without_evidence_attempts = 0usize;
     let mut forced_parallel_runs = 0usize;
 
-    for turn in 0..MAX_TURNS {
-        let _ = app_handle.emit("chat://status", format!("Thinking (step {}/{})", turn + 1, MAX_TURNS));
+    for turn in 0..max_turns {
+        emit_status(app_handle, format!("Thinking (step {}/{})", turn + 1, max_turns), usage);
         // 1. Call LLM with streaming callback
         // We accumulate the full content here, while also streaming it to the frontend
         let mut full_response = String::new();
@@ -408,7 +875,8 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
             }
         };
 
-        call_llm_stream(model, &api_key, &messages, &mut full_response, on_token).await?;
+        let call_usage = call_llm_stream(model, &api_key, settings.ai.agent_temperature, &messages, &mut full_response, Some(app_handle), on_token).await?;
+        usage.add(&call_usage);
 
         // 2. Parse Response
         let parsed_response = try_parse_tool_call_response(&full_response)
@@ -421,14 +889,17 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                     .replace("<think>", "")
                     .replace("</think>", "");
                 let normalized = normalize_final_answer_hardened(&cleaned_answer);
-                let normalized = scrub_unsupported_communication_claims(&normalized, user_query, &steps);
-                if must_validate_with_tools && steps.is_empty() && forced_parallel_runs < 2 {
+                let normalized = scrub_unsupported_communication_claims(&normalized, user_query, &steps, agent.evidence_strictness);
+                let normalized = drop_unknown_citations(&normalized, &all_activities);
+                if agent.force_validation_enabled && must_validate_with_tools && steps.is_empty() && forced_parallel_runs < 2 {
                     let forced_args = build_forced_validation_parallel_args(&resolved_scope, &intent, user_query);
                     let (out, activities) = execute_parallel_search(
                         &db_path,
                         &forced_args,
                         Some(&resolved_scope),
                         user_query,
+                        &policy,
+                        Some(&progress),
                     )?;
                     forced_parallel_runs += 1;
                     if !activities.is_empty() {
@@ -451,12 +922,12 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                         role: "user".to_string(),
                         content: format!(
                             "You attempted to answer without evidence. Use this forced evidence and continue with additional tool calls if needed:\n{}",
-                            truncate_for_token_limit(&truncated, 3500)
+                            truncate_for_token_limit(&truncated, evidence_chars)
                         ),
                     });
                     continue;
                 }
-                if contains_internal_tool_markup(&normalized) && turn + 1 < MAX_TURNS {
+                if contains_internal_tool_markup(&normalized) && turn + 1 < max_turns {
                     messages.push(ChatMessage {
                         role: "assistant".to_string(),
                         content: full_response.clone(),
@@ -468,28 +939,34 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                     continue;
                 }
                 
-                let is_complex_query = user_query.to_lowercase().contains("who") || user_query.to_lowercase().contains("crush") || user_query.to_lowercase().contains("relationship");
-                if is_complex_query && steps.len() < 5 && turn + 1 < MAX_TURNS {
+                let min_identity_tools = identity_tool_minimum(user_query, agent);
+                if steps.len() < min_identity_tools && turn + 1 < max_turns {
                     messages.push(ChatMessage {
                         role: "assistant".to_string(),
                         content: full_response.clone(),
                     });
                     messages.push(ChatMessage {
                         role: "user".to_string(),
-                        content: format!("You have only made {} tool calls. For this type of query, you MUST make at least 5 distinct tool calls to gather comprehensive evidence before answering. Please make another tool call.", steps.len()),
+                        content: format!("You have only made {} tool calls. For this type of query, you MUST make at least {} distinct tool calls to gather comprehensive evidence before answering. Please make another tool call.", steps.len(), min_identity_tools),
                     });
                     continue;
                 }
 
-                if !has_minimum_evidence_for_query(user_query, &steps) {
+                if !has_minimum_evidence_for_query(user_query, &steps, agent) {
                     final_without_evidence_attempts += 1;
-                    if must_validate_with_tools && final_without_evidence_attempts >= 2 && forced_parallel_runs < 2 {
+                    if agent.force_validation_enabled
+                        && must_validate_with_tools
+                        && final_without_evidence_attempts >= 2
+                        && forced_parallel_runs < 2
+                    {
                         let forced_args = build_forced_validation_parallel_args(&resolved_scope, &intent, user_query);
                         let (out, activities) = execute_parallel_search(
                             &db_path,
                             &forced_args,
                             Some(&resolved_scope),
                             user_query,
+                            &policy,
+                            Some(&progress),
                         )?;
                         forced_parallel_runs += 1;
                         if !activities.is_empty() {
@@ -512,12 +989,12 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                             role: "user".to_string(),
                             content: format!(
                                 "Your answer was not sufficiently evidenced. Continue using this tool output and fetch more if needed:\n{}",
-                                truncate_for_token_limit(&truncated, 3500)
+                                truncate_for_token_limit(&truncated, evidence_chars)
                             ),
                         });
                         continue;
                     }
-                    if turn + 1 < MAX_TURNS {
+                    if turn + 1 < max_turns {
                         messages.push(ChatMessage {
                             role: "assistant".to_string(),
                             content: full_response.clone(),
@@ -529,64 +1006,109 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                         continue;
                     }
                     let _ = app_handle.emit("chat://done", "final_answer");
-                    let action_marker = build_insufficient_evidence_action_marker(user_query, &resolved_scope);
                     return Ok(AgentResult {
-                        answer: format!(
-                            "I don't have enough cross-checked evidence to answer confidently. Try widening the time range (Last 7 Days or All Time) and enabling Browser History / Files & Documents, then ask me to retry.{}",
-                            action_marker
-                        ),
+                        answer: Msg::InsufficientEvidence.text(lang).to_string(),
                         steps,
                         activities_referenced: all_activities,
+                        actions: build_insufficient_evidence_action(user_query, &resolved_scope).into_iter().collect(),
+                        usage: usage.clone(),
+                        time_accounting,
+                        confidence_factors: Vec::new(),
                     });
                 }
                 // Done!
                 let _ = app_handle.emit("chat://done", "final_answer");
+                let confidence_factors = time_accounting
+                    .as_ref()
+                    .map(|t| check_stated_totals(&normalized, t))
+                    .unwrap_or_default();
                 return Ok(AgentResult {
                     answer: normalized,
                     steps,
                     activities_referenced: all_activities,
+                    actions: Vec::new(),
+                    usage: usage.clone(),
+                    time_accounting,
+                    confidence_factors,
                 });
             }
             AgentResponse::ToolCall { tool, args, reasoning } => {
-                // Handle resolve_query_scope as a special case — it returns a user-facing action prompt
-                if tool == "resolve_query_scope" {
-                    let suggested_scope = args["suggested_scope"].as_str().unwrap_or("last_7_days");
-                    let reason = args["reason"].as_str().unwrap_or("Your query requires a wider search range.");
-                    let enable_sources: Vec<String> = args.get("enable_sources")
-                        .and_then(|v| v.as_array())
-                        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                        .unwrap_or_default();
-
+                if !policy.is_enabled(&tool) {
+                    log::info!("Turn {}: rejected disabled tool {}", turn + 1, tool);
+                    let rejection = disabled_tool_message(&tool);
                     steps.push(AgentStep {
                         turn: turn + 1,
-                        tool_name: "resolve_query_scope".to_string(),
+                        tool_name: tool.clone(),
                         tool_args: args.clone(),
-                        tool_result: format!("Requesting scope change to {} with sources {:?}", suggested_scope, enable_sources),
+                        tool_result: rejection.clone(),
                         reasoning: reasoning.as_deref().unwrap_or("").to_string(),
                     });
-
-                    let payload = serde_json::json!({
-                        "kind": "confirm_scope_or_sources",
-                        "reason": reason,
-                        "suggested_time_range": suggested_scope,
-                        "enable_sources": enable_sources,
-                        "retry_message": user_query
+                    messages.push(ChatMessage {
+                        role: "assistant".to_string(),
+                        content: full_response.clone(),
+                    });
+                    messages.push(ChatMessage {
+                        role: "user".to_string(),
+                        content: format!("Tool Output (JSON): {}", rejection),
                     });
+                    continue;
+                }
+                // Handle resolve_query_scope as a special case — it returns a user-facing action prompt
+                if tool == "resolve_query_scope" {
+                    let action = scope_action_from_args(&args, user_query);
+                    if let AgentAction::ConfirmScopeOrSources { suggested_time_range, enable_sources, .. } = &action {
+                        steps.push(AgentStep {
+                            turn: turn + 1,
+                            tool_name: "resolve_query_scope".to_string(),
+                            tool_args: args.clone(),
+                            tool_result: format!(
+                                "Requesting scope change to {} with sources {:?}",
+                                suggested_time_range.as_deref().unwrap_or("current range"),
+                                enable_sources
+                            ),
+                            reasoning: reasoning.as_deref().unwrap_or("").to_string(),
+                        });
+                    }
 
                     let _ = app_handle.emit("chat://done", "final_answer");
                     return Ok(AgentResult {
-                        answer: format!(
-                            "I can answer this more accurately after your confirmation.\n\n[[IF_ACTION:{}]]",
-                            payload
-                        ),
+                        answer: "I can answer this more accurately after your confirmation.".to_string(),
                         steps,
                         activities_referenced: all_activities,
+                        actions: vec![action],
+                        usage: usage.clone(),
+                        time_accounting: None,
+                        confidence_factors: Vec::new(),
                     });
                 }
 
+                // ask_clarification ends the run the same way: the question goes back to the user
+                if tool == "ask_clarification" {
+                    if let Some((question, suggestions)) = parse_clarification_args(&args) {
+                        steps.push(AgentStep {
+                            turn: turn + 1,
+                            tool_name: "ask_clarification".to_string(),
+                            tool_args: args.clone(),
+                            tool_result: format!("Asked the user: {}", question),
+                            reasoning: reasoning.as_deref().unwrap_or("").to_string(),
+                        });
+
+                        let _ = app_handle.emit("chat://done", "final_answer");
+                        return Ok(AgentResult {
+                            answer: question.clone(),
+                            steps,
+                            activities_referenced: all_activities,
+                            actions: vec![AgentAction::Clarify { question, suggestions }],
+                            usage: usage.clone(),
+                            time_accounting: None,
+                            confidence_factors: Vec::new(),
+                        });
+                    }
+                }
+
                 let enforced_args = enforce_tool_args_with_scope(&tool, &args, &resolved_scope, user_query);
-                println!("[Agent] Turn {}: Calling {} ({:?})", turn + 1, tool, enforced_args);
-                let _ = app_handle.emit("chat://status", format!("Running {}", tool));
+                log::debug!("Turn {}: calling {} ({:?})", turn + 1, tool, enforced_args);
+                emit_status(app_handle, format!("Running {}", tool), usage);
                 // Notify frontend of agent step (tool call) start?
                 // For now, frontend just sees tokens.
                 
@@ -612,11 +1134,13 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                         &enforced_args,
                         Some(&resolved_scope),
                         user_query,
+                        &policy,
+                        Some(&progress),
                     )?;
                     (out, activities, 1usize)
                 } else {
-                    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-                    execute_tool_with_retries(&conn, &tool, &enforced_args, MAX_TOOL_RETRY_LOOPS)?
+                    let conn = open_tool_connection(&db_path, policy.include_private)?;
+                    execute_tool_with_retries(&conn, &tool, &enforced_args, MAX_TOOL_RETRY_LOOPS, &policy, Some(&progress))?
                 };
 
                 // Add activities from tool result to referenced activities
@@ -625,9 +1149,10 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                 if !intent.wants_music {
                     all_activities.retain(|item| !is_media_activity_ref(item));
                 }
-                let _ = app_handle.emit(
-                    "chat://status",
-                    format!("{} completed ({} referenced items)", tool, tool_activities.len())
+                emit_status(
+                    app_handle,
+                    format!("{} completed ({} referenced items)", tool, tool_activities.len()),
+                    usage,
                 );
                 
                 // Truncate output if too long to save tokens
@@ -640,7 +1165,7 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
                 } else {
                     tool_output
                 };
-                let truncated_output = truncate_for_token_limit(&with_retry_note, 10000);
+                let truncated_output = truncate_for_token_limit(&with_retry_note, caps.tool_output_chars());
                 
                 // Record step
                 steps.push(AgentStep {
@@ -660,17 +1185,33 @@ pub async fn run_agentic_search_with_steps_and_history_and_scope(
         }
     }
 
-    let _ = app_handle.emit("chat://status", "Finalizing answer from gathered evidence...");
+    emit_status(app_handle, "Finalizing answer from gathered evidence...", usage);
     let answer = synthesize_answer_from_evidence(
         app_handle,
         model,
         &api_key,
+        settings.ai.agent_temperature,
         user_query,
         &resolved_scope,
         &steps,
         &all_activities,
-    ).await.unwrap_or_else(|_| "I checked your activity and found partial evidence, but not enough for a fully confident answer. Ask with a specific date/app and I will give exact details.".to_string());
-    Ok(AgentResult { answer, steps, activities_referenced: all_activities })
+        time_accounting.as_ref(),
+        agent.evidence_strictness,
+        usage,
+    ).await.unwrap_or_else(|_| Msg::PartialEvidence.text(lang).to_string());
+    let confidence_factors = time_accounting
+        .as_ref()
+        .map(|t| check_stated_totals(&answer, t))
+        .unwrap_or_default();
+    Ok(AgentResult {
+        answer,
+        steps,
+        activities_referenced: all_activities,
+        actions: Vec::new(),
+        usage: usage.clone(),
+        time_accounting,
+        confidence_factors,
+    })
 }
 
 // ─── Tool Execution ───
@@ -707,6 +1248,25 @@ fn detect_query_intent(query: &str) -> QueryIntent {
         || q.contains("yearly")
         || q.contains("annual")
         || (wants_timeline && (wants_ocr || wants_files || wants_music));
+    let wants_comparison = q.contains("more than")
+        || q.contains("less than")
+        || q.contains("compared to")
+        || q.contains("compare")
+        || q.contains(" vs ")
+        || q.contains(" vs. ")
+        || q.contains("versus")
+        || q.contains("vs last");
+    let chat_like = ["chat", "message", "whatsapp", "telegram", "discord", "slack", "texted"]
+        .iter()
+        .any(|k| q.contains(k));
+    let code_like = ["code", "coding", "programming", "debug", "terminal", "compile"]
+        .iter()
+        .any(|k| q.contains(k));
+    let ocr_category = match (chat_like, code_like) {
+        (true, false) => Some(CHAT_CATEGORY_ID),
+        (false, true) => Some(CODE_CATEGORY_ID),
+        _ => None,
+    };
 
     QueryIntent {
         wants_music,
@@ -714,30 +1274,18 @@ fn detect_query_intent(query: &str) -> QueryIntent {
         wants_files,
         wants_timeline,
         broad_summary,
+        wants_comparison,
+        ocr_category,
     }
 }
 
-fn requires_broad_scope(query: &str) -> bool {
+fn requires_broad_scope(query: &str, agent: &AgentSettings) -> bool {
     let q = query.to_lowercase();
-    
-    let time_indicators = [
-        "first", "last time", "ever", "always", "never", "usually", "often", 
-        "history", "past", "before", "earlier", "since", "overall", "all time",
-        "months", "years", "weeks", "days ago", "long time", "recently"
-    ];
-    
-    let general_questions = [
-        "how many times", "how often", "when did i", "longest", "best", "worst", 
-        "favorite", "most", "top", "frequent"
-    ];
-    
-    let identity_questions = [
-        "who is", "what is my", "guess", "crush", "relationship", "friend", "girlfriend", "boyfriend"
-    ];
-
-    time_indicators.iter().any(|&w| q.contains(w)) ||
-    general_questions.iter().any(|&w| q.contains(w)) ||
-    identity_questions.iter().any(|&w| q.contains(w))
+    agent
+        .broad_scope_keywords
+        .iter()
+        .map(|k| k.trim().to_lowercase())
+        .any(|k| !k.is_empty() && q.contains(&k))
 }
 
 fn query_has_time_hint(query: &str) -> bool {
@@ -773,18 +1321,13 @@ fn query_has_time_hint(query: &str) -> bool {
         || q.chars().any(|c| c.is_ascii_digit())
 }
 
-fn local_day_bounds(days_ago: i64) -> Option<(i64, i64)> {
-    let now = Local::now();
-    let target_date = now.date_naive() - Duration::days(days_ago);
-    let start_naive = target_date.and_hms_opt(0, 0, 0)?;
-    let end_naive = target_date.and_hms_opt(23, 59, 59)?;
-    let tz = now.timezone();
-    let start = tz.from_local_datetime(&start_naive).single()?.timestamp();
-    let end = tz.from_local_datetime(&end_naive).single()?.timestamp();
-    Some((start, end))
+fn local_day_bounds(conn: Option<&Connection>, days_ago: i64) -> Option<(i64, i64)> {
+    let target_date = crate::utils::time::today() - Duration::days(days_ago);
+    let bounds = crate::utils::time::day_bounds(conn, target_date);
+    Some((bounds.start_ts, bounds.end_ts - 1))
 }
 
-fn resolve_time_scope(explicit_scope: Option<&str>) -> TimeScope {
+fn resolve_time_scope(explicit_scope: Option<&str>, conn: Option<&Connection>) -> TimeScope {
     let now = chrono::Utc::now().timestamp();
     let scope_id = explicit_scope
         .filter(|s| !s.trim().is_empty())
@@ -793,29 +1336,25 @@ fn resolve_time_scope(explicit_scope: Option<&str>) -> TimeScope {
 
     match scope_id.as_str() {
         "yesterday" => {
-            let (start_ts, end_ts) = local_day_bounds(1).unwrap_or((now - 86400, now));
+            let (start_ts, end_ts) = local_day_bounds(conn, 1).unwrap_or((now - 86400, now));
             TimeScope { id: scope_id, label: "Yesterday".to_string(), start_ts, end_ts }
         }
         "last_3_days" => {
-            let start_ts = local_day_bounds(2).map(|(s, _)| s).unwrap_or(now - 3 * 86400);
+            let start_ts = local_day_bounds(conn, 2).map(|(s, _)| s).unwrap_or(now - 3 * 86400);
             TimeScope { id: scope_id, label: "Last 3 Days".to_string(), start_ts, end_ts: now }
         }
         "last_7_days" => {
-            let start_ts = local_day_bounds(6).map(|(s, _)| s).unwrap_or(now - 7 * 86400);
+            let start_ts = local_day_bounds(conn, 6).map(|(s, _)| s).unwrap_or(now - 7 * 86400);
             TimeScope { id: scope_id, label: "Last 7 Days".to_string(), start_ts, end_ts: now }
         }
         "last_30_days" => {
-            let start_ts = local_day_bounds(29).map(|(s, _)| s).unwrap_or(now - 30 * 86400);
+            let start_ts = local_day_bounds(conn, 29).map(|(s, _)| s).unwrap_or(now - 30 * 86400);
             TimeScope { id: scope_id, label: "Last 30 Days".to_string(), start_ts, end_ts: now }
         }
         "this_year" => {
-            let local_now = Local::now();
-            let year = local_now.year();
-            let start_naive = chrono::NaiveDate::from_ymd_opt(year, 1, 1)
-                .and_then(|d| d.and_hms_opt(0, 0, 0));
-            let start_ts = start_naive
-                .and_then(|dt| local_now.timezone().from_local_datetime(&dt).single())
-                .map(|dt| dt.timestamp())
+            let year = crate::utils::time::today().year();
+            let start_ts = chrono::NaiveDate::from_ymd_opt(year, 1, 1)
+                .map(|d| crate::utils::time::day_bounds(conn, d).start_ts)
                 .unwrap_or(now - 365 * 86400);
             TimeScope { id: scope_id, label: "This Year".to_string(), start_ts, end_ts: now }
         }
@@ -825,20 +1364,35 @@ fn resolve_time_scope(explicit_scope: Option<&str>) -> TimeScope {
             start_ts: 0,
             end_ts: now,
         },
-        _ => {
-            let start_ts = local_day_bounds(0).map(|(s, _)| s).unwrap_or(now - 86400);
-            TimeScope { id: "today".to_string(), label: "Today".to_string(), start_ts, end_ts: now }
-        }
+        _ => match crate::utils::date_expr::parse_date_expression(&scope_id, conn) {
+            // "2024-03-15", "june", "q2 2024", "2024-03-01..2024-03-10"
+            Some(range) => TimeScope {
+                id: range.scope_id(),
+                label: range.label,
+                start_ts: range.start_ts,
+                end_ts: range.end_ts,
+            },
+            None => {
+                let start_ts = local_day_bounds(conn, 0).map(|(s, _)| s).unwrap_or(now - 86400);
+                TimeScope { id: "today".to_string(), label: "Today".to_string(), start_ts, end_ts: now }
+            }
+        },
     }
 }
 
-fn format_time_scope_ts(ts: i64) -> String {
+fn format_time_scope_ts(ts: i64, lang: Lang) -> String {
     if ts <= 0 {
         return "beginning".to_string();
     }
-    chrono::DateTime::from_timestamp(ts, 0)
-        .map(|dt| dt.with_timezone(&Local).format("%b %d, %Y %I:%M %p").to_string())
-        .unwrap_or_else(|| "unknown".to_string())
+    let Some(dt) = chrono::DateTime::from_timestamp(ts, 0) else {
+        return "unknown".to_string();
+    };
+    let date = dt.with_timezone(&crate::utils::time::offset_at(ts)).date_naive();
+    format!(
+        "{} {}",
+        crate::utils::i18n::format_date(date, lang),
+        crate::utils::time::format_local_time(ts).unwrap_or_default()
+    )
 }
 
 fn enforce_tool_args_with_scope(tool: &str, args: &Value, scope: &TimeScope, user_query: &str) -> Value {
@@ -866,6 +1420,29 @@ fn enforce_tool_args_with_scope(tool: &str, args: &Value, scope: &TimeScope, use
         return next;
     }
 
+    // Conversations are searched by their own dates, not the activity scope.
+    if tool == "search_chat_history" {
+        return args.clone();
+    }
+
+    // Both ranges are explicit; only fall back to the selected scope for a missing range_a.
+    if tool == "compare_usage" {
+        let mut next = args.clone();
+        if let Some(obj) = next.as_object_mut() {
+            if !obj.get("range_a").map(|v| v.is_object()).unwrap_or(false) {
+                obj.insert(
+                    "range_a".to_string(),
+                    serde_json::json!({
+                        "label": scope.label,
+                        "start_ts": scope.start_ts,
+                        "end_ts": scope.end_ts,
+                    }),
+                );
+            }
+        }
+        return next;
+    }
+
     let mut next = args.clone();
     let Some(obj) = next.as_object_mut() else {
         return args.clone();
@@ -915,6 +1492,15 @@ fn resolve_window_from_args(args: &Value, default_hours: i64) -> (i64, i64) {
     (start_ts, end_ts)
 }
 
+/// `get_recent_ocr` args, narrowed to the intent's category if it has one.
+fn recent_ocr_args(limit: u64, intent: &QueryIntent) -> Value {
+    let mut args = serde_json::json!({ "limit": limit });
+    if let Some(category_id) = intent.ocr_category {
+        args["category_id"] = Value::from(category_id);
+    }
+    args
+}
+
 fn build_prefetch_parallel_args(scope: &TimeScope, intent: &QueryIntent) -> Value {
     let mut calls = vec![serde_json::json!({
         "tool": "get_recent_activities",
@@ -927,7 +1513,7 @@ fn build_prefetch_parallel_args(scope: &TimeScope, intent: &QueryIntent) -> Valu
     if intent.wants_ocr || intent.broad_summary {
         calls.push(serde_json::json!({
             "tool": "get_recent_ocr",
-            "args": { "limit": if scope.id == "all_time" { 80 } else { 50 } }
+            "args": recent_ocr_args(if scope.id == "all_time" { 80 } else { 50 }, intent)
         }));
     }
 
@@ -956,7 +1542,7 @@ fn build_forced_validation_parallel_args(scope: &TimeScope, intent: &QueryIntent
         }),
         serde_json::json!({
             "tool": "get_recent_ocr",
-            "args": { "limit": if scope.id == "all_time" { 120 } else { 80 } }
+            "args": recent_ocr_args(if scope.id == "all_time" { 120 } else { 80 }, intent)
         }),
     ];
 
@@ -977,7 +1563,7 @@ fn build_forced_validation_parallel_args(scope: &TimeScope, intent: &QueryIntent
     serde_json::json!({ "calls": calls })
 }
 
-fn should_use_long_range_pipeline(query: &str, scope: &TimeScope, intent: &QueryIntent) -> bool {
+fn should_use_long_range_pipeline(query: &str, scope: &TimeScope, intent: &QueryIntent, agent: &AgentSettings) -> bool {
     let q = query.to_lowercase();
     let summary_like = q.contains("summary")
         || q.contains("overview")
@@ -990,23 +1576,30 @@ fn should_use_long_range_pipeline(query: &str, scope: &TimeScope, intent: &Query
         return false;
     }
     let span_days = ((scope.end_ts - scope.start_ts).max(0)) / 86_400;
-    scope.id == "this_year" || scope.id == "all_time" || span_days >= 90 || intent.broad_summary
+    scope.id == "this_year"
+        || scope.id == "all_time"
+        || span_days >= agent.long_range_pipeline_threshold_days.max(1)
+        || intent.broad_summary
 }
 
 fn run_long_range_summary_pipeline(
+    app_handle: &tauri::AppHandle,
     db_path: &std::path::Path,
     scope: &TimeScope,
     intent: &QueryIntent,
     user_query: &str,
+    policy: &ToolPolicy,
 ) -> Result<(Vec<AgentStep>, Vec<Value>, String), String> {
-    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    let conn = open_tool_connection(db_path, policy.include_private)?;
     let mut steps: Vec<AgentStep> = Vec::new();
     let mut all_refs: Vec<Value> = Vec::new();
     let mut digest_parts: Vec<String> = Vec::new();
 
     // Step 1: Aggregate app usage for the whole range.
     execute_and_record_long_range_step(
+        app_handle,
         &conn,
+        policy,
         scope,
         user_query,
         "get_usage_stats",
@@ -1019,15 +1612,18 @@ fn run_long_range_summary_pipeline(
 
     // Step 2: Monthly category rollup to avoid feeding raw per-event data.
     let monthly_category_sql = format!(
-        "SELECT strftime('%Y-%m', datetime(start_time, 'unixepoch', 'localtime')) AS month, category_id, SUM(duration_seconds) AS total_seconds, COUNT(*) AS events \
+        "SELECT strftime('%Y-%m', {}) AS month, category_id, SUM(duration_seconds) AS total_seconds, COUNT(*) AS events \
          FROM activities WHERE start_time >= {} AND start_time <= {} \
          GROUP BY month, category_id \
          ORDER BY month DESC, total_seconds DESC LIMIT 600",
+        crate::utils::time::ROW_LOCAL_DATETIME_SQL,
         scope.start_ts,
         scope.end_ts
     );
     execute_and_record_long_range_step(
+        app_handle,
         &conn,
+        policy,
         scope,
         user_query,
         "query_activities",
@@ -1048,7 +1644,9 @@ fn run_long_range_summary_pipeline(
         scope.end_ts
     );
     execute_and_record_long_range_step(
+        app_handle,
         &conn,
+        policy,
         scope,
         user_query,
         "query_activities",
@@ -1061,7 +1659,9 @@ fn run_long_range_summary_pipeline(
 
     // Step 4: Recent high-signal activity slice for concrete examples.
     execute_and_record_long_range_step(
+        app_handle,
         &conn,
+        policy,
         scope,
         user_query,
         "get_recent_activities",
@@ -1079,7 +1679,9 @@ fn run_long_range_summary_pipeline(
     let needs_files = intent.wants_files || q.contains("project") || q.contains("repo") || q.contains("code");
     if needs_files {
         execute_and_record_long_range_step(
+            app_handle,
             &conn,
+            policy,
             scope,
             user_query,
             "get_recent_file_changes",
@@ -1094,7 +1696,9 @@ fn run_long_range_summary_pipeline(
     let needs_chat = intent.wants_ocr || q.contains("chat") || q.contains("text") || q.contains("message");
     if needs_chat {
         execute_and_record_long_range_step(
+            app_handle,
             &conn,
+            policy,
             scope,
             user_query,
             "get_recent_ocr",
@@ -1108,7 +1712,9 @@ fn run_long_range_summary_pipeline(
 
     if intent.wants_music || q.contains("music") || q.contains("song") {
         execute_and_record_long_range_step(
+            app_handle,
             &conn,
+            policy,
             scope,
             user_query,
             "get_music_history",
@@ -1125,7 +1731,9 @@ fn run_long_range_summary_pipeline(
 }
 
 fn execute_and_record_long_range_step(
+    app_handle: &tauri::AppHandle,
     conn: &Connection,
+    policy: &ToolPolicy,
     scope: &TimeScope,
     user_query: &str,
     tool: &str,
@@ -1135,9 +1743,41 @@ fn execute_and_record_long_range_step(
     all_refs: &mut Vec<Value>,
     digest_parts: &mut Vec<String>,
 ) -> Result<(), String> {
+    let index = steps.len() + 1;
+    let started = std::time::Instant::now();
+    let emit_step = |status: &str, rows: usize, digest: Option<String>| {
+        let _ = app_handle.emit(
+            "chat://pipeline_step",
+            PipelineStepEvent {
+                index,
+                tool: tool.to_string(),
+                label: reasoning.to_string(),
+                status: status.to_string(),
+                rows,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                digest,
+            },
+        );
+    };
+    emit_step("started", 0, None);
+
     let enforced_args = enforce_tool_args_with_scope(tool, &raw_args, scope, user_query);
     let (tool_output, tool_activities, attempts_used) =
-        execute_tool_with_retries(conn, tool, &enforced_args, MAX_TOOL_RETRY_LOOPS)?;
+        match execute_tool_with_retries(
+            conn,
+            tool,
+            &enforced_args,
+            MAX_TOOL_RETRY_LOOPS,
+            policy,
+            Some(&tool_progress_emitter(app_handle)),
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                emit_step("failed", 0, None);
+                return Err(e);
+            }
+        };
+    let rows = tool_activities.len();
     let with_retry_note = if attempts_used > 1 {
         format!(
             "Auto-retried with broader search {} time(s).\n{}",
@@ -1157,11 +1797,13 @@ fn execute_and_record_long_range_step(
     });
     let refs = transform_activities_for_frontend(tool, &tool_activities);
     all_refs.extend(refs);
-    digest_parts.push(format!(
+    let digest_line = format!(
         "{} -> {}",
         tool,
         truncate_for_token_limit(&normalize_whitespace(&truncated), 900)
-    ));
+    );
+    emit_step("completed", rows, Some(digest_line.clone()));
+    digest_parts.push(digest_line);
     Ok(())
 }
 
@@ -1209,6 +1851,12 @@ fn is_low_signal_result(tool: &str, output: &str, activities: &[Value]) -> bool
         "get_music_history" => text.contains("no music activity found"),
         "get_recent_activities" => text.contains("no activity events found"),
         "get_recent_file_changes" => text.contains("no file changes found"),
+        "search_clipboard" => text.contains("no clipboard entries"),
+        "get_process_runtime" => text.contains("no process runs"),
+        "get_detected_patterns" => text.contains("no detected patterns"),
+        "get_meetings" => text.contains("no meetings found"),
+        "search_chat_history" => text.contains("no matching chat messages"),
+        "get_entries" => text.contains("no entries found"),
         "search_ocr" | "get_recent_ocr" => text.contains("no ocr") || text.contains("no matches"),
         "query_activities" => text.contains("[]") || text.contains("no rows"),
         _ => false,
@@ -1227,8 +1875,14 @@ fn broaden_tool_args(tool: &str, args: &Value, attempt: usize) -> Value {
     let has_fixed_window = obj.get("start_ts").and_then(|v| v.as_i64()).is_some()
         && obj.get("end_ts").and_then(|v| v.as_i64()).is_some();
 
+    // Still nothing on the second retry: the category guess may be what's wrong.
+    if attempt >= 2 && (tool == "search_ocr" || tool == "get_recent_ocr") {
+        obj.remove("category_id");
+        obj.remove("categories");
+    }
+
     match tool {
-        "get_music_history" | "get_recent_activities" | "get_recent_ocr" | "get_recent_file_changes" => {
+        "get_music_history" | "get_recent_activities" | "get_recent_ocr" | "get_recent_file_changes" | "search_clipboard" => {
             let new_limit = std::cmp::min(limit + 20, 250);
             obj.insert("limit".to_string(), Value::Number(serde_json::Number::from(new_limit)));
             if !has_fixed_window {
@@ -1254,17 +1908,37 @@ fn broaden_tool_args(tool: &str, args: &Value, attempt: usize) -> Value {
     next
 }
 
+/// `category_id` / `categories` tool args as an `AND category_id IN (...)` clause
+/// (served by `idx_activities_category_start`); empty when neither is given.
+fn category_clause(args: &Value) -> String {
+    let as_id = |v: &Value| v.as_i64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()));
+    let mut ids: Vec<i64> = args["categories"]
+        .as_array()
+        .map(|a| a.iter().filter_map(as_id).collect())
+        .unwrap_or_default();
+    ids.extend(as_id(&args["category_id"]));
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() {
+        return String::new();
+    }
+    let list = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+    format!(" AND category_id IN ({})", list)
+}
+
 fn execute_tool_with_retries(
     conn: &Connection,
     tool: &str,
     args: &Value,
     max_loops: usize,
+    policy: &ToolPolicy,
+    progress: Option<&ProgressCallback>,
 ) -> Result<(String, Vec<Value>, usize), String> {
     let loops = std::cmp::max(max_loops, 1);
     let mut current_args = args.clone();
 
     for attempt in 1..=loops {
-        let (output, activities) = execute_tool(conn, tool, &current_args)?;
+        let (output, activities) = execute_tool(conn, tool, &current_args, policy, progress)?;
         if attempt == loops || !is_low_signal_result(tool, &output, &activities) {
             return Ok((output, activities, attempt));
         }
@@ -1274,11 +1948,19 @@ fn execute_tool_with_retries(
     Err("Tool execution failed after retries".to_string())
 }
 
+enum ParallelSlot {
+    Skipped(String),
+    Failed(String, String),
+    Running(String, std::thread::JoinHandle<Result<(String, Vec<Value>, usize), String>>),
+}
+
 fn execute_parallel_search(
     db_path: &std::path::Path,
     args: &Value,
     scope: Option<&TimeScope>,
     user_query: &str,
+    policy: &ToolPolicy,
+    progress: Option<&ProgressCallback>,
 ) -> Result<(String, Vec<Value>), String> {
     let calls = args
         .get("calls")
@@ -1288,15 +1970,23 @@ fn execute_parallel_search(
         return Err("parallel_search requires at least one tool call".to_string());
     }
 
-    let mut handles = Vec::new();
+    // One slot per call, in request order, so output and activities line up
+    // with the calls the model made regardless of which worker finishes first.
+    // A failing call becomes an error line instead of aborting the batch.
+    let mut slots: Vec<ParallelSlot> = Vec::with_capacity(calls.len());
+    let finished_calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
     for call in calls {
-        let tool = call
-            .get("tool")
-            .and_then(|v| v.as_str())
-            .ok_or("Each parallel call needs a tool field")?
-            .to_string();
+        let Some(tool) = call.get("tool").and_then(|v| v.as_str()).map(|t| t.to_string()) else {
+            slots.push(ParallelSlot::Failed("unknown".to_string(), "call is missing a tool field".to_string()));
+            continue;
+        };
         if tool == "parallel_search" {
-            return Err("Nested parallel_search is not allowed".to_string());
+            slots.push(ParallelSlot::Failed(tool, "nested parallel_search is not allowed".to_string()));
+            continue;
+        }
+        if !policy.is_enabled(&tool) {
+            slots.push(ParallelSlot::Skipped(tool));
+            continue;
         }
         let raw_tool_args = call.get("args").cloned().unwrap_or_else(|| serde_json::json!({}));
         let tool_args = if let Some(active_scope) = scope {
@@ -1305,37 +1995,106 @@ fn execute_parallel_search(
             raw_tool_args
         };
         let db_path = db_path.to_path_buf();
-
-        handles.push(std::thread::spawn(move || -> Result<(String, String, Vec<Value>, usize), String> {
-            let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-            let (output, activities, attempts) =
-                execute_tool_with_retries(&conn, &tool, &tool_args, MAX_TOOL_RETRY_LOOPS)?;
-            Ok((tool, output, activities, attempts))
-        }));
+        let policy = policy.clone();
+        let worker_tool = tool.clone();
+
+        let progress = progress.cloned();
+        let finished_calls = finished_calls.clone();
+        let handle = std::thread::spawn(move || -> Result<(String, Vec<Value>, usize), String> {
+            let result = open_tool_connection(&db_path, policy.include_private).and_then(|conn| {
+                execute_tool_with_retries(&conn, &worker_tool, &tool_args, MAX_TOOL_RETRY_LOOPS, &policy, progress.as_ref())
+            });
+            // Reported as each worker finishes rather than in join order.
+            if let Some(callback) = progress {
+                callback(ToolProgress {
+                    tool: "parallel_search".to_string(),
+                    phase: "call_done".to_string(),
+                    scanned: finished_calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1,
+                    matched: result.as_ref().map(|(_, activities, _)| activities.len()).unwrap_or(0),
+                    detail: Some(worker_tool),
+                });
+            }
+            result
+        });
+        slots.push(ParallelSlot::Running(tool, handle));
     }
 
-    let mut combined_output = format!("Parallel search executed {} tool calls:\n", calls.len());
+    let mut combined_output = format!("Parallel search executed {} tool calls:\n", slots.len());
     let mut combined_activities: Vec<Value> = Vec::new();
-
-    for handle in handles {
-        let (tool, output, activities, attempts) = handle
-            .join()
-            .map_err(|_| "Parallel search worker panicked".to_string())??;
-        combined_output.push_str(&format!(
-            "- {} (attempts: {})\n",
-            tool, attempts
-        ));
+    let mut failures = 0;
+    for slot in slots {
+        match slot {
+            ParallelSlot::Skipped(tool) => {
+                combined_output.push_str(&format!("- {} (skipped)\n  {}\n", tool, disabled_tool_message(&tool)));
+            }
+            ParallelSlot::Failed(tool, error) => {
+                failures += 1;
+                combined_output.push_str(&format!("- {} (error: {})\n", tool, error));
+            }
+            ParallelSlot::Running(tool, handle) => {
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err("worker panicked".to_string()));
+                match result {
+                    Ok((output, activities, attempts)) => {
+                        combined_output.push_str(&format!(
+                            "- {} (attempts: {})\n",
+                            tool, attempts
+                        ));
+                        combined_output.push_str(&format!(
+                            "  {}\n",
+                            truncate_for_token_limit(&normalize_whitespace(&output), 500)
+                        ));
+                        combined_activities.extend(transform_activities_for_frontend(&tool, &activities));
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        log::warn!("parallel_search call {} failed: {}", tool, e);
+                        combined_output.push_str(&format!(
+                            "- {} (error: {})\n",
+                            tool,
+                            truncate_for_token_limit(&normalize_whitespace(&e), 300)
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    if failures > 0 {
         combined_output.push_str(&format!(
-            "  {}\n",
-            truncate_for_token_limit(&normalize_whitespace(&output), 500)
+            "{} call(s) failed; use the successful results above or retry the failed tool with corrected args.\n",
+            failures
         ));
-        combined_activities.extend(transform_activities_for_frontend(&tool, &activities));
     }
 
     Ok((combined_output, combined_activities))
 }
 
-fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String, Vec<Value>), String> {
+/// Opens the connection agent tools read from. Unless private rows are allowed,
+/// a temp view shadows `activities` so every tool (including raw SQL from
+/// `query_activities`) only sees rows that are not marked private.
+fn open_tool_connection(db_path: &std::path::Path, include_private: bool) -> Result<Connection, String> {
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    if !include_private {
+        conn.execute_batch(
+            "CREATE TEMP VIEW IF NOT EXISTS activities AS
+             SELECT * FROM main.activities WHERE COALESCE(private, 0) = 0",
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(conn)
+}
+
+fn execute_tool(
+    conn: &Connection,
+    tool: &str,
+    args: &Value,
+    policy: &ToolPolicy,
+    progress: Option<&ProgressCallback>,
+) -> Result<(String, Vec<Value>), String> {
+    if !policy.is_enabled(tool) {
+        return Ok((disabled_tool_message(tool), Vec::new()));
+    }
     match tool {
         // Dedicated music history tool - finds songs from Spotify, YouTube, etc.
         "get_music_history" => {
@@ -1348,7 +2107,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
             // Query a broad slice of recent activity and filter by media_info in Rust.
             // Music can be present while the active app is not an entertainment app.
             let mut stmt = conn.prepare(
-                "SELECT app_name, window_title, start_time, duration_seconds, metadata, category_id
+                "SELECT app_name, window_title, start_time, duration_seconds, metadata, category_id, id
                  FROM activities 
                  WHERE start_time >= ?1 AND start_time <= ?2 AND metadata IS NOT NULL
                  ORDER BY start_time DESC 
@@ -1362,96 +2121,72 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 let duration_seconds: i32 = row.get(3)?;
                 let metadata_blob: Option<Vec<u8>> = row.get(4)?;
                 let category_id: i32 = row.get(5)?;
+                let id: i64 = row.get(6)?;
                 
-                // Parse metadata to extract media_info
-                let media_info = if let Some(blob) = &metadata_blob {
-                    if let Ok(meta) = serde_json::from_slice::<ActivityMetadata>(blob) {
-                        meta.media_info
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
+                // Legacy rows have no stored media_kind; classify them with the same rules.
+                let meta = metadata_blob
+                    .as_deref()
+                    .and_then(|blob| serde_json::from_slice::<ActivityMetadata>(blob).ok());
+                let media_info = meta.as_ref().and_then(|m| m.media_info.clone());
+                let media_kind = meta.as_ref().and_then(|m| m.media_kind).or_else(|| {
+                    media_info.as_ref().map(|media| {
+                        crate::utils::media::classify_media(
+                            media,
+                            &app_name,
+                            &window_title,
+                            duration_seconds as i64,
+                        )
+                    })
+                });
                 
-                Ok(serde_json::json!({
-                    "app_name": app_name,
-                    "window_title": window_title,
-                    "start_time": start_time,
-                    "duration_seconds": duration_seconds,
-                    "media_info": media_info,
-                    "category_id": category_id
-                }))
+                Ok((
+                    media_kind,
+                    serde_json::json!({
+                        "id": id,
+                        "app_name": app_name,
+                        "window_title": window_title,
+                        "start_time": start_time,
+                        "duration_seconds": duration_seconds,
+                        "media_info": media_info,
+                        "category_id": category_id
+                    }),
+                ))
             }).map_err(|e| e.to_string())?;
             
             let mut results: Vec<Value> = Vec::new();
             let mut seen_songs: std::collections::HashSet<String> = std::collections::HashSet::new();
             
-            for r in rows {
-                if let Ok(val) = r {
-                    let app_name = val.get("app_name").and_then(|a| a.as_str()).unwrap_or("");
-                    
-                    // Check if it's Spotify by checking raw bytes (handles encoding issues)
-                    // Spotify app name can be "Spotify\u00008\u0016\u0001FileV" with embedded nulls
-                    let is_spotify = app_name.as_bytes().windows(7).any(|w| w == b"Spotify") ||
-                                     app_name.starts_with("Spotify");
-                    
-                    // Get media info to check if it's actual music
-                    let media = val.get("media_info").and_then(|m| m.as_object());
-                    let title = media.as_ref().and_then(|m| m.get("title"))
-                        .and_then(|t| t.as_str()).unwrap_or("");
-                    let artist = media.as_ref().and_then(|m| m.get("artist"))
-                        .and_then(|a| a.as_str()).unwrap_or("");
-                    
-                    // Keywords that indicate video content, not music
-                    let video_keywords = [
-                        "tutorial", "course", "how to", "guide", "qwiklab", 
-                        "google cloud", "aws cloud", "certification", "#gsp", 
-                        "feb 2026", "validate data", "finding data", "interacting with",
-                        "vault policies", "google sheets", "data in google"
-                    ];
-                    let title_lower = title.to_lowercase();
-                    let is_video = video_keywords.iter().any(|kw| title_lower.contains(kw));
-                    
-                    // Check if it looks like a song (has artist and title, not too long)
-                    let is_song = !title.is_empty() && !artist.is_empty() && title.len() < 100;
-                    
-                    // Create a unique key for deduplication
-                    let song_key = format!("{}-{}", title, artist);
-                    
-                    // Include if:
-                    // 1. It's Spotify with media info, OR
-                    // 2. It has media info that looks like a song (not a video)
-                    // And we haven't seen this song before (dedupe)
-                    let should_include = (is_spotify && media.is_some()) || 
-                                         (media.is_some() && is_song && !is_video);
-                    
-                    if should_include && !seen_songs.contains(&song_key) {
-                        seen_songs.insert(song_key);
-                        results.push(val);
-                        if results.len() as i32 >= limit {
-                            break;
-                        }
+            let mut scan = ScanProgress::new(progress, "get_music_history");
+            for (media_kind, val) in rows.filter_map(|r| r.ok()) {
+                scan.row();
+                if media_kind != Some(MediaKind::Music) {
+                    continue;
+                }
+                let media = val.get("media_info");
+                let title = media.and_then(|m| m.get("title")).and_then(|t| t.as_str()).unwrap_or("");
+                let artist = media.and_then(|m| m.get("artist")).and_then(|a| a.as_str()).unwrap_or("");
+                
+                // Dedupe repeated sessions of the same track
+                let song_key = format!("{}-{}", title, artist);
+                if seen_songs.insert(song_key) {
+                    results.push(val);
+                    scan.matched(results.len());
+                    if results.len() as i32 >= limit {
+                        break;
                     }
                 }
             }
+            scan.finish();
             
             // Create activity references for frontend (transform to expected format)
             let activity_refs: Vec<Value> = results.iter().map(|track| {
                 let media = track.get("media_info").and_then(|m| m.as_object());
                 let category_id = track.get("category_id").and_then(|v| v.as_i64()).unwrap_or(4);
-                let category_name = category_name_from_id(category_id);
-                // Normalize app name for display (handle Spotify encoding issues)
+                let category_name = crate::utils::categories::name_for(category_id);
                 let app_raw = track.get("app_name").and_then(|a| a.as_str()).unwrap_or("");
-                let is_spotify = app_raw.as_bytes().windows(7).any(|w| w == b"Spotify") || app_raw.starts_with("Spotify");
-                let app_display = if is_spotify {
-                    "Spotify"
-                } else if app_raw.to_lowercase().contains("youtube") {
-                    "YouTube"
-                } else {
-                    app_raw
-                };
+                let app_display = crate::utils::media::player_display_name(app_raw);
                 serde_json::json!({
+                    "id": track.get("id").cloned(),
                     "app": app_display,
                     "title": track.get("window_title").and_then(|t| t.as_str()).unwrap_or(""),
                     "time": track.get("start_time").and_then(|t| t.as_i64()).unwrap_or(0),
@@ -1469,19 +2204,11 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 for (i, track) in results.iter().enumerate() {
                     let media = track.get("media_info").and_then(|m| m.as_object());
                     let app_raw = track.get("app_name").and_then(|a| a.as_str()).unwrap_or("");
-                    // Normalize Spotify app name (handle encoding issues)
-                    let is_spotify = app_raw.as_bytes().windows(7).any(|w| w == b"Spotify") || app_raw.starts_with("Spotify");
-                    let app = if is_spotify {
-                        "Spotify"
-                    } else if app_raw.to_lowercase().contains("youtube") {
-                        "YouTube"
-                    } else {
-                        app_raw
-                    };
+                    let app = crate::utils::media::player_display_name(app_raw);
                     let time = track.get("start_time").and_then(|t| t.as_i64()).unwrap_or(0);
+                    let cite = citation_tag(track.get("id").and_then(|v| v.as_i64()));
                     // Convert Unix timestamp to local time
-                    let dt = chrono::DateTime::from_timestamp(time, 0)
-                        .map(|dt| dt.with_timezone(&chrono::Local).format("%I:%M %p").to_string())
+                    let dt = crate::utils::time::format_local_time(time)
                         .unwrap_or_default();
 
                     if let Some(m) = media {
@@ -1489,18 +2216,20 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                         let artist = m.get("artist").and_then(|a| a.as_str()).unwrap_or("Unknown");
                         let status = m.get("status").and_then(|s| s.as_str()).unwrap_or("");
                         f.push_str(&format!(
-                            "{}. {} - {}\n   {} | {} | {}\n",
+                            "{}. {} - {}{}\n   {} | {} | {}\n",
                             i + 1,
                             title,
                             artist,
+                            cite,
                             app,
                             status,
                             dt
                         ));
                     } else {
                         f.push_str(&format!(
-                            "{}. [Unknown track]\n   {} | {}\n",
+                            "{}. [Unknown track]{}\n   {} | {}\n",
                             i + 1,
+                            cite,
                             app,
                             dt
                         ));
@@ -1521,7 +2250,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
 
             let (sql, params): (&str, Vec<rusqlite::types::Value>) = if let Some(cat) = category_filter {
                 (
-                    "SELECT app_name, window_title, start_time, duration_seconds, category_id, metadata
+                    "SELECT id, app_name, window_title, start_time, duration_seconds, category_id, metadata
                      FROM activities
                      WHERE start_time >= ?1 AND start_time <= ?2 AND category_id = ?3
                      ORDER BY start_time DESC
@@ -1535,7 +2264,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 )
             } else {
                 (
-                    "SELECT app_name, window_title, start_time, duration_seconds, category_id, metadata
+                    "SELECT id, app_name, window_title, start_time, duration_seconds, category_id, metadata
                      FROM activities
                      WHERE start_time >= ?1 AND start_time <= ?2
                      ORDER BY start_time DESC
@@ -1550,18 +2279,19 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
 
             let mut stmt = conn.prepare(sql).map_err(|e| format!("SQL Error: {}", e))?;
             let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
-                let metadata_blob: Option<Vec<u8>> = row.get(5)?;
+                let metadata_blob: Option<Vec<u8>> = row.get(6)?;
                 let media_info = metadata_blob
                     .as_ref()
                     .and_then(|blob| serde_json::from_slice::<ActivityMetadata>(blob).ok())
                     .and_then(|m| m.media_info);
 
                 Ok(serde_json::json!({
-                    "app_name": row.get::<_, String>(0)?,
-                    "window_title": row.get::<_, String>(1)?,
-                    "start_time": row.get::<_, i64>(2)?,
-                    "duration_seconds": row.get::<_, i32>(3)?,
-                    "category_id": row.get::<_, i32>(4)?,
+                    "id": row.get::<_, i64>(0)?,
+                    "app_name": row.get::<_, String>(1)?,
+                    "window_title": row.get::<_, String>(2)?,
+                    "start_time": row.get::<_, i64>(3)?,
+                    "duration_seconds": row.get::<_, i32>(4)?,
+                    "category_id": row.get::<_, i32>(5)?,
                     "media_info": media_info
                 }))
             }).map_err(|e| e.to_string())?;
@@ -1580,11 +2310,12 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                     let category_id = event.get("category_id").and_then(|v| v.as_i64()).unwrap_or(7);
                     let media = event.get("media_info").cloned();
                     serde_json::json!({
+                        "id": event.get("id").cloned(),
                         "app": app,
                         "title": title,
                         "time": time,
                         "duration_seconds": duration,
-                        "category": category_name_from_id(category_id),
+                        "category": crate::utils::categories::name_for(category_id),
                         "media": media
                     })
                 })
@@ -1603,16 +2334,16 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                     let start_time = event.get("start_time").and_then(|v| v.as_i64()).unwrap_or(0);
                     let duration = event.get("duration_seconds").and_then(|v| v.as_i64()).unwrap_or(0);
                     let category_id = event.get("category_id").and_then(|v| v.as_i64()).unwrap_or(7);
-                    let dt = chrono::DateTime::from_timestamp(start_time, 0)
-                        .map(|dt| dt.with_timezone(&chrono::Local).format("%I:%M %p").to_string())
+                    let dt = crate::utils::time::format_local_time(start_time)
                         .unwrap_or_else(|| "Unknown time".to_string());
                     out.push_str(&format!(
-                        "{}. {} | {} | {} | {}\n   {}\n",
+                        "{}. {} | {} | {} | {}{}\n   {}\n",
                         i + 1,
                         app,
-                        category_name_from_id(category_id),
+                        crate::utils::categories::name_for(category_id),
                         dt,
                         format_duration(duration),
+                        citation_tag(event.get("id").and_then(|v| v.as_i64())),
                         if title.is_empty() { "(No window title)".to_string() } else { title.to_string() }
                     ));
                 }
@@ -1627,8 +2358,8 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
             let change_type = args["change_type"].as_str();
             let (start_ts, end_ts) = resolve_window_from_args(args, hours);
             let scope_label = args["scope_label"].as_str().unwrap_or("the selected time range");
-            println!(
-                "[Timeline][FileChanges] Query start: start_ts={}, end_ts={}, limit={}, change_type={}",
+            log::debug!(
+                "File changes query: start_ts={}, end_ts={}, limit={}, change_type={}",
                 start_ts,
                 end_ts,
                 limit,
@@ -1679,8 +2410,8 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 .map_err(|e| e.to_string())?;
 
             let changes: Vec<Value> = rows.filter_map(|r| r.ok()).collect();
-            println!(
-                "[Timeline][FileChanges] Retrieved {} rows (start_ts={}, end_ts={})",
+            log::debug!(
+                "File changes retrieved {} rows (start_ts={}, end_ts={})",
                 changes.len(),
                 start_ts,
                 end_ts
@@ -1691,11 +2422,10 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 let entity_type = item.get("entity_type").and_then(|v| v.as_str()).unwrap_or("file");
                 let preview = item.get("content_preview").and_then(|v| v.as_str());
                 let detected = item.get("detected_at").and_then(|v| v.as_i64()).unwrap_or(0);
-                let dt = chrono::DateTime::from_timestamp(detected, 0)
-                    .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %I:%M:%S %p").to_string())
+                let dt = crate::utils::time::format_local_datetime_secs(detected, "%Y-%m-%d")
                     .unwrap_or_else(|| "Unknown time".to_string());
-                println!(
-                    "[Timeline][FileChanges] {} | {} {} | {}{}",
+                log::trace!(
+                    "File change {} | {} {} | {}{}",
                     dt,
                     entity_type,
                     change,
@@ -1714,8 +2444,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                     let change = item.get("change_type").and_then(|v| v.as_str()).unwrap_or("");
                     let preview = item.get("content_preview").and_then(|v| v.as_str()).unwrap_or("");
                     let detected = item.get("detected_at").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let dt = chrono::DateTime::from_timestamp(detected, 0)
-                        .map(|dt| dt.with_timezone(&chrono::Local).format("%I:%M %p").to_string())
+                    let dt = crate::utils::time::format_local_time(detected)
                         .unwrap_or_else(|| "Unknown time".to_string());
                     out.push_str(&format!(
                         "{}. [{} {}] {} ({})\n   {}\n",
@@ -1735,30 +2464,399 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
 
             Ok((formatted, changes))
         }
-        "resolve_query_scope" => {
-            // This tool lets the LLM request a wider time scope or additional sources.
-            // It returns a confirmation action marker that the frontend will show to the user.
-            let suggested_scope = args["suggested_scope"].as_str().unwrap_or("last_7_days");
-            let reason = args["reason"].as_str().unwrap_or("Your query requires a wider search range.");
-            let enable_sources: Vec<String> = args.get("enable_sources")
-                .and_then(|v| v.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                .unwrap_or_default();
+        "search_clipboard" => {
+            let keyword = args["keyword"].as_str().map(|k| k.trim().to_lowercase()).filter(|k| !k.is_empty());
+            let limit = args["limit"].as_u64().unwrap_or(30).min(200) as i64;
+            let hours = args["hours"].as_u64().unwrap_or(24) as i64;
+            let (start_ts, end_ts) = resolve_window_from_args(args, hours);
+            let scope_label = args["scope_label"].as_str().unwrap_or("the selected time range");
 
-            let payload = serde_json::json!({
-                "kind": "confirm_scope_or_sources",
-                "reason": reason,
-                "suggested_time_range": suggested_scope,
-                "enable_sources": enable_sources,
-                "retry_message": "" // Will use the original query on retry
-            });
+            let pattern = keyword.as_ref().map(|k| format!("%{}%", k)).unwrap_or_else(|| "%".to_string());
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, content, app_name, window_title, captured_at
+                     FROM clipboard_events
+                     WHERE captured_at >= ?1 AND captured_at <= ?2 AND LOWER(content) LIKE ?3
+                     ORDER BY captured_at DESC
+                     LIMIT ?4",
+                )
+                .map_err(|e| e.to_string())?;
+            let entries: Vec<Value> = stmt
+                .query_map(rusqlite::params![start_ts, end_ts, pattern, limit], |row| {
+                    Ok(serde_json::json!({
+                        "id": row.get::<_, i64>(0)?,
+                        "content": row.get::<_, String>(1)?,
+                        "app_name": row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                        "window_title": row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                        "captured_at": row.get::<_, i64>(4)?,
+                    }))
+                })
+                .map_err(|e| e.to_string())?
+                .filter_map(|r| r.ok())
+                .collect();
 
-            let output = format!(
-                "Scope change requested: time range → {}, additional sources → [{}]. Reason: {}",
-                suggested_scope,
-                enable_sources.join(", "),
-                reason
-            );
+            let formatted = if entries.is_empty() {
+                format!(
+                    "No clipboard entries found in {}{}. Clipboard history only exists if the user enabled it in settings.",
+                    scope_label,
+                    keyword.as_ref().map(|k| format!(" matching \"{}\"", k)).unwrap_or_default()
+                )
+            } else {
+                let mut out = format!("Copied text ({}):\n\n", scope_label);
+                for (idx, item) in entries.iter().enumerate() {
+                    let content = item.get("content").and_then(|v| v.as_str()).unwrap_or("");
+                    let app = item.get("app_name").and_then(|v| v.as_str()).unwrap_or("");
+                    let captured_at = item.get("captured_at").and_then(|v| v.as_i64()).unwrap_or(0);
+                    let dt = crate::utils::time::format_local_time(captured_at)
+                        .unwrap_or_else(|| "Unknown time".to_string());
+                    out.push_str(&format!(
+                        "{}. [{}] in {}\n   {}\n",
+                        idx + 1,
+                        dt,
+                        if app.is_empty() { "unknown app" } else { app },
+                        truncate_for_token_limit(&normalize_whitespace(content), 400)
+                    ));
+                }
+                out
+            };
+
+            Ok((formatted, entries))
+        }
+        "get_process_runtime" => {
+            let app = args["app"].as_str().map(str::trim).filter(|a| !a.is_empty());
+            let hours = args["hours"].as_u64().unwrap_or(24) as i64;
+            let (start_ts, end_ts) = resolve_window_from_args(args, hours);
+            let scope_label = args["scope_label"].as_str().unwrap_or("the selected time range");
+
+            let sessions = crate::database::queries::get_process_sessions(conn, app, start_ts, end_ts)
+                .map_err(|e| e.to_string())?;
+            let entries: Vec<Value> = sessions
+                .iter()
+                .map(|s| serde_json::to_value(s).unwrap_or(Value::Null))
+                .collect();
+
+            let formatted = if sessions.is_empty() {
+                format!(
+                    "No process runs found in {}{}. Process runtime is only recorded for apps on the user's process watch list (Settings → Tracking).",
+                    scope_label,
+                    app.map(|a| format!(" for \"{}\"", a)).unwrap_or_default()
+                )
+            } else {
+                // Running time inside the window, per executable.
+                let mut totals: Vec<(String, i64)> = Vec::new();
+                for s in &sessions {
+                    let clipped = s.end_time.unwrap_or(end_ts).min(end_ts) - s.start_time.max(start_ts);
+                    match totals.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case(&s.process_name)) {
+                        Some((_, total)) => *total += clipped.max(0),
+                        None => totals.push((s.process_name.clone(), clipped.max(0))),
+                    }
+                }
+                let mut out = format!("Process runs overlapping {}:\n\n", scope_label);
+                for (idx, s) in sessions.iter().enumerate() {
+                    let started = crate::utils::time::format_local_datetime(s.start_time, "%b %d")
+                        .unwrap_or_else(|| "unknown".to_string());
+                    let ended = match s.end_time {
+                        Some(end) => crate::utils::time::format_local_datetime(end, "%b %d")
+                            .unwrap_or_else(|| "unknown".to_string()),
+                        None => "still running".to_string(),
+                    };
+                    out.push_str(&format!(
+                        "{}. {} (pid {}) {} → {} ({}){}\n",
+                        idx + 1,
+                        s.process_name,
+                        s.pid,
+                        started,
+                        ended,
+                        format_duration(s.duration_seconds),
+                        s.parent_name.as_ref().map(|p| format!(", started by {}", p)).unwrap_or_default()
+                    ));
+                }
+                out.push_str("\nRunning time within the range:\n");
+                for (name, total) in totals {
+                    out.push_str(&format!("- {}: {}\n", name, format_duration(total)));
+                }
+                out
+            };
+
+            Ok((formatted, entries))
+        }
+        "get_meetings" => {
+            let hours = args["hours"].as_u64().unwrap_or(24) as i64;
+            let (start_ts, end_ts) = resolve_window_from_args(args, hours);
+            let scope_label = args["scope_label"].as_str().unwrap_or("the selected time range");
+
+            let report = crate::services::dashboard_engine::meetings_report(conn, start_ts, end_ts, policy.include_private)?;
+            let entries: Vec<Value> = report
+                .meetings
+                .iter()
+                .map(|m| serde_json::to_value(m).unwrap_or(Value::Null))
+                .collect();
+
+            let formatted = if report.meetings.is_empty() {
+                format!(
+                    "No meetings found in {}. Meetings are detected from Teams, Zoom and Google Meet window titles and need at least 10 minutes in the meeting window.",
+                    scope_label
+                )
+            } else {
+                let mut out = format!(
+                    "{} meeting(s) in {}, {} in total:\n\n",
+                    report.meetings.len(),
+                    scope_label,
+                    format_duration(report.total_duration_seconds)
+                );
+                for (idx, m) in report.meetings.iter().enumerate() {
+                    let started = crate::utils::time::format_local_datetime(m.start_time, "%b %d")
+                        .unwrap_or_else(|| "unknown".to_string());
+                    out.push_str(&format!(
+                        "{}. {} — {} ({}) in {}{}\n",
+                        idx + 1,
+                        m.name,
+                        started,
+                        format_duration(m.duration_seconds),
+                        m.apps.join(", "),
+                        if m.attendees.is_empty() {
+                            String::new()
+                        } else {
+                            format!("; attendees (from screen text): {}", m.attendees.join(", "))
+                        }
+                    ));
+                }
+                out
+            };
+
+            Ok((formatted, entries))
+        }
+        "get_detected_patterns" => {
+            let kind = args["kind"].as_str().and_then(crate::models::PatternKind::parse);
+            let patterns = crate::services::pattern_engine::load_patterns(conn, kind, false)
+                .map_err(|e| e.to_string())?;
+            let items: Vec<Value> = patterns
+                .iter()
+                .map(|p| serde_json::to_value(p).unwrap_or(Value::Null))
+                .collect();
+
+            let formatted = if patterns.is_empty() {
+                format!(
+                    "No detected patterns{}. The pattern engine needs a few weekdays of history; fall back to activity queries.",
+                    kind.map(|k| format!(" of kind {}", k.as_str())).unwrap_or_default()
+                )
+            } else {
+                let mut out = String::from("Detected patterns (computed in the background):\n\n");
+                for (idx, p) in patterns.iter().enumerate() {
+                    out.push_str(&format!(
+                        "{}. [{}] {} (confidence {:.0}%, based on {})\n",
+                        idx + 1,
+                        p.kind.as_str(),
+                        p.description,
+                        p.confidence * 100.0,
+                        p.supporting_window
+                    ));
+                }
+                out
+            };
+
+            Ok((formatted, items))
+        }
+        "get_entries" => {
+            let status = args["status"]
+                .as_str()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| s == "active" || s == "completed");
+            let entry_type = args["entry_type"]
+                .as_str()
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| t == "task" || t == "note" || t == "goal");
+            let limit = args["limit"].as_u64().unwrap_or(50).clamp(1, 200) as i64;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, entry_type, title, COALESCE(content, ''), COALESCE(status, 'active'), created_at, completed_at
+                     FROM manual_entries
+                     WHERE COALESCE(status, 'active') != 'archived'
+                       AND (?1 IS NULL OR COALESCE(status, 'active') = ?1)
+                       AND (?2 IS NULL OR entry_type = ?2)
+                     ORDER BY CASE WHEN COALESCE(status, 'active') = 'completed' THEN 1 ELSE 0 END, updated_at DESC
+                     LIMIT ?3",
+                )
+                .map_err(|e| e.to_string())?;
+            let entries: Vec<Value> = stmt
+                .query_map(rusqlite::params![status, entry_type, limit], |row| {
+                    let content: String = row.get(3)?;
+                    Ok(serde_json::json!({
+                        "id": row.get::<_, i64>(0)?,
+                        "entry_type": row.get::<_, String>(1)?,
+                        "title": row.get::<_, String>(2)?,
+                        "due": crate::services::dashboard_engine::extract_due_hint(&content),
+                        "content": content,
+                        "status": row.get::<_, String>(4)?,
+                        "created_at": row.get::<_, i64>(5)?,
+                        "completed_at": row.get::<_, Option<i64>>(6)?,
+                    }))
+                })
+                .map_err(|e| e.to_string())?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            let formatted = if entries.is_empty() {
+                "No entries found. The user has not added matching tasks or notes in the app.".to_string()
+            } else {
+                let mut out = format!("{} entr{} (newest first, open before completed):\n\n", entries.len(), if entries.len() == 1 { "y" } else { "ies" });
+                for (idx, e) in entries.iter().enumerate() {
+                    let created = e["created_at"]
+                        .as_i64()
+                        .and_then(|ts| crate::utils::time::format_local_datetime(ts, "%b %d"))
+                        .unwrap_or_else(|| "unknown".to_string());
+                    let content = normalize_whitespace(&e["content"].as_str().unwrap_or("").chars().take(200).collect::<String>());
+                    out.push_str(&format!(
+                        "{}. [{}] {} — {}{}, added {}{}\n",
+                        idx + 1,
+                        e["entry_type"].as_str().unwrap_or("entry"),
+                        e["title"].as_str().unwrap_or(""),
+                        e["status"].as_str().unwrap_or("active"),
+                        e["due"].as_str().map(|d| format!(", {}", d)).unwrap_or_default(),
+                        created,
+                        if content.trim().is_empty() {
+                            String::new()
+                        } else {
+                            format!("\n   {}", content.trim())
+                        }
+                    ));
+                }
+                out
+            };
+
+            Ok((formatted, entries))
+        }
+        "search_chat_history" => {
+            let keyword = args["keyword"].as_str().map(|k| k.trim().to_lowercase()).filter(|k| !k.is_empty());
+            let role = args["role"]
+                .as_str()
+                .map(|r| r.trim().to_lowercase())
+                .filter(|r| r == "user" || r == "assistant");
+            let limit = args["limit"].as_u64().unwrap_or(20).clamp(1, 100) as usize;
+            let current_only = args["session"]
+                .as_str()
+                .map(|s| s.trim().eq_ignore_ascii_case("current"))
+                .unwrap_or(false);
+            let session_filter = if current_only { policy.chat_session_id.as_deref() } else { None };
+            let (start_ts, end_ts) = chat_history_window(conn, args);
+
+            // The LIKE only narrows the scan; matches are confirmed on the cleaned text
+            // so reasoning blocks and action markers never produce hits.
+            let pattern = keyword.as_ref().map(|k| format!("%{}%", k)).unwrap_or_else(|| "%".to_string());
+            let scan_limit = (limit * 10).max(200) as i64;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT m.id, m.session_id, m.role, m.content, m.created_at, COALESCE(s.title, '')
+                     FROM chat_messages m
+                     LEFT JOIN chat_sessions s ON s.id = m.session_id
+                     WHERE m.created_at >= ?1 AND m.created_at <= ?2
+                       AND LOWER(m.content) LIKE ?3
+                       AND (?4 IS NULL OR m.role = ?4)
+                       AND (?5 IS NULL OR m.session_id = ?5)
+                       AND m.id <> COALESCE((SELECT MAX(id) FROM chat_messages WHERE session_id = ?6), -1)
+                     ORDER BY m.created_at DESC, m.id DESC
+                     LIMIT ?7",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows: Vec<(i64, String, String, String, i64, String)> = stmt
+                .query_map(
+                    rusqlite::params![
+                        start_ts,
+                        end_ts,
+                        pattern,
+                        role,
+                        session_filter,
+                        policy.chat_session_id,
+                        scan_limit
+                    ],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+                )
+                .map_err(|e| e.to_string())?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            let mut entries: Vec<Value> = Vec::new();
+            for (id, session_id, role, content, created_at, title) in rows {
+                let cleaned = strip_action_markers(&strip_think_blocks(&content));
+                let cleaned = cleaned.trim();
+                if cleaned.is_empty() {
+                    continue;
+                }
+                if let Some(k) = keyword.as_ref() {
+                    if !cleaned.to_lowercase().contains(k.as_str()) {
+                        continue;
+                    }
+                }
+                entries.push(serde_json::json!({
+                    "id": id,
+                    "session_id": session_id,
+                    "session_title": title,
+                    "role": role,
+                    "created_at": created_at,
+                    "snippet": snippet_around(cleaned, keyword.as_deref(), 300),
+                }));
+                if entries.len() >= limit {
+                    break;
+                }
+            }
+
+            let where_label = if session_filter.is_some() { "this conversation" } else { "all conversations" };
+            let formatted = if entries.is_empty() {
+                format!(
+                    "No matching chat messages found in {}{}.",
+                    where_label,
+                    keyword.as_ref().map(|k| format!(" for \"{}\"", k)).unwrap_or_default()
+                )
+            } else {
+                let mut out = format!("Earlier chat messages ({}, newest first):\n\n", where_label);
+                for (idx, item) in entries.iter().enumerate() {
+                    let created_at = item.get("created_at").and_then(|v| v.as_i64()).unwrap_or(0);
+                    let dt = crate::utils::time::format_local_datetime(created_at, "%b %d")
+                        .unwrap_or_else(|| "Unknown time".to_string());
+                    let role = item.get("role").and_then(|v| v.as_str()).unwrap_or("");
+                    let title = item.get("session_title").and_then(|v| v.as_str()).unwrap_or("");
+                    out.push_str(&format!(
+                        "{}. [{}] {}{}\n   {}\n",
+                        idx + 1,
+                        dt,
+                        if role == "assistant" { "you (assistant)" } else { "user" },
+                        if title.is_empty() { String::new() } else { format!(" in \"{}\"", title) },
+                        item.get("snippet").and_then(|v| v.as_str()).unwrap_or("")
+                    ));
+                }
+                out
+            };
+
+            Ok((formatted, entries))
+        }
+        "ask_clarification" => {
+            // Normally intercepted by the agent loop; inside parallel_search it can't
+            // pause the run, so the question is handed back as plain output.
+            let question = args["question"].as_str().unwrap_or("").trim();
+            if question.is_empty() {
+                return Err("Missing 'question' argument".to_string());
+            }
+            Ok((
+                format!("Clarification can't be asked from parallel_search. Ask it on its own turn: {}", question),
+                Vec::new(),
+            ))
+        }
+        "resolve_query_scope" => {
+            // This tool lets the LLM request a wider time scope or additional sources.
+            // It returns a confirmation action that the frontend will show to the user.
+            // An empty retry message means the original query is used on retry.
+            let action = scope_action_from_args(args, "");
+            let output = match &action {
+                AgentAction::ConfirmScopeOrSources { reason, suggested_time_range, enable_sources, .. } => format!(
+                    "Scope change requested: time range → {}, additional sources → [{}]. Reason: {}",
+                    suggested_time_range.as_deref().unwrap_or("unchanged"),
+                    enable_sources.join(", "),
+                    reason
+                ),
+                _ => String::new(),
+            };
+            let payload = serde_json::to_value(&action).map_err(|e| e.to_string())?;
 
             Ok((output, vec![payload]))
         }
@@ -1811,12 +2909,13 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
             
             // Search in metadata blobs (inefficient but works for now without FTS5)
             // Ideally we'd have a separate text table.
-            let mut stmt = conn.prepare(
-                "SELECT start_time, app_name, window_title, duration_seconds, category_id, metadata FROM activities 
-                 WHERE start_time >= ?1 AND start_time <= ?2
+            let mut stmt = conn.prepare(&format!(
+                "SELECT start_time, app_name, window_title, duration_seconds, category_id, metadata, id FROM activities 
+                 WHERE start_time >= ?1 AND start_time <= ?2{}
                  AND LOWER(CAST(metadata AS TEXT)) LIKE ?3
-                 ORDER BY start_time DESC LIMIT 20000"
-            ).map_err(|e| e.to_string())?;
+                 ORDER BY start_time DESC LIMIT 20000",
+                category_clause(args)
+            )).map_err(|e| e.to_string())?;
             
             let mut matches: Vec<Value> = Vec::new();
             let mut seen_snippets = std::collections::HashSet::new();
@@ -1829,12 +2928,16 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                     row.get::<_, String>(2)?,
                     row.get::<_, i32>(3)?,
                     row.get::<_, i32>(4)?,
-                    row.get::<_, Option<Vec<u8>>>(5)?
+                    row.get::<_, Option<Vec<u8>>>(5)?,
+                    row.get::<_, i64>(6)?
                 ))
             }).map_err(|e| e.to_string())?;
             
+            // Rows are stepped lazily, so stopping at `limit` also stops the LIKE scan.
+            let mut scan = ScanProgress::new(progress, "search_ocr");
             for r in rows {
-                if let Ok((start_time, app_name, window_title, duration_seconds, category_id, meta_blob)) = r {
+                scan.row();
+                if let Ok((start_time, app_name, window_title, duration_seconds, category_id, meta_blob, id)) = r {
                      if app_name.to_lowercase().contains("intentflow") {
                          continue;
                      }
@@ -1846,12 +2949,13 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                                     continue;
                                 }
                                 if cleaned.to_lowercase().contains(&keyword.to_lowercase()) {
-                                    let snippet = truncate_snippet(&cleaned, &keyword.to_lowercase());
+                                    let snippet = normalize_lines(&truncate_snippet(&cleaned, &keyword.to_lowercase()));
                                     let short = normalize_whitespace(&snippet.chars().take(500).collect::<String>());
-                                    if !seen_snippets.insert(short.clone()) {
+                                    if !seen_snippets.insert(short) {
                                         continue;
                                     }
                                     matches.push(serde_json::json!({
+                                        "id": id,
                                         "app_name": app_name,
                                         "window_title": window_title,
                                         "start_time": start_time,
@@ -1862,6 +2966,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                                             "ocr_snippet": snippet
                                         }
                                     }));
+                                    scan.matched(matches.len());
                                     if matches.len() >= limit { break; }
                                 }
                             }
@@ -1869,6 +2974,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                      }
                 }
             }
+            scan.finish();
             let formatted = if matches.is_empty() {
                 format!("No OCR results found for '{}'.", keyword)
             } else {
@@ -1876,15 +2982,15 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 for (i, item) in matches.iter().enumerate() {
                     let app = item.get("app_name").and_then(|v| v.as_str()).unwrap_or("Unknown");
                     let start_time = item.get("start_time").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let dt = chrono::DateTime::from_timestamp(start_time, 0)
-                        .map(|dt| dt.with_timezone(&chrono::Local).format("%I:%M %p").to_string())
+                    let dt = crate::utils::time::format_local_time(start_time)
                         .unwrap_or_else(|| "Unknown time".to_string());
                     let snippet = item
                         .get("metadata")
                         .and_then(|m| m.get("ocr_snippet"))
                         .and_then(|v| v.as_str())
                         .unwrap_or("");
-                    out.push_str(&format!("{}. {} at {}\n   {}\n", i + 1, app, dt, snippet));
+                    let cite = citation_tag(item.get("id").and_then(|v| v.as_i64()));
+                    out.push_str(&format!("{}. {} at {}{}\n   {}\n", i + 1, app, dt, cite, indent_snippet(snippet)));
                 }
                 out
             };
@@ -1899,15 +3005,16 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
             let scope_label = args["scope_label"].as_str().unwrap_or("the selected time range");
             let scan_limit = std::cmp::max((limit as i64) * 50, 10000);
 
-            let mut stmt = conn.prepare(
-                "SELECT start_time, app_name, window_title, duration_seconds, category_id, metadata
+            let mut stmt = conn.prepare(&format!(
+                "SELECT start_time, app_name, window_title, duration_seconds, category_id, metadata, id
                  FROM activities
-                 WHERE start_time >= ?1 AND start_time <= ?2 AND metadata IS NOT NULL
+                 WHERE start_time >= ?1 AND start_time <= ?2 AND metadata IS NOT NULL{}
                  AND (?4 IS NULL OR LOWER(app_name) LIKE ?4)
                  AND (?5 IS NULL OR LOWER(CAST(metadata AS TEXT)) LIKE ?5)
                  ORDER BY start_time DESC
-                 LIMIT ?3"
-            ).map_err(|e| e.to_string())?;
+                 LIMIT ?3",
+                category_clause(args)
+            )).map_err(|e| e.to_string())?;
 
             let app_param = app_filter.as_ref().map(|a| format!("%{}%", a));
             let kw_param = keyword.as_ref().map(|k| format!("%{}%", k));
@@ -1919,14 +3026,17 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                     row.get::<_, String>(2)?,
                     row.get::<_, i32>(3)?,
                     row.get::<_, i32>(4)?,
-                    row.get::<_, Option<Vec<u8>>>(5)?
+                    row.get::<_, Option<Vec<u8>>>(5)?,
+                    row.get::<_, i64>(6)?
                 ))
             }).map_err(|e| e.to_string())?;
 
             let mut seen_snippets = std::collections::HashSet::new();
             let mut results: Vec<Value> = Vec::new();
+            let mut scan = ScanProgress::new(progress, "get_recent_ocr");
             for row in rows {
-                if let Ok((start_time, app_name, window_title, duration_seconds, category_id, metadata_blob)) = row {
+                scan.row();
+                if let Ok((start_time, app_name, window_title, duration_seconds, category_id, metadata_blob, id)) = row {
                     if app_name.to_lowercase().contains("intentflow") {
                         continue;
                     }
@@ -1949,12 +3059,13 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                                     }
                                 }
 
-                                let short = normalize_whitespace(&normalized_text.chars().take(500).collect::<String>());
-                                if !seen_snippets.insert(short.clone()) {
+                                let short = normalize_lines(&normalized_text.chars().take(500).collect::<String>());
+                                if !seen_snippets.insert(normalize_whitespace(&short)) {
                                     continue;
                                 }
 
                                 results.push(serde_json::json!({
+                                    "id": id,
                                     "app_name": app_name,
                                     "window_title": window_title,
                                     "start_time": start_time,
@@ -1965,6 +3076,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                                         "ocr_snippet": short
                                     }
                                 }));
+                                scan.matched(results.len());
 
                                 if results.len() >= limit {
                                     break;
@@ -1974,6 +3086,7 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                     }
                 }
             }
+            scan.finish();
 
             let activity_refs: Vec<Value> = results.iter().map(|item| {
                 let app = item.get("app_name").and_then(|v| v.as_str()).unwrap_or("");
@@ -1982,11 +3095,12 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 let duration = item.get("duration_seconds").and_then(|v| v.as_i64()).unwrap_or(0);
                 let category_id = item.get("category_id").and_then(|v| v.as_i64()).unwrap_or(7);
                 serde_json::json!({
+                    "id": item.get("id").cloned(),
                     "app": app,
                     "title": title,
                     "time": time,
                     "duration_seconds": duration,
-                    "category": category_name_from_id(category_id),
+                    "category": crate::utils::categories::name_for(category_id),
                     "media": Value::Null
                 })
             }).collect();
@@ -1998,15 +3112,15 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
                 for (i, item) in results.iter().enumerate() {
                     let app = item.get("app_name").and_then(|v| v.as_str()).unwrap_or("Unknown");
                     let start_time = item.get("start_time").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let dt = chrono::DateTime::from_timestamp(start_time, 0)
-                        .map(|dt| dt.with_timezone(&chrono::Local).format("%I:%M %p").to_string())
+                    let dt = crate::utils::time::format_local_time(start_time)
                         .unwrap_or_else(|| "Unknown time".to_string());
                     let snippet = item
                         .get("metadata")
                         .and_then(|m| m.get("ocr_snippet"))
                         .and_then(|v| v.as_str())
                         .unwrap_or("");
-                    out.push_str(&format!("{}. {} at {}\n   {}\n", i + 1, app, dt, snippet));
+                    let cite = citation_tag(item.get("id").and_then(|v| v.as_i64()));
+                    out.push_str(&format!("{}. {} at {}{}\n   {}\n", i + 1, app, dt, cite, indent_snippet(snippet)));
                 }
                 out
             };
@@ -2020,13 +3134,14 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
             let s_ts = parse_iso_to_unix(start).unwrap_or(0);
             let e_ts = parse_iso_to_unix(end).unwrap_or(chrono::Utc::now().timestamp());
             
-            let mut stmt = conn.prepare(
-                "SELECT app_name, SUM(duration_seconds) as total_dur, COUNT(*) as cnt
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} AS app, SUM(duration_seconds) as total_dur, COUNT(*) as cnt
                  FROM activities 
                  WHERE start_time >= ?1 AND start_time <= ?2 
-                 GROUP BY app_name
-                 ORDER BY total_dur DESC LIMIT 20"
-            ).map_err(|e| e.to_string())?;
+                 GROUP BY app
+                 ORDER BY total_dur DESC LIMIT 20",
+                crate::database::queries::MERGED_APP_NAME_SQL
+            )).map_err(|e| e.to_string())?;
             
             let rows = stmt.query_map(rusqlite::params![s_ts, e_ts], |row: &rusqlite::Row| {
                 Ok(serde_json::json!({
@@ -2039,6 +3154,18 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
             let results: Vec<Value> = rows.filter_map(|r: Result<Value, rusqlite::Error>| r.ok()).collect();
             Ok((serde_json::to_string(&results).unwrap_or_else(|_| "[]".to_string()), results))
         },
+        "compare_usage" => {
+            let filter = UsageFilter {
+                category_id: args["category_id"].as_i64(),
+                app: args["app"].as_str().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            };
+            let range_a = resolve_compare_range(conn, &args["range_a"], "Range A")?;
+            let range_b = resolve_compare_range(conn, &args["range_b"], "Range B")?;
+            let usage_a = aggregate_range_usage(conn, &range_a, &filter)?;
+            let usage_b = aggregate_range_usage(conn, &range_b, &filter)?;
+            let result = build_usage_comparison(&usage_a, &usage_b, &filter);
+            Ok((serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string()), Vec::new()))
+        },
         "query_history" => {
              // Alias for old query_activities call?
               Err("Use query_activities instead".to_string()) 
@@ -2047,18 +3174,256 @@ fn execute_tool(conn: &Connection, tool: &str, args: &Value) -> Result<(String,
     }
 }
 
-// ─── Helpers ───
+// ─── Usage comparison ───
+
+#[derive(Clone, Debug, Default)]
+struct UsageFilter {
+    category_id: Option<i64>,
+    app: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct CompareRange {
+    label: String,
+    start_ts: i64,
+    end_ts: i64,
+}
+
+#[derive(Clone, Debug)]
+struct RangeUsage {
+    range: CompareRange,
+    total_seconds: i64,
+    /// Calendar days covered (partial days round up), used to normalize ranges of unequal length.
+    days: i64,
+    categories: Vec<(String, i64)>,
+    top_apps: Vec<(String, i64)>,
+}
+
+impl RangeUsage {
+    fn per_day_seconds(&self) -> i64 {
+        self.total_seconds / self.days.max(1)
+    }
+}
+
+/// Accepts `{scope: "..."}` or explicit bounds (`start_time_iso`/`end_time_iso` or `start_ts`/`end_ts`).
+fn resolve_compare_range(conn: &Connection, spec: &Value, default_label: &str) -> Result<CompareRange, String> {
+    let now = chrono::Utc::now().timestamp();
+    let label = spec["label"].as_str().map(|s| s.to_string());
+
+    if let Some(scope_id) = spec["scope"].as_str().or_else(|| spec.as_str()) {
+        let (start_ts, end_ts, scope_label) = resolve_compare_scope(conn, scope_id, now)
+            .ok_or_else(|| format!("Unknown scope '{}' for {}", scope_id, default_label))?;
+        return Ok(CompareRange {
+            label: label.unwrap_or(scope_label),
+            start_ts,
+            end_ts,
+        });
+    }
+
+    let start_ts = spec["start_ts"]
+        .as_i64()
+        .or_else(|| spec["start_time_iso"].as_str().and_then(parse_iso_to_unix));
+    let end_ts = spec["end_ts"]
+        .as_i64()
+        .or_else(|| spec["end_time_iso"].as_str().and_then(parse_iso_to_unix));
+    let (Some(mut start_ts), Some(mut end_ts)) = (start_ts, end_ts) else {
+        return Err(format!(
+            "{} needs either a scope or both start_time_iso and end_time_iso",
+            default_label
+        ));
+    };
+    if start_ts > end_ts {
+        std::mem::swap(&mut start_ts, &mut end_ts);
+    }
+    Ok(CompareRange {
+        label: label.unwrap_or_else(|| default_label.to_string()),
+        start_ts: start_ts.max(0),
+        end_ts: end_ts.min(now),
+    })
+}
+
+fn resolve_compare_scope(conn: &Connection, scope_id: &str, now: i64) -> Option<(i64, i64, String)> {
+    let today = crate::utils::time::today();
+    let day_start = |date: chrono::NaiveDate| crate::utils::time::day_bounds(Some(conn), date).start_ts;
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = today.with_day(1)?;
+    let prev_month_start = (month_start - Duration::days(1)).with_day(1)?;
+
+    let resolved = match scope_id.trim().to_lowercase().as_str() {
+        "this_week" => (day_start(week_start), now, "This Week"),
+        "last_week" => (day_start(week_start - Duration::days(7)), day_start(week_start), "Last Week"),
+        "this_month" => (day_start(month_start), now, "This Month"),
+        "last_month" => (day_start(prev_month_start), day_start(month_start), "Last Month"),
+        id @ ("today" | "yesterday" | "last_3_days" | "last_7_days" | "last_30_days" | "this_year" | "all_time") => {
+            let scope = resolve_time_scope(Some(id), Some(conn));
+            return Some((scope.start_ts, scope.end_ts, scope.label));
+        }
+        other => {
+            let range = crate::utils::date_expr::parse_date_expression(other, Some(conn))?;
+            return Some((range.start_ts, range.end_ts, range.label));
+        }
+    };
+    Some((resolved.0, resolved.1, resolved.2.to_string()))
+}
+
+fn aggregate_range_usage(conn: &Connection, range: &CompareRange, filter: &UsageFilter) -> Result<RangeUsage, String> {
+    let app_pattern = filter.app.as_ref().map(|a| format!("%{}%", a.to_lowercase()));
+    let mut stmt = conn
+        .prepare(
+            "SELECT app_name, category_id, SUM(duration_seconds)
+             FROM activities
+             WHERE start_time >= ?1 AND start_time < ?2
+               AND (?3 IS NULL OR category_id = ?3)
+               AND (?4 IS NULL OR LOWER(app_name) LIKE ?4)
+             GROUP BY app_name, category_id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![range.start_ts, range.end_ts, filter.category_id, app_pattern],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut total_seconds = 0i64;
+    let mut by_category: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    let mut by_app: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    for (app, category_id, seconds) in rows.filter_map(|r| r.ok()) {
+        total_seconds += seconds;
+        *by_category.entry(crate::utils::categories::name_for(category_id)).or_insert(0) += seconds;
+        *by_app.entry(app).or_insert(0) += seconds;
+    }
+
+    let mut categories: Vec<(String, i64)> = by_category.into_iter().collect();
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let mut top_apps: Vec<(String, i64)> = by_app.into_iter().collect();
+    top_apps.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_apps.truncate(5);
+
+    let span = (range.end_ts - range.start_ts).max(0);
+    Ok(RangeUsage {
+        range: range.clone(),
+        total_seconds,
+        days: ((span + 86_399) / 86_400).max(1),
+        categories,
+        top_apps,
+    })
+}
+
+/// Percent change from `base` to `value`; `None` when there is no baseline to compare against.
+fn percent_change(value: i64, base: i64) -> Option<f64> {
+    if base <= 0 {
+        return None;
+    }
+    Some((((value - base) as f64 / base as f64) * 1000.0).round() / 10.0)
+}
+
+fn range_usage_json(usage: &RangeUsage) -> Value {
+    serde_json::json!({
+        "label": usage.range.label,
+        "from": format_time_scope_ts(usage.range.start_ts, crate::utils::i18n::default_lang()),
+        "to": format_time_scope_ts(usage.range.end_ts, crate::utils::i18n::default_lang()),
+        "days": usage.days,
+        "total_seconds": usage.total_seconds,
+        "total": format_duration(usage.total_seconds),
+        "per_day_seconds": usage.per_day_seconds(),
+        "per_day": format_duration(usage.per_day_seconds()),
+        "categories": usage.categories.iter()
+            .map(|(name, secs)| serde_json::json!({ "category": name, "seconds": secs }))
+            .collect::<Vec<_>>(),
+        "top_apps": usage.top_apps.iter()
+            .map(|(name, secs)| serde_json::json!({ "app": name, "seconds": secs }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Deltas are range_a minus range_b. Percent changes use per-day averages so a
+/// partial week can be compared with a full one.
+fn build_usage_comparison(a: &RangeUsage, b: &RangeUsage, filter: &UsageFilter) -> Value {
+    let mut category_names: Vec<&String> = a.categories.iter().chain(b.categories.iter()).map(|(n, _)| n).collect();
+    category_names.sort();
+    category_names.dedup();
+    let seconds_in = |usage: &RangeUsage, name: &str| {
+        usage.categories.iter().find(|(n, _)| n == name).map(|(_, s)| *s).unwrap_or(0)
+    };
+    let category_deltas: Vec<Value> = category_names
+        .into_iter()
+        .map(|name| {
+            let sa = seconds_in(a, name);
+            let sb = seconds_in(b, name);
+            let per_day_a = sa / a.days.max(1);
+            let per_day_b = sb / b.days.max(1);
+            serde_json::json!({
+                "category": name,
+                "a_seconds": sa,
+                "b_seconds": sb,
+                "delta_seconds": sa - sb,
+                "per_day_delta_seconds": per_day_a - per_day_b,
+                "pct_change_per_day": percent_change(per_day_a, per_day_b),
+            })
+        })
+        .collect();
 
-fn category_name_from_id(category_id: i64) -> &'static str {
-    match category_id {
-        1 => "Development",
-        2 => "Browser",
-        3 => "Communication",
-        4 => "Entertainment",
-        5 => "Productivity",
-        6 => "System",
-        _ => "Other",
+    let mut notes: Vec<String> = Vec::new();
+    if a.total_seconds == 0 {
+        notes.push(format!("No matching activity in {}.", a.range.label));
+    }
+    if b.total_seconds == 0 {
+        notes.push(format!("No matching activity in {}; percent change is undefined.", b.range.label));
+    }
+    if a.days != b.days {
+        notes.push(format!(
+            "Ranges differ in length ({} vs {} days); compare per-day averages.",
+            a.days, b.days
+        ));
     }
+
+    serde_json::json!({
+        "filter": {
+            "category": filter.category_id.map(crate::utils::categories::name_for),
+            "app": filter.app,
+        },
+        "range_a": range_usage_json(a),
+        "range_b": range_usage_json(b),
+        "delta": {
+            "total_seconds": a.total_seconds - b.total_seconds,
+            "per_day_seconds": a.per_day_seconds() - b.per_day_seconds(),
+            "pct_change_total": percent_change(a.total_seconds, b.total_seconds),
+            "pct_change_per_day": percent_change(a.per_day_seconds(), b.per_day_seconds()),
+            "categories": category_deltas,
+        },
+        "notes": notes,
+    })
+}
+
+// ─── Helpers ───
+
+/// ` [#1234]` tag appended to a tool result line so the answer can cite that
+/// activity row; the chat UI turns the tag into a link to the row's capture.
+fn citation_tag(id: Option<i64>) -> String {
+    id.map(|id| format!(" [#{}]", id)).unwrap_or_default()
+}
+
+/// Remove `[#id]` tags that don't match a referenced activity, so every link
+/// left in the answer opens a row the agent actually looked at.
+fn drop_unknown_citations(answer: &str, activities: &[Value]) -> String {
+    use regex::Regex;
+    let known: std::collections::HashSet<i64> = activities
+        .iter()
+        .filter_map(|a| a.get("id").and_then(|v| v.as_i64()))
+        .collect();
+    let Ok(re) = Regex::new(r"\s?\[#(\d+)\]") else {
+        return answer.to_string();
+    };
+    re.replace_all(answer, |caps: &regex::Captures| {
+        let cited = caps[1].parse::<i64>().ok();
+        if cited.is_some_and(|id| known.contains(&id)) {
+            caps[0].to_string()
+        } else {
+            String::new()
+        }
+    })
+    .into_owned()
 }
 
 fn transform_activities_for_frontend(tool: &str, tool_activities: &[Value]) -> Vec<Value> {
@@ -2101,17 +3466,39 @@ fn transform_activities_for_frontend(tool: &str, tool_activities: &[Value]) -> V
             .collect();
     }
 
+    if tool == "get_meetings" {
+        return tool_activities
+            .iter()
+            .map(|meeting| {
+                let apps = meeting
+                    .get("apps")
+                    .and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "))
+                    .unwrap_or_default();
+                serde_json::json!({
+                    "app": apps,
+                    "title": meeting.get("name").and_then(|v| v.as_str()).unwrap_or(""),
+                    "time": meeting.get("start_time").and_then(|v| v.as_i64()).unwrap_or(0),
+                    "duration_seconds": meeting.get("duration_seconds").and_then(|v| v.as_i64()).unwrap_or(0),
+                    "category": "Communication",
+                    "media": Value::Null
+                })
+            })
+            .collect();
+    }
+
     if tool == "query_activities" || tool == "search_ocr" {
         let mut transformed = Vec::new();
         for act in tool_activities {
             let media = act.get("metadata").and_then(|m| m.get("media_info")).cloned();
             let category_id = act.get("category_id").and_then(|v| v.as_i64()).unwrap_or(0);
             transformed.push(serde_json::json!({
+                "id": act.get("id").and_then(|v| v.as_i64()),
                 "app": act.get("app_name").and_then(|v| v.as_str()).unwrap_or(""),
                 "title": act.get("window_title").and_then(|v| v.as_str()).unwrap_or(""),
                 "time": act.get("start_time").and_then(|v| v.as_i64()).unwrap_or(0),
                 "duration_seconds": act.get("duration_seconds").and_then(|v| v.as_i64()).unwrap_or(0),
-                "category": category_name_from_id(category_id),
+                "category": crate::utils::categories::name_for(category_id),
                 "media": media,
             }));
         }
@@ -2122,19 +3509,7 @@ fn transform_activities_for_frontend(tool: &str, tool_activities: &[Value]) -> V
 }
 
 fn format_duration(total_seconds: i64) -> String {
-    if total_seconds <= 0 {
-        return "0s".to_string();
-    }
-    let hours = total_seconds / 3600;
-    let minutes = (total_seconds % 3600) / 60;
-    let seconds = total_seconds % 60;
-    if hours > 0 {
-        format!("{}h {}m {}s", hours, minutes, seconds)
-    } else if minutes > 0 {
-        format!("{}m {}s", minutes, seconds)
-    } else {
-        format!("{}s", seconds)
-    }
+    crate::utils::i18n::format_duration(total_seconds, crate::utils::i18n::default_lang())
 }
 
 fn normalize_final_answer(answer: &str) -> String {
@@ -2150,6 +3525,8 @@ fn normalize_final_answer_hardened(answer: &str) -> String {
     let cleaned = strip_think_blocks(&cleaned);
     let cleaned = strip_internal_stream_markup(&cleaned);
     let cleaned = strip_reasoning_fragments(&cleaned);
+    // Actions travel in `AgentResult::actions`; a marker here was echoed by the model.
+    let cleaned = strip_action_markers(&cleaned);
     cleaned
         .lines()
         .filter(|line| !contains_internal_tool_markup(line))
@@ -2200,8 +3577,18 @@ fn try_parse_tool_call_response(full_response: &str) -> Option<AgentResponse> {
         }
     }
 
+    // 2. Scan for complete top-level objects (prose before/after, code fences,
+    //    braces or quotes inside SQL strings) and take the first tool call.
+    for candidate in top_level_json_objects(trimmed) {
+        if let Ok(resp) = serde_json::from_str::<AgentResponse>(candidate) {
+            if matches!(resp, AgentResponse::ToolCall { .. }) {
+                return Some(resp);
+            }
+        }
+    }
+
     if trimmed.contains("\"tool\"") && trimmed.contains("\"args\"") {
-        // 2. Try extracting a top-level JSON object
+        // 3. Unescaped quotes in a field break the scan; fall back to the outermost braces
         let start = trimmed.find('{')?;
         let end = trimmed.rfind('}')?;
         if end > start {
@@ -2212,7 +3599,7 @@ fn try_parse_tool_call_response(full_response: &str) -> Option<AgentResponse> {
                 }
             }
 
-            // 3. Try stripping the "reasoning" field entirely (it often has unescaped quotes)
+            // 4. Try stripping the "reasoning" field entirely (it often has unescaped quotes)
             if let Some(fixed) = try_fix_broken_reasoning_json(candidate) {
                 if let Ok(resp) = serde_json::from_str::<AgentResponse>(&fixed) {
                     if matches!(resp, AgentResponse::ToolCall { .. }) {
@@ -2226,8 +3613,143 @@ fn try_parse_tool_call_response(full_response: &str) -> Option<AgentResponse> {
     None
 }
 
-/// Strip <think>...</think> blocks (potentially unclosed) from a string.
-fn strip_think_blocks(text: &str) -> String {
+/// Complete top-level `{...}` spans in `text`, in order. Tracks brace depth
+/// outside string literals only, so `{`, `}` and escaped quotes inside values
+/// don't end an object early. An unterminated object ends the scan.
+pub(crate) fn top_level_json_objects(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut objects = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &b) in bytes.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match b {
+            // Quotes only open strings inside an object; prose apostrophes and quotes are ignored.
+            b'"' if depth > 0 => in_string = true,
+            b'{' => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    objects.push(&text[start..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    objects
+}
+
+/// Remove legacy `[[IF_ACTION:...]]` markers from message text.
+pub(crate) fn strip_action_markers(text: &str) -> String {
+    extract_action_markers(text).0
+}
+
+/// Split legacy `[[IF_ACTION:{json}]]` markers out of message text. Older
+/// assistant messages stored their actions this way. The JSON is scanned
+/// string-aware, so a `]]` inside a value doesn't end the marker early;
+/// payloads that don't parse as an `AgentAction` are dropped with the marker.
+pub(crate) fn extract_action_markers(text: &str) -> (String, Vec<AgentAction>) {
+    const OPEN: &str = "[[IF_ACTION:";
+    let mut result = String::with_capacity(text.len());
+    let mut actions = Vec::new();
+    let mut remaining = text;
+    while let Some(start) = remaining.find(OPEN) {
+        result.push_str(&remaining[..start]);
+        let body = &remaining[start + OPEN.len()..];
+        let json_len = if body.trim_start().starts_with('{') {
+            let offset = body.len() - body.trim_start().len();
+            top_level_json_objects(&body[offset..]).first().map(|obj| {
+                if let Ok(action) = serde_json::from_str::<AgentAction>(obj) {
+                    actions.push(action);
+                }
+                offset + obj.len()
+            })
+        } else {
+            None
+        };
+        let after_json = &body[json_len.unwrap_or(0)..];
+        match after_json.find("]]") {
+            Some(end) => remaining = &after_json[end + 2..],
+            None => {
+                remaining = "";
+                break;
+            }
+        }
+    }
+    result.push_str(remaining);
+    (result.trim().to_string(), actions)
+}
+
+/// At most `max_chars` of `text` (whitespace collapsed) centred on the first
+/// case-insensitive occurrence of `keyword`, with "..." where it was cut.
+fn snippet_around(text: &str, keyword: Option<&str>, max_chars: usize) -> String {
+    let chars: Vec<char> = normalize_whitespace(text).chars().collect();
+    if chars.len() <= max_chars {
+        return chars.into_iter().collect();
+    }
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let hit = keyword.and_then(|k| {
+        let needle: Vec<char> = k.chars().map(fold).collect();
+        if needle.is_empty() || needle.len() > chars.len() {
+            return None;
+        }
+        (0..=chars.len() - needle.len())
+            .find(|&i| chars[i..i + needle.len()].iter().zip(&needle).all(|(a, b)| fold(*a) == *b))
+    });
+    let start = hit
+        .map(|i| i.saturating_sub(max_chars / 3))
+        .unwrap_or(0)
+        .min(chars.len() - max_chars);
+    let end = start + max_chars;
+    format!(
+        "{}{}{}",
+        if start > 0 { "..." } else { "" },
+        chars[start..end].iter().collect::<String>(),
+        if end < chars.len() { "..." } else { "" }
+    )
+}
+
+/// Time window for `search_chat_history`: `start_date`/`end_date` as local days,
+/// else the last `days`, else everything.
+fn chat_history_window(conn: &Connection, args: &Value) -> (i64, i64) {
+    let now = chrono::Utc::now().timestamp();
+    let parse_day = |key: &str| {
+        args[key]
+            .as_str()
+            .and_then(|s| chrono::NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok())
+            .map(|date| crate::utils::time::day_bounds(Some(conn), date))
+    };
+    let start_day = parse_day("start_date");
+    let end_day = parse_day("end_date");
+    if start_day.is_some() || end_day.is_some() {
+        let start_ts = start_day.as_ref().or(end_day.as_ref()).map(|b| b.start_ts).unwrap_or(0);
+        let end_ts = end_day.as_ref().or(start_day.as_ref()).map(|b| b.end_ts).unwrap_or(now);
+        return (start_ts.min(end_ts), start_ts.max(end_ts));
+    }
+    match args["days"].as_u64() {
+        Some(days) if days > 0 => (now - days as i64 * 86_400, now),
+        _ => (0, now),
+    }
+}
+
+/// Strip <think>...</think> blocks (potentially unclosed) from a string.
+pub(crate) fn strip_think_blocks(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
     let mut remaining = text;
     loop {
@@ -2315,14 +3837,57 @@ fn is_smalltalk_query(query: &str) -> bool {
         || q.contains("good night")
 }
 
-fn requires_evidence_for_query(query: &str) -> bool {
-    if is_smalltalk_query(query) {
+fn requires_evidence_for_query(query: &str, agent: &AgentSettings) -> bool {
+    if agent.evidence_strictness == EvidenceStrictness::Off {
+        return false;
+    }
+    if agent.smalltalk_bypass && is_smalltalk_query(query) {
         return false;
     }
     true
 }
 
-fn build_insufficient_evidence_action_marker(query: &str, scope: &TimeScope) -> String {
+/// Confirmation action for a `resolve_query_scope` call.
+fn scope_action_from_args(args: &Value, retry_message: &str) -> AgentAction {
+    AgentAction::ConfirmScopeOrSources {
+        reason: args["reason"]
+            .as_str()
+            .unwrap_or("Your query requires a wider search range.")
+            .to_string(),
+        suggested_time_range: Some(args["suggested_scope"].as_str().unwrap_or("last_7_days").to_string()),
+        enable_sources: args
+            .get("enable_sources")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default(),
+        retry_message: retry_message.to_string(),
+    }
+}
+
+/// Question and suggestions of an `ask_clarification` call; `None` without a question.
+/// Suggestions are trimmed, deduplicated and capped so they fit as quick-reply chips.
+fn parse_clarification_args(args: &Value) -> Option<(String, Vec<String>)> {
+    const MAX_SUGGESTIONS: usize = 4;
+    const MAX_SUGGESTION_CHARS: usize = 60;
+
+    let question = args["question"].as_str().map(str::trim).filter(|q| !q.is_empty())?;
+    let mut suggestions: Vec<String> = Vec::new();
+    for suggestion in args["suggestions"].as_array().into_iter().flatten() {
+        let Some(text) = suggestion.as_str().map(str::trim).filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let text: String = text.chars().take(MAX_SUGGESTION_CHARS).collect();
+        if !suggestions.iter().any(|s| s.eq_ignore_ascii_case(&text)) {
+            suggestions.push(text);
+        }
+        if suggestions.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    Some((question.to_string(), suggestions))
+}
+
+fn build_insufficient_evidence_action(query: &str, scope: &TimeScope) -> Option<AgentAction> {
     let mut enable_sources: Vec<&str> = Vec::new();
     let q = query.to_lowercase();
     if q.contains("project") || q.contains("repo") || q.contains("code") || q.contains("file") {
@@ -2335,7 +3900,13 @@ fn build_insufficient_evidence_action_marker(query: &str, scope: &TimeScope) ->
         enable_sources.push("screen");
     }
 
-    let suggested_scope = if q.contains("this year") && scope.id != "this_year" {
+    // A date the question names explicitly beats widening to everything.
+    let mentioned = crate::utils::date_expr::parse_date_expression(&q, None)
+        .filter(|range| range.start_ts < scope.start_ts || range.end_ts > scope.end_ts)
+        .map(|range| range.scope_id());
+    let suggested_scope = if let Some(id) = mentioned.as_deref() {
+        id
+    } else if q.contains("this year") && scope.id != "this_year" {
         "this_year"
     } else if scope.id != "all_time" {
         "all_time"
@@ -2346,21 +3917,19 @@ fn build_insufficient_evidence_action_marker(query: &str, scope: &TimeScope) ->
     };
 
     if suggested_scope.is_empty() && enable_sources.is_empty() {
-        return String::new();
+        return None;
     }
 
-    let payload = serde_json::json!({
-        "kind": "confirm_scope_or_sources",
-        "reason": "Evidence is insufficient in the current scope/source settings.",
-        "suggested_time_range": if suggested_scope.is_empty() { Value::Null } else { Value::String(suggested_scope.to_string()) },
-        "enable_sources": enable_sources,
-        "retry_message": query
-    });
-    format!("\n\n[[IF_ACTION:{}]]", payload)
+    Some(AgentAction::ConfirmScopeOrSources {
+        reason: "Evidence is insufficient in the current scope/source settings.".to_string(),
+        suggested_time_range: (!suggested_scope.is_empty()).then(|| suggested_scope.to_string()),
+        enable_sources: enable_sources.into_iter().map(String::from).collect(),
+        retry_message: query.to_string(),
+    })
 }
 
-fn has_minimum_evidence_for_query(query: &str, steps: &[AgentStep]) -> bool {
-    if !requires_evidence_for_query(query) {
+fn has_minimum_evidence_for_query(query: &str, steps: &[AgentStep], agent: &AgentSettings) -> bool {
+    if !requires_evidence_for_query(query, agent) {
         return true;
     }
     let evidence_steps = collect_evidence_tool_names(steps);
@@ -2385,6 +3954,32 @@ fn has_minimum_evidence_for_query(query: &str, steps: &[AgentStep]) -> bool {
     evidence_steps.len() >= 1
 }
 
+/// Tool calls required before answering `query`; only strict runs apply the identity rule.
+fn identity_tool_minimum(query: &str, agent: &AgentSettings) -> usize {
+    if agent.evidence_strictness == EvidenceStrictness::Strict && is_person_identity_query(query) {
+        agent.min_evidence_tools_for_identity
+    } else {
+        0
+    }
+}
+
+/// Questions about who a person is, which get the stricter evidence rule.
+/// "who" alone is not enough ("who sang this song"); it needs a word that
+/// points at the user's own contacts.
+fn is_person_identity_query(query: &str) -> bool {
+    const PERSON_CONTEXT: &[&str] = &[
+        "my", "me", "i", "friend", "friends", "girl", "boy", "girlfriend", "boyfriend", "love", "date",
+        "dating", "talk", "talked", "chat", "chatted", "text", "texted", "message", "messaged", "someone",
+    ];
+    let q = query.to_lowercase();
+    if q.contains("crush") || q.contains("relationship") {
+        return true;
+    }
+    let words: Vec<&str> = q.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    words.iter().any(|w| *w == "who" || *w == "whom")
+        && words.iter().any(|w| PERSON_CONTEXT.contains(w))
+}
+
 fn is_project_query(query: &str) -> bool {
     let q = query.to_lowercase();
     q.contains("project")
@@ -2462,8 +4057,16 @@ fn has_non_chat_strong_identity_evidence(steps: &[AgentStep]) -> bool {
     support_hits >= 2
 }
 
-fn scrub_unsupported_communication_claims(answer: &str, query: &str, steps: &[AgentStep]) -> String {
-    if !is_identity_or_romance_query(query) || has_explicit_chat_evidence(steps) {
+fn scrub_unsupported_communication_claims(
+    answer: &str,
+    query: &str,
+    steps: &[AgentStep],
+    strictness: EvidenceStrictness,
+) -> String {
+    if strictness == EvidenceStrictness::Off
+        || !is_identity_or_romance_query(query)
+        || has_explicit_chat_evidence(steps)
+    {
         return answer.to_string();
     }
 
@@ -2476,10 +4079,7 @@ fn scrub_unsupported_communication_claims(answer: &str, query: &str, steps: &[Ag
         return answer.to_string();
     }
 
-    format!(
-        "{}\n\nNote: I don't have explicit chat-app evidence in this time range, so I cannot claim texting/chats.",
-        answer
-    )
+    format!("{}\n\n{}", answer, Msg::NoChatEvidence.text(crate::utils::i18n::answer_lang(query)))
 }
 
 fn collect_evidence_tool_names(steps: &[AgentStep]) -> std::collections::HashSet<String> {
@@ -2489,7 +4089,7 @@ fn collect_evidence_tool_names(steps: &[AgentStep]) -> std::collections::HashSet
             continue;
         }
         match step.tool_name.as_str() {
-            "get_recent_ocr" | "search_ocr" | "get_recent_activities" | "query_activities" | "get_recent_file_changes" | "get_music_history" | "get_usage_stats" => {
+            "get_recent_ocr" | "search_ocr" | "get_recent_activities" | "query_activities" | "get_recent_file_changes" | "get_music_history" | "get_usage_stats" | "compare_usage" | "search_clipboard" | "get_process_runtime" | "get_detected_patterns" | "get_meetings" | "search_chat_history" | "get_entries" => {
                 distinct.insert(step.tool_name.clone());
             }
             "parallel_search" => {
@@ -2544,7 +4144,7 @@ fn parse_iso_to_unix(iso: &str) -> Option<i64> {
         .or_else(|| {
              chrono::NaiveDateTime::parse_from_str(iso, "%Y-%m-%dT%H:%M:%S")
                 .ok()
-                .and_then(|dt| dt.and_local_timezone(chrono::Local).single())
+                .and_then(|dt| dt.and_local_timezone(crate::utils::time::current_offset()).single())
                 .map(|dt| dt.timestamp())
         })
 }
@@ -2579,21 +4179,25 @@ fn normalize_whitespace(input: &str) -> String {
     input.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
+/// Strip OCR noise line by line. Line breaks survive, so chat captures stored
+/// with `OcrMode::LinesPreserved` keep one message per line.
 fn sanitize_ocr_for_query(text: &str) -> String {
-    let compact = normalize_whitespace(text);
-    if compact.is_empty() {
-        return String::new();
-    }
-
-    let filtered: String = compact
-        .chars()
-        .filter(|c| {
-            c.is_alphanumeric()
-                || c.is_whitespace()
-                || ",.;:!?()[]{}'\"/@#&+-_|".contains(*c)
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            let filtered: String = line
+                .chars()
+                .filter(|c| {
+                    c.is_alphanumeric()
+                        || c.is_whitespace()
+                        || crate::utils::text::is_expected_symbol(*c)
+                })
+                .collect();
+            normalize_whitespace(&filtered)
         })
+        .filter(|line| !line.is_empty())
         .collect();
-    let cleaned = normalize_whitespace(&filtered);
+    let cleaned = lines.join("\n");
     if cleaned.len() < 3 {
         return String::new();
     }
@@ -2603,6 +4207,21 @@ fn sanitize_ocr_for_query(text: &str) -> String {
     cleaned
 }
 
+/// Collapse whitespace within each line but keep the line breaks.
+fn normalize_lines(input: &str) -> String {
+    input
+        .lines()
+        .map(normalize_whitespace)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Indent continuation lines of a multi-line snippet under its list item.
+fn indent_snippet(snippet: &str) -> String {
+    snippet.replace('\n', "\n   ")
+}
+
 fn looks_like_gibberish(text: &str) -> bool {
     let chars: Vec<char> = text.chars().collect();
     if chars.is_empty() {
@@ -2610,32 +4229,33 @@ fn looks_like_gibberish(text: &str) -> bool {
     }
     let total = chars.len() as f64;
     let letters = chars.iter().filter(|c| c.is_alphabetic()).count() as f64;
+    let emoji = chars.iter().filter(|c| crate::utils::text::is_emoji(**c)).count() as f64;
     let digits = chars.iter().filter(|c| c.is_ascii_digit()).count() as f64;
     let symbols = chars
         .iter()
-        .filter(|c| !c.is_alphanumeric() && !c.is_whitespace())
-        .count() as f64;
-    let vowels = chars
-        .iter()
-        .filter(|c| "aeiouAEIOU".contains(**c))
+        .filter(|c| !c.is_alphanumeric() && !c.is_whitespace() && !crate::utils::text::is_emoji(**c))
         .count() as f64;
 
     let symbol_ratio = symbols / total;
-    let alpha_ratio = letters / total;
+    let alpha_ratio = (letters + emoji) / total;
     let digit_ratio = digits / total;
-    let vowel_ratio = if letters > 0.0 { vowels / letters } else { 0.0 };
+    let consonant_soup = crate::utils::text::latin_vowel_ratio(&chars, 11).is_some_and(|ratio| ratio < 0.06);
 
-    symbol_ratio > 0.35 || alpha_ratio < 0.18 || (letters > 10.0 && vowel_ratio < 0.06) || digit_ratio > 0.7
+    symbol_ratio > 0.35 || alpha_ratio < 0.18 || consonant_soup || digit_ratio > 0.7
 }
 
 async fn synthesize_answer_from_evidence(
     app_handle: &tauri::AppHandle,
     model: &str,
     api_key: &str,
+    temperature: f32,
     user_query: &str,
     scope: &TimeScope,
     steps: &[AgentStep],
     activities: &[Value],
+    time_accounting: Option<&TimeAccounting>,
+    strictness: EvidenceStrictness,
+    usage: &mut LlmUsage,
 ) -> Result<String, String> {
     let mut evidence_lines: Vec<String> = Vec::new();
     for (i, step) in steps.iter().take(8).enumerate() {
@@ -2647,15 +4267,21 @@ async fn synthesize_answer_from_evidence(
         ));
     }
 
+    let lang = crate::utils::i18n::answer_lang(user_query);
+    let language = crate::utils::i18n::language_instruction(user_query);
     let summary_prompt = format!(
-        "User query: {query}\nTime scope: {label} ({start} to {end})\nEvidence items: {count}\nTool evidence:\n{evidence}\n\nReturn a detailed, crisp, and highly specific final answer. Start with a direct answer, then provide evidence bullets with exact times, app names, and window titles. Break down activities chronologically or by major tasks. Do not just give a high-level summary of time spent. Include a short confidence statement and explicitly list missing evidence when uncertain. Do not call tools.",
+        "User query: {query}\nTime scope: {label} ({start} to {end})\nEvidence items: {count}\nTool evidence:\n{evidence}\n\n{language}\nReturn a detailed, crisp, and highly specific final answer. Start with a direct answer, then provide evidence bullets with exact times, app names, and window titles, each ending with the `[#id]` tags of the rows it is based on, copied exactly from the tool evidence. Break down activities chronologically or by major tasks. Do not just give a high-level summary of time spent. Include a short confidence statement and explicitly list missing evidence when uncertain. Do not call tools.",
         query = user_query,
         label = scope.label,
-        start = format_time_scope_ts(scope.start_ts),
-        end = format_time_scope_ts(scope.end_ts),
+        start = format_time_scope_ts(scope.start_ts, lang),
+        end = format_time_scope_ts(scope.end_ts, lang),
         count = activities.len(),
         evidence = evidence_lines.join("\n\n"),
     );
+    let summary_prompt = match time_accounting {
+        Some(accounting) => format!("{}\n\n{}", time_accounting_prompt(accounting), summary_prompt),
+        None => summary_prompt,
+    };
 
     let mut out = String::new();
     let on_token = |chunk: &str| {
@@ -2664,113 +4290,375 @@ async fn synthesize_answer_from_evidence(
     let messages = vec![
         ChatMessage {
             role: "system".to_string(),
-            content: "You are a precise assistant. Produce one final answer from provided evidence only. Be detailed, specific, and not overly brief. Include direct answer, evidence bullets, and confidence. No tool JSON.".to_string(),
+            content: format!(
+                "You are a precise assistant. Produce one final answer from provided evidence only. Be detailed, specific, and not overly brief. Include direct answer, evidence bullets, and confidence. No tool JSON. {}",
+                language
+            ),
         },
         ChatMessage {
             role: "user".to_string(),
             content: summary_prompt,
         },
     ];
-    call_llm_stream(model, api_key, &messages, &mut out, on_token).await?;
+    usage.add(&call_llm_stream(model, api_key, temperature, &messages, &mut out, Some(app_handle), on_token).await?);
     if matches!(try_parse_tool_call_response(&out), Some(AgentResponse::ToolCall { .. })) {
-        return Ok("I gathered evidence but could not produce a stable final summary. Please ask with a specific app/date and I’ll answer exactly.".to_string());
+        return Ok(Msg::UnstableSummary.text(lang).to_string());
     }
     let cleaned = strip_internal_stream_markup(&out)
         .replace("<think>", "")
         .replace("</think>", "");
     let normalized = normalize_final_answer_hardened(&cleaned);
-    Ok(scrub_unsupported_communication_claims(&normalized, user_query, steps))
+    let scrubbed = scrub_unsupported_communication_claims(&normalized, user_query, steps, strictness);
+    Ok(drop_unknown_citations(&scrubbed, activities))
+}
+
+/// Overlap of each activity with `[start, end)`, so rows straddling the scope edges count only their inside part.
+const SCOPE_OVERLAP_SQL: &str = "MAX(0, MIN(start_time + duration_seconds, ?2) - MAX(start_time, ?1))";
+
+/// Deterministic split of the scope into tracked, idle and untracked time, with
+/// per-category and top-app shares. Runs on the raw tables so the figures don't
+/// depend on what the agent happened to query.
+fn compute_time_accounting(conn: &Connection, scope: &TimeScope, include_private: bool) -> Result<TimeAccounting, String> {
+    let start = scope.start_ts;
+    let end = scope.end_ts.min(chrono::Utc::now().timestamp()).max(start);
+    let span_seconds = end - start;
+
+    let (tracked_seconds, idle_seconds): (i64, i64) = conn
+        .query_row(
+            &format!(
+                "SELECT
+                    COALESCE(SUM(CASE WHEN CAST(metadata AS TEXT) LIKE '%\"is_idle\":true%' THEN 0 ELSE {overlap} END), 0),
+                    COALESCE(SUM(CASE WHEN CAST(metadata AS TEXT) LIKE '%\"is_idle\":true%' THEN {overlap} ELSE 0 END), 0)
+                 FROM main.activities
+                 WHERE start_time < ?2 AND start_time + duration_seconds > ?1",
+                overlap = SCOPE_OVERLAP_SQL
+            ),
+            rusqlite::params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    // Overlapping rows (e.g. a recovered session) can push the sum past the span.
+    let idle_seconds = idle_seconds.min(span_seconds);
+    let tracked_seconds = tracked_seconds.min(span_seconds - idle_seconds);
+    let untracked_seconds = span_seconds - tracked_seconds - idle_seconds;
+
+    let share = |name: String, seconds: i64| TimeShare {
+        name,
+        seconds,
+        percent: if tracked_seconds > 0 {
+            (seconds as f64 * 1000.0 / tracked_seconds as f64).round() / 10.0
+        } else {
+            0.0
+        },
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT category_id, SUM({overlap}) AS total
+             FROM main.activities
+             WHERE start_time < ?2 AND start_time + duration_seconds > ?1
+               AND CAST(metadata AS TEXT) NOT LIKE '%\"is_idle\":true%'
+             GROUP BY category_id
+             HAVING total > 0
+             ORDER BY total DESC",
+            overlap = SCOPE_OVERLAP_SQL
+        ))
+        .map_err(|e| e.to_string())?;
+    let categories = stmt
+        .query_map(rusqlite::params![start, end], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(|(category_id, seconds)| share(crate::utils::categories::name_for(category_id), seconds))
+        .collect();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT app_name, SUM({overlap}) AS total
+             FROM main.activities
+             WHERE start_time < ?2 AND start_time + duration_seconds > ?1
+               AND CAST(metadata AS TEXT) NOT LIKE '%\"is_idle\":true%'
+               AND (?3 OR COALESCE(private, 0) = 0)
+             GROUP BY app_name
+             HAVING total > 0
+             ORDER BY total DESC
+             LIMIT 5",
+            overlap = SCOPE_OVERLAP_SQL
+        ))
+        .map_err(|e| e.to_string())?;
+    let top_apps = stmt
+        .query_map(rusqlite::params![start, end, include_private], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(|(app, seconds)| share(app, seconds))
+        .collect();
+
+    Ok(TimeAccounting {
+        scope_label: scope.label.clone(),
+        start_ts: start,
+        end_ts: end,
+        span_seconds,
+        tracked_seconds,
+        idle_seconds,
+        untracked_seconds,
+        categories,
+        top_apps,
+    })
+}
+
+/// The accounting as prompt text, with the instruction to stick to its numbers.
+fn time_accounting_prompt(accounting: &TimeAccounting) -> String {
+    let mut out = format!(
+        "Exact time accounting for {} (computed from the database):\n- Span: {}\n- Tracked: {}\n- Idle: {}\n- Untracked (machine off, paused or excluded): {}\n",
+        accounting.scope_label,
+        format_duration(accounting.span_seconds),
+        format_duration(accounting.tracked_seconds),
+        format_duration(accounting.idle_seconds),
+        format_duration(accounting.untracked_seconds),
+    );
+    out.push_str("By category:\n");
+    for c in &accounting.categories {
+        out.push_str(&format!("- {}: {} ({:.1}%)\n", c.name, format_duration(c.seconds), c.percent));
+    }
+    out.push_str("Top apps:\n");
+    for a in &accounting.top_apps {
+        out.push_str(&format!("- {}: {} ({:.1}%)\n", a.name, format_duration(a.seconds), a.percent));
+    }
+    out.push_str("This table is shown to the user next to your answer. Any total, duration or percentage you state for the whole scope, a category or one of these apps must match these figures exactly; do not add up time yourself.");
+    out
+}
+
+/// A duration written in an answer ("3h 20m", "2 hours", "45 minutes", "1.5 hrs").
+fn stated_duration_regex() -> &'static regex::Regex {
+    static PATTERN: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    PATTERN.get_or_init(|| {
+        regex::Regex::new(r"(?i)\b(\d+(?:[.,]\d+)?)\s*(?:h|hrs?|hours?)\b(?:\s*(?:and\s*)?(\d+)\s*(?:m|mins?|minutes?)\b)?|\b(\d+)\s*(?:m|mins?|minutes?)\b")
+            .expect("valid duration pattern")
+    })
+}
+
+fn parse_stated_duration(caps: &regex::Captures) -> Option<i64> {
+    if let Some(hours) = caps.get(1) {
+        let hours: f64 = hours.as_str().replace(',', ".").parse().ok()?;
+        let minutes: i64 = caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        return Some((hours * 3600.0).round() as i64 + minutes * 60);
+    }
+    caps.get(3)?.as_str().parse::<i64>().ok().map(|m| m * 60)
+}
+
+/// Lines of the answer whose first duration contradicts the accounting by more than 10%.
+/// A line is matched to a category or top app it names, else to the tracked total
+/// when it talks about a total; other lines (single sessions, meetings) are ignored.
+fn check_stated_totals(answer: &str, accounting: &TimeAccounting) -> Vec<String> {
+    const TOLERANCE: f64 = 0.10;
+    let mut factors = Vec::new();
+    for line in answer.lines() {
+        let Some(caps) = stated_duration_regex().captures(line) else {
+            continue;
+        };
+        let Some(stated) = parse_stated_duration(&caps) else {
+            continue;
+        };
+        let lower = line.to_lowercase();
+        let named = accounting
+            .categories
+            .iter()
+            .chain(accounting.top_apps.iter())
+            .find(|share| lower.contains(&share.name.to_lowercase()));
+        let (label, expected) = match named {
+            Some(share) => (share.name.clone(), share.seconds),
+            None if lower.contains("total") || lower.contains("tracked") || lower.contains("in all") => {
+                ("tracked total".to_string(), accounting.tracked_seconds)
+            }
+            None => continue,
+        };
+        if expected <= 0 {
+            continue;
+        }
+        let deviation = (stated - expected).abs() as f64 / expected as f64;
+        if deviation > TOLERANCE {
+            factors.push(format!(
+                "Answer states {} for {}, but the computed figure is {} ({:.0}% off)",
+                format_duration(stated),
+                label,
+                format_duration(expected),
+                deviation * 100.0
+            ));
+        }
+    }
+    factors
+}
+
+/// Incremental server-sent-events decoder. Bytes are buffered until a full
+/// line is available, so chunks may split lines, JSON payloads or UTF-8
+/// characters anywhere. Accepts LF, CRLF and CR line endings, skips `:`
+/// comment lines (keep-alives) and joins multi-line `data:` fields.
+#[derive(Default)]
+struct SseParser {
+    pending: Vec<u8>,
+    data_lines: Vec<String>,
+}
+
+impl SseParser {
+    /// Consume a chunk and return the data of every event it completed.
+    fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        let mut start = 0;
+        while let Some(offset) = self.pending[start..].iter().position(|b| *b == b'\n' || *b == b'\r') {
+            let end = start + offset;
+            let next = if self.pending[end] == b'\r' {
+                match self.pending.get(end + 1) {
+                    Some(b'\n') => end + 2,
+                    Some(_) => end + 1,
+                    // A trailing CR may be the first half of CRLF; wait for more bytes.
+                    None => break,
+                }
+            } else {
+                end + 1
+            };
+            let line = String::from_utf8_lossy(&self.pending[start..end]).into_owned();
+            if let Some(data) = self.process_line(&line) {
+                events.push(data);
+            }
+            start = next;
+        }
+        self.pending.drain(..start);
+        events
+    }
+
+    /// Flush at end of stream; the last event may arrive without a trailing blank line.
+    fn finish(&mut self) -> Option<String> {
+        if !self.pending.is_empty() {
+            let line = String::from_utf8_lossy(&self.pending).into_owned();
+            self.pending.clear();
+            let line = line.trim_end_matches('\r').to_string();
+            if let Some(data) = self.process_line(&line) {
+                return Some(data);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<String> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        if field == "data" {
+            self.data_lines.push(value.to_string());
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<String> {
+        if self.data_lines.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.data_lines).join("\n"))
+    }
 }
 
 // Streaming LLM Call
+/// `status` receives rate-limit waits as `chat://status` events. Returns the
+/// call's token usage, estimated unless the provider reported it.
 async fn call_llm_stream<F>(
     model: &str, 
     api_key: &str, 
+    temperature: f32,
     messages: &[ChatMessage], 
     output_buffer: &mut String,
+    status: Option<&tauri::AppHandle>,
     mut on_token: F
-) -> Result<(), String> 
+) -> Result<LlmUsage, AppError> 
 where F: FnMut(&str) {
-    let client = reqwest::Client::builder()
-        .timeout(StdDuration::from_secs(LLM_TIMEOUT_SECS))
-        .build()
-        .map_err(|e| format!("Failed to init HTTP client: {}", e))?;
-    
     let request = ChatRequest {
         model: model.to_string(),
         messages: messages.to_vec(),
-        temperature: 0.0,
-        max_tokens: 1600,
+        temperature,
+        max_tokens: crate::services::model_capabilities::resolve(model).max_tokens,
         stream: true,
     };
 
-    let mut response = client
-        .post("https://integrate.api.nvidia.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Net err: {}", e))?;
-
-    if !response.status().is_success() {
-        // Read full body error
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("API Error {}: {}", status, text));
-    }
+    let mut response = llm_client::send_chat_request(
+        api_key,
+        &request.model,
+        &request,
+        StdDuration::from_secs(LLM_TIMEOUT_SECS),
+        llm_client::Priority::Interactive,
+        status,
+    )
+    .await?;
 
-    // Process stream line by line
-    let mut buffer = String::new();
+    let mut parser = SseParser::default();
+    let mut reported_usage: Option<llm_client::ProviderUsage> = None;
+    let completion_start = output_buffer.len();
     let mut reasoning_open = false;
-    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
-        let chunk_str = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&chunk_str);
-        
-        // Split by lines
-        let lines: Vec<&str> = buffer.split('\n').collect();
-        // Keep the last part if it doesn't end with \n
-        let last_part = if chunk_str.ends_with('\n') {
-            String::new()
-        } else {
-            lines.last().unwrap_or(&"").to_string()
+    let mut done = false;
+    let mut handle_event = |data: &str, output_buffer: &mut String| -> bool {
+        if data.trim() == "[DONE]" {
+            return true;
+        }
+        // Providers that skip the blank line between events leave several
+        // JSON objects in one event; fall back to one object per data line.
+        let parsed: Vec<ChatStreamResponse> = match serde_json::from_str::<ChatStreamResponse>(data) {
+            Ok(resp) => vec![resp],
+            Err(_) => data
+                .lines()
+                .filter_map(|line| serde_json::from_str::<ChatStreamResponse>(line).ok())
+                .collect(),
         };
-        
-        // Process complete lines
-        for line in lines {
-            let line = line.trim();
-            if line.starts_with("data: ") {
-                let data = &line[6..];
-                if data == "[DONE]" { break; }
-                
-                if let Ok(stream_resp) = serde_json::from_str::<ChatStreamResponse>(data) {
-                    if let Some(choice) = stream_resp.choices.first() {
-                        if let Some(ref reasoning) = choice.delta.reasoning_content {
-                            if !reasoning.is_empty() {
-                                if !reasoning_open {
-                                    output_buffer.push_str("<think>");
-                                    on_token("<think>");
-                                    reasoning_open = true;
-                                }
-                                output_buffer.push_str(reasoning);
-                                on_token(reasoning);
-                            }
-                        }
-                        if let Some(ref content) = choice.delta.content {
-                            if reasoning_open {
-                                output_buffer.push_str("</think>");
-                                on_token("</think>");
-                                reasoning_open = false;
-                            }
-                            output_buffer.push_str(content);
-                            on_token(content);
-                        }
+        for stream_resp in parsed {
+            if stream_resp.usage.is_some() {
+                reported_usage = stream_resp.usage;
+            }
+            let Some(choice) = stream_resp.choices.first() else { continue };
+            if let Some(ref reasoning) = choice.delta.reasoning_content {
+                if !reasoning.is_empty() {
+                    if !reasoning_open {
+                        output_buffer.push_str("<think>");
+                        on_token("<think>");
+                        reasoning_open = true;
                     }
+                    output_buffer.push_str(reasoning);
+                    on_token(reasoning);
+                }
+            }
+            if let Some(ref content) = choice.delta.content {
+                if reasoning_open {
+                    output_buffer.push_str("</think>");
+                    on_token("</think>");
+                    reasoning_open = false;
                 }
+                output_buffer.push_str(content);
+                on_token(content);
             }
         }
-        
-        buffer = last_part;
+        false
+    };
+
+    while let Some(chunk) = response.chunk().await? {
+        for data in parser.feed(&chunk) {
+            if handle_event(&data, output_buffer) {
+                done = true;
+                break;
+            }
+        }
+        if done {
+            break;
+        }
+    }
+    if !done {
+        if let Some(data) = parser.finish() {
+            handle_event(&data, output_buffer);
+        }
     }
 
     if reasoning_open {
@@ -2778,12 +4666,883 @@ where F: FnMut(&str) {
         on_token("</think>");
     }
 
-    Ok(())
+    Ok(llm_client::call_usage(
+        model,
+        reported_usage,
+        llm_client::estimate_prompt_tokens(messages.iter().map(|m| m.content.as_str())),
+        &output_buffer[completion_start..],
+    ))
+}
+
+/// One cheap, non-streaming call that names a chat session after its first exchange.
+pub async fn generate_chat_title(
+    app_handle: &tauri::AppHandle,
+    model: &str,
+    api_key: &str,
+    user_message: &str,
+    assistant_answer: &str,
+) -> Result<String, String> {
+    let request = ChatRequest {
+        model: model.to_string(),
+        messages: vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: "Write a 4-8 word title for this conversation. Reply with the title only: no quotes, no trailing punctuation, no markdown.".to_string(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: format!(
+                    "User: {}\nAssistant: {}",
+                    truncate_for_token_limit(user_message, 600),
+                    truncate_for_token_limit(assistant_answer, 600)
+                ),
+            },
+        ],
+        temperature: 0.2,
+        max_tokens: 24,
+        stream: false,
+    };
+
+    // Titles are cosmetic; let them queue behind interactive traffic.
+    let response = llm_client::send_chat_request(
+        api_key,
+        &request.model,
+        &request,
+        StdDuration::from_secs(20),
+        llm_client::Priority::Background,
+        None,
+    )
+    .await?;
+    let text = response.text().await.map_err(|e| e.to_string())?;
+
+    let parsed: ChatResponse = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let raw = parsed
+        .choices
+        .first()
+        .and_then(|c| c.message.content.clone())
+        .unwrap_or_default();
+    let usage = llm_client::call_usage(
+        model,
+        parsed.usage,
+        llm_client::estimate_prompt_tokens(request.messages.iter().map(|m| m.content.as_str())),
+        &raw,
+    );
+    llm_client::record_usage(app_handle, llm_client::CALLER_CHAT_TITLE, model, &usage);
+    let cleaned = strip_think_blocks(&raw);
+    let title = cleaned
+        .lines()
+        .map(|l| l.trim().trim_matches(|c: char| c == '"' || c == '\'' || c == '*' || c == '#'))
+        .find(|l| !l.is_empty())
+        .unwrap_or("")
+        .split_whitespace()
+        .take(8)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let title = title.trim_end_matches(|c: char| c == '.' || c == ':' || c == ';').to_string();
+    if title.is_empty() {
+        return Err("AI returned an empty title".to_string());
+    }
+    Ok(title)
 }
 
 // Kept for backward compat if needed, but we don't really use it now
 async fn call_llm(model: &str, api_key: &str, messages: &[ChatMessage]) -> Result<String, String> {
     let mut out = String::new();
-    call_llm_stream(model, api_key, messages, &mut out, |_| {}).await?;
+    call_llm_stream(model, api_key, crate::models::settings::default_agent_temperature(), messages, &mut out, None, |_| {}).await?;
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_connection;
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    const T0: i64 = 1_767_225_600;
+
+
+    fn insert(conn: &Connection, app: &str, category_id: i64, start: i64, seconds: i64) {
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+             VALUES (?1, 1, 'w', ?2, ?3, ?3 + ?4, ?4)",
+            rusqlite::params![app, category_id, start, seconds],
+        )
+        .unwrap();
+    }
+
+    fn range(label: &str, start_ts: i64, end_ts: i64) -> CompareRange {
+        CompareRange { label: label.to_string(), start_ts, end_ts }
+    }
+
+    #[test]
+    fn unequal_ranges_compare_per_day_averages() {
+        let conn = test_connection();
+        // Two hours of coding a day for a week, then one hour a day for two and a half days.
+        for day in 0..7 {
+            insert(&conn, "Code", 1, T0 + day * DAY + 3600, 7200);
+        }
+        for day in 0..3 {
+            insert(&conn, "Code", 1, T0 + (7 + day) * DAY + 3600, 3600);
+        }
+        insert(&conn, "Spotify", 4, T0 + 8 * DAY, 600);
+        let filter = UsageFilter::default();
+        let week = aggregate_range_usage(&conn, &range("Week", T0, T0 + 7 * DAY), &filter).unwrap();
+        let short = aggregate_range_usage(&conn, &range("Short", T0 + 7 * DAY, T0 + 9 * DAY + DAY / 2), &filter).unwrap();
+
+        assert_eq!((week.total_seconds, week.days), (14 * 3600, 7));
+        // A partial day counts as a whole one.
+        assert_eq!((short.total_seconds, short.days), (3 * 3600 + 600, 3));
+
+        let result = build_usage_comparison(&week, &short, &filter);
+        let delta = &result["delta"];
+        assert_eq!(delta["total_seconds"], 14 * 3600 - (3 * 3600 + 600));
+        assert_eq!(delta["per_day_seconds"], 7200 - 3800);
+        assert_eq!(delta["pct_change_per_day"], 89.5);
+        let coding = delta["categories"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["category"] == crate::utils::categories::name_for(1))
+            .unwrap();
+        assert_eq!(coding["per_day_delta_seconds"], 7200 - 3600);
+        assert_eq!(coding["pct_change_per_day"], 100.0);
+        assert!(result["notes"][0].as_str().unwrap().contains("7 vs 3 days"));
+    }
+
+    #[test]
+    fn filters_apply_to_both_ranges() {
+        let conn = test_connection();
+        insert(&conn, "Code", 1, T0, 3600);
+        insert(&conn, "Spotify", 4, T0, 1800);
+        insert(&conn, "Code", 1, T0 + DAY, 1800);
+        let filter = UsageFilter { category_id: None, app: Some("CODE".to_string()) };
+        let a = aggregate_range_usage(&conn, &range("A", T0, T0 + DAY), &filter).unwrap();
+        let b = aggregate_range_usage(&conn, &range("B", T0 + DAY, T0 + 2 * DAY), &filter).unwrap();
+        assert_eq!(a.top_apps, vec![("Code".to_string(), 3600)]);
+        let result = build_usage_comparison(&a, &b, &filter);
+        assert_eq!(result["delta"]["pct_change_total"], 100.0);
+        assert_eq!(result["filter"]["app"], "CODE");
+    }
+
+    #[test]
+    fn empty_ranges_have_no_percent_change() {
+        let conn = test_connection();
+        insert(&conn, "Code", 1, T0 + 3600, 3600);
+        let filter = UsageFilter::default();
+        let busy = aggregate_range_usage(&conn, &range("Busy", T0, T0 + DAY), &filter).unwrap();
+        let empty = aggregate_range_usage(&conn, &range("Empty", T0 + DAY, T0 + 2 * DAY), &filter).unwrap();
+        assert_eq!(empty.total_seconds, 0);
+        assert!(empty.categories.is_empty() && empty.top_apps.is_empty());
+
+        let result = build_usage_comparison(&busy, &empty, &filter);
+        assert!(result["delta"]["pct_change_total"].is_null());
+        assert!(result["delta"]["pct_change_per_day"].is_null());
+        assert_eq!(result["notes"].as_array().unwrap().len(), 1);
+        assert!(result["notes"][0].as_str().unwrap().contains("Empty"));
+
+        // Against a busy baseline an empty range is a full drop.
+        let result = build_usage_comparison(&empty, &busy, &filter);
+        assert_eq!(result["delta"]["pct_change_total"], -100.0);
+        assert_eq!(result["delta"]["total_seconds"], -3600);
+
+        // A zero-length range still counts as one day instead of dividing by zero.
+        let instant = aggregate_range_usage(&conn, &range("Now", T0, T0), &filter).unwrap();
+        assert_eq!((instant.total_seconds, instant.days, instant.per_day_seconds()), (0, 1, 0));
+        let result = build_usage_comparison(&instant, &empty, &filter);
+        assert_eq!(result["delta"]["total_seconds"], 0);
+        assert_eq!(result["notes"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn query_sanitizer_keeps_german_turkish_and_emoji_chat() {
+        for text in [
+            "Schöne Grüße aus München\nBitte prüfen Sie die Überweisung",
+            "Bugün akşam toplantıya katılabilir misiniz?\nŞimdi çıkıyorum, görüşürüz",
+            "😂😂😂 🔥🔥 👍\nok 👍👍 see you 🎉🎉🎉",
+        ] {
+            assert!(!looks_like_gibberish(text), "{:?}", text);
+            assert_eq!(sanitize_ocr_for_query(text), text);
+        }
+    }
+
+    #[test]
+    fn query_sanitizer_zeroes_noise() {
+        assert_eq!(sanitize_ocr_for_query("¦¦ ^^ ¬¬"), "");
+        assert_eq!(sanitize_ocr_for_query("xkcd qrst pmnb zxcv wkdl"), "");
+        assert_eq!(sanitize_ocr_for_query("1234 5678 9012 3456 $$"), "");
+    }
+
+    fn seed_chats(conn: &Connection) {
+        let noon = T0 + DAY / 2;
+        conn.execute_batch(&format!(
+            "INSERT INTO chat_sessions (id, title, created_at, updated_at)
+                 VALUES ('rust', 'Rust questions', {old}, {old}), ('plans', 'Weekend plans', {new}, {new});
+             INSERT INTO chat_messages (session_id, role, content, created_at) VALUES
+                 ('rust', 'user', 'How do I fix the borrow checker error?', {old}),
+                 ('rust', 'assistant', '<think>the borrow is held across await</think>Clone the Arc before the await.', {old1}),
+                 ('rust', 'assistant', 'You told me the borrow checker complained in main.rs. [[IF_ACTION:open:main.rs]]', {old2}),
+                 ('plans', 'assistant', 'I can check your calendar. [[IF_ACTION:borrow:calendar]]', {new}),
+                 ('plans', 'user', 'Remind me what you said about the borrow checker', {new1}),
+                 ('plans', 'assistant', 'Hiking on Saturday, borrow the tent from Sam.', {new2}),
+                 ('plans', 'user', 'And what did you tell me about the borrow checker?', {new3});",
+            old = noon,
+            old1 = noon + 60,
+            old2 = noon + 120,
+            new = noon + 5 * DAY,
+            new1 = noon + 5 * DAY + 60,
+            new2 = noon + 5 * DAY + 120,
+            new3 = noon + 5 * DAY + 180,
+        ))
+        .unwrap();
+    }
+
+    fn policy() -> ToolPolicy {
+        ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: Some("plans".to_string()),
+        }
+    }
+
+    fn search(conn: &Connection, args: Value) -> (String, Vec<String>) {
+        let (output, entries) = execute_tool(conn, "search_chat_history", &args, &policy(), None).unwrap();
+        let snippets = entries
+            .iter()
+            .map(|e| e["snippet"].as_str().unwrap().to_string())
+            .collect();
+        (output, snippets)
+    }
+
+    #[test]
+    fn chat_search_ignores_reasoning_markers_and_the_pending_question() {
+        let conn = test_connection();
+        seed_chats(&conn);
+        let (output, snippets) = search(&conn, serde_json::json!({ "keyword": "Borrow" }));
+        // Newest first; the unanswered question of the current session is not a hit,
+        // nor are messages where the keyword only sits in <think> or an action marker.
+        assert_eq!(
+            snippets,
+            vec![
+                "Hiking on Saturday, borrow the tent from Sam.",
+                "Remind me what you said about the borrow checker",
+                "You told me the borrow checker complained in main.rs.",
+                "How do I fix the borrow checker error?",
+            ]
+        );
+        assert!(output.contains("all conversations"));
+        assert!(output.contains("in \"Rust questions\""));
+        assert!(!output.contains("IF_ACTION") && !output.contains("await"));
+    }
+
+    #[test]
+    fn chat_search_filters_by_role_session_and_dates() {
+        let conn = test_connection();
+        seed_chats(&conn);
+        let (_, assistant) = search(&conn, serde_json::json!({ "keyword": "borrow", "role": "assistant" }));
+        assert_eq!(assistant.len(), 2);
+
+        let (output, current) = search(&conn, serde_json::json!({ "keyword": "borrow", "session": "current" }));
+        assert!(output.contains("this conversation"));
+        assert_eq!(current.len(), 2);
+
+        let (_, first_day) = search(
+            &conn,
+            serde_json::json!({ "keyword": "borrow", "start_date": "2026-01-01", "end_date": "2026-01-01" }),
+        );
+        assert_eq!(first_day.len(), 2);
+        let (_, limited) = search(&conn, serde_json::json!({ "limit": 1 }));
+        assert_eq!(limited, vec!["Hiking on Saturday, borrow the tent from Sam."]);
+    }
+
+    #[test]
+    fn chat_search_results_count_as_evidence_only_when_found() {
+        let conn = test_connection();
+        seed_chats(&conn);
+        let step = |tool_result: String| AgentStep {
+            turn: 1,
+            tool_name: "search_chat_history".to_string(),
+            tool_args: serde_json::json!({}),
+            tool_result,
+            reasoning: String::new(),
+        };
+        let (found, _) = search(&conn, serde_json::json!({ "keyword": "tent" }));
+        let (missing, _) = search(&conn, serde_json::json!({ "keyword": "kubernetes" }));
+        assert!(missing.starts_with("No matching chat messages found in all conversations"));
+
+        assert!(collect_evidence_tool_names(&[step(found)]).contains("search_chat_history"));
+        assert!(collect_evidence_tool_names(&[step(missing)]).is_empty());
+    }
+
+    #[test]
+    fn chat_snippets_centre_on_the_keyword() {
+        assert_eq!(strip_action_markers("ok [[IF_ACTION:a]] done [[IF_ACTION:b"), "ok  done");
+        let long = format!("{} needle {}", "a ".repeat(200), "b ".repeat(200));
+        let snippet = snippet_around(&long, Some("NEEDLE"), 60);
+        assert!(snippet.starts_with("...") && snippet.ends_with("..."));
+        assert!(snippet.contains("needle"));
+        assert_eq!(snippet_around("short  text", Some("x"), 60), "short text");
+    }
+
+    #[test]
+    fn echoed_action_markers_never_survive_into_the_answer() {
+        let answers = [
+            r#"You spent 2h in VS Code. [[IF_ACTION:{"kind":"retry_with_query","query":"a ]] b"}]]"#,
+            r#"[[IF_ACTION:{"kind":"confirm_scope_or_sources","reason":"x","suggested_time_range":"all_time"}]]Nothing found today."#,
+            "Here is the answer.\n[[IF_ACTION:not json]]\nMore text.",
+            r#"Cut off [[IF_ACTION:{"kind":"retry_with_query","query":"unterminated"#,
+        ];
+        for answer in answers {
+            let normalized = normalize_final_answer_hardened(answer);
+            assert!(!normalized.contains("IF_ACTION"), "{:?}", normalized);
+            assert!(!normalized.contains("]]"), "{:?}", normalized);
+        }
+        assert_eq!(
+            normalize_final_answer_hardened(answers[0]),
+            "You spent 2h in VS Code."
+        );
+        assert_eq!(normalize_final_answer_hardened(answers[1]), "Nothing found today.");
+    }
+
+    #[test]
+    fn legacy_markers_become_typed_actions() {
+        let stored = r#"No evidence yet. [[IF_ACTION:{"kind":"confirm_scope_or_sources","reason":"wider ]] range","suggested_time_range":"all_time","enable_sources":["files"]}]] [[IF_ACTION:{"kind":"unknown"}]]"#;
+        let (content, actions) = extract_action_markers(stored);
+        assert_eq!(content, "No evidence yet.");
+        assert_eq!(actions.len(), 1);
+        match &actions[0] {
+            AgentAction::ConfirmScopeOrSources {
+                reason,
+                suggested_time_range,
+                enable_sources,
+                retry_message,
+            } => {
+                assert_eq!(reason, "wider ]] range");
+                assert_eq!(suggested_time_range.as_deref(), Some("all_time"));
+                assert_eq!(enable_sources, &vec!["files".to_string()]);
+                assert!(retry_message.is_empty());
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+
+        // Stored actions round-trip through the `actions` column unchanged.
+        let json = serde_json::to_string(&actions).unwrap();
+        assert!(json.contains(r#""kind":"confirm_scope_or_sources""#));
+        let back: Vec<AgentAction> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+    }
+
+    #[test]
+    fn scope_actions_are_built_as_data() {
+        let action = scope_action_from_args(
+            &serde_json::json!({ "reason": "Need last week", "suggested_scope": "last_7_days", "enable_sources": ["browser"] }),
+            "what did I read",
+        );
+        let value = serde_json::to_value(&action).unwrap();
+        assert_eq!(value["kind"], "confirm_scope_or_sources");
+        assert_eq!(value["suggested_time_range"], "last_7_days");
+        assert_eq!(value["enable_sources"], serde_json::json!(["browser"]));
+        assert_eq!(value["retry_message"], "what did I read");
+    }
+
+    #[test]
+    fn chat_ocr_tools_return_one_message_per_line() {
+        let conn = test_connection();
+        let text = "Anna Schmidt\nAre we still on for dinner tonight? 19:02\nYes! Table is booked for 8 19:04\n¦¦ ^^\nCan you bring the charger 19:05";
+        let meta = serde_json::to_vec(&ActivityMetadata {
+            screen_text: Some(text.to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+             VALUES ('WhatsApp', 1, 'Anna Schmidt', 3, ?1, ?1 + 60, 60, ?2)",
+            rusqlite::params![T0, meta],
+        )
+        .unwrap();
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+        let window = |extra: Value| {
+            let mut args = serde_json::json!({ "start_ts": T0 - 60, "end_ts": T0 + DAY });
+            args.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            args
+        };
+        let expected = "Anna Schmidt\nAre we still on for dinner tonight? 19:02\nYes! Table is booked for 8 19:04\nCan you bring the charger 19:05";
+
+        let (output, matches) =
+            execute_tool(&conn, "search_ocr", &window(serde_json::json!({ "keyword": "dinner" })), &policy, None).unwrap();
+        assert_eq!(matches[0]["metadata"]["screen_text"], expected);
+        assert!(output.contains("\n   Are we still on for dinner tonight? 19:02\n   Yes! Table is booked"));
+
+        let (output, recent) =
+            execute_tool(&conn, "get_recent_ocr", &window(serde_json::json!({ "app": "whatsapp" })), &policy, None).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert!(output.contains(&format!("\n   {}\n", expected.replace('\n', "\n   "))));
+    }
+
+    #[test]
+    fn agent_prompt_ends_with_the_language_instruction() {
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+        let agent = AgentSettings::default();
+        let question = "Combien de temps ai-je passé dans VS Code cette semaine ?";
+        for context_window in [8_192, 128_000] {
+            let caps = ModelCapabilities {
+                context_window,
+                supports_reasoning: false,
+                supports_native_tools: false,
+                max_tokens: 1_600,
+            };
+            let prompt = build_agent_system_prompt(&policy, &caps, &agent, question);
+            let instruction = crate::utils::i18n::language_instruction(question);
+            assert!(instruction.contains("French"));
+            assert!(prompt.ends_with(&format!("\n## Language\n{}\n", instruction)));
+        }
+    }
+
+    #[test]
+    fn meetings_tool_reports_clustered_meetings_and_their_total() {
+        let conn = test_connection();
+        let t0 = 1_700_000_000;
+        for (title, start, secs) in [
+            ("Weekly Sync | Microsoft Teams", t0, 1_800),
+            ("Weekly Sync | Microsoft Teams", t0 + 1_900, 1_800),
+            ("1:1 with Priya | Microsoft Teams", t0 + 7_200, 1_200),
+            ("Chat | Priya Sharma | Microsoft Teams", t0 + 9_000, 1_200),
+        ] {
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds)
+                 VALUES ('Microsoft Teams', 1, ?1, 3, ?2, ?2 + ?3, ?3)",
+                rusqlite::params![title, start, secs],
+            )
+            .unwrap();
+        }
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+        let args = serde_json::json!({ "start_ts": t0, "end_ts": t0 + 86_400, "scope_label": "this week" });
+        let (output, meetings) = execute_tool(&conn, "get_meetings", &args, &policy, None).unwrap();
+        assert!(output.starts_with("2 meeting(s) in this week, 1h 20m 0s in total"), "{}", output);
+        assert_eq!(meetings.len(), 2);
+        assert_eq!(meetings[0]["name"], "Weekly Sync");
+        assert_eq!(meetings[0]["duration_seconds"], 3_600);
+        assert_eq!(meetings[1]["name"], "1:1 with Priya");
+
+        let empty = serde_json::json!({ "start_ts": 0, "end_ts": 1_000 });
+        let (output, meetings) = execute_tool(&conn, "get_meetings", &empty, &policy, None).unwrap();
+        assert!(meetings.is_empty());
+        assert!(is_low_signal_result("get_meetings", &output, &meetings));
+    }
+
+    fn step(tool: &str, result: &str) -> AgentStep {
+        AgentStep {
+            turn: 0,
+            tool_name: tool.to_string(),
+            tool_args: Value::Null,
+            tool_result: result.to_string(),
+            reasoning: String::new(),
+        }
+    }
+
+    fn scope(id: &str, days: i64) -> TimeScope {
+        TimeScope {
+            id: id.to_string(),
+            label: id.to_string(),
+            start_ts: 0,
+            end_ts: days * 86_400,
+        }
+    }
+
+    #[test]
+    fn default_guardrails_keep_the_previous_behavior() {
+        let agent = AgentSettings::default();
+        assert_eq!(agent.max_turns, 20);
+        assert_eq!(agent.min_evidence_tools_for_identity, 5);
+        assert!(agent.force_validation_enabled && agent.smalltalk_bypass);
+
+        for query in ["when did I first open Figma", "how often do I use Slack", "who is my crush", "my favorite song"] {
+            assert!(requires_broad_scope(query, &agent), "{}", query);
+        }
+        for query in ["what am I doing now", "summarize today"] {
+            assert!(!requires_broad_scope(query, &agent), "{}", query);
+        }
+
+        assert!(!requires_evidence_for_query("thanks!", &agent));
+        assert!(requires_evidence_for_query("what did I do today", &agent));
+        assert!(!has_minimum_evidence_for_query("what did I do today", &[], &agent));
+        let found = [step("get_recent_activities", "Code — main.rs (12m)")];
+        assert!(has_minimum_evidence_for_query("how long was I in Slack today", &found, &agent));
+        // "what did" questions still want two tools agreeing.
+        assert!(!has_minimum_evidence_for_query("what did I do today", &found, &agent));
+        let both = [found[0].clone(), step("get_usage_stats", "Code: 2h 10m")];
+        assert!(has_minimum_evidence_for_query("what did I do today", &both, &agent));
+
+        let summary = detect_query_intent("give me a summary of my quarter");
+        assert!(should_use_long_range_pipeline("give me a summary of my quarter", &scope("q", 91), &summary, &agent));
+        assert!(!should_use_long_range_pipeline("give me a summary of my month", &scope("m", 30), &summary, &agent));
+    }
+
+    #[test]
+    fn only_person_questions_trigger_the_identity_rule() {
+        let strict = AgentSettings {
+            evidence_strictness: EvidenceStrictness::Strict,
+            ..AgentSettings::default()
+        };
+        for query in ["who is my crush", "who did I text the most", "who was that friend from the party"] {
+            assert_eq!(identity_tool_minimum(query, &strict), 5, "{}", query);
+        }
+        for query in ["who sang this song", "who won the match", "whoever wrote this code"] {
+            assert_eq!(identity_tool_minimum(query, &strict), 0, "{}", query);
+        }
+        // Normal strictness leaves the rule off entirely.
+        assert_eq!(identity_tool_minimum("who is my crush", &AgentSettings::default()), 0);
+    }
+
+    #[test]
+    fn custom_guardrails_relax_the_defaults() {
+        let relaxed = AgentSettings {
+            min_evidence_tools_for_identity: 1,
+            smalltalk_bypass: false,
+            broad_scope_keywords: vec!["  Archive ".to_string(), String::new()],
+            long_range_pipeline_threshold_days: 14,
+            evidence_strictness: EvidenceStrictness::Strict,
+            ..AgentSettings::default()
+        };
+        assert_eq!(identity_tool_minimum("who is my crush", &relaxed), 1);
+        assert!(requires_evidence_for_query("thanks!", &relaxed));
+        assert!(requires_broad_scope("search the archive", &relaxed));
+        assert!(!requires_broad_scope("how often do I use Slack", &relaxed));
+        let summary = detect_query_intent("give me a summary of my fortnight");
+        assert!(should_use_long_range_pipeline("give me a summary of my fortnight", &scope("f", 14), &summary, &relaxed));
+
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+        let caps = ModelCapabilities {
+            context_window: 128_000,
+            supports_reasoning: false,
+            supports_native_tools: false,
+            max_tokens: 1_600,
+        };
+        let prompt = build_agent_system_prompt(&policy, &caps, &relaxed, "who is my crush");
+        assert!(prompt.contains("a minimum of 1 distinct tool calls"));
+        assert!(!prompt.contains("{identity_min_tools}"));
+    }
+
+    const T_SCAN: i64 = 1_700_000_000;
+
+    /// Inserts one OCR capture per text, newest first, a minute apart before `T_SCAN`.
+    fn seed_ocr(conn: &Connection, texts: &[String]) {
+        let mut stmt = conn
+            .prepare(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+                 VALUES ('Notes', 1, 'notes', 7, ?1, ?1 + 60, 60, ?2)",
+            )
+            .unwrap();
+        for (i, text) in texts.iter().enumerate() {
+            let meta = serde_json::to_vec(&ActivityMetadata {
+                screen_text: Some(text.clone()),
+                ..Default::default()
+            })
+            .unwrap();
+            stmt.execute(rusqlite::params![T_SCAN - 60 * i as i64, meta]).unwrap();
+        }
+    }
+
+
+    fn recorder() -> (ProgressCallback, std::sync::Arc<std::sync::Mutex<Vec<ToolProgress>>>) {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let callback: ProgressCallback = std::sync::Arc::new(move |p| sink.lock().unwrap().push(p));
+        (callback, events)
+    }
+
+    fn scan_window(keyword: &str, limit: u64) -> Value {
+        serde_json::json!({ "keyword": keyword, "limit": limit, "start_ts": 0, "end_ts": T_SCAN + 60 })
+    }
+
+    #[test]
+    fn ocr_search_stops_scanning_at_the_limit() {
+        let conn = test_connection();
+        // 2 000 copies of one screen (deduplicated to a single match), then 3 distinct older ones.
+        let mut texts = vec!["zebra crossing on the main screen".to_string(); 2_000];
+        texts.extend((0..3).map(|i| format!("zebra notes page {}", i)));
+        texts.extend((0..3_000).map(|i| format!("unrelated capture {}", i)));
+        conn.execute_batch("BEGIN").unwrap();
+        seed_ocr(&conn, &texts);
+        conn.execute_batch("COMMIT").unwrap();
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+
+        let (callback, events) = recorder();
+        let (_, matches) = execute_tool(&conn, "search_ocr", &scan_window("zebra", 1), &policy, Some(&callback)).unwrap();
+        assert_eq!(matches.len(), 1);
+        let done: Vec<(String, usize, usize)> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| (e.phase.clone(), e.scanned, e.matched))
+            .collect();
+        // The first row satisfies the limit; none of the other 2 004 candidates are read.
+        assert_eq!(done, vec![("done".to_string(), 1, 1)]);
+
+        let (callback, events) = recorder();
+        let (_, matches) = execute_tool(&conn, "search_ocr", &scan_window("zebra", 4), &policy, Some(&callback)).unwrap();
+        assert_eq!(matches.len(), 4);
+        let events = events.lock().unwrap();
+        let scanning: Vec<usize> = events.iter().filter(|e| e.phase == "scanning").map(|e| e.scanned).collect();
+        assert_eq!(scanning, vec![500, 1_000, 1_500, 2_000]);
+        let last = events.last().unwrap();
+        assert_eq!((last.tool.as_str(), last.phase.as_str(), last.scanned, last.matched), ("search_ocr", "done", 2_003, 4));
+    }
+
+    #[test]
+    fn parallel_search_reports_each_finished_call() {
+        let dir = crate::utils::test_dir("progress");
+        let path = dir.join("intentflow.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            crate::database::schema::create_tables(&conn).unwrap();
+            crate::database::run_migrations(&conn).unwrap();
+            seed_ocr(&conn, &["zebra one".to_string(), "zebra two".to_string(), "okapi".to_string()]);
+        }
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+        let args = serde_json::json!({ "calls": [
+            { "tool": "search_ocr", "args": scan_window("zebra", 10) },
+            { "tool": "search_ocr", "args": scan_window("okapi", 10) },
+        ]});
+        let (callback, events) = recorder();
+        let (_, activities) = execute_parallel_search(&path, &args, None, "zebra", &policy, Some(&callback)).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(activities.len(), 3);
+
+        let events = events.lock().unwrap();
+        let mut calls: Vec<(usize, usize)> = events
+            .iter()
+            .filter(|e| e.phase == "call_done")
+            .inspect(|e| assert_eq!((e.tool.as_str(), e.detail.as_deref()), ("parallel_search", Some("search_ocr"))))
+            .map(|e| (e.scanned, e.matched))
+            .collect();
+        calls.sort();
+        // Finished-call counts run 1..=2 whichever worker is first.
+        assert_eq!(calls.iter().map(|c| c.0).collect::<Vec<_>>(), vec![1, 2]);
+        let mut matched: Vec<usize> = calls.iter().map(|c| c.1).collect();
+        matched.sort();
+        assert_eq!(matched, vec![1, 2]);
+        assert_eq!(events.iter().filter(|e| e.phase == "done").count(), 2);
+    }
+
+    fn accounting_row(conn: &Connection, app: &str, category_id: i64, start: i64, secs: i64, idle: bool, private: bool) {
+        let meta = serde_json::to_vec(&ActivityMetadata { is_idle: idle, ..Default::default() }).unwrap();
+        conn.execute(
+            "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata, private)
+             VALUES (?1, 1, 'w', ?2, ?3, ?3 + ?4, ?4, ?5, ?6)",
+            rusqlite::params![app, category_id, start, secs, meta, private],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn gap_time_is_the_span_minus_tracked_and_idle() {
+        let conn = test_connection();
+        let start = 1_700_000_000;
+        let scope = TimeScope {
+            id: "custom".to_string(),
+            label: "that day".to_string(),
+            start_ts: start,
+            end_ts: start + 10 * 3_600,
+        };
+        // Starts an hour before the scope: only its last 30 minutes count.
+        accounting_row(&conn, "Code", 1, start - 3_600, 5_400, false, false);
+        accounting_row(&conn, "Code", 1, start + 3_600, 7_200, false, false);
+        accounting_row(&conn, "Slack", 3, start + 3 * 3_600, 1_800, false, false);
+        accounting_row(&conn, "Diary", 7, start + 4 * 3_600, 600, false, true);
+        accounting_row(&conn, "Code", 1, start + 5 * 3_600, 2_700, true, false);
+        // Runs past the scope end: 15 of its 60 minutes count.
+        accounting_row(&conn, "Spotify", 6, start + 10 * 3_600 - 900, 3_600, false, false);
+        accounting_row(&conn, "Code", 1, start + 11 * 3_600, 3_600, false, false);
+
+        let accounting = compute_time_accounting(&conn, &scope, false).unwrap();
+        assert_eq!(accounting.span_seconds, 36_000);
+        assert_eq!(accounting.tracked_seconds, 1_800 + 7_200 + 1_800 + 600 + 900);
+        assert_eq!(accounting.idle_seconds, 2_700);
+        assert_eq!(
+            accounting.untracked_seconds,
+            accounting.span_seconds - accounting.tracked_seconds - accounting.idle_seconds
+        );
+        assert_eq!(accounting.untracked_seconds, 21_000);
+
+        let categories: Vec<(&str, i64)> = accounting.categories.iter().map(|c| (c.name.as_str(), c.seconds)).collect();
+        let (dev, comms, other, media) = (
+            crate::utils::categories::name_for(1),
+            crate::utils::categories::name_for(3),
+            crate::utils::categories::name_for(7),
+            crate::utils::categories::name_for(6),
+        );
+        assert_eq!(categories, vec![(dev.as_str(), 9_000), (comms.as_str(), 1_800), (media.as_str(), 900), (other.as_str(), 600)]);
+        assert_eq!(accounting.categories[0].percent, 73.2);
+        // The private app counts toward its category but is not named.
+        let apps: Vec<&str> = accounting.top_apps.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(apps, vec!["Code", "Slack", "Spotify"]);
+        let with_private = compute_time_accounting(&conn, &scope, true).unwrap();
+        assert_eq!(with_private.top_apps.len(), 4);
+
+        // A scope reaching into the future is capped at now and never counts negative gaps.
+        let now = chrono::Utc::now().timestamp();
+        let future = TimeScope { start_ts: now - 60, end_ts: now + 86_400, ..scope };
+        let accounting = compute_time_accounting(&conn, &future, false).unwrap();
+        assert!(accounting.span_seconds <= 61 && accounting.untracked_seconds == accounting.span_seconds);
+    }
+
+    #[test]
+    fn stated_totals_off_by_more_than_ten_percent_are_flagged() {
+        let accounting = TimeAccounting {
+            scope_label: "today".to_string(),
+            start_ts: 0,
+            end_ts: 36_000,
+            span_seconds: 36_000,
+            tracked_seconds: 18_000,
+            idle_seconds: 1_800,
+            untracked_seconds: 16_200,
+            categories: vec![TimeShare { name: "Development".to_string(), seconds: 12_000, percent: 66.7 }],
+            top_apps: vec![TimeShare { name: "Slack".to_string(), seconds: 2_700, percent: 15.0 }],
+        };
+        let answer = "You tracked 5 hours in total.\nDevelopment took 3h 20m.\nSlack: 45 minutes.\nA 2 hour meeting at noon.";
+        assert!(check_stated_totals(answer, &accounting).is_empty());
+
+        let answer = "You tracked 4 hours in total.\nDevelopment took 3.5 hrs.\nSlack: 1h 10m.";
+        let factors = check_stated_totals(answer, &accounting);
+        assert_eq!(factors.len(), 2, "{:?}", factors);
+        assert!(factors[0].contains("tracked total"));
+        assert!(factors[1].contains("Slack"));
+    }
+
+    /// `count` captures in `category_id`, a minute apart, starting `first` minutes before `T_SCAN`.
+    fn seed_category(conn: &Connection, category_id: i64, count: usize, first: usize, text: &str) {
+        for i in 0..count {
+            let meta = serde_json::to_vec(&ActivityMetadata {
+                screen_text: Some(format!("{} #{}", text, i)),
+                ..Default::default()
+            })
+            .unwrap();
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, metadata)
+                 VALUES ('App', 1, 'w', ?1, ?2, ?2 + 60, 60, ?3)",
+                rusqlite::params![category_id, T_SCAN - 60 * (first + i) as i64, meta],
+            )
+            .unwrap();
+        }
+    }
+
+    fn last_scan(events: &std::sync::Mutex<Vec<ToolProgress>>) -> (usize, usize) {
+        let events = events.lock().unwrap();
+        let done = events.iter().rev().find(|e| e.phase == "done").unwrap();
+        (done.scanned, done.matched)
+    }
+
+    #[test]
+    fn category_filter_narrows_the_ocr_scan() {
+        let conn = test_connection();
+        conn.execute_batch("BEGIN").unwrap();
+        // Hours of IDE captures mentioning the keyword, then a handful of chat ones.
+        seed_category(&conn, CODE_CATEGORY_ID, 1_500, 0, "cargo run dinner_service");
+        seed_category(&conn, CHAT_CATEGORY_ID, 12, 1_500, "are we still on for dinner");
+        conn.execute_batch("COMMIT").unwrap();
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+
+        let (callback, events) = recorder();
+        let mut args = scan_window("dinner", 2_000);
+        execute_tool(&conn, "search_ocr", &args, &policy, Some(&callback)).unwrap();
+        assert_eq!(last_scan(&events), (1_512, 1_512));
+
+        let (callback, events) = recorder();
+        args["category_id"] = Value::from(CHAT_CATEGORY_ID);
+        let (_, matches) = execute_tool(&conn, "search_ocr", &args, &policy, Some(&callback)).unwrap();
+        assert_eq!(last_scan(&events), (12, 12));
+        assert!(matches.iter().all(|m| m["category_id"] == CHAT_CATEGORY_ID));
+
+        let (callback, events) = recorder();
+        let recent = serde_json::json!({ "limit": 20, "start_ts": 0, "end_ts": T_SCAN + 60, "categories": ["3"] });
+        let (_, matches) = execute_tool(&conn, "get_recent_ocr", &recent, &policy, Some(&callback)).unwrap();
+        assert_eq!(matches.len(), 12);
+        assert_eq!(last_scan(&events).0, 12);
+
+        assert_eq!(category_clause(&serde_json::json!({ "category_id": 3, "categories": [1, "3"] })), " AND category_id IN (1, 3)");
+        assert_eq!(category_clause(&serde_json::json!({ "categories": ["chat"] })), "");
+    }
+
+    #[test]
+    fn broadening_drops_the_category_on_the_second_retry() {
+        let args = serde_json::json!({ "keyword": "dinner", "category_id": 3, "categories": [3] });
+        let first = broaden_tool_args("search_ocr", &args, 1);
+        assert_eq!(first["category_id"], 3);
+        let second = broaden_tool_args("search_ocr", &first, 2);
+        assert!(second.get("category_id").is_none() && second.get("categories").is_none());
+        // Other tools keep their args.
+        let activities = broaden_tool_args("get_recent_activities", &args, 2);
+        assert_eq!(activities["category_id"], 3);
+
+        // The guess was wrong: the only match is a code capture, found once the filter is gone.
+        let conn = test_connection();
+        seed_category(&conn, CODE_CATEGORY_ID, 1, 0, "dinner reservation script");
+        let policy = ToolPolicy {
+            include_private: false,
+            disabled_tools: Vec::new(),
+            chat_session_id: None,
+        };
+        let mut args = scan_window("dinner", 5);
+        args["category_id"] = Value::from(CHAT_CATEGORY_ID);
+        let (_, matches, attempts) = execute_tool_with_retries(&conn, "search_ocr", &args, MAX_TOOL_RETRY_LOOPS, &policy, None).unwrap();
+        assert_eq!((matches.len(), attempts), (1, 3));
+    }
+
+    #[test]
+    fn chat_and_code_questions_pass_their_category_to_the_prefetch() {
+        let scope = TimeScope {
+            id: "today".to_string(),
+            label: "Today".to_string(),
+            start_ts: 0,
+            end_ts: 86_400,
+        };
+        let ocr_category = |query: &str| {
+            let intent = detect_query_intent(query);
+            let forced = build_forced_validation_parallel_args(&scope, &intent, query);
+            forced["calls"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|c| c["tool"] == "get_recent_ocr")
+                .and_then(|c| c["args"]["category_id"].as_i64())
+        };
+        assert_eq!(ocr_category("what did I chat about on WhatsApp"), Some(CHAT_CATEGORY_ID));
+        assert_eq!(ocr_category("what was I debugging in the terminal"), Some(CODE_CATEGORY_ID));
+        assert_eq!(ocr_category("what did I do today"), None);
+        // Both at once is ambiguous, so no filter.
+        assert_eq!(ocr_category("did I message anyone about that code"), None);
+
+        let intent = detect_query_intent("show my whatsapp chat messages");
+        let prefetch = build_prefetch_parallel_args(&scope, &intent);
+        let ocr = prefetch["calls"].as_array().unwrap().iter().find(|c| c["tool"] == "get_recent_ocr").unwrap();
+        assert_eq!(ocr["args"]["category_id"], CHAT_CATEGORY_ID);
+    }
+}
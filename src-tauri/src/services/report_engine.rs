@@ -0,0 +1,600 @@
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate, Timelike};
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager};
+
+use crate::models::{
+    DailyTotal, FocusMetrics, GeneratedReport, PeriodReport, ReportProject, ReportSettings,
+};
+
+const REPORT_TOP_APPS: usize = 10;
+const REPORT_TOP_PROJECTS: usize = 10;
+const REPORT_MAX_DAYS: i64 = 366;
+const FOCUS_CATEGORY_IDS: [i32; 2] = [1, 5]; // Development, Productivity
+const FOCUS_BLOCK_MIN_SECS: i64 = 25 * 60;
+const FOCUS_BLOCK_GAP_SECS: i64 = 2 * 60;
+const SCHEDULER_TICK_SECS: u64 = 60;
+
+const REPORT_CSS: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Roboto, sans-serif; color: #1f2933; background: #f5f7fa; margin: 0; }
+main { max-width: 760px; margin: 32px auto; background: #fff; padding: 32px 40px; border-radius: 12px; }
+h1 { font-size: 24px; margin: 0 0 4px; }
+h2 { font-size: 16px; margin: 28px 0 10px; text-transform: uppercase; letter-spacing: .04em; color: #52606d; }
+.sub { color: #7b8794; margin: 0 0 20px; }
+.stats { display: flex; gap: 12px; flex-wrap: wrap; }
+.stat { flex: 1; min-width: 140px; background: #f5f7fa; border-radius: 8px; padding: 12px 14px; }
+.stat b { display: block; font-size: 20px; }
+.stat span { color: #7b8794; font-size: 12px; }
+table { width: 100%; border-collapse: collapse; font-size: 14px; }
+td, th { text-align: left; padding: 6px 4px; border-bottom: 1px solid #e4e7eb; }
+td.num, th.num { text-align: right; }
+.empty { color: #7b8794; font-style: italic; }
+svg text { font-size: 12px; fill: #3e4c59; }
+"#;
+
+// ─── Public API ───
+
+/// Build the report for the local dates `[start_date, end_date]` (inclusive).
+pub fn build_period_report(
+    conn: &Connection,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<PeriodReport, String> {
+    if end_date < start_date {
+        return Err("end_date must not be before start_date".to_string());
+    }
+    if (end_date - start_date).num_days() >= REPORT_MAX_DAYS {
+        return Err(format!("Report range is limited to {} days", REPORT_MAX_DAYS));
+    }
+
+    let start_ts = crate::utils::time::day_bounds(Some(conn), start_date).start_ts;
+    let end_ts = crate::utils::time::day_bounds(Some(conn), end_date).end_ts;
+
+    let (total_tracked_seconds, apps, category_split) =
+        crate::services::dashboard_engine::usage_totals_for_range(conn, start_ts, end_ts)?;
+    let (contacts, deadlines) =
+        crate::services::dashboard_engine::contacts_and_deadlines_for_range(conn, start_ts, end_ts)?;
+
+    let mut top_apps = apps;
+    let others = if top_apps.len() > REPORT_TOP_APPS {
+        top_apps.split_off(REPORT_TOP_APPS)
+    } else {
+        Vec::new()
+    };
+
+    let daily_totals = daily_totals(conn, start_date, end_date, start_ts, end_ts)?;
+    let active_days = daily_totals.iter().filter(|d| d.seconds > 0).count() as i32;
+
+    Ok(PeriodReport {
+        start_date: start_date.format("%Y-%m-%d").to_string(),
+        end_date: end_date.format("%Y-%m-%d").to_string(),
+        total_tracked_seconds,
+        active_days,
+        daily_average_seconds: if active_days > 0 {
+            total_tracked_seconds / active_days as i64
+        } else {
+            0
+        },
+        daily_totals,
+        category_split,
+        other_apps_count: others.len() as i32,
+        other_apps_seconds: others.iter().map(|a| a.duration).sum(),
+        top_apps,
+        projects: project_counts(conn, start_ts, end_ts)?,
+        focus: focus_metrics(conn, start_ts, end_ts)?,
+        contacts,
+        deadlines,
+        generated_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// Build the report and write it as HTML and/or Markdown into the configured directory.
+pub fn generate_period_report(
+    app_handle: &AppHandle,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    format: &str,
+) -> Result<GeneratedReport, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let conn = crate::database::open(app_handle)?;
+    let report = build_period_report(&conn, start_date, end_date)?;
+
+    let settings = crate::services::settings_bus::current(app_handle).unwrap_or_default();
+    let output_dir = report_output_dir(&data_dir, &settings.reports);
+    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+
+    let (html, markdown) = match format.trim().to_lowercase().as_str() {
+        "" | "html" => (true, false),
+        "md" | "markdown" => (false, true),
+        "both" => (true, true),
+        other => return Err(format!("Unsupported report format '{}'", other)),
+    };
+
+    let stem = format!("intentflow-report-{}-to-{}", report.start_date, report.end_date);
+    let mut paths = Vec::new();
+    if html {
+        let path = output_dir.join(format!("{}.html", stem));
+        std::fs::write(&path, render_html(&report)).map_err(|e| e.to_string())?;
+        paths.push(path.to_string_lossy().to_string());
+    }
+    if markdown {
+        let path = output_dir.join(format!("{}.md", stem));
+        std::fs::write(&path, render_markdown(&report)).map_err(|e| e.to_string())?;
+        paths.push(path.to_string_lossy().to_string());
+    }
+
+    println!("[Reports] Wrote {} file(s) for {} to {}", paths.len(), report.start_date, report.end_date);
+    Ok(GeneratedReport { paths, report })
+}
+
+/// Writes the weekly report once per week on the configured weekday/time.
+pub fn start_report_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(SCHEDULER_TICK_SECS)).await;
+            if let Err(e) = run_scheduled_report(&app_handle) {
+                println!("[Reports] Scheduled report failed: {}", e);
+            }
+        }
+    });
+}
+
+// ─── Scheduling ───
+
+fn last_scheduled_store() -> &'static Mutex<Option<NaiveDate>> {
+    static STORE: OnceLock<Mutex<Option<NaiveDate>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(None))
+}
+
+fn run_scheduled_report(app_handle: &AppHandle) -> Result<(), String> {
+    let Some(settings) = crate::services::settings_bus::current(app_handle) else {
+        return Ok(());
+    };
+    let config = &settings.reports;
+    if !config.weekly_enabled {
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now().with_timezone(&crate::utils::time::current_offset());
+    let today = now.date_naive();
+    let Some(weekday) = parse_weekday(&config.weekday) else {
+        return Err(format!("Unknown report weekday '{}'", config.weekday));
+    };
+    if today.weekday() != weekday {
+        return Ok(());
+    }
+    let (hour, minute) = parse_hhmm(&config.time).unwrap_or((16, 0));
+    if (now.hour(), now.minute()) < (hour, minute) {
+        return Ok(());
+    }
+    if last_scheduled_store().lock().map(|d| *d == Some(today)).unwrap_or(false) {
+        return Ok(());
+    }
+
+    // The week ending today; skip if it was already written (e.g. before a restart).
+    let start_date = today - chrono::Duration::days(6);
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let stem = format!(
+        "intentflow-report-{}-to-{}",
+        start_date.format("%Y-%m-%d"),
+        today.format("%Y-%m-%d")
+    );
+    let output_dir = report_output_dir(&data_dir, config);
+    let already_written = ["html", "md"]
+        .iter()
+        .any(|ext| output_dir.join(format!("{}.{}", stem, ext)).exists());
+
+    if let Ok(mut last) = last_scheduled_store().lock() {
+        *last = Some(today);
+    }
+    if already_written {
+        return Ok(());
+    }
+
+    let generated = generate_period_report(app_handle, start_date, today, &config.format)?;
+    let location = generated.paths.first().cloned().unwrap_or_default();
+    crate::services::notifier::notify(
+        app_handle,
+        "weekly_report",
+        "Weekly report ready",
+        &format!("Saved to {}", location),
+    );
+    Ok(())
+}
+
+fn report_output_dir(data_dir: &std::path::Path, config: &ReportSettings) -> PathBuf {
+    let configured = config.output_dir.trim();
+    if configured.is_empty() {
+        data_dir.join("reports")
+    } else {
+        PathBuf::from(configured)
+    }
+}
+
+fn parse_weekday(value: &str) -> Option<chrono::Weekday> {
+    value.trim().parse::<chrono::Weekday>().ok()
+}
+
+fn parse_hhmm(value: &str) -> Option<(u32, u32)> {
+    let (h, m) = value.trim().split_once(':')?;
+    let hour = h.parse::<u32>().ok().filter(|h| *h < 24)?;
+    let minute = m.parse::<u32>().ok().filter(|m| *m < 60)?;
+    Some((hour, minute))
+}
+
+// ─── Aggregation ───
+
+fn daily_totals(
+    conn: &Connection,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    start_ts: i64,
+    end_ts: i64,
+) -> Result<Vec<DailyTotal>, String> {
+    let sql = format!(
+        "SELECT date({}) AS day, SUM(duration_seconds)
+         FROM activities
+         WHERE start_time >= ?1 AND start_time < ?2 AND COALESCE(private, 0) = 0
+         GROUP BY day",
+        crate::utils::time::ROW_LOCAL_DATETIME_SQL
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let totals: std::collections::HashMap<String, i64> = stmt
+        .query_map(rusqlite::params![start_ts, end_ts], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // One entry per calendar day so empty days still show up in the chart.
+    let mut out = Vec::new();
+    let mut date = start_date;
+    while date <= end_date {
+        let key = date.format("%Y-%m-%d").to_string();
+        let seconds = totals.get(&key).copied().unwrap_or(0);
+        out.push(DailyTotal { date_key: key, seconds });
+        date += chrono::Duration::days(1);
+    }
+    Ok(out)
+}
+
+fn project_counts(conn: &Connection, start_ts: i64, end_ts: i64) -> Result<Vec<ReportProject>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT project_root, COUNT(*) AS events, COUNT(DISTINCT path)
+             FROM code_file_events
+             WHERE detected_at >= ?1 AND detected_at < ?2
+             GROUP BY project_root
+             ORDER BY events DESC
+             LIMIT ?3",
+        )
+        .map_err(|e| e.to_string())?;
+    let projects = stmt
+        .query_map(
+            rusqlite::params![start_ts, end_ts, REPORT_TOP_PROJECTS as i64],
+            |row| {
+                Ok(ReportProject {
+                    name: row.get(0)?,
+                    file_events: row.get(1)?,
+                    files_changed: row.get(2)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(projects)
+}
+
+fn focus_metrics(conn: &Connection, start_ts: i64, end_ts: i64) -> Result<FocusMetrics, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT app_name, category_id, start_time, end_time
+             FROM activities
+             WHERE start_time >= ?1 AND start_time < ?2 AND COALESCE(private, 0) = 0
+             ORDER BY start_time ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![start_ts, end_ts], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i32>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut metrics = FocusMetrics::default();
+    let mut switches = 0i64;
+    let mut tracked = 0i64;
+    let mut last_app: Option<String> = None;
+    let mut block: Option<(i64, i64)> = None;
+    let close_block = |block: (i64, i64), metrics: &mut FocusMetrics| {
+        let length = block.1 - block.0;
+        if length >= FOCUS_BLOCK_MIN_SECS {
+            metrics.focus_blocks += 1;
+            metrics.deep_work_seconds += length;
+            metrics.longest_focus_block_seconds = metrics.longest_focus_block_seconds.max(length);
+        }
+    };
+
+    for (app, category_id, start, end) in rows.filter_map(|r| r.ok()) {
+        tracked += (end - start).max(0);
+        if last_app.as_deref().is_some_and(|prev| prev != app) {
+            switches += 1;
+        }
+        last_app = Some(app);
+
+        if !FOCUS_CATEGORY_IDS.contains(&category_id) {
+            if let Some(open) = block.take() {
+                close_block(open, &mut metrics);
+            }
+            continue;
+        }
+        block = match block {
+            Some((block_start, block_end)) if start - block_end <= FOCUS_BLOCK_GAP_SECS => {
+                Some((block_start, block_end.max(end)))
+            }
+            Some(open) => {
+                close_block(open, &mut metrics);
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    if let Some(open) = block {
+        close_block(open, &mut metrics);
+    }
+
+    if tracked > 0 {
+        metrics.app_switches_per_hour = (switches as f64 / (tracked as f64 / 3600.0)) as f32;
+    }
+    Ok(metrics)
+}
+
+// ─── Rendering ───
+
+fn format_hm(total_seconds: i64) -> String {
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Horizontal bar chart as inline SVG; values are seconds.
+fn svg_bar_chart(rows: &[(String, i64)], color: &str) -> String {
+    const LABEL_WIDTH: i64 = 170;
+    const BAR_WIDTH: i64 = 420;
+    const ROW_HEIGHT: i64 = 24;
+    let max = rows.iter().map(|(_, v)| *v).max().unwrap_or(0).max(1);
+    let height = rows.len() as i64 * ROW_HEIGHT + 4;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100%\" viewBox=\"0 0 {} {}\" role=\"img\">",
+        LABEL_WIDTH + BAR_WIDTH + 80,
+        height
+    );
+    for (i, (label, value)) in rows.iter().enumerate() {
+        let y = i as i64 * ROW_HEIGHT;
+        let width = (*value * BAR_WIDTH / max).max(if *value > 0 { 2 } else { 0 });
+        let short: String = label.chars().take(26).collect();
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{}</text><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"16\" rx=\"3\" fill=\"{}\"/><text x=\"{}\" y=\"{}\">{}</text>",
+            y + 15,
+            escape_html(&short),
+            LABEL_WIDTH,
+            y + 3,
+            width,
+            color,
+            LABEL_WIDTH + width + 6,
+            y + 15,
+            format_hm(*value)
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn render_html(report: &PeriodReport) -> String {
+    let mut body = String::new();
+    body.push_str(&format!(
+        "<h1>IntentFlow report</h1><p class=\"sub\">{} to {}</p>",
+        report.start_date, report.end_date
+    ));
+    body.push_str(&format!(
+        "<div class=\"stats\">\
+         <div class=\"stat\"><b>{}</b><span>Total tracked</span></div>\
+         <div class=\"stat\"><b>{}</b><span>Daily average ({} active day{})</span></div>\
+         <div class=\"stat\"><b>{}</b><span>Deep work ({} block{})</span></div>\
+         <div class=\"stat\"><b>{:.1}</b><span>App switches / hour</span></div>\
+         </div>",
+        format_hm(report.total_tracked_seconds),
+        format_hm(report.daily_average_seconds),
+        report.active_days,
+        if report.active_days == 1 { "" } else { "s" },
+        format_hm(report.focus.deep_work_seconds),
+        report.focus.focus_blocks,
+        if report.focus.focus_blocks == 1 { "" } else { "s" },
+        report.focus.app_switches_per_hour
+    ));
+
+    if report.total_tracked_seconds == 0 {
+        body.push_str("<p class=\"empty\">No tracked activity in this period.</p>");
+    } else {
+        let days: Vec<(String, i64)> = report
+            .daily_totals
+            .iter()
+            .map(|d| (d.date_key.clone(), d.seconds))
+            .collect();
+        body.push_str("<h2>Daily time</h2>");
+        body.push_str(&svg_bar_chart(&days, "#3e7bfa"));
+
+        let categories: Vec<(String, i64)> = report
+            .category_split
+            .iter()
+            .map(|c| (c.category_name.clone(), c.duration))
+            .collect();
+        body.push_str("<h2>Time per category</h2>");
+        body.push_str(&svg_bar_chart(&categories, "#2bb673"));
+
+        body.push_str("<h2>Top apps</h2><table><tr><th>App</th><th class=\"num\">Time</th><th class=\"num\">Share</th></tr>");
+        for app in &report.top_apps {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.0}%</td></tr>",
+                escape_html(&app.app_name),
+                format_hm(app.duration),
+                app.percentage
+            ));
+        }
+        if report.other_apps_count > 0 {
+            body.push_str(&format!(
+                "<tr><td>{} others</td><td class=\"num\">{}</td><td></td></tr>",
+                report.other_apps_count,
+                format_hm(report.other_apps_seconds)
+            ));
+        }
+        body.push_str("</table>");
+    }
+
+    body.push_str("<h2>Projects</h2>");
+    if report.projects.is_empty() {
+        body.push_str("<p class=\"empty\">No file changes recorded.</p>");
+    } else {
+        body.push_str("<table><tr><th>Project</th><th class=\"num\">Files</th><th class=\"num\">Changes</th></tr>");
+        for project in &report.projects {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                escape_html(&project.name),
+                project.files_changed,
+                project.file_events
+            ));
+        }
+        body.push_str("</table>");
+    }
+
+    body.push_str("<h2>Top contacts</h2>");
+    if report.contacts.is_empty() {
+        body.push_str("<p class=\"empty\">No contacts detected.</p>");
+    } else {
+        body.push_str("<table>");
+        for contact in &report.contacts {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape_html(&contact.name),
+                escape_html(&contact.context)
+            ));
+        }
+        body.push_str("</table>");
+    }
+
+    body.push_str("<h2>Deadlines</h2>");
+    if report.deadlines.is_empty() {
+        body.push_str("<p class=\"empty\">No open deadlines.</p>");
+    } else {
+        body.push_str("<table>");
+        for task in &report.deadlines {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape_html(&task.title),
+                escape_html(task.due_date.as_deref().unwrap_or(""))
+            ));
+        }
+        body.push_str("</table>");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\"><title>IntentFlow report {} to {}</title><style>{}</style></head><body><main>{}</main></body></html>\n",
+        report.start_date, report.end_date, REPORT_CSS, body
+    )
+}
+
+fn render_markdown(report: &PeriodReport) -> String {
+    let mut out = format!(
+        "# IntentFlow report\n\n{} to {}\n\n",
+        report.start_date, report.end_date
+    );
+    out.push_str(&format!(
+        "- **Total tracked:** {}\n- **Daily average:** {} ({} active days)\n- **Deep work:** {} in {} blocks (longest {})\n- **App switches / hour:** {:.1}\n\n",
+        format_hm(report.total_tracked_seconds),
+        format_hm(report.daily_average_seconds),
+        report.active_days,
+        format_hm(report.focus.deep_work_seconds),
+        report.focus.focus_blocks,
+        format_hm(report.focus.longest_focus_block_seconds),
+        report.focus.app_switches_per_hour
+    ));
+
+    if report.total_tracked_seconds == 0 {
+        out.push_str("_No tracked activity in this period._\n\n");
+    } else {
+        out.push_str("## Time per category\n\n");
+        for category in &report.category_split {
+            out.push_str(&format!(
+                "- {}: {} ({:.0}%)\n",
+                category.category_name,
+                format_hm(category.duration),
+                category.percentage
+            ));
+        }
+        out.push_str("\n## Top apps\n\n");
+        for app in &report.top_apps {
+            out.push_str(&format!("- {}: {}\n", app.app_name, format_hm(app.duration)));
+        }
+        if report.other_apps_count > 0 {
+            out.push_str(&format!(
+                "- {} others: {}\n",
+                report.other_apps_count,
+                format_hm(report.other_apps_seconds)
+            ));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Projects\n\n");
+    if report.projects.is_empty() {
+        out.push_str("_No file changes recorded._\n");
+    }
+    for project in &report.projects {
+        out.push_str(&format!(
+            "- {}: {} files, {} changes\n",
+            project.name, project.files_changed, project.file_events
+        ));
+    }
+
+    out.push_str("\n## Top contacts\n\n");
+    if report.contacts.is_empty() {
+        out.push_str("_No contacts detected._\n");
+    }
+    for contact in &report.contacts {
+        out.push_str(&format!("- {} ({})\n", contact.name, contact.context));
+    }
+
+    out.push_str("\n## Deadlines\n\n");
+    if report.deadlines.is_empty() {
+        out.push_str("_No open deadlines._\n");
+    }
+    for task in &report.deadlines {
+        match &task.due_date {
+            Some(due) => out.push_str(&format!("- {} ({})\n", task.title, due)),
+            None => out.push_str(&format!("- {}\n", task.title)),
+        }
+    }
+    out
+}
@@ -1,103 +1,736 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
 use std::path::PathBuf;
-use tauri::AppHandle;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
 use image::{DynamicImage, GrayImage, ImageBuffer, Rgba, RgbaImage};
 
+use crate::models::{OcrMode, OcrProfile};
+use crate::services::service_status::{self, SCREEN_CAPTURE};
+use crate::utils::text::{is_emoji, is_expected_symbol, latin_vowel_ratio};
+
 // ─── Shared state ───
 // The screen capture service writes OCR text here,
 // and the activity tracker reads it when storing activities.
 
 static CAPTURE_ENABLED: AtomicBool = AtomicBool::new(true);
+static SAVE_THUMBNAILS: AtomicBool = AtomicBool::new(false);
+static OCR_DEBUG_EVENTS: AtomicBool = AtomicBool::new(false);
+/// Set by `clear_latest_screen_text`; the capture loop drops its diff base on the next tick.
+static RESET_DIFF_BASE: AtomicBool = AtomicBool::new(false);
+static OCR_INTERVAL_SECS: AtomicU64 = AtomicU64::new(DEFAULT_OCR_INTERVAL_SECS);
+static ADAPTIVE_OCR: AtomicBool = AtomicBool::new(true);
+static ADAPTIVE_MIN_SECS: AtomicU64 = AtomicU64::new(5);
+static ADAPTIVE_MAX_SECS: AtomicU64 = AtomicU64::new(30);
+pub const DEFAULT_OCR_INTERVAL_SECS: u64 = 10;
+const MIN_OCR_INTERVAL_SECS: u64 = 2;
+const MAX_OCR_INTERVAL_SECS: u64 = 600;
+/// How often a paused (fullscreen) capture loop checks whether the window changed.
+const FULLSCREEN_RECHECK_SECS: u64 = 3;
 const MAX_OCR_CHARS: usize = 2000;
-const MIN_OCR_QUALITY_SCORE: f64 = 0.28;
+/// Fallback when settings are unavailable; see `TrackingSettings::ocr_min_quality`.
+pub const DEFAULT_MIN_OCR_QUALITY: f64 = 0.28;
+const THUMBNAIL_WIDTH: u32 = 320;
+pub const THUMBNAIL_DIR: &str = "thumbnails";
+const OCR_ALERT_COOLDOWN_SECS: i64 = 5 * 60;
+const OCR_ALERT_SNIPPET_CHARS: usize = 60;
+
+/// Latest accepted OCR text.
+fn screen_text_store() -> &'static Mutex<Option<OcrReading>> {
+    static STORE: OnceLock<Mutex<Option<OcrReading>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(None))
+}
+
+/// BCP-47 tags to run OCR with; empty uses the Windows profile languages.
+/// Latest capture outcome and latest stored reading, for `get_ocr_status`.
+fn ocr_status_store() -> &'static Mutex<OcrStatusSummary> {
+    static STORE: OnceLock<Mutex<OcrStatusSummary>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(OcrStatusSummary::default()))
+}
+
+fn ocr_languages_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn min_quality_store() -> &'static Mutex<f64> {
+    static STORE: OnceLock<Mutex<f64>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(DEFAULT_MIN_OCR_QUALITY))
+}
+
+/// Latest thumbnail as (app name it was captured for, path relative to app data dir).
+fn thumbnail_store() -> &'static Mutex<Option<(String, String)>> {
+    static STORE: OnceLock<Mutex<Option<(String, String)>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(None))
+}
+
+fn excluded_apps_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Lowercased `tracking.ocr_allowed_apps`; empty means every app is OCR'd.
+fn ocr_allowed_apps_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
 
-fn screen_text_store() -> &'static Mutex<String> {
-    static STORE: OnceLock<Mutex<String>> = OnceLock::new();
-    STORE.get_or_init(|| Mutex::new(String::new()))
+/// `tracking.ocr_profiles` with lowercased patterns, in match order.
+fn ocr_profiles_store() -> &'static Mutex<Vec<OcrProfile>> {
+    static STORE: OnceLock<Mutex<Vec<OcrProfile>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(crate::models::default_ocr_profiles()))
 }
 
-/// Get the latest OCR-extracted screen text.
+fn ocr_alert_keywords_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Last alert per keyword: (timestamp, snippet) used for debouncing.
+fn ocr_alert_history_store() -> &'static Mutex<HashMap<String, (i64, String)>> {
+    static STORE: OnceLock<Mutex<HashMap<String, (i64, String)>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrAlert {
+    pub keyword: String,
+    pub snippet: String,
+    pub app_name: String,
+    pub timestamp: i64,
+}
+
+/// Pipeline stage reported in `ocr://status` events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OcrStage {
+    /// Taking the screenshot (verbose only).
+    Capturing,
+    /// Screen looked the same as last time, so OCR didn't run.
+    DiffingSkipped,
+    /// The app's OCR profile is `skip`.
+    ProfileSkipped,
+    /// The app is in `exclude_apps` or not in a non-empty `ocr_allowed_apps`.
+    AppSkipped,
+    /// Text recognition started (verbose only).
+    OcrRunning,
+    Stored,
+    /// Every variant scored below `ocr_min_quality`.
+    LowQualitySkipped,
+    Failed,
+}
+
+impl OcrStage {
+    /// In-progress stages; the rest are the outcome of a capture and are always sent.
+    fn is_verbose(self) -> bool {
+        matches!(self, OcrStage::Capturing | OcrStage::OcrRunning)
+    }
+}
+
+/// Payload of the `ocr://status` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrStatus {
+    pub stage: OcrStage,
+    /// Capture number since startup.
+    pub capture: u32,
+    pub app_name: String,
+    pub timestamp: i64,
+    /// Time since the capture started.
+    pub elapsed_ms: u64,
+    /// Time spent in text recognition, once it ran.
+    pub ocr_ms: Option<u64>,
+    /// Characters kept (for `stored`).
+    pub chars: usize,
+    pub score: Option<f64>,
+    pub error: Option<String>,
+}
+
+impl OcrStatus {
+    fn new(stage: OcrStage, capture: u32, app_name: &str, started: Instant) -> Self {
+        Self {
+            stage,
+            capture,
+            app_name: app_name.to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            ocr_ms: None,
+            chars: 0,
+            score: None,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OcrStatusSummary {
+    /// Outcome of the most recent capture.
+    pub latest: Option<OcrStatus>,
+    /// Most recent capture whose text was stored.
+    pub last_stored: Option<OcrStatus>,
+}
+
+/// OCR text kept for a capture, with the score and recognizer language that won.
+#[derive(Debug, Clone)]
+pub struct OcrReading {
+    pub text: String,
+    pub score: f64,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrVariantReport {
+    pub variant: String,
+    /// Recognizer language tag, e.g. "de-DE".
+    pub language: String,
+    pub raw_text: String,
+    pub cleaned_text: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrTestReport {
+    pub app_name: String,
+    pub threshold: f64,
+    pub variants: Vec<OcrVariantReport>,
+    /// Variant the capture loop would keep; `None` when every score is below the threshold.
+    pub accepted_variant: Option<String>,
+    pub accepted_language: Option<String>,
+}
+
+/// Get the latest OCR-extracted screen text with its score and language.
 /// Called by activity_tracker when storing activities.
-pub fn get_latest_screen_text() -> Option<String> {
-    let text = screen_text_store().lock().ok()?.clone();
-    if text.is_empty() { None } else { Some(text) }
+pub fn get_latest_screen_text() -> Option<OcrReading> {
+    screen_text_store()
+        .lock()
+        .ok()?
+        .clone()
+        .filter(|reading| !reading.text.is_empty())
+}
+
+/// Forget the latest OCR reading, thumbnail and diff image so nothing captured
+/// so far can be attached to a session or compared against.
+pub fn clear_latest_screen_text() {
+    if let Ok(mut store) = screen_text_store().lock() {
+        *store = None;
+    }
+    if let Ok(mut store) = thumbnail_store().lock() {
+        *store = None;
+    }
+    RESET_DIFF_BASE.store(true, Ordering::Relaxed);
+}
+
+/// Get the latest thumbnail path, but only if it was captured for `app_name`,
+/// so a session never points at a frame of the previously focused window.
+pub fn get_latest_thumbnail_for(app_name: &str) -> Option<String> {
+    let guard = thumbnail_store().lock().ok()?;
+    let (captured_app, path) = guard.as_ref()?;
+    let wanted = app_name.to_lowercase();
+    let captured = captured_app.to_lowercase();
+    if wanted.is_empty() || captured.is_empty() {
+        return None;
+    }
+    if captured.contains(&wanted) || wanted.contains(&captured) {
+        Some(path.clone())
+    } else {
+        None
+    }
+}
+
+pub fn configure_thumbnails(enabled: bool, exclude_apps: &[String]) {
+    SAVE_THUMBNAILS.store(enabled, Ordering::Relaxed);
+    if let Ok(mut store) = excluded_apps_store().lock() {
+        *store = exclude_apps
+            .iter()
+            .map(|a| a.trim().to_lowercase())
+            .filter(|a| !a.is_empty())
+            .collect();
+    }
+    if !enabled {
+        if let Ok(mut store) = thumbnail_store().lock() {
+            *store = None;
+        }
+    }
+    log::info!("Thumbnails enabled: {}", enabled);
+}
+
+fn is_app_excluded(app_name: &str) -> bool {
+    let app = app_name.to_lowercase();
+    excluded_apps_store()
+        .lock()
+        .map(|list| list.iter().any(|excluded| app.contains(excluded.as_str())))
+        .unwrap_or(true)
+}
+
+pub fn configure_ocr_allowed_apps(apps: Option<&[String]>) {
+    if let Ok(mut store) = ocr_allowed_apps_store().lock() {
+        *store = apps
+            .unwrap_or_default()
+            .iter()
+            .map(|a| a.trim().to_lowercase())
+            .filter(|a| !a.is_empty())
+            .collect();
+    }
+}
+
+/// Whether the capture loop should OCR `app_name`: never for excluded apps, and
+/// only for allowlisted ones when `ocr_allowed_apps` is set.
+fn is_ocr_allowed(app_name: &str) -> bool {
+    if is_app_excluded(app_name) {
+        return false;
+    }
+    let app = app_name.to_lowercase();
+    ocr_allowed_apps_store()
+        .lock()
+        .map(|list| list.is_empty() || list.iter().any(|allowed| app.contains(allowed.as_str())))
+        .unwrap_or(false)
 }
 
 /// Start the periodic screen capture + OCR service.
-/// Runs every ~10 seconds on a background task, non-blocking.
-pub fn start_screen_capture(_app_handle: AppHandle) {
+/// Runs every `tracking.ocr_interval` seconds (10 by default), adapted to the focused
+/// window when `tracking.adaptive_ocr` is on, on a background task, non-blocking.
+pub fn start_screen_capture(app_handle: AppHandle) {
+    crate::services::settings_bus::spawn_subscriber(&app_handle, "screen_capture", |settings| {
+        // Pausing tracking also pauses screen capture, so no OCR is taken while paused.
+        set_capture_enabled(crate::effective_monitoring_enabled(settings.tracking.enabled));
+        configure_thumbnails(settings.tracking.save_thumbnails, &settings.tracking.exclude_apps);
+        configure_ocr_alerts(&settings.notifications.ocr_alert_keywords);
+        configure_min_quality(settings.tracking.ocr_min_quality);
+        configure_ocr_languages(&settings.tracking.ocr_languages);
+        configure_ocr_profiles(&settings.tracking.ocr_profiles);
+        configure_ocr_allowed_apps(settings.tracking.ocr_allowed_apps.as_deref());
+        OCR_DEBUG_EVENTS.store(settings.tracking.ocr_debug_events, Ordering::Relaxed);
+        set_ocr_interval(settings.tracking.ocr_interval);
+        configure_adaptive_interval(
+            settings.tracking.adaptive_ocr,
+            settings.tracking.ocr_min_interval,
+            settings.tracking.ocr_max_interval,
+        );
+    });
     tauri::async_runtime::spawn(async move {
+        let data_dir = app_handle.path().app_data_dir().ok();
+
         // Wait a bit on startup before first capture
-        println!("[OCR] ⏳ Screen capture service waiting 15s before first capture...");
+        log::info!("Screen capture waiting 15s before first capture");
         tokio::time::sleep(Duration::from_secs(15)).await;
         
-        println!("[OCR] ✅ Screen capture + OCR service started (every 10s)");
+        log::info!(
+            "Screen capture + OCR started (every {}s)",
+            OCR_INTERVAL_SECS.load(Ordering::Relaxed)
+        );
+        service_status::mark_started(SCREEN_CAPTURE);
         
         let mut capture_count: u32 = 0;
         let mut last_image: Option<RgbaImage> = None;
         
         loop {
-            if CAPTURE_ENABLED.load(Ordering::Relaxed) {
+            if RESET_DIFF_BASE.swap(false, Ordering::Relaxed) {
+                last_image = None;
+            }
+            let next_interval = adaptive_interval();
+            if CAPTURE_ENABLED.load(Ordering::Relaxed)
+                && !crate::services::tracking_pause::is_paused()
+                && next_interval.is_some()
+            {
                 capture_count += 1;
                 let count = capture_count;
                 
                 // clone last_image for the blocking task
                 let prev_img = last_image.clone();
+                let thumbnail_root = if SAVE_THUMBNAILS.load(Ordering::Relaxed) {
+                    data_dir.clone()
+                } else {
+                    None
+                };
                 
                 // Run capture + OCR in a blocking task so it doesn't block the async runtime
+                let started = Instant::now();
+                let pipeline_handle = app_handle.clone();
                 let result = tokio::task::spawn_blocking(move || {
-                    capture_and_ocr_pipeline(count, prev_img)
+                    capture_and_ocr_pipeline(&pipeline_handle, count, prev_img, thumbnail_root)
                 }).await;
 
                 match result {
-                    Ok(Ok((text, new_img))) => {
+                    Ok(Ok(outcome)) => {
+                        service_status::heartbeat(SCREEN_CAPTURE);
+                        if let Some(entry) = outcome.thumbnail {
+                            if let Ok(mut store) = thumbnail_store().lock() {
+                                *store = entry;
+                            }
+                        }
+
                         // Update last image for diffing next time
-                        if let Some(img) = new_img {
+                        if let Some(img) = outcome.image {
                              last_image = Some(img);
                         }
                         
+                        // A skipped app must not inherit the previous window's text.
+                        if outcome.ocr_mode == OcrMode::Skip {
+                            if let Ok(mut store) = screen_text_store().lock() {
+                                *store = None;
+                            }
+                        }
+
+                        let mut status = outcome.status;
                         // Store text if we got some
-                        if let Some(extracted_text) = text {
-                            if !extracted_text.trim().is_empty() {
+                        if let Some(mut reading) = outcome.text {
+                            if !reading.text.trim().is_empty() {
+                                check_ocr_alerts(&app_handle, &reading.text, &outcome.app_name);
                                 // Truncate to avoid bloating metadata payloads.
-                                let truncated = truncate_at_char_boundary(&extracted_text, MAX_OCR_CHARS);
+                                reading.text = truncate_at_char_boundary(&reading.text, MAX_OCR_CHARS);
+                                status.chars = reading.text.chars().count();
                                 if let Ok(mut store) = screen_text_store().lock() {
-                                    *store = truncated;
+                                    *store = Some(reading);
                                 }
                             }
                         }
+                        status.elapsed_ms = started.elapsed().as_millis() as u64;
+                        emit_ocr_status(&app_handle, status);
                     },
                     Ok(Err(e)) => {
-                        println!("[OCR] ❌ Pipeline error: {}", e);
+                        log::error!("Pipeline error: {}", e);
+                        service_status::report_error(SCREEN_CAPTURE, &e);
+                        let mut status = OcrStatus::new(OcrStage::Failed, count, "", started);
+                        status.error = Some(e);
+                        emit_ocr_status(&app_handle, status);
                     },
                     Err(e) => {
-                        println!("[OCR] ❌ spawn_blocking task failed: {:?}", e);
+                        log::error!("Capture task failed: {:?}", e);
+                        service_status::report_error(SCREEN_CAPTURE, format!("{:?}", e));
+                        let mut status = OcrStatus::new(OcrStage::Failed, count, "", started);
+                        status.error = Some(format!("{:?}", e));
+                        emit_ocr_status(&app_handle, status);
                     }
                 }
             } else {
-                println!("[OCR] ⏸️ Capture disabled, skipping");
+                log::trace!("Capture disabled or paused for fullscreen, skipping");
+                service_status::heartbeat(SCREEN_CAPTURE);
             }
             
-            tokio::time::sleep(Duration::from_secs(10)).await;
+            // Read every iteration so a changed interval or focused window applies from the next capture.
+            tokio::time::sleep(Duration::from_secs(next_interval.unwrap_or(FULLSCREEN_RECHECK_SECS))).await;
         }
     });
 }
 
+/// Send `status` as `ocr://status`; in-progress stages only when debug events are on.
+fn emit_ocr_status(app_handle: &AppHandle, status: OcrStatus) {
+    if status.stage.is_verbose() {
+        if OCR_DEBUG_EVENTS.load(Ordering::Relaxed) {
+            let _ = app_handle.emit("ocr://status", &status);
+        }
+        return;
+    }
+    if let Ok(mut summary) = ocr_status_store().lock() {
+        if status.stage == OcrStage::Stored {
+            summary.last_stored = Some(status.clone());
+        }
+        summary.latest = Some(status.clone());
+    }
+    let _ = app_handle.emit("ocr://status", &status);
+}
+
+/// Latest capture outcome and latest stored reading, so a status indicator
+/// doesn't have to wait for the next capture.
+pub fn ocr_status() -> OcrStatusSummary {
+    ocr_status_store().lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+pub fn set_ocr_interval(seconds: u64) {
+    OCR_INTERVAL_SECS.store(seconds.clamp(MIN_OCR_INTERVAL_SECS, MAX_OCR_INTERVAL_SECS), Ordering::Relaxed);
+}
+
+pub fn configure_adaptive_interval(enabled: bool, min_secs: u64, max_secs: u64) {
+    let min_secs = min_secs.clamp(MIN_OCR_INTERVAL_SECS, MAX_OCR_INTERVAL_SECS);
+    ADAPTIVE_OCR.store(enabled, Ordering::Relaxed);
+    ADAPTIVE_MIN_SECS.store(min_secs, Ordering::Relaxed);
+    ADAPTIVE_MAX_SECS.store(max_secs.clamp(min_secs, MAX_OCR_INTERVAL_SECS), Ordering::Relaxed);
+}
+
+/// Seconds until the next capture for the window the tracker last saw, or
+/// `None` to hold off while fullscreen video or a game is in front. Chats and
+/// pages change fastest, so they get half the base interval; code is already
+/// covered by the file monitor, so it gets three times as much.
+fn adaptive_interval() -> Option<u64> {
+    let base = OCR_INTERVAL_SECS.load(Ordering::Relaxed);
+    if !ADAPTIVE_OCR.load(Ordering::Relaxed) {
+        return Some(base);
+    }
+    let Some((category_id, fullscreen)) = crate::services::activity_tracker::foreground() else {
+        return Some(base);
+    };
+    scaled_interval(
+        &crate::utils::categories::name_for(category_id as i64),
+        fullscreen,
+        base,
+        ADAPTIVE_MIN_SECS.load(Ordering::Relaxed),
+        ADAPTIVE_MAX_SECS.load(Ordering::Relaxed),
+    )
+}
+
+/// `base` scaled for the named category and kept within `min..=max`.
+fn scaled_interval(category: &str, fullscreen: bool, base: u64, min: u64, max: u64) -> Option<u64> {
+    let scaled = match category.to_lowercase().as_str() {
+        "entertainment" | "other" if fullscreen => return None,
+        "communication" | "browser" => base / 2,
+        "development" => base * 3,
+        _ => base,
+    };
+    Some(scaled.clamp(min, max))
+}
+
 pub fn set_capture_enabled(enabled: bool) {
     CAPTURE_ENABLED.store(enabled, Ordering::Relaxed);
-    println!("[OCR] Capture enabled: {}", enabled);
+    service_status::set_enabled(SCREEN_CAPTURE, enabled);
+    log::info!("Capture enabled: {}", enabled);
+}
+
+pub fn configure_min_quality(threshold: f64) {
+    let threshold = if threshold.is_finite() { threshold.clamp(0.0, 1.0) } else { DEFAULT_MIN_OCR_QUALITY };
+    if let Ok(mut store) = min_quality_store().lock() {
+        *store = threshold;
+    }
+}
+
+pub fn configure_ocr_languages(languages: &[String]) {
+    if let Ok(mut store) = ocr_languages_store().lock() {
+        let mut cleaned: Vec<String> = Vec::new();
+        for tag in languages.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            if !cleaned.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+                cleaned.push(tag.to_string());
+            }
+        }
+        *store = cleaned;
+    }
+}
+
+pub fn configure_ocr_profiles(profiles: &[OcrProfile]) {
+    if let Ok(mut store) = ocr_profiles_store().lock() {
+        *store = profiles
+            .iter()
+            .map(|p| OcrProfile {
+                app_pattern: p.app_pattern.trim().to_lowercase(),
+                mode: p.mode,
+            })
+            .filter(|p| !p.app_pattern.is_empty())
+            .collect();
+    }
+}
+
+/// OCR mode of the first profile matching `app_name`; `Full` when none does.
+fn ocr_mode_for(app_name: &str) -> OcrMode {
+    let app = app_name.to_lowercase();
+    if app.is_empty() {
+        return OcrMode::Full;
+    }
+    ocr_profiles_store()
+        .lock()
+        .ok()
+        .and_then(|profiles| {
+            profiles
+                .iter()
+                .find(|p| app.contains(p.app_pattern.as_str()))
+                .map(|p| p.mode)
+        })
+        .unwrap_or_default()
+}
+
+fn ocr_languages() -> Vec<String> {
+    ocr_languages_store()
+        .lock()
+        .map(|l| l.clone())
+        .unwrap_or_default()
+}
+
+fn min_quality() -> f64 {
+    min_quality_store()
+        .lock()
+        .map(|v| *v)
+        .unwrap_or(DEFAULT_MIN_OCR_QUALITY)
+}
+
+pub fn configure_ocr_alerts(keywords: &[String]) {
+    if let Ok(mut store) = ocr_alert_keywords_store().lock() {
+        *store = keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .filter(|k| !k.is_empty())
+            .collect();
+    }
+}
+
+/// Emit `ocr://alert` (plus a notification) for each configured keyword found in
+/// freshly extracted text. A keyword fires at most once per cooldown, and never
+/// twice in a row for the same snippet.
+fn check_ocr_alerts(app_handle: &AppHandle, text: &str, app_name: &str) {
+    let keywords = match ocr_alert_keywords_store().lock() {
+        Ok(store) if !store.is_empty() => store.clone(),
+        _ => return,
+    };
+    if !app_name.is_empty() && is_app_excluded(app_name) {
+        return;
+    }
+
+    let compact = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let lower = compact.to_lowercase();
+    let now = chrono::Utc::now().timestamp();
+
+    for keyword in keywords {
+        let Some(idx) = lower.find(&keyword) else { continue; };
+        let before = lower[..idx].chars().count();
+        let snippet: String = compact
+            .chars()
+            .skip(before.saturating_sub(OCR_ALERT_SNIPPET_CHARS))
+            .take(OCR_ALERT_SNIPPET_CHARS * 2 + keyword.chars().count())
+            .collect();
+        let snippet = snippet.trim().to_string();
+
+        {
+            let Ok(mut history) = ocr_alert_history_store().lock() else { continue; };
+            if let Some((last_ts, last_snippet)) = history.get(&keyword) {
+                if *last_snippet == snippet || now - last_ts < OCR_ALERT_COOLDOWN_SECS {
+                    continue;
+                }
+            }
+            history.insert(keyword.clone(), (now, snippet.clone()));
+        }
+
+        log::info!("Alert keyword '{}' seen in {}", keyword, app_name);
+        let alert = OcrAlert {
+            keyword: keyword.clone(),
+            snippet: snippet.clone(),
+            app_name: app_name.to_string(),
+            timestamp: now,
+        };
+        let _ = app_handle.emit("ocr://alert", &alert);
+        let source = if app_name.is_empty() { "screen" } else { app_name };
+        crate::services::notifier::notify(
+            app_handle,
+            "ocr_alert",
+            &format!("\"{}\" on {}", keyword, source),
+            &snippet,
+        );
+    }
 }
 
 // ─── Capture Pipeline ───
 
-fn capture_and_ocr_pipeline(count: u32, prev_image: Option<RgbaImage>) -> Result<(Option<String>, Option<RgbaImage>), String> {
-    println!("\n[OCR] ── Capture #{} ──────────────────────", count);
+struct CaptureOutcome {
+    /// Fresh OCR reading; `None` when the screen was unchanged.
+    text: Option<OcrReading>,
+    image: Option<RgbaImage>,
+    /// `Some` when the thumbnail state changed: `Some(None)` clears it
+    /// (excluded app), `Some(Some((app, path)))` records a freshly written thumbnail.
+    thumbnail: Option<Option<(String, String)>>,
+    app_name: String,
+    ocr_mode: OcrMode,
+    /// Outcome reported as `ocr://status`; the capture loop fills in the final timing.
+    status: OcrStatus,
+}
+
+fn capture_and_ocr_pipeline(
+    app_handle: &AppHandle,
+    count: u32,
+    prev_image: Option<RgbaImage>,
+    thumbnail_root: Option<PathBuf>,
+) -> Result<CaptureOutcome, String> {
+    log::debug!("Capture #{}", count);
     let start = Instant::now();
+    emit_ocr_status(app_handle, OcrStatus::new(OcrStage::Capturing, count, "", start));
+
+    let (app_name, processed_image) = capture_active_window_image()?;
+    // Apps outside the allowlist go down the `skip` path so they don't keep the previous text.
+    let ocr_allowed = is_ocr_allowed(&app_name);
+    let ocr_mode = if ocr_allowed { ocr_mode_for(&app_name) } else { OcrMode::Skip };
+
+    // 2. Diffing
+    if let Some(ref prev) = prev_image {
+        if is_visually_similar(prev, &processed_image) {
+            log::debug!("Screen unchanged, skipping OCR");
+            return Ok(CaptureOutcome {
+                text: None,
+                image: Some(processed_image),
+                thumbnail: None,
+                status: OcrStatus::new(OcrStage::DiffingSkipped, count, &app_name, start),
+                app_name,
+                ocr_mode,
+            });
+        }
+    }
 
+    let thumbnail = thumbnail_root.map(|root| {
+        if app_name.is_empty() || is_app_excluded(&app_name) {
+            return None;
+        }
+        match save_thumbnail(&root, &processed_image) {
+            Ok(relative) => Some((app_name.clone(), relative)),
+            Err(e) => {
+                log::warn!("Thumbnail save failed: {}", e);
+                None
+            }
+        }
+    });
+
+    if ocr_mode == OcrMode::Skip {
+        let stage = if ocr_allowed {
+            log::debug!("OCR skipped for {} by its profile", app_name);
+            OcrStage::ProfileSkipped
+        } else {
+            log::debug!("OCR skipped for {}: excluded or not in the OCR allowlist", app_name);
+            OcrStage::AppSkipped
+        };
+        return Ok(CaptureOutcome {
+            text: None,
+            image: Some(processed_image),
+            thumbnail,
+            status: OcrStatus::new(stage, count, &app_name, start),
+            app_name,
+            ocr_mode,
+        });
+    }
+
+    // 3. OCR via temp file (Windows OCR works most reliably with StorageFile)
+    let ocr_start = Instant::now();
+    emit_ocr_status(app_handle, OcrStatus::new(OcrStage::OcrRunning, count, &app_name, start));
+    
+    let reading = run_ocr_with_variants(&processed_image, ocr_mode)?;
+    
+    let elapsed = start.elapsed();
+    log::debug!(
+        "OCR completed in {:.1}s (OCR part: {}ms), {} chars",
+        elapsed.as_secs_f64(),
+        ocr_start.elapsed().as_millis(),
+        reading.text.len()
+    );
+
+    let stage = if reading.text.trim().is_empty() {
+        OcrStage::LowQualitySkipped
+    } else {
+        OcrStage::Stored
+    };
+    let mut status = OcrStatus::new(stage, count, &app_name, start);
+    status.ocr_ms = Some(ocr_start.elapsed().as_millis() as u64);
+    status.score = Some(reading.score);
+
+    Ok(CaptureOutcome {
+        text: Some(reading),
+        image: Some(processed_image),
+        thumbnail,
+        app_name,
+        ocr_mode,
+        status,
+    })
+}
+
+/// Screenshot of the focused window (primary monitor as fallback), downscaled to at most 1080p.
+fn capture_active_window_image() -> Result<(String, RgbaImage), String> {
     // Try to get specific active window first
     let active_window_info = active_win_pos_rs::get_active_window().ok();
+    let app_name = active_window_info
+        .as_ref()
+        .map(|info| info.app_name.clone())
+        .unwrap_or_default();
     
     // Capture screenshot - use xcap types directly, convert later
     let screenshot_bytes: Vec<u8>;
@@ -148,25 +781,33 @@ fn capture_and_ocr_pipeline(count: u32, prev_image: Option<RgbaImage>) -> Result
         screenshot
     };
 
-    // 2. Diffing
-    if let Some(ref prev) = prev_image {
-        if is_visually_similar(prev, &processed_image) {
-            println!("[OCR] ⏭️ Screen unchanged, skipping OCR");
-            return Ok((None, Some(processed_image)));
-        }
+    Ok((app_name, processed_image))
+}
+
+/// Write a small WEBP thumbnail under `thumbnails/<date>/` and return its relative path.
+/// Day folders keep retention cleanup a directory delete.
+fn save_thumbnail(data_dir: &std::path::Path, image: &RgbaImage) -> Result<String, String> {
+    let (w, h) = image.dimensions();
+    if w == 0 || h == 0 {
+        return Err("empty frame".to_string());
     }
+    let thumb_w = THUMBNAIL_WIDTH.min(w);
+    let thumb_h = ((h as f64) * (thumb_w as f64 / w as f64)).round().max(1.0) as u32;
+    let thumb = image::imageops::resize(image, thumb_w, thumb_h, image::imageops::FilterType::Triangle);
 
-    // 3. OCR via temp file (Windows OCR works most reliably with StorageFile)
-    println!("[OCR] 🔍 Running Windows OCR...");
-    let ocr_start = Instant::now();
-    
-    let text = run_ocr_with_variants(&processed_image)?;
-    
-    let elapsed = start.elapsed();
-    println!("[OCR] ✅ OCR completed in {:.1}s (OCR part: {}ms). Found {} chars.", 
-        elapsed.as_secs_f64(), ocr_start.elapsed().as_millis(), text.len());
+    let now = chrono::Local::now();
+    let relative = PathBuf::from(THUMBNAIL_DIR)
+        .join(now.format("%Y-%m-%d").to_string())
+        .join(format!("{}.webp", now.timestamp_millis()));
+    let absolute = data_dir.join(&relative);
+    if let Some(parent) = absolute.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    DynamicImage::ImageRgba8(thumb)
+        .save_with_format(&absolute, image::ImageFormat::WebP)
+        .map_err(|e| e.to_string())?;
 
-    Ok((Some(text), Some(processed_image)))
+    Ok(relative.to_string_lossy().replace('\\', "/"))
 }
 
 /// Capture primary monitor and return (width, height, raw_bytes)
@@ -213,47 +854,106 @@ fn is_visually_similar(img1: &RgbaImage, img2: &RgbaImage) -> bool {
     avg_diff < 15.0
 }
 
-fn run_ocr_with_variants(img: &RgbaImage) -> Result<String, String> {
-    // Two OCR variants: original + high-contrast binarized image.
+/// OCR the original frame and a high-contrast binarized copy, once per
+/// configured language (or once with the profile languages).
+fn ocr_variants(img: &RgbaImage, mode: OcrMode) -> Result<Vec<OcrVariantReport>, String> {
     let variants: [(&str, RgbaImage); 2] = [
         ("original", img.clone()),
         ("contrast", preprocess_for_text(img)),
     ];
+    let languages = ocr_languages();
 
-    let mut best_text = String::new();
-    let mut best_score = f64::MIN;
-
+    let mut reports = Vec::with_capacity(variants.len() * languages.len().max(1));
     for (name, variant) in variants {
         let temp_path = std::env::temp_dir().join(format!("intentflow_ocr_{}.png", name));
         variant.save(&temp_path).map_err(|e| format!("Save temp {}: {}", name, e))?;
-        let raw_text = run_windows_ocr(&temp_path)?;
+        let passes = run_windows_ocr(&temp_path, &languages);
         let _ = std::fs::remove_file(&temp_path);
 
-        let cleaned = clean_ocr_text(&raw_text);
-        let score = score_ocr_text(&cleaned);
-        println!(
-            "[OCR] variant={} raw_chars={} cleaned_chars={} score={:.3}",
-            name,
-            raw_text.len(),
-            cleaned.len(),
-            score
-        );
-
-        if score > best_score {
-            best_score = score;
-            best_text = cleaned;
+        for (language, raw_text) in passes? {
+            let cleaned_text = clean_ocr_text(&raw_text, mode);
+            let score = score_ocr_text(&cleaned_text);
+            log::debug!(
+                "variant={} lang={} raw_chars={} cleaned_chars={} score={:.3}",
+                name,
+                language,
+                raw_text.len(),
+                cleaned_text.len(),
+                score
+            );
+            reports.push(OcrVariantReport {
+                variant: name.to_string(),
+                language,
+                raw_text,
+                cleaned_text,
+                score,
+            });
         }
     }
+    Ok(reports)
+}
 
-    if best_score < MIN_OCR_QUALITY_SCORE {
-        println!(
-            "[OCR] ⚠️ Low-quality OCR skipped (score={:.3}, threshold={:.3})",
-            best_score, MIN_OCR_QUALITY_SCORE
-        );
-        return Ok(String::new());
+/// Index of the best-scoring variant, if it clears the configured threshold.
+fn pick_variant(reports: &[OcrVariantReport], threshold: f64) -> Option<usize> {
+    reports
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by(|a, b| a.1.score.total_cmp(&b.1.score))
+        .filter(|(_, r)| r.score >= threshold)
+        .map(|(idx, _)| idx)
+}
+
+fn run_ocr_with_variants(img: &RgbaImage, mode: OcrMode) -> Result<OcrReading, String> {
+    let mut reports = ocr_variants(img, mode)?;
+    let threshold = min_quality();
+
+    match pick_variant(&reports, threshold) {
+        Some(idx) => {
+            let best = reports.swap_remove(idx);
+            Ok(OcrReading {
+                text: best.cleaned_text,
+                score: best.score,
+                language: Some(best.language).filter(|l| !l.is_empty()),
+            })
+        }
+        None => {
+            let best_score = reports.iter().map(|r| r.score).fold(0.0, f64::max);
+            log::info!(
+                "Low-quality OCR skipped (score={:.3}, threshold={:.3})",
+                best_score, threshold
+            );
+            Ok(OcrReading {
+                text: String::new(),
+                score: best_score,
+                language: None,
+            })
+        }
     }
+}
 
-    Ok(best_text)
+/// One-off capture for tuning `ocr_min_quality`: runs every variant on the
+/// focused window and reports raw text, cleaned text and score without
+/// touching the shared screen text.
+pub fn test_ocr_now() -> Result<OcrTestReport, String> {
+    let (app_name, image) = capture_active_window_image()?;
+    // A skip profile would leave nothing to tune, so test those apps with full text.
+    let mode = match ocr_mode_for(&app_name) {
+        OcrMode::Skip => OcrMode::Full,
+        mode => mode,
+    };
+    let variants = ocr_variants(&image, mode)?;
+    let threshold = min_quality();
+    let accepted = pick_variant(&variants, threshold).map(|idx| &variants[idx]);
+    let accepted_variant = accepted.map(|r| r.variant.clone());
+    let accepted_language = accepted.map(|r| r.language.clone());
+    Ok(OcrTestReport {
+        app_name,
+        threshold,
+        variants,
+        accepted_variant,
+        accepted_language,
+    })
 }
 
 fn preprocess_for_text(img: &RgbaImage) -> RgbaImage {
@@ -283,7 +983,9 @@ fn truncate_at_char_boundary(text: &str, max_chars: usize) -> String {
     text.chars().take(max_chars).collect()
 }
 
-fn clean_ocr_text(text: &str) -> String {
+/// Drop empty and gibberish lines. `LinesPreserved` keeps one recognized line per
+/// output line; otherwise everything is flattened into one line.
+fn clean_ocr_text(text: &str, mode: OcrMode) -> String {
     let mut out_lines = Vec::new();
     for raw_line in text.lines() {
         let line = normalize_whitespace(raw_line);
@@ -296,7 +998,10 @@ fn clean_ocr_text(text: &str) -> String {
         out_lines.push(line);
     }
 
-    normalize_whitespace(&out_lines.join("\n"))
+    match mode {
+        OcrMode::LinesPreserved => out_lines.join("\n"),
+        _ => normalize_whitespace(&out_lines.join("\n")),
+    }
 }
 
 fn score_ocr_text(text: &str) -> f64 {
@@ -306,19 +1011,21 @@ fn score_ocr_text(text: &str) -> f64 {
     let chars: Vec<char> = text.chars().collect();
     let len = chars.len() as f64;
     let alpha = chars.iter().filter(|c| c.is_alphabetic()).count() as f64 / len;
+    // Emoji-heavy chat is real text; count emoji toward the "readable" share.
     let digits = chars.iter().filter(|c| c.is_ascii_digit()).count() as f64 / len;
     let spaces = chars.iter().filter(|c| c.is_whitespace()).count() as f64 / len;
     let symbols = chars
         .iter()
-        .filter(|c| !c.is_alphanumeric() && !c.is_whitespace() && !",.;:!?()[]{}'\"/@#&+-_".contains(**c))
+        .filter(|c| !c.is_alphanumeric() && !c.is_whitespace() && !is_expected_symbol(**c))
         .count() as f64
         / len;
+    let emoji = chars.iter().filter(|c| is_emoji(**c)).count() as f64 / len;
     let words = text.split_whitespace().count() as f64;
     let replacement = text.matches('�').count() as f64 / len;
 
     // Weighted score tuned for chat/code/web text.
     let mut score = 0.0;
-    score += (alpha * 1.2).min(0.65);
+    score += ((alpha + emoji) * 1.2).min(0.65);
     score += (spaces * 0.9).min(0.18);
     score += ((words / 20.0) * 0.25).min(0.25);
     score += (digits * 0.3).min(0.08);
@@ -328,26 +1035,22 @@ fn score_ocr_text(text: &str) -> f64 {
 }
 
 fn is_gibberish_line(line: &str) -> bool {
-    if line.len() >= 80 {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() >= 80 {
         return false;
     }
-    let chars: Vec<char> = line.chars().collect();
     if chars.is_empty() {
         return true;
     }
     let total = chars.len() as f64;
     let weird = chars
         .iter()
-        .filter(|c| !c.is_alphanumeric() && !c.is_whitespace() && !",.;:!?()[]{}'\"/@#&+-_".contains(**c))
+        .filter(|c| !c.is_alphanumeric() && !c.is_whitespace() && !is_expected_symbol(**c))
         .count() as f64
         / total;
-    let vowels = chars
-        .iter()
-        .filter(|c| "aeiouAEIOU".contains(**c))
-        .count() as f64;
-    let letters = chars.iter().filter(|c| c.is_alphabetic()).count() as f64;
-    let vowel_ratio = if letters > 0.0 { vowels / letters } else { 0.0 };
-    weird > 0.42 || (letters >= 8.0 && vowel_ratio < 0.08)
+    // Only Latin-script lines are held to a vowel ratio; other scripts skip the check.
+    let consonant_soup = latin_vowel_ratio(&chars, 8).is_some_and(|ratio| ratio < 0.08);
+    weird > 0.42 || consonant_soup
 }
 
 fn normalize_whitespace(input: &str) -> String {
@@ -362,7 +1065,11 @@ fn normalize_whitespace(input: &str) -> String {
 
 // ─── Windows OCR via StorageFile ───
 
-fn run_windows_ocr(image_path: &PathBuf) -> Result<String, String> {
+/// Recognize the image once per language tag and return `(language, text)`
+/// pairs. Tags the OCR runtime doesn't have installed are skipped; if none are
+/// usable (or none were given) the user's profile languages are used instead.
+fn run_windows_ocr(image_path: &PathBuf, languages: &[String]) -> Result<Vec<(String, String)>, String> {
+    use windows::Globalization::Language;
     use windows::Graphics::Imaging::BitmapDecoder;
     use windows::Media::Ocr::OcrEngine;
     use windows::Storage::{FileAccessMode, StorageFile};
@@ -390,17 +1097,195 @@ fn run_windows_ocr(image_path: &PathBuf) -> Result<String, String> {
         .get()
         .map_err(|e| format!("Bitmap await: {}", e))?;
     
-    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
-        .map_err(|e| format!("OcrEngine: {}", e))?;
-    
-    let result = engine.RecognizeAsync(&bitmap)
-        .map_err(|e| format!("Recognize: {}", e))?
-        .get()
-        .map_err(|e| format!("Recognize await: {}", e))?;
-    
-    let text = result.Text()
-        .map_err(|e| format!("Text: {}", e))?
-        .to_string();
-    
-    Ok(text)
+    let mut engines: Vec<OcrEngine> = Vec::new();
+    for tag in languages {
+        let engine = Language::CreateLanguage(&windows::core::HSTRING::from(tag.as_str()))
+            .ok()
+            .filter(|lang| OcrEngine::IsLanguageSupported(lang).unwrap_or(false))
+            .and_then(|lang| OcrEngine::TryCreateFromLanguage(&lang).ok());
+        match engine {
+            Some(engine) => engines.push(engine),
+            None => log::warn!("OCR language '{}' is not installed, skipping", tag),
+        }
+    }
+    if engines.is_empty() {
+        engines.push(
+            OcrEngine::TryCreateFromUserProfileLanguages()
+                .map_err(|e| format!("OcrEngine: {}", e))?,
+        );
+    }
+
+    let mut passes = Vec::with_capacity(engines.len());
+    for engine in engines {
+        let language = engine
+            .RecognizerLanguage()
+            .and_then(|lang| lang.LanguageTag())
+            .map(|tag| tag.to_string())
+            .unwrap_or_default();
+
+        let result = engine.RecognizeAsync(&bitmap)
+            .map_err(|e| format!("Recognize: {}", e))?
+            .get()
+            .map_err(|e| format!("Recognize await: {}", e))?;
+
+        // Join line by line: `OcrResult::Text` runs all lines together with spaces,
+        // which loses the message boundaries `OcrMode::LinesPreserved` keeps.
+        let lines = result.Lines().map_err(|e| format!("Lines: {}", e))?;
+        let text = lines
+            .into_iter()
+            .filter_map(|line| line.Text().ok())
+            .map(|text| text.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        passes.push((language, text));
+    }
+
+    Ok(passes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        clean_ocr_text, configure_ocr_profiles, is_gibberish_line, ocr_mode_for, pick_variant, scaled_interval,
+        score_ocr_text, OcrVariantReport, DEFAULT_MIN_OCR_QUALITY,
+    };
+    use crate::models::{default_ocr_profiles, OcrMode, OcrProfile};
+
+    #[test]
+    fn categories_scale_the_base_interval() {
+        assert_eq!(scaled_interval("Communication", false, 10, 5, 30), Some(5));
+        assert_eq!(scaled_interval("Browser", false, 10, 5, 30), Some(5));
+        assert_eq!(scaled_interval("Development", false, 10, 5, 30), Some(30));
+        assert_eq!(scaled_interval("Productivity", false, 10, 5, 30), Some(10));
+        assert_eq!(scaled_interval("Entertainment", true, 10, 5, 30), None);
+        assert_eq!(scaled_interval("Entertainment", false, 10, 5, 30), Some(10));
+    }
+
+    #[test]
+    fn adapted_intervals_stay_within_the_bounds() {
+        assert_eq!(scaled_interval("Productivity", false, 60, 5, 30), Some(30));
+        assert_eq!(scaled_interval("Development", false, 60, 5, 30), Some(30));
+        assert_eq!(scaled_interval("Browser", false, 60, 5, 30), Some(30));
+        assert_eq!(scaled_interval("Development", false, 3, 5, 30), Some(9));
+        assert_eq!(scaled_interval("Browser", false, 3, 5, 30), Some(5));
+        assert_eq!(scaled_interval("Productivity", false, 3, 5, 30), Some(5));
+    }
+
+    #[test]
+    fn clearing_drops_the_latest_reading_and_diff_base() {
+        *super::screen_text_store().lock().unwrap() = Some(super::OcrReading {
+            text: "just flashed".to_string(),
+            score: 0.9,
+            language: None,
+        });
+        *super::thumbnail_store().lock().unwrap() = Some(("App".to_string(), "thumbs/1.jpg".to_string()));
+        super::RESET_DIFF_BASE.store(false, std::sync::atomic::Ordering::Relaxed);
+
+        super::clear_latest_screen_text();
+
+        assert!(super::get_latest_screen_text().is_none());
+        assert!(super::get_latest_thumbnail_for("App").is_none());
+        assert!(super::RESET_DIFF_BASE.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    const GERMAN: &str = "Schöne Grüße aus München\nBitte prüfen Sie die Überweisung für Straßenbahn";
+    const TURKISH: &str = "Bugün akşam toplantıya katılabilir misiniz?\nŞimdi çıkıyorum, görüşürüz";
+    const EMOJI_CHAT: &str = "😂😂😂 🔥🔥 👍\nok 👍👍 see you tmrw 🎉🎉🎉";
+
+    fn report(language: &str, raw_text: &str) -> OcrVariantReport {
+        let cleaned_text = clean_ocr_text(raw_text, OcrMode::LinesPreserved);
+        OcrVariantReport {
+            variant: "original".to_string(),
+            language: language.to_string(),
+            raw_text: raw_text.to_string(),
+            score: score_ocr_text(&cleaned_text),
+            cleaned_text,
+        }
+    }
+
+    #[test]
+    fn accented_and_emoji_lines_are_not_gibberish() {
+        for fixture in [GERMAN, TURKISH, EMOJI_CHAT] {
+            for line in fixture.lines() {
+                assert!(!is_gibberish_line(line), "dropped {:?}", line);
+            }
+            assert_eq!(clean_ocr_text(fixture, OcrMode::LinesPreserved), fixture);
+            let score = score_ocr_text(&clean_ocr_text(fixture, OcrMode::Full));
+            assert!(score >= DEFAULT_MIN_OCR_QUALITY, "{:?} scored {:.3}", fixture, score);
+        }
+    }
+
+    #[test]
+    fn noise_lines_are_still_dropped() {
+        assert!(is_gibberish_line("#$^^ ¬¬ ¦¦ \\\\ ^^$$"));
+        assert!(is_gibberish_line("xkcd qrst pmnb zxcv"));
+        let mixed = "Schöne Grüße aus München\n¦¦ ^^ \\\\ ¬¬ ^$\nxkcd qrst pmnb zxcv\nŞimdi çıkıyorum 👍";
+        assert_eq!(
+            clean_ocr_text(mixed, OcrMode::LinesPreserved),
+            "Schöne Grüße aus München\nŞimdi çıkıyorum 👍"
+        );
+        assert_eq!(
+            clean_ocr_text(mixed, OcrMode::Full),
+            "Schöne Grüße aus München Şimdi çıkıyorum 👍"
+        );
+        assert_eq!(score_ocr_text(""), 0.0);
+    }
+
+    #[test]
+    fn the_best_scoring_language_wins() {
+        let reports = vec![
+            // An English-only recognizer misreads the umlauts as symbols.
+            report("en-US", "Sch¤ne Gr¦¦e aus M¬nchen\nBitte pr¤fen Sie die ¦berweisung f¬r Stra¦enbahn"),
+            report("de-DE", GERMAN),
+            report("tr-TR", "B�tte pr�fen S�e d�e"),
+        ];
+        let idx = pick_variant(&reports, DEFAULT_MIN_OCR_QUALITY).unwrap();
+        assert_eq!(reports[idx].language, "de-DE");
+        // Nothing clears an impossible threshold, so the capture is skipped.
+        assert_eq!(pick_variant(&reports, 1.1), None);
+        assert_eq!(pick_variant(&[], 0.0), None);
+    }
+
+    /// Raw recognizer output for a WhatsApp window: one OCR line per row, with
+    /// ragged spacing and a line of UI noise.
+    const CHAT_DUMP: &str = "Anna   Schmidt\n  Are we still on for dinner tonight?   19:02\n\n¦¦ ^^ ¬¬\nYes!  Table is booked for 8   19:04\nCan you bring the charger 🔌  19:05\n";
+
+    #[test]
+    fn lines_preserved_keeps_chat_messages_apart() {
+        let cleaned = clean_ocr_text(CHAT_DUMP, OcrMode::LinesPreserved);
+        assert_eq!(
+            cleaned.lines().collect::<Vec<_>>(),
+            vec![
+                "Anna Schmidt",
+                "Are we still on for dinner tonight? 19:02",
+                "Yes! Table is booked for 8 19:04",
+                "Can you bring the charger 🔌 19:05",
+            ]
+        );
+        assert_eq!(
+            clean_ocr_text(CHAT_DUMP, OcrMode::Full),
+            "Anna Schmidt Are we still on for dinner tonight? 19:02 Yes! Table is booked for 8 19:04 Can you bring the charger 🔌 19:05"
+        );
+    }
+
+    #[test]
+    fn profiles_pick_the_mode_by_app_pattern() {
+        configure_ocr_profiles(&default_ocr_profiles());
+        assert_eq!(ocr_mode_for("WhatsApp"), OcrMode::LinesPreserved);
+        assert_eq!(ocr_mode_for("Telegram Desktop"), OcrMode::LinesPreserved);
+        assert_eq!(ocr_mode_for("Visual Studio Code"), OcrMode::Skip);
+        assert_eq!(ocr_mode_for("Windows Terminal"), OcrMode::Skip);
+        assert_eq!(ocr_mode_for("Google Chrome"), OcrMode::Full);
+        assert_eq!(ocr_mode_for(""), OcrMode::Full);
+
+        // First match wins; blank patterns are ignored rather than matching everything.
+        configure_ocr_profiles(&[
+            OcrProfile { app_pattern: "  ".to_string(), mode: OcrMode::Skip },
+            OcrProfile { app_pattern: " Slack ".to_string(), mode: OcrMode::Full },
+            OcrProfile { app_pattern: "slack".to_string(), mode: OcrMode::Skip },
+        ]);
+        assert_eq!(ocr_mode_for("Slack"), OcrMode::Full);
+        assert_eq!(ocr_mode_for("Notepad"), OcrMode::Full);
+        configure_ocr_profiles(&default_ocr_profiles());
+    }
 }
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+// ─── Service heartbeats ───
+// Background services report ticks and errors here so the UI can show
+// whether tracking/capture are actually running instead of relying on logs.
+
+pub const ACTIVITY_TRACKER: &str = "activity_tracker";
+pub const SCREEN_CAPTURE: &str = "screen_capture";
+pub const FILE_MONITOR: &str = "file_monitor";
+pub const PATTERN_ENGINE: &str = "pattern_engine";
+pub const DASHBOARD_ENGINE: &str = "dashboard_engine";
+pub const CLIPBOARD_MONITOR: &str = "clipboard_monitor";
+pub const GLOBAL_HOTKEYS: &str = "global_hotkeys";
+pub const PROCESS_MONITOR: &str = "process_monitor";
+
+const ALL_SERVICES: [&str; 8] = [
+    ACTIVITY_TRACKER,
+    SCREEN_CAPTURE,
+    FILE_MONITOR,
+    PATTERN_ENGINE,
+    DASHBOARD_ENGINE,
+    CLIPBOARD_MONITOR,
+    GLOBAL_HOTKEYS,
+    PROCESS_MONITOR,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ServiceStatus {
+    pub name: String,
+    pub running: bool,
+    pub enabled: bool,
+    pub started_at: Option<i64>,
+    pub uptime_seconds: Option<i64>,
+    pub last_tick: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+}
+
+fn status_store() -> &'static RwLock<HashMap<&'static str, ServiceStatus>> {
+    static STORE: OnceLock<RwLock<HashMap<&'static str, ServiceStatus>>> = OnceLock::new();
+    STORE.get_or_init(|| {
+        let map = ALL_SERVICES
+            .iter()
+            .map(|name| {
+                (
+                    *name,
+                    ServiceStatus {
+                        name: name.to_string(),
+                        enabled: true,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        RwLock::new(map)
+    })
+}
+
+fn update(service: &'static str, apply: impl FnOnce(&mut ServiceStatus)) {
+    if let Ok(mut store) = status_store().write() {
+        let entry = store.entry(service).or_insert_with(|| ServiceStatus {
+            name: service.to_string(),
+            enabled: true,
+            ..Default::default()
+        });
+        apply(entry);
+    }
+}
+
+pub fn mark_started(service: &'static str) {
+    let now = chrono::Utc::now().timestamp();
+    update(service, |s| {
+        s.running = true;
+        s.started_at = Some(now);
+    });
+}
+
+pub fn set_enabled(service: &'static str, enabled: bool) {
+    update(service, |s| s.enabled = enabled);
+}
+
+/// Record a successful loop iteration.
+pub fn heartbeat(service: &'static str) {
+    let now = chrono::Utc::now().timestamp();
+    update(service, |s| s.last_tick = Some(now));
+}
+
+pub fn report_error(service: &'static str, error: impl ToString) {
+    let now = chrono::Utc::now().timestamp();
+    let message = error.to_string();
+    update(service, |s| {
+        s.last_error = Some(message);
+        s.last_error_at = Some(now);
+    });
+}
+
+pub fn snapshot() -> Vec<ServiceStatus> {
+    let now = chrono::Utc::now().timestamp();
+    let Ok(store) = status_store().read() else {
+        return Vec::new();
+    };
+    ALL_SERVICES
+        .iter()
+        .filter_map(|name| store.get(name).cloned())
+        .map(|mut s| {
+            s.uptime_seconds = if s.running {
+                s.started_at.map(|started| (now - started).max(0))
+            } else {
+                None
+            };
+            s
+        })
+        .collect()
+}
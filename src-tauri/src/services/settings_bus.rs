@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::watch;
+
+use crate::models::Settings;
+
+// ─── Settings bus ───
+// `update_settings` publishes here after writing settings.json; services
+// subscribe once at startup and apply the fields they care about, so
+// changes take effect without restarting the app. Commands read `current`
+// instead of re-reading the file.
+
+/// A burst of saves (a slider being dragged, several toggles in a row) is
+/// broadcast once, this long after the last one.
+const BROADCAST_DEBOUNCE_MS: u64 = 300;
+
+#[derive(Debug, Clone)]
+pub struct SettingsUpdate {
+    pub revision: u64,
+    pub settings: Settings,
+}
+
+pub struct SettingsBus {
+    sender: watch::Sender<SettingsUpdate>,
+    /// Updated as soon as settings are saved, ahead of the debounced broadcast.
+    latest: RwLock<Settings>,
+    /// Bumped by every `publish`; a pending broadcast only fires if it is still the newest.
+    pending: AtomicU64,
+}
+
+/// Last revision each subscriber applied; lets the UI (or a developer)
+/// confirm that an update actually reached every service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsPropagation {
+    pub service: String,
+    pub revision: u64,
+    pub applied_at: i64,
+    pub updates_received: u64,
+}
+
+fn propagation_store() -> &'static Mutex<HashMap<String, SettingsPropagation>> {
+    static STORE: OnceLock<Mutex<HashMap<String, SettingsPropagation>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Register the bus as managed state. Must run before any service subscribes.
+pub fn init(app_handle: &AppHandle, mut initial: Settings) {
+    crate::utils::config::apply_env_defaults(&mut initial);
+    let (sender, _) = watch::channel(SettingsUpdate {
+        revision: 0,
+        settings: initial.clone(),
+    });
+    app_handle.manage(SettingsBus {
+        sender,
+        latest: RwLock::new(initial),
+        pending: AtomicU64::new(0),
+    });
+}
+
+/// Latest saved settings, if the bus is running. Includes a save whose broadcast is still pending.
+pub fn current(app_handle: &AppHandle) -> Option<Settings> {
+    let bus = app_handle.try_state::<SettingsBus>()?;
+    let settings = bus.latest.read().ok()?.clone();
+    Some(settings)
+}
+
+pub fn subscribe(app_handle: &AppHandle) -> Option<watch::Receiver<SettingsUpdate>> {
+    let bus = app_handle.try_state::<SettingsBus>()?;
+    Some(bus.sender.subscribe())
+}
+
+/// Broadcast new settings to every subscriber and the frontend (as `settings://changed`),
+/// debounced so a burst of saves reaches services once.
+/// Env defaults are layered on top so an empty API key still resolves from `NVIDIA_API_KEY`.
+pub fn publish(app_handle: &AppHandle, mut settings: Settings) {
+    crate::utils::config::apply_env_defaults(&mut settings);
+    let Some(bus) = app_handle.try_state::<SettingsBus>() else {
+        println!("[Settings] Bus not initialized, update not broadcast");
+        return;
+    };
+    if let Ok(mut latest) = bus.latest.write() {
+        *latest = settings;
+    }
+    let ticket = bus.pending.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(BROADCAST_DEBOUNCE_MS)).await;
+        let Some(bus) = app_handle.try_state::<SettingsBus>() else {
+            return;
+        };
+        if bus.pending.load(Ordering::SeqCst) != ticket {
+            return; // a newer save will broadcast
+        }
+        let Some(settings) = bus.latest.read().ok().map(|s| s.clone()) else {
+            return;
+        };
+        let revision = bus.sender.borrow().revision + 1;
+        bus.sender.send_replace(SettingsUpdate {
+            revision,
+            settings: settings.clone(),
+        });
+        println!("[Settings] Broadcast revision {}", revision);
+        // Like `get_settings`, the webview never sees the unlock password hash.
+        let mut settings = settings;
+        settings.privacy.unlock_password_hash = None;
+        let _ = app_handle.emit("settings://changed", &settings);
+    });
+}
+
+/// Apply the current settings with `apply`, then again on every update.
+/// Runs until the bus is dropped.
+pub fn spawn_subscriber<F>(app_handle: &AppHandle, service: &'static str, apply: F)
+where
+    F: Fn(&Settings) + Send + 'static,
+{
+    let Some(mut receiver) = subscribe(app_handle) else {
+        println!("[Settings] Bus not initialized, {} will not receive updates", service);
+        return;
+    };
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let update = receiver.borrow_and_update().clone();
+            apply(&update.settings);
+            record_applied(service, update.revision);
+            if receiver.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn record_applied(service: &str, revision: u64) {
+    if let Ok(mut store) = propagation_store().lock() {
+        let entry = store
+            .entry(service.to_string())
+            .or_insert_with(|| SettingsPropagation {
+                service: service.to_string(),
+                revision,
+                applied_at: 0,
+                updates_received: 0,
+            });
+        entry.revision = revision;
+        entry.applied_at = chrono::Utc::now().timestamp();
+        entry.updates_received += 1;
+    }
+}
+
+pub fn propagation_status() -> Vec<SettingsPropagation> {
+    let mut status: Vec<SettingsPropagation> = propagation_store()
+        .lock()
+        .map(|store| store.values().cloned().collect())
+        .unwrap_or_default();
+    status.sort_by(|a, b| a.service.cmp(&b.service));
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagation_records_each_applied_revision() {
+        record_applied("test_harness", 0);
+        record_applied("test_harness", 3);
+        let entry = propagation_status()
+            .into_iter()
+            .find(|p| p.service == "test_harness")
+            .expect("harness recorded");
+        assert_eq!(entry.revision, 3);
+        assert_eq!(entry.updates_received, 2);
+    }
+
+    #[test]
+    fn subscribers_see_only_the_latest_update() {
+        let (sender, mut receiver) = watch::channel(SettingsUpdate {
+            revision: 0,
+            settings: Settings::default(),
+        });
+        for revision in 1..=3 {
+            let mut settings = Settings::default();
+            settings.tracking.tracking_interval = revision;
+            sender.send_replace(SettingsUpdate { revision, settings });
+        }
+        assert!(receiver.has_changed().unwrap());
+        let update = receiver.borrow_and_update().clone();
+        assert_eq!(update.revision, 3);
+        assert_eq!(update.settings.tracking.tracking_interval, 3);
+        assert!(!receiver.has_changed().unwrap());
+    }
+}
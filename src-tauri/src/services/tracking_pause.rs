@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use rusqlite::Connection;
+
+// ─── Timed pause ───
+// "Pause for N minutes" from the tray or UI. The deadline is kept in the
+// `settings` table so a restart mid-pause doesn't resume tracking early.
+
+const PAUSED_UNTIL_KEY: &str = "paused_until";
+/// Longest pause accepted, in minutes.
+pub const MAX_PAUSE_MINUTES: u32 = 24 * 60;
+
+/// Unix seconds tracking stays paused until; 0 when not paused.
+static PAUSED_UNTIL: AtomicI64 = AtomicI64::new(0);
+
+/// Restore a pause stored before the last shutdown.
+pub fn load(conn: &Connection) {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT CAST(value AS TEXT) FROM settings WHERE key = ?1",
+            [PAUSED_UNTIL_KEY],
+            |row| row.get(0),
+        )
+        .ok();
+    let until = stored.and_then(|v| v.trim().parse::<i64>().ok()).unwrap_or(0);
+    PAUSED_UNTIL.store(until, Ordering::Relaxed);
+    if let Some(until) = paused_until() {
+        println!("[Pause] Tracking paused until {}", until);
+    }
+}
+
+/// Pause deadline, if it is still in the future.
+pub fn paused_until() -> Option<i64> {
+    let until = PAUSED_UNTIL.load(Ordering::Relaxed);
+    (until > chrono::Utc::now().timestamp()).then_some(until)
+}
+
+pub fn is_paused() -> bool {
+    paused_until().is_some()
+}
+
+/// Pause for `minutes` from now; 0 resumes immediately. Returns the new deadline.
+pub fn pause_for(conn: &Connection, minutes: u32) -> Result<Option<i64>, String> {
+    let now = chrono::Utc::now().timestamp();
+    let until = if minutes == 0 {
+        0
+    } else {
+        now + minutes.min(MAX_PAUSE_MINUTES) as i64 * 60
+    };
+    conn.execute(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        rusqlite::params![PAUSED_UNTIL_KEY, until.to_string(), now],
+    )
+    .map_err(|e| e.to_string())?;
+    PAUSED_UNTIL.store(until, Ordering::Relaxed);
+    if until > 0 {
+        println!("[Pause] Tracking paused for {} min", minutes.min(MAX_PAUSE_MINUTES));
+    } else {
+        println!("[Pause] Tracking resumed");
+    }
+    Ok(paused_until())
+}
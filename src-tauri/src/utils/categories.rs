@@ -0,0 +1,168 @@
+use std::sync::{OnceLock, RwLock};
+
+use rusqlite::Connection;
+
+use crate::models::{get_default_categories, Category};
+
+/// Fallback for unknown ids and unmatched windows; also receives activities of deleted categories.
+pub const OTHER_CATEGORY_ID: i32 = 7;
+
+/// Prompt hints for the seeded categories; custom ones are described by their rules.
+const BUILTIN_HINTS: [(i32, &str); 7] = [
+    (1, "VS Code, Antigravity/Cursor IDE, terminals, code editors"),
+    (2, "Chrome, Brave, Firefox, Edge — general web browsing"),
+    (3, "Slack, Discord, Teams, WhatsApp, email"),
+    (4, "Spotify, YouTube, Netflix, music/video streaming"),
+    (5, "Notion, Obsidian, Word, Excel, note-taking"),
+    (6, "File Explorer, Settings, Task Manager"),
+    (7, "unknown"),
+];
+
+fn category_store() -> &'static RwLock<Vec<Category>> {
+    static STORE: OnceLock<RwLock<Vec<Category>>> = OnceLock::new();
+    STORE.get_or_init(|| RwLock::new(get_default_categories()))
+}
+
+pub fn load_categories(conn: &Connection) -> rusqlite::Result<Vec<Category>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, icon, color, keywords, apps, COALESCE(is_builtin, 0)
+         FROM categories ORDER BY id"
+    )?;
+    let categories = stmt.query_map([], |row| {
+        let keywords: Option<String> = row.get(4)?;
+        let apps: Option<String> = row.get(5)?;
+
+        Ok(Category {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            icon: row.get(2)?,
+            color: row.get(3)?,
+            keywords: keywords.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+            apps: apps.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+            is_builtin: row.get::<_, i64>(6)? != 0,
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(categories)
+}
+
+/// Refresh the in-memory copy from the `categories` table.
+/// Call after startup and whenever categories are edited.
+pub fn reload(conn: &Connection) {
+    match load_categories(conn) {
+        Ok(categories) if !categories.is_empty() => {
+            println!("[Categories] Loaded {} categories", categories.len());
+            if let Ok(mut store) = category_store().write() {
+                *store = categories;
+            }
+        }
+        Ok(_) => println!("[Categories] Table is empty, keeping defaults"),
+        Err(e) => println!("[Categories] Failed to load categories: {}", e),
+    }
+}
+
+pub fn all() -> Vec<Category> {
+    category_store()
+        .read()
+        .map(|store| store.clone())
+        .unwrap_or_else(|_| get_default_categories())
+}
+
+pub fn name_for(category_id: i64) -> String {
+    category_store()
+        .read()
+        .ok()
+        .and_then(|store| {
+            store
+                .iter()
+                .find(|c| c.id as i64 == category_id)
+                .map(|c| c.name.clone())
+        })
+        .unwrap_or_else(|| "Other".to_string())
+}
+
+pub fn get(category_id: i32) -> Option<Category> {
+    category_store()
+        .read()
+        .ok()
+        .and_then(|store| store.iter().find(|c| c.id == category_id).cloned())
+}
+
+/// `category_id` if it is a loaded category, else Other.
+pub fn known_or_other(category_id: i32) -> i32 {
+    let known = category_store()
+        .read()
+        .map(|store| store.iter().any(|c| c.id == category_id))
+        .unwrap_or(false);
+    if known { category_id } else { OTHER_CATEGORY_ID }
+}
+
+/// The category's first app or keyword rule matching the window, described for
+/// `explain_categorization`.
+fn rules_match(category: &Category, app_lower: &str, title_lower: &str) -> Option<String> {
+    let app_hit = category.apps.iter().find(|rule| {
+        let rule = rule.trim().to_lowercase();
+        !rule.is_empty() && app_lower.contains(&rule)
+    });
+    if let Some(rule) = app_hit {
+        return Some(format!("app rule \"{}\" of category \"{}\"", rule.trim(), category.name));
+    }
+    category
+        .keywords
+        .iter()
+        .find(|rule| {
+            let rule = rule.trim().to_lowercase();
+            !rule.is_empty() && (app_lower.contains(&rule) || title_lower.contains(&rule))
+        })
+        .map(|rule| format!("keyword \"{}\" of category \"{}\"", rule.trim(), category.name))
+}
+
+/// First category whose stored app/keyword rules match the window, with the rule.
+/// `custom_only` restricts the search to user-defined categories, which are
+/// checked before the built-in heuristics so they can claim apps like Figma.
+pub fn match_rule(app_name: &str, title: &str, custom_only: bool) -> Option<(i32, String)> {
+    let app_lower = app_name.to_lowercase();
+    let title_lower = title.to_lowercase();
+    let store = category_store().read().ok()?;
+    store
+        .iter()
+        .filter(|c| !custom_only || !c.is_builtin)
+        .find_map(|c| rules_match(c, &app_lower, &title_lower).map(|rule| (c.id, rule)))
+}
+
+fn hint_for(category: &Category) -> String {
+    if let Some((_, hint)) = BUILTIN_HINTS.iter().find(|(id, _)| *id == category.id) {
+        return hint.to_string();
+    }
+    let rules: Vec<&str> = category
+        .apps
+        .iter()
+        .chain(category.keywords.iter())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .take(6)
+        .collect();
+    if rules.is_empty() {
+        "user-defined".to_string()
+    } else {
+        rules.join(", ")
+    }
+}
+
+/// "1 = Development | 2 = Browser | ..." for compact prompts.
+pub fn prompt_inline() -> String {
+    all()
+        .iter()
+        .map(|c| format!("{} = {}", c.id, c.name))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// One line per category with a short description of what it covers.
+pub fn prompt_described() -> String {
+    all()
+        .iter()
+        .map(|c| format!("{} = {} ({})", c.id, c.name, hint_for(c)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,3 +1,9 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
 use crate::models::Settings;
 
 const ENV_API_KEY: &str = "NVIDIA_API_KEY";
@@ -23,3 +29,260 @@ pub fn apply_env_defaults(settings: &mut Settings) {
         settings.ai.api_key = api_key_from_env().unwrap_or_default();
     }
 }
+
+// ─── Settings file ───
+// Every reader goes through `load_settings`. A file from an older or newer
+// version, or one edited by hand, still loads: unknown keys are dropped, bad
+// values fall back to their defaults and out-of-range numbers are clamped.
+// What was changed is kept for `get_settings_warnings`.
+
+static LOAD_WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn settings_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(settings_path_in(&data_dir))
+}
+
+fn settings_path_in(data_dir: &Path) -> PathBuf {
+    data_dir.join("config").join("settings.json")
+}
+
+/// Settings as the services see them: the bus once it's up, the file before that.
+pub fn load_settings(app_handle: &AppHandle) -> Settings {
+    if let Some(settings) = crate::services::settings_bus::current(app_handle) {
+        return settings;
+    }
+    read_settings_file(app_handle)
+}
+
+/// Read settings.json leniently. When it doesn't parse as-is, the original is
+/// kept as settings.json.bak and the repaired settings are written in its place.
+pub fn read_settings_file(app_handle: &AppHandle) -> Settings {
+    let (mut settings, mut warnings) = match settings_path(app_handle) {
+        Ok(path) => {
+            let (settings, warnings, repaired) = read_settings_at(&path);
+            if repaired {
+                crate::services::notifier::notify(
+                    app_handle,
+                    "settings_repaired",
+                    "Settings repaired",
+                    &format!(
+                        "settings.json had {} problem(s) and was repaired; the original is saved as settings.json.bak.",
+                        warnings.len().max(1)
+                    ),
+                );
+            }
+            (settings, warnings)
+        }
+        Err(_) => (Settings::default(), Vec::new()),
+    };
+    warnings.extend(settings.sanitize());
+    for warning in &warnings {
+        log::warn!("[Settings] {}", warning);
+    }
+    *LOAD_WARNINGS.lock().unwrap() = warnings;
+    apply_env_defaults(&mut settings);
+    settings
+}
+
+/// Problems found the last time settings.json was read.
+pub fn settings_warnings() -> Vec<String> {
+    LOAD_WARNINGS.lock().unwrap().clone()
+}
+
+/// A save rewrites the file from validated settings, so earlier findings no longer apply.
+pub fn clear_settings_warnings() {
+    LOAD_WARNINGS.lock().unwrap().clear();
+}
+
+pub fn save_settings_file(app_handle: &AppHandle, settings: &Settings) -> Result<(), String> {
+    write_settings_at(&settings_path(app_handle)?, settings)
+}
+
+fn write_settings_at(path: &Path, settings: &Settings) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Parse the file at `path`, repairing it when it doesn't load as-is. Returns
+/// the settings, the parse warnings and whether the file was repaired.
+fn read_settings_at(path: &Path) -> (Settings, Vec<String>, bool) {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return (Settings::default(), Vec::new(), false);
+    };
+    let (settings, warnings) = parse_settings(&content);
+    let repaired = serde_json::from_str::<Settings>(&content).is_err() && repair_settings_file(path, &content, &settings);
+    (settings, warnings, repaired)
+}
+
+fn repair_settings_file(path: &Path, original: &str, settings: &Settings) -> bool {
+    let backup = path.with_extension("json.bak");
+    if let Err(e) = std::fs::write(&backup, original) {
+        log::warn!("[Settings] Could not back up settings.json: {}", e);
+        return false;
+    }
+    if let Err(e) = write_settings_at(path, settings) {
+        log::warn!("[Settings] Could not write repaired settings: {}", e);
+        return false;
+    }
+    true
+}
+
+/// Parse settings, keeping every valid value from `content` and reporting the rest.
+pub fn parse_settings(content: &str) -> (Settings, Vec<String>) {
+    let user: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => return (Settings::default(), vec![format!("settings.json is not valid JSON ({}), using defaults", e)]),
+    };
+    let Value::Object(user) = user else {
+        return (Settings::default(), vec!["settings.json is not a JSON object, using defaults".to_string()]);
+    };
+
+    let mut merged = serde_json::to_value(Settings::default()).unwrap_or(Value::Null);
+    let mut warnings = Vec::new();
+    overlay(&mut merged, "", user, &mut warnings);
+    let settings = serde_json::from_value(merged).unwrap_or_default();
+    (settings, warnings)
+}
+
+/// Copy `user` onto the object at `pointer` in `merged` one key at a time,
+/// backing out any key that stops the whole thing from deserializing.
+fn overlay(merged: &mut Value, pointer: &str, user: Map<String, Value>, warnings: &mut Vec<String>) {
+    for (key, value) in user {
+        let child = format!("{}/{}", pointer, key);
+        let name = child.trim_start_matches('/').replace('/', ".");
+        let current = merged.pointer(&child).cloned();
+
+        // Recurse into sections; empty objects are free-form maps and taken whole.
+        if let (Some(Value::Object(defaults)), Value::Object(nested)) = (&current, &value) {
+            if !defaults.is_empty() {
+                overlay(merged, &child, nested.clone(), warnings);
+                continue;
+            }
+        }
+
+        let Some(Value::Object(parent)) = merged.pointer_mut(pointer) else { continue };
+        parent.insert(key.clone(), value);
+        match serde_json::from_value::<Settings>(merged.clone()) {
+            Ok(_) if current.is_some() => {}
+            // Keys the defaults leave out (optional fields) survive a round trip; unknown ones don't.
+            Ok(parsed) => {
+                let known = serde_json::to_value(parsed).ok().and_then(|v| v.pointer(&child).cloned()).is_some();
+                if !known {
+                    warnings.push(format!("Unknown setting {} was ignored", name));
+                    restore(merged, pointer, &key, None);
+                }
+            }
+            Err(_) => {
+                warnings.push(format!("Invalid value for {}, using the default", name));
+                restore(merged, pointer, &key, current);
+            }
+        }
+    }
+}
+
+fn restore(merged: &mut Value, pointer: &str, key: &str, previous: Option<Value>) {
+    if let Some(Value::Object(parent)) = merged.pointer_mut(pointer) {
+        match previous {
+            Some(value) => parent.insert(key.to_string(), value),
+            None => parent.remove(key),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir;
+
+    #[test]
+    fn saved_settings_are_what_the_next_read_loads() {
+        let dir = test_dir("config-roundtrip");
+        // The old query path read settings.json at the data dir root instead.
+        std::fs::write(dir.join("settings.json"), r#"{"ai": {"model": "stale-model"}}"#).unwrap();
+
+        let mut settings = Settings::default();
+        settings.ai.model = "saved-model".to_string();
+        settings.tracking.tracking_interval = 7;
+        let path = settings_path_in(&dir);
+        assert_eq!(path, dir.join("config").join("settings.json"));
+        write_settings_at(&path, &settings).unwrap();
+
+        let (loaded, warnings, repaired) = read_settings_at(&path);
+        assert_eq!(loaded.ai.model, "saved-model");
+        assert_eq!(loaded.tracking.tracking_interval, 7);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert!(!repaired);
+        assert!(!path.with_extension("json.bak").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn partial_file_keeps_valid_values_and_is_repaired() {
+        let dir = test_dir("config-partial");
+        let path = settings_path_in(&dir);
+        let original = r#"{
+            "general": {"theme": "dark", "no_such_option": 1},
+            "tracking": {"tracking_interval": "fast", "idle_timeout": 600},
+            "ai": {"model": "my-model"}
+        }"#;
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, original).unwrap();
+
+        let (settings, warnings, repaired) = read_settings_at(&path);
+        assert!(repaired);
+        assert_eq!(settings.general.theme, "dark");
+        assert_eq!(settings.tracking.idle_timeout, 600);
+        assert_eq!(settings.ai.model, "my-model");
+        assert_eq!(settings.tracking.tracking_interval, Settings::default().tracking.tracking_interval);
+        assert_eq!(
+            warnings,
+            vec![
+                "Unknown setting general.no_such_option was ignored".to_string(),
+                "Invalid value for tracking.tracking_interval, using the default".to_string(),
+            ]
+        );
+
+        // The original is kept aside and the rewritten file loads cleanly.
+        assert_eq!(std::fs::read_to_string(path.with_extension("json.bak")).unwrap(), original);
+        let (reloaded, warnings, repaired) = read_settings_at(&path);
+        assert!(warnings.is_empty() && !repaired, "{:?}", warnings);
+        assert_eq!(reloaded.ai.model, "my-model");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_json_falls_back_to_defaults_and_keeps_a_backup() {
+        let dir = test_dir("config-truncated");
+        let path = settings_path_in(&dir);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{"ai": {"model": "my-mo"#).unwrap();
+
+        let (settings, warnings, repaired) = read_settings_at(&path);
+        assert!(repaired);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(settings.ai.model, Settings::default().ai.model);
+        assert!(path.with_extension("json.bak").exists());
+        assert!(serde_json::from_str::<Settings>(&std::fs::read_to_string(&path).unwrap()).is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file_loads_defaults_without_writing() {
+        let dir = test_dir("config-missing");
+        let path = settings_path_in(&dir);
+        let (_, warnings, repaired) = read_settings_at(&path);
+        assert!(warnings.is_empty() && !repaired);
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn no_tools_are_disabled_by_default() {
+        let (settings, _) = parse_settings("{}");
+        assert!(settings.ai.disabled_tools.is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+use std::sync::OnceLock;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use regex::Regex;
+use rusqlite::Connection;
+
+// ─── Natural date expressions ───
+// Explicit dates the fixed keyword list ("yesterday", "last week", ...) can't
+// express: "on 2024-03-15", "March 3", "June", "Q2 2024" and ranges joined by
+// "to"/"through"/"-" such as "last Tuesday to Thursday" or "Dec 20 to Jan 5".
+// Shared by the direct query path and the agent's time scope.
+
+/// A resolved expression: `[start_ts, end_ts]` (inclusive, clamped to now) and a display label.
+#[derive(Debug, Clone)]
+pub struct DateRange {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub label: String,
+}
+
+impl DateRange {
+    /// ISO form ("2024-03-15" or "2024-03-01..2024-03-10") that parses back to the same days.
+    pub fn scope_id(&self) -> String {
+        if self.start_date == self.end_date {
+            self.start_date.format("%Y-%m-%d").to_string()
+        } else {
+            format!("{}..{}", self.start_date.format("%Y-%m-%d"), self.end_date.format("%Y-%m-%d"))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Anchor {
+    Day { month: u32, day: u32, year: Option<i32> },
+    DaySpan { month: u32, first: u32, last: u32, year: Option<i32> },
+    Month { month: u32, year: Option<i32>, last: bool },
+    Quarter { quarter: u32, year: Option<i32>, last: bool },
+    Weekday { weekday: Weekday, last: bool },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Mention {
+    anchor: Anchor,
+    start: usize,
+    end: usize,
+}
+
+const MONTH_ALT: &str = "january|february|march|april|may|june|july|august|september|october|november|december|jan|feb|mar|apr|jun|jul|aug|sept|sep|oct|nov|dec";
+const WEEKDAY_ALT: &str = "monday|tuesday|wednesday|thursday|friday|saturday|sunday";
+
+/// Words that make an abbreviation or "may" read as a month when no day or year follows.
+const MONTH_CONTEXT: &[&str] = &[
+    "in", "during", "since", "for", "of", "from", "between", "to", "until", "through", "last", "this", "early", "late", "mid",
+];
+
+/// Text allowed between two mentions for them to form a range.
+const RANGE_CONNECTORS: &[&str] = &["to", "until", "till", "through", "thru", "-", "–", "..", "and"];
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: impl FnOnce() -> String) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(&pattern()).expect("valid date expression pattern"))
+}
+
+fn iso_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    regex(&PATTERN, || r"\b(\d{4})-(\d{1,2})-(\d{1,2})\b".to_string())
+}
+
+fn month_day_span_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    regex(&PATTERN, || {
+        format!(
+            r"\b({})\.?\s+(\d{{1,2}})(?:st|nd|rd|th)?\s*(?:-|–|to|through|until)\s*(\d{{1,2}})(?:st|nd|rd|th)?\b(?:,?\s+(\d{{4}}))?",
+            MONTH_ALT
+        )
+    })
+}
+
+fn month_day_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    regex(&PATTERN, || {
+        format!(r"\b({})\.?\s+(\d{{1,2}})(?:st|nd|rd|th)?\b(?:,?\s+(\d{{4}}))?", MONTH_ALT)
+    })
+}
+
+fn day_month_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    regex(&PATTERN, || {
+        format!(r"\b(\d{{1,2}})(?:st|nd|rd|th)?\s+(?:of\s+)?({})\b\.?(?:,?\s+(\d{{4}}))?", MONTH_ALT)
+    })
+}
+
+fn quarter_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    regex(&PATTERN, || r"\b(?:(last|this)\s+)?q([1-4])\b(?:\s+(\d{4}))?|\b(\d{4})\s+q([1-4])\b".to_string())
+}
+
+fn month_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    regex(&PATTERN, || format!(r"\b(?:(\w+)\s+)?({})\b\.?(?:\s+(\d{{4}}))?", MONTH_ALT))
+}
+
+fn weekday_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    regex(&PATTERN, || format!(r"\b(?:(last|this)\s+)?({})\b", WEEKDAY_ALT))
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let month = match &name[..name.len().min(3)] {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" => 8,
+        "sep" => 9,
+        "oct" => 10,
+        "nov" => 11,
+        "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+fn weekday_from_name(name: &str) -> Option<Weekday> {
+    name.parse::<Weekday>().ok()
+}
+
+fn parse_year(m: Option<regex::Match>) -> Option<i32> {
+    m.and_then(|m| m.as_str().parse::<i32>().ok())
+}
+
+/// Full month names other than "may" are unambiguous; the rest need a nearby cue.
+fn reads_as_month(name: &str, preceding: Option<&str>, has_year: bool) -> bool {
+    let unambiguous = name.len() > 4 || matches!(name, "june" | "july");
+    unambiguous || has_year || preceding.map(|w| MONTH_CONTEXT.contains(&w)).unwrap_or(false)
+}
+
+/// Every date mention in `text` (lowercased), in order of position.
+fn find_mentions(text: &str) -> Vec<Mention> {
+    // Patterns run most specific first; a later match overlapping an earlier one is dropped.
+    fn push(mentions: &mut Vec<Mention>, anchor: Anchor, start: usize, end: usize) {
+        if !mentions.iter().any(|m| start < m.end && m.start < end) {
+            mentions.push(Mention { anchor, start, end });
+        }
+    }
+
+    let mut mentions: Vec<Mention> = Vec::new();
+
+    for caps in month_day_span_pattern().captures_iter(text) {
+        let whole = caps.get(0).expect("match");
+        let (Some(month), Some(first), Some(last)) = (
+            month_number(&caps[1]),
+            caps[2].parse::<u32>().ok(),
+            caps[3].parse::<u32>().ok(),
+        ) else {
+            continue;
+        };
+        if first <= last {
+            let year = parse_year(caps.get(4));
+            push(&mut mentions, Anchor::DaySpan { month, first, last, year }, whole.start(), whole.end());
+        }
+    }
+    for caps in iso_pattern().captures_iter(text) {
+        let whole = caps.get(0).expect("match");
+        let (Some(year), Some(month), Some(day)) =
+            (parse_year(caps.get(1)), caps[2].parse::<u32>().ok(), caps[3].parse::<u32>().ok())
+        else {
+            continue;
+        };
+        push(&mut mentions, Anchor::Day { month, day, year: Some(year) }, whole.start(), whole.end());
+    }
+    for caps in month_day_pattern().captures_iter(text) {
+        let whole = caps.get(0).expect("match");
+        // A day number is cue enough, even for "may" or an abbreviation.
+        let (Some(month), Some(day)) = (month_number(&caps[1]), caps[2].parse::<u32>().ok()) else {
+            continue;
+        };
+        let year = parse_year(caps.get(3));
+        push(&mut mentions, Anchor::Day { month, day, year }, whole.start(), whole.end());
+    }
+    for caps in day_month_pattern().captures_iter(text) {
+        let whole = caps.get(0).expect("match");
+        let (Some(day), Some(month)) = (caps[1].parse::<u32>().ok(), month_number(&caps[2])) else {
+            continue;
+        };
+        let year = parse_year(caps.get(3));
+        push(&mut mentions, Anchor::Day { month, day, year }, whole.start(), whole.end());
+    }
+    for caps in quarter_pattern().captures_iter(text) {
+        let whole = caps.get(0).expect("match");
+        let (quarter, year) = match (caps.get(2), caps.get(5)) {
+            (Some(q), _) => (q.as_str().parse::<u32>().unwrap_or(1), parse_year(caps.get(3))),
+            (None, Some(q)) => (q.as_str().parse::<u32>().unwrap_or(1), parse_year(caps.get(4))),
+            _ => continue,
+        };
+        let last = caps.get(1).map(|m| m.as_str() == "last").unwrap_or(false);
+        push(&mut mentions, Anchor::Quarter { quarter, year, last }, whole.start(), whole.end());
+    }
+    for caps in month_pattern().captures_iter(text) {
+        let name_match = caps.get(2).expect("month group");
+        let preceding = caps.get(1).map(|m| m.as_str());
+        let year = parse_year(caps.get(3));
+        let Some(month) = month_number(name_match.as_str()) else {
+            continue;
+        };
+        if !reads_as_month(name_match.as_str(), preceding, year.is_some()) {
+            continue;
+        }
+        let last = preceding == Some("last");
+        // The preceding word is only context; the mention starts at the month
+        // unless it was "last"/"this", so "from June" still leaves "from" outside.
+        let start = match caps.get(1) {
+            Some(word) if matches!(word.as_str(), "last" | "this") => word.start(),
+            _ => name_match.start(),
+        };
+        let end = caps.get(3).map(|m| m.end()).unwrap_or(name_match.end());
+        push(&mut mentions, Anchor::Month { month, year, last }, start, end);
+    }
+    for caps in weekday_pattern().captures_iter(text) {
+        let whole = caps.get(0).expect("match");
+        let Some(weekday) = weekday_from_name(&caps[2]) else {
+            continue;
+        };
+        let last = caps.get(1).map(|m| m.as_str() == "last").unwrap_or(false);
+        push(&mut mentions, Anchor::Weekday { weekday, last }, whole.start(), whole.end());
+    }
+
+    mentions.sort_by_key(|m| m.start);
+    mentions
+}
+
+/// Calendar span of a non-weekday anchor in the given year.
+fn span_in_year(anchor: Anchor, year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    match anchor {
+        Anchor::Day { month, day, .. } => {
+            let date = NaiveDate::from_ymd_opt(year, month, day)?;
+            Some((date, date))
+        }
+        Anchor::DaySpan { month, first, last, .. } => Some((
+            NaiveDate::from_ymd_opt(year, month, first)?,
+            NaiveDate::from_ymd_opt(year, month, last)?,
+        )),
+        Anchor::Month { month, .. } => Some((NaiveDate::from_ymd_opt(year, month, 1)?, last_day_of_month(year, month)?)),
+        Anchor::Quarter { quarter, .. } => {
+            let first_month = (quarter - 1) * 3 + 1;
+            Some((
+                NaiveDate::from_ymd_opt(year, first_month, 1)?,
+                last_day_of_month(year, first_month + 2)?,
+            ))
+        }
+        Anchor::Weekday { .. } => None,
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1).map(|d| d - Duration::days(1))
+}
+
+fn explicit_year(anchor: Anchor) -> Option<i32> {
+    match anchor {
+        Anchor::Day { year, .. } | Anchor::DaySpan { year, .. } | Anchor::Month { year, .. } | Anchor::Quarter { year, .. } => year,
+        Anchor::Weekday { .. } => None,
+    }
+}
+
+/// Span of an anchor without a year: the most recent one that has started.
+/// A month or quarter that hasn't come yet this year means last year's; with
+/// "last", the one in progress is skipped too.
+fn resolve_recent(anchor: Anchor, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    if let Some(year) = explicit_year(anchor) {
+        return span_in_year(anchor, year);
+    }
+    let (start, end) = span_in_year(anchor, today.year())?;
+    let skip_current = matches!(anchor, Anchor::Month { last: true, .. } | Anchor::Quarter { last: true, .. });
+    if start > today || (skip_current && end >= today) {
+        span_in_year(anchor, today.year() - 1)
+    } else {
+        Some((start, end))
+    }
+}
+
+/// Most recent past occurrence of a weekday, never today (matching "last monday").
+fn recent_weekday(weekday: Weekday, today: NaiveDate) -> NaiveDate {
+    let mut days_back = today.weekday().num_days_from_monday() as i64 - weekday.num_days_from_monday() as i64;
+    if days_back <= 0 {
+        days_back += 7;
+    }
+    today - Duration::days(days_back)
+}
+
+/// First occurrence of a weekday on or after `from`.
+fn weekday_on_or_after(weekday: Weekday, from: NaiveDate) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() as i64 - from.weekday().num_days_from_monday() as i64).rem_euclid(7);
+    from + Duration::days(ahead)
+}
+
+/// Resolve two mentions joined by a connector into one date span.
+fn resolve_range(first: Anchor, second: Anchor, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    match (first, second) {
+        (Anchor::Weekday { weekday: a, last }, Anchor::Weekday { weekday: b, .. }) => {
+            let mut start = recent_weekday(a, today);
+            let mut end = weekday_on_or_after(b, start);
+            // "last Tuesday to Thursday" means a finished stretch, not one still running.
+            if last && end > today {
+                start -= Duration::days(7);
+                end -= Duration::days(7);
+            }
+            Some((start, end))
+        }
+        (_, Anchor::Weekday { weekday, .. }) => {
+            let (start, _) = resolve_recent(first, today)?;
+            Some((start, weekday_on_or_after(weekday, start)))
+        }
+        (Anchor::Weekday { weekday, .. }, _) => {
+            let (_, end) = resolve_recent(second, today)?;
+            let start = recent_weekday(weekday, today).min(end);
+            Some((start, end))
+        }
+        _ => {
+            // A year on either side carries over to the other ("Dec 20 to Jan 5 2025").
+            let (first_year, second_year) = match (explicit_year(first), explicit_year(second)) {
+                (Some(a), Some(b)) => (a, b),
+                (Some(a), None) => {
+                    let b = if span_in_year(second, a)?.0 < span_in_year(first, a)?.0 { a + 1 } else { a };
+                    (a, b)
+                }
+                (None, Some(b)) => {
+                    let a = if span_in_year(first, b)?.0 > span_in_year(second, b)?.0 { b - 1 } else { b };
+                    (a, b)
+                }
+                (None, None) => {
+                    let b = resolve_recent(second, today)?.0.year();
+                    let a = if span_in_year(first, b)?.0 > span_in_year(second, b)?.0 { b - 1 } else { b };
+                    (a, b)
+                }
+            };
+            let (start, _) = span_in_year(first, first_year)?;
+            let (_, end) = span_in_year(second, second_year)?;
+            (start <= end).then_some((start, end))
+        }
+    }
+}
+
+fn is_range_connector(between: &str) -> bool {
+    let between = between.trim();
+    RANGE_CONNECTORS.contains(&between)
+}
+
+fn span_label(anchor: Option<Anchor>, start: NaiveDate, end: NaiveDate) -> String {
+    match anchor {
+        Some(Anchor::Month { .. }) => start.format("%B %Y").to_string(),
+        Some(Anchor::Quarter { quarter, .. }) => format!("Q{} {}", quarter, start.year()),
+        _ if start == end => start.format("%b %d, %Y").to_string(),
+        _ if start.year() == end.year() => format!("{} – {}", start.format("%a %b %d"), end.format("%a %b %d, %Y")),
+        _ => format!("{} – {}", start.format("%b %d, %Y"), end.format("%b %d, %Y")),
+    }
+}
+
+/// Parse the first explicit date, month, quarter or range in `text`.
+/// A lone weekday is left to the callers' own "last monday" handling.
+pub fn parse_date_expression(text: &str, conn: Option<&Connection>) -> Option<DateRange> {
+    let today = crate::utils::time::today();
+    let now = chrono::Utc::now().timestamp();
+    let lower = text.to_lowercase();
+    let mentions = find_mentions(&lower);
+
+    let ranged = mentions.windows(2).find_map(|pair| {
+        let between = lower.get(pair[0].end..pair[1].start)?;
+        if !is_range_connector(between) {
+            return None;
+        }
+        resolve_range(pair[0].anchor, pair[1].anchor, today).map(|(start, end)| (None, start, end))
+    });
+    let (anchor, start, end) = match ranged {
+        Some(found) => found,
+        None => mentions
+            .iter()
+            .filter(|m| !matches!(m.anchor, Anchor::Weekday { .. }))
+            .find_map(|m| resolve_recent(m.anchor, today).map(|(start, end)| (Some(m.anchor), start, end)))?,
+    };
+
+    let start_ts = crate::utils::time::day_bounds(conn, start).start_ts;
+    if start_ts > now {
+        return None;
+    }
+    let end_ts = (crate::utils::time::day_bounds(conn, end).end_ts - 1).min(now);
+    Some(DateRange {
+        start_date: start,
+        end_date: end,
+        start_ts,
+        end_ts,
+        label: span_label(anchor, start, end),
+    })
+}
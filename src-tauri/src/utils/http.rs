@@ -0,0 +1,93 @@
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+// ─── Shared HTTP client ───
+// One pooled client for every outbound call. reqwest reads HTTP_PROXY,
+// HTTPS_PROXY and NO_PROXY from the environment on its own; the optional
+// extra CA lets TLS-inspecting corporate proxies through.
+
+const CONNECT_TIMEOUT_SECS: u64 = 15;
+
+struct SharedClient {
+    extra_ca_cert: Option<String>,
+    client: reqwest::Client,
+}
+
+fn client_store() -> &'static RwLock<SharedClient> {
+    static STORE: OnceLock<RwLock<SharedClient>> = OnceLock::new();
+    STORE.get_or_init(|| {
+        RwLock::new(SharedClient {
+            extra_ca_cert: None,
+            client: build_client(None),
+        })
+    })
+}
+
+fn load_certificates(path: &str) -> Result<Vec<reqwest::Certificate>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    match reqwest::Certificate::from_pem_bundle(&bytes) {
+        Ok(certs) if !certs.is_empty() => Ok(certs),
+        _ => reqwest::Certificate::from_der(&bytes)
+            .map(|cert| vec![cert])
+            .map_err(|e| format!("{} is not a PEM or DER certificate: {}", path, e)),
+    }
+}
+
+fn build_client(extra_ca_cert: Option<&str>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .pool_idle_timeout(Duration::from_secs(90));
+
+    if let Some(path) = extra_ca_cert {
+        match load_certificates(path) {
+            Ok(certs) => {
+                println!("[HTTP] Trusting {} extra CA certificate(s) from {}", certs.len(), path);
+                for cert in certs {
+                    builder = builder.add_root_certificate(cert);
+                }
+            }
+            Err(e) => println!("[HTTP] Ignoring extra CA: {}", e),
+        }
+    }
+
+    for var in ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"] {
+        if std::env::var(var).map(|v| !v.trim().is_empty()).unwrap_or(false) {
+            println!("[HTTP] Using proxy from {}", var);
+            break;
+        }
+    }
+
+    builder.build().unwrap_or_else(|e| {
+        println!("[HTTP] Failed to build configured client, using defaults: {}", e);
+        reqwest::Client::new()
+    })
+}
+
+/// Rebuild the shared client when the CA setting changes. Called from the settings bus.
+pub fn configure(extra_ca_cert: Option<&str>) {
+    let extra_ca_cert = extra_ca_cert
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    {
+        let store = client_store().read().unwrap_or_else(|e| e.into_inner());
+        if store.extra_ca_cert == extra_ca_cert {
+            return;
+        }
+    }
+    let client = build_client(extra_ca_cert.as_deref());
+    let mut store = client_store().write().unwrap_or_else(|e| e.into_inner());
+    *store = SharedClient {
+        extra_ca_cert,
+        client,
+    };
+}
+
+/// Pooled client shared by all callers; cloning only bumps a refcount.
+/// Set per-request timeouts on the request builder.
+pub fn http_client() -> reqwest::Client {
+    client_store()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .client
+        .clone()
+}
@@ -0,0 +1,438 @@
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{Datelike, NaiveDate};
+
+// ─── Answer language ───
+// `settings.ai.answer_language` picks the language of generated text: "auto"
+// follows the language of each question, anything else is fixed. LLM prompts
+// get an instruction to answer in it; the deterministic fallbacks (dashboard
+// summary, insufficient-evidence replies, durations, dates) come from the
+// table below, which covers English, French and German. Other languages still
+// reach the prompts but fall back to English for the table.
+
+pub const AUTO: &str = "auto";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Fr,
+    De,
+}
+
+impl Lang {
+    /// "fr", "fr-FR", "French", ... Unsupported codes give `None`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let lower = code.trim().to_lowercase();
+        let primary = lower.split(['-', '_']).next().unwrap_or("");
+        match primary {
+            "en" | "english" => Some(Lang::En),
+            "fr" | "french" | "français" | "francais" => Some(Lang::Fr),
+            "de" | "german" | "deutsch" => Some(Lang::De),
+            _ => None,
+        }
+    }
+
+    /// Name used in prompt instructions.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lang::En => "English",
+            Lang::Fr => "French (français)",
+            Lang::De => "German (Deutsch)",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Lang::En => 0,
+            Lang::Fr => 1,
+            Lang::De => 2,
+        }
+    }
+}
+
+fn answer_language_store() -> &'static Mutex<String> {
+    static STORE: OnceLock<Mutex<String>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(AUTO.to_string()))
+}
+
+/// Apply `settings.ai.answer_language`; empty means "auto".
+pub fn set_answer_language(setting: &str) {
+    let value = setting.trim();
+    if let Ok(mut store) = answer_language_store().lock() {
+        *store = if value.is_empty() { AUTO.to_string() } else { value.to_string() };
+    }
+}
+
+fn configured() -> String {
+    answer_language_store()
+        .lock()
+        .map(|s| s.clone())
+        .unwrap_or_else(|_| AUTO.to_string())
+}
+
+/// Table language for replying to `text`: the fixed setting, or the detected
+/// language of `text` on "auto".
+pub fn answer_lang(text: &str) -> Lang {
+    let setting = configured();
+    if setting.eq_ignore_ascii_case(AUTO) {
+        detect_language(text)
+    } else {
+        Lang::from_code(&setting).unwrap_or(Lang::En)
+    }
+}
+
+/// Table language when there is no question to follow (durations in tool output).
+pub fn default_lang() -> Lang {
+    Lang::from_code(&configured()).unwrap_or(Lang::En)
+}
+
+/// Prompt line telling the model which language to write in. On "auto" the
+/// language is detected from `text`; a fixed setting outside the table is
+/// passed to the model as given.
+pub fn language_instruction(text: &str) -> String {
+    let setting = configured();
+    let name = if setting.eq_ignore_ascii_case(AUTO) {
+        detect_language(text).name().to_string()
+    } else {
+        Lang::from_code(&setting)
+            .map(|lang| lang.name().to_string())
+            .unwrap_or(setting)
+    };
+    format!(
+        "Write all user-facing text in {}, whatever language the activity data is in. Keep app names, window titles and quoted screen text unchanged, and write dates and durations the way a {} speaker would.",
+        name,
+        name.split(" (").next().unwrap_or(&name)
+    )
+}
+
+const FRENCH_WORDS: &[&str] = &[
+    "le", "la", "les", "des", "du", "une", "et", "est", "que", "qu", "quoi", "qui", "je", "ai", "mon", "ma", "mes",
+    "hier", "aujourd", "hui", "quand", "combien", "avec", "pour", "sur", "fait", "dans", "ce", "cette", "semaine",
+];
+const GERMAN_WORDS: &[&str] = &[
+    "der", "die", "das", "und", "ich", "habe", "wie", "wann", "mein", "meine", "gestern", "heute", "mit", "für",
+    "auf", "ist", "nicht", "hat", "gemacht", "welche", "woche", "wieviel", "viel", "zeit",
+];
+const ENGLISH_WORDS: &[&str] = &[
+    "the", "and", "what", "did", "i", "my", "you", "how", "when", "is", "to", "of", "in", "yesterday", "today",
+    "week", "which", "with", "for", "on",
+];
+
+/// Guess English, French or German from common words and accents. Anything
+/// without a clear French or German signal is English.
+pub fn detect_language(text: &str) -> Lang {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower.split(|c: char| !c.is_alphabetic()).filter(|w| !w.is_empty()).collect();
+    let count = |list: &[&str]| words.iter().filter(|w| list.contains(w)).count();
+    let accents = |set: &[char]| lower.chars().filter(|c| set.contains(c)).count().min(3);
+
+    let english = count(ENGLISH_WORDS);
+    let french = count(FRENCH_WORDS) + accents(&['é', 'è', 'ê', 'à', 'ç', 'ù', 'ô', 'â', 'î']);
+    let german = count(GERMAN_WORDS) + accents(&['ä', 'ö', 'ü', 'ß']);
+
+    if french >= 2 && french > english && french >= german {
+        Lang::Fr
+    } else if german >= 2 && german > english && german > french {
+        Lang::De
+    } else {
+        Lang::En
+    }
+}
+
+/// Deterministic strings shown without going through the LLM. `{}` marks are
+/// filled in order by `fill`. Every key has to list all three languages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    NoActivityToday,
+    MostlyWorkedIn,
+    StatedFocus,
+    ActiveStreaks,
+    NoSummaryYet,
+    AiSummaryNoKey,
+    AiSummaryDisabled,
+    KeepGoingOn,
+    DueOn,
+    ContinueProject,
+    FollowUpWith,
+    NothingToFollowUp,
+    Interactions,
+    TaggedInteractions,
+    TagScheduling,
+    TagQuestion,
+    TagWork,
+    TagSocial,
+    InsufficientEvidence,
+    PartialEvidence,
+    UnstableSummary,
+    NoChatEvidence,
+    DurationHms,
+    DurationMs,
+    DurationS,
+}
+
+impl Msg {
+    /// English, French, German.
+    fn variants(self) -> [&'static str; 3] {
+        match self {
+            Msg::NoActivityToday => [
+                "No tracked activity yet today.",
+                "Aucune activité enregistrée pour l'instant aujourd'hui.",
+                "Heute wurde noch keine Aktivität erfasst.",
+            ],
+            Msg::MostlyWorkedIn => [
+                "Today you mostly worked in {}.",
+                "Aujourd'hui, vous avez surtout travaillé dans {}.",
+                "Heute hast du hauptsächlich in {} gearbeitet.",
+            ],
+            Msg::StatedFocus => [
+                "You said you're working on {}. {}",
+                "Vous avez indiqué travailler sur {}. {}",
+                "Du arbeitest laut deiner Angabe an {}. {}",
+            ],
+            Msg::ActiveStreaks => [
+                "{} Streaks going: {}.",
+                "{} Séries en cours : {}.",
+                "{} Laufende Serien: {}.",
+            ],
+            Msg::NoSummaryYet => [
+                "No summary generated yet.",
+                "Aucun résumé généré pour l'instant.",
+                "Noch keine Zusammenfassung erstellt.",
+            ],
+            Msg::AiSummaryNoKey => [
+                "(AI Summary disabled: No API key provided)",
+                "(Résumé IA désactivé : aucune clé API fournie)",
+                "(KI-Zusammenfassung deaktiviert: kein API-Schlüssel angegeben)",
+            ],
+            Msg::AiSummaryDisabled => [
+                "(AI Summary disabled in settings)",
+                "(Résumé IA désactivé dans les paramètres)",
+                "(KI-Zusammenfassung in den Einstellungen deaktiviert)",
+            ],
+            Msg::KeepGoingOn => ["Keep going on {}", "Continuer sur {}", "Weiter an {} arbeiten"],
+            Msg::DueOn => ["{} (due {})", "{} (échéance {})", "{} (fällig {})"],
+            Msg::ContinueProject => [
+                "Continue {} ({} files changed today)",
+                "Reprendre {} ({} fichiers modifiés aujourd'hui)",
+                "{} fortsetzen ({} Dateien heute geändert)",
+            ],
+            Msg::FollowUpWith => ["Follow up with {}", "Recontacter {}", "Bei {} nachhaken"],
+            Msg::NothingToFollowUp => [
+                "Nothing to follow up on yet today",
+                "Rien à suivre pour l'instant aujourd'hui",
+                "Heute noch nichts nachzuverfolgen",
+            ],
+            Msg::Interactions => [
+                "{} interaction(s) in {}",
+                "{} interaction(s) dans {}",
+                "{} Interaktion(en) in {}",
+            ],
+            Msg::TaggedInteractions => ["{} in {}", "{} dans {}", "{} in {}"],
+            Msg::TagScheduling => ["scheduling", "planification", "Terminplanung"],
+            Msg::TagQuestion => ["question", "question", "Frage"],
+            Msg::TagWork => ["work", "travail", "Arbeit"],
+            Msg::TagSocial => ["social", "social", "privat"],
+            Msg::InsufficientEvidence => [
+                "I don't have enough cross-checked evidence to answer confidently. Try widening the time range (Last 7 Days or All Time) and enabling Browser History / Files & Documents, then ask me to retry.",
+                "Je n'ai pas assez d'éléments recoupés pour répondre avec certitude. Élargissez la période (7 derniers jours ou Tout) et activez l'historique du navigateur / Fichiers et documents, puis relancez la question.",
+                "Ich habe nicht genug abgeglichene Belege, um sicher zu antworten. Erweitere den Zeitraum (Letzte 7 Tage oder Gesamte Zeit) und aktiviere Browserverlauf / Dateien & Dokumente, dann frag noch einmal.",
+            ],
+            Msg::PartialEvidence => [
+                "I checked your activity and found partial evidence, but not enough for a fully confident answer. Ask with a specific date/app and I will give exact details.",
+                "J'ai vérifié votre activité et trouvé des éléments partiels, mais pas assez pour une réponse sûre. Précisez une date ou une application et je donnerai les détails exacts.",
+                "Ich habe deine Aktivität geprüft und nur teilweise Belege gefunden, nicht genug für eine sichere Antwort. Frag mit einem bestimmten Datum oder einer App, dann nenne ich genaue Details.",
+            ],
+            Msg::UnstableSummary => [
+                "I gathered evidence but could not produce a stable final summary. Please ask with a specific app/date and I’ll answer exactly.",
+                "J'ai rassemblé des éléments mais n'ai pas pu produire de résumé final fiable. Précisez une application ou une date et je répondrai exactement.",
+                "Ich habe Belege gesammelt, konnte aber keine stabile Zusammenfassung erstellen. Frag bitte mit einer bestimmten App oder einem Datum, dann antworte ich genau.",
+            ],
+            Msg::NoChatEvidence => [
+                "Note: I don't have explicit chat-app evidence in this time range, so I cannot claim texting/chats.",
+                "Remarque : je n'ai aucune preuve explicite d'application de messagerie sur cette période, je ne peux donc pas affirmer qu'il y a eu des échanges.",
+                "Hinweis: Für diesen Zeitraum habe ich keine eindeutigen Belege aus Chat-Apps, daher kann ich keine Nachrichten oder Chats bestätigen.",
+            ],
+            Msg::DurationHms => ["{}h {}m {}s", "{} h {} min {} s", "{} Std. {} Min. {} Sek."],
+            Msg::DurationMs => ["{}m {}s", "{} min {} s", "{} Min. {} Sek."],
+            Msg::DurationS => ["{}s", "{} s", "{} Sek."],
+        }
+    }
+
+    pub fn text(self, lang: Lang) -> &'static str {
+        self.variants()[lang.index()]
+    }
+}
+
+/// `msg` in `lang` with each `{}` replaced by the next argument.
+pub fn fill(msg: Msg, lang: Lang, args: &[&dyn std::fmt::Display]) -> String {
+    let template = msg.text(lang);
+    let mut out = String::with_capacity(template.len() + 16);
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(idx) = rest.find("{}") {
+        out.push_str(&rest[..idx]);
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        rest = &rest[idx + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn format_duration(total_seconds: i64, lang: Lang) -> String {
+    if total_seconds <= 0 {
+        return fill(Msg::DurationS, lang, &[&0]);
+    }
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    if hours > 0 {
+        fill(Msg::DurationHms, lang, &[&hours, &minutes, &seconds])
+    } else if minutes > 0 {
+        fill(Msg::DurationMs, lang, &[&minutes, &seconds])
+    } else {
+        fill(Msg::DurationS, lang, &[&seconds])
+    }
+}
+
+const MONTHS_SHORT: [[&str; 12]; 3] = [
+    ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+    ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+    ["Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sep.", "Okt.", "Nov.", "Dez."],
+];
+
+/// "Mar 05, 2024", "5 mars 2024", "5. März 2024".
+pub fn format_date(date: NaiveDate, lang: Lang) -> String {
+    let month = MONTHS_SHORT[lang.index()][date.month0() as usize];
+    match lang {
+        Lang::En => format!("{} {:02}, {}", month, date.day(), date.year()),
+        Lang::Fr => format!("{} {} {}", date.day(), month, date.year()),
+        Lang::De => format!("{}. {} {}", date.day(), month, date.year()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LANGS: [Lang; 3] = [Lang::En, Lang::Fr, Lang::De];
+
+    /// Every key; the match makes a new `Msg` variant fail to compile until it is listed.
+    fn all_keys() -> Vec<Msg> {
+        let keys = vec![
+            Msg::NoActivityToday,
+            Msg::MostlyWorkedIn,
+            Msg::StatedFocus,
+            Msg::ActiveStreaks,
+            Msg::NoSummaryYet,
+            Msg::AiSummaryNoKey,
+            Msg::AiSummaryDisabled,
+            Msg::KeepGoingOn,
+            Msg::DueOn,
+            Msg::ContinueProject,
+            Msg::FollowUpWith,
+            Msg::NothingToFollowUp,
+            Msg::Interactions,
+            Msg::TaggedInteractions,
+            Msg::TagScheduling,
+            Msg::TagQuestion,
+            Msg::TagWork,
+            Msg::TagSocial,
+            Msg::InsufficientEvidence,
+            Msg::PartialEvidence,
+            Msg::UnstableSummary,
+            Msg::NoChatEvidence,
+            Msg::DurationHms,
+            Msg::DurationMs,
+            Msg::DurationS,
+        ];
+        for key in &keys {
+            match key {
+                Msg::NoActivityToday
+                | Msg::MostlyWorkedIn
+                | Msg::StatedFocus
+                | Msg::ActiveStreaks
+                | Msg::NoSummaryYet
+                | Msg::AiSummaryNoKey
+                | Msg::AiSummaryDisabled
+                | Msg::KeepGoingOn
+                | Msg::DueOn
+                | Msg::ContinueProject
+                | Msg::FollowUpWith
+                | Msg::NothingToFollowUp
+                | Msg::Interactions
+                | Msg::TaggedInteractions
+                | Msg::TagScheduling
+                | Msg::TagQuestion
+                | Msg::TagWork
+                | Msg::TagSocial
+                | Msg::InsufficientEvidence
+                | Msg::PartialEvidence
+                | Msg::UnstableSummary
+                | Msg::NoChatEvidence
+                | Msg::DurationHms
+                | Msg::DurationMs
+                | Msg::DurationS => {}
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn every_key_is_translated_with_the_same_placeholders() {
+        for key in all_keys() {
+            let english = key.text(Lang::En);
+            for lang in LANGS {
+                let text = key.text(lang);
+                assert!(!text.trim().is_empty(), "{:?} is empty in {:?}", key, lang);
+                assert_eq!(
+                    text.matches("{}").count(),
+                    english.matches("{}").count(),
+                    "{:?} has different placeholders in {:?}",
+                    key,
+                    lang
+                );
+            }
+            // Long messages must actually be translated, not copied.
+            if english.len() > 20 {
+                assert_ne!(key.text(Lang::Fr), english, "{:?}", key);
+                assert_ne!(key.text(Lang::De), english, "{:?}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn fill_replaces_placeholders_in_order() {
+        assert_eq!(fill(Msg::DueOn, Lang::Fr, &[&"Rapport", &"vendredi"]), "Rapport (échéance vendredi)");
+        assert_eq!(fill(Msg::DueOn, Lang::En, &[&"Report"]), "Report (due )");
+        assert_eq!(format_duration(3_725, Lang::En), "1h 2m 5s");
+        assert_eq!(format_duration(125, Lang::Fr), "2 min 5 s");
+        assert_eq!(format_duration(-5, Lang::De), "0 Sek.");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        assert_eq!(format_date(date, Lang::En), "Mar 05, 2024");
+        assert_eq!(format_date(date, Lang::Fr), "5 mars 2024");
+        assert_eq!(format_date(date, Lang::De), "5. März 2024");
+    }
+
+    #[test]
+    fn questions_are_detected_by_common_words() {
+        assert_eq!(detect_language("Qu'est-ce que j'ai fait hier sur mon ordinateur ?"), Lang::Fr);
+        assert_eq!(detect_language("Combien de temps dans VS Code cette semaine"), Lang::Fr);
+        assert_eq!(detect_language("Was habe ich gestern gemacht?"), Lang::De);
+        assert_eq!(detect_language("Wie viel Zeit hatte ich heute für E-Mails?"), Lang::De);
+        assert_eq!(detect_language("What did I do yesterday?"), Lang::En);
+        assert_eq!(detect_language("Spotify"), Lang::En);
+        assert_eq!(detect_language(""), Lang::En);
+        assert_eq!(Lang::from_code("fr-FR"), Some(Lang::Fr));
+        assert_eq!(Lang::from_code(" Deutsch "), Some(Lang::De));
+        assert_eq!(Lang::from_code("es"), None);
+    }
+
+    #[test]
+    fn auto_instruction_follows_the_question() {
+        assert!(language_instruction("Qu'est-ce que j'ai fait hier ?").contains("in French (français)"));
+        assert!(language_instruction("Was habe ich gestern gemacht?").contains("a German speaker"));
+        assert!(language_instruction("What did I do?").contains("in English"));
+    }
+}
@@ -0,0 +1,94 @@
+// ─── iCalendar (RFC 5545) output ───
+// Just enough of the format for exporting tracked time as events that
+// Google Calendar and Outlook import.
+
+pub struct IcsEvent {
+    pub uid: String,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub summary: String,
+    pub description: String,
+    pub categories: Vec<String>,
+}
+
+/// A complete VCALENDAR with CRLF line endings.
+pub fn render_calendar(name: &str, events: &[IcsEvent]) -> String {
+    let mut lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//IntentFlow//Timeline Export//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+    let stamp = format_utc(chrono::Utc::now().timestamp());
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", format_utc(event.start_ts)));
+        lines.push(format!("DTEND:{}", format_utc(event.end_ts)));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if !event.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+        }
+        if !event.categories.is_empty() {
+            let categories: Vec<String> = event.categories.iter().map(|c| escape_text(c)).collect();
+            lines.push(format!("CATEGORIES:{}", categories.join(",")));
+        }
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// UTC date-time form, e.g. 20240131T093000Z.
+fn format_utc(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_else(|| "19700101T000000Z".to_string())
+}
+
+/// TEXT value escaping: backslash, semicolon, comma and newlines.
+fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Lines longer than 75 octets continue on the next line after a space,
+/// never splitting a UTF-8 character.
+fn fold_line(line: &str) -> String {
+    const MAX_OCTETS: usize = 75;
+    if line.len() <= MAX_OCTETS {
+        return line.to_string();
+    }
+    let mut out = String::with_capacity(line.len() + line.len() / MAX_OCTETS * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > MAX_OCTETS {
+            out.push_str("\r\n ");
+            width = 1; // the leading space counts toward the continuation line
+        }
+        out.push(c);
+        width += len;
+    }
+    out
+}
@@ -0,0 +1,98 @@
+use crate::models::{MediaInfo, MediaKind};
+
+// ─── Media classification ───
+// Decides whether an SMTC session was music or video. Runs once when an
+// activity is stored; rows written before `media_kind` existed go through
+// the same rules on read.
+
+/// Dedicated audio players; anything they report is treated as music.
+const MUSIC_PLAYERS: [&str; 12] = [
+    "spotify",
+    "applemusic",
+    "itunes",
+    "zunemusic",
+    "tidal",
+    "deezer",
+    "amazon music",
+    "amazonmusic",
+    "foobar2000",
+    "musicbee",
+    "aimp",
+    "winamp",
+];
+
+const BROWSERS: [&str; 7] = ["chrome", "msedge", "edge", "firefox", "brave", "opera", "vivaldi"];
+
+/// Typical single-track length; browser sessions outside this range are usually videos.
+const SONG_MIN_SECS: i64 = 90;
+const SONG_MAX_SECS: i64 = 420;
+
+fn contains_any(haystack: &str, needles: &[&str]) -> bool {
+    needles.iter().any(|n| haystack.contains(n))
+}
+
+/// Display name of a player app. Spotify's window can report its name with
+/// stray bytes around it ("Spotify8FileV"), so any name containing it is Spotify.
+pub fn player_display_name(app_name: &str) -> &str {
+    if app_name.contains("Spotify") || app_name.to_lowercase().starts_with("spotify") {
+        "Spotify"
+    } else if app_name.to_lowercase().contains("youtube") {
+        "YouTube"
+    } else {
+        app_name
+    }
+}
+
+/// `app_name`/`window_title` are the focused window's, which is only a hint
+/// when the media session has no `source_app` (older rows).
+pub fn classify_media(
+    media: &MediaInfo,
+    app_name: &str,
+    window_title: &str,
+    duration_seconds: i64,
+) -> MediaKind {
+    if media.title.trim().is_empty() {
+        return MediaKind::Unknown;
+    }
+
+    let source = media
+        .source_app
+        .as_deref()
+        .unwrap_or(app_name)
+        .to_lowercase()
+        .replace(' ', "");
+    let title_lower = window_title.to_lowercase();
+
+    if contains_any(&source, &MUSIC_PLAYERS) || title_lower.contains("youtube music") {
+        return MediaKind::Music;
+    }
+
+    let playing = media.status.eq_ignore_ascii_case("playing");
+    let has_artist = !media.artist.trim().is_empty();
+
+    if contains_any(&source, &BROWSERS) {
+        let song_length = (SONG_MIN_SECS..=SONG_MAX_SECS).contains(&duration_seconds);
+        return if playing && has_artist && song_length {
+            MediaKind::Music
+        } else {
+            MediaKind::Video
+        };
+    }
+
+    // Without a recorded source the focused app may have nothing to do with
+    // the media (Spotify playing behind an editor), so trust the metadata.
+    if media.source_app.is_none() {
+        return if playing && has_artist {
+            MediaKind::Music
+        } else {
+            MediaKind::Unknown
+        };
+    }
+
+    // Other sources (VLC, Movies & TV, games...): only judge sessions that were actually playing.
+    if playing {
+        MediaKind::Video
+    } else {
+        MediaKind::Unknown
+    }
+}
@@ -1,4 +1,13 @@
+pub mod categories;
 pub mod config;
+pub mod date_expr;
+pub mod http;
+pub mod i18n;
+pub mod ics;
+pub mod media;
+pub mod redact;
+pub mod text;
+pub mod time;
 pub mod windows;
 
 use std::hash::Hasher;
@@ -9,3 +18,13 @@ pub fn hash_string(s: &str) -> i64 {
     hasher.write(s.to_lowercase().as_bytes());
     hasher.finish() as i64
 }
+
+/// Fresh, empty directory under the system temp dir for one test; `name`
+/// keeps tests running in parallel apart.
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("intentflow-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,85 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+// ─── Secret redaction ───
+// Applied to free text before it is stored (clipboard entries) so obvious
+// credentials never reach the database or the agent.
+
+const REDACTED: &str = "[REDACTED]";
+
+fn secret_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // PEM private keys
+            r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+            // JWTs
+            r"\beyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}",
+            // Provider API keys (OpenAI/Anthropic-style, NVIDIA, GitHub, AWS, Slack)
+            r"\b(?:sk|pk|rk)-[A-Za-z0-9_-]{16,}",
+            r"\bnvapi-[A-Za-z0-9_-]{16,}",
+            r"\bgh[pousr]_[A-Za-z0-9]{20,}",
+            r"\bAKIA[0-9A-Z]{16}\b",
+            r"\bxox[abprs]-[A-Za-z0-9-]{10,}",
+            r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]{16,}=*",
+        ]
+        .iter()
+        .filter_map(|p| Regex::new(p).ok())
+        .collect()
+    })
+}
+
+/// `password: hunter2` style assignments; the key is kept, the value dropped.
+fn assignment_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r#"(?i)\b(password|passwd|pwd|secret|token|api[_-]?key|access[_-]?key)(\s*[:=]\s*)("[^"]*"|'[^']*'|\S+)"#)
+            .expect("valid assignment pattern")
+    })
+}
+
+fn card_candidate_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").expect("valid card pattern"))
+}
+
+fn passes_luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// Replace credentials, private keys and card numbers with `[REDACTED]`.
+pub fn redact_sensitive(text: &str) -> String {
+    let mut out = text.to_string();
+    for pattern in secret_patterns() {
+        out = pattern.replace_all(&out, REDACTED).into_owned();
+    }
+    out = assignment_pattern()
+        .replace_all(&out, format!("${{1}}${{2}}{}", REDACTED).as_str())
+        .into_owned();
+    out = card_candidate_pattern()
+        .replace_all(&out, |caps: &regex::Captures| {
+            let matched = &caps[0];
+            let digits: Vec<u32> = matched.chars().filter_map(|c| c.to_digit(10)).collect();
+            if (13..=19).contains(&digits.len()) && passes_luhn(&digits) {
+                REDACTED.to_string()
+            } else {
+                matched.to_string()
+            }
+        })
+        .into_owned();
+    out
+}
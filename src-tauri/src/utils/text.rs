@@ -0,0 +1,61 @@
+// ─── OCR text heuristics ───
+// Shared by the capture-time cleaner and the query-time sanitizer. Both drop
+// lines that look like recognition noise; these helpers keep that judgement
+// from penalising accented Latin text, non-Latin scripts and emoji.
+
+/// Punctuation that shows up in ordinary prose, chat and code.
+const COMMON_PUNCTUATION: &str = ",.;:!?()[]{}'\"/@#&+-_|*%=<>~";
+/// Typographic marks from Latin-1 and General Punctuation (quotes, dashes, currency).
+const TYPOGRAPHIC_PUNCTUATION: &str = "«»„“”‚‘’‹›–—…•·¿¡°§€£¥¢©®™";
+
+/// Vowels of Latin-script languages, including accented and dotless forms
+/// (ä, é, ı, ő, ø, œ, …), so German or Turkish lines aren't read as consonant soup.
+pub fn is_latin_vowel(c: char) -> bool {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    matches!(
+        lower,
+        'a' | 'e' | 'i' | 'o' | 'u' | 'y'
+            | 'à'..='æ'
+            | 'è'..='ï'
+            | 'ò'..='ö'
+            | 'ø'..='ü'
+            | 'ý'
+            | 'ÿ'
+            | 'ā' | 'ă' | 'ą' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě'
+            | 'ī' | 'ĭ' | 'į' | 'ı' | 'ō' | 'ŏ' | 'ő' | 'œ'
+            | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' | 'ŷ'
+    )
+}
+
+/// Letters from Basic Latin through Latin Extended-B plus Latin Extended Additional.
+pub fn is_latin_letter(c: char) -> bool {
+    c.is_alphabetic() && (c <= '\u{024F}' || ('\u{1E00}'..='\u{1EFF}').contains(&c))
+}
+
+/// Pictographs, dingbats and the joiners/selectors that glue emoji sequences.
+pub fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1F000}'..='\u{1FAFF}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2B00}'..='\u{2BFF}'
+            | '\u{FE0F}'
+            | '\u{200D}'
+    )
+}
+
+/// Non-alphanumeric characters that are expected in real text rather than OCR noise.
+pub fn is_expected_symbol(c: char) -> bool {
+    COMMON_PUNCTUATION.contains(c) || TYPOGRAPHIC_PUNCTUATION.contains(c) || is_emoji(c)
+}
+
+/// Share of vowels among Latin letters, or `None` when the text has fewer than
+/// `min_letters` of them (mostly non-Latin script, numbers or symbols).
+pub fn latin_vowel_ratio(chars: &[char], min_letters: usize) -> Option<f64> {
+    let letters = chars.iter().filter(|c| is_latin_letter(**c)).count();
+    if letters < min_letters.max(1) {
+        return None;
+    }
+    let vowels = chars.iter().filter(|c| is_latin_vowel(**c)).count();
+    Some(vowels as f64 / letters as f64)
+}
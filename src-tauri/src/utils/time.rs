@@ -0,0 +1,305 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{FixedOffset, NaiveDate, Offset, TimeZone, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// SQL expression for an activity row's local wall-clock datetime, using the
+/// offset recorded at capture time and falling back to the current system zone
+/// for rows captured before offsets were stored.
+pub const ROW_LOCAL_DATETIME_SQL: &str = "CASE WHEN tz_offset_minutes IS NULL \
+     THEN datetime(start_time, 'unixepoch', 'localtime') \
+     ELSE datetime(start_time + tz_offset_minutes * 60, 'unixepoch') END";
+
+#[derive(Debug, Clone)]
+enum TimezoneMode {
+    System,
+    Fixed(chrono_tz::Tz),
+}
+
+fn timezone_mode_store() -> &'static Mutex<TimezoneMode> {
+    static STORE: OnceLock<Mutex<TimezoneMode>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(TimezoneMode::System))
+}
+
+/// Apply `settings.general.timezone_mode` / `fixed_timezone`.
+/// Unknown zone names fall back to the system zone.
+pub fn set_timezone_mode(mode: &str, fixed_timezone: &str) {
+    let resolved = if mode.eq_ignore_ascii_case("fixed") {
+        match fixed_timezone.trim().parse::<chrono_tz::Tz>() {
+            Ok(tz) => TimezoneMode::Fixed(tz),
+            Err(_) => {
+                println!("[Time] Unknown fixed timezone '{}', using system zone", fixed_timezone);
+                TimezoneMode::System
+            }
+        }
+    } else {
+        TimezoneMode::System
+    };
+    if let Ok(mut store) = timezone_mode_store().lock() {
+        *store = resolved;
+    }
+}
+
+static USE_24_HOUR: AtomicBool = AtomicBool::new(false);
+
+/// Apply `settings.general.time_format`; anything but "24h" keeps 12-hour output.
+pub fn set_time_format(format: &str) {
+    USE_24_HOUR.store(format.trim().eq_ignore_ascii_case("24h"), Ordering::Relaxed);
+}
+
+fn time_pattern(with_seconds: bool) -> &'static str {
+    match (USE_24_HOUR.load(Ordering::Relaxed), with_seconds) {
+        (true, false) => "%H:%M",
+        (true, true) => "%H:%M:%S",
+        (false, false) => "%I:%M %p",
+        (false, true) => "%I:%M:%S %p",
+    }
+}
+
+/// Offset of the effective zone at `ts` (fixed zone, else the system zone's rules).
+pub fn offset_at(ts: i64) -> FixedOffset {
+    match current_mode() {
+        TimezoneMode::Fixed(tz) => tz.timestamp_opt(ts, 0).single().map(|dt| dt.offset().fix()),
+        TimezoneMode::System => chrono::Local.timestamp_opt(ts, 0).single().map(|dt| *dt.offset()),
+    }
+    .unwrap_or_else(current_offset)
+}
+
+fn format_local(ts: i64, date_pattern: Option<&str>, with_seconds: bool) -> Option<String> {
+    let dt = chrono::DateTime::from_timestamp(ts, 0)?;
+    let pattern = match date_pattern {
+        Some(date) => format!("{} {}", date, time_pattern(with_seconds)),
+        None => time_pattern(with_seconds).to_string(),
+    };
+    Some(dt.with_timezone(&offset_at(ts)).format(&pattern).to_string())
+}
+
+/// Wall-clock time of `ts` in the configured zone and 12h/24h format, e.g. "02:30 PM" or "14:30".
+pub fn format_local_time(ts: i64) -> Option<String> {
+    format_local(ts, None, false)
+}
+
+/// `format_local_time` prefixed with a strftime date, e.g. `format_local_datetime(ts, "%b %d, %Y")`.
+pub fn format_local_datetime(ts: i64, date_pattern: &str) -> Option<String> {
+    format_local(ts, Some(date_pattern), false)
+}
+
+/// Like `format_local_datetime`, with seconds.
+pub fn format_local_datetime_secs(ts: i64, date_pattern: &str) -> Option<String> {
+    format_local(ts, Some(date_pattern), true)
+}
+
+fn current_mode() -> TimezoneMode {
+    timezone_mode_store()
+        .lock()
+        .map(|m| m.clone())
+        .unwrap_or(TimezoneMode::System)
+}
+
+/// "system" or the IANA name of the fixed zone, for display.
+pub fn timezone_mode_label() -> String {
+    match current_mode() {
+        TimezoneMode::Fixed(tz) => tz.name().to_string(),
+        TimezoneMode::System => "system".to_string(),
+    }
+}
+
+/// Offset of the system zone right now. This is what gets stored on each activity row.
+pub fn system_offset_minutes() -> i32 {
+    chrono::Local::now().offset().local_minus_utc() / 60
+}
+
+/// Offset used for "now" (relative ranges like "last 3 hours" or "this week").
+pub fn current_offset() -> FixedOffset {
+    match current_mode() {
+        TimezoneMode::Fixed(tz) => Utc::now().with_timezone(&tz).offset().fix(),
+        TimezoneMode::System => *chrono::Local::now().offset(),
+    }
+}
+
+/// Calendar date of "today" in the effective zone.
+pub fn today() -> NaiveDate {
+    Utc::now().with_timezone(&current_offset()).date_naive()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayBounds {
+    pub date_key: String,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub offset_minutes: i32,
+    /// "fixed", "stored" (dominant offset recorded on that day's rows) or "system".
+    pub offset_source: String,
+}
+
+/// Resolve the offset in effect on `date`.
+/// Fixed mode always wins; otherwise prefer the offset most rows on that
+/// date were captured with, so past days keep their bounds after travel.
+pub fn offset_for_date(conn: Option<&Connection>, date: NaiveDate) -> (FixedOffset, &'static str) {
+    let noon = date.and_hms_opt(12, 0, 0).expect("valid noon");
+    if let TimezoneMode::Fixed(tz) = current_mode() {
+        let offset = tz
+            .from_local_datetime(&noon)
+            .earliest()
+            .map(|dt| dt.offset().fix())
+            .unwrap_or_else(|| Utc.fix());
+        return (offset, "fixed");
+    }
+
+    if let Some(minutes) = conn.and_then(|c| dominant_stored_offset(c, date)) {
+        if let Some(offset) = FixedOffset::east_opt(minutes * 60) {
+            return (offset, "stored");
+        }
+    }
+
+    let offset = chrono::Local
+        .from_local_datetime(&noon)
+        .earliest()
+        .map(|dt| *dt.offset())
+        .unwrap_or_else(|| *chrono::Local::now().offset());
+    (offset, "system")
+}
+
+/// First instant of `date` in `tz`: local midnight, or the end of the gap
+/// when a DST change skips midnight.
+fn day_start_in<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> Option<i64> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    (0..=3).find_map(|hour| {
+        tz.from_local_datetime(&(midnight + chrono::Duration::hours(hour)))
+            .earliest()
+            .map(|dt| dt.timestamp())
+    })
+}
+
+/// Bounds `[start, end)` of a calendar day in `tz`, from its local midnight to
+/// the next one, so DST days last 23 or 25 hours.
+pub fn day_bounds_in<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> (i64, i64) {
+    let start = day_start_in(date, tz)
+        .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).expect("valid midnight").and_utc().timestamp());
+    let end = date
+        .succ_opt()
+        .and_then(|next| day_start_in(next, tz))
+        .unwrap_or(start + 24 * 3600);
+    (start, end)
+}
+
+pub fn day_bounds(conn: Option<&Connection>, date: NaiveDate) -> DayBounds {
+    let (offset, source) = offset_for_date(conn, date);
+    let (start_ts, end_ts) = match current_mode() {
+        TimezoneMode::Fixed(tz) => day_bounds_in(date, &tz),
+        // Offsets recorded on the rows can change from one day to the next
+        // (DST, travel), so the day ends where the next one starts.
+        TimezoneMode::System if source == "stored" => {
+            let start = day_bounds_in(date, &offset).0;
+            let end = date
+                .succ_opt()
+                .map(|next| day_bounds_in(next, &offset_for_date(conn, next).0).0)
+                .filter(|end| *end > start)
+                .unwrap_or(start + 24 * 3600);
+            (start, end)
+        }
+        TimezoneMode::System => day_bounds_in(date, &chrono::Local),
+    };
+    DayBounds {
+        date_key: date.format("%Y-%m-%d").to_string(),
+        start_ts,
+        end_ts,
+        offset_minutes: offset.local_minus_utc() / 60,
+        offset_source: source.to_string(),
+    }
+}
+
+/// Offsets recorded on rows whose local date is `date`, most frequent first.
+pub fn stored_offsets_for_date(conn: &Connection, date: NaiveDate) -> Vec<(i32, i64)> {
+    let midnight_utc = date
+        .and_hms_opt(0, 0, 0)
+        .expect("valid midnight")
+        .and_utc()
+        .timestamp();
+    // UTC offsets range from -12h to +14h, so this window covers every zone's version of the day.
+    let scan_start = midnight_utc - 14 * 3600;
+    let scan_end = midnight_utc + 36 * 3600;
+    let date_key = date.format("%Y-%m-%d").to_string();
+
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT tz_offset_minutes, COUNT(*) as cnt
+         FROM activities
+         WHERE tz_offset_minutes IS NOT NULL
+           AND start_time >= ?1 AND start_time < ?2
+           AND date(start_time + tz_offset_minutes * 60, 'unixepoch') = ?3
+         GROUP BY tz_offset_minutes
+         ORDER BY cnt DESC",
+    ) else {
+        return Vec::new();
+    };
+    stmt.query_map(rusqlite::params![scan_start, scan_end, date_key], |row| {
+        Ok((row.get::<_, i32>(0)?, row.get::<_, i64>(1)?))
+    })
+    .map(|rows| rows.filter_map(|r| r.ok()).collect())
+    .unwrap_or_default()
+}
+
+fn dominant_stored_offset(conn: &Connection, date: NaiveDate) -> Option<i32> {
+    stored_offsets_for_date(conn, date).first().map(|(minutes, _)| *minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn hours((start, end): (i64, i64)) -> i64 {
+        (end - start) / 3600
+    }
+
+    #[test]
+    fn dst_days_end_at_the_next_local_midnight() {
+        let berlin = chrono_tz::Europe::Berlin;
+        assert_eq!(hours(day_bounds_in(date(2026, 3, 29), &berlin)), 23);
+        assert_eq!(hours(day_bounds_in(date(2026, 10, 25), &berlin)), 25);
+        assert_eq!(hours(day_bounds_in(date(2026, 6, 1), &berlin)), 24);
+
+        let (start, end) = day_bounds_in(date(2026, 3, 29), &berlin);
+        assert_eq!(start, day_bounds_in(date(2026, 3, 28), &berlin).1);
+        assert_eq!(end, day_bounds_in(date(2026, 3, 30), &berlin).0);
+        // 2026-03-29 00:00 CET is 2026-03-28 23:00 UTC.
+        assert_eq!(start, date(2026, 3, 28).and_hms_opt(23, 0, 0).unwrap().and_utc().timestamp());
+    }
+
+    #[test]
+    fn a_skipped_midnight_starts_the_day_after_the_gap() {
+        // Until 2019 Brazil sprang forward at midnight, so 2018-11-04 00:00 never happened.
+        let sao_paulo = chrono_tz::America::Sao_Paulo;
+        let (start, end) = day_bounds_in(date(2018, 11, 4), &sao_paulo);
+        assert_eq!(start, date(2018, 11, 4).and_hms_opt(3, 0, 0).unwrap().and_utc().timestamp());
+        assert_eq!(hours((start, end)), 23);
+    }
+
+    #[test]
+    fn a_zone_change_between_days_leaves_no_gap_or_overlap() {
+        let conn = crate::database::test_connection();
+        // Noon local time on each day: UTC+2 on the 10th, then UTC-5 after flying west.
+        for (utc_noon, offset) in [("2026-03-10 10:00:00", 120), ("2026-03-11 17:00:00", -300)] {
+            conn.execute(
+                "INSERT INTO activities (app_name, app_hash, window_title, category_id, start_time, end_time, duration_seconds, tz_offset_minutes)
+                 VALUES ('App', 1, 'w', 1, strftime('%s', ?1), strftime('%s', ?1) + 60, 60, ?2)",
+                rusqlite::params![utc_noon, offset],
+            )
+            .unwrap();
+        }
+
+        let first = day_bounds(Some(&conn), date(2026, 3, 10));
+        let second = day_bounds(Some(&conn), date(2026, 3, 11));
+        assert_eq!(first.offset_source, "stored");
+        assert_eq!(second.offset_source, "stored");
+        assert_eq!(first.start_ts, date(2026, 3, 9).and_hms_opt(22, 0, 0).unwrap().and_utc().timestamp());
+        assert_eq!(first.end_ts, second.start_ts);
+        assert_eq!(second.start_ts, date(2026, 3, 11).and_hms_opt(5, 0, 0).unwrap().and_utc().timestamp());
+        assert_eq!(hours((first.start_ts, first.end_ts)), 31);
+    }
+}
@@ -3,9 +3,51 @@ use winapi::shared::windef::HWND;
 use winapi::um::winuser::{
     EnumWindows, GetWindowTextW, GetWindowTextLengthW, IsWindowVisible, 
 };
+use std::sync::{Mutex, OnceLock};
 
+/// Most background window titles kept per snapshot.
+pub const MAX_BACKGROUND_WINDOWS: usize = 20;
 
-/// Get a list of titles for all currently visible windows
+fn background_denylist_store() -> &'static Mutex<Vec<String>> {
+    static STORE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(crate::models::settings::default_background_window_denylist()))
+}
+
+/// Apply `settings.tracking.background_window_denylist`.
+pub fn set_background_window_denylist(titles: &[String]) {
+    if let Ok(mut store) = background_denylist_store().lock() {
+        *store = titles.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()).collect();
+    }
+}
+
+/// Shell and overlay windows that are always "open" and say nothing about the user.
+pub fn is_background_noise(title: &str) -> bool {
+    let title = title.trim();
+    title.is_empty()
+        || background_denylist_store()
+            .lock()
+            .map(|list| list.iter().any(|denied| title.eq_ignore_ascii_case(denied)))
+            .unwrap_or(false)
+}
+
+/// Drop noise and duplicates, keep the topmost `MAX_BACKGROUND_WINDOWS` (input is in
+/// z-order) and sort them.
+fn clean_background_windows(titles: Vec<String>) -> Vec<String> {
+    let mut kept: Vec<String> = Vec::new();
+    for title in titles {
+        if kept.len() >= MAX_BACKGROUND_WINDOWS {
+            break;
+        }
+        if !is_background_noise(&title) && !kept.contains(&title) {
+            kept.push(title);
+        }
+    }
+    kept.sort();
+    kept
+}
+
+/// Titles of the visible windows, topmost first, without noise (see `is_background_noise`)
+/// and capped at `MAX_BACKGROUND_WINDOWS`.
 pub fn get_open_windows() -> Vec<String> {
 
 
@@ -22,10 +64,7 @@ pub fn get_open_windows() -> Vec<String> {
                         let mut buf = vec![0u16; (len + 1) as usize];
                         let copied = GetWindowTextW(hwnd, buf.as_mut_ptr(), buf.len() as i32);
                         if copied > 0 {
-                            let title = String::from_utf16_lossy(&buf[..copied as usize]);
-                            if !title.trim().is_empty() && title != "Program Manager" {
-                                titles.push(title);
-                            }
+                            titles.push(String::from_utf16_lossy(&buf[..copied as usize]));
                         }
                     }
                 }
@@ -36,11 +75,7 @@ pub fn get_open_windows() -> Vec<String> {
         EnumWindows(Some(enum_window_callback), &mut titles as *mut _ as LPARAM);
     }
     
-    // Sort and deduplicate
-    titles.sort();
-    titles.dedup();
-    
-    titles
+    clean_background_windows(titles)
 }
 
 pub fn get_media_info() -> Option<crate::models::activity::MediaInfo> {
@@ -64,6 +99,11 @@ pub fn get_media_info() -> Option<crate::models::activity::MediaInfo> {
     let props = session.TryGetMediaPropertiesAsync().ok()?.get().ok()?;
     let title = props.Title().ok()?.to_string();
     let artist = props.Artist().ok()?.to_string();
+    let source_app = session
+        .SourceAppUserModelId()
+        .ok()
+        .map(|id| id.to_string())
+        .filter(|id| !id.is_empty());
 
     if title.is_empty() {
         return None;
@@ -73,5 +113,250 @@ pub fn get_media_info() -> Option<crate::models::activity::MediaInfo> {
         title,
         artist,
         status: status_str.to_string(),
+        source_app,
     })
 }
+
+/// Save the executable's main icon as a PNG at `out_path`.
+pub fn extract_exe_icon_png(exe_path: &std::path::Path, out_path: &std::path::Path) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::shared::windef::HICON;
+    use winapi::um::shellapi::ExtractIconExW;
+    use winapi::um::winuser::DestroyIcon;
+
+    let wide: Vec<u16> = exe_path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let (width, height, pixels) = unsafe {
+        let mut icon: HICON = std::ptr::null_mut();
+        let count = ExtractIconExW(wide.as_ptr(), 0, &mut icon, std::ptr::null_mut(), 1);
+        if count == 0 || icon.is_null() {
+            return Err(format!("No icon found in {}", exe_path.display()));
+        }
+        let result = icon_to_rgba(icon);
+        DestroyIcon(icon);
+        result?
+    };
+
+    let image = image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| "Icon bitmap has an unexpected size".to_string())?;
+    image.save(out_path).map_err(|e| e.to_string())
+}
+
+/// Top-down RGBA pixels of an icon's color bitmap.
+unsafe fn icon_to_rgba(icon: winapi::shared::windef::HICON) -> Result<(u32, u32, Vec<u8>), String> {
+    use winapi::um::wingdi::DeleteObject;
+    use winapi::um::winuser::{GetIconInfo, ICONINFO};
+
+    let mut info: ICONINFO = std::mem::zeroed();
+    if GetIconInfo(icon, &mut info) == 0 {
+        return Err("GetIconInfo failed".to_string());
+    }
+    let result = if info.hbmColor.is_null() {
+        Err("Monochrome icons are not supported".to_string())
+    } else {
+        bitmap_to_rgba(info.hbmColor)
+    };
+    // GetIconInfo hands out copies of both bitmaps; the caller owns them.
+    if !info.hbmColor.is_null() {
+        DeleteObject(info.hbmColor as _);
+    }
+    if !info.hbmMask.is_null() {
+        DeleteObject(info.hbmMask as _);
+    }
+    result
+}
+
+unsafe fn bitmap_to_rgba(bitmap: winapi::shared::windef::HBITMAP) -> Result<(u32, u32, Vec<u8>), String> {
+    use winapi::um::wingdi::{GetDIBits, GetObjectW, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS};
+    use winapi::um::winuser::{GetDC, ReleaseDC};
+
+    let mut bmp: BITMAP = std::mem::zeroed();
+    if GetObjectW(bitmap as _, std::mem::size_of::<BITMAP>() as i32, &mut bmp as *mut _ as *mut _) == 0 {
+        return Err("GetObject failed for icon bitmap".to_string());
+    }
+    let (width, height) = (bmp.bmWidth, bmp.bmHeight);
+    if width <= 0 || height <= 0 {
+        return Err("Icon bitmap is empty".to_string());
+    }
+
+    let mut header: BITMAPINFO = std::mem::zeroed();
+    header.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+    header.bmiHeader.biWidth = width;
+    header.bmiHeader.biHeight = -height; // negative: rows top-down
+    header.bmiHeader.biPlanes = 1;
+    header.bmiHeader.biBitCount = 32;
+    header.bmiHeader.biCompression = BI_RGB;
+
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    let dc = GetDC(std::ptr::null_mut());
+    let lines = GetDIBits(
+        dc,
+        bitmap,
+        0,
+        height as u32,
+        pixels.as_mut_ptr() as *mut _,
+        &mut header,
+        DIB_RGB_COLORS,
+    );
+    ReleaseDC(std::ptr::null_mut(), dc);
+    if lines == 0 {
+        return Err("GetDIBits failed for icon bitmap".to_string());
+    }
+
+    // BGRA -> RGBA. Old-style icons carry no alpha at all; show those opaque.
+    let has_alpha = pixels.chunks_exact(4).any(|px| px[3] != 0);
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        if !has_alpha {
+            px[3] = 255;
+        }
+    }
+    Ok((width as u32, height as u32, pixels))
+}
+
+/// One process from `snapshot_processes`.
+#[derive(Debug, Clone)]
+pub struct ProcessSnapshotEntry {
+    pub pid: u32,
+    pub parent_pid: u32,
+    /// Executable file name, e.g. "Docker Desktop.exe".
+    pub name: String,
+    /// Creation time (unix seconds); only read for processes `with_start_time`
+    /// accepts, and `None` when the process can't be opened.
+    pub start_time: Option<i64>,
+}
+
+/// All running processes. Reading creation times opens each process, so it
+/// is limited to the names `with_start_time` accepts.
+pub fn snapshot_processes(with_start_time: &dyn Fn(&str) -> bool) -> Vec<ProcessSnapshotEntry> {
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::tlhelp32::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS};
+
+    let mut processes = Vec::new();
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return processes;
+        }
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        let mut ok = Process32FirstW(snapshot, &mut entry);
+        while ok != 0 {
+            let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+            let start_time = if with_start_time(&name) {
+                process_start_time(entry.th32ProcessID)
+            } else {
+                None
+            };
+            processes.push(ProcessSnapshotEntry {
+                pid: entry.th32ProcessID,
+                parent_pid: entry.th32ParentProcessID,
+                name,
+                start_time,
+            });
+            ok = Process32NextW(snapshot, &mut entry);
+        }
+        CloseHandle(snapshot);
+    }
+    processes
+}
+
+/// Creation time of a process in unix seconds.
+fn process_start_time(pid: u32) -> Option<i64> {
+    use winapi::shared::minwindef::FILETIME;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{GetProcessTimes, OpenProcess};
+    use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+
+    // 100ns ticks between 1601-01-01 (FILETIME epoch) and 1970-01-01.
+    const UNIX_EPOCH_TICKS: u64 = 116_444_736_000_000_000;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let mut created: FILETIME = std::mem::zeroed();
+        let mut exited: FILETIME = std::mem::zeroed();
+        let mut kernel: FILETIME = std::mem::zeroed();
+        let mut user: FILETIME = std::mem::zeroed();
+        let ok = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user);
+        CloseHandle(handle);
+        if ok == 0 {
+            return None;
+        }
+        let ticks = ((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64;
+        ticks.checked_sub(UNIX_EPOCH_TICKS).map(|t| (t / 10_000_000) as i64)
+    }
+}
+
+/// Full path registered for `exe` under `App Paths` (per-user first, then
+/// machine-wide), the same lookup the shell's Run dialog uses.
+pub fn app_paths_lookup(exe: &str) -> Option<String> {
+    use winapi::shared::minwindef::HKEY;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::winreg::{RegGetValueW, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_EXPAND_SZ, RRF_RT_REG_SZ};
+
+    let subkey = format!("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths\\{}", exe);
+    let wide: Vec<u16> = subkey.encode_utf16().chain(Some(0)).collect();
+    let roots: [HKEY; 2] = [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE];
+
+    for root in roots {
+        let mut buf = vec![0u16; 1024];
+        let mut size = (buf.len() * 2) as u32;
+        let status = unsafe {
+            RegGetValueW(
+                root,
+                wide.as_ptr(),
+                std::ptr::null(),
+                RRF_RT_REG_SZ | RRF_RT_REG_EXPAND_SZ,
+                std::ptr::null_mut(),
+                buf.as_mut_ptr() as *mut _,
+                &mut size,
+            )
+        };
+        if status as u32 != ERROR_SUCCESS {
+            continue;
+        }
+        let len = (size as usize / 2).min(buf.len());
+        let end = buf[..len].iter().position(|&c| c == 0).unwrap_or(len);
+        // Values are often written quoted.
+        let path = String::from_utf16_lossy(&buf[..end]).trim().trim_matches('"').to_string();
+        if !path.is_empty() {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Whether a window rect covers the whole monitor it sits on (borderless
+/// fullscreen video or games); a maximized window leaves the taskbar visible.
+pub fn covers_monitor(x: f64, y: f64, width: f64, height: f64) -> bool {
+    use winapi::shared::windef::POINT;
+    use winapi::um::winuser::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONULL};
+
+    if width <= 0.0 || height <= 0.0 {
+        return false;
+    }
+    unsafe {
+        let center = POINT {
+            x: (x + width / 2.0) as i32,
+            y: (y + height / 2.0) as i32,
+        };
+        let monitor = MonitorFromPoint(center, MONITOR_DEFAULTTONULL);
+        if monitor.is_null() {
+            return false;
+        }
+        let mut info: MONITORINFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if GetMonitorInfoW(monitor, &mut info) == 0 {
+            return false;
+        }
+        let rect = info.rcMonitor;
+        // Allow a pixel of rounding on scaled displays.
+        x <= rect.left as f64 + 1.0
+            && y <= rect.top as f64 + 1.0
+            && x + width >= rect.right as f64 - 1.0
+            && y + height >= rect.bottom as f64 - 1.0
+    }
+}
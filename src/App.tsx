@@ -6,6 +6,9 @@ import { Timeline } from './components/Timeline/Timeline';
 import { PersonalDashboard } from './components/Dashboard/PersonalDashboard';
 import { SettingsModal } from './components/Settings/SettingsModal';
 import { AppShell } from './components/Layout/AppShell';
+import { LockScreen } from './components/Layout/LockScreen';
+import { isAppLocked } from './services/tauri';
+import type { AppNotification } from './types';
 
 export type PageType = 'home' | 'chat' | 'timeline' | 'workflows' | 'settings';
 const PAGE_STORAGE_KEY = 'intentflow_active_page';
@@ -27,6 +30,9 @@ function App() {
   const [sidebarOpen, setSidebarOpen] = useState(false);
   const [settingsOpen, setSettingsOpen] = useState(false);
   const [chatPrompt, setChatPrompt] = useState<string | undefined>();
+  const [chatFocusRequest, setChatFocusRequest] = useState(0);
+  // Assume locked until the backend says otherwise, so nothing flashes before the check.
+  const [locked, setLocked] = useState(true);
 
   const handleNavigate = (page: PageType) => {
     if (page === 'settings') {
@@ -44,6 +50,30 @@ function App() {
     setActivePage('chat');
   };
 
+  // App lock: the window may have been revealed before these listeners existed, so ask once on load too.
+  useEffect(() => {
+    let unlistenRequired: (() => void) | undefined;
+    let unlistenUnlocked: (() => void) | undefined;
+    const setup = async () => {
+      unlistenRequired = await listen('lock://required', () => {
+        setLocked(true);
+        setSettingsOpen(false);
+      });
+      unlistenUnlocked = await listen<string | null>('lock://unlocked', (event) => {
+        setLocked(false);
+        if (event.payload === 'home' || event.payload === 'chat') {
+          setActivePage(event.payload);
+        }
+      });
+      setLocked(await isAppLocked().catch(() => false));
+    };
+    setup();
+    return () => {
+      if (unlistenRequired) unlistenRequired();
+      if (unlistenUnlocked) unlistenUnlocked();
+    };
+  }, []);
+
   useEffect(() => {
     let unlisten: (() => void) | undefined;
     const setup = async () => {
@@ -65,6 +95,40 @@ function App() {
     };
   }, []);
 
+  // Quick-chat hotkey: the chat page may not be mounted yet, so the request is passed down as a counter.
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    const setup = async () => {
+      unlisten = await listen('chat://focus-input', () => {
+        setActivePage('chat');
+        setChatFocusRequest((n) => n + 1);
+      });
+    };
+    setup();
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    const setup = async () => {
+      unlisten = await listen<AppNotification>('notification://show', async (event) => {
+        if (typeof Notification === 'undefined') return;
+        if (Notification.permission === 'default') {
+          await Notification.requestPermission();
+        }
+        if (Notification.permission === 'granted') {
+          new Notification(event.payload.title, { body: event.payload.body });
+        }
+      });
+    };
+    setup();
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
   useEffect(() => {
     if (activePage === 'settings') return;
     try {
@@ -74,6 +138,10 @@ function App() {
     }
   }, [activePage]);
 
+  if (locked) {
+    return <LockScreen onUnlocked={() => setLocked(false)} />;
+  }
+
   return (
     <>
       <AppShell
@@ -89,7 +157,7 @@ function App() {
           />
         )}
         {activePage === 'chat' && (
-          <ChatPage initialPrompt={chatPrompt} />
+          <ChatPage initialPrompt={chatPrompt} focusRequest={chatFocusRequest} />
         )}
         {activePage === 'timeline' && (
           <div className="max-w-5xl mx-auto px-6 py-8">
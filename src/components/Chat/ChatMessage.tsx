@@ -1,8 +1,9 @@
 import { useEffect, useState } from 'react';
 import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
-import type { ChatMessage as ChatMessageType, AgentStep, ActivityRef } from '../../types';
-import { formatTime } from '../../lib/utils';
+import type { ChatMessage as ChatMessageType, AgentStep, ActivityRef, ActivityCapture, TimeAccounting, TimeShare } from '../../types';
+import { formatTime, formatDateTime, formatUsage, formatDuration } from '../../lib/utils';
+import { getActivityOcr } from '../../services/tauri';
 import {
     ChevronDown,
     ChevronRight,
@@ -11,6 +12,8 @@ import {
     Monitor,
     Clock,
     Brain,
+    X,
+    AlertTriangle,
 } from 'lucide-react';
 
 interface ChatMessageProps {
@@ -21,6 +24,7 @@ interface ChatMessageProps {
 export function ChatMessage({ message, isStreaming = false }: ChatMessageProps) {
     const [showSteps, setShowSteps] = useState(false);
     const [showThinking, setShowThinking] = useState(false);
+    const [citedId, setCitedId] = useState<number | null>(null);
     const [displayedText, setDisplayedText] = useState('');
     const isUser = message.role === 'user';
     const hasSteps = message.tool_calls && message.tool_calls.length > 0;
@@ -87,12 +91,24 @@ export function ChatMessage({ message, isStreaming = false }: ChatMessageProps)
                         : 'bg-dark-800 text-dark-100 rounded-bl-md border border-dark-700'
                         }`}
                 >
-                    <MarkdownMessage text={isStreaming && !isUser ? displayedText : bubbleText} />
+                    <MarkdownMessage
+                        text={isStreaming && !isUser ? displayedText : bubbleText}
+                        onCite={isUser ? undefined : (id) => setCitedId(citedId === id ? null : id)}
+                    />
                     {isStreaming && !isUser && (
                         <span className="inline-block w-[6px] h-[1em] ml-0.5 align-[-2px] bg-dark-300 animate-pulse" />
                     )}
                 </div>
 
+                {/* Cited activity */}
+                {citedId !== null && (
+                    <CitationPanel
+                        activityId={citedId}
+                        activity={message.activities?.find((act) => act.id === citedId)}
+                        onClose={() => setCitedId(null)}
+                    />
+                )}
+
                 {/* Agent steps toggle */}
                 {hasSteps && (
                     <div className="mt-2">
@@ -119,6 +135,22 @@ export function ChatMessage({ message, isStreaming = false }: ChatMessageProps)
                     </div>
                 )}
 
+                {/* Computed time accounting */}
+                {!isUser && message.time_accounting && (
+                    <TimeAccountingTable accounting={message.time_accounting} />
+                )}
+
+                {!isUser && message.confidence_factors && message.confidence_factors.length > 0 && (
+                    <div className="mt-2 space-y-1">
+                        {message.confidence_factors.map((factor, i) => (
+                            <div key={i} className="flex items-start gap-1.5 text-[11px] text-amber-400">
+                                <AlertTriangle className="w-3 h-3 mt-0.5 shrink-0" />
+                                <span>{factor}</span>
+                            </div>
+                        ))}
+                    </div>
+                )}
+
                 {/* Activity references */}
                 {hasActivities && (
                     <div className="mt-3 space-y-1.5">
@@ -139,13 +171,71 @@ export function ChatMessage({ message, isStreaming = false }: ChatMessageProps)
                 {/* Timestamp */}
                 <p className={`text-[10px] mt-1 ${isUser ? 'text-right text-primary-300' : 'text-dark-500'}`}>
                     {formatTime(message.created_at)}
+                    {!isUser && message.usage && message.usage.calls > 0 && (
+                        <span title={`${message.usage.calls} LLM call(s)`}> · {formatUsage(message.usage)}</span>
+                    )}
                 </p>
             </div>
         </div>
     );
 }
 
-function MarkdownMessage({ text }: { text: string }) {
+function TimeAccountingTable({ accounting }: { accounting: TimeAccounting }) {
+    const totals: [string, number][] = [
+        ['Span', accounting.span_seconds],
+        ['Tracked', accounting.tracked_seconds],
+        ['Idle', accounting.idle_seconds],
+        ['Untracked', accounting.untracked_seconds],
+    ];
+    return (
+        <div className="mt-3 bg-dark-900/60 border border-dark-700 rounded-lg p-2.5 text-xs">
+            <p className="text-dark-500 font-medium uppercase tracking-wide mb-1.5">
+                Time accounting · {accounting.scope_label}
+            </p>
+            <div className="grid grid-cols-4 gap-2 mb-2">
+                {totals.map(([label, seconds]) => (
+                    <div key={label}>
+                        <p className="text-[10px] text-dark-500">{label}</p>
+                        <p className="text-dark-200 font-medium">{formatDuration(seconds)}</p>
+                    </div>
+                ))}
+            </div>
+            <TimeShareTable label="Categories" rows={accounting.categories} />
+            <TimeShareTable label="Top apps" rows={accounting.top_apps} />
+        </div>
+    );
+}
+
+function TimeShareTable({ label, rows }: { label: string; rows: TimeShare[] }) {
+    if (rows.length === 0) return null;
+    return (
+        <table className="w-full mt-1">
+            <thead>
+                <tr className="text-[10px] text-dark-500 text-left">
+                    <th className="font-normal">{label}</th>
+                    <th className="font-normal text-right">Time</th>
+                    <th className="font-normal text-right w-12">%</th>
+                </tr>
+            </thead>
+            <tbody>
+                {rows.map((row) => (
+                    <tr key={row.name} className="text-dark-300">
+                        <td className="truncate max-w-[12rem]">{row.name}</td>
+                        <td className="text-right">{formatDuration(row.seconds)}</td>
+                        <td className="text-right">{row.percent.toFixed(0)}%</td>
+                    </tr>
+                ))}
+            </tbody>
+        </table>
+    );
+}
+
+/** `[#1234]` citation tags from the agent, rewritten as in-page links handled by `onCite`. */
+const CITATION_PATTERN = /\[#(\d+)\](?!\()/g;
+const CITATION_HREF_PREFIX = '#activity-';
+
+function MarkdownMessage({ text, onCite }: { text: string; onCite?: (activityId: number) => void }) {
+    const source = onCite ? text.replace(CITATION_PATTERN, `[#$1](${CITATION_HREF_PREFIX}$1)`) : text;
     return (
         <ReactMarkdown
             remarkPlugins={[remarkGfm]}
@@ -160,11 +250,20 @@ function MarkdownMessage({ text }: { text: string }) {
                 ul: ({ children }) => <ul className="list-disc pl-5 text-sm my-1 space-y-1">{children}</ul>,
                 ol: ({ children }) => <ol className="list-decimal pl-5 text-sm my-1 space-y-1">{children}</ol>,
                 li: ({ children }) => <li>{children}</li>,
-                a: ({ href, children }) => (
-                    <a href={href} target="_blank" rel="noreferrer" className="text-primary-300 underline hover:text-primary-200">
-                        {children}
-                    </a>
-                ),
+                a: ({ href, children }) =>
+                    onCite && href?.startsWith(CITATION_HREF_PREFIX) ? (
+                        <button
+                            type="button"
+                            onClick={() => onCite(Number(href.slice(CITATION_HREF_PREFIX.length)))}
+                            className="text-[11px] font-mono text-primary-300 hover:text-primary-200 hover:underline"
+                        >
+                            {children}
+                        </button>
+                    ) : (
+                        <a href={href} target="_blank" rel="noreferrer" className="text-primary-300 underline hover:text-primary-200">
+                            {children}
+                        </a>
+                    ),
                 strong: ({ children }) => <strong className="font-semibold text-white">{children}</strong>,
                 em: ({ children }) => <em className="italic">{children}</em>,
                 code: ({ className, children }) =>
@@ -191,7 +290,7 @@ function MarkdownMessage({ text }: { text: string }) {
                 ),
             }}
         >
-            {text}
+            {source}
         </ReactMarkdown>
     );
 }
@@ -506,3 +605,69 @@ function ActivityCard({ activity }: { activity: ActivityRef }) {
         </div>
     );
 }
+
+function CitationPanel({
+    activityId,
+    activity,
+    onClose,
+}: {
+    activityId: number;
+    activity?: ActivityRef;
+    onClose: () => void;
+}) {
+    const [capture, setCapture] = useState<ActivityCapture | null>(null);
+    const [loading, setLoading] = useState(true);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        let cancelled = false;
+        setLoading(true);
+        setError(null);
+        getActivityOcr(activityId)
+            .then((result) => {
+                if (cancelled) return;
+                setCapture(result);
+                if (!result) setError('This activity no longer exists.');
+            })
+            .catch((e) => {
+                if (!cancelled) setError(String(e));
+            })
+            .finally(() => {
+                if (!cancelled) setLoading(false);
+            });
+        return () => {
+            cancelled = true;
+        };
+    }, [activityId]);
+
+    return (
+        <div className="mt-2 bg-dark-900 border border-dark-700 rounded-lg p-3">
+            <div className="flex items-start justify-between gap-2">
+                <div className="min-w-0">
+                    <p className="text-xs text-dark-200 font-medium truncate">
+                        {activity ? activity.title || activity.app : `Activity #${activityId}`}
+                    </p>
+                    {activity && (
+                        <p className="text-[10px] text-dark-500">
+                            {activity.app} · {formatDateTime(activity.time)} · #{activityId}
+                        </p>
+                    )}
+                </div>
+                <button onClick={onClose} className="text-dark-500 hover:text-dark-200" title="Close">
+                    <X className="w-3.5 h-3.5" />
+                </button>
+            </div>
+            {loading ? (
+                <p className="mt-2 text-xs text-dark-500">Loading capture...</p>
+            ) : error ? (
+                <p className="mt-2 text-xs text-red-400">{error}</p>
+            ) : capture?.screen_text ? (
+                <pre className="mt-2 text-[11px] text-dark-300 bg-dark-950 rounded p-2 max-h-60 overflow-y-auto whitespace-pre-wrap">
+                    {capture.screen_text}
+                </pre>
+            ) : (
+                <p className="mt-2 text-xs text-dark-500">No screen text was captured for this activity.</p>
+            )}
+        </div>
+    );
+}
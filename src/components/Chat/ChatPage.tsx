@@ -1,11 +1,13 @@
 import { useState, useEffect, useRef } from 'react';
-import type { ChatSession, ChatMessage as ChatMessageType } from '../../types';
+import type { AgentAction, ChatSession, ChatMessage as ChatMessageType, ChatStatus, PipelineStepEvent, ToolProgress } from '../../types';
+import { formatUsage } from '../../lib/utils';
 import {
     createChatSession,
     getChatSessions,
     deleteChatSession,
     getChatMessages,
     sendChatMessage,
+    errorMessage,
 } from '../../services/tauri';
 import { ChatMessage } from './ChatMessage';
 import {
@@ -49,24 +51,28 @@ const TIME_RANGE_OPTIONS = [
 
 interface ChatPageProps {
     initialPrompt?: string;
+    /** Bumped by the quick-chat hotkey; each change focuses the input. */
+    focusRequest?: number;
 }
 
 const CHAT_MODEL_STORAGE_KEY = 'intentflow_chat_selected_model';
 
-interface ConfirmActionPayload {
-    kind: string;
-    reason: string;
-    suggested_time_range?: string;
-    enable_sources?: string[];
-    retry_message: string;
-}
+type ConfirmActionPayload = Extract<AgentAction, { kind: 'confirm_scope_or_sources' }>;
 
 interface ParsedAssistantAction {
     cleanedContent: string;
     action: ConfirmActionPayload | null;
 }
 
-function parseAssistantAction(content: string): ParsedAssistantAction {
+function parseAssistantAction(message: ChatMessageType): ParsedAssistantAction {
+    const content = message.content;
+    if ((message.response_version ?? 1) >= 2) {
+        const action = (message.actions || []).find(
+            (a): a is ConfirmActionPayload => a.kind === 'confirm_scope_or_sources'
+        );
+        return { cleanedContent: content, action: action ?? null };
+    }
+    // Older backends embedded the action as a marker in the answer text.
     const marker = /\[\[IF_ACTION:(\{[\s\S]*\})\]\]/m;
     const match = content.match(marker);
     if (!match) {
@@ -90,7 +96,7 @@ function loadSelectedModelFromStorage(): string {
     }
 }
 
-export function ChatPage({ initialPrompt }: ChatPageProps) {
+export function ChatPage({ initialPrompt, focusRequest }: ChatPageProps) {
     const [sessions, setSessions] = useState<ChatSession[]>([]);
     const [activeSessionId, setActiveSessionId] = useState<string | null>(null);
     const [messages, setMessages] = useState<ChatMessageType[]>([]);
@@ -113,6 +119,8 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
     );
     const [selectedTimeRange, setSelectedTimeRange] = useState('today');
     const [selectedModel, setSelectedModel] = useState<string>(loadSelectedModelFromStorage);
+    // An AI profile picked here replaces the model for this chat's messages.
+    const [selectedProfile, setSelectedProfile] = useState<string | null>(null);
     const [pendingAction, setPendingAction] = useState<ConfirmActionPayload | null>(null);
 
     // Hooks
@@ -178,6 +186,12 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
         }
     }, [initialPrompt]);
 
+    useEffect(() => {
+        if (focusRequest) {
+            inputRef.current?.focus();
+        }
+    }, [focusRequest]);
+
     // Load messages when active session changes
     useEffect(() => {
         if (activeSessionId) {
@@ -197,13 +211,32 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
     useEffect(() => {
         let unlistenToken: (() => void) | undefined;
         let unlistenStatus: (() => void) | undefined;
+        let unlistenPipeline: (() => void) | undefined;
+        let unlistenProgress: (() => void) | undefined;
         let unlistenDone: (() => void) | undefined;
         async function setupListener() {
             unlistenToken = await listen<string>('chat://token', (event) => {
                 setStreamingContent((prev) => prev + event.payload);
             });
-            unlistenStatus = await listen<string>('chat://status', (event) => {
-                setAgentStatus(event.payload || '');
+            unlistenStatus = await listen<ChatStatus>('chat://status', (event) => {
+                const { message, usage } = event.payload;
+                setAgentStatus(message && usage?.calls ? `${message} (${formatUsage(usage)})` : message || '');
+            });
+            unlistenPipeline = await listen<PipelineStepEvent>('chat://pipeline_step', (event) => {
+                const step = event.payload;
+                if (step.status === 'started') {
+                    setAgentStatus(`Step ${step.index}: ${step.label}...`);
+                } else if (step.status === 'completed') {
+                    setAgentStatus(`Step ${step.index}: ${step.label} (${step.rows} rows, ${(step.elapsed_ms / 1000).toFixed(1)}s)`);
+                }
+            });
+            unlistenProgress = await listen<ToolProgress>('chat://tool-progress', (event) => {
+                const progress = event.payload;
+                if (progress.phase === 'scanning') {
+                    setAgentStatus(`Running ${progress.tool}: ${progress.scanned} rows scanned, ${progress.matched} matches`);
+                } else if (progress.phase === 'call_done') {
+                    setAgentStatus(`Parallel search: ${progress.scanned} done (${progress.detail ?? 'call'}: ${progress.matched} items)`);
+                }
             });
             unlistenDone = await listen<string>('chat://done', () => {
                 setAgentStatus('');
@@ -214,6 +247,8 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
         return () => {
             if (unlistenToken) unlistenToken();
             if (unlistenStatus) unlistenStatus();
+            if (unlistenPipeline) unlistenPipeline();
+            if (unlistenProgress) unlistenProgress();
             if (unlistenDone) unlistenDone();
         };
     }, []);
@@ -317,16 +352,17 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
             const response = await sendChatMessage(
                 sessionId,
                 messageText.trim(),
-                selectedModel || undefined,
+                selectedProfile ? undefined : selectedModel || undefined,
                 overrides?.timeRange || selectedTimeRange,
-                overrides?.sources || selectedSources
+                overrides?.sources || selectedSources,
+                selectedProfile ?? undefined
             );
-            const { cleanedContent, action } = parseAssistantAction(response.content);
+            const { cleanedContent, action } = parseAssistantAction(response);
             const normalizedResponse: ChatMessageType = {
                 ...response,
                 content: cleanedContent || 'Please confirm the suggested scope/source update to continue.',
             };
-            if (selectedModel) {
+            if (selectedModel && !selectedProfile) {
                 const selected = favorites.find((f) => f.id === selectedModel);
                 addFavorite({ id: selectedModel, name: selected?.name || selectedModel });
             }
@@ -341,7 +377,7 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
                 id: Date.now() + 1,
                 session_id: sessionId,
                 role: 'assistant',
-                content: `Sorry, something went wrong: ${error}`,
+                content: `Sorry, something went wrong: ${errorMessage(error)}`,
                 created_at: Math.floor(Date.now() / 1000),
             };
             setMessages((prev) => [...prev, errorMsg]);
@@ -355,6 +391,15 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
 
     const handleSend = () => handleSendWithMessage(input);
 
+    // Quick replies for a clarifying question, offered only while it is the latest message.
+    const lastMessage = messages[messages.length - 1];
+    const clarification =
+        !isSending && lastMessage?.role === 'assistant'
+            ? lastMessage.actions?.find(
+                  (a): a is Extract<AgentAction, { kind: 'clarify' }> => a.kind === 'clarify'
+              )
+            : undefined;
+
     const handleKeyDown = (e: React.KeyboardEvent) => {
         if (e.key === 'Enter' && !e.shiftKey) {
             e.preventDefault();
@@ -415,6 +460,7 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
     };
 
     const getModelDisplayName = () => {
+        if (selectedProfile) return selectedProfile;
         if (!selectedModel) return 'Select Model';
         const fav = favorites.find((f) => f.id === selectedModel);
         if (fav) return fav.name;
@@ -507,13 +553,13 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
                                 favorites.map((model) => (
                                     <button
                                         key={model.id}
-                                        onClick={() => { setSelectedModel(model.id); addFavorite({ id: model.id, name: model.name }); setShowModelDropdown(false); }}
-                                        className={`w-full flex items-center gap-2 px-3 py-2 text-left text-sm hover:bg-dark-700/50 transition-colors ${selectedModel === model.id ? 'text-blue-400' : 'text-dark-200'
+                                        onClick={() => { setSelectedModel(model.id); setSelectedProfile(null); addFavorite({ id: model.id, name: model.name }); setShowModelDropdown(false); }}
+                                        className={`w-full flex items-center gap-2 px-3 py-2 text-left text-sm hover:bg-dark-700/50 transition-colors ${!selectedProfile && selectedModel === model.id ? 'text-blue-400' : 'text-dark-200'
                                             }`}
                                     >
                                         <Sparkles className="w-3.5 h-3.5 flex-shrink-0" />
                                         <span className="flex-1 truncate text-xs">{model.name}</span>
-                                        {selectedModel === model.id && <Check className="w-3.5 h-3.5 text-blue-400 flex-shrink-0" />}
+                                        {!selectedProfile && selectedModel === model.id && <Check className="w-3.5 h-3.5 text-blue-400 flex-shrink-0" />}
                                     </button>
                                 ))
                             )}
@@ -523,16 +569,36 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
                                         <p className="text-[10px] font-semibold text-dark-400 uppercase tracking-wider">Current (Settings)</p>
                                     </div>
                                     <button
-                                        onClick={() => { setSelectedModel(settings.ai.model); setShowModelDropdown(false); }}
-                                        className={`w-full flex items-center gap-2 px-3 py-2 text-left text-sm hover:bg-dark-700/50 transition-colors ${selectedModel === settings.ai.model ? 'text-blue-400' : 'text-dark-200'
+                                        onClick={() => { setSelectedModel(settings.ai.model); setSelectedProfile(null); setShowModelDropdown(false); }}
+                                        className={`w-full flex items-center gap-2 px-3 py-2 text-left text-sm hover:bg-dark-700/50 transition-colors ${!selectedProfile && selectedModel === settings.ai.model ? 'text-blue-400' : 'text-dark-200'
                                             }`}
                                     >
                                         <Sparkles className="w-3.5 h-3.5 flex-shrink-0" />
                                         <span className="flex-1 truncate text-xs">{settings.ai.model}</span>
-                                        {selectedModel === settings.ai.model && <Check className="w-3.5 h-3.5 text-blue-400 flex-shrink-0" />}
+                                        {!selectedProfile && selectedModel === settings.ai.model && <Check className="w-3.5 h-3.5 text-blue-400 flex-shrink-0" />}
                                     </button>
                                 </>
                             )}
+                            {(settings?.ai.ai_profiles?.length ?? 0) > 0 && (
+                                <>
+                                    <div className="px-3 py-2 border-t border-dark-700/50">
+                                        <p className="text-[10px] font-semibold text-dark-400 uppercase tracking-wider">Profiles</p>
+                                    </div>
+                                    {settings?.ai.ai_profiles?.map((profile) => (
+                                        <button
+                                            key={profile.name}
+                                            onClick={() => { setSelectedProfile(profile.name); setShowModelDropdown(false); }}
+                                            className={`w-full flex items-center gap-2 px-3 py-2 text-left text-sm hover:bg-dark-700/50 transition-colors ${selectedProfile === profile.name ? 'text-blue-400' : 'text-dark-200'
+                                                }`}
+                                        >
+                                            <Sparkles className="w-3.5 h-3.5 flex-shrink-0" />
+                                            <span className="flex-1 truncate text-xs">{profile.name}</span>
+                                            <span className="text-[10px] text-dark-500 truncate max-w-[90px]">{profile.model}</span>
+                                            {selectedProfile === profile.name && <Check className="w-3.5 h-3.5 text-blue-400 flex-shrink-0" />}
+                                        </button>
+                                    ))}
+                                </>
+                            )}
                         </div>
                     )}
                 </div>
@@ -718,6 +784,19 @@ export function ChatPage({ initialPrompt }: ChatPageProps) {
                                 {messages.map((msg) => (
                                     <ChatMessage key={msg.id} message={msg} />
                                 ))}
+                                {clarification && clarification.suggestions.length > 0 && (
+                                    <div className="flex flex-wrap gap-2 mb-4">
+                                        {clarification.suggestions.map((suggestion) => (
+                                            <button
+                                                key={suggestion}
+                                                onClick={() => handleSendWithMessage(suggestion)}
+                                                className="px-3 py-1.5 rounded-full border border-dark-700 bg-dark-900 text-xs text-dark-200 hover:text-white hover:border-blue-500/60 transition-colors"
+                                            >
+                                                {suggestion}
+                                            </button>
+                                        ))}
+                                    </div>
+                                )}
                                 {streamingContent ? renderStreamingMessage() : isSending && (
                                     <div className="flex items-center gap-2 text-dark-400 mb-4">
                                         <div className="bg-dark-800 rounded-2xl rounded-bl-md px-4 py-3 border border-dark-700">
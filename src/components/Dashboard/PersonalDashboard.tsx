@@ -1,11 +1,12 @@
 import { useEffect, useState } from 'react';
-import { RefreshCw, CalendarClock, FolderKanban, MessageCircle, Sparkles } from 'lucide-react';
+import { RefreshCw, CalendarClock, FolderKanban, MessageCircle, Sparkles, Video } from 'lucide-react';
 import { motion, AnimatePresence } from 'framer-motion';
 import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import { Card, CardHeader, CardContent, Button } from '../common';
-import { getDashboardOverview, refreshDashboardOverview, summarizeContact, summarizeProject } from '../../services/tauri';
-import type { DashboardOverview, DashboardTask, ProjectOverview, ContactOverview } from '../../types';
+import { formatDuration } from '../../lib/utils';
+import { errorMessage, getDashboardOverview, refreshDashboardOverview, summarizeContact, summarizeProject } from '../../services/tauri';
+import type { DashboardOverview, DashboardTask, ProjectOverview, ContactOverview, FocusPoint, Meeting } from '../../types';
 
 function formatTime(ts?: number): string {
     if (!ts) return 'N/A';
@@ -32,7 +33,7 @@ export function PersonalDashboard() {
             const next = forceRefresh ? await refreshDashboardOverview() : await getDashboardOverview(false);
             setData(next);
         } catch (e) {
-            setError(e instanceof Error ? e.message : 'Failed to load dashboard');
+            setError(errorMessage(e) || 'Failed to load dashboard');
         } finally {
             setLoading(false);
             setRefreshing(false);
@@ -43,6 +44,17 @@ export function PersonalDashboard() {
         load(false);
     }, []);
 
+    const openMeetingDetail = (item: Meeting) => {
+        setDetailPopup({
+            title: item.name,
+            summary: item.attendees.length
+                ? `Attendees (approximate, from screen text): ${item.attendees.join(', ')}`
+                : 'No attendees could be read from the meeting window.',
+            detail: `${formatDuration(item.duration_seconds)} in ${item.apps.join(', ')}`,
+            when: `${formatTime(item.start_time)} to ${formatTime(item.end_time)}`,
+        });
+    };
+
     const openContactDetail = async (item: ContactOverview) => {
         const when = item.last_seen ? formatTime(item.last_seen) : 'Unknown time';
         setDetailPopup({
@@ -64,7 +76,7 @@ export function PersonalDashboard() {
             setDetailPopup({
                 title: item.name,
                 summary: item.context || 'Communication activity detected.',
-                detail: `Failed to generate AI summary: ${e instanceof Error ? e.message : String(e)}`,
+                detail: `Failed to generate AI summary: ${errorMessage(e)}`,
                 when,
             });
         }
@@ -91,7 +103,7 @@ export function PersonalDashboard() {
             setDetailPopup({
                 title: item.name,
                 summary: item.update || `${item.files_changed} file change(s) detected today.`,
-                detail: `Failed to generate AI summary: ${e instanceof Error ? e.message : String(e)}`,
+                detail: `Failed to generate AI summary: ${errorMessage(e)}`,
                 when,
             });
         }
@@ -106,6 +118,24 @@ export function PersonalDashboard() {
         });
     };
 
+    // Focus points link to an item in the lists below; fall back to a bare entry if it has dropped off them.
+    const openFocusPoint = (point: FocusPoint) => {
+        const name = point.entity_ref?.name ?? point.entity_ref?.id;
+        if (!name) return;
+        const same = (other: string) => other.toLowerCase() === name.toLowerCase();
+        switch (point.entity_ref?.type) {
+            case 'project':
+                openProjectDetail(data?.projects.find((p) => same(p.name)) ?? { name, update: '', files_changed: 0 });
+                break;
+            case 'contact':
+                openContactDetail(data?.contacts.find((c) => same(c.name)) ?? { name, context: '' });
+                break;
+            case 'deadline':
+                openDeadlineDetail(data?.deadlines.find((d) => same(d.title)) ?? { title: name, status: 'pending', source: 'focus' });
+                break;
+        }
+    };
+
     if (loading) {
         return (
             <div className="flex items-center justify-center py-12">
@@ -147,11 +177,16 @@ export function PersonalDashboard() {
                     )}
                     {data?.focus_points && data.focus_points.length > 0 && (
                         <div className="mt-4 space-y-2">
-                            {data.focus_points.slice(0, 5).map((point) => (
-                                <div key={point} className="flex items-start gap-2 text-sm text-dark-300">
-                                    <Sparkles className="w-3.5 h-3.5 text-primary-400 mt-0.5 flex-shrink-0" />
-                                    <span>{point}</span>
-                                </div>
+                            {data.focus_points.slice(0, 5).map((point, idx) => (
+                                <button
+                                    key={`${point.kind}-${idx}`}
+                                    onClick={() => openFocusPoint(point)}
+                                    disabled={!point.entity_ref}
+                                    className="w-full flex items-start gap-2 text-left text-sm text-dark-300 enabled:hover:text-white disabled:cursor-default"
+                                >
+                                    <Sparkles className={`w-3.5 h-3.5 mt-0.5 flex-shrink-0 ${point.priority >= 3 ? 'text-amber-400' : 'text-primary-400'}`} />
+                                    <span>{point.text}</span>
+                                </button>
                             ))}
                         </div>
                     )}
@@ -235,6 +270,32 @@ export function PersonalDashboard() {
                         </div>
                     </CardContent>
                 </Card>
+
+                <Card variant="bordered">
+                    <CardHeader title="Meetings" subtitle="Detected from Teams, Zoom and Meet windows" />
+                    <CardContent>
+                        <div className="space-y-2">
+                            {data?.meetings?.length ? data.meetings.slice(0, 8).map((item, idx) => (
+                                <button
+                                    key={`${item.name}-${item.start_time}-${idx}`}
+                                    onClick={() => openMeetingDetail(item)}
+                                    className="w-full text-left p-3 bg-dark-800 rounded-lg hover:bg-dark-700 transition-colors"
+                                >
+                                    <div className="flex items-center gap-2 mb-1">
+                                        <Video className="w-4 h-4 text-sky-400" />
+                                        <p className="text-sm text-white truncate">{item.name}</p>
+                                    </div>
+                                    <p className="text-xs text-dark-400 break-words">
+                                        {formatTime(item.start_time)} - {formatDuration(item.duration_seconds)}
+                                        {item.attendees.length ? ` - ${item.attendees.length} attendee${item.attendees.length === 1 ? '' : 's'}` : ''}
+                                    </p>
+                                </button>
+                            )) : (
+                                <p className="text-xs text-dark-500">No meetings detected today.</p>
+                            )}
+                        </div>
+                    </CardContent>
+                </Card>
             </div>
 
             <AnimatePresence>
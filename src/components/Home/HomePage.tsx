@@ -14,6 +14,7 @@ import {
     Lock,
 } from 'lucide-react';
 import { ActivityTimebar } from './ActivityTimebar';
+import { SinceLastSeenCard } from './SinceLastSeenCard';
 import { getActivityStats } from '../../services/tauri';
 import type { ActivityStats } from '../../types';
 import { getDayRange } from '../../lib/utils';
@@ -130,6 +131,8 @@ export function HomePage({ onNavigate, onChatWithPrompt }: HomePageProps) {
                         </h1>
                     </div>
 
+                    <SinceLastSeenCard />
+
                     {/* Single-Click Summaries */}
                     <section className="mb-10">
                         <h2 className="text-xs font-semibold text-dark-400 uppercase tracking-wider mb-4">
@@ -0,0 +1,79 @@
+import { useEffect, useState } from 'react';
+import { Bell, Folder, Music } from 'lucide-react';
+import { getSinceLastSeen, markSurfaceSeen } from '../../services/tauri';
+import type { SinceLastSeen } from '../../types';
+import { formatDuration, formatTime } from '../../lib/utils';
+
+/** "Since 9:42 AM": what happened between the previous visit to the home screen and now. */
+export function SinceLastSeenCard() {
+    const [delta, setDelta] = useState<SinceLastSeen | null>(null);
+
+    useEffect(() => {
+        const load = async () => {
+            try {
+                setDelta(await getSinceLastSeen('dashboard'));
+                await markSurfaceSeen('dashboard');
+            } catch (error) {
+                console.error('Failed to load changes since last visit:', error);
+            }
+        };
+        load();
+    }, []);
+
+    if (!delta || (delta.new_activities === 0 && delta.projects.length === 0 && delta.notifications.length === 0)) {
+        return null;
+    }
+
+    const label = delta.first_visit ? 'Today so far' : `Since ${formatTime(delta.gap_start)}`;
+
+    return (
+        <section className="mb-10">
+            <h2 className="text-xs font-semibold text-dark-400 uppercase tracking-wider mb-4">{label}</h2>
+            <div className="p-4 rounded-xl border border-dark-700/60 bg-dark-900 space-y-3 text-sm">
+                <p className="text-dark-300">
+                    <span className="text-white font-medium">{delta.new_activities}</span> sessions ·{' '}
+                    <span className="text-white font-medium">{formatDuration(delta.tracked_seconds)}</span> tracked
+                    {delta.top_apps.length > 0 && (
+                        <span className="text-dark-400">
+                            {' '}· mostly {delta.top_apps.slice(0, 3).map((app) => app.app_name).join(', ')}
+                        </span>
+                    )}
+                </p>
+                {delta.projects.length > 0 && (
+                    <div className="flex items-start gap-2 text-dark-300">
+                        <Folder className="w-4 h-4 mt-0.5 text-primary-400 flex-shrink-0" />
+                        <span>
+                            {delta.projects
+                                .map((project) => `${project.name} (${project.files} file${project.files === 1 ? '' : 's'})`)
+                                .join(', ')}
+                        </span>
+                    </div>
+                )}
+                {delta.media.length > 0 && (
+                    <div className="flex items-start gap-2 text-dark-300">
+                        <Music className="w-4 h-4 mt-0.5 text-emerald-400 flex-shrink-0" />
+                        <span className="truncate">
+                            {delta.media
+                                .slice(0, 3)
+                                .map((media) => (media.artist ? `${media.title} – ${media.artist}` : media.title))
+                                .join(', ')}
+                        </span>
+                    </div>
+                )}
+                {delta.notifications.length > 0 && (
+                    <div className="space-y-1">
+                        {delta.notifications.slice(0, 5).map((notification) => (
+                            <div key={`${notification.created_at}-${notification.title}`} className="flex items-start gap-2 text-dark-300">
+                                <Bell className="w-4 h-4 mt-0.5 text-amber-400 flex-shrink-0" />
+                                <span>
+                                    <span className="text-dark-500 font-mono text-xs mr-1.5">{formatTime(notification.created_at)}</span>
+                                    {notification.title}: {notification.body}
+                                </span>
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </section>
+    );
+}
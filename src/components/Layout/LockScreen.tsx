@@ -0,0 +1,54 @@
+import { useState, type FormEvent } from 'react';
+import { Lock } from 'lucide-react';
+import { Button } from '../common';
+import { unlockApp } from '../../services/tauri';
+
+interface LockScreenProps {
+  onUnlocked: () => void;
+}
+
+export function LockScreen({ onUnlocked }: LockScreenProps) {
+  const [password, setPassword] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    if (!password || isChecking) return;
+    setIsChecking(true);
+    setError(null);
+    try {
+      if (await unlockApp(password)) {
+        setPassword('');
+        onUnlocked();
+      } else {
+        setError('Wrong password');
+      }
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
+  return (
+    <div className="h-screen flex items-center justify-center bg-dark-950">
+      <form onSubmit={handleSubmit} className="w-72 space-y-4 text-center">
+        <Lock className="w-10 h-10 mx-auto text-dark-400" />
+        <p className="text-sm text-dark-300">IntentFlow is locked</p>
+        <input
+          type="password"
+          autoFocus
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          placeholder="Password"
+          className="w-full px-3 py-2 bg-dark-800 border border-dark-700 rounded-lg text-white placeholder-dark-400 text-sm focus:outline-none focus:ring-2 focus:ring-primary-500"
+        />
+        {error && <p className="text-xs text-red-400">{error}</p>}
+        <Button type="submit" className="w-full" isLoading={isChecking}>
+          Unlock
+        </Button>
+      </form>
+    </div>
+  );
+}
@@ -0,0 +1,46 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { Music, Pause } from 'lucide-react';
+import { getNowPlaying } from '../../services/tauri';
+import type { NowPlaying } from '../../types';
+
+/** Current track from the media session; hidden when nothing is playing or it went stale. */
+export function NowPlayingMini() {
+    const [nowPlaying, setNowPlaying] = useState<NowPlaying | null>(null);
+
+    useEffect(() => {
+        let unlisten: (() => void) | undefined;
+        getNowPlaying().then(setNowPlaying).catch(() => {});
+        (async () => {
+            unlisten = await listen<NowPlaying>('media://now-playing', (event) => {
+                setNowPlaying(event.payload);
+            });
+        })();
+        // Staleness is decided by the backend, so re-read it now and then.
+        const timer = window.setInterval(() => {
+            getNowPlaying().then(setNowPlaying).catch(() => {});
+        }, 15000);
+        return () => {
+            if (unlisten) unlisten();
+            window.clearInterval(timer);
+        };
+    }, []);
+
+    const media = nowPlaying?.media;
+    if (!media || nowPlaying.stale || media.status.toLowerCase() === 'stopped') return null;
+
+    const playing = media.status.toLowerCase() === 'playing';
+    const Icon = playing ? Music : Pause;
+    return (
+        <div
+            className="flex items-center gap-2 text-[10px] text-dark-400"
+            title={nowPlaying.source_app ? `${media.status} in ${nowPlaying.source_app}` : media.status}
+        >
+            <Icon className={`w-3 h-3 shrink-0 ${playing ? 'text-emerald-400' : 'text-dark-500'}`} />
+            <span className="truncate">
+                {media.title}
+                {media.artist && <span className="text-dark-500"> · {media.artist}</span>}
+            </span>
+        </div>
+    );
+}
@@ -0,0 +1,70 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { getOcrStatus } from '../../services/tauri';
+import type { OcrStatus } from '../../types';
+
+/** No stored reading for this long reads as "stalled". */
+const STALE_AFTER_SECS = 120;
+
+const STAGE_LABELS: Record<OcrStatus['stage'], string> = {
+    capturing: 'capturing',
+    'diffing-skipped': 'screen unchanged',
+    'profile-skipped': 'skipped by profile',
+    'app-skipped': 'app excluded from OCR',
+    'ocr-running': 'reading screen',
+    stored: 'stored',
+    'low-quality-skipped': 'low quality, skipped',
+    failed: 'failed',
+};
+
+function agoLabel(timestamp: number, now: number): string {
+    const secs = Math.max(0, now - timestamp);
+    if (secs < 60) return `${secs}s ago`;
+    if (secs < 3600) return `${Math.floor(secs / 60)}m ago`;
+    return `${Math.floor(secs / 3600)}h ago`;
+}
+
+/** Small indicator showing that screen capture + OCR is alive. */
+export function OcrStatusDot() {
+    const [latest, setLatest] = useState<OcrStatus | null>(null);
+    const [lastStored, setLastStored] = useState<OcrStatus | null>(null);
+    const [now, setNow] = useState(() => Math.floor(Date.now() / 1000));
+
+    useEffect(() => {
+        let unlisten: (() => void) | undefined;
+        getOcrStatus()
+            .then((summary) => {
+                setLatest(summary.latest);
+                setLastStored(summary.last_stored);
+            })
+            .catch(() => {});
+        (async () => {
+            unlisten = await listen<OcrStatus>('ocr://status', (event) => {
+                setLatest(event.payload);
+                if (event.payload.stage === 'stored') setLastStored(event.payload);
+            });
+        })();
+        const timer = window.setInterval(() => setNow(Math.floor(Date.now() / 1000)), 5000);
+        return () => {
+            if (unlisten) unlisten();
+            window.clearInterval(timer);
+        };
+    }, []);
+
+    const failed = latest?.stage === 'failed';
+    const stale = !lastStored || now - lastStored.timestamp > STALE_AFTER_SECS;
+    const color = failed ? 'bg-red-500' : stale ? 'bg-amber-500' : 'bg-emerald-500';
+    const label = lastStored
+        ? `last OCR ${agoLabel(lastStored.timestamp, now)}, ${lastStored.chars} chars`
+        : 'no OCR yet';
+    const detail = latest
+        ? `${STAGE_LABELS[latest.stage]}${latest.app_name ? ` in ${latest.app_name}` : ''} (${latest.elapsed_ms} ms)${latest.error ? `: ${latest.error}` : ''}`
+        : 'waiting for first capture';
+
+    return (
+        <div className="flex items-center gap-2 text-[10px] text-dark-500" title={detail}>
+            <span className={`w-1.5 h-1.5 rounded-full ${color}`} />
+            <span className="truncate">{label}</span>
+        </div>
+    );
+}
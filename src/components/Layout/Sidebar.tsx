@@ -6,6 +6,8 @@ import {
     Zap,
     X,
 } from 'lucide-react';
+import { OcrStatusDot } from './OcrStatusDot';
+import { NowPlayingMini } from './NowPlayingMini';
 
 interface SidebarProps {
     isOpen: boolean;
@@ -74,7 +76,9 @@ export function Sidebar({ isOpen, activePage, onNavigate, onClose }: SidebarProp
                 </nav>
 
                 {/* Bottom */}
-                <div className="px-4 py-3 border-t border-dark-800/50">
+                <div className="px-4 py-3 border-t border-dark-800/50 space-y-2">
+                    <NowPlayingMini />
+                    <OcrStatusDot />
                     <p className="text-[10px] text-dark-600 text-center">IntentFlow v1.0</p>
                 </div>
             </aside>
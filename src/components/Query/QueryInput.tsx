@@ -1,7 +1,7 @@
 import { useState } from 'react';
 import { Search, Send } from 'lucide-react';
 import { Button } from '../common';
-import { executeQuery } from '../../services/tauri';
+import { errorMessage, executeQuery } from '../../services/tauri';
 import type { QueryResult } from '../../types';
 
 interface QueryInputProps {
@@ -26,7 +26,7 @@ export function QueryInput({ onResult, placeholder = "Ask anything about your ac
       onResult?.(result);
       setQuery('');
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to execute query');
+      setError(errorMessage(err) || 'Failed to execute query');
     } finally {
       setIsLoading(false);
     }
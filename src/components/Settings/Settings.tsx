@@ -20,8 +20,8 @@ import {
 } from 'lucide-react';
 import { Card, CardHeader, CardContent, Button } from '../common';
 import { useSettings } from '../../hooks/useSettings';
-import { getStorageStats, cleanupOldData, exportData, getNvidiaModels, ModelInfo } from '../../services/tauri';
-import type { Settings as SettingsType, StorageStats } from '../../types';
+import { getStorageStats, cleanupOldData, exportData, getNvidiaModels, setUnlockPassword, ModelInfo } from '../../services/tauri';
+import type { Settings as SettingsType, StorageStats, StorageCategoryUsage } from '../../types';
 import { formatBytes } from '../../lib/utils';
 import { useFavoriteModels } from '../../hooks/useFavoriteModels';
 
@@ -40,6 +40,9 @@ export function SettingsPanel() {
     const [modelsError, setModelsError] = useState<string | null>(null);
     const [modelSearch, setModelSearch] = useState('');
     const [showModelDropdown, setShowModelDropdown] = useState(false);
+    const [currentPassword, setCurrentPassword] = useState('');
+    const [newPassword, setNewPassword] = useState('');
+    const [passwordMessage, setPasswordMessage] = useState<string | null>(null);
     const modelInputRef = useRef<HTMLInputElement>(null);
     const modelDropdownRef = useRef<HTMLDivElement>(null);
 
@@ -130,6 +133,18 @@ export function SettingsPanel() {
         }
     };
 
+    const handleSetPassword = async () => {
+        try {
+            await setUnlockPassword(currentPassword, newPassword);
+            setPasswordMessage(newPassword ? 'Unlock password saved' : 'Unlock password removed');
+            if (!newPassword) update('privacy', 'require_unlock', false);
+            setCurrentPassword('');
+            setNewPassword('');
+        } catch (e) {
+            setPasswordMessage(String(e));
+        }
+    };
+
     const handleReset = () => {
         if (settings) setLocalSettings(settings);
     };
@@ -311,6 +326,12 @@ export function SettingsPanel() {
                                         value={localSettings.tracking.track_browser}
                                         onChange={(v) => update('tracking', 'track_browser', v)}
                                     />
+                                    <SettingToggle
+                                        label="Verbose OCR Status"
+                                        description="Report every OCR stage to the status indicator, not just results"
+                                        value={localSettings.tracking.ocr_debug_events ?? false}
+                                        onChange={(v) => update('tracking', 'ocr_debug_events', v)}
+                                    />
                                 </div>
                             </CardContent>
                         </Card>
@@ -342,6 +363,13 @@ export function SettingsPanel() {
                                                     <p className="text-sm font-bold text-white">{storageStats.entries_count.toLocaleString()}</p>
                                                 </div>
                                             </div>
+                                            {storageStats.categories?.length > 0 && (
+                                                <StorageBreakdown
+                                                    categories={storageStats.categories}
+                                                    bytesPerDay={storageStats.bytes_per_day}
+                                                    estimated={storageStats.size_source === 'estimate'}
+                                                />
+                                            )}
                                         </div>
                                     )}
 
@@ -510,6 +538,43 @@ export function SettingsPanel() {
                                         value={localSettings.ai.fallback_to_local}
                                         onChange={(v) => update('ai', 'fallback_to_local', v)}
                                     />
+                                    <SettingSelect
+                                        label="Answer Language"
+                                        value={localSettings.ai.answer_language ?? 'auto'}
+                                        onChange={(v) => update('ai', 'answer_language', v)}
+                                        options={[
+                                            { value: 'auto', label: 'Match my question' },
+                                            { value: 'en', label: 'English' },
+                                            { value: 'fr', label: 'Français' },
+                                            { value: 'de', label: 'Deutsch' },
+                                        ]}
+                                    />
+                                    <div className="grid grid-cols-3 gap-3">
+                                        <SettingNumber
+                                            label="Agent Temperature"
+                                            value={localSettings.ai.agent_temperature ?? 0}
+                                            onChange={(v) => update('ai', 'agent_temperature', v)}
+                                            min={0}
+                                            max={2}
+                                            step={0.1}
+                                        />
+                                        <SettingNumber
+                                            label="Summary Temperature"
+                                            value={localSettings.ai.summary_temperature ?? 0.7}
+                                            onChange={(v) => update('ai', 'summary_temperature', v)}
+                                            min={0}
+                                            max={2}
+                                            step={0.1}
+                                        />
+                                        <SettingNumber
+                                            label="Dashboard Temperature"
+                                            value={localSettings.ai.dashboard_temperature ?? 0.2}
+                                            onChange={(v) => update('ai', 'dashboard_temperature', v)}
+                                            min={0}
+                                            max={2}
+                                            step={0.1}
+                                        />
+                                    </div>
 
                                     {/* Recent Models */}
                                     <div className="border-t border-dark-700/50 pt-5">
@@ -572,6 +637,34 @@ export function SettingsPanel() {
                                         value={localSettings.privacy.anonymize_data}
                                         onChange={(v) => update('privacy', 'anonymize_data', v)}
                                     />
+                                    <SettingToggle
+                                        label="Require Unlock"
+                                        description="Ask for a password before showing the window (needs a password below)"
+                                        value={localSettings.privacy.require_unlock ?? false}
+                                        onChange={(v) => update('privacy', 'require_unlock', v)}
+                                    />
+                                    <div className="space-y-3">
+                                        <SettingText
+                                            label="Current Password"
+                                            type="password"
+                                            value={currentPassword}
+                                            onChange={setCurrentPassword}
+                                            placeholder="Leave empty if none is set"
+                                        />
+                                        <SettingText
+                                            label="New Password"
+                                            type="password"
+                                            value={newPassword}
+                                            onChange={setNewPassword}
+                                            placeholder="Leave empty to remove the password"
+                                        />
+                                        <div className="flex items-center gap-3">
+                                            <Button size="sm" variant="secondary" onClick={handleSetPassword}>
+                                                {newPassword ? 'Set Password' : 'Remove Password'}
+                                            </Button>
+                                            {passwordMessage && <p className="text-xs text-dark-400">{passwordMessage}</p>}
+                                        </div>
+                                    </div>
                                 </div>
                             </CardContent>
                         </Card>
@@ -620,6 +713,61 @@ export function SettingsPanel() {
     );
 }
 
+/* ─────────────── Storage Breakdown ─────────────── */
+
+const BREAKDOWN_COLORS = ['#6366f1', '#f59e0b', '#10b981', '#ef4444', '#3b82f6', '#a855f7', '#14b8a6', '#f97316', '#64748b'];
+
+function StorageBreakdown({
+    categories,
+    bytesPerDay,
+    estimated,
+}: {
+    categories: StorageCategoryUsage[];
+    bytesPerDay: number;
+    estimated: boolean;
+}) {
+    const total = categories.reduce((sum, c) => sum + c.size_bytes, 0);
+    if (total <= 0) return null;
+
+    // Donut as a conic gradient: each category gets an arc proportional to its bytes.
+    let offset = 0;
+    const stops = categories.map((c, i) => {
+        const start = (offset / total) * 100;
+        offset += c.size_bytes;
+        const end = (offset / total) * 100;
+        const color = BREAKDOWN_COLORS[i % BREAKDOWN_COLORS.length];
+        return `${color} ${start}% ${end}%`;
+    });
+
+    return (
+        <div className="mt-4 pt-4 border-t border-dark-700 flex items-center gap-6">
+            <div
+                className="w-28 h-28 rounded-full flex-shrink-0 flex items-center justify-center"
+                style={{ background: `conic-gradient(${stops.join(', ')})` }}
+            >
+                <div className="w-16 h-16 rounded-full bg-dark-800" />
+            </div>
+            <div className="flex-1 space-y-1">
+                {categories.map((c, i) => (
+                    <div key={c.category} className="flex items-center justify-between text-xs">
+                        <span className="flex items-center gap-2 text-dark-300">
+                            <span
+                                className="w-2.5 h-2.5 rounded-sm"
+                                style={{ background: BREAKDOWN_COLORS[i % BREAKDOWN_COLORS.length] }}
+                            />
+                            {c.category}
+                        </span>
+                        <span className="text-dark-400">{formatBytes(c.size_bytes)}</span>
+                    </div>
+                ))}
+                <p className="text-xs text-dark-500 pt-1">
+                    Growing about {formatBytes(bytesPerDay)} per day{estimated ? ' (sizes estimated)' : ''}
+                </p>
+            </div>
+        </div>
+    );
+}
+
 /* ─────────────── Reusable Setting Field Components ─────────────── */
 
 function SettingToggle({
@@ -688,12 +836,14 @@ function SettingNumber({
     onChange,
     min,
     max,
+    step,
 }: {
     label: string;
     value: number;
     onChange: (v: number) => void;
     min?: number;
     max?: number;
+    step?: number;
 }) {
     return (
         <div>
@@ -701,9 +851,10 @@ function SettingNumber({
             <input
                 type="number"
                 value={value}
-                onChange={(e) => onChange(parseInt(e.target.value) || 0)}
+                onChange={(e) => onChange((step ? parseFloat(e.target.value) : parseInt(e.target.value)) || 0)}
                 min={min}
                 max={max}
+                step={step}
                 className="w-full px-3 py-2 bg-dark-800 border border-dark-700 rounded-lg text-white text-sm focus:outline-none focus:ring-2 focus:ring-primary-500"
             />
         </div>
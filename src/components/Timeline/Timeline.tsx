@@ -1,4 +1,4 @@
-import { useState } from 'react';
+import { useEffect, useMemo, useState } from 'react';
 import {
     Clock,
     ChevronLeft,
@@ -7,34 +7,49 @@ import {
     Monitor,
     RefreshCw,
     Music,
+    AlertTriangle,
 } from 'lucide-react';
 import { Card, CardHeader, CardContent, Button } from '../common';
 import { useActivities } from '../../hooks/useActivities';
+import { useSettings } from '../../hooks/useSettings';
 import { formatDuration, formatTime } from '../../lib/utils';
+import { getTrackingGaps, markSurfaceSeen } from '../../services/tauri';
+import type { TrackingGap } from '../../types';
 
-const CATEGORY_COLORS: Record<number, string> = {
-    1: '#3b82f6', // Development - blue
-    2: '#10b981', // Browser - green
-    3: '#8b5cf6', // Communication - purple
-    4: '#f59e0b', // Entertainment - amber
-    5: '#ec4899', // Productivity - pink
-    6: '#6b7280', // System - gray
-    7: '#9ca3af', // Other - slate
-};
+const FALLBACK_CATEGORY_COLOR = '#9ca3af';
 
-const CATEGORY_NAMES: Record<number, string> = {
-    1: 'Development',
-    2: 'Browser',
-    3: 'Communication',
-    4: 'Entertainment',
-    5: 'Productivity',
-    6: 'System',
-    7: 'Other',
-};
+function localDateKey(daysAgo: number): string {
+    const date = new Date();
+    date.setDate(date.getDate() - daysAgo);
+    const pad = (n: number) => String(n).padStart(2, '0');
+    return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`;
+}
 
 export function Timeline() {
     const [daysAgo, setDaysAgo] = useState(0);
     const { activities, stats, isLoading, error, refresh } = useActivities(daysAgo);
+    const { categories } = useSettings();
+    const [gaps, setGaps] = useState<TrackingGap[]>([]);
+
+    useEffect(() => {
+        markSurfaceSeen('timeline').catch((error) => console.error('Failed to mark timeline seen:', error));
+    }, []);
+
+    useEffect(() => {
+        let cancelled = false;
+        getTrackingGaps(localDateKey(daysAgo))
+            .then((found) => !cancelled && setGaps(found))
+            .catch(() => !cancelled && setGaps([]));
+        return () => {
+            cancelled = true;
+        };
+    }, [daysAgo, activities]);
+
+    // Names and colors come from the categories table so custom categories render too.
+    const categoryById = useMemo(
+        () => new Map(categories.map((category) => [category.id, category])),
+        [categories]
+    );
 
     const goBack = () => setDaysAgo((d) => d + 1);
     const goForward = () => setDaysAgo((d) => Math.max(0, d - 1));
@@ -116,6 +131,31 @@ export function Timeline() {
                 </div>
             )}
 
+            {/* Tracking gaps */}
+            {gaps.length > 0 && (
+                <Card variant="bordered">
+                    <div className="flex items-start gap-3">
+                        <AlertTriangle className="w-5 h-5 text-amber-400 flex-shrink-0 mt-0.5" />
+                        <div>
+                            <p className="text-sm text-dark-300">
+                                No data recorded — tracking may have been down:
+                            </p>
+                            <div className="flex flex-wrap gap-2 mt-2">
+                                {gaps.map((gap) => (
+                                    <span
+                                        key={gap.start}
+                                        className="px-2 py-0.5 rounded-full text-xs bg-amber-500/10 text-amber-300"
+                                        title={formatDuration(gap.duration)}
+                                    >
+                                        {formatTime(gap.start)}–{formatTime(gap.end)}
+                                    </span>
+                                ))}
+                            </div>
+                        </div>
+                    </div>
+                </Card>
+            )}
+
             {/* Loading / Error */}
             {isLoading && (
                 <div className="flex items-center justify-center py-12">
@@ -160,8 +200,9 @@ export function Timeline() {
 
                                 <div className="space-y-1">
                                     {activities.map((activity, index) => {
-                                        const color = CATEGORY_COLORS[activity.category_id] || CATEGORY_COLORS[8];
-                                        const categoryName = CATEGORY_NAMES[activity.category_id] || 'Other';
+                                        const category = categoryById.get(activity.category_id);
+                                        const color = category?.color || FALLBACK_CATEGORY_COLOR;
+                                        const categoryName = category?.name || 'Other';
 
                                         return (
                                             <div
@@ -240,7 +281,7 @@ export function Timeline() {
                     <CardContent>
                         <div className="space-y-3">
                             {stats.top_categories.map((cat) => {
-                                const color = CATEGORY_COLORS[cat.category_id] || CATEGORY_COLORS[8];
+                                const color = categoryById.get(cat.category_id)?.color || FALLBACK_CATEGORY_COLOR;
                                 return (
                                     <div key={cat.category_id} className="flex items-center gap-4">
                                         <div
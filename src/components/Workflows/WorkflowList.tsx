@@ -9,10 +9,12 @@ import {
     FileText,
     Monitor,
     X,
+    FlaskConical,
 } from 'lucide-react';
 import { Card, CardHeader, CardContent, Button, Modal } from '../common';
 import { useWorkflows } from '../../hooks/useWorkflows';
-import type { Workflow } from '../../types';
+import { executeWorkflow } from '../../services/tauri';
+import type { Workflow, WorkflowRunResult } from '../../types';
 import { getRelativeTime } from '../../lib/utils';
 
 export function WorkflowList() {
@@ -20,6 +22,7 @@ export function WorkflowList() {
     const [showEditor, setShowEditor] = useState(false);
     const [editingWorkflow, setEditingWorkflow] = useState<Workflow | null>(null);
     const [confirmDelete, setConfirmDelete] = useState<string | null>(null);
+    const [dryRun, setDryRun] = useState<WorkflowRunResult | null>(null);
 
     const handleCreate = () => {
         setEditingWorkflow(null);
@@ -50,6 +53,14 @@ export function WorkflowList() {
         await execute(id);
     };
 
+    const handleDryRun = async (id: string) => {
+        try {
+            setDryRun(await executeWorkflow(id, true));
+        } catch (err) {
+            console.error('Workflow dry run failed:', err);
+        }
+    };
+
     if (isLoading) {
         return (
             <div className="flex items-center justify-center py-12">
@@ -160,6 +171,9 @@ export function WorkflowList() {
                                                 : 'Never used'}
                                         </span>
                                         <div className="flex items-center gap-1">
+                                            <Button variant="ghost" size="sm" onClick={() => handleDryRun(workflow.id)} title="Test without running">
+                                                <FlaskConical className="w-4 h-4 text-primary-400" />
+                                            </Button>
                                             <Button variant="ghost" size="sm" onClick={() => handleExecute(workflow.id)}>
                                                 <Play className="w-4 h-4 text-green-400" />
                                             </Button>
@@ -200,6 +214,43 @@ export function WorkflowList() {
                 </p>
             </Modal>
 
+            {/* Dry Run Modal */}
+            <Modal
+                isOpen={dryRun !== null}
+                onClose={() => setDryRun(null)}
+                title="Workflow Test"
+                size="md"
+                footer={
+                    <Button variant="secondary" onClick={() => setDryRun(null)}>
+                        Close
+                    </Button>
+                }
+            >
+                <div className="space-y-2">
+                    {dryRun?.checks.filter((check) => !check.passed).map((check, i) => (
+                        <p key={`check-${i}`} className="text-sm text-red-400">
+                            Check failed: {check.detail}
+                        </p>
+                    ))}
+                    {dryRun?.planned?.length === 0 && <p className="text-sm text-dark-400">This workflow has no actions.</p>}
+                    {dryRun?.planned?.map((step, i) => (
+                        <div key={i} className="p-2 rounded-lg bg-dark-800/60 text-sm">
+                            <div className="flex items-center gap-2">
+                                <span className="text-dark-500 font-mono text-xs">{i + 1}.</span>
+                                <span className={step.will_run ? 'text-white' : 'text-dark-500 line-through'}>
+                                    {step.target}
+                                    {step.args.length > 0 && ` ${step.args.join(' ')}`}
+                                </span>
+                            </div>
+                            {step.resolved && step.resolved !== step.target && (
+                                <p className="text-xs text-dark-400 mt-0.5 ml-5 truncate">→ {step.resolved}</p>
+                            )}
+                            {step.note && <p className="text-xs text-amber-400 mt-0.5 ml-5">{step.note}</p>}
+                        </div>
+                    ))}
+                </div>
+            </Modal>
+
             {/* Editor Modal */}
             {showEditor && (
                 <WorkflowEditor
@@ -49,7 +49,8 @@ export function useSettings() {
         setError(null);
         try {
             await updateCategoriesApi(newCategories);
-            setCategories(newCategories);
+            // Re-read so newly created categories pick up their assigned ids.
+            setCategories(await getCategories());
         } catch (err) {
             setError(err instanceof Error ? err.message : 'Failed to save categories');
             throw err;
@@ -61,8 +61,13 @@ export function useWorkflows() {
 
     const execute = useCallback(async (id: string) => {
         try {
-            await executeWorkflow(id);
+            const result = await executeWorkflow(id);
+            if (!result.executed) {
+                const failed = result.checks.filter((c) => !c.passed).map((c) => c.detail);
+                throw new Error(`Workflow not run: ${failed.join('; ')}`);
+            }
             await refresh();
+            return result;
         } catch (err) {
             setError(err instanceof Error ? err.message : 'Failed to execute workflow');
             throw err;
@@ -1,5 +1,6 @@
 import { clsx, type ClassValue } from 'clsx';
 import { twMerge } from 'tailwind-merge';
+import type { LlmUsage } from '../types';
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
@@ -42,6 +43,15 @@ export function formatTime(timestamp: number): string {
   });
 }
 
+/** "1.2k tokens · $0.0031", with "~" when any part was estimated. */
+export function formatUsage(usage: LlmUsage): string {
+  const tokens = usage.prompt_tokens + usage.completion_tokens;
+  const count = tokens >= 1000 ? `${(tokens / 1000).toFixed(1)}k` : `${tokens}`;
+  const prefix = usage.estimated ? '~' : '';
+  const cost = usage.cost_usd != null ? ` · ${prefix}$${usage.cost_usd.toFixed(4)}` : '';
+  return `${prefix}${count} tokens${cost}`;
+}
+
 export function formatDate(timestamp: number): string {
   const date = new Date(timestamp * 1000);
   return date.toLocaleDateString('en-US', {
@@ -1,21 +1,99 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
   Activity,
+  SinceLastSeen,
+  Surface,
+  TrackingGap,
+  ListeningStats,
   ActivityStats,
+  AppDirectoryEntry,
+  AppMerge,
+  AppRenameReport,
+  ProcessSession,
+  GroupedStats,
+  RelatedActivities,
+  StatsGroupBy,
+  StatsMetric,
   Category,
+  CategorizationExplanation,
   ManualEntry,
-  Pattern,
+  DetectedPattern,
+  PatternKind,
   Intent,
+  ModelCapabilities,
+  CapabilitySource,
+  LlmUsageStats,
+  QuickAction,
+  CurrentFocus,
   Workflow,
+  WorkflowRunResult,
+  PreconditionCheck,
+  AppAlert,
+  CategoryGoal,
+  GoalStreak,
+  ProfileImportMode,
+  ProfileImportReport,
+  ProfileSection,
   WorkflowSuggestion,
   QueryResult,
+  SearchHit,
   Settings,
   StorageStats,
+  StorageForecast,
+  KeywordAudit,
+  ActivityDeleteFilter,
+  ActivityDeletion,
+  RecentDataPurge,
+  DataIntegrityReport,
+  IndexRebuildProgress,
+  SampleDataSummary,
   ChatSession,
+  ChatSessionFilter,
+  ChatSessionSearchResult,
   ChatMessage,
   DashboardOverview,
+  DailyRecap,
+  DayBoundaryDebug,
+  ActivityDetail,
+  ActivityCapture,
+  ContactTimeline,
+  ProjectChanges,
+  MeetingsReport,
+  SettingsPropagation,
+  ServiceStatus,
+  OcrStatusSummary,
+  NowPlaying,
+  OcrTestReport,
+  GeneratedReport,
+  AppError,
+  LogEntry,
+  LogLevel,
 } from '../types';
 
+/** Prefix the backend puts on every database open failure. */
+export const DB_UNAVAILABLE = 'Database unavailable — check storage';
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as AppError).kind === 'string' &&
+    typeof (error as AppError).message === 'string'
+  );
+}
+
+/** Readable text for anything a command can reject with: `AppError`, plain string or `Error`. */
+export function errorMessage(error: unknown): string {
+  if (isAppError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
+
+export function isDatabaseUnavailable(error: unknown): boolean {
+  if (isAppError(error)) return error.kind === 'database' && error.message.startsWith(DB_UNAVAILABLE);
+  return String(error).startsWith(DB_UNAVAILABLE);
+}
+
 // Activity commands
 export async function getActivities(
   startTime: number,
@@ -32,13 +110,123 @@ export async function getActivityStats(
   return invoke('get_activity_stats', { startTime, endTime });
 }
 
+export async function getGroupedStats(
+  startTime: number,
+  endTime: number,
+  groupBy: StatsGroupBy,
+  metric: StatsMetric = 'duration',
+  topN = 10,
+  includeOtherBucket = true
+): Promise<GroupedStats> {
+  return invoke('get_grouped_stats', { startTime, endTime, groupBy, metric, topN, includeOtherBucket });
+}
+
+export async function getRelatedActivities(activityId: number, limit?: number): Promise<RelatedActivities> {
+  return invoke('get_related_activities', { activityId, limit });
+}
+
 export async function getCurrentActivity(): Promise<Activity | null> {
   return invoke('get_current_activity');
 }
 
+export async function markPrivate(
+  startTs: number,
+  endTs: number,
+  isPrivate: boolean
+): Promise<number> {
+  return invoke('mark_private', { startTs, endTs, private: isPrivate });
+}
+
+export async function getDayBoundaryDebug(date: string): Promise<DayBoundaryDebug> {
+  return invoke('get_day_boundary_debug', { date });
+}
+
+export async function getActivityDetail(activityId: number): Promise<ActivityDetail> {
+  return invoke('get_activity_detail', { activityId });
+}
+
+export async function deleteLastSession(): Promise<number | null> {
+  return invoke('delete_last_session');
+}
+
+export async function findActivityDetail(
+  appName: string,
+  windowTitle: string,
+  startTime: number
+): Promise<ActivityDetail | null> {
+  return invoke('find_activity_detail', { appName, windowTitle, startTime });
+}
+
+export async function getActivityOcr(activityId: number): Promise<ActivityCapture | null> {
+  return invoke('get_activity_ocr', { activityId });
+}
+
+export async function getAppDirectory(): Promise<AppDirectoryEntry[]> {
+  return invoke('get_app_directory');
+}
+
+export async function renameApp(
+  fromPatterns: string[],
+  to: string,
+  options: { dryRun?: boolean; remember?: boolean } = {}
+): Promise<AppRenameReport> {
+  return invoke('rename_app', { fromPatterns, to, dryRun: options.dryRun, remember: options.remember });
+}
+
+export async function getAppMerges(): Promise<AppMerge[]> {
+  return invoke('get_app_merges');
+}
+
+export async function createAppMerge(
+  fromName: string,
+  toName: string,
+  searchOnly = false
+): Promise<AppMerge> {
+  return invoke('create_app_merge', { fromName, toName, searchOnly });
+}
+
+export async function deleteAppMerge(fromName: string): Promise<boolean> {
+  return invoke('delete_app_merge', { fromName });
+}
+
+export async function applyAppMergesToHistory(dryRun?: boolean): Promise<AppRenameReport[]> {
+  return invoke('apply_app_merges_to_history', { dryRun });
+}
+
+export async function getProcessSessions(
+  startTime: number,
+  endTime: number,
+  app?: string
+): Promise<ProcessSession[]> {
+  return invoke('get_process_sessions', { app, startTime, endTime });
+}
+
+/** Stretches of the day (YYYY-MM-DD) within the active hours with no recorded activity. */
+export async function getTrackingGaps(dateKey: string): Promise<TrackingGap[]> {
+  return invoke('get_tracking_gaps', { dateKey });
+}
+
+/** Top tracks and artists, total play time and per-day totals for [startTs, endTs). */
+export async function getListeningStats(startTs: number, endTs: number): Promise<ListeningStats> {
+  return invoke('get_listening_stats', { startTs, endTs });
+}
+
 // Query commands
-export async function executeQuery(query: string): Promise<QueryResult> {
-  return invoke('execute_query', { query });
+export async function executeQuery(query: string, profile?: string): Promise<QueryResult> {
+  return invoke('execute_query', { query, profile });
+}
+
+export async function searchEverything(
+  query: string,
+  startTs: number,
+  endTs: number,
+  limit?: number
+): Promise<SearchHit[]> {
+  return invoke('search_everything', { query, startTs, endTs, limit });
+}
+
+export async function summarizeActivities(ids: number[]): Promise<string> {
+  return invoke('summarize_activities', { ids });
 }
 
 export async function getQueryHistory(limit?: number): Promise<QueryResult[]> {
@@ -54,6 +242,25 @@ export async function executeIntent(intent: Intent): Promise<void> {
   return invoke('execute_intent', { intent });
 }
 
+export async function getQuickActions(): Promise<QuickAction[]> {
+  return invoke('get_quick_actions');
+}
+
+export async function recordQuickActionFeedback(actionId: string, accepted: boolean): Promise<void> {
+  return invoke('record_quick_action_feedback', { actionId, accepted });
+}
+
+export async function setCurrentFocus(
+  text: string,
+  ttlMinutes?: number
+): Promise<CurrentFocus | null> {
+  return invoke('set_current_focus', { text, ttlMinutes });
+}
+
+export async function getCurrentFocus(): Promise<CurrentFocus | null> {
+  return invoke('get_current_focus');
+}
+
 // Workflow commands
 export async function getWorkflows(): Promise<Workflow[]> {
   return invoke('get_workflows');
@@ -71,14 +278,57 @@ export async function deleteWorkflow(workflowId: string): Promise<void> {
   return invoke('delete_workflow', { workflowId });
 }
 
-export async function executeWorkflow(workflowId: string): Promise<void> {
-  return invoke('execute_workflow', { workflowId });
+/** With `dryRun` nothing is launched; `planned` lists the resolved actions instead. */
+export async function executeWorkflow(workflowId: string, dryRun?: boolean): Promise<WorkflowRunResult> {
+  return invoke('execute_workflow', { workflowId, dryRun });
+}
+
+export async function validateWorkflow(workflowId: string): Promise<PreconditionCheck[]> {
+  return invoke('validate_workflow', { workflowId });
 }
 
 export async function getWorkflowSuggestions(): Promise<WorkflowSuggestion[]> {
   return invoke('get_workflow_suggestions');
 }
 
+// App usage alert commands
+export async function getAppAlerts(): Promise<AppAlert[]> {
+  return invoke('get_app_alerts');
+}
+
+export async function createAppAlert(alert: Pick<AppAlert, 'app_pattern' | 'daily_threshold_seconds' | 'repeat_every_seconds'>): Promise<number> {
+  return invoke('create_app_alert', { alert });
+}
+
+export async function updateAppAlert(alert: AppAlert): Promise<void> {
+  return invoke('update_app_alert', { alert });
+}
+
+export async function deleteAppAlert(alertId: number): Promise<void> {
+  return invoke('delete_app_alert', { alertId });
+}
+
+// Category goal commands
+export async function getCategoryGoals(): Promise<CategoryGoal[]> {
+  return invoke('get_category_goals');
+}
+
+export async function createCategoryGoal(goal: Pick<CategoryGoal, 'category_id' | 'kind' | 'target_seconds' | 'no_data_policy'>): Promise<number> {
+  return invoke('create_category_goal', { goal });
+}
+
+export async function updateCategoryGoal(goal: CategoryGoal): Promise<void> {
+  return invoke('update_category_goal', { goal });
+}
+
+export async function deleteCategoryGoal(goalId: number): Promise<void> {
+  return invoke('delete_category_goal', { goalId });
+}
+
+export async function getGoalStreaks(): Promise<GoalStreak[]> {
+  return invoke('get_goal_streaks');
+}
+
 // Manual entry commands
 export async function createEntry(
   entryType: 'task' | 'note' | 'goal',
@@ -109,8 +359,15 @@ export async function deleteEntry(id: number): Promise<void> {
 }
 
 // Pattern commands
-export async function getPatterns(): Promise<Pattern[]> {
-  return invoke('get_patterns');
+export async function getPatterns(
+  kind?: PatternKind,
+  includeDismissed?: boolean
+): Promise<DetectedPattern[]> {
+  return invoke('get_patterns', { kind, includeDismissed });
+}
+
+export async function dismissPattern(patternId: number): Promise<void> {
+  return invoke('dismiss_pattern', { patternId });
 }
 
 // Settings commands
@@ -122,6 +379,15 @@ export async function updateSettings(settings: Partial<Settings>): Promise<void>
   return invoke('update_settings', { settings });
 }
 
+export async function getSettingsPropagation(): Promise<SettingsPropagation[]> {
+  return invoke('get_settings_propagation');
+}
+
+/** Settings that were dropped, reset or clamped when settings.json was last read. */
+export async function getSettingsWarnings(): Promise<string[]> {
+  return invoke('get_settings_warnings');
+}
+
 export async function getCategories(): Promise<Category[]> {
   return invoke('get_categories');
 }
@@ -130,9 +396,34 @@ export async function updateCategories(categories: Category[]): Promise<void> {
   return invoke('update_categories', { categories });
 }
 
+export async function createCategory(
+  name: string,
+  options: { color?: string; icon?: string; keywords?: string[]; apps?: string[] } = {}
+): Promise<Category> {
+  return invoke('create_category', { name, ...options });
+}
+
+export async function editCategory(
+  id: number,
+  changes: { name?: string; color?: string; icon?: string }
+): Promise<Category> {
+  return invoke('edit_category', { id, ...changes });
+}
+
+export async function deleteCategory(id: number): Promise<number> {
+  return invoke('delete_category', { id });
+}
+
+/** Category a window with this app name and title would get, and the rule that decided it. */
+export async function explainCategorization(appName: string, title: string): Promise<CategorizationExplanation> {
+  return invoke('explain_categorization', { appName, title });
+}
+
 export interface ModelInfo {
   id: string;
   name: string;
+  capabilities: ModelCapabilities;
+  capability_source: CapabilitySource;
 }
 
 export interface RecentModel {
@@ -146,6 +437,15 @@ export async function getNvidiaModels(apiKey: string): Promise<ModelInfo[]> {
   return invoke('get_nvidia_models', { apiKey });
 }
 
+export async function probeModelCapabilities(model: string): Promise<ModelCapabilities> {
+  return invoke('probe_model_capabilities', { model });
+}
+
+/** LLM token usage and estimated cost for `month` ("YYYY-MM"), default the current month. */
+export async function getLlmUsageStats(month?: string): Promise<LlmUsageStats> {
+  return invoke('get_llm_usage_stats', { month });
+}
+
 export async function getRecentModels(limit = 5): Promise<RecentModel[]> {
   return invoke('get_recent_models', { limit });
 }
@@ -159,6 +459,10 @@ export async function getStorageStats(): Promise<StorageStats> {
   return invoke('get_storage_stats');
 }
 
+export async function getStorageForecast(): Promise<StorageForecast> {
+  return invoke('get_storage_forecast');
+}
+
 export async function cleanupOldData(retentionDays: number): Promise<number> {
   return invoke('cleanup_old_data', { retentionDays });
 }
@@ -167,6 +471,73 @@ export async function exportData(): Promise<string> {
   return invoke('export_data');
 }
 
+export async function exportTimelineIcs(
+  startTs: number,
+  endTs: number,
+  minDurationSecs?: number
+): Promise<string> {
+  return invoke('export_timeline_ics', { startTs, endTs, minDurationSecs });
+}
+
+/** Write settings, categories, alerts and workflows to `path`; all sections when `sections` is empty. */
+export async function exportProfile(
+  path: string,
+  sections: ProfileSection[] = [],
+  includeSecrets = false,
+  passphrase?: string
+): Promise<string> {
+  return invoke('export_profile', { path, sections, includeSecrets, passphrase });
+}
+
+export async function importProfile(
+  path: string,
+  mode: ProfileImportMode,
+  passphrase?: string
+): Promise<ProfileImportReport> {
+  return invoke('import_profile', { path, mode, passphrase });
+}
+
+export async function auditKeyword(keyword: string, purge = false): Promise<KeywordAudit> {
+  return invoke('audit_keyword', { keyword, purge });
+}
+
+export async function deleteActivities(filter: ActivityDeleteFilter): Promise<ActivityDeletion> {
+  return invoke('delete_activities', { filter });
+}
+
+export async function purgeRecentData(minutes?: number): Promise<RecentDataPurge> {
+  return invoke('purge_recent_data', { minutes });
+}
+
+export async function verifyDataIntegrity(): Promise<DataIntegrityReport> {
+  return invoke('verify_data_integrity');
+}
+
+export async function rebuildIndexes(): Promise<IndexRebuildProgress> {
+  return invoke('rebuild_indexes');
+}
+
+export async function cancelIndexRebuild(): Promise<boolean> {
+  return invoke('cancel_index_rebuild');
+}
+
+export async function purgeClipboardHistory(): Promise<number> {
+  return invoke('purge_clipboard_history');
+}
+
+export async function seedSampleData(force = false): Promise<SampleDataSummary> {
+  return invoke('seed_sample_data', { force });
+}
+
+// Report commands
+export async function generatePeriodReport(
+  startDate: string,
+  endDate: string,
+  format?: 'html' | 'markdown' | 'both'
+): Promise<GeneratedReport> {
+  return invoke('generate_period_report', { startDate, endDate, format });
+}
+
 // App control commands
 export async function minimizeToTray(): Promise<void> {
   return invoke('minimize_to_tray');
@@ -176,17 +547,97 @@ export async function showWindow(): Promise<void> {
   return invoke('show_window');
 }
 
+export async function isAppLocked(): Promise<boolean> {
+  return invoke('is_app_locked');
+}
+
+/** Resolves to false if the password is wrong. */
+export async function unlockApp(password: string): Promise<boolean> {
+  return invoke('unlock_app', { password });
+}
+
+export async function lockApp(): Promise<void> {
+  return invoke('lock_app');
+}
+
+/** An empty `newPassword` removes the password and turns the lock off. */
+export async function setUnlockPassword(currentPassword: string, newPassword: string): Promise<void> {
+  return invoke('set_unlock_password', { currentPassword, newPassword });
+}
+
+/** Most recent log lines, oldest first. `levelFilter` keeps that level and more severe. */
+export async function getRecentLogs(
+  lines?: number,
+  levelFilter?: LogLevel,
+  moduleFilter?: string
+): Promise<LogEntry[]> {
+  return invoke('get_recent_logs', { lines, levelFilter, moduleFilter });
+}
+
+/** Resolves to the level now in effect. */
+export async function setLogLevel(level: LogLevel): Promise<string> {
+  return invoke('set_log_level', { level });
+}
+
 export async function quitApp(): Promise<void> {
   return invoke('quit_app');
 }
 
+export async function getServiceStatus(): Promise<ServiceStatus[]> {
+  return invoke('get_service_status');
+}
+
+export async function getOcrStatus(): Promise<OcrStatusSummary> {
+  return invoke('get_ocr_status');
+}
+
+export async function getNowPlaying(): Promise<NowPlaying> {
+  return invoke('get_now_playing');
+}
+
+export async function getDemoMode(): Promise<boolean> {
+  return invoke('get_demo_mode');
+}
+
+export async function testOcrNow(): Promise<OcrTestReport> {
+  return invoke('test_ocr_now');
+}
+
+/** Pause tracking and capture; 0 resumes. Resolves to the resume timestamp, or null. */
+export async function pauseTrackingFor(minutes: number): Promise<number | null> {
+  return invoke('pause_tracking_for', { minutes });
+}
+
 // Chat commands
 export async function createChatSession(): Promise<ChatSession> {
   return invoke('create_chat_session');
 }
 
-export async function getChatSessions(): Promise<ChatSession[]> {
-  return invoke('get_chat_sessions');
+export async function getChatSessions(filter?: ChatSessionFilter): Promise<ChatSession[]> {
+  return invoke('get_chat_sessions', { filter });
+}
+
+export async function renameChatSession(sessionId: string, title: string): Promise<void> {
+  return invoke('rename_chat_session', { sessionId, title });
+}
+
+export async function archiveChatSession(sessionId: string, archived?: boolean): Promise<void> {
+  return invoke('archive_chat_session', { sessionId, archived });
+}
+
+export async function setChatSessionArchived(sessionId: string, archived: boolean): Promise<void> {
+  return invoke('set_chat_session_archived', { sessionId, archived });
+}
+
+export async function setChatSessionPinned(sessionId: string, pinned: boolean): Promise<void> {
+  return invoke('set_chat_session_pinned', { sessionId, pinned });
+}
+
+export async function searchChatSessions(
+  query: string,
+  limit?: number
+): Promise<ChatSessionSearchResult[]> {
+  return invoke('search_chat_sessions', { query, limit });
 }
 
 export async function deleteChatSession(sessionId: string): Promise<void> {
@@ -202,9 +653,10 @@ export async function sendChatMessage(
   message: string,
   model?: string,
   timeRange?: string,
-  selectedSources?: string[]
+  selectedSources?: string[],
+  profile?: string
 ): Promise<ChatMessage> {
-  return invoke('send_chat_message', { sessionId, message, model, timeRange, selectedSources });
+  return invoke('send_chat_message', { sessionId, message, model, timeRange, selectedSources, profile });
 }
 
 // Dashboard commands
@@ -216,6 +668,32 @@ export async function refreshDashboardOverview(): Promise<DashboardOverview> {
   return invoke('refresh_dashboard_overview');
 }
 
+/** Record that the user is looking at `surface` now; returns the stored timestamp. */
+export async function markSurfaceSeen(surface: Surface): Promise<number> {
+  return invoke('mark_surface_seen', { surface });
+}
+
+export async function getSinceLastSeen(surface: Surface): Promise<SinceLastSeen> {
+  return invoke('get_since_last_seen', { surface });
+}
+
+export async function getDailyRecap(dateKey?: string): Promise<DailyRecap> {
+  return invoke('get_daily_recap', { dateKey });
+}
+
+/** The day as Markdown; today when `dateKey` (YYYY-MM-DD) is omitted. */
+export async function exportJournal(dateKey?: string): Promise<string> {
+  return invoke('export_journal', { dateKey });
+}
+
+export async function getContactTimeline(
+  contactNameOrId: string,
+  days?: number,
+  limit?: number
+): Promise<ContactTimeline> {
+  return invoke('get_contact_timeline', { contactNameOrId, days, limit });
+}
+
 export async function summarizeContact(name: string): Promise<string> {
   return invoke('summarize_contact', { name });
 }
@@ -224,3 +702,16 @@ export async function summarizeProject(name: string): Promise<string> {
   return invoke('summarize_project', { name });
 }
 
+/** Teams/Zoom/Meet meetings detected from window titles in `[startTs, endTs)`. */
+export async function getMeetings(startTs: number, endTs: number): Promise<MeetingsReport> {
+  return invoke('get_meetings', { startTs, endTs });
+}
+
+export async function getProjectChanges(
+  name: string,
+  startTs: number,
+  endTs: number
+): Promise<ProjectChanges> {
+  return invoke('get_project_changes', { name, startTs, endTs });
+}
+
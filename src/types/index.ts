@@ -18,14 +18,60 @@ export interface ActivityMetadata {
   process_id?: number;
   url?: string;
   screen_text?: string;
+  ocr_score?: number | null;
+  ocr_language?: string | null;
   background_windows?: string[];
   media_info?: MediaInfo;
+  thumbnail_path?: string;
+  media_kind?: MediaKind | null;
+  recovered?: boolean;
 }
 
+export type MediaKind = 'music' | 'video' | 'unknown';
+
 export interface MediaInfo {
   title: string;
   artist: string;
   status: string;
+  source_app?: string | null;
+}
+
+export type StatsGroupBy = 'app' | 'category' | 'title' | 'hour' | 'day' | 'weekday';
+export type StatsMetric = 'duration' | 'sessions';
+
+export interface GroupedStatRow {
+  key: string;
+  label: string;
+  value: number;
+  share: number;
+}
+
+export interface GroupedStats {
+  group_by: StatsGroupBy;
+  metric: StatsMetric;
+  total: number;
+  rows: GroupedStatRow[];
+}
+
+export interface RelatedActivity {
+  activity: Activity;
+  relation: 'same_title' | 'same_app';
+  gap_seconds: number;
+  score: number;
+}
+
+export interface RelatedFileEvent {
+  path: string;
+  project_root: string;
+  change_type: string;
+  detected_at: number;
+}
+
+export interface RelatedActivities {
+  activity_id: number;
+  project: string | null;
+  activities: RelatedActivity[];
+  file_events: RelatedFileEvent[];
 }
 
 export interface ActivityStats {
@@ -58,6 +104,18 @@ export interface Category {
   color: string;
   keywords: string[];
   apps: string[];
+  is_builtin?: boolean;
+}
+
+/** user_rule: a custom category's app/keyword rule (checked first); builtin_default: built-in heuristic;
+ *  builtin_rule: app/keyword rule stored on a built-in category; fallback: nothing matched. */
+export type CategorizationSource = 'user_rule' | 'builtin_default' | 'builtin_rule' | 'fallback';
+
+export interface CategorizationExplanation {
+  category_id: number;
+  category_name: string;
+  source: CategorizationSource;
+  matched_rule: string;
 }
 
 // Manual entry types
@@ -74,33 +132,18 @@ export interface ManualEntry {
 }
 
 // Pattern types
-export interface Pattern {
+export type PatternKind = 'routine' | 'anomaly' | 'correlation';
+
+export interface DetectedPattern {
   id: number;
-  pattern_type: 'time' | 'sequence' | 'context' | 'mood';
-  pattern_data: PatternData;
+  kind: PatternKind;
+  description: string;
   confidence: number;
+  supporting_window: string;
+  detected_at: number;
   last_observed: number;
-  occurrence_count: number;
-}
-
-export interface PatternData {
-  // Time-based pattern
-  hour?: number;
-  day_of_week?: number;
-  likely_activities?: string[];
-
-  // Sequence pattern
-  sequence?: string[];
-  avg_gap_seconds?: number;
-
-  // Context pattern
-  trigger_activity?: string;
-  following_activities?: string[];
-
-  // Mood pattern
-  idle_duration?: number;
-  time_of_day?: number;
-  likely_intent?: string;
+  dismissed: boolean;
+  data: Record<string, unknown>;
 }
 
 // Intent types
@@ -134,6 +177,21 @@ export type ActionType =
   | 'show_notification'
   | 'execute_workflow';
 
+export interface QuickAction {
+  id: string;
+  label: string;
+  source: 'history' | 'routine' | 'workflow';
+  reason: string;
+  score: number;
+  intent: Intent;
+}
+
+export interface CurrentFocus {
+  text: string;
+  set_at: number;
+  expires_at: number;
+}
+
 // Workflow types
 export interface Workflow {
   id: string;
@@ -151,6 +209,116 @@ export interface Workflow {
 export interface AppLaunch {
   path: string;
   args: string[];
+  preconditions?: WorkflowPrecondition[];
+  continue_on_failure?: boolean;
+}
+
+export type WorkflowPrecondition =
+  | { kind: 'path_exists'; path: string }
+  | { kind: 'app_installed'; exe: string }
+  | { kind: 'network_reachable'; host: string };
+
+export interface PreconditionCheck {
+  action: string;
+  precondition: WorkflowPrecondition;
+  passed: boolean;
+  detail: string;
+}
+
+export interface WorkflowRunResult {
+  /** False when a check aborted the run, and on a dry run. */
+  executed: boolean;
+  dry_run?: boolean;
+  /** Dry run only: every action in execution order with what it resolved to. */
+  planned?: PlannedAction[];
+  checks: PreconditionCheck[];
+  skipped_actions: string[];
+  errors: string[];
+}
+
+export interface PlannedAction {
+  action_type: ActionType;
+  target: string;
+  args: string[];
+  /** Executable path found for a launch, or the absolute path of a file. */
+  resolved: string | null;
+  will_run: boolean;
+  note: string | null;
+}
+
+// App usage alerts
+export interface AppAlert {
+  id: number;
+  app_pattern: string;
+  daily_threshold_seconds: number;
+  repeat_every_seconds: number | null;
+  enabled: boolean;
+  created_at: number;
+}
+
+export type ProfileSection = 'settings' | 'categories' | 'goals' | 'app_alerts' | 'workflows';
+export type ProfileImportMode = 'merge' | 'replace';
+
+export interface ProfileSectionResult {
+  section: ProfileSection;
+  added: number;
+  updated: number;
+  unchanged: number;
+  removed: number;
+}
+
+export interface ProfileConflict {
+  section: ProfileSection;
+  name: string;
+  detail: string;
+  resolution: string;
+}
+
+export interface ProfileImportReport {
+  mode: ProfileImportMode;
+  bundle_version: number;
+  sections: ProfileSectionResult[];
+  conflicts: ProfileConflict[];
+  secrets_imported: boolean;
+  warnings: string[];
+}
+
+export interface AppAlertTriggered {
+  alert_id: number;
+  app_pattern: string;
+  app_name: string;
+  total_seconds: number;
+  threshold_seconds: number;
+}
+
+// Category goals and streaks
+export type GoalKind = 'at_least' | 'at_most';
+export type NoDataPolicy = 'preserve' | 'break';
+export type GoalDayStatus = 'met' | 'missed' | 'no_data' | 'pending';
+
+export interface CategoryGoal {
+  id: number;
+  category_id: number;
+  kind: GoalKind;
+  target_seconds: number;
+  no_data_policy: NoDataPolicy;
+  enabled: boolean;
+  best_streak: number;
+  created_at: number;
+}
+
+export interface GoalDay {
+  date_key: string;
+  seconds: number;
+  status: GoalDayStatus;
+}
+
+export interface GoalStreak {
+  goal: CategoryGoal;
+  category_name: string;
+  current_streak: number;
+  best_streak: number;
+  calendar: GoalDay[];
 }
 
 export interface WorkflowSuggestion {
@@ -176,6 +344,12 @@ export interface QueryItem {
   details?: string;
 }
 
+export interface SearchHit extends QueryItem {
+  activity_id: number;
+  match_field: 'title' | 'app' | 'ocr';
+  score: number;
+}
+
 // Settings types
 export interface Settings {
   version: string;
@@ -185,6 +359,7 @@ export interface Settings {
   ai: AISettings;
   privacy: PrivacySettings;
   notifications: NotificationSettings;
+  reports?: ReportSettings;
 }
 
 export interface GeneralSettings {
@@ -194,6 +369,17 @@ export interface GeneralSettings {
   startup_behavior: 'show_window' | 'minimized_to_tray' | 'hidden';
   minimize_to_tray: boolean;
   close_to_tray: boolean;
+  timezone_mode?: 'system' | 'fixed';
+  fixed_timezone?: string;
+  time_format?: '12h' | '24h';
+  demo_mode?: boolean;
+  hotkeys?: HotkeySettings;
+}
+
+/** Global shortcuts like "Ctrl+Alt+Space"; an empty string leaves the action unbound. */
+export interface HotkeySettings {
+  toggle_incognito: string;
+  quick_chat: string;
 }
 
 export interface TrackingSettings {
@@ -203,6 +389,42 @@ export interface TrackingSettings {
   exclude_apps: string[];
   exclude_urls: string[];
   track_browser: boolean;
+  save_thumbnails?: boolean;
+  sources?: TrackingSources;
+  ocr_min_quality?: number;
+  ocr_languages?: string[];
+  ocr_interval?: number;
+  /** Scale `ocr_interval` by the focused window's category; paused for fullscreen video and games. */
+  adaptive_ocr?: boolean;
+  ocr_min_interval?: number;
+  ocr_max_interval?: number;
+  code_roots?: string[];
+  /** Executable names whose running time is recorded; empty disables it. */
+  process_watch_list?: string[];
+  /** First matching profile decides how an app's screen text is kept; unmatched apps use 'full'. */
+  ocr_profiles?: OcrProfile[];
+  ocr_debug_events?: boolean;
+  /** When non-empty, only these apps (substring of the app name) are OCR'd; `exclude_apps` still wins. */
+  ocr_allowed_apps?: string[] | null;
+  /** Local hours [start, end) normally at the computer; gaps outside them aren't reported. End <= start wraps past midnight. */
+  active_hours_start?: number;
+  active_hours_end?: number;
+  /** Minimum minutes without activity reported as a tracking gap. */
+  gap_threshold_minutes?: number;
+  /** Window titles (case-insensitive, whole title) never recorded as background windows. */
+  background_window_denylist?: string[];
+}
+
+export type OcrMode = 'full' | 'lines_preserved' | 'skip';
+
+export interface OcrProfile {
+  /** Case-insensitive substring of the app name. */
+  app_pattern: string;
+  mode: OcrMode;
+}
+
+export interface TrackingSources {
+  clipboard: boolean;
 }
 
 export interface StorageSettings {
@@ -219,12 +441,58 @@ export interface AISettings {
   model: string;
   local_only: boolean;
   fallback_to_local: boolean;
+  disabled_tools?: string[];
+  extra_ca_cert?: string | null;
+  model_overrides?: Record<string, ModelCapabilities>;
+  answer_language?: string;
+  agent_temperature?: number;
+  summary_temperature?: number;
+  dashboard_temperature?: number;
+  agent?: AgentSettings;
+  ai_profiles?: AiProfile[];
+  /** Profile used when a request names none; null uses `model`. */
+  active_profile?: string | null;
+  /** Profile for dashboard summaries; null follows `active_profile`. */
+  dashboard_profile?: string | null;
+}
+
+export interface AiProfile {
+  name: string;
+  model: string;
+  /** OpenAI-compatible API root; empty uses the default endpoint. */
+  base_url: string;
+  temperature?: number | null;
+  max_tokens?: number | null;
 }
 
+export interface AgentSettings {
+  max_turns: number;
+  min_evidence_tools_for_identity: number;
+  force_validation_enabled: boolean;
+  broad_scope_keywords: string[];
+  smalltalk_bypass: boolean;
+  long_range_pipeline_threshold_days: number;
+  /** 'off' accepts the first answer, 'normal' skips the identity tool-call minimum, 'strict' enforces everything. */
+  evidence_strictness?: EvidenceStrictness;
+}
+
+export type EvidenceStrictness = 'off' | 'normal' | 'strict';
+
+export interface ModelCapabilities {
+  context_window: number;
+  supports_reasoning: boolean;
+  supports_native_tools: boolean;
+  max_tokens: number;
+}
+
+export type CapabilitySource = 'override' | 'probed' | 'builtin' | 'default';
+
 export interface PrivacySettings {
   encrypt_database: boolean;
   exclude_incognito: boolean;
   anonymize_data: boolean;
+  include_private_in_ai?: boolean;
+  require_unlock?: boolean;
 }
 
 export interface NotificationSettings {
@@ -232,6 +500,15 @@ export interface NotificationSettings {
   pattern_insights: boolean;
   daily_summary: boolean;
   summary_time: string;
+  ocr_alert_keywords?: string[];
+}
+
+export interface ReportSettings {
+  weekly_enabled: boolean;
+  weekday: string;
+  time: string;
+  format: 'html' | 'markdown' | 'both';
+  output_dir: string;
 }
 
 // Storage stats
@@ -243,6 +520,147 @@ export interface StorageStats {
   entries_count: number;
   oldest_activity: number;
   newest_activity: number;
+  tables: TableStorage[];
+  categories: StorageCategoryUsage[];
+  ocr_text_bytes: number;
+  thumbnails_bytes: number;
+  icons_bytes: number;
+  bytes_per_day: number;
+  size_source: 'dbstat' | 'estimate' | string;
+}
+
+export interface TableStorage {
+  table: string;
+  row_count: number;
+  size_bytes: number;
+  oldest_record?: number | null;
+  newest_record?: number | null;
+}
+
+export interface StorageCategoryUsage {
+  category: string;
+  size_bytes: number;
+}
+
+export interface StorageForecast {
+  total_size_bytes: number;
+  size_source: string;
+  daily_ingest: DailyIngest[];
+  bytes_per_day: number;
+  overhead_factor: number;
+  forecast_30d_bytes: number;
+  forecast_90d_bytes: number;
+  limit_bytes: number;
+  days_until_limit: number | null;
+  contributors: StorageContributor[];
+  retention_impacts: RetentionImpact[];
+}
+
+export interface DailyIngest {
+  date_key: string;
+  bytes: number;
+}
+
+export interface StorageContributor {
+  name: string;
+  size_bytes: number;
+  rows: number;
+}
+
+export interface RetentionImpact {
+  setting: 'retention_days' | 'ocr_retention_days' | 'auto_cleanup';
+  days: number;
+  bytes_removed: number;
+  description: string;
+}
+
+export interface PurgedTableRows {
+  table: string;
+  action: 'deleted' | 'redacted' | 'rebuilt';
+  rows: number;
+}
+
+export interface ActivityDeleteFilter {
+  start_time?: number | null;
+  end_time?: number | null;
+  app_patterns?: string[];
+  category_ids?: number[];
+  include_file_events?: boolean;
+  dry_run?: boolean;
+}
+
+export interface ActivityDeletion {
+  dry_run: boolean;
+  rows: PurgedTableRows[];
+  affected_days: string[];
+  log_id: number | null;
+}
+
+export interface RecentDataPurge {
+  start_time: number;
+  end_time: number;
+  rows: PurgedTableRows[];
+}
+
+// Logs
+export interface LogEntry {
+  timestamp: string;
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE' | string;
+  target: string;
+  message: string;
+}
+
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export interface IntegrityViolation {
+  check: string;
+  table: string;
+  count: number;
+  detail: string;
+}
+
+export interface DataIntegrityReport {
+  clean: boolean;
+  violations: IntegrityViolation[];
+  checked_at: number;
+}
+
+/** Payload of `index://progress` and result of `rebuildIndexes`. */
+export interface IndexRebuildProgress {
+  processed: number;
+  total: number;
+  indexed: number;
+  done: boolean;
+  cancelled: boolean;
+}
+
+export interface SampleDataSummary {
+  activities: number;
+  file_events: number;
+  media_sessions: number;
+  ocr_snapshots: number;
+  chat_sessions: number;
+  start_time: number;
+  end_time: number;
+}
+
+export interface KeywordAudit {
+  keyword: string;
+  activity_title_matches: number;
+  activity_app_matches: number;
+  activity_ocr_matches: number;
+  activity_metadata_matches: number;
+  chat_message_matches: number;
+  chat_session_matches: number;
+  dashboard_snapshot_matches: number;
+  code_file_event_matches: number;
+  query_cache_matches: number;
+  other_matches: number;
+  ocr_match_mode: 'fts' | 'like';
+  first_match_at: number | null;
+  last_match_at: number | null;
+  purged: boolean;
+  purged_rows: PurgedTableRows[];
 }
 
 // Chat types
@@ -251,6 +669,18 @@ export interface ChatSession {
   title: string;
   created_at: number;
   updated_at: number;
+  archived?: boolean;
+  pinned?: boolean;
+  /** Tokens and cost of all answers; only set by `getChatSessions`. */
+  usage?: LlmUsage | null;
+}
+
+export type ChatSessionFilter = 'all' | 'pinned' | 'active';
+
+export interface ChatSessionSearchResult {
+  session: ChatSession;
+  snippet: string;
+  match_count: number;
 }
 
 export interface AgentStep {
@@ -261,7 +691,27 @@ export interface AgentStep {
   reasoning: string;
 }
 
+export interface PipelineStepEvent {
+  index: number;
+  tool: string;
+  label: string;
+  status: 'started' | 'completed' | 'failed';
+  rows: number;
+  elapsed_ms: number;
+  digest?: string | null;
+}
+
+/** Payload of the `chat://tool-progress` event. */
+export interface ToolProgress {
+  tool: string;
+  phase: 'scanning' | 'done' | 'call_done';
+  scanned: number;
+  matched: number;
+  detail?: string | null;
+}
+
 export interface ActivityRef {
+  id?: number | null;
   app: string;
   title: string;
   time: number;
@@ -278,9 +728,76 @@ export interface ChatMessage {
   content: string;
   tool_calls?: AgentStep[];
   activities?: ActivityRef[];
+  actions?: AgentAction[];
   created_at: number;
+  /** 2 and up: actions arrive in `actions`, never as markers in `content`. */
+  response_version?: number;
+  usage?: LlmUsage | null;
+  /** Computed from the database for summary answers; the table shown under the bubble. */
+  time_accounting?: TimeAccounting | null;
+  /** Places where the answer's stated durations disagree with `time_accounting`. */
+  confidence_factors?: string[];
 }
 
+export interface TimeShare {
+  name: string;
+  seconds: number;
+  percent: number;
+}
+
+export interface TimeAccounting {
+  scope_label: string;
+  start_ts: number;
+  end_ts: number;
+  span_seconds: number;
+  tracked_seconds: number;
+  idle_seconds: number;
+  untracked_seconds: number;
+  categories: TimeShare[];
+  top_apps: TimeShare[];
+}
+
+export interface LlmUsage {
+  prompt_tokens: number;
+  completion_tokens: number;
+  calls: number;
+  /** Some call had no provider usage and was estimated from text length. */
+  estimated: boolean;
+  /** US dollars at list price; null when a model's price is unknown. */
+  cost_usd: number | null;
+}
+
+/** Payload of the `chat://status` event. */
+export interface ChatStatus {
+  message: string;
+  usage: LlmUsage | null;
+}
+
+export type LlmCaller = 'chat' | 'dashboard' | 'summary' | 'intent' | 'chat_title';
+
+export interface LlmCallerUsage {
+  caller: LlmCaller;
+  usage: LlmUsage;
+}
+
+export interface LlmUsageStats {
+  /** "YYYY-MM". */
+  month: string;
+  total: LlmUsage;
+  by_caller: LlmCallerUsage[];
+}
+
+export type AgentAction =
+  | {
+      kind: 'confirm_scope_or_sources';
+      reason: string;
+      suggested_time_range?: string | null;
+      enable_sources?: string[];
+      retry_message: string;
+    }
+  | { kind: 'retry_with_query'; query: string }
+  | { kind: 'clarify'; question: string; suggestions: string[] };
+
 // Dashboard types
 export interface DashboardTask {
   title: string;
@@ -299,14 +816,419 @@ export interface ContactOverview {
   name: string;
   context: string;
   last_seen?: number;
+  /** Interactions per tag, most frequent first; 'unknown' is never counted. */
+  tags?: InteractionTagCount[];
+}
+
+export type InteractionTag = 'scheduling' | 'question' | 'work' | 'social' | 'unknown';
+
+export interface InteractionTagCount {
+  tag: InteractionTag;
+  count: number;
+}
+
+export type FocusPointKind = 'deadline' | 'project' | 'contact' | 'habit' | 'info';
+
+export interface FocusEntityRef {
+  type: 'project' | 'contact' | 'deadline' | string;
+  id?: string | null;
+  name?: string | null;
+}
+
+export interface FocusPoint {
+  kind: FocusPointKind;
+  text: string;
+  entity_ref?: FocusEntityRef | null;
+  /** 1 (low) to 3 (high). */
+  priority: number;
 }
 
 export interface DashboardOverview {
   date_key: string;
   summary: string;
-  focus_points: string[];
+  focus_points: FocusPoint[];
   deadlines: DashboardTask[];
   projects: ProjectOverview[];
   contacts: ContactOverview[];
+  /** Detected from meeting-app window titles, not by the model. */
+  meetings?: Meeting[];
   updated_at: number;
 }
+
+export interface Meeting {
+  name: string;
+  start_time: number;
+  end_time: number;
+  /** Time in the meeting's windows, not end minus start. */
+  duration_seconds: number;
+  /** Approximate, read from the participant list on screen. */
+  attendees: string[];
+  apps: string[];
+}
+
+export interface MeetingsReport {
+  start_ts: number;
+  end_ts: number;
+  total_duration_seconds: number;
+  meetings: Meeting[];
+}
+
+export interface DailyRecap {
+  date_key: string;
+  total_tracked_seconds: number;
+  top_apps: AppStat[];
+  category_split: CategoryStat[];
+  projects: ProjectOverview[];
+  songs_played: number;
+  contacts: ContactOverview[];
+  generated_at: number;
+}
+
+export interface DayBounds {
+  date_key: string;
+  start_ts: number;
+  end_ts: number;
+  offset_minutes: number;
+  offset_source: 'fixed' | 'stored' | 'system';
+}
+
+export interface DayBoundaryDebug {
+  bounds: DayBounds;
+  timezone_mode: string;
+  start_local: string;
+  end_local: string;
+  start_utc: string;
+  end_utc: string;
+  stored_offsets: [number, number][];
+}
+
+export interface ActivityDetail {
+  activity: Activity;
+  private: boolean;
+  tz_offset_minutes?: number | null;
+  screen_text_truncated: boolean;
+  screen_text_chars: number;
+}
+
+export interface ActivityCapture {
+  activity_id: number;
+  private: boolean;
+  screen_text: string | null;
+  ocr_score: number | null;
+  ocr_language: string | null;
+  media_info: MediaInfo | null;
+  background_windows: string[] | null;
+}
+
+export interface ProcessSession {
+  id: number;
+  process_name: string;
+  pid: number;
+  parent_pid: number | null;
+  parent_name: string | null;
+  start_time: number;
+  /** null while the process is still running. */
+  end_time: number | null;
+  duration_seconds: number;
+}
+
+export type Surface = 'dashboard' | 'timeline';
+
+/** What happened since a surface was last marked seen (at most the last 48h). */
+export interface SinceLastSeen {
+  surface: Surface;
+  /** Nothing was marked seen yet; the gap starts at the beginning of today. */
+  first_visit: boolean;
+  gap_start: number;
+  gap_end: number;
+  new_activities: number;
+  tracked_seconds: number;
+  top_apps: AppStat[];
+  projects: ProjectDelta[];
+  media: MediaDelta[];
+  notifications: NotificationRecord[];
+}
+
+export interface ProjectDelta {
+  name: string;
+  project_root: string;
+  changes: number;
+  files: number;
+}
+
+export interface MediaDelta {
+  title: string;
+  artist: string;
+  sessions: number;
+  seconds: number;
+}
+
+export interface NotificationRecord {
+  kind: string;
+  title: string;
+  body: string;
+  created_at: number;
+}
+
+/** Music listening over a range; only time reported as playing counts. */
+export interface ListeningStats {
+  top_tracks: TrackStat[];
+  top_artists: ArtistStat[];
+  total_play_seconds: number;
+  by_day: DailyTotal[];
+}
+
+export interface TrackStat {
+  title: string;
+  artist: string;
+  app: string;
+  play_seconds: number;
+  sessions: number;
+}
+
+export interface ArtistStat {
+  artist: string;
+  play_seconds: number;
+  tracks: number;
+}
+
+/** A stretch of the active hours with no recorded activity. */
+export interface TrackingGap {
+  start: number;
+  end: number;
+  duration: number;
+}
+
+export interface AppMerge {
+  from_name: string;
+  to_name: string;
+  search_only: boolean;
+  created_at: number;
+}
+
+export interface AppRenameMatch {
+  app_name: string;
+  activities: number;
+  clipboard_events: number;
+}
+
+export interface AppRenameReport {
+  dry_run: boolean;
+  to: string;
+  matched: AppRenameMatch[];
+  activities_updated: number;
+  clipboard_events_updated: number;
+  aliases: string[];
+}
+
+export interface AppDirectoryEntry {
+  app_name: string;
+  display_name: string;
+  category_id: number;
+  total_seconds: number;
+  activity_count: number;
+  first_seen: number;
+  last_seen: number;
+  /** PNG data URL; null when no icon could be extracted. */
+  icon: string | null;
+}
+
+export interface ContactInteraction {
+  app_name: string;
+  window_title: string;
+  start_time: number;
+  duration_seconds: number;
+  snippet?: string | null;
+  tag?: InteractionTag;
+}
+
+export interface ContactConversationBlock {
+  start_time: number;
+  end_time: number;
+  total_duration_seconds: number;
+  apps: string[];
+  events: ContactInteraction[];
+}
+
+export interface ContactTimeline {
+  contact: string;
+  days: number;
+  blocks: ContactConversationBlock[];
+  has_more: boolean;
+}
+
+export interface ProjectFileChange {
+  path: string;
+  area?: string | null;
+  entity_type: string;
+  created: number;
+  modified: number;
+  deleted: number;
+  last_change_type: string;
+  last_detected_at: number;
+  preview?: string | null;
+}
+
+export interface ProjectAreaChange {
+  area: string;
+  changes: number;
+}
+
+export interface ProjectChanges {
+  name: string;
+  project_roots: string[];
+  start_ts: number;
+  end_ts: number;
+  total_changes: number;
+  created: number;
+  modified: number;
+  deleted: number;
+  noise_filtered: number;
+  areas: ProjectAreaChange[];
+  files: ProjectFileChange[];
+  files_truncated: boolean;
+}
+
+export interface SettingsPropagation {
+  service: string;
+  revision: number;
+  applied_at: number;
+  updates_received: number;
+}
+
+export interface ServiceStatus {
+  name: string;
+  running: boolean;
+  enabled: boolean;
+  started_at: number | null;
+  uptime_seconds: number | null;
+  last_tick: number | null;
+  last_error: string | null;
+  last_error_at: number | null;
+}
+
+export type OcrStage =
+  | 'capturing'
+  | 'diffing-skipped'
+  | 'profile-skipped'
+  | 'app-skipped'
+  | 'ocr-running'
+  | 'stored'
+  | 'low-quality-skipped'
+  | 'failed';
+
+/** Payload of the `ocr://status` event. */
+export interface OcrStatus {
+  stage: OcrStage;
+  capture: number;
+  app_name: string;
+  timestamp: number;
+  elapsed_ms: number;
+  ocr_ms: number | null;
+  chars: number;
+  score: number | null;
+  error: string | null;
+}
+
+export interface OcrStatusSummary {
+  latest: OcrStatus | null;
+  last_stored: OcrStatus | null;
+}
+
+/** Payload of `get_now_playing` and the `media://now-playing` event. */
+export interface NowPlaying {
+  media: MediaInfo | null;
+  source_app: string | null;
+  updated_at: number | null;
+  seconds_ago: number | null;
+  /** Reported as playing but not seen for over 30s; the player was probably closed. */
+  stale: boolean;
+}
+
+export interface DailyTotal {
+  date_key: string;
+  seconds: number;
+}
+
+export interface ReportProject {
+  name: string;
+  file_events: number;
+  files_changed: number;
+}
+
+export interface FocusMetrics {
+  deep_work_seconds: number;
+  focus_blocks: number;
+  longest_focus_block_seconds: number;
+  app_switches_per_hour: number;
+}
+
+export interface PeriodReport {
+  start_date: string;
+  end_date: string;
+  total_tracked_seconds: number;
+  active_days: number;
+  daily_average_seconds: number;
+  daily_totals: DailyTotal[];
+  category_split: CategoryStat[];
+  top_apps: AppStat[];
+  other_apps_count: number;
+  other_apps_seconds: number;
+  projects: ReportProject[];
+  focus: FocusMetrics;
+  contacts: ContactOverview[];
+  deadlines: DashboardTask[];
+  generated_at: number;
+}
+
+export interface GeneratedReport {
+  paths: string[];
+  report: PeriodReport;
+}
+
+export interface AppNotification {
+  kind: string;
+  title: string;
+  body: string;
+}
+
+export interface OcrAlert {
+  keyword: string;
+  snippet: string;
+  app_name: string;
+  timestamp: number;
+}
+
+export interface OcrVariantReport {
+  variant: string;
+  language: string;
+  raw_text: string;
+  cleaned_text: string;
+  score: number;
+}
+
+export interface OcrTestReport {
+  app_name: string;
+  threshold: number;
+  variants: OcrVariantReport[];
+  accepted_variant: string | null;
+  accepted_language: string | null;
+}
+
+// Error types
+export type AppErrorKind =
+  | 'ai_disabled'
+  | 'api_auth'
+  | 'api'
+  | 'network'
+  | 'database'
+  | 'not_found'
+  | 'invalid_input'
+  | 'ocr'
+  | 'internal';
+
+/** Rejection value of commands that return a typed error. */
+export interface AppError {
+  kind: AppErrorKind;
+  message: string;
+}